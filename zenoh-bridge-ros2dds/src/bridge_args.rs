@@ -33,6 +33,9 @@ pub struct BridgeArgs {
     /// If not set, a random UUIDv4 will be used.
     #[arg(short, long, verbatim_doc_comment)]
     pub id: Option<String>,
+    /// A human-readable name for this bridge (e.g. "robot-07"), displayed by the other bridges. It doesn't need to be unique.
+    #[arg(long)]
+    pub alias: Option<String>,
     /// A ROS 2 namespace to be used by the "zenoh_bridge_dds" node'
     #[arg(short, long)]
     pub namespace: Option<String>,
@@ -48,6 +51,10 @@ pub struct BridgeArgs {
         verbatim_doc_comment
     )]
     pub ros_localhost_only: bool,
+    /// A network interface to be used by CycloneDDS for the DDS discovery and traffic, as a name (e.g. "eth0") or an IP address.
+    /// Repeat this option to configure several interfaces. Ignored if "--ros-localhost-only" is active.
+    #[arg(long, value_name = "NAME | IP", verbatim_doc_comment)]
+    pub dds_network_interface: Vec<String>,
     /// Configure CycloneDDS to use Iceoryx shared memory. If not set, CycloneDDS will instead use any shared memory settings defined in "$CYCLONEDDS_URI" configuration.
     #[cfg(feature = "dds_shm")]
     #[arg(long)]
@@ -65,6 +72,15 @@ pub struct BridgeArgs {
     /// This value overwrites the value possibly set in configuration file under 'plugins/ros2dds/queries_timeout/default' key [default: 5.0].
     #[arg(long, value_name = "FLOAT", verbatim_doc_comment)]
     pub queries_timeout_default: Option<f32>,
+    /// The maximum number of attempts to restore the routes (Zenoh entities and liveliness tokens)
+    /// after a reconnection to the Zenoh infrastructure (e.g. after a router restart). 0 disables the restoration.
+    /// This value overwrites the value possibly set in configuration file under 'plugins/ros2dds/reconnect/max_attempts' key [default: 3].
+    #[arg(long, value_name = "INT", verbatim_doc_comment)]
+    pub reconnect_max_attempts: Option<u32>,
+    /// A float in seconds between 2 attempts to restore the routes after a reconnection.
+    /// This value overwrites the value possibly set in configuration file under 'plugins/ros2dds/reconnect/period' key [default: 1.0].
+    #[arg(long, value_name = "FLOAT", verbatim_doc_comment)]
+    pub reconnect_period: Option<f32>,
 
     /// Configures HTTP interface for the REST API (disabled by default, setting this option enables it). Accepted values:
     ///  - a port number
@@ -98,6 +114,7 @@ impl From<&BridgeArgs> for Config {
         let mut config = (&args.session_args).into();
 
         insert_json5_option(&mut config, "plugins/ros2dds/id", &args.id);
+        insert_json5_option(&mut config, "plugins/ros2dds/alias", &args.alias);
         insert_json5_option(&mut config, "plugins/ros2dds/namespace", &args.namespace);
         if let Some(domain) = args.domain {
             insert_json5(&mut config, "plugins/ros2dds/domain", &domain);
@@ -107,6 +124,11 @@ impl From<&BridgeArgs> for Config {
             "plugins/ros2dds/ros_localhost_only",
             &args.ros_localhost_only,
         );
+        insert_json5_list(
+            &mut config,
+            "plugins/ros2dds/dds_network_interfaces",
+            &args.dds_network_interface,
+        );
         #[cfg(feature = "dds_shm")]
         {
             insert_json5(
@@ -125,6 +147,16 @@ impl From<&BridgeArgs> for Config {
             "plugins/ros2dds/queries_timeout/default",
             &args.queries_timeout_default,
         );
+        insert_json5_option(
+            &mut config,
+            "plugins/ros2dds/reconnect/max_attempts",
+            &args.reconnect_max_attempts,
+        );
+        insert_json5_option(
+            &mut config,
+            "plugins/ros2dds/reconnect/period",
+            &args.reconnect_period,
+        );
 
         insert_json5_option(&mut config, "plugins/rest/http_port", &args.rest_http_port);
 
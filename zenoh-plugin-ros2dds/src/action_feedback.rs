@@ -0,0 +1,290 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use zenoh_core::zlock;
+
+use crate::cdr_check::check_cdr_header;
+use crate::dds_utils::is_cdr_little_endian;
+use crate::forward_discovery::RouteKind;
+use crate::rate_limiter::RateLimiter;
+use crate::ros2_utils::{split_action_suffix, KE_SUFFIX_ACTION_FEEDBACK, KE_SUFFIX_ACTION_STATUS};
+
+// The terminal values of action_msgs/msg/GoalStatus.status: SUCCEEDED, CANCELED and ABORTED
+const GOAL_STATUS_TERMINAL: [i8; 3] = [4, 5, 6];
+
+#[inline]
+pub fn is_action_feedback_topic(ros2_name: &str) -> bool {
+    matches!(split_action_suffix(ros2_name), Some((_, suffix)) if suffix == KE_SUFFIX_ACTION_FEEDBACK.as_str())
+}
+
+#[inline]
+pub fn is_action_status_topic(ros2_name: &str) -> bool {
+    matches!(split_action_suffix(ros2_name), Some((_, suffix)) if suffix == KE_SUFFIX_ACTION_STATUS.as_str())
+}
+
+/// The goal ID of a serialized FeedbackMessage: the UUID (16 octets) starting its CDR body
+pub fn feedback_goal_id(payload: &[u8]) -> Option<[u8; 16]> {
+    payload.get(4..20)?.try_into().ok()
+}
+
+/// The IDs of the goals in a terminal state (succeeded, canceled or aborted) in a serialized GoalStatusArray
+/// (an empty list if it can't be decoded)
+pub fn terminal_goal_ids(payload: &[u8]) -> Vec<[u8; 16]> {
+    if check_cdr_header(payload).is_err() {
+        return Vec::new();
+    }
+    let little_endian =
+        is_cdr_little_endian(payload).expect("Shouldn't happen: payload is longer than 4 bytes");
+    // the CDR body, each member being aligned relative to its start
+    let body = &payload[4..];
+    let read_u32 = |pos: usize| -> Option<u32> {
+        let bytes: [u8; 4] = body.get(pos..pos + 4)?.try_into().ok()?;
+        Some(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    };
+    let Some(count) = read_u32(0) else {
+        return Vec::new();
+    };
+    let mut goals = Vec::new();
+    let mut pos = 4;
+    for _ in 0..count {
+        // GoalStatus: goal_info.goal_id (16 octets), goal_info.stamp (int32 + uint32 aligned to 4), status (int8)
+        let Some(goal_id) = body.get(pos..pos + 16) else {
+            break;
+        };
+        let stamp = (pos + 16 + 3) & !3;
+        let Some(status) = body.get(stamp + 8) else {
+            break;
+        };
+        if GOAL_STATUS_TERMINAL.contains(&(*status as i8)) {
+            goals.push(
+                goal_id
+                    .try_into()
+                    .expect("Shouldn't happen: slice is 16 bytes"),
+            );
+        }
+        pos = stamp + 9;
+    }
+    goals
+}
+
+/// The routing at once of the feedback messages kept for some goals
+pub trait GoalsFlush: Send + Sync {
+    fn flush_goals(&self, goals: &[[u8; 16]]);
+}
+
+/// The throttling of the feedback messages of an Action routed by a Route Publisher or Subscriber (see
+/// `feedback_max_frequency` config), per goal: the RateLimiter of the route with its messages keyed by goal ID.
+/// The last feedback kept for a goal is routed when the goal terminates, before the status change.
+pub struct FeedbackThrottle<T> {
+    limiter: Arc<RateLimiter<T>>,
+    route: Box<dyn Fn(T) + Send + Sync>,
+}
+
+impl<T> FeedbackThrottle<T> {
+    pub fn new<F>(limiter: Arc<RateLimiter<T>>, route: F) -> FeedbackThrottle<T>
+    where
+        F: Fn(T) + Send + Sync + 'static,
+    {
+        FeedbackThrottle {
+            limiter,
+            route: Box::new(route),
+        }
+    }
+}
+
+impl<T: Send> GoalsFlush for FeedbackThrottle<T> {
+    fn flush_goals(&self, goals: &[[u8; 16]]) {
+        for goal in goals {
+            if let Some(msg) = self.limiter.flush(goal) {
+                (self.route)(msg);
+            }
+        }
+    }
+}
+
+/// The FeedbackThrottles of the routes of the feedback topics (per route kind and topic name),
+/// flushed on the terminal status of their goals received by the route of the status topic
+#[derive(Default)]
+pub struct FeedbackThrottles {
+    routes: Mutex<HashMap<(RouteKind, String), Weak<dyn GoalsFlush>>>,
+}
+
+impl FeedbackThrottles {
+    /// Register the throttle of the route of a feedback topic (replacing the one of a previous route)
+    pub fn register<G: GoalsFlush + 'static>(
+        &self,
+        kind: RouteKind,
+        feedback_topic: &str,
+        throttle: &Arc<G>,
+    ) {
+        let throttle: Arc<dyn GoalsFlush> = throttle.clone();
+        let mut routes = zlock!(self.routes);
+        routes.retain(|_, t| t.strong_count() > 0);
+        routes.insert(
+            (kind, feedback_topic.to_string()),
+            Arc::downgrade(&throttle),
+        );
+    }
+
+    /// On a GoalStatusArray received by the route of a status topic: route the feedback kept for the
+    /// terminated goals by the route of the same kind for the Action's feedback topic (if any)
+    pub fn on_status(&self, kind: RouteKind, status_topic: &str, payload: &[u8]) {
+        let Some((action_name, _)) = split_action_suffix(status_topic) else {
+            return;
+        };
+        let key = (
+            kind,
+            format!("{action_name}/{}", *KE_SUFFIX_ACTION_FEEDBACK),
+        );
+        let Some(throttle) = zlock!(self.routes).get(&key).and_then(Weak::upgrade) else {
+            return;
+        };
+        let goals = terminal_goal_ids(payload);
+        if !goals.is_empty() {
+            throttle.flush_goals(&goals);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RateLimitPolicy;
+    use std::time::{Duration, Instant};
+
+    // A serialized GoalStatusArray (XCDR1) with a list of (goal ID, status)
+    fn goal_status_array(goals: &[([u8; 16], i8)], little_endian: bool) -> Vec<u8> {
+        let u32_bytes = |v: u32| {
+            if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            }
+        };
+        let mut payload = vec![0x00, little_endian as u8, 0x00, 0x00];
+        payload.extend_from_slice(&u32_bytes(goals.len() as u32));
+        for (goal_id, status) in goals {
+            payload.extend_from_slice(goal_id);
+            while (payload.len() - 4) % 4 != 0 {
+                payload.push(0);
+            }
+            payload.extend_from_slice(&u32_bytes(1700000000));
+            payload.extend_from_slice(&u32_bytes(500));
+            payload.push(*status as u8);
+        }
+        payload
+    }
+
+    // A serialized FeedbackMessage with a goal ID and a sequence number as feedback
+    fn feedback_message(goal_id: [u8; 16], seq: u32) -> Vec<u8> {
+        let mut payload = vec![0x00, 0x01, 0x00, 0x00];
+        payload.extend_from_slice(&goal_id);
+        payload.extend_from_slice(&seq.to_le_bytes());
+        payload
+    }
+
+    #[test]
+    fn test_terminal_goal_ids() {
+        for little_endian in [true, false] {
+            let payload = goal_status_array(
+                &[([1; 16], 2), ([2; 16], 4), ([3; 16], 1), ([4; 16], 6)],
+                little_endian,
+            );
+            assert_eq!(terminal_goal_ids(&payload), vec![[2; 16], [4; 16]]);
+        }
+        assert!(terminal_goal_ids(&goal_status_array(&[], true)).is_empty());
+        // a truncated GoalStatusArray gives the goals decoded so far
+        let payload = goal_status_array(&[([5; 16], 5), ([6; 16], 4)], true);
+        assert_eq!(terminal_goal_ids(&payload[..40]), vec![[5; 16]]);
+        assert!(terminal_goal_ids(&[0x00, 0x01]).is_empty());
+
+        assert_eq!(
+            feedback_goal_id(&feedback_message([7; 16], 1)),
+            Some([7; 16])
+        );
+        assert_eq!(feedback_goal_id(&[0x00, 0x01, 0x00, 0x00, 7]), None);
+        assert!(is_action_feedback_topic(
+            "/navigate_to_pose/_action/feedback"
+        ));
+        assert!(!is_action_feedback_topic(
+            "/navigate_to_pose/_action/status"
+        ));
+        assert!(is_action_status_topic("/navigate_to_pose/_action/status"));
+        assert!(!is_action_status_topic("/status"));
+    }
+
+    #[test]
+    fn test_feedback_throttle() {
+        const FEEDBACK: &str = "/navigate_to_pose/_action/feedback";
+        const STATUS: &str = "/navigate_to_pose/_action/status";
+        let goal = [42u8; 16];
+        let throttles = FeedbackThrottles::default();
+        // the route of the feedback topic, limited to 2 Hz
+        let limiter = Arc::new(RateLimiter::new(RateLimitPolicy::Periodic, Some(2.0)));
+        let (tx, rx) = flume::unbounded::<(String, u32)>();
+        let throttle = Arc::new(FeedbackThrottle::new(limiter.clone(), {
+            let tx = tx.clone();
+            move |payload: Vec<u8>| {
+                let seq = u32::from_le_bytes(payload[20..24].try_into().unwrap());
+                tx.send(("feedback".into(), seq)).unwrap();
+            }
+        }));
+        throttles.register(RouteKind::Publisher, FEEDBACK, &throttle);
+
+        // a synthetic Action publishing its feedback at 20 Hz during 3.1 s (ticking as the timer would)
+        let start = Instant::now();
+        let mut next_tick = start + limiter.next_tick_delay(start);
+        for seq in 0..62u32 {
+            let now = start + Duration::from_millis(seq as u64 * 50);
+            while next_tick <= now {
+                for payload in limiter.tick(next_tick) {
+                    (throttle.route)(payload);
+                }
+                next_tick += limiter.next_tick_delay(next_tick);
+            }
+            let payload = feedback_message(goal, seq);
+            let instance = feedback_goal_id(&payload).unwrap();
+            if let Some(payload) = limiter.offer(instance, payload, now) {
+                (throttle.route)(payload);
+            }
+        }
+        let forwarded: Vec<u32> = rx.drain().map(|(_, seq)| seq).collect();
+        // 2 Hz: the last feedback of each of the 6 periods elapsed
+        assert_eq!(forwarded, vec![9, 19, 29, 39, 49, 59]);
+
+        // the goal succeeds: its last feedback is routed at once, before the status
+        let status = goal_status_array(&[(goal, 4)], true);
+        throttles.on_status(RouteKind::Publisher, STATUS, &status);
+        tx.send(("status".into(), 0)).unwrap();
+        assert_eq!(
+            rx.drain().collect::<Vec<_>>(),
+            vec![("feedback".to_string(), 61), ("status".to_string(), 0)]
+        );
+        // and nothing is left to the timer
+        assert!(limiter.tick(start + Duration::from_secs(10)).is_empty());
+
+        // the throttles of the other route kind or of a dropped route are not flushed
+        limiter.offer(goal, feedback_message(goal, 62), start);
+        throttles.on_status(RouteKind::Subscriber, STATUS, &status);
+        assert!(rx.is_empty());
+        drop(throttle);
+        throttles.on_status(RouteKind::Publisher, STATUS, &status);
+        assert!(rx.is_empty());
+    }
+}
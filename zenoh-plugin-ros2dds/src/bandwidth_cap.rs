@@ -0,0 +1,210 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::Serializer;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use zenoh::prelude::Priority;
+use zenoh_core::zlock;
+
+/// The global budget of the bytes published over Zenoh by all the Publisher routes (see `max_bandwidth`),
+/// as a token bucket refilled at `max_bandwidth` bytes per second, holding at most 1 second of budget.
+///
+/// The messages of the routes with a Priority higher or equal to `shedding_priority` are always routed
+/// (still consuming the budget). The messages of the lower priorities are routed only if the budget left
+/// after them exceeds a reserve, growing as their priority lowers: when the budget is short, the lowest
+/// priorities are shed first, and the budget is kept for the highest ones.
+pub struct BandwidthCap {
+    max_bandwidth: f64,
+    shedding_priority: Priority,
+    // the available tokens (in bytes, negative after a burst of high priority messages), and their last refill
+    bucket: Mutex<(f64, Instant)>,
+}
+
+impl BandwidthCap {
+    pub fn new(max_bandwidth: u64, shedding_priority: Priority, now: Instant) -> BandwidthCap {
+        BandwidthCap {
+            max_bandwidth: max_bandwidth as f64,
+            shedding_priority,
+            bucket: Mutex::new((max_bandwidth as f64, now)),
+        }
+    }
+
+    /// Return true if a message of `bytes` published with `priority` can be routed at `now`,
+    /// consuming its tokens.
+    pub fn admit(&self, bytes: usize, priority: Priority, now: Instant) -> bool {
+        let mut bucket = zlock!(self.bucket);
+        let (tokens, last_refill) = *bucket;
+        let elapsed = now.saturating_duration_since(last_refill).as_secs_f64();
+        let tokens = (tokens + elapsed * self.max_bandwidth).min(self.max_bandwidth);
+        let left = tokens - bytes as f64;
+        let admitted = match self.reserve(priority) {
+            // a burst of high priority messages is paid back by the lower priorities
+            None => true,
+            Some(reserve) => left >= reserve,
+        };
+        *bucket = (
+            if admitted {
+                left.max(-self.max_bandwidth)
+            } else {
+                tokens
+            },
+            last_refill.max(now),
+        );
+        admitted
+    }
+
+    // The tokens to be left in the bucket after a message with `priority` (None if never shed):
+    // none for the 1st priority below `shedding_priority`, up to almost the full bucket for Background.
+    fn reserve(&self, priority: Priority) -> Option<f64> {
+        let (p, threshold) = (priority as u8, self.shedding_priority as u8);
+        if p <= threshold {
+            return None;
+        }
+        let levels = (Priority::Background as u8 - threshold) as f64;
+        Some(self.max_bandwidth * (p - threshold - 1) as f64 / levels)
+    }
+}
+
+/// The share of the BandwidthCap of a Publisher route, with its Priority
+/// and the count of its messages that were shed.
+pub struct RouteBandwidth {
+    cap: Arc<BandwidthCap>,
+    priority: Priority,
+    dropped: AtomicU64,
+}
+
+impl RouteBandwidth {
+    pub fn new(cap: Arc<BandwidthCap>, priority: Priority) -> RouteBandwidth {
+        RouteBandwidth {
+            cap,
+            priority,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Return true if a message of `bytes` can be routed, otherwise count it as dropped
+    pub fn admit(&self, bytes: usize) -> bool {
+        if self.cap.admit(bytes, self.priority, Instant::now()) {
+            true
+        } else {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            false
+        }
+    }
+
+    #[inline]
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+pub fn serialize_route_bandwidth<S>(
+    bandwidth: &Option<Arc<RouteBandwidth>>,
+    s: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match bandwidth {
+        Some(b) => s.serialize_u64(b.dropped()),
+        None => s.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BandwidthCap;
+    use std::time::{Duration, Instant};
+    use zenoh::prelude::Priority;
+
+    #[test]
+    fn test_bandwidth_cap_within_budget() {
+        let start = Instant::now();
+        let cap = BandwidthCap::new(10_000, Priority::DataHigh, start);
+        // 10 messages of 100 bytes per 100ms: 10 KB/s
+        for i in 1..=100 {
+            let now = start + Duration::from_millis(i * 10);
+            assert!(cap.admit(100, Priority::Background, now));
+        }
+    }
+
+    #[test]
+    fn test_bandwidth_cap_shedding_order() {
+        let start = Instant::now();
+        // a budget of 20 KB/s, shedding the priorities lower than DataHigh
+        let cap = BandwidthCap::new(20_000, Priority::DataHigh, start);
+        // 3 topics offering 5 KB/s, 10 KB/s and 10 KB/s: 25 KB/s in total
+        let topics = [
+            (Priority::RealTime, 50),
+            (Priority::Data, 100),
+            (Priority::Background, 100),
+        ];
+        let mut dropped = [0u64; 3];
+        let mut routed_bytes = [0usize; 3];
+        for i in 1..=1000 {
+            let now = start + Duration::from_millis(i * 10);
+            for (t, (priority, bytes)) in topics.iter().enumerate() {
+                if cap.admit(*bytes, *priority, now) {
+                    routed_bytes[t] += bytes;
+                } else {
+                    dropped[t] += 1;
+                }
+            }
+        }
+        // the high priority topic is never shed, nor the Data one fitting in the budget left
+        assert_eq!(dropped[0], 0);
+        assert_eq!(dropped[1], 0);
+        // the Background topic gets the remaining budget (about 5 KB/s over 10s)
+        assert!(dropped[2] > 0);
+        let total: usize = routed_bytes.iter().sum();
+        assert!(total <= 20_000 * 10 + 20_000, "{total} bytes routed");
+        assert!(routed_bytes[2] > 40_000, "{} bytes routed", routed_bytes[2]);
+
+        // a heavier Data traffic (40 KB/s) is shed in turn, the Background topic getting nearly nothing
+        let mut dropped = [0u64; 3];
+        for i in 1001..=2000 {
+            let now = start + Duration::from_millis(i * 10);
+            for (t, (priority, bytes)) in topics.iter().enumerate() {
+                let bytes = if t == 1 { bytes * 4 } else { *bytes };
+                if !cap.admit(bytes, *priority, now) {
+                    dropped[t] += 1;
+                }
+            }
+        }
+        assert_eq!(dropped[0], 0);
+        assert!(dropped[1] > 0);
+        assert!(dropped[2] > dropped[1], "{dropped:?}");
+        assert!(dropped[2] >= 990, "{dropped:?}");
+    }
+
+    #[test]
+    fn test_bandwidth_cap_reserve() {
+        let start = Instant::now();
+        let cap = BandwidthCap::new(1000, Priority::DataHigh, start);
+        // Data (1st level below DataHigh) may empty the bucket
+        assert!(cap.admit(600, Priority::Data, start));
+        // DataLow and Background must leave 1/3 and 2/3 of the bucket
+        assert!(!cap.admit(100, Priority::Background, start));
+        assert!(cap.admit(50, Priority::DataLow, start));
+        assert!(!cap.admit(50, Priority::DataLow, start));
+        assert!(cap.admit(350, Priority::Data, start));
+        // the bucket is refilled with time
+        let now = start + Duration::from_millis(800);
+        assert!(cap.admit(100, Priority::Background, now));
+        // a high priority message is routed even beyond the budget
+        assert!(cap.admit(5000, Priority::InteractiveHigh, now));
+        assert!(!cap.admit(1, Priority::Data, now));
+    }
+}
@@ -0,0 +1,368 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use zenoh_core::zlock;
+
+use crate::config::BatchingConf;
+
+// A batch of messages is published as a frame made of:
+//  - the "ZB" marker, which can't start a CDR payload (its encapsulation identifier starts with 0x00)
+//  - the frame format version (1)
+//  - a reserved byte (0)
+//  - the count of messages (u32 little endian)
+//  - each message, prefixed with its length (u32 little endian)
+const MARKER: [u8; 2] = *b"ZB";
+const VERSION: u8 = 1;
+const HEADER_LEN: usize = 8;
+const LENGTH_PREFIX_LEN: usize = 4;
+
+/// The batching of the messages routed by a Route Publisher (see `batching` config).
+/// The messages are kept until the oldest one waited `max_batch_delay`, or until the next one would make
+/// the frame exceed `max_batch_size`, and are then published in a single frame. A batch of a single message
+/// is published as is (i.e. not framed).
+pub struct Batcher {
+    max_delay: Duration,
+    max_size: usize,
+    batch: Mutex<Batch>,
+    // notifies the timer of the first message of a batch
+    notifier: flume::Sender<()>,
+    notified: flume::Receiver<()>,
+    batches: AtomicU64,
+    batched: AtomicU64,
+}
+
+#[derive(Default)]
+struct Batch {
+    messages: Vec<Vec<u8>>,
+    // the size of the frame of those messages
+    size: usize,
+    // the reception time of the oldest message
+    since: Option<Instant>,
+}
+
+impl Batcher {
+    pub fn new(conf: &BatchingConf) -> Batcher {
+        let (notifier, notified) = flume::bounded(1);
+        Batcher {
+            max_delay: conf.get_max_batch_delay(),
+            max_size: conf.max_batch_size,
+            batch: Mutex::new(Batch::default()),
+            notifier,
+            notified,
+            batches: AtomicU64::new(0),
+            batched: AtomicU64::new(0),
+        }
+    }
+
+    /// Add a message received at `now`, returning the payloads to be published immediately
+    /// (i.e. the previous batch if this message doesn't fit in it, and this message if too large for a batch)
+    pub fn push(&self, message: Vec<u8>, now: Instant) -> Vec<Vec<u8>> {
+        let mut result = Vec::new();
+        let mut batch = zlock!(self.batch);
+        let message_size = LENGTH_PREFIX_LEN + message.len();
+        if !batch.messages.is_empty() && batch.size + message_size > self.max_size {
+            result.extend(self.take(&mut batch));
+        }
+        if HEADER_LEN + message_size > self.max_size {
+            result.push(message);
+            return result;
+        }
+        if batch.messages.is_empty() {
+            batch.size = HEADER_LEN;
+            batch.since = Some(now);
+            // the timer might be waiting for a message (ignore if already notified)
+            let _ = self.notifier.try_send(());
+        }
+        batch.size += message_size;
+        batch.messages.push(message);
+        result
+    }
+
+    /// Return the payload of the current batch if its oldest message waited `max_batch_delay` at `now`
+    pub fn flush_due(&self, now: Instant) -> Option<Vec<u8>> {
+        let mut batch = zlock!(self.batch);
+        match batch.since {
+            Some(since) if now.saturating_duration_since(since) >= self.max_delay => {
+                self.take(&mut batch)
+            }
+            _ => None,
+        }
+    }
+
+    /// The delay from `now` until the current batch is due, or `None` if there is no message to be published
+    pub fn next_flush_delay(&self, now: Instant) -> Option<Duration> {
+        zlock!(self.batch)
+            .since
+            .map(|since| (since + self.max_delay).saturating_duration_since(now))
+    }
+
+    fn take(&self, batch: &mut Batch) -> Option<Vec<u8>> {
+        let messages = std::mem::take(&mut batch.messages);
+        let size = batch.size;
+        batch.size = 0;
+        batch.since = None;
+        match messages.len() {
+            0 => None,
+            1 => messages.into_iter().next(),
+            count => {
+                self.batches.fetch_add(1, Ordering::Relaxed);
+                self.batched.fetch_add(count as u64, Ordering::Relaxed);
+                let mut frame = Vec::with_capacity(size);
+                frame.extend_from_slice(&MARKER);
+                frame.push(VERSION);
+                frame.push(0);
+                frame.extend_from_slice(&(count as u32).to_le_bytes());
+                for message in messages {
+                    frame.extend_from_slice(&(message.len() as u32).to_le_bytes());
+                    frame.extend_from_slice(&message);
+                }
+                Some(frame)
+            }
+        }
+    }
+
+    /// Spawn the timer publishing the batches via `publish` when they are due, until the Batcher is dropped
+    pub fn spawn_timer<F>(self: &Arc<Self>, publish: F)
+    where
+        F: Fn(Vec<u8>) + Send + 'static,
+    {
+        let batcher: Weak<Batcher> = Arc::downgrade(self);
+        let notified = self.notified.clone();
+        async_std::task::spawn(async move {
+            loop {
+                let delay = match batcher.upgrade() {
+                    Some(batcher) => batcher.next_flush_delay(Instant::now()),
+                    None => break,
+                };
+                match delay {
+                    Some(delay) => async_std::task::sleep(delay).await,
+                    // wait for a message (the Batcher holds a Sender while it exists)
+                    None => {
+                        if notified.recv_async().await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                }
+                let Some(batcher) = batcher.upgrade() else {
+                    break;
+                };
+                if let Some(payload) = batcher.flush_due(Instant::now()) {
+                    publish(payload);
+                }
+            }
+        });
+    }
+}
+
+impl Serialize for Batcher {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("Batcher", 4)?;
+        s.serialize_field("max_batch_delay", &self.max_delay.as_secs_f64())?;
+        s.serialize_field("max_batch_size", &self.max_size)?;
+        s.serialize_field("batches", &self.batches.load(Ordering::Relaxed))?;
+        s.serialize_field("batched_messages", &self.batched.load(Ordering::Relaxed))?;
+        s.end()
+    }
+}
+
+pub fn serialize_batcher<S>(batcher: &Option<Arc<Batcher>>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match batcher {
+        Some(batcher) => batcher.serialize(s),
+        None => s.serialize_none(),
+    }
+}
+
+/// If a payload is a batch of messages (i.e. starts with the batch marker)
+#[inline]
+pub fn is_batch(payload: &[u8]) -> bool {
+    payload.starts_with(&MARKER)
+}
+
+/// The messages of a batch, in their publication order. An error is returned if the frame is invalid
+pub fn split_batch(payload: &[u8]) -> Result<Vec<&[u8]>, String> {
+    if payload.len() < HEADER_LEN || !is_batch(payload) {
+        return Err("not a batch of messages".into());
+    }
+    if payload[2] != VERSION {
+        return Err(format!("unsupported batch version: {}", payload[2]));
+    }
+    let count = u32::from_le_bytes([payload[4], payload[5], payload[6], payload[7]]) as usize;
+    // each message has at least its length prefix
+    if count > (payload.len() - HEADER_LEN) / LENGTH_PREFIX_LEN {
+        return Err(format!("invalid batch of {count} messages"));
+    }
+    let mut messages = Vec::with_capacity(count);
+    let mut rest = &payload[HEADER_LEN..];
+    for i in 0..count {
+        if rest.len() < LENGTH_PREFIX_LEN {
+            return Err(format!("truncated batch at message {i}/{count}"));
+        }
+        let (prefix, tail) = rest.split_at(LENGTH_PREFIX_LEN);
+        let len = u32::from_le_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize;
+        if tail.len() < len {
+            return Err(format!("truncated batch at message {i}/{count}"));
+        }
+        let (message, tail) = tail.split_at(len);
+        messages.push(message);
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        return Err(format!(
+            "{} unexpected bytes after a batch of {count} messages",
+            rest.len()
+        ));
+    }
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batcher(max_batch_delay: f64, max_batch_size: usize) -> Batcher {
+        Batcher::new(&BatchingConf {
+            max_batch_delay,
+            max_batch_size,
+        })
+    }
+
+    // a CDR message of `len` bytes, identified by `i`
+    fn message(i: u8, len: usize) -> Vec<u8> {
+        let mut m = vec![i; len];
+        m[..4].copy_from_slice(&[0x00, 0x01, 0x00, 0x00]);
+        m
+    }
+
+    #[test]
+    fn test_batch_ordering() {
+        let batcher = batcher(0.01, 8192);
+        let now = Instant::now();
+        for i in 0..10 {
+            assert!(batcher.push(message(i, 200), now).is_empty());
+        }
+        let frame = batcher.flush_due(now + Duration::from_millis(10)).unwrap();
+        assert!(is_batch(&frame));
+        assert_eq!(frame.len(), HEADER_LEN + 10 * (LENGTH_PREFIX_LEN + 200));
+        let messages = split_batch(&frame).unwrap();
+        assert_eq!(messages.len(), 10);
+        for (i, m) in messages.iter().enumerate() {
+            assert_eq!(*m, message(i as u8, 200).as_slice());
+        }
+        assert!(batcher.flush_due(now + Duration::from_secs(1)).is_none());
+        assert_eq!(
+            serde_json::to_value(&batcher).unwrap(),
+            serde_json::json!({"max_batch_delay": 0.01, "max_batch_size": 8192, "batches": 1, "batched_messages": 10})
+        );
+    }
+
+    #[test]
+    fn test_batch_boundaries() {
+        // room for 4 messages of 200 bytes
+        let batcher = batcher(1.0, HEADER_LEN + 4 * (LENGTH_PREFIX_LEN + 200));
+        let now = Instant::now();
+        let mut frames = Vec::new();
+        for i in 0..10 {
+            frames.extend(batcher.push(message(i, 200), now));
+        }
+        frames.extend(batcher.flush_due(now + Duration::from_secs(1)));
+        let batches: Vec<Vec<&[u8]>> = frames.iter().map(|f| split_batch(f).unwrap()).collect();
+        assert_eq!(
+            batches.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![4, 4, 2]
+        );
+        assert_eq!(
+            batches.concat(),
+            (0..10).map(|i| message(i, 200)).collect::<Vec<_>>()
+        );
+
+        // a message too large for a batch is published as is, after the pending batch
+        assert!(batcher.push(message(10, 200), now).is_empty());
+        assert_eq!(
+            batcher.push(message(11, 1000), now),
+            vec![message(10, 200), message(11, 1000)]
+        );
+        assert_eq!(batcher.next_flush_delay(now), None);
+    }
+
+    #[test]
+    fn test_batch_delay() {
+        let batcher = batcher(0.005, 8192);
+        let start = Instant::now();
+        assert_eq!(batcher.next_flush_delay(start), None);
+        // a 1 kHz source: each batch is published 5ms after its oldest message
+        let mut published = Vec::new();
+        for i in 0..20u64 {
+            let now = start + Duration::from_millis(i);
+            if let Some(frame) = batcher.flush_due(now) {
+                published.push((i, split_batch(&frame).unwrap().len()));
+            }
+            assert!(batcher.push(message(i as u8, 200), now).is_empty());
+            assert!(batcher.next_flush_delay(now).unwrap() <= Duration::from_millis(5));
+        }
+        assert_eq!(published, vec![(5, 5), (10, 5), (15, 5)]);
+        // not due before the delay
+        let last = start + Duration::from_millis(15);
+        assert!(batcher.flush_due(last + Duration::from_millis(4)).is_none());
+        assert_eq!(
+            batcher.next_flush_delay(last + Duration::from_millis(4)),
+            Some(Duration::from_millis(1))
+        );
+        assert!(batcher.flush_due(last + Duration::from_millis(5)).is_some());
+
+        // a single message is published as is
+        batcher.push(message(42, 200), start);
+        assert_eq!(
+            batcher.flush_due(start + Duration::from_millis(5)),
+            Some(message(42, 200))
+        );
+    }
+
+    #[test]
+    fn test_split_invalid_batch() {
+        let batcher = batcher(0.01, 8192);
+        let now = Instant::now();
+        batcher.push(message(1, 100), now);
+        batcher.push(message(2, 100), now);
+        let frame = batcher.flush_due(now + Duration::from_secs(1)).unwrap();
+        assert!(split_batch(&frame).is_ok());
+        // truncated
+        assert!(split_batch(&frame[..frame.len() - 1]).is_err());
+        assert!(split_batch(&frame[..HEADER_LEN - 1]).is_err());
+        // trailing bytes
+        let mut longer = frame.clone();
+        longer.push(0);
+        assert!(split_batch(&longer).is_err());
+        // wrong count
+        let mut wrong_count = frame.clone();
+        wrong_count[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(split_batch(&wrong_count).is_err());
+        // unknown version
+        let mut unknown = frame.clone();
+        unknown[2] = 2;
+        assert!(split_batch(&unknown).is_err());
+        // a CDR message is not a batch
+        assert!(!is_batch(&message(1, 100)));
+        assert!(split_batch(&message(1, 100)).is_err());
+    }
+}
@@ -0,0 +1,180 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::Serializer;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zenoh_core::zlock;
+
+// Minimal period between 2 warnings logged by a same route for dropped invalid samples
+const WARNING_PERIOD: Duration = Duration::from_secs(1);
+
+// The representation identifiers of the encapsulation header (DDS-XTypes 1.3 §7.6.3.1.2):
+// CDR_BE, CDR_LE, PL_CDR_BE, PL_CDR_LE, CDR2_BE, CDR2_LE, PL_CDR2_BE, PL_CDR2_LE, D_CDR2_BE, D_CDR2_LE
+const KNOWN_REPRESENTATIONS: [[u8; 2]; 10] = [
+    [0x00, 0x00],
+    [0x00, 0x01],
+    [0x00, 0x02],
+    [0x00, 0x03],
+    [0x00, 0x10],
+    [0x00, 0x11],
+    [0x00, 0x12],
+    [0x00, 0x13],
+    [0x00, 0x14],
+    [0x00, 0x15],
+];
+
+/// Check the 4 bytes encapsulation header of a serialized sample: a known representation identifier,
+/// and options where only the 2 last bits (the count of padding bytes at the end of the sample) may be set.
+pub fn check_cdr_header(payload: &[u8]) -> Result<(), String> {
+    if payload.len() < 4 {
+        return Err(format!(
+            "{} bytes, shorter than a CDR encapsulation header",
+            payload.len()
+        ));
+    }
+    if !KNOWN_REPRESENTATIONS.contains(&[payload[0], payload[1]]) {
+        return Err(format!(
+            "unknown CDR representation identifier {:02x?}",
+            &payload[..2]
+        ));
+    }
+    if payload[2] != 0 || payload[3] & !0x03 != 0 {
+        return Err(format!(
+            "invalid CDR encapsulation options {:02x?}",
+            &payload[2..4]
+        ));
+    }
+    let padding = (payload[3] & 0x03) as usize;
+    if payload.len() - 4 < padding {
+        return Err(format!(
+            "{} bytes, shorter than its CDR encapsulation header and {padding} padding bytes",
+            payload.len()
+        ));
+    }
+    Ok(())
+}
+
+/// The check of the CDR encapsulation header of the samples routed by a route, with the counter of
+/// the invalid samples it dropped (not counted if `forward_invalid_cdr`, the samples being then only logged).
+pub struct CdrCheck {
+    forward_invalid: bool,
+    dropped: AtomicU64,
+    // time of the last logged warning, and number of invalid samples since
+    last_warning: Mutex<(Option<Instant>, u64)>,
+}
+
+impl CdrCheck {
+    pub fn new(forward_invalid: bool) -> CdrCheck {
+        CdrCheck {
+            forward_invalid,
+            dropped: AtomicU64::new(0),
+            last_warning: Mutex::new((None, 0)),
+        }
+    }
+
+    /// Return true if the sample can be routed.
+    /// Otherwise, count it as dropped and log a (rate-limited) warning with its `origin`.
+    pub fn check<F>(&self, payload: &[u8], route_id: &str, origin: F) -> bool
+    where
+        F: FnOnce() -> String,
+    {
+        let Err(e) = check_cdr_header(payload) else {
+            return true;
+        };
+        let total = if self.forward_invalid {
+            self.dropped.load(Ordering::Relaxed)
+        } else {
+            self.dropped.fetch_add(1, Ordering::Relaxed) + 1
+        };
+        let mut last_warning = zlock!(self.last_warning);
+        last_warning.1 += 1;
+        let now = Instant::now();
+        if last_warning
+            .0
+            .map_or(true, |t| now.duration_since(t) >= WARNING_PERIOD)
+        {
+            let action = if self.forward_invalid {
+                "forwarded (forward_invalid_cdr)"
+            } else {
+                "dropped"
+            };
+            tracing::warn!(
+                "{route_id}: {action} {} sample(s) with an invalid CDR header (last one from {}: {e}, total dropped: {total})",
+                last_warning.1,
+                origin()
+            );
+            *last_warning = (Some(now), 0);
+        }
+        self.forward_invalid
+    }
+
+    #[inline]
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+pub fn serialize_cdr_check<S>(check: &Arc<CdrCheck>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.serialize_u64(check.dropped())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_cdr_header, CdrCheck};
+
+    const CDR_LE_SAMPLE: [u8; 8] = [0x00, 0x01, 0x00, 0x00, 0x2a, 0x00, 0x00, 0x00];
+    const CDR_BE_SAMPLE: [u8; 8] = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2a];
+
+    #[test]
+    fn test_cdr_header() {
+        assert!(check_cdr_header(&CDR_LE_SAMPLE).is_ok());
+        assert!(check_cdr_header(&CDR_BE_SAMPLE).is_ok());
+        // XCDR2 with 3 padding bytes
+        assert!(check_cdr_header(&[0x00, 0x11, 0x00, 0x03, 0x01, 0x00, 0x00, 0x00]).is_ok());
+
+        // truncated
+        assert!(check_cdr_header(&[]).is_err());
+        assert!(check_cdr_header(&CDR_LE_SAMPLE[..3]).is_err());
+        assert!(check_cdr_header(&[0x00, 0x01, 0x00, 0x02, 0x00]).is_err());
+        // garbage representation identifier
+        assert!(check_cdr_header(&[0xde, 0xad, 0x00, 0x00, 0x2a]).is_err());
+        assert!(check_cdr_header(&[0x00, 0x04, 0x00, 0x00, 0x2a]).is_err());
+        // garbage options
+        assert!(check_cdr_header(&[0x00, 0x01, 0xff, 0x00, 0x2a]).is_err());
+        assert!(check_cdr_header(&[0x00, 0x01, 0x00, 0x04, 0x2a]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_samples_dropped() {
+        let check = CdrCheck::new(false);
+        assert!(check.check(&CDR_LE_SAMPLE, "test", || "writer1".into()));
+        assert!(check.check(&CDR_BE_SAMPLE, "test", || "writer1".into()));
+        assert!(!check.check(&[0xde, 0xad, 0xbe, 0xef], "test", || "writer2".into()));
+        assert!(!check.check(&[0x00, 0x01], "test", || "writer2".into()));
+        assert!(check.check(&CDR_LE_SAMPLE, "test", || "writer1".into()));
+        assert_eq!(check.dropped(), 2);
+    }
+
+    #[test]
+    fn test_forward_invalid() {
+        let check = CdrCheck::new(true);
+        assert!(check.check(&[0xde, 0xad, 0xbe, 0xef], "test", || "writer2".into()));
+        assert!(check.check(&CDR_BE_SAMPLE, "test", || "writer1".into()));
+        assert_eq!(check.dropped(), 0);
+    }
+}
@@ -0,0 +1,271 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use zenoh::buffers::ZBuf;
+use zenoh::prelude::*;
+
+use crate::config::Compression;
+
+// A compressed payload starts with a header made of:
+//  - the "ZC" marker, which can't start a CDR payload (its encapsulation identifier starts with 0x00).
+//    Thus a bridge that doesn't know this header never writes a compressed payload as a valid DDS message
+//  - the compression algorithm
+//  - a reserved byte (0)
+//  - the size of the uncompressed payload (u32 little endian)
+const MARKER: [u8; 2] = *b"ZC";
+const HEADER_LEN: usize = 8;
+const ALGO_LZ4: u8 = 1;
+const ALGO_ZSTD: u8 = 2;
+
+// The default zstd level (a good tradeoff between speed and ratio)
+const ZSTD_LEVEL: i32 = 3;
+
+// The maximum size of a decompressed payload, protecting against corrupted or malicious headers
+const MAX_DECOMPRESSED_SIZE: usize = 1 << 30;
+
+/// Compress a payload with its header. Return `None` if the payload is not to be compressed
+/// (i.e. `Compression::None`, or if the compressed payload is not smaller)
+pub fn compress(payload: &[u8], compression: Compression) -> Result<Option<Vec<u8>>, String> {
+    let algo = match compression {
+        Compression::None => return Ok(None),
+        Compression::Lz4 => ALGO_LZ4,
+        Compression::Zstd => ALGO_ZSTD,
+    };
+    let size = u32::try_from(payload.len()).map_err(|_| {
+        format!(
+            "payload too large to be compressed: {} bytes",
+            payload.len()
+        )
+    })?;
+    let compressed = match compression {
+        Compression::Lz4 => lz4_flex::block::compress(payload),
+        _ => zstd::bulk::compress(payload, ZSTD_LEVEL)
+            .map_err(|e| format!("zstd compression failed: {e}"))?,
+    };
+    if HEADER_LEN + compressed.len() >= payload.len() {
+        return Ok(None);
+    }
+    let mut result = Vec::with_capacity(HEADER_LEN + compressed.len());
+    result.extend_from_slice(&MARKER);
+    result.push(algo);
+    result.push(0);
+    result.extend_from_slice(&size.to_le_bytes());
+    result.extend_from_slice(&compressed);
+    Ok(Some(result))
+}
+
+/// If a payload was compressed by [`compress()`] (i.e. starts with the compression marker)
+#[inline]
+pub fn is_compressed(payload: &[u8]) -> bool {
+    payload.starts_with(&MARKER)
+}
+
+/// Decompress a payload compressed by [`compress()`], checking its header and its decompressed size
+pub fn decompress(payload: &[u8]) -> Result<Vec<u8>, String> {
+    if payload.len() < HEADER_LEN || !is_compressed(payload) {
+        return Err("not a compressed payload".into());
+    }
+    let size = u32::from_le_bytes([payload[4], payload[5], payload[6], payload[7]]) as usize;
+    if size > MAX_DECOMPRESSED_SIZE {
+        return Err(format!(
+            "compressed payload announcing a too large size: {size} bytes"
+        ));
+    }
+    let data = &payload[HEADER_LEN..];
+    let result = match payload[2] {
+        ALGO_LZ4 => lz4_flex::block::decompress(data, size)
+            .map_err(|e| format!("lz4 decompression failed: {e}"))?,
+        ALGO_ZSTD => zstd::bulk::decompress(data, size)
+            .map_err(|e| format!("zstd decompression failed: {e}"))?,
+        algo => return Err(format!("unknown compression algorithm: {algo}")),
+    };
+    if result.len() != size {
+        return Err(format!(
+            "decompressed payload of {} bytes instead of {size}",
+            result.len()
+        ));
+    }
+    Ok(result)
+}
+
+/// Compress a payload to be routed over Zenoh (unchanged if not to be compressed)
+pub fn compress_zbuf(payload: ZBuf, compression: Compression) -> Result<ZBuf, String> {
+    if compression == Compression::None {
+        return Ok(payload);
+    }
+    match compress(&payload.contiguous(), compression)? {
+        Some(compressed) => Ok(compressed.into()),
+        None => Ok(payload),
+    }
+}
+
+/// Decompress a payload received from Zenoh (unchanged if not compressed)
+pub fn decompress_zbuf(payload: ZBuf) -> Result<ZBuf, String> {
+    let decompressed = {
+        let bytes = payload.contiguous();
+        if !is_compressed(&bytes) {
+            None
+        } else {
+            Some(decompress(&bytes)?)
+        }
+    };
+    Ok(decompressed.map_or(payload, ZBuf::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A realistic PointCloud2 message (CDR) of a 16 rings lidar with a PCL PointXYZI layout
+    // (x, y, z, padding, intensity, padding: 32 bytes per point), its upper rings having no return
+    fn point_cloud2_cdr() -> Vec<u8> {
+        fn push_string(cdr: &mut Vec<u8>, s: &str) {
+            while cdr.len() % 4 != 0 {
+                cdr.push(0);
+            }
+            cdr.extend_from_slice(&(s.len() as u32 + 1).to_le_bytes());
+            cdr.extend_from_slice(s.as_bytes());
+            cdr.push(0);
+        }
+        fn push_u32(cdr: &mut Vec<u8>, v: u32) {
+            while cdr.len() % 4 != 0 {
+                cdr.push(0);
+            }
+            cdr.extend_from_slice(&v.to_le_bytes());
+        }
+
+        const RINGS: u32 = 16;
+        const COLUMNS: u32 = 1024;
+        const POINT_STEP: u32 = 32;
+        // CDR_LE encapsulation
+        let mut cdr = vec![0x00, 0x01, 0x00, 0x00];
+        // header: stamp and frame_id
+        cdr.extend_from_slice(&1_700_000_000u32.to_le_bytes());
+        cdr.extend_from_slice(&123_456_789u32.to_le_bytes());
+        push_string(&mut cdr, "velodyne");
+        // height, width
+        push_u32(&mut cdr, RINGS);
+        push_u32(&mut cdr, COLUMNS);
+        // fields: name, offset, datatype (FLOAT32), count
+        push_u32(&mut cdr, 4);
+        for (name, offset) in [("x", 0), ("y", 4), ("z", 8), ("intensity", 16)] {
+            push_string(&mut cdr, name);
+            push_u32(&mut cdr, offset);
+            cdr.push(7);
+            push_u32(&mut cdr, 1);
+        }
+        // is_bigendian, point_step, row_step
+        cdr.push(0);
+        push_u32(&mut cdr, POINT_STEP);
+        push_u32(&mut cdr, POINT_STEP * COLUMNS);
+        // data
+        push_u32(&mut cdr, POINT_STEP * COLUMNS * RINGS);
+        for ring in 0..RINGS {
+            let elevation = (ring as f32 - 12.0).to_radians() * 2.0;
+            for column in 0..COLUMNS {
+                let mut point = [0u8; POINT_STEP as usize];
+                if ring < 12 {
+                    let azimuth = column as f32 * std::f32::consts::TAU / COLUMNS as f32;
+                    let range = 8.0 + 2.0 * (azimuth * 3.0).sin() + ring as f32 * 0.5;
+                    let (x, y, z) = (
+                        range * elevation.cos() * azimuth.cos(),
+                        range * elevation.cos() * azimuth.sin(),
+                        range * elevation.sin(),
+                    );
+                    let intensity = ((column / 64 + ring) % 8) as f32 * 16.0;
+                    point[0..4].copy_from_slice(&x.to_le_bytes());
+                    point[4..8].copy_from_slice(&y.to_le_bytes());
+                    point[8..12].copy_from_slice(&z.to_le_bytes());
+                    point[16..20].copy_from_slice(&intensity.to_le_bytes());
+                } else {
+                    for offset in [0, 4, 8, 16] {
+                        point[offset..offset + 4].copy_from_slice(&f32::NAN.to_le_bytes());
+                    }
+                }
+                cdr.extend_from_slice(&point);
+            }
+        }
+        // is_dense
+        cdr.push(0);
+        cdr
+    }
+
+    #[test]
+    fn test_compression_round_trip() {
+        let payload = point_cloud2_cdr();
+        assert!(!is_compressed(&payload));
+        for compression in [Compression::Lz4, Compression::Zstd] {
+            let compressed = compress(&payload, compression).unwrap().unwrap();
+            assert!(is_compressed(&compressed));
+            assert_eq!(decompress(&compressed).unwrap(), payload);
+        }
+        assert_eq!(compress(&payload, Compression::None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_compression_size_reduction() {
+        let payload = point_cloud2_cdr();
+        let lz4 = compress(&payload, Compression::Lz4).unwrap().unwrap();
+        let zstd = compress(&payload, Compression::Zstd).unwrap().unwrap();
+        assert!(
+            lz4.len() * 10 < payload.len() * 6,
+            "lz4: {} -> {} bytes",
+            payload.len(),
+            lz4.len()
+        );
+        assert!(
+            zstd.len() * 10 < payload.len() * 5,
+            "zstd: {} -> {} bytes",
+            payload.len(),
+            zstd.len()
+        );
+    }
+
+    #[test]
+    fn test_compression_not_smaller() {
+        // a small CDR payload (e.g. a std_msgs/String) is routed uncompressed
+        let payload = [0x00, 0x01, 0x00, 0x00, 3, 0, 0, 0, b'h', b'i', 0];
+        assert_eq!(compress(&payload, Compression::Lz4).unwrap(), None);
+        assert_eq!(compress(&payload, Compression::Zstd).unwrap(), None);
+    }
+
+    #[test]
+    fn test_decompress_corrupted() {
+        let payload = point_cloud2_cdr();
+        for compression in [Compression::Lz4, Compression::Zstd] {
+            let compressed = compress(&payload, compression).unwrap().unwrap();
+            // truncated
+            assert!(decompress(&compressed[..compressed.len() / 2]).is_err());
+            assert!(decompress(&compressed[..HEADER_LEN - 1]).is_err());
+            // announcing another size
+            let mut wrong_size = compressed.clone();
+            wrong_size[4..8].copy_from_slice(&(payload.len() as u32 - 1).to_le_bytes());
+            assert!(decompress(&wrong_size).is_err());
+            let mut too_large = compressed.clone();
+            too_large[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+            assert!(decompress(&too_large).is_err());
+            // corrupted data
+            let mut corrupted = compressed.clone();
+            for b in corrupted[HEADER_LEN..HEADER_LEN + 16].iter_mut() {
+                *b = 0xff;
+            }
+            assert!(decompress(&corrupted).is_err());
+            // unknown algorithm
+            let mut unknown = compressed.clone();
+            unknown[2] = 42;
+            assert!(decompress(&unknown).is_err());
+        }
+        // not compressed
+        assert!(decompress(&payload).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::{ser::SerializeStruct, Serializer};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use zenoh_core::zlock;
+
+/// A deferred forwarding of a Service request, run once admitted by the route's [`ConcurrencyLimit`]
+pub type Task = Box<dyn FnOnce() + Send>;
+
+/// The bound on the count of the requests of a Service in progress on a route (see `max_concurrent_requests`).
+/// The requests beyond it wait for a request in progress to complete, in their order of arrival.
+/// Without bound, the requests are always admitted at once (only their count is tracked).
+pub struct ConcurrencyLimit<T> {
+    max: Option<usize>,
+    state: Mutex<LimitState<T>>,
+}
+
+struct LimitState<T> {
+    in_progress: usize,
+    waiting: VecDeque<T>,
+}
+
+impl<T> Default for ConcurrencyLimit<T> {
+    fn default() -> Self {
+        ConcurrencyLimit::new(None)
+    }
+}
+
+impl<T> ConcurrencyLimit<T> {
+    pub fn new(max: Option<usize>) -> ConcurrencyLimit<T> {
+        ConcurrencyLimit {
+            max,
+            state: Mutex::new(LimitState {
+                in_progress: 0,
+                waiting: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Admit a task: it's returned to be run at once if below the bound, or else it waits (returning None)
+    pub fn admit(&self, task: T) -> Option<T> {
+        let mut state = zlock!(self.state);
+        if self.max.map_or(true, |max| state.in_progress < max) {
+            state.in_progress += 1;
+            Some(task)
+        } else {
+            state.waiting.push_back(task);
+            None
+        }
+    }
+
+    /// On completion of a task in progress, the next waiting task to be run in its place, if any
+    pub fn release(&self) -> Option<T> {
+        let mut state = zlock!(self.state);
+        let next = state.waiting.pop_front();
+        if next.is_none() {
+            state.in_progress = state.in_progress.saturating_sub(1);
+        }
+        next
+    }
+
+    pub fn in_progress(&self) -> usize {
+        zlock!(self.state).in_progress
+    }
+
+    pub fn waiting(&self) -> usize {
+        zlock!(self.state).waiting.len()
+    }
+}
+
+impl ConcurrencyLimit<Task> {
+    /// On completion of a request in progress, forward the next waiting one
+    /// (in a separate task, out of the locks of the caller's route)
+    pub fn on_completed(&self) {
+        if let Some(task) = self.release() {
+            async_std::task::spawn(async move { task() });
+        }
+    }
+}
+
+pub fn serialize_concurrency_limit<S, L, T>(limit: &L, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    L: std::ops::Deref<Target = ConcurrencyLimit<T>>,
+{
+    let mut s = serializer.serialize_struct("ConcurrencyLimit", 3)?;
+    s.serialize_field("max", &limit.max)?;
+    s.serialize_field("in_progress", &limit.in_progress())?;
+    s.serialize_field("waiting", &limit.waiting())?;
+    s.end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConcurrencyLimit;
+
+    #[test]
+    fn test_concurrency_limit() {
+        let limit = ConcurrencyLimit::new(Some(2));
+        assert_eq!(limit.admit(1), Some(1));
+        assert_eq!(limit.admit(2), Some(2));
+        // beyond the bound, the tasks wait in their order of arrival
+        assert_eq!(limit.admit(3), None);
+        assert_eq!(limit.admit(4), None);
+        assert_eq!((limit.in_progress(), limit.waiting()), (2, 2));
+        assert_eq!(limit.release(), Some(3));
+        assert_eq!(limit.release(), Some(4));
+        assert_eq!((limit.in_progress(), limit.waiting()), (2, 0));
+        assert_eq!(limit.release(), None);
+        assert_eq!(limit.admit(5), Some(5));
+        assert_eq!(limit.release(), None);
+        assert_eq!(limit.release(), None);
+        assert_eq!(limit.release(), None);
+        assert_eq!((limit.in_progress(), limit.waiting()), (0, 0));
+    }
+
+    #[test]
+    fn test_unbounded_concurrency() {
+        let limit = ConcurrencyLimit::default();
+        for i in 0..100 {
+            assert_eq!(limit.admit(i), Some(i));
+        }
+        assert_eq!((limit.in_progress(), limit.waiting()), (100, 0));
+        assert_eq!(limit.release(), None);
+        assert_eq!(limit.in_progress(), 99);
+    }
+}
@@ -12,62 +12,465 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use regex::Regex;
-use serde::{de, de::Visitor, ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+use serde::{
+    de, de::IntoDeserializer, de::Visitor, ser::SerializeMap, ser::SerializeSeq, Deserialize,
+    Deserializer, Serialize, Serializer,
+};
+use std::collections::HashMap;
 use std::env;
 use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
 use std::time::Duration;
 use zenoh::prelude::*;
 
+use crate::cdr_check::check_cdr_header;
+use crate::forward_discovery::RouteKind;
+use crate::ros2_utils::{
+    is_hidden_ros2_name, is_ros2_dds_topic, parameter_service_node, route_to_target,
+    service_of_event_topic, split_action_suffix, KE_SUFFIX_ACTION_FEEDBACK,
+    ROS2_PARAMETER_EVENTS_TOPIC,
+};
+
 pub const DEFAULT_NAMESPACE: &str = "/";
 pub const DEFAULT_NODENAME: &str = "zenoh_bridge_ros2dds";
 pub const DEFAULT_DOMAIN: u32 = 0;
 pub const DEFAULT_RELIABLE_ROUTES_BLOCKING: bool = true;
+pub const DEFAULT_PROPAGATE_DEADLINE: bool = true;
+pub const DEFAULT_WRITE_SUPPRESSION: bool = true;
+pub const DEFAULT_LATCHED_AGGREGATE_TOPIC: &str = "/tf_static";
+pub const DEFAULT_PROPAGATE_TIME_BASED_FILTER: bool = true;
+// the History depth of the ROS parameter events QoS (see `bridge_parameters`)
+pub const PARAMETER_EVENTS_HISTORY_DEPTH: usize = 1000;
 pub const DEFAULT_TRANSIENT_LOCAL_CACHE_MULTIPLIER: usize = 10;
 pub const DEFAULT_DDS_LOCALHOST_ONLY: bool = false;
 pub const DEFAULT_QUERIES_TIMEOUT: f32 = 5.0;
+pub const DEFAULT_RECONNECT_MAX_ATTEMPTS: u32 = 3;
+pub const DEFAULT_RECONNECT_PERIOD: f32 = 1.0;
+pub const DEFAULT_DDS_RETRY_MAX_ATTEMPTS: u32 = 10;
+pub const DEFAULT_DDS_RETRY_INITIAL_PERIOD: f32 = 0.1;
+pub const DEFAULT_DDS_RETRY_MAX_PERIOD: f32 = 10.0;
+pub const DEFAULT_DISCOVERY_BATCH_WINDOW_MS: u64 = 50;
+pub const DEFAULT_MAX_CONCURRENT_ROUTE_CREATIONS: usize = 16;
+pub const DEFAULT_MAX_BATCH_DELAY: f64 = 0.005;
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 8192;
+pub const DEFAULT_HEAVY_SERVICES_WORKERS: usize = 2;
+pub const DEFAULT_HEAVY_SERVICES_QUEUE_SIZE: usize = 64;
+pub const DEFAULT_STATS_RATE_HALF_LIFE: f32 = 5.0;
+pub const DEFAULT_BANDWIDTH_SHEDDING_PRIORITY: Priority = Priority::DataHigh;
+pub const DEFAULT_SERVICE_RETRY_BACKOFF: f64 = 0.1;
 
 #[derive(Deserialize, Debug, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
+    #[serde(default)]
+    pub profile: Option<Profile>,
     #[serde(default)]
     pub id: Option<OwnedKeyExpr>,
+    #[serde(default)]
+    pub alias: Option<String>,
     #[serde(default = "default_namespace")]
     pub namespace: String,
+    #[serde(default)]
+    pub namespace_to_zenoh: Option<String>,
+    #[serde(default)]
+    pub namespace_from_zenoh: Option<String>,
+    #[serde(default)]
+    pub key_prefix_from_env: Option<String>,
+    // the key prefix read from the `key_prefix_from_env` variable (see resolve_key_prefix())
+    #[serde(skip)]
+    key_prefix: Option<String>,
+    #[serde(default)]
+    pub scope_out: Option<OwnedKeyExpr>,
+    #[serde(default)]
+    pub scope_in: Option<OwnedKeyExpr>,
+    #[serde(default)]
+    pub typed_key_exprs: bool,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_regex",
+        serialize_with = "serialize_regex"
+    )]
+    pub instance_key_exprs: Option<Regex>,
+    #[serde(default, deserialize_with = "deserialize_namespace_rules")]
+    pub namespace_rules: Vec<NamespaceRule>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_remappings",
+        serialize_with = "serialize_remappings"
+    )]
+    pub remappings: Vec<Remapping>,
     #[serde(default = "default_nodename")]
     pub nodename: OwnedKeyExpr,
     #[serde(default = "default_domain")]
     pub domain: u32,
     #[serde(default = "default_localhost_only")]
     pub ros_localhost_only: bool,
+    #[serde(default)]
+    pub dds_network_interfaces: Vec<String>,
+    #[serde(default)]
+    pub mode: RoutingMode,
     #[serde(default, flatten)]
     pub allowance: Option<Allowance>,
+    #[serde(default)]
+    pub bridge_hidden_topics: bool,
+    #[serde(default, deserialize_with = "deserialize_bridge_parameters")]
+    pub bridge_parameters: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_raw_dds_topics")]
+    pub raw_dds_topics: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_segment_separators")]
+    pub segment_separators: Vec<char>,
     #[serde(
         default,
         deserialize_with = "deserialize_vec_regex_f32",
         serialize_with = "serialize_vec_regex_f32"
     )]
     pub pub_max_frequencies: Vec<(Regex, f32)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_f32",
+        serialize_with = "serialize_vec_regex_f32"
+    )]
+    pub sub_max_frequencies: Vec<(Regex, f32)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_f32",
+        serialize_with = "serialize_vec_regex_f32"
+    )]
+    pub feedback_max_frequency: Vec<(Regex, f32)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_rate_limit_policy",
+        serialize_with = "serialize_vec_regex_rate_limit_policy"
+    )]
+    pub rate_limit_policy: Vec<(Regex, RateLimitPolicy)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_compression",
+        serialize_with = "serialize_vec_regex_compression"
+    )]
+    pub compression: Vec<(Regex, Compression)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_batching",
+        serialize_with = "serialize_vec_regex_map"
+    )]
+    pub batching: Vec<(Regex, BatchingConf)>,
     #[serde(default)]
     #[cfg(feature = "dds_shm")]
     pub shm_enabled: bool,
     #[serde(default = "default_transient_local_cache_multiplier")]
     pub transient_local_cache_multiplier: usize,
     #[serde(default)]
+    pub transient_local_cache: Option<TransientLocalCacheConf>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_regex",
+        serialize_with = "serialize_regex"
+    )]
+    pub force_transient_local: Option<Regex>,
+    #[serde(
+        default = "default_latched_aggregate_topics",
+        deserialize_with = "deserialize_regex",
+        serialize_with = "serialize_regex"
+    )]
+    pub latched_aggregate_topics: Option<Regex>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_usize",
+        serialize_with = "serialize_vec_regex_usize"
+    )]
+    pub max_payload_size: Vec<(Regex, usize)>,
+    #[serde(default)]
+    pub max_bandwidth: u64,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_prio",
+        serialize_with = "serialize_option_prio"
+    )]
+    pub bandwidth_shedding_priority: Option<Priority>,
+    #[serde(default, deserialize_with = "deserialize_queries_timeout")]
     pub queries_timeout: Option<QueriesTimeouts>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_service_error_replies",
+        serialize_with = "serialize_service_error_replies"
+    )]
+    pub service_error_replies: Vec<(Regex, Vec<u8>)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_service_retries",
+        serialize_with = "serialize_vec_regex_map"
+    )]
+    pub service_retries: Vec<(Regex, ServiceRetryPolicy)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_service_unavailable_policy",
+        serialize_with = "serialize_vec_regex_service_unavailable_policy"
+    )]
+    pub on_service_unavailable: Vec<(Regex, ServiceUnavailablePolicy)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_f32",
+        serialize_with = "serialize_vec_regex_f32"
+    )]
+    pub long_calls: Vec<(Regex, f32)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_usize",
+        serialize_with = "serialize_vec_regex_usize"
+    )]
+    pub max_concurrent_requests: Vec<(Regex, usize)>,
+    #[serde(default)]
+    pub heavy_services: Option<HeavyServicesConf>,
     #[serde(default = "default_reliable_routes_blocking")]
     pub reliable_routes_blocking: bool,
+    #[serde(default)]
+    pub deduplication: bool,
+    #[serde(default)]
+    pub source_info: bool,
+    #[serde(default)]
+    pub sequence_tracking: bool,
+    #[serde(default)]
+    pub sequence_gap_log_threshold: Option<u64>,
+    #[serde(default = "default_write_suppression")]
+    pub write_suppression: bool,
+    #[serde(default)]
+    pub forward_invalid_cdr: bool,
     #[serde(
         default,
         deserialize_with = "deserialize_vec_regex_prio",
         serialize_with = "serialize_vec_regex_prio"
     )]
     pub pub_priorities: Vec<(Regex, Priority)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_reliability",
+        serialize_with = "serialize_vec_regex_reliability"
+    )]
+    pub reliability: Vec<(Regex, ReliabilityOverride)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_reliability",
+        serialize_with = "serialize_vec_regex_reliability"
+    )]
+    pub zenoh_reliability: Vec<(Regex, ReliabilityOverride)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_bool",
+        serialize_with = "serialize_vec_regex_bool"
+    )]
+    pub keyed: Vec<(Regex, bool)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_deadline_miss_policy",
+        serialize_with = "serialize_vec_regex_deadline_miss_policy"
+    )]
+    pub on_deadline_miss: Vec<(Regex, DeadlineMissPolicy)>,
+    #[serde(default = "default_propagate_deadline")]
+    pub propagate_deadline: bool,
+    #[serde(default = "default_propagate_time_based_filter")]
+    pub propagate_time_based_filter: bool,
+    #[serde(default)]
+    pub auto_adapt_qos: bool,
+    #[serde(default)]
+    pub keep_all_max_depth: Option<usize>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_dds_partition",
+        serialize_with = "serialize_dds_partition"
+    )]
+    pub dds_partition: Vec<(Regex, Vec<String>)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_route_to",
+        serialize_with = "serialize_vec_regex_map"
+    )]
+    pub route_to: Vec<(Regex, Vec<String>)>,
+    #[serde(default)]
+    pub qos_presets: HashMap<String, TopicQos>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_topic_qos",
+        serialize_with = "serialize_vec_regex_map"
+    )]
+    pub topic_qos: Vec<(Regex, TopicQos)>,
+    #[serde(default)]
+    pub topic_types: HashMap<String, String>,
+    #[serde(default)]
+    pub sample_transforms: HashMap<String, String>,
+    #[serde(default)]
+    pub long_name_policy: LongNamePolicy,
+    #[serde(default)]
+    pub startup_grace_period: f32,
+    #[serde(default)]
+    pub route_linger: f32,
+    #[serde(default, deserialize_with = "deserialize_route_idle_timeout")]
+    pub route_idle_timeout: Option<RouteIdleTimeouts>,
+    #[serde(default = "default_discovery_batch_window")]
+    pub discovery_batch_window: u64,
+    #[serde(default = "default_max_concurrent_route_creations")]
+    pub max_concurrent_route_creations: usize,
+    #[serde(default = "default_stats_rate_half_life")]
+    pub stats_rate_half_life: f32,
+    #[serde(default)]
+    pub reconnect: ReconnectConf,
+    #[serde(default)]
+    pub dds_retry: DdsRetryConf,
     __required__: Option<bool>,
     #[serde(default, deserialize_with = "deserialize_path")]
     __path__: Option<Vec<String>>,
 }
 
+// The configuration fields which values must not be exposed (e.g. credentials).
+// Their values are replaced with "<redacted>" by Config::to_redacted_json()
+const REDACTED_FIELDS: &[&str] = &[];
+
 impl Config {
+    /// The effective configuration (i.e. including the default values) as JSON,
+    /// with the values of the sensitive fields redacted.
+    pub fn to_redacted_json(&self) -> serde_json::Result<serde_json::Value> {
+        let mut value = serde_json::to_value(self)?;
+        redact_json(&mut value, REDACTED_FIELDS);
+        Ok(value)
+    }
+
+    /// Check the consistency between configuration fields, that can't be done when deserializing each of them:
+    /// the presets referenced in `topic_qos` must be defined in `qos_presets` (and the presets can't reference another one).
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, preset) in &self.qos_presets {
+            if let Some(other) = &preset.preset {
+                return Err(format!(
+                    r#"qos_presets: the preset "{name}" cannot reference another preset ("{other}")"#
+                ));
+            }
+        }
+        for (re, topic_qos) in &self.topic_qos {
+            if let Some(preset) = &topic_qos.preset {
+                if !self.qos_presets.contains_key(preset) {
+                    return Err(format!(
+                        r#"topic_qos: "{}" references the undefined preset "{preset}""#,
+                        re.as_str()
+                    ));
+                }
+            }
+        }
+        for pattern in self.sample_transforms.keys() {
+            if keyexpr::new(pattern.as_str()).is_err() {
+                return Err(format!(
+                    r#"sample_transforms: "{pattern}" is not a valid key expression"#
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the key prefix from the `key_prefix_from_env` environment variable (if configured),
+    /// and prefix `namespace`, `namespace_to_zenoh` and `namespace_from_zenoh` with it.
+    /// An error is returned if the variable is unset or is not a valid key prefix.
+    pub fn resolve_key_prefix(&mut self) -> Result<(), String> {
+        self.resolve_key_prefix_with(|name| env::var(name).ok())
+    }
+
+    fn resolve_key_prefix_with<F>(&mut self, lookup: F) -> Result<(), String>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let Some(var) = &self.key_prefix_from_env else {
+            return Ok(());
+        };
+        if self.key_prefix.is_some() {
+            // already resolved
+            return Ok(());
+        }
+        let prefix = lookup(var).ok_or_else(|| {
+            format!("key_prefix_from_env: the environment variable {var} is not set (it's required to prefix the key expressions)")
+        })?;
+        if prefix.is_empty()
+            || !prefix
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(format!(
+                r#"key_prefix_from_env: invalid value "{prefix}" of the environment variable {var}: it must be a single key expression chunk with only alphanumeric or '_' characters"#
+            ));
+        }
+        let prefixed = |namespace: &str| match namespace {
+            "/" => format!("/{prefix}"),
+            _ => format!("/{prefix}{namespace}"),
+        };
+        self.namespace = prefixed(&self.namespace);
+        for namespace in [&mut self.namespace_to_zenoh, &mut self.namespace_from_zenoh]
+            .into_iter()
+            .flatten()
+        {
+            *namespace = prefixed(namespace);
+        }
+        self.key_prefix = Some(prefix);
+        Ok(())
+    }
+
+    /// The key prefix read from the `key_prefix_from_env` variable (None if not configured or not resolved yet)
+    pub fn get_key_prefix(&self) -> Option<&str> {
+        self.key_prefix.as_deref()
+    }
+
+    /// The namespace prefixing the key expressions of the interfaces routed from DDS to zenoh
+    /// (i.e. for the local Publishers, Service Servers and Action Servers).
+    /// It's `namespace_to_zenoh` if configured, `namespace` otherwise.
+    pub fn get_namespace_to_zenoh(&self) -> &str {
+        self.namespace_to_zenoh
+            .as_deref()
+            .unwrap_or(&self.namespace)
+    }
+
+    /// The namespace removed from the key expressions of the interfaces routed from zenoh to DDS
+    /// (i.e. for the local Subscribers, Service Clients and Action Clients).
+    /// It's `namespace_from_zenoh` if configured, `namespace` otherwise.
+    pub fn get_namespace_from_zenoh(&self) -> &str {
+        self.namespace_from_zenoh
+            .as_deref()
+            .unwrap_or(&self.namespace)
+    }
+
+    /// Check if a DDS topic is a raw DDS topic to be bridged: not following the ROS 2 naming
+    /// conventions and matching one of the `raw_dds_topics` patterns.
+    pub fn is_raw_dds_topic(&self, dds_topic: &str) -> bool {
+        !is_ros2_dds_topic(dds_topic)
+            && self
+                .raw_dds_topics
+                .iter()
+                .any(|pattern| glob_match(pattern, dds_topic))
+    }
+
+    /// Check if a local interface can be bridged regarding its name:
+    /// a hidden name (e.g. "/_internal/status") is bridged only if `bridge_hidden_topics` is true.
+    /// The introspection events topic of a Service (e.g. "/add_two_ints/_service_event") is bridged
+    /// as its Service.
+    pub fn is_name_bridged(&self, ros2_name: &str) -> bool {
+        self.bridge_hidden_topics
+            || !is_hidden_ros2_name(service_of_event_topic(ros2_name).unwrap_or(ros2_name))
+    }
+
+    /// Check if the parameters of a Node are bridged, i.e. if its name matches one of the
+    /// `bridge_parameters` patterns
+    pub fn is_node_parameters_bridged(&self, node: &str) -> bool {
+        self.bridge_parameters
+            .iter()
+            .any(|pattern| glob_match(pattern, node))
+    }
+
+    /// Check if an interface is bridged for the parameters of the Nodes configured in `bridge_parameters`:
+    /// either a parameter Service of such a Node (e.g. "/talker/get_parameters"), either the "/parameter_events" topic.
+    /// Such an interface is allowed whatever the `allow`/`deny` configuration.
+    pub fn is_parameters_interface(&self, ros2_name: &str) -> bool {
+        if ros2_name == ROS2_PARAMETER_EVENTS_TOPIC {
+            return !self.bridge_parameters.is_empty();
+        }
+        parameter_service_node(ros2_name)
+            .map_or(false, |(node, _)| self.is_node_parameters_bridged(node))
+    }
+
     pub fn get_pub_max_frequencies(&self, ros2_name: &str) -> Option<f32> {
         for (re, freq) in &self.pub_max_frequencies {
             if re.is_match(ros2_name) {
@@ -77,678 +480,4838 @@ impl Config {
         None
     }
 
-    pub fn get_pub_priorities(&self, ros2_name: &str) -> Option<Priority> {
-        for (re, p) in &self.pub_priorities {
+    /// The maximum frequency of the messages routed from Zenoh to a Subscriber, if configured
+    /// in `sub_max_frequencies`
+    pub fn get_sub_max_frequencies(&self, ros2_name: &str) -> Option<f32> {
+        for (re, freq) in &self.sub_max_frequencies {
             if re.is_match(ros2_name) {
-                return Some(*p);
+                return Some(*freq);
             }
         }
         None
     }
 
-    pub fn get_queries_timeout_tl_sub(&self, ros2_name: &str) -> Duration {
-        if let Some(qt) = &self.queries_timeout {
-            for (re, secs) in &qt.transient_local_subscribers {
-                if re.is_match(ros2_name) {
-                    return Duration::from_secs_f32(*secs);
-                }
+    /// The maximum frequency of the feedback messages of an Action (from its feedback topic name),
+    /// if configured in `feedback_max_frequency` for the Action
+    pub fn get_feedback_max_frequency(&self, ros2_name: &str) -> Option<f32> {
+        match split_action_suffix(ros2_name) {
+            Some((action_name, suffix)) if suffix == KE_SUFFIX_ACTION_FEEDBACK.as_str() => self
+                .feedback_max_frequency
+                .iter()
+                .find(|(re, _)| re.is_match(action_name))
+                .map(|(_, freq)| *freq),
+            _ => None,
+        }
+    }
+
+    /// The policy of the `pub_max_frequencies` and `sub_max_frequencies` for a topic
+    /// (periodic sampling by default)
+    pub fn get_rate_limit_policy(&self, ros2_name: &str) -> RateLimitPolicy {
+        for (re, p) in &self.rate_limit_policy {
+            if re.is_match(ros2_name) {
+                return *p;
             }
-            return Duration::from_secs_f32(qt.default);
         }
-        Duration::from_secs_f32(DEFAULT_QUERIES_TIMEOUT)
+        RateLimitPolicy::default()
     }
 
-    pub fn get_queries_timeout_service(&self, ros2_name: &str) -> Duration {
-        if let Some(qt) = &self.queries_timeout {
-            for (re, secs) in &qt.services {
-                if re.is_match(ros2_name) {
-                    return Duration::from_secs_f32(*secs);
-                }
+    /// The compression of the payloads routed over Zenoh by a Publisher route (none by default)
+    pub fn get_compression(&self, ros2_name: &str) -> Compression {
+        for (re, c) in &self.compression {
+            if re.is_match(ros2_name) {
+                return *c;
             }
-            return Duration::from_secs_f32(qt.default);
         }
-        Duration::from_secs_f32(DEFAULT_QUERIES_TIMEOUT)
+        Compression::default()
     }
 
-    pub fn get_queries_timeout_action_send_goal(&self, ros2_name: &str) -> Duration {
-        if let Some(QueriesTimeouts {
-            default,
-            actions: Some(at),
-            ..
-        }) = &self.queries_timeout
-        {
-            for (re, secs) in &at.send_goal {
-                if re.is_match(ros2_name) {
-                    return Duration::from_secs_f32(*secs);
-                }
+    /// The batching of the messages routed over Zenoh by a Publisher route, if configured for this topic
+    pub fn get_batching(&self, ros2_name: &str) -> Option<&BatchingConf> {
+        self.batching
+            .iter()
+            .find(|(re, _)| re.is_match(ros2_name))
+            .map(|(_, conf)| conf)
+    }
+
+    /// The Zenoh Priority configured for a Publisher (for its publications), or for a Service or an Action
+    /// Server (for the replies to its long calls), in `topic_qos` or in `pub_priorities`
+    pub fn get_pub_priorities(&self, ros2_name: &str) -> Option<Priority> {
+        if let Some(p) = self.get_topic_qos(ros2_name).and_then(|q| q.zenoh_priority) {
+            return Some(p);
+        }
+        for (re, p) in &self.pub_priorities {
+            if re.is_match(ros2_name) {
+                return Some(*p);
             }
-            return Duration::from_secs_f32(*default);
         }
-        Duration::from_secs_f32(DEFAULT_QUERIES_TIMEOUT)
+        None
     }
 
-    pub fn get_queries_timeout_action_cancel_goal(&self, ros2_name: &str) -> Duration {
-        if let Some(QueriesTimeouts {
-            default,
-            actions: Some(at),
-            ..
-        }) = &self.queries_timeout
-        {
-            for (re, secs) in &at.cancel_goal {
-                if re.is_match(ros2_name) {
-                    return Duration::from_secs_f32(*secs);
-                }
+    /// The period after startup during which the routes creation is deferred, if configured
+    pub fn get_startup_grace_period(&self) -> Option<Duration> {
+        if self.startup_grace_period > 0.0 {
+            Some(Duration::from_secs_f32(self.startup_grace_period))
+        } else {
+            None
+        }
+    }
+
+    /// The half-life of the rates of the routed messages reported in the routes statistics
+    /// (the default one if not strictly positive)
+    pub fn get_stats_rate_half_life(&self) -> Duration {
+        if self.stats_rate_half_life > 0.0 {
+            Duration::from_secs_f32(self.stats_rate_half_life)
+        } else {
+            Duration::from_secs_f32(DEFAULT_STATS_RATE_HALF_LIFE)
+        }
+    }
+
+    /// The delay a Publisher or Subscriber route is kept after the undiscovery of its last local Node, if configured
+    pub fn get_route_linger(&self) -> Option<Duration> {
+        if self.route_linger > 0.0 {
+            Some(Duration::from_secs_f32(self.route_linger))
+        } else {
+            None
+        }
+    }
+
+    /// The inactivity timeout after which a route of this kind without local Node is removed, if configured
+    pub fn get_route_idle_timeout(&self, kind: RouteKind) -> Option<Duration> {
+        let timeouts = self.route_idle_timeout.as_ref()?;
+        let timeout = match kind {
+            RouteKind::Publisher => timeouts.publishers,
+            RouteKind::Subscriber => timeouts.subscribers,
+            RouteKind::ServiceSrv => timeouts.service_servers,
+            RouteKind::ServiceCli => timeouts.service_clients,
+            RouteKind::ActionSrv => timeouts.action_servers,
+            RouteKind::ActionCli => timeouts.action_clients,
+        }
+        .unwrap_or(timeouts.default);
+        if timeout > 0.0 {
+            Some(Duration::from_secs_f32(timeout))
+        } else {
+            None
+        }
+    }
+
+    /// The window during which the discovery events are coalesced before being processed, if not 0
+    pub fn get_discovery_batch_window(&self) -> Option<Duration> {
+        if self.discovery_batch_window > 0 {
+            Some(Duration::from_millis(self.discovery_batch_window))
+        } else {
+            None
+        }
+    }
+
+    /// The type expected for a topic, if configured in `topic_types`
+    pub fn get_topic_type(&self, ros2_name: &str) -> Option<&str> {
+        self.topic_types.get(ros2_name).map(String::as_str)
+    }
+
+    pub fn get_deadline_miss_policy(&self, ros2_name: &str) -> Option<DeadlineMissPolicy> {
+        for (re, p) in &self.on_deadline_miss {
+            if re.is_match(ros2_name) {
+                return Some(*p);
             }
-            return Duration::from_secs_f32(*default);
         }
-        Duration::from_secs_f32(DEFAULT_QUERIES_TIMEOUT)
+        None
     }
 
-    pub fn get_queries_timeout_action_get_result(&self, ros2_name: &str) -> Duration {
-        if let Some(QueriesTimeouts {
-            default,
-            actions: Some(at),
-            ..
-        }) = &self.queries_timeout
-        {
-            for (re, secs) in &at.get_result {
-                if re.is_match(ros2_name) {
-                    return Duration::from_secs_f32(*secs);
-                }
+    /// The maximum size of the payloads routed for a topic, if configured. 0 means no limit.
+    pub fn get_max_payload_size(&self, ros2_name: &str) -> Option<usize> {
+        for (re, size) in &self.max_payload_size {
+            if re.is_match(ros2_name) {
+                return Some(*size);
             }
-            return Duration::from_secs_f32(*default);
         }
-        Duration::from_secs_f32(DEFAULT_QUERIES_TIMEOUT)
+        None
     }
-}
 
-#[derive(Deserialize, Debug, Serialize)]
-#[serde(deny_unknown_fields)]
-pub struct QueriesTimeouts {
-    #[serde(default = "default_queries_timeout")]
-    default: f32,
-    #[serde(
-        default,
-        deserialize_with = "deserialize_vec_regex_f32",
-        serialize_with = "serialize_vec_regex_f32"
-    )]
-    transient_local_subscribers: Vec<(Regex, f32)>,
-    #[serde(
-        default,
-        deserialize_with = "deserialize_vec_regex_f32",
-        serialize_with = "serialize_vec_regex_f32"
-    )]
-    services: Vec<(Regex, f32)>,
-    #[serde(default)]
-    actions: Option<ActionsTimeouts>,
-}
+    /// The global budget in bytes per second of the messages routed from DDS to Zenoh, if configured
+    pub fn get_max_bandwidth(&self) -> Option<u64> {
+        (self.max_bandwidth > 0).then_some(self.max_bandwidth)
+    }
 
-#[derive(Deserialize, Debug, Serialize)]
-#[serde(deny_unknown_fields)]
-pub struct ActionsTimeouts {
-    #[serde(
-        default,
-        deserialize_with = "deserialize_vec_regex_f32",
-        serialize_with = "serialize_vec_regex_f32"
-    )]
-    send_goal: Vec<(Regex, f32)>,
-    #[serde(
-        default,
-        deserialize_with = "deserialize_vec_regex_f32",
-        serialize_with = "serialize_vec_regex_f32"
-    )]
-    cancel_goal: Vec<(Regex, f32)>,
-    #[serde(
-        default,
-        deserialize_with = "deserialize_vec_regex_f32",
-        serialize_with = "serialize_vec_regex_f32"
-    )]
-    get_result: Vec<(Regex, f32)>,
-}
+    /// The Priority under which the messages are shed when exceeding `max_bandwidth`
+    pub fn get_bandwidth_shedding_priority(&self) -> Priority {
+        self.bandwidth_shedding_priority
+            .unwrap_or(DEFAULT_BANDWIDTH_SHEDDING_PRIORITY)
+    }
 
-#[derive(Deserialize, Debug, Serialize)]
-pub enum Allowance {
-    #[serde(rename = "allow")]
-    Allow(ROS2InterfacesRegex),
-    #[serde(rename = "deny")]
-    Deny(ROS2InterfacesRegex),
-}
+    /// True if the topic is configured in `force_transient_local` (or with a "transient_local" durability
+    /// in `topic_qos`), meaning it shall be routed as TRANSIENT_LOCAL even if the discovered Writers are VOLATILE.
+    pub fn is_transient_local_forced(&self, ros2_name: &str) -> bool {
+        if let Some(durability) = self.get_topic_qos(ros2_name).and_then(|q| q.durability) {
+            return durability == DurabilityOverride::TransientLocal;
+        }
+        self.force_transient_local
+            .as_ref()
+            .map_or(false, |re| re.is_match(ros2_name))
+    }
 
-impl Allowance {
-    pub fn is_publisher_allowed(&self, name: &str) -> bool {
-        use Allowance::*;
-        match self {
-            Allow(r) => r
-                .publishers
-                .as_ref()
-                .map(|re| re.is_match(name))
-                .unwrap_or(false),
-            Deny(r) => r
-                .publishers
-                .as_ref()
-                .map(|re| !re.is_match(name))
-                .unwrap_or(true),
+    /// True if the topic is configured in `latched_aggregate_topics`, meaning that its historical data
+    /// is the union of the last publication of each of its Writers (e.g. "/tf_static")
+    pub fn is_latched_aggregate(&self, ros2_name: &str) -> bool {
+        self.latched_aggregate_topics
+            .as_ref()
+            .map_or(false, |re| re.is_match(ros2_name))
+    }
+
+    /// True if the topic is configured in `instance_key_exprs`, meaning that for a keyed topic
+    /// each instance is routed on its own key expression (with the instance's key hash as last chunk).
+    pub fn is_instance_keyed(&self, ros2_name: &str) -> bool {
+        self.instance_key_exprs
+            .as_ref()
+            .map_or(false, |re| re.is_match(ros2_name))
+    }
+
+    pub fn get_reliability_override(&self, ros2_name: &str) -> Option<ReliabilityOverride> {
+        if let Some(r) = self.get_topic_qos(ros2_name).and_then(|q| q.reliability) {
+            return Some(r);
+        }
+        for (re, r) in &self.reliability {
+            if re.is_match(ros2_name) {
+                return Some(*r);
+            }
         }
+        None
     }
 
-    pub fn is_subscriber_allowed(&self, name: &str) -> bool {
-        use Allowance::*;
-        match self {
-            Allow(r) => r
-                .subscribers
-                .as_ref()
-                .map(|re| re.is_match(name))
-                .unwrap_or(false),
-            Deny(r) => r
-                .subscribers
-                .as_ref()
-                .map(|re| !re.is_match(name))
-                .unwrap_or(true),
+    /// If the Zenoh Publisher or Subscriber of a route must be reliable: as configured via `zenoh_reliability`
+    /// for this topic, or else the `default` one (i.e. depending on the DDS QoS)
+    pub fn is_zenoh_reliable(&self, ros2_name: &str, default: bool) -> bool {
+        if let Some(r) = self
+            .get_topic_qos(ros2_name)
+            .and_then(|q| q.zenoh_reliability)
+        {
+            return r == ReliabilityOverride::Reliable;
+        }
+        for (re, r) in &self.zenoh_reliability {
+            if re.is_match(ros2_name) {
+                return *r == ReliabilityOverride::Reliable;
+            }
         }
+        default
     }
 
-    pub fn is_service_srv_allowed(&self, name: &str) -> bool {
-        use Allowance::*;
-        match self {
-            Allow(r) => r
-                .service_servers
-                .as_ref()
-                .map(|re| re.is_match(name))
-                .unwrap_or(false),
-            Deny(r) => r
-                .service_servers
-                .as_ref()
-                .map(|re| !re.is_match(name))
-                .unwrap_or(true),
+    /// If a topic is keyless: as forced via `keyed` for this topic, or else the `discovered` one
+    /// (i.e. as declared by the type support of the DDS entities, or announced by the remote bridges)
+    pub fn is_keyless(&self, ros2_name: &str, discovered: bool) -> bool {
+        for (re, keyed) in &self.keyed {
+            if re.is_match(ros2_name) {
+                return !*keyed;
+            }
         }
+        discovered
     }
 
-    pub fn is_service_cli_allowed(&self, name: &str) -> bool {
-        use Allowance::*;
-        match self {
-            Allow(r) => r
-                .service_clients
-                .as_ref()
-                .map(|re| re.is_match(name))
-                .unwrap_or(false),
-            Deny(r) => r
-                .service_clients
-                .as_ref()
-                .map(|re| !re.is_match(name))
-                .unwrap_or(true),
+    /// The QoS configured for a topic via the first matching entry of `topic_qos`, if any.
+    /// The fields not set in this entry are completed with the ones of its preset (see `qos_presets`).
+    /// It takes precedence over the other options configuring the same QoS for the topic
+    /// (e.g. `reliability`, `force_transient_local` or `pub_priorities`).
+    /// With `bridge_parameters`, "/parameter_events" is by default bridged with the QoS of the ROS
+    /// parameter events (reliable, volatile and a KEEP_LAST history of 1000).
+    pub fn get_topic_qos(&self, ros2_name: &str) -> Option<TopicQos> {
+        let Some((_, topic_qos)) = self.topic_qos.iter().find(|(re, _)| re.is_match(ros2_name))
+        else {
+            let parameter_events =
+                ros2_name == ROS2_PARAMETER_EVENTS_TOPIC && !self.bridge_parameters.is_empty();
+            return parameter_events.then(parameter_events_qos);
+        };
+        let preset = topic_qos
+            .preset
+            .as_ref()
+            .and_then(|name| self.qos_presets.get(name));
+        Some(topic_qos.completed_with(preset))
+    }
+
+    /// The congestion control configured for a topic via `topic_qos`, if any
+    pub fn get_congestion_control(&self, ros2_name: &str) -> Option<CongestionControlOverride> {
+        self.get_topic_qos(ros2_name)
+            .and_then(|q| q.congestion_control)
+    }
+
+    /// The DDS Partitions configured for the DDS Readers and Writers created for a topic, if any
+    pub fn get_dds_partition(&self, ros2_name: &str) -> Option<&Vec<String>> {
+        for (re, partitions) in &self.dds_partition {
+            if re.is_match(ros2_name) {
+                return Some(partitions);
+            }
         }
+        None
     }
 
-    pub fn is_action_srv_allowed(&self, name: &str) -> bool {
-        use Allowance::*;
-        match self {
-            Allow(r) => r
-                .action_servers
-                .as_ref()
-                .map(|re| re.is_match(name))
-                .unwrap_or(false),
-            Deny(r) => r
-                .action_servers
-                .as_ref()
-                .map(|re| !re.is_match(name))
-                .unwrap_or(true),
+    /// The remote bridges (ids or aliases) a topic is exclusively routed to, if configured in `route_to`
+    pub fn get_route_to(&self, ros2_name: &str) -> Option<&Vec<String>> {
+        for (re, targets) in &self.route_to {
+            if re.is_match(ros2_name) {
+                return Some(targets);
+            }
         }
+        None
     }
 
-    pub fn is_action_cli_allowed(&self, name: &str) -> bool {
-        use Allowance::*;
-        match self {
-            Allow(r) => r
-                .action_clients
-                .as_ref()
-                .map(|re| re.is_match(name))
-                .unwrap_or(false),
-            Deny(r) => r
-                .action_clients
-                .as_ref()
-                .map(|re| !re.is_match(name))
-                .unwrap_or(true),
+    /// The number of publications to be cached for a TRANSIENT_LOCAL topic, if configured
+    /// via `transient_local_cache.history`. 0 means no caching.
+    pub fn get_transient_local_cache_history(&self, ros2_name: &str) -> Option<usize> {
+        if let Some(tlc) = &self.transient_local_cache {
+            for (re, history) in &tlc.history {
+                if re.is_match(ros2_name) {
+                    return Some(*history);
+                }
+            }
+        }
+        None
+    }
+
+    /// The cap on the number of publications to be cached for a TRANSIENT_LOCAL topic,
+    /// when computed from the DDS Writer's QoS (i.e. not configured via `transient_local_cache.history`)
+    pub fn get_transient_local_cache_max_history(&self, ros2_name: &str) -> Option<usize> {
+        if let Some(tlc) = &self.transient_local_cache {
+            for (re, max_history) in &tlc.max_history {
+                if re.is_match(ros2_name) {
+                    return Some(*max_history);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn get_transient_local_cache_max_bytes(&self, ros2_name: &str) -> Option<usize> {
+        if let Some(tlc) = &self.transient_local_cache {
+            for (re, max_bytes) in &tlc.max_bytes {
+                if re.is_match(ros2_name) {
+                    return Some(*max_bytes);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn get_queries_timeout_tl_sub(&self, ros2_name: &str) -> Duration {
+        if let Some(qt) = &self.queries_timeout {
+            for (re, secs) in &qt.transient_local_subscribers {
+                if re.is_match(ros2_name) {
+                    return Duration::from_secs_f32(*secs);
+                }
+            }
+            return Duration::from_secs_f32(qt.default);
+        }
+        Duration::from_secs_f32(DEFAULT_QUERIES_TIMEOUT)
+    }
+
+    pub fn get_queries_timeout_service(&self, ros2_name: &str) -> Duration {
+        if let Some(qt) = &self.queries_timeout {
+            for (re, secs) in &qt.services {
+                if re.is_match(ros2_name) {
+                    return Duration::from_secs_f32(*secs);
+                }
+            }
+            return Duration::from_secs_f32(qt.default);
+        }
+        Duration::from_secs_f32(DEFAULT_QUERIES_TIMEOUT)
+    }
+
+    /// The CDR payload of the Response written to a Service Client whose call failed (e.g. timed out),
+    /// as configured via `service_error_replies` for this Service (if any)
+    pub fn get_service_error_reply(&self, ros2_name: &str) -> Option<&[u8]> {
+        self.service_error_replies
+            .iter()
+            .find(|(re, _)| re.is_match(ros2_name))
+            .map(|(_, payload)| payload.as_slice())
+    }
+
+    /// The retry policy of the calls to this Service that got no reply, if configured via `service_retries`
+    pub fn get_service_retry_policy(&self, ros2_name: &str) -> Option<&ServiceRetryPolicy> {
+        self.service_retries
+            .iter()
+            .find(|(re, _)| re.is_match(ros2_name))
+            .map(|(_, policy)| policy)
+    }
+
+    /// The policy applied to the calls of this Service when no remote Server is announced,
+    /// if configured in `on_service_unavailable`
+    pub fn get_service_unavailable_policy(
+        &self,
+        ros2_name: &str,
+    ) -> Option<ServiceUnavailablePolicy> {
+        self.on_service_unavailable
+            .iter()
+            .find(|(re, _)| re.is_match(ros2_name))
+            .map(|(_, p)| *p)
+    }
+
+    /// The maximum duration of the calls of this Service, if configured in `long_calls` to be
+    /// replied asynchronously (i.e. after the queries timeout)
+    pub fn get_long_call_duration(&self, ros2_name: &str) -> Option<Duration> {
+        self.long_calls
+            .iter()
+            .find(|(re, _)| re.is_match(ros2_name))
+            .and_then(|(_, secs)| (*secs > 0.0).then(|| Duration::from_secs_f32(*secs)))
+    }
+
+    /// The maximum count of the requests of this Service in progress on its routes, if configured
+    /// in `max_concurrent_requests` (0 meaning unbounded)
+    pub fn get_max_concurrent_requests(&self, ros2_name: &str) -> Option<usize> {
+        self.max_concurrent_requests
+            .iter()
+            .find(|(re, _)| re.is_match(ros2_name))
+            .and_then(|(_, max)| (*max > 0).then_some(*max))
+    }
+
+    pub fn get_queries_timeout_action_send_goal(&self, ros2_name: &str) -> Duration {
+        if let Some(QueriesTimeouts {
+            default,
+            actions: Some(at),
+            ..
+        }) = &self.queries_timeout
+        {
+            for (re, secs) in &at.send_goal {
+                if re.is_match(ros2_name) {
+                    return Duration::from_secs_f32(*secs);
+                }
+            }
+            return Duration::from_secs_f32(*default);
+        }
+        Duration::from_secs_f32(DEFAULT_QUERIES_TIMEOUT)
+    }
+
+    pub fn get_queries_timeout_action_cancel_goal(&self, ros2_name: &str) -> Duration {
+        if let Some(QueriesTimeouts {
+            default,
+            actions: Some(at),
+            ..
+        }) = &self.queries_timeout
+        {
+            for (re, secs) in &at.cancel_goal {
+                if re.is_match(ros2_name) {
+                    return Duration::from_secs_f32(*secs);
+                }
+            }
+            return Duration::from_secs_f32(*default);
+        }
+        Duration::from_secs_f32(DEFAULT_QUERIES_TIMEOUT)
+    }
+
+    pub fn get_queries_timeout_action_get_result(&self, ros2_name: &str) -> Duration {
+        if let Some(QueriesTimeouts {
+            default,
+            actions: Some(at),
+            ..
+        }) = &self.queries_timeout
+        {
+            for (re, secs) in &at.get_result {
+                if re.is_match(ros2_name) {
+                    return Duration::from_secs_f32(*secs);
+                }
+            }
+            return Duration::from_secs_f32(*default);
         }
+        Duration::from_secs_f32(DEFAULT_QUERIES_TIMEOUT)
     }
 }
 
-#[derive(Deserialize, Debug, Default, Serialize)]
-pub struct ROS2InterfacesRegex {
-    #[serde(
-        default,
-        deserialize_with = "deserialize_regex",
-        serialize_with = "serialize_regex",
+/// A rule selecting the namespace to use for the interfaces with a name matching `pattern`,
+/// instead of `namespace`, `namespace_to_zenoh` or `namespace_from_zenoh`.
+#[derive(Debug, Serialize)]
+pub struct NamespaceRule {
+    pattern: String,
+    namespace: String,
+    #[serde(skip)]
+    regex: Regex,
+}
+
+impl NamespaceRule {
+    #[inline]
+    pub fn is_match(&self, ros2_name: &str) -> bool {
+        self.regex.is_match(ros2_name)
+    }
+
+    #[inline]
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    // A pattern without any regex special character (i.e. matching a single name)
+    fn is_literal(&self) -> bool {
+        self.pattern.len() == self.literal_prefix_len()
+    }
+
+    // The length of the pattern before its 1st regex special character
+    fn literal_prefix_len(&self) -> usize {
+        self.pattern
+            .find(|c| "\\.^$*+?()[]{}|".contains(c))
+            .unwrap_or(self.pattern.len())
+    }
+}
+
+impl<'de> Deserialize<'de> for NamespaceRule {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct NamespaceRuleConf {
+            pattern: String,
+            namespace: String,
+        }
+
+        let NamespaceRuleConf { pattern, namespace } =
+            NamespaceRuleConf::deserialize(deserializer)?;
+        if pattern.is_empty() {
+            return Err(de::Error::custom(
+                "Invalid empty pattern in namespace_rules",
+            ));
+        }
+        let regex = Regex::new(&format!("^(?:{pattern})$"))
+            .map_err(|e| de::Error::custom(format!("Invalid regex '{pattern}': {e}")))?;
+        if !is_valid_namespace(&namespace) {
+            return Err(de::Error::custom(format!(
+                r#"Invalid namespace "{namespace}" for pattern '{pattern}': it must start with '/' and contain only alphanumeric, '_' or non-consecutive '/' characters (not at the end)"#
+            )));
+        }
+        Ok(NamespaceRule {
+            pattern,
+            namespace,
+            regex,
+        })
+    }
+}
+
+// Deserialize the "namespace_rules", rejecting duplicate patterns, and sorting them most-specific-first:
+//   1. the literal patterns (i.e. without any regex special character)
+//   2. the patterns with the longest literal prefix (i.e. before the 1st special character)
+//   3. the configuration order
+fn deserialize_namespace_rules<'de, D>(deserializer: D) -> Result<Vec<NamespaceRule>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut rules: Vec<NamespaceRule> = Deserialize::deserialize(deserializer)?;
+    for (i, rule) in rules.iter().enumerate() {
+        if rules[..i].iter().any(|r| r.pattern == rule.pattern) {
+            return Err(de::Error::custom(format!(
+                "Duplicate pattern '{}' in namespace_rules",
+                rule.pattern
+            )));
+        }
+    }
+    // Note: sort_by_key() is stable, preserving the configuration order for equivalent rules
+    rules.sort_by_key(|r| (!r.is_literal(), usize::MAX - r.literal_prefix_len()));
+    Ok(rules)
+}
+
+fn is_valid_namespace(namespace: &str) -> bool {
+    namespace == "/"
+        || (namespace.starts_with('/')
+            && namespace[1..]
+                .split('/')
+                .all(|s| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')))
+}
+
+/// A remapping of a ROS2 name to another one on the zenoh side, either:
+///  - for an exact name (e.g. `"/old_cmd_vel": "/cmd_vel"`)
+///  - for a whole subtree (e.g. `"/ns/**": "/other_ns/**"`), replacing the prefix of the names below it
+#[derive(Debug)]
+pub struct Remapping {
+    from: String,
+    to: String,
+}
+
+impl Remapping {
+    fn new(from: String, to: String) -> Result<Self, String> {
+        let is_valid_name = |name: &str| {
+            name.starts_with('/')
+                && (name.len() == 1 || !name.ends_with('/'))
+                && !name.contains("//")
+                && !name.contains("**")
+        };
+        // the prefix of a subtree ends with a '/' (e.g. "/ns/" or "/")
+        let is_valid_prefix = |prefix: &str| {
+            prefix == "/" || (prefix.len() > 2 && is_valid_name(&prefix[..prefix.len() - 1]))
+        };
+        match (from.strip_suffix("**"), to.strip_suffix("**")) {
+            (Some(f), Some(t)) if f.ends_with('/') && t.ends_with('/') => {
+                if !is_valid_prefix(f) || !is_valid_prefix(t) {
+                    return Err(format!("Invalid remapping '{from}' -> '{to}'"));
+                }
+            }
+            (None, None) => {
+                if !is_valid_name(&from) || !is_valid_name(&to) {
+                    return Err(format!("Invalid remapping '{from}' -> '{to}'"));
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid remapping '{from}' -> '{to}': both names must either end with '/**', or not"
+                ))
+            }
+        }
+        Ok(Remapping { from, to })
+    }
+
+    // The prefix of the names in the subtree if it's a subtree remapping ("/ns/" for "/ns/**")
+    #[inline]
+    fn subtree(name: &str) -> Option<&str> {
+        name.strip_suffix("**")
+    }
+
+    fn rewrite(name: &str, from: &str, to: &str) -> Option<String> {
+        match (Self::subtree(from), Self::subtree(to)) {
+            (Some(from), Some(to)) => name
+                .strip_prefix(from)
+                .filter(|rest| !rest.is_empty())
+                .map(|rest| format!("{to}{rest}")),
+            _ if name == from => Some(to.to_string()),
+            _ => None,
+        }
+    }
+
+    /// The remapped name, if `ros2_name` matches this remapping
+    #[inline]
+    pub fn apply(&self, ros2_name: &str) -> Option<String> {
+        Self::rewrite(ros2_name, &self.from, &self.to)
+    }
+
+    /// The original name, if `ros2_name` matches the destination of this remapping
+    #[inline]
+    pub fn apply_inverse(&self, ros2_name: &str) -> Option<String> {
+        Self::rewrite(ros2_name, &self.to, &self.from)
+    }
+
+    // The length of the exact name, or of the subtree prefix (for sorting most-specific-first)
+    fn specificity(name: &str) -> (bool, usize) {
+        match Self::subtree(name) {
+            Some(prefix) => (true, usize::MAX - prefix.len()),
+            None => (false, 0),
+        }
+    }
+}
+
+// Deserialize the "remappings" map (preserving its order), rejecting the collisions
+// (i.e. 2 remappings that could map different names to the same one), and sorting them
+// most-specific-first: the exact names, then the subtrees with the longest prefix.
+fn deserialize_remappings<'de, D>(deserializer: D) -> Result<Vec<Remapping>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct RemappingsVisitor;
+
+    impl<'de> Visitor<'de> for RemappingsVisitor {
+        type Value = Vec<Remapping>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str(r#"a map of "<ros2_name>": "<remapped_name>" entries"#)
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::MapAccess<'de>,
+        {
+            let mut result: Vec<Remapping> = Vec::with_capacity(map.size_hint().unwrap_or(0));
+            while let Some((from, to)) = map.next_entry::<String, String>()? {
+                if result.iter().any(|r| r.from == from) {
+                    return Err(de::Error::custom(format!(
+                        "Duplicate remapping of '{from}'"
+                    )));
+                }
+                result.push(Remapping::new(from, to).map_err(de::Error::custom)?);
+            }
+            Ok(result)
+        }
+    }
+
+    let mut remappings = deserializer.deserialize_map(RemappingsVisitor)?;
+    for (i, r1) in remappings.iter().enumerate() {
+        for r2 in &remappings[..i] {
+            let collides = match (Remapping::subtree(&r1.to), Remapping::subtree(&r2.to)) {
+                (Some(p1), Some(p2)) => p1.starts_with(p2) || p2.starts_with(p1),
+                (Some(p), None) => r2.to.starts_with(p),
+                (None, Some(p)) => r1.to.starts_with(p),
+                (None, None) => {
+                    r1.to == r2.to || collides_with_action(r1, r2) || collides_with_action(r2, r1)
+                }
+            };
+            if collides {
+                return Err(de::Error::custom(format!(
+                    "Collision in remappings: '{}' and '{}' could be both remapped to the same name ('{}' and '{}')",
+                    r2.from, r1.from, r2.to, r1.to
+                )));
+            }
+        }
+    }
+    // Note: sort_by_key() is stable, preserving the configuration order for equivalent remappings
+    remappings.sort_by_key(|r| Remapping::specificity(&r.from));
+    Ok(remappings)
+}
+
+// True if `r1` remaps a name to the one of a sub-interface of the Action remapped by `r2`
+// (e.g. "/b/_action/status" -> "/x/_action/status" and "/a" -> "/x"), without being the same remapping
+// as the one implicitly applied by `r2` to this sub-interface.
+fn collides_with_action(r1: &Remapping, r2: &Remapping) -> bool {
+    match split_action_suffix(&r1.to) {
+        Some((action_name, suffix)) if action_name == r2.to => {
+            r1.from != format!("{}/{suffix}", r2.from)
+        }
+        _ => false,
+    }
+}
+
+fn serialize_remappings<S>(v: &[Remapping], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(Some(v.len()))?;
+    for r in v {
+        map.serialize_entry(&r.from, &r.to)?;
+    }
+    map.end()
+}
+
+/// A named set of tuned defaults for the configuration, applied by `apply_profile()`
+/// before the user's configuration (which overrides them).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Profile {
+    // everything default
+    Lan,
+    // for low bandwidth networks: BEST_EFFORT sensors, throttled cameras and point clouds
+    WanLowbw,
+    // for teleoperation: real-time priority on command topics and short timeouts
+    Teleop,
+}
+
+impl Profile {
+    /// The configuration defaults of this profile
+    pub fn defaults(&self) -> serde_json::Value {
+        use serde_json::json;
+        match self {
+            Profile::Lan => json!({}),
+            Profile::WanLowbw => json!({
+                "reliability": [
+                    ".*/image.*=best_effort",
+                    ".*/camera/.*=best_effort",
+                    ".*/points.*=best_effort",
+                    ".*/scan=best_effort",
+                    ".*/imu.*=best_effort",
+                ],
+                "pub_max_frequencies": [
+                    ".*/image.*=2",
+                    ".*/camera/.*=2",
+                    ".*/points.*=2",
+                ],
+                "pub_priorities": ["/rosout=7"],
+                "reliable_routes_blocking": false,
+                "queries_timeout": { "default": 10.0 },
+            }),
+            Profile::Teleop => json!({
+                "pub_priorities": [
+                    ".*/cmd_vel.*=1",
+                    ".*/joy=1",
+                    "/rosout=7",
+                ],
+                "reliable_routes_blocking": false,
+                "queries_timeout": { "default": 1.0 },
+            }),
+        }
+    }
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lan" => Ok(Profile::Lan),
+            "wan-lowbw" => Ok(Profile::WanLowbw),
+            "teleop" => Ok(Profile::Teleop),
+            _ => Err(format!(
+                r#"expected "lan", "wan-lowbw" or "teleop", got "{s}""#
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Profile::Lan => write!(f, "lan"),
+            Profile::WanLowbw => write!(f, "wan-lowbw"),
+            Profile::Teleop => write!(f, "teleop"),
+        }
+    }
+}
+
+/// A Reliability configured for a topic, overriding the one of the discovered DDS Readers/Writers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReliabilityOverride {
+    Reliable,
+    BestEffort,
+}
+
+impl FromStr for ReliabilityOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reliable" => Ok(ReliabilityOverride::Reliable),
+            "best_effort" => Ok(ReliabilityOverride::BestEffort),
+            _ => Err(format!(
+                r#"expected "reliable" or "best_effort", got "{s}""#
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ReliabilityOverride {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReliabilityOverride::Reliable => write!(f, "reliable"),
+            ReliabilityOverride::BestEffort => write!(f, "best_effort"),
+        }
+    }
+}
+
+/// The congestion control configured for a topic (see `topic_qos`): when the routing of a message is congested
+/// (i.e. the Zenoh publication of a Route Publisher, or the DDS write of a Route Subscriber), either block until
+/// it can be routed, either drop it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CongestionControlOverride {
+    Block,
+    Drop,
+}
+
+impl fmt::Display for CongestionControlOverride {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CongestionControlOverride::Block => write!(f, "block"),
+            CongestionControlOverride::Drop => write!(f, "drop"),
+        }
+    }
+}
+
+/// A Durability configured for a topic (see `topic_qos`), overriding the one of the discovered DDS Readers/Writers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DurabilityOverride {
+    Volatile,
+    TransientLocal,
+}
+
+/// A History configured for a topic (see `topic_qos`): either a KEEP_LAST depth, either "keep_all".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryOverride {
+    KeepLast(usize),
+    KeepAll,
+}
+
+impl<'de> Deserialize<'de> for HistoryOverride {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct HistoryOverrideVisitor;
+
+        impl<'de> Visitor<'de> for HistoryOverrideVisitor {
+            type Value = HistoryOverride;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str(r#"either a positive depth, either "keep_all""#)
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                match value {
+                    0 => Err(de::Error::custom("History depth must be positive")),
+                    _ => Ok(HistoryOverride::KeepLast(value as usize)),
+                }
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                match u64::try_from(value) {
+                    Ok(value) => self.visit_u64(value),
+                    Err(_) => Err(de::Error::custom("History depth must be positive")),
+                }
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                match value {
+                    "keep_all" => Ok(HistoryOverride::KeepAll),
+                    _ => Err(de::Error::custom(format!(
+                        r#"expected a positive depth or "keep_all", got "{value}""#
+                    ))),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(HistoryOverrideVisitor)
+    }
+}
+
+impl Serialize for HistoryOverride {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            HistoryOverride::KeepLast(depth) => serializer.serialize_u64(*depth as u64),
+            HistoryOverride::KeepAll => serializer.serialize_str("keep_all"),
+        }
+    }
+}
+
+/// The QoS configured for a topic in `topic_qos`, or for a named preset in `qos_presets`.
+/// A `topic_qos` entry can reference a preset, that completes the fields it doesn't set.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TopicQos {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reliability: Option<ReliabilityOverride>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub durability: Option<DurabilityOverride>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<HistoryOverride>,
+    // the Deadline period in seconds (f64 to be exactly converted to DDS nanoseconds)
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_positive_f64",
         skip_serializing_if = "Option::is_none"
     )]
-    pub publishers: Option<Regex>,
+    pub deadline: Option<f64>,
+    // the Lifespan duration in seconds
     #[serde(
         default,
-        deserialize_with = "deserialize_regex",
-        serialize_with = "serialize_regex",
+        deserialize_with = "deserialize_option_positive_f64",
         skip_serializing_if = "Option::is_none"
     )]
-    pub subscribers: Option<Regex>,
+    pub lifespan: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zenoh_reliability: Option<ReliabilityOverride>,
     #[serde(
         default,
-        deserialize_with = "deserialize_regex",
-        serialize_with = "serialize_regex",
+        deserialize_with = "deserialize_option_prio",
+        serialize_with = "serialize_option_prio",
         skip_serializing_if = "Option::is_none"
     )]
-    pub service_servers: Option<Regex>,
+    pub zenoh_priority: Option<Priority>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub congestion_control: Option<CongestionControlOverride>,
+}
+
+impl TopicQos {
+    /// This QoS with the fields it doesn't set taken from a preset (if any)
+    pub fn completed_with(&self, preset: Option<&TopicQos>) -> TopicQos {
+        let Some(preset) = preset else {
+            return TopicQos {
+                preset: None,
+                ..self.clone()
+            };
+        };
+        TopicQos {
+            preset: None,
+            reliability: self.reliability.or(preset.reliability),
+            durability: self.durability.or(preset.durability),
+            history: self.history.or(preset.history),
+            deadline: self.deadline.or(preset.deadline),
+            lifespan: self.lifespan.or(preset.lifespan),
+            zenoh_reliability: self.zenoh_reliability.or(preset.zenoh_reliability),
+            zenoh_priority: self.zenoh_priority.or(preset.zenoh_priority),
+            congestion_control: self.congestion_control.or(preset.congestion_control),
+        }
+    }
+
+    pub fn get_deadline(&self) -> Option<Duration> {
+        self.deadline.map(Duration::from_secs_f64)
+    }
+
+    pub fn get_lifespan(&self) -> Option<Duration> {
+        self.lifespan.map(Duration::from_secs_f64)
+    }
+}
+
+/// The policy applied by a Route Subscriber when no message was routed during the Deadline period
+/// of the topic (e.g. because of a network outage):
+///  - `None`: nothing is done
+///  - `Log`: a warning is logged
+///  - `RepublishLast`: the last routed message is re-written to DDS, on each Deadline period
+///  - `Teardown`: the DDS Writer is deleted (and re-created when messages are routed again)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadlineMissPolicy {
+    None,
+    Log,
+    RepublishLast,
+    Teardown,
+}
+
+impl FromStr for DeadlineMissPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(DeadlineMissPolicy::None),
+            "log" => Ok(DeadlineMissPolicy::Log),
+            "republish_last" => Ok(DeadlineMissPolicy::RepublishLast),
+            "teardown" => Ok(DeadlineMissPolicy::Teardown),
+            _ => Err(format!(
+                r#"expected "none", "log", "republish_last" or "teardown", got "{s}""#
+            )),
+        }
+    }
+}
+
+impl fmt::Display for DeadlineMissPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeadlineMissPolicy::None => write!(f, "none"),
+            DeadlineMissPolicy::Log => write!(f, "log"),
+            DeadlineMissPolicy::RepublishLast => write!(f, "republish_last"),
+            DeadlineMissPolicy::Teardown => write!(f, "teardown"),
+        }
+    }
+}
+
+/// The policy applied to a call of a Service Client route while no remote Service Server is announced
+/// (see `on_service_unavailable` config), instead of issuing a query that would time out:
+///  - `ErrorReply`: the call fails at once, replying the Response configured in `service_error_replies`
+///  - `Drop`: the request is dropped at once, without reply
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceUnavailablePolicy {
+    ErrorReply,
+    Drop,
+}
+
+impl FromStr for ServiceUnavailablePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error_reply" => Ok(ServiceUnavailablePolicy::ErrorReply),
+            "drop" => Ok(ServiceUnavailablePolicy::Drop),
+            _ => Err(format!(r#"expected "error_reply" or "drop", got "{s}""#)),
+        }
+    }
+}
+
+impl fmt::Display for ServiceUnavailablePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceUnavailablePolicy::ErrorReply => write!(f, "error_reply"),
+            ServiceUnavailablePolicy::Drop => write!(f, "drop"),
+        }
+    }
+}
+
+/// The policy applied to the messages of a topic exceeding its maximum frequency
+/// (configured in `pub_max_frequencies` or `sub_max_frequencies`):
+///  - `Periodic`: strict periodic sampling, routing the last message of each instance received during each period
+///  - `DropExcess`: a message is routed immediately if the period elapsed since the last routed one of its instance.
+///    Otherwise, it's kept until the period elapses, replacing the previously kept one (i.e. keeping the latest)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitPolicy {
+    #[default]
+    Periodic,
+    DropExcess,
+}
+
+impl FromStr for RateLimitPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "periodic" => Ok(RateLimitPolicy::Periodic),
+            "drop_excess" => Ok(RateLimitPolicy::DropExcess),
+            _ => Err(format!(
+                r#"expected "periodic" or "drop_excess", got "{s}""#
+            )),
+        }
+    }
+}
+
+impl fmt::Display for RateLimitPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateLimitPolicy::Periodic => write!(f, "periodic"),
+            RateLimitPolicy::DropExcess => write!(f, "drop_excess"),
+        }
+    }
+}
+
+/// The compression of the payloads published over Zenoh by a Route Publisher (see `compression` config).
+/// Whatever its configuration, a Route Subscriber decompresses the payloads it receives compressed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    #[default]
+    None,
+    Lz4,
+    Zstd,
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Compression::None),
+            "lz4" => Ok(Compression::Lz4),
+            "zstd" => Ok(Compression::Zstd),
+            _ => Err(format!(r#"expected "none", "lz4" or "zstd", got "{s}""#)),
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compression::None => write!(f, "none"),
+            Compression::Lz4 => write!(f, "lz4"),
+            Compression::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+/// The policy applied to a ROS interface which DDS topic name exceeds the DDS maximum length:
+///  - `Reject`: the interface is not routed (an error is logged)
+///  - `Hash`: the DDS topic name is truncated and completed with a deterministic hash of the full name
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LongNamePolicy {
+    #[default]
+    Reject,
+    Hash,
+}
+
+impl FromStr for LongNamePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reject" => Ok(LongNamePolicy::Reject),
+            "hash" => Ok(LongNamePolicy::Hash),
+            _ => Err(format!(r#"expected "reject" or "hash", got "{s}""#)),
+        }
+    }
+}
+
+impl fmt::Display for LongNamePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LongNamePolicy::Reject => write!(f, "reject"),
+            LongNamePolicy::Hash => write!(f, "hash"),
+        }
+    }
+}
+
+/// The routing mode of the bridge:
+///  - `LocalRouting`: a route is created for each discovered local ROS interface and for each
+///    interface announced by a remote bridge (so that the local ROS Nodes can discover it).
+///  - `ForwardDiscovery`: a route is created only for a local ROS interface that has a matching
+///    counterpart announced by a remote bridge (e.g. a local Publisher and a remote Subscriber).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingMode {
+    #[default]
+    LocalRouting,
+    ForwardDiscovery,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransientLocalCacheConf {
     #[serde(
         default,
-        deserialize_with = "deserialize_regex",
-        serialize_with = "serialize_regex",
-        skip_serializing_if = "Option::is_none"
+        deserialize_with = "deserialize_vec_regex_usize",
+        serialize_with = "serialize_vec_regex_usize"
     )]
-    pub service_clients: Option<Regex>,
+    history: Vec<(Regex, usize)>,
     #[serde(
         default,
-        deserialize_with = "deserialize_regex",
-        serialize_with = "serialize_regex",
-        skip_serializing_if = "Option::is_none"
+        deserialize_with = "deserialize_vec_regex_usize",
+        serialize_with = "serialize_vec_regex_usize"
     )]
-    pub action_servers: Option<Regex>,
+    max_history: Vec<(Regex, usize)>,
     #[serde(
         default,
-        deserialize_with = "deserialize_regex",
-        serialize_with = "serialize_regex",
-        skip_serializing_if = "Option::is_none"
+        deserialize_with = "deserialize_vec_regex_usize",
+        serialize_with = "serialize_vec_regex_usize"
     )]
-    pub action_clients: Option<Regex>,
+    max_bytes: Vec<(Regex, usize)>,
+}
+
+/// The batching of the messages of a topic by its Route Publisher (see `batching` config): the messages are
+/// published over Zenoh in a single payload when the oldest one was received `max_batch_delay` seconds ago,
+/// or before the payload exceeds `max_batch_size` bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchingConf {
+    #[serde(
+        default = "default_max_batch_delay",
+        deserialize_with = "deserialize_positive_f64"
+    )]
+    pub max_batch_delay: f64,
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
 }
 
-fn default_namespace() -> String {
-    DEFAULT_NAMESPACE.to_string()
-}
+impl BatchingConf {
+    pub fn get_max_batch_delay(&self) -> Duration {
+        Duration::from_secs_f64(self.max_batch_delay)
+    }
+}
+
+/// The retry policy of a Service Client route (see `service_retries` config): a call that got no reply
+/// within the queries timeout is re-issued up to `retries` times, after `retry_backoff` seconds
+/// doubled at each retry. The Service Server route answers a retry of an already served call
+/// with the cached Response, without calling the Service again.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServiceRetryPolicy {
+    pub retries: u32,
+    #[serde(
+        default = "default_service_retry_backoff",
+        deserialize_with = "deserialize_positive_f64"
+    )]
+    pub retry_backoff: f64,
+}
+
+impl ServiceRetryPolicy {
+    /// The delay before the retry following the failed `attempt` (0 for the initial call)
+    pub fn get_retry_backoff(&self, attempt: u32) -> Duration {
+        Duration::from_secs_f64(self.retry_backoff * 2f64.powi(attempt.min(16) as i32))
+    }
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReconnectConf {
+    #[serde(default = "default_reconnect_max_attempts")]
+    pub max_attempts: u32,
+    #[serde(default = "default_reconnect_period")]
+    pub period: f32,
+}
+
+impl Default for ReconnectConf {
+    fn default() -> Self {
+        ReconnectConf {
+            max_attempts: default_reconnect_max_attempts(),
+            period: default_reconnect_period(),
+        }
+    }
+}
+
+impl ReconnectConf {
+    #[inline]
+    pub fn get_period(&self) -> Duration {
+        Duration::from_secs_f32(self.period.max(0.0))
+    }
+}
+
+/// The isolation of the heavy Services (see `heavy_services` config): the replies routed to the local Clients
+/// of the Services matching `services`, or of the Services which replied `reply_size` bytes or more, are
+/// routed by a pool of `workers` threads, with at most `queue_size` replies waiting for a thread.
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct HeavyServicesConf {
+    #[serde(
+        default,
+        deserialize_with = "deserialize_regex",
+        serialize_with = "serialize_regex"
+    )]
+    pub services: Option<Regex>,
+    #[serde(default)]
+    pub reply_size: Option<usize>,
+    #[serde(default = "default_heavy_services_workers")]
+    pub workers: usize,
+    #[serde(default = "default_heavy_services_queue_size")]
+    pub queue_size: usize,
+}
+
+impl HeavyServicesConf {
+    /// True if the Service is configured as heavy in `services`
+    pub fn is_heavy_service(&self, ros2_name: &str) -> bool {
+        self.services
+            .as_ref()
+            .map_or(false, |re| re.is_match(ros2_name))
+    }
+
+    #[inline]
+    pub fn get_workers(&self) -> usize {
+        self.workers.max(1)
+    }
+
+    #[inline]
+    pub fn get_queue_size(&self) -> usize {
+        self.queue_size.max(1)
+    }
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DdsRetryConf {
+    #[serde(default = "default_dds_retry_max_attempts")]
+    pub max_attempts: u32,
+    #[serde(default = "default_dds_retry_initial_period")]
+    pub initial_period: f32,
+    #[serde(default = "default_dds_retry_max_period")]
+    pub max_period: f32,
+}
+
+impl Default for DdsRetryConf {
+    fn default() -> Self {
+        DdsRetryConf {
+            max_attempts: default_dds_retry_max_attempts(),
+            initial_period: default_dds_retry_initial_period(),
+            max_period: default_dds_retry_max_period(),
+        }
+    }
+}
+
+impl DdsRetryConf {
+    #[inline]
+    pub fn get_initial_period(&self) -> Duration {
+        Duration::from_secs_f32(self.initial_period.max(0.0))
+    }
+
+    #[inline]
+    pub fn get_max_period(&self) -> Duration {
+        Duration::from_secs_f32(self.max_period.max(0.0))
+    }
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RouteIdleTimeouts {
+    #[serde(default)]
+    default: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    publishers: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    subscribers: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    service_servers: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    service_clients: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    action_servers: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    action_clients: Option<f32>,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct QueriesTimeouts {
+    #[serde(default = "default_queries_timeout")]
+    default: f32,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_f32",
+        serialize_with = "serialize_vec_regex_f32"
+    )]
+    transient_local_subscribers: Vec<(Regex, f32)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_f32",
+        serialize_with = "serialize_vec_regex_f32"
+    )]
+    services: Vec<(Regex, f32)>,
+    #[serde(default)]
+    actions: Option<ActionsTimeouts>,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ActionsTimeouts {
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_f32",
+        serialize_with = "serialize_vec_regex_f32"
+    )]
+    send_goal: Vec<(Regex, f32)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_f32",
+        serialize_with = "serialize_vec_regex_f32"
+    )]
+    cancel_goal: Vec<(Regex, f32)>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_vec_regex_f32",
+        serialize_with = "serialize_vec_regex_f32"
+    )]
+    get_result: Vec<(Regex, f32)>,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub enum Allowance {
+    #[serde(rename = "allow")]
+    Allow(ROS2InterfacesRegex),
+    #[serde(rename = "deny")]
+    Deny(ROS2InterfacesRegex),
+}
+
+impl Allowance {
+    pub fn is_publisher_allowed(&self, name: &str) -> bool {
+        use Allowance::*;
+        match self {
+            Allow(r) => r
+                .publishers
+                .as_ref()
+                .map(|re| re.is_match(name))
+                .unwrap_or(false),
+            Deny(r) => r
+                .publishers
+                .as_ref()
+                .map(|re| !re.is_match(name))
+                .unwrap_or(true),
+        }
+    }
+
+    pub fn is_subscriber_allowed(&self, name: &str) -> bool {
+        use Allowance::*;
+        match self {
+            Allow(r) => r
+                .subscribers
+                .as_ref()
+                .map(|re| re.is_match(name))
+                .unwrap_or(false),
+            Deny(r) => r
+                .subscribers
+                .as_ref()
+                .map(|re| !re.is_match(name))
+                .unwrap_or(true),
+        }
+    }
+
+    pub fn is_service_srv_allowed(&self, name: &str) -> bool {
+        use Allowance::*;
+        match self {
+            Allow(r) => r
+                .service_servers
+                .as_ref()
+                .map(|re| re.is_match(name))
+                .unwrap_or(false),
+            Deny(r) => r
+                .service_servers
+                .as_ref()
+                .map(|re| !re.is_match(name))
+                .unwrap_or(true),
+        }
+    }
+
+    pub fn is_service_cli_allowed(&self, name: &str) -> bool {
+        use Allowance::*;
+        match self {
+            Allow(r) => r
+                .service_clients
+                .as_ref()
+                .map(|re| re.is_match(name))
+                .unwrap_or(false),
+            Deny(r) => r
+                .service_clients
+                .as_ref()
+                .map(|re| !re.is_match(name))
+                .unwrap_or(true),
+        }
+    }
+
+    pub fn is_action_srv_allowed(&self, name: &str) -> bool {
+        use Allowance::*;
+        match self {
+            Allow(r) => r
+                .action_servers
+                .as_ref()
+                .map(|re| re.is_match(name))
+                .unwrap_or(false),
+            Deny(r) => r
+                .action_servers
+                .as_ref()
+                .map(|re| !re.is_match(name))
+                .unwrap_or(true),
+        }
+    }
+
+    pub fn is_action_cli_allowed(&self, name: &str) -> bool {
+        use Allowance::*;
+        match self {
+            Allow(r) => r
+                .action_clients
+                .as_ref()
+                .map(|re| re.is_match(name))
+                .unwrap_or(false),
+            Deny(r) => r
+                .action_clients
+                .as_ref()
+                .map(|re| !re.is_match(name))
+                .unwrap_or(true),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Serialize)]
+pub struct ROS2InterfacesRegex {
+    #[serde(
+        default,
+        deserialize_with = "deserialize_regex",
+        serialize_with = "serialize_regex",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub publishers: Option<Regex>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_regex",
+        serialize_with = "serialize_regex",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub subscribers: Option<Regex>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_regex",
+        serialize_with = "serialize_regex",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub service_servers: Option<Regex>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_regex",
+        serialize_with = "serialize_regex",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub service_clients: Option<Regex>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_regex",
+        serialize_with = "serialize_regex",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub action_servers: Option<Regex>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_regex",
+        serialize_with = "serialize_regex",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub action_clients: Option<Regex>,
+}
+
+fn default_namespace() -> String {
+    DEFAULT_NAMESPACE.to_string()
+}
+
+fn default_nodename() -> OwnedKeyExpr {
+    unsafe { OwnedKeyExpr::from_string_unchecked(DEFAULT_NODENAME.into()) }
+}
+
+fn default_domain() -> u32 {
+    if let Ok(s) = env::var("ROS_DOMAIN_ID") {
+        s.parse::<u32>().unwrap_or(DEFAULT_DOMAIN)
+    } else {
+        DEFAULT_DOMAIN
+    }
+}
+
+fn default_queries_timeout() -> f32 {
+    DEFAULT_QUERIES_TIMEOUT
+}
+
+// "queries_timeout" can be either a float (used as 'default' value for all queries),
+// either a QueriesTimeouts struct
+fn deserialize_queries_timeout<'de, D>(deserializer: D) -> Result<Option<QueriesTimeouts>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AcceptedValues {
+        Float(f32),
+        Struct(QueriesTimeouts),
+    }
+
+    match Option::<AcceptedValues>::deserialize(deserializer)? {
+        Some(AcceptedValues::Float(default)) => Ok(Some(QueriesTimeouts {
+            default,
+            transient_local_subscribers: Vec::new(),
+            services: Vec::new(),
+            actions: None,
+        })),
+        Some(AcceptedValues::Struct(qt)) => Ok(Some(qt)),
+        None => Ok(None),
+    }
+}
+
+// "route_idle_timeout" can be either a float (used for all kinds of routes),
+// either a RouteIdleTimeouts struct
+fn deserialize_route_idle_timeout<'de, D>(
+    deserializer: D,
+) -> Result<Option<RouteIdleTimeouts>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AcceptedValues {
+        Float(f32),
+        Struct(RouteIdleTimeouts),
+    }
+
+    match Option::<AcceptedValues>::deserialize(deserializer)? {
+        Some(AcceptedValues::Float(default)) => Ok(Some(RouteIdleTimeouts {
+            default,
+            publishers: None,
+            subscribers: None,
+            service_servers: None,
+            service_clients: None,
+            action_servers: None,
+            action_clients: None,
+        })),
+        Some(AcceptedValues::Struct(t)) => Ok(Some(t)),
+        None => Ok(None),
+    }
+}
+
+fn deserialize_raw_dds_topics<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let patterns: Vec<String> = Deserialize::deserialize(deserializer)?;
+    for pattern in &patterns {
+        if pattern.is_empty() || pattern.chars().all(|c| c == '*') {
+            return Err(de::Error::custom(format!(
+                r#"Invalid raw_dds_topics pattern "{pattern}": it must not be empty or match any topic"#
+            )));
+        }
+        if is_ros2_dds_topic(pattern) {
+            return Err(de::Error::custom(format!(
+                r#"Invalid raw_dds_topics pattern "{pattern}": ROS 2 topics are not raw DDS topics"#
+            )));
+        }
+    }
+    Ok(patterns)
+}
+
+fn deserialize_segment_separators<'de, D>(deserializer: D) -> Result<Vec<char>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let separators: Vec<char> = Deserialize::deserialize(deserializer)?;
+    for (i, c) in separators.iter().enumerate() {
+        if matches!(c, '/' | '*' | '$' | '?' | '#' | '%' | '@') || c.is_whitespace() {
+            return Err(de::Error::custom(format!(
+                r#"Invalid segment_separators character "{c}": it's already a separator or has a special meaning in key expressions"#
+            )));
+        }
+        if separators[..i].contains(c) {
+            return Err(de::Error::custom(format!(
+                r#"Duplicate segment_separators character "{c}""#
+            )));
+        }
+    }
+    Ok(separators)
+}
+
+// The QoS of the ROS parameter events
+fn parameter_events_qos() -> TopicQos {
+    TopicQos {
+        reliability: Some(ReliabilityOverride::Reliable),
+        durability: Some(DurabilityOverride::Volatile),
+        history: Some(HistoryOverride::KeepLast(PARAMETER_EVENTS_HISTORY_DEPTH)),
+        ..Default::default()
+    }
+}
+
+fn deserialize_bridge_parameters<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let patterns: Vec<String> = Deserialize::deserialize(deserializer)?;
+    for pattern in &patterns {
+        if !pattern.starts_with('/') || pattern.len() < 2 {
+            return Err(de::Error::custom(format!(
+                r#"Invalid bridge_parameters pattern "{pattern}": it must be an absolute Node name (e.g. "/talker" or "/ns/*")"#
+            )));
+        }
+    }
+    Ok(patterns)
+}
+
+// Match a string against a pattern where '*' matches any sequence of characters (possibly empty)
+fn glob_match(pattern: &str, s: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // the first part must be a prefix and (if there is no '*') the whole string
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = s.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    // the intermediate parts are matched left-most, the last one must be a suffix
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+fn deserialize_path<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_option(OptPathVisitor)
+}
+
+struct OptPathVisitor;
+
+impl<'de> serde::de::Visitor<'de> for OptPathVisitor {
+    type Value = Option<Vec<String>>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "none or a string or an array of strings")
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(PathVisitor).map(Some)
+    }
+}
+
+struct PathVisitor;
+
+impl<'de> serde::de::Visitor<'de> for PathVisitor {
+    type Value = Vec<String>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "a string or an array of strings")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(vec![v.into()])
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut v = if let Some(l) = seq.size_hint() {
+            Vec::with_capacity(l)
+        } else {
+            Vec::new()
+        };
+        while let Some(s) = seq.next_element()? {
+            v.push(s);
+        }
+        Ok(v)
+    }
+}
+
+fn default_discovery_batch_window() -> u64 {
+    DEFAULT_DISCOVERY_BATCH_WINDOW_MS
+}
+
+fn default_max_concurrent_route_creations() -> usize {
+    DEFAULT_MAX_CONCURRENT_ROUTE_CREATIONS
+}
+
+fn default_stats_rate_half_life() -> f32 {
+    DEFAULT_STATS_RATE_HALF_LIFE
+}
+
+fn default_service_retry_backoff() -> f64 {
+    DEFAULT_SERVICE_RETRY_BACKOFF
+}
+
+fn default_max_batch_delay() -> f64 {
+    DEFAULT_MAX_BATCH_DELAY
+}
+
+fn default_max_batch_size() -> usize {
+    DEFAULT_MAX_BATCH_SIZE
+}
+
+fn default_heavy_services_workers() -> usize {
+    DEFAULT_HEAVY_SERVICES_WORKERS
+}
+
+fn default_heavy_services_queue_size() -> usize {
+    DEFAULT_HEAVY_SERVICES_QUEUE_SIZE
+}
+
+fn default_reconnect_max_attempts() -> u32 {
+    DEFAULT_RECONNECT_MAX_ATTEMPTS
+}
+
+fn default_reconnect_period() -> f32 {
+    DEFAULT_RECONNECT_PERIOD
+}
+
+fn default_dds_retry_max_attempts() -> u32 {
+    DEFAULT_DDS_RETRY_MAX_ATTEMPTS
+}
+
+fn default_dds_retry_initial_period() -> f32 {
+    DEFAULT_DDS_RETRY_INITIAL_PERIOD
+}
+
+fn default_dds_retry_max_period() -> f32 {
+    DEFAULT_DDS_RETRY_MAX_PERIOD
+}
+
+fn default_reliable_routes_blocking() -> bool {
+    DEFAULT_RELIABLE_ROUTES_BLOCKING
+}
+
+fn default_latched_aggregate_topics() -> Option<Regex> {
+    Some(Regex::new(&format!("^{DEFAULT_LATCHED_AGGREGATE_TOPIC}$")).unwrap())
+}
+
+fn default_write_suppression() -> bool {
+    DEFAULT_WRITE_SUPPRESSION
+}
+
+fn default_propagate_deadline() -> bool {
+    DEFAULT_PROPAGATE_DEADLINE
+}
+
+fn default_propagate_time_based_filter() -> bool {
+    DEFAULT_PROPAGATE_TIME_BASED_FILTER
+}
+
+fn default_localhost_only() -> bool {
+    env::var("ROS_LOCALHOST_ONLY").as_deref() == Ok("1")
+}
+
+fn default_transient_local_cache_multiplier() -> usize {
+    DEFAULT_TRANSIENT_LOCAL_CACHE_MULTIPLIER
+}
+
+// Serialize a Regex built by RegexVisitor in its canonical form: the list of its patterns
+// (i.e. without the added anchors), so it can be deserialized back to the same Regex
+fn serialize_regex<S>(r: &Option<Regex>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match r {
+        Some(ex) => {
+            let s = ex.as_str();
+            let s = s
+                .strip_prefix('^')
+                .and_then(|s| s.strip_suffix('$'))
+                .unwrap_or(s);
+            let patterns: Vec<&str> = s.split("$|^").collect();
+            serializer.serialize_some(&patterns)
+        }
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_regex<'de, D>(deserializer: D) -> Result<Option<Regex>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(RegexVisitor)
+}
+
+// Serde Visitor for Regex deserialization.
+// It accepts either a String, either a list of Strings (that are concatenated with `|`)
+struct RegexVisitor;
+
+impl<'de> Visitor<'de> for RegexVisitor {
+    type Value = Option<Regex>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(r#"either a string or a list of strings"#)
+    }
+
+    // null, as serialized for no regex
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Regex::new(&format!("^{value}$"))
+            .map(Some)
+            .map_err(|e| de::Error::custom(format!("Invalid regex '{value}': {e}")))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut vec: Vec<String> = Vec::new();
+        while let Some(s) = seq.next_element::<String>()? {
+            vec.push(format!("^{s}$"));
+        }
+        if vec.is_empty() {
+            return Ok(None);
+        };
+
+        let s: String = vec.join("|");
+        Regex::new(&s)
+            .map(Some)
+            .map_err(|e| de::Error::custom(format!("Invalid regex '{s}': {e}")))
+    }
+}
+
+fn deserialize_vec_regex_f32<'de, D>(deserializer: D) -> Result<Vec<(Regex, f32)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(VecRegexValueVisitor::<f32>::new("float"))
+}
+
+fn deserialize_vec_regex_usize<'de, D>(deserializer: D) -> Result<Vec<(Regex, usize)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(VecRegexValueVisitor::<usize>::new("integer"))
+}
+
+fn deserialize_vec_regex_bool<'de, D>(deserializer: D) -> Result<Vec<(Regex, bool)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(VecRegexValueVisitor::<bool>::new("boolean"))
+}
+
+fn deserialize_vec_regex_reliability<'de, D>(
+    deserializer: D,
+) -> Result<Vec<(Regex, ReliabilityOverride)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(VecRegexValueVisitor::<ReliabilityOverride>::new(
+        "reliability",
+    ))
+}
+
+// Serde Visitor for a list of (Regex, value) deserialization.
+// It accepts either:
+//   - a single value (that applies to any string, i.e. matching ".*")
+//   - a list of "<regex>=<value>" strings
+//   - a map of "<regex>": <value> entries (the order of the entries is preserved)
+struct VecRegexValueVisitor<T> {
+    value_name: &'static str,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> VecRegexValueVisitor<T> {
+    fn new(value_name: &'static str) -> Self {
+        VecRegexValueVisitor {
+            value_name,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn single_value<E>(self, value: T) -> Result<Vec<(Regex, T)>, E>
+    where
+        E: de::Error,
+    {
+        // same value for any string (i.e. matching ".*")
+        Ok(vec![(Regex::new(".*").unwrap(), value)])
+    }
+}
+
+impl<'de, T> Visitor<'de> for VecRegexValueVisitor<T>
+where
+    T: Deserialize<'de> + FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    type Value = Vec<(Regex, T)>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            r#"either a {}, either a list of "<regex>={}" strings, either a map of "<regex>": <{}> entries"#,
+            self.value_name, self.value_name, self.value_name
+        )
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let deserializer: de::value::F64Deserializer<E> = value.into_deserializer();
+        let value = T::deserialize(deserializer)?;
+        self.single_value(value)
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let deserializer: de::value::U64Deserializer<E> = value.into_deserializer();
+        let value = T::deserialize(deserializer)?;
+        self.single_value(value)
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let deserializer: de::value::I64Deserializer<E> = value.into_deserializer();
+        let value = T::deserialize(deserializer)?;
+        self.single_value(value)
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let deserializer: de::value::StrDeserializer<E> = value.into_deserializer();
+        let value = T::deserialize(deserializer)?;
+        self.single_value(value)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut result: Vec<(Regex, T)> = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(s) = seq.next_element::<String>()? {
+            let i = s.find('=').ok_or_else(|| {
+                de::Error::custom(format!(
+                    r#"Invalid list of "<regex>=<{}>" elements": {s}"#,
+                    self.value_name
+                ))
+            })?;
+            let regex = Regex::new(&s[0..i])
+                .map_err(|e| de::Error::custom(format!("Invalid regex in '{s}': {e}")))?;
+            let value: T = s[i + 1..].parse().map_err(|e| {
+                de::Error::custom(format!("Invalid {} value in '{s}': {e}", self.value_name))
+            })?;
+            result.push((regex, value));
+        }
+        Ok(result)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut result: Vec<(Regex, T)> = Vec::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((s, value)) = map.next_entry::<String, T>()? {
+            let regex = Regex::new(&s)
+                .map_err(|e| de::Error::custom(format!("Invalid regex '{s}': {e}")))?;
+            result.push((regex, value));
+        }
+        Ok(result)
+    }
+}
+
+fn serialize_vec_regex_f32<S>(v: &Vec<(Regex, f32)>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_vec_regex_value(v, serializer)
+}
+
+fn serialize_vec_regex_usize<S>(v: &Vec<(Regex, usize)>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_vec_regex_value(v, serializer)
+}
+
+fn deserialize_vec_regex_deadline_miss_policy<'de, D>(
+    deserializer: D,
+) -> Result<Vec<(Regex, DeadlineMissPolicy)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(VecRegexValueVisitor::<DeadlineMissPolicy>::new("policy"))
+}
+
+fn deserialize_vec_regex_service_unavailable_policy<'de, D>(
+    deserializer: D,
+) -> Result<Vec<(Regex, ServiceUnavailablePolicy)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(VecRegexValueVisitor::<ServiceUnavailablePolicy>::new(
+        "policy",
+    ))
+}
+
+fn deserialize_vec_regex_rate_limit_policy<'de, D>(
+    deserializer: D,
+) -> Result<Vec<(Regex, RateLimitPolicy)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(VecRegexValueVisitor::<RateLimitPolicy>::new("policy"))
+}
+
+fn deserialize_vec_regex_compression<'de, D>(
+    deserializer: D,
+) -> Result<Vec<(Regex, Compression)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(VecRegexValueVisitor::<Compression>::new("compression"))
+}
+
+fn serialize_vec_regex_compression<S>(
+    v: &Vec<(Regex, Compression)>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_vec_regex_value(v, serializer)
+}
+
+fn serialize_vec_regex_rate_limit_policy<S>(
+    v: &Vec<(Regex, RateLimitPolicy)>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_vec_regex_value(v, serializer)
+}
+
+fn serialize_vec_regex_deadline_miss_policy<S>(
+    v: &Vec<(Regex, DeadlineMissPolicy)>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_vec_regex_value(v, serializer)
+}
+
+fn serialize_vec_regex_service_unavailable_policy<S>(
+    v: &Vec<(Regex, ServiceUnavailablePolicy)>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_vec_regex_value(v, serializer)
+}
+
+fn serialize_vec_regex_bool<S>(v: &Vec<(Regex, bool)>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_vec_regex_value(v, serializer)
+}
+
+fn serialize_vec_regex_reliability<S>(
+    v: &Vec<(Regex, ReliabilityOverride)>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_vec_regex_value(v, serializer)
+}
+
+fn serialize_vec_regex_value<S, T>(v: &[(Regex, T)], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: fmt::Display,
+{
+    let mut seq = serializer.serialize_seq(Some(v.len()))?;
+    for (r, f) in v {
+        let s = format!("{}={}", r.as_str(), f);
+        seq.serialize_element(&s)?;
+    }
+    seq.end()
+}
+
+fn deserialize_vec_regex_prio<'de, D>(deserializer: D) -> Result<Vec<(Regex, Priority)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let strs: Vec<String> = Deserialize::deserialize(deserializer).unwrap();
+    let mut result: Vec<(Regex, Priority)> = Vec::with_capacity(strs.len());
+    for s in strs {
+        let i = s.find('=').ok_or_else(|| {
+            de::Error::custom(format!(r#"Invalid list of "<regex>=<int>" elements": {s}"#))
+        })?;
+        let regex = Regex::new(&s[0..i])
+            .map_err(|e| de::Error::custom(format!("Invalid regex in '{s}': {e}")))?;
+        let i: u8 = s[i + 1..].parse().map_err(|e| {
+            de::Error::custom(format!("Invalid priority (not an integer) in '{s}': {e}"))
+        })?;
+        let priority = Priority::try_from(i)
+            .map_err(|e| de::Error::custom(format!("Invalid priority in '{s}': {e}")))?;
+        result.push((regex, priority));
+    }
+    Ok(result)
+}
+
+fn serialize_vec_regex_prio<S>(v: &Vec<(Regex, Priority)>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut seq = serializer.serialize_seq(Some(v.len()))?;
+    for (r, p) in v {
+        let s = format!("{}={}", r.as_str(), *p as u8);
+        seq.serialize_element(&s)?;
+    }
+    seq.end()
+}
+
+fn deserialize_dds_partition<'de, D>(deserializer: D) -> Result<Vec<(Regex, Vec<String>)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(DdsPartitionVisitor)
+}
+
+// Serde Visitor for the `dds_partition` deserialization.
+// It accepts either:
+//   - a list of Partitions (that applies to any topic, i.e. matching ".*")
+//   - a map of "<regex>": <Partition or list of Partitions> entries (the order of the entries is preserved)
+struct DdsPartitionVisitor;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrManyPartitions {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl<'de> Visitor<'de> for DdsPartitionVisitor {
+    type Value = Vec<(Regex, Vec<String>)>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(
+            r#"either a list of partitions, either a map of "<regex>": <list of partitions> entries"#,
+        )
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut partitions: Vec<String> = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(s) = seq.next_element::<String>()? {
+            partitions.push(s);
+        }
+        // same partitions for any topic (i.e. matching ".*")
+        Ok(vec![(Regex::new(".*").unwrap(), partitions)])
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut result: Vec<(Regex, Vec<String>)> =
+            Vec::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((s, partitions)) = map.next_entry::<String, OneOrManyPartitions>()? {
+            let regex = Regex::new(&s)
+                .map_err(|e| de::Error::custom(format!("Invalid regex '{s}': {e}")))?;
+            let partitions = match partitions {
+                OneOrManyPartitions::One(p) => vec![p],
+                OneOrManyPartitions::Many(v) => v,
+            };
+            result.push((regex, partitions));
+        }
+        Ok(result)
+    }
+}
+
+fn serialize_dds_partition<S>(
+    v: &Vec<(Regex, Vec<String>)>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(Some(v.len()))?;
+    for (r, partitions) in v {
+        map.serialize_entry(r.as_str(), partitions)?;
+    }
+    map.end()
+}
+
+fn deserialize_route_to<'de, D>(deserializer: D) -> Result<Vec<(Regex, Vec<String>)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let route_to = deserializer.deserialize_map(VecRegexMapVisitor::<Vec<String>>::new(
+        "list of bridge ids or aliases",
+    ))?;
+    for (re, targets) in &route_to {
+        if targets.is_empty() {
+            return Err(de::Error::custom(format!(
+                "Empty list of bridges in route_to for '{re}'"
+            )));
+        }
+        for target in targets {
+            route_to_target(target).map_err(de::Error::custom)?;
+        }
+    }
+    Ok(route_to)
+}
+
+fn deserialize_service_error_replies<'de, D>(
+    deserializer: D,
+) -> Result<Vec<(Regex, Vec<u8>)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let replies =
+        deserializer.deserialize_map(VecRegexMapVisitor::<String>::new("hex CDR payload"))?;
+    replies
+        .into_iter()
+        .map(|(re, s)| {
+            let payload = hex::decode(&s).map_err(|e| {
+                de::Error::custom(format!(
+                    "Invalid hex payload in service_error_replies for '{re}': {e}"
+                ))
+            })?;
+            // a CDR encapsulation header (CDR_BE or CDR_LE), followed by the serialized Response
+            if payload.len() < 5 || payload[1] > 1 || check_cdr_header(&payload).is_err() {
+                return Err(de::Error::custom(format!(
+                    "Invalid payload in service_error_replies for '{re}': it must start with a CDR_BE or CDR_LE header (\"00000000\" or \"00010000\") followed by the serialized Response"
+                )));
+            }
+            Ok((re, payload))
+        })
+        .collect()
+}
+
+fn serialize_service_error_replies<S>(
+    v: &Vec<(Regex, Vec<u8>)>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(Some(v.len()))?;
+    for (r, payload) in v {
+        map.serialize_entry(r.as_str(), &hex::encode(payload))?;
+    }
+    map.end()
+}
+
+fn deserialize_topic_qos<'de, D>(deserializer: D) -> Result<Vec<(Regex, TopicQos)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_map(VecRegexMapVisitor::<TopicQos>::new("QoS"))
+}
+
+fn deserialize_service_retries<'de, D>(
+    deserializer: D,
+) -> Result<Vec<(Regex, ServiceRetryPolicy)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_map(VecRegexMapVisitor::<ServiceRetryPolicy>::new(
+        "service retry policy",
+    ))
+}
+
+fn deserialize_batching<'de, D>(deserializer: D) -> Result<Vec<(Regex, BatchingConf)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_map(VecRegexMapVisitor::<BatchingConf>::new("batching"))
+}
+
+// Serde Visitor for a map of "<regex>": <value> entries, where the value is a structure
+// (e.g. `topic_qos`). The order of the entries is preserved.
+struct VecRegexMapVisitor<T> {
+    value_name: &'static str,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> VecRegexMapVisitor<T> {
+    fn new(value_name: &'static str) -> Self {
+        VecRegexMapVisitor {
+            value_name,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'de, T> Visitor<'de> for VecRegexMapVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Vec<(Regex, T)>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            r#"a map of "<regex>": <{}> entries"#,
+            self.value_name
+        )
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut result: Vec<(Regex, T)> = Vec::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((s, value)) = map.next_entry::<String, T>()? {
+            let regex = Regex::new(&s)
+                .map_err(|e| de::Error::custom(format!("Invalid regex '{s}': {e}")))?;
+            result.push((regex, value));
+        }
+        Ok(result)
+    }
+}
+
+fn serialize_vec_regex_map<S, T>(v: &Vec<(Regex, T)>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    let mut map = serializer.serialize_map(Some(v.len()))?;
+    for (r, value) in v {
+        map.serialize_entry(r.as_str(), value)?;
+    }
+    map.end()
+}
+
+fn deserialize_positive_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let f: f64 = Deserialize::deserialize(deserializer)?;
+    if !(f.is_finite() && f > 0.0) {
+        return Err(de::Error::custom(format!(
+            "Invalid duration {f}: it must be a positive number of seconds"
+        )));
+    }
+    Ok(f)
+}
+
+fn deserialize_option_positive_f64<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Option<f64> = Deserialize::deserialize(deserializer)?;
+    match value {
+        Some(f) if !(f.is_finite() && f > 0.0) => Err(de::Error::custom(format!(
+            "Invalid duration {f}: it must be a positive number of seconds"
+        ))),
+        _ => Ok(value),
+    }
+}
+
+fn deserialize_option_prio<'de, D>(deserializer: D) -> Result<Option<Priority>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Option<u8> = Deserialize::deserialize(deserializer)?;
+    value
+        .map(|i| {
+            Priority::try_from(i)
+                .map_err(|e| de::Error::custom(format!("Invalid priority {i}: {e}")))
+        })
+        .transpose()
+}
+
+fn serialize_option_prio<S>(v: &Option<Priority>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match v {
+        Some(p) => serializer.serialize_some(&(*p as u8)),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn serialize_duration_as_f32<S>(d: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_f32(d.as_secs_f32())
+}
+
+/// If the JSON configuration sets a `profile`, layer it over the defaults of this profile:
+/// the fields set by the user override the profile's ones (recursively for the objects).
+pub fn apply_profile(value: &mut serde_json::Value) -> Result<(), String> {
+    let profile = match value.get("profile") {
+        None | Some(serde_json::Value::Null) => return Ok(()),
+        Some(serde_json::Value::String(s)) => Profile::from_str(s)?,
+        Some(v) => return Err(format!("invalid profile: expected a string, got {v}")),
+    };
+    let mut layered = profile.defaults();
+    merge_json(&mut layered, std::mem::take(value));
+    *value = layered;
+    Ok(())
+}
+
+fn redact_json(value: &mut serde_json::Value, fields: &[&str]) {
+    if let serde_json::Value::Object(map) = value {
+        for field in fields {
+            if let Some(v) = map.get_mut(*field).filter(|v| !v.is_null()) {
+                *v = serde_json::Value::String("<redacted>".into());
+            }
+        }
+    }
+}
+
+fn merge_json(base: &mut serde_json::Value, over: serde_json::Value) {
+    use serde_json::Value;
+    match (base, over) {
+        (Value::Object(base), Value::Object(over)) => {
+            for (k, v) in over {
+                match base.get_mut(&k) {
+                    Some(b) => merge_json(b, v),
+                    None => {
+                        base.insert(k, v);
+                    }
+                }
+            }
+        }
+        (base, over) => *base = over,
+    }
+}
+
+/// Expand the environment variables referenced in all the strings of a JSON value
+/// (including the keys of the maps), using the `${VAR}` or `${VAR:-default}` syntax.
+/// A `$$` is replaced by a literal `$`. The default value can itself reference other variables.
+/// An error listing all the unset variables without default value is returned if any.
+pub fn expand_env_vars(value: &mut serde_json::Value) -> Result<(), String> {
+    expand_vars_with(value, &|name| env::var(name).ok())
+}
+
+fn expand_vars_with<F>(value: &mut serde_json::Value, lookup: &F) -> Result<(), String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut unset: Vec<String> = Vec::new();
+    expand_vars_in_value(value, lookup, &mut unset)?;
+    if unset.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "unset environment variable(s) without default value: {}",
+            unset.join(", ")
+        ))
+    }
+}
+
+fn expand_vars_in_value<F>(
+    value: &mut serde_json::Value,
+    lookup: &F,
+    unset: &mut Vec<String>,
+) -> Result<(), String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    use serde_json::Value;
+    match value {
+        Value::String(s) => *s = expand_vars_in_str(s, lookup, unset)?,
+        Value::Array(a) => {
+            for v in a.iter_mut() {
+                expand_vars_in_value(v, lookup, unset)?;
+            }
+        }
+        Value::Object(o) => {
+            let entries = std::mem::take(o);
+            for (k, mut v) in entries {
+                let k = expand_vars_in_str(&k, lookup, unset)?;
+                expand_vars_in_value(&mut v, lookup, unset)?;
+                o.insert(k, v);
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+fn expand_vars_in_str<F>(s: &str, lookup: &F, unset: &mut Vec<String>) -> Result<String, String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != '$' {
+            result.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some((_, '$')) => {
+                chars.next();
+                result.push('$');
+            }
+            Some((_, '{')) => {
+                chars.next();
+                // find the matching '}', taking nested "${...}" into account
+                let start = i + 2;
+                let mut depth = 1;
+                let mut end = None;
+                for (j, c) in chars.by_ref() {
+                    match c {
+                        '{' => depth += 1,
+                        '}' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                end = Some(j);
+                                break;
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+                let end = end.ok_or_else(|| format!("unterminated '${{' in \"{s}\""))?;
+                let (name, default) = match s[start..end].split_once(":-") {
+                    Some((name, default)) => (name, Some(default)),
+                    None => (&s[start..end], None),
+                };
+                if !is_valid_env_var_name(name) {
+                    return Err(format!(
+                        "invalid environment variable name '{name}' in \"{s}\""
+                    ));
+                }
+                match (lookup(name), default) {
+                    (Some(v), _) => result.push_str(&v),
+                    (None, Some(default)) => {
+                        result.push_str(&expand_vars_in_str(default, lookup, unset)?)
+                    }
+                    (None, None) => {
+                        if !unset.iter().any(|n| n == name) {
+                            unset.push(name.to_string());
+                        }
+                    }
+                }
+            }
+            // a '$' not followed by '{' or '$' (e.g. at the end of a regex) is kept as is
+            _ => result.push(c),
+        }
+    }
+    Ok(result)
+}
+
+fn is_valid_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+
+    #[test]
+    fn test_allowance() {
+        use super::*;
+
+        let allow: Allowance = serde_json::from_str(
+            r#"{
+                "allow": {
+                    "publishers": ["/tf", ".*/pose"],
+                    "subscribers": [],
+                    "service_servers": [".*"],
+                    "action_servers": [".*/rotate_absolute"],
+                    "action_clients": [ "" ]
+                }
+            }"#,
+        )
+        .unwrap();
+        println!("allow: {}", serde_json::to_string(&allow).unwrap());
+
+        assert!(matches!(
+            allow,
+            Allowance::Allow(ROS2InterfacesRegex {
+                publishers: Some(_),
+                subscribers: None,
+                service_servers: Some(_),
+                service_clients: None,
+                action_servers: Some(_),
+                action_clients: Some(_),
+            })
+        ));
+
+        assert!(allow.is_publisher_allowed("/tf"));
+        assert!(allow.is_publisher_allowed("/x/y/pose"));
+        assert!(!allow.is_publisher_allowed("/abc/rotate_absolute"));
+        assert!(!allow.is_publisher_allowed("/cmd_vel"));
+        assert!(!allow.is_service_cli_allowed("/some_pseudo_random_name"));
+
+        assert!(!allow.is_subscriber_allowed("/tf"));
+        assert!(!allow.is_subscriber_allowed("/x/y/pose"));
+        assert!(!allow.is_publisher_allowed("/abc/rotate_absolute"));
+        assert!(!allow.is_subscriber_allowed("/cmd_vel"));
+        assert!(!allow.is_service_cli_allowed("/some_pseudo_random_name"));
+
+        assert!(allow.is_service_srv_allowed("/tf"));
+        assert!(allow.is_service_srv_allowed("/x/y/pose"));
+        assert!(allow.is_service_srv_allowed("/abc/rotate_absolute"));
+        assert!(allow.is_service_srv_allowed("/cmd_vel"));
+        assert!(allow.is_service_srv_allowed("/some_pseudo_random_name"));
+
+        assert!(!allow.is_service_cli_allowed("/tf"));
+        assert!(!allow.is_service_cli_allowed("/x/y/pose"));
+        assert!(!allow.is_service_cli_allowed("/abc/rotate_absolute"));
+        assert!(!allow.is_service_cli_allowed("/cmd_vel"));
+        assert!(!allow.is_service_cli_allowed("/some_pseudo_random_name"));
+
+        assert!(!allow.is_action_srv_allowed("/tf"));
+        assert!(!allow.is_action_srv_allowed("/x/y/pose"));
+        assert!(allow.is_action_srv_allowed("/abc/rotate_absolute"));
+        assert!(!allow.is_action_srv_allowed("/cmd_vel"));
+        assert!(!allow.is_action_srv_allowed("/some_pseudo_random_name"));
+
+        assert!(!allow.is_action_cli_allowed("/tf"));
+        assert!(!allow.is_action_cli_allowed("/x/y/pose"));
+        assert!(!allow.is_action_cli_allowed("/abc/rotate_absolute"));
+        assert!(!allow.is_action_cli_allowed("/cmd_vel"));
+        assert!(!allow.is_action_cli_allowed("/some_pseudo_random_name"));
+
+        let deny: Allowance = serde_json::from_str(
+            r#"{
+                "deny": {
+                    "publishers": ["/tf", ".*/pose"],
+                    "subscribers": [],
+                    "service_servers": [".*"],
+                    "action_servers": [".*/rotate_absolute"],
+                    "action_clients": [ "" ]
+                }
+            }"#,
+        )
+        .unwrap();
+        println!("deny: {}", serde_json::to_string(&allow).unwrap());
+
+        assert!(matches!(
+            deny,
+            Allowance::Deny(ROS2InterfacesRegex {
+                publishers: Some(_),
+                subscribers: None,
+                service_servers: Some(_),
+                service_clients: None,
+                action_servers: Some(_),
+                action_clients: Some(_),
+            })
+        ));
+
+        assert!(!deny.is_publisher_allowed("/tf"));
+        assert!(!deny.is_publisher_allowed("/x/y/pose"));
+        assert!(deny.is_publisher_allowed("/abc/rotate_absolute"));
+        assert!(deny.is_publisher_allowed("/cmd_vel"));
+        assert!(deny.is_service_cli_allowed("/some_pseudo_random_name"));
+
+        assert!(deny.is_subscriber_allowed("/tf"));
+        assert!(deny.is_subscriber_allowed("/x/y/pose"));
+        assert!(deny.is_publisher_allowed("/abc/rotate_absolute"));
+        assert!(deny.is_subscriber_allowed("/cmd_vel"));
+        assert!(deny.is_service_cli_allowed("/some_pseudo_random_name"));
+
+        assert!(!deny.is_service_srv_allowed("/tf"));
+        assert!(!deny.is_service_srv_allowed("/x/y/pose"));
+        assert!(!deny.is_service_srv_allowed("/abc/rotate_absolute"));
+        assert!(!deny.is_service_srv_allowed("/cmd_vel"));
+        assert!(!deny.is_service_srv_allowed("/some_pseudo_random_name"));
+
+        assert!(deny.is_service_cli_allowed("/tf"));
+        assert!(deny.is_service_cli_allowed("/x/y/pose"));
+        assert!(deny.is_service_cli_allowed("/abc/rotate_absolute"));
+        assert!(deny.is_service_cli_allowed("/cmd_vel"));
+        assert!(deny.is_service_cli_allowed("/some_pseudo_random_name"));
+
+        assert!(deny.is_action_srv_allowed("/tf"));
+        assert!(deny.is_action_srv_allowed("/x/y/pose"));
+        assert!(!deny.is_action_srv_allowed("/abc/rotate_absolute"));
+        assert!(deny.is_action_srv_allowed("/cmd_vel"));
+        assert!(deny.is_action_srv_allowed("/some_pseudo_random_name"));
+
+        assert!(deny.is_action_cli_allowed("/tf"));
+        assert!(deny.is_action_cli_allowed("/x/y/pose"));
+        assert!(deny.is_action_cli_allowed("/abc/rotate_absolute"));
+        assert!(deny.is_action_cli_allowed("/cmd_vel"));
+        assert!(deny.is_action_cli_allowed("/some_pseudo_random_name"));
+
+        let invalid = serde_json::from_str::<Allowance>(
+            r#"{
+                "allow": {
+                    "publishers": ["/tf", ".*/pose"],
+                    "subscribers": [],
+                    "service_servers": [".*"],
+                    "action_servers": [".*/rotate_absolute"],
+                    "action_clients": [ "" ]
+                },
+                "deny": {
+                    "subscribers": ["/tf", ".*/pose"],
+                    "service_clients": [".*"],
+                    "action_servers": [""],
+                    "action_clients": [ ".*/rotate_absolute" ]
+                },
+            }"#,
+        );
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_service_error_replies() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_service_error_reply("/get_state"), None);
+
+        // std_srvs/srv/Trigger Responses with success=false and an empty message, little and big endian
+        let config = serde_json::from_str::<Config>(
+            r#"{"service_error_replies": {"/get_state": "00010000000000000100000000", ".*/trigger": "00000000000000000000000100"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_service_error_reply("/get_state"),
+            Some([0u8, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0].as_slice())
+        );
+        assert_eq!(
+            config.get_service_error_reply("/robot/trigger"),
+            Some([0u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0].as_slice())
+        );
+        assert_eq!(config.get_service_error_reply("/load_map"), None);
+
+        // not hex, or without a CDR header
+        assert!(serde_json::from_str::<Config>(
+            r#"{"service_error_replies": {"/get_state": "not hex"}}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<Config>(
+            r#"{"service_error_replies": {"/get_state": "00010000"}}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<Config>(
+            r#"{"service_error_replies": {"/get_state": "0700000001"}}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_queries_timeout_as_float() {
+        use std::time::Duration;
+
+        let config = serde_json::from_str::<Config>(r#"{"queries_timeout": 2}"#).unwrap();
+        assert_eq!(
+            config.get_queries_timeout_service("/any/service"),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            config.get_queries_timeout_action_get_result("/any/action"),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            config.get_queries_timeout_tl_sub("/any/topic"),
+            Duration::from_secs(2)
+        );
+
+        let config = serde_json::from_str::<Config>(r#"{"queries_timeout": 0.5}"#).unwrap();
+        assert_eq!(
+            config.get_queries_timeout_service("/any/service"),
+            Duration::from_millis(500)
+        );
+
+        let config = serde_json::from_str::<Config>("{}").unwrap();
+        assert_eq!(
+            config.get_queries_timeout_service("/any/service"),
+            Duration::from_secs_f32(super::DEFAULT_QUERIES_TIMEOUT)
+        );
+    }
+
+    #[test]
+    fn test_queries_timeout_as_struct() {
+        use std::time::Duration;
+
+        let config = serde_json::from_str::<Config>(
+            r#"{
+                "queries_timeout": {
+                    "default": 2.0,
+                    "services": {
+                        "/map_server/load_map": 60,
+                        ".*/get_.*": 0.5
+                    },
+                    "actions": {
+                        "send_goal": 1.0,
+                        "get_result": [".*/navigate_.*=3600", ".*=10"]
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.get_queries_timeout_service("/map_server/load_map"),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            config.get_queries_timeout_service("/robot/get_state"),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            config.get_queries_timeout_service("/add_two_ints"),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            config.get_queries_timeout_tl_sub("/map"),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            config.get_queries_timeout_action_send_goal("/navigate_to_pose"),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            config.get_queries_timeout_action_cancel_goal("/navigate_to_pose"),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            config.get_queries_timeout_action_get_result("/navigate_to_pose"),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(
+            config.get_queries_timeout_action_get_result("/fibonacci"),
+            Duration::from_secs(10)
+        );
+
+        // the map order is preserved: 1st matching regex wins
+        let config = serde_json::from_str::<Config>(
+            r#"{"queries_timeout": {"services": {"/slow": 30, ".*": 1}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_queries_timeout_service("/slow"),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            config.get_queries_timeout_service("/fast"),
+            Duration::from_secs(1)
+        );
+
+        // invalid values
+        assert!(serde_json::from_str::<Config>(r#"{"queries_timeout": "2.0"}"#).is_err());
+        assert!(serde_json::from_str::<Config>(
+            r#"{"queries_timeout": {"services": {"/slow": "abc"}}}"#
+        )
+        .is_err());
+        assert!(
+            serde_json::from_str::<Config>(r#"{"queries_timeout": {"services": ["/slow"]}}"#)
+                .is_err()
+        );
+        assert!(
+            serde_json::from_str::<Config>(r#"{"queries_timeout": {"unknown_field": 1.0}}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_map_order_from_value() {
+        use std::time::Duration;
+
+        // the plugin's config is received as a serde_json::Value (see lib.rs): the entries of the maps of
+        // "<regex>": <value> must keep their order of declaration, the 1st matching regex applying
+        let json = r#"{
+            "queries_timeout": {"services": {"/map_server/load_map": 60.0, ".*": 1.0}},
+            "max_concurrent_requests": {"/merge_maps": 1, ".*": 16},
+            "dds_partition": {"/diag/.*": "diag", ".*": "prod"},
+            "service_retries": {"/get_state": {"retries": 1}, ".*": {"retries": 3}},
+            "batching": {"/joint_states": {"max_batch_size": 4096}, ".*": {}}
+        }"#;
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let config = serde_json::from_value::<Config>(value).unwrap();
+        assert_eq!(
+            config.get_queries_timeout_service("/map_server/load_map"),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            config.get_queries_timeout_service("/add_two_ints"),
+            Duration::from_secs(1)
+        );
+        assert_eq!(config.get_max_concurrent_requests("/merge_maps"), Some(1));
+        assert_eq!(config.get_max_concurrent_requests("/get_map"), Some(16));
+        assert_eq!(
+            config.get_dds_partition("/diag/cpu"),
+            Some(&vec!["diag".to_string()])
+        );
+        assert_eq!(
+            config
+                .get_service_retry_policy("/get_state")
+                .unwrap()
+                .retries,
+            1
+        );
+        assert_eq!(
+            config
+                .get_service_retry_policy("/add_two_ints")
+                .unwrap()
+                .retries,
+            3
+        );
+        assert_eq!(
+            config.get_batching("/joint_states").unwrap().max_batch_size,
+            4096
+        );
+        // and the order is kept by the serialization, for the admin space
+        assert_eq!(
+            serde_json::to_value(&config).unwrap()["max_concurrent_requests"],
+            serde_json::json!(["/merge_maps=1", ".*=16"])
+        );
+    }
+
+    #[test]
+    fn test_namespace_rules() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.namespace_rules.is_empty());
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"namespace_rules": [
+                {"pattern": "/tf|/tf_static", "namespace": "/"},
+                {"pattern": ".*", "namespace": "/robot1/ns"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(config.namespace_rules.len(), 2);
+        assert!(config.namespace_rules[0].is_match("/tf_static"));
+        // patterns must match the full name
+        assert!(!config.namespace_rules[0].is_match("/tf_static_2"));
+        assert!(!config.namespace_rules[0].is_match("/a/tf"));
+
+        // invalid rule sets
+        for json in [
+            // duplicate pattern
+            r#"[{"pattern": "/tf", "namespace": "/"}, {"pattern": "/tf", "namespace": "/robot1"}]"#,
+            // invalid regex
+            r#"[{"pattern": "/tf(", "namespace": "/"}]"#,
+            // empty pattern
+            r#"[{"pattern": "", "namespace": "/"}]"#,
+            // invalid namespaces
+            r#"[{"pattern": "/tf", "namespace": "robot1"}]"#,
+            r#"[{"pattern": "/tf", "namespace": "/robot1/"}]"#,
+            r#"[{"pattern": "/tf", "namespace": "/robot1//ns"}]"#,
+            r#"[{"pattern": "/tf", "namespace": "/robot-1"}]"#,
+            r#"[{"pattern": "/tf", "namespace": ""}]"#,
+            // missing or unknown field
+            r#"[{"pattern": "/tf"}]"#,
+            r#"[{"pattern": "/tf", "namespace": "/", "prefix": "/"}]"#,
+        ] {
+            assert!(
+                serde_json::from_str::<Config>(&format!(r#"{{"namespace_rules": {json}}}"#))
+                    .is_err(),
+                "{json} shall be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_remappings() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.remappings.is_empty());
+
+        // sorted most-specific-first
+        let config = serde_json::from_str::<Config>(
+            r#"{"remappings": {
+                "/**": "/site_a/**",
+                "/ns/**": "/other_ns/**",
+                "/old_cmd_vel": "/cmd_vel"
+            }}"#,
+        )
+        .unwrap();
+        let froms: Vec<&str> = config.remappings.iter().map(|r| r.from.as_str()).collect();
+        assert_eq!(froms, vec!["/old_cmd_vel", "/ns/**", "/**"]);
+        assert_eq!(
+            config.remappings[0].apply("/old_cmd_vel").as_deref(),
+            Some("/cmd_vel")
+        );
+        assert_eq!(config.remappings[0].apply("/old_cmd_vel2"), None);
+        assert_eq!(
+            config.remappings[1].apply("/ns/a/b").as_deref(),
+            Some("/other_ns/a/b")
+        );
+        assert_eq!(config.remappings[1].apply("/ns"), None);
+        assert_eq!(config.remappings[1].apply("/ns/"), None);
+        assert_eq!(config.remappings[1].apply("/nsx/a"), None);
+        assert_eq!(
+            config.remappings[1]
+                .apply_inverse("/other_ns/a/b")
+                .as_deref(),
+            Some("/ns/a/b")
+        );
+        assert_eq!(config.remappings[1].apply_inverse("/ns/a/b"), None);
+        assert_eq!(
+            config.remappings[2].apply("/a").as_deref(),
+            Some("/site_a/a")
+        );
+        assert_eq!(
+            serde_json::to_value(&config).unwrap()["remappings"],
+            serde_json::json!({"/old_cmd_vel": "/cmd_vel", "/ns/**": "/other_ns/**", "/**": "/site_a/**"})
+        );
+
+        // invalid remappings and collisions
+        for json in [
+            r#"{"/a": "/b/**"}"#,
+            r#"{"/a/**": "/b"}"#,
+            r#"{"a": "/b"}"#,
+            r#"{"/a/": "/b"}"#,
+            r#"{"/a**": "/b**"}"#,
+            r#"{"//**": "/b/**"}"#,
+            r#"{"/a/**/b": "/c"}"#,
+            r#"{"/a": "/c", "/b": "/c"}"#,
+            r#"{"/a/**": "/c/**", "/b/**": "/c/**"}"#,
+            r#"{"/a/**": "/c/**", "/b/**": "/c/d/**"}"#,
+            r#"{"/a/**": "/c/**", "/b": "/c/b"}"#,
+            r#"{"/b": "/c/b/d", "/a/**": "/c/b/**"}"#,
+            // the sub-interfaces of a remapped Action are implicitly remapped
+            r#"{"/a": "/c", "/b/_action/status": "/c/_action/status"}"#,
+            r#"{"/b/_action/feedback": "/c/_action/feedback", "/a": "/c"}"#,
+            r#"[]"#,
+        ] {
+            assert!(
+                serde_json::from_str::<Config>(&format!(r#"{{"remappings": {json}}}"#)).is_err(),
+                "{json} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_action_remappings() {
+        // an explicit remapping of an Action's sub-interface, consistent with the Action's one
+        // or to another name, is accepted
+        for json in [
+            r#"{"/a": "/c", "/a/_action/status": "/c/_action/status"}"#,
+            r#"{"/a": "/c", "/a/_action/status": "/status"}"#,
+            r#"{"/a": "/c", "/b/_action/status": "/c/_action/status2"}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<Config>(&format!(r#"{{"remappings": {json}}}"#)).is_ok(),
+                "{json} should be accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_prefix_from_env() {
+        let lookup = |name: &str| match name {
+            "ROBOT_ID" => Some("robot7".to_string()),
+            "BAD_ROBOT_ID" => Some("robot-7".to_string()),
+            "ROBOT_PATH" => Some("fleet/robot7".to_string()),
+            "ROBOT_WILD" => Some("robot*".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        };
+
+        // not configured
+        let mut config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.resolve_key_prefix_with(lookup).is_ok());
+        assert_eq!(config.get_key_prefix(), None);
+        assert_eq!(config.namespace, "/");
+
+        // set
+        let mut config =
+            serde_json::from_str::<Config>(r#"{"key_prefix_from_env": "ROBOT_ID"}"#).unwrap();
+        assert_eq!(config.get_key_prefix(), None);
+        config.resolve_key_prefix_with(lookup).unwrap();
+        assert_eq!(config.get_key_prefix(), Some("robot7"));
+        assert_eq!(config.namespace, "/robot7");
+        assert_eq!(config.get_namespace_to_zenoh(), "/robot7");
+        assert_eq!(config.get_namespace_from_zenoh(), "/robot7");
+        // resolved only once
+        config.resolve_key_prefix_with(lookup).unwrap();
+        assert_eq!(config.namespace, "/robot7");
+
+        // used like the namespace, prefixing the configured ones
+        let mut config = serde_json::from_str::<Config>(
+            r#"{"key_prefix_from_env": "ROBOT_ID", "namespace": "/ns", "namespace_from_zenoh": "/fleet"}"#,
+        )
+        .unwrap();
+        config.resolve_key_prefix_with(lookup).unwrap();
+        assert_eq!(config.get_namespace_to_zenoh(), "/robot7/ns");
+        assert_eq!(config.get_namespace_from_zenoh(), "/robot7/fleet");
+
+        // unset
+        let mut config =
+            serde_json::from_str::<Config>(r#"{"key_prefix_from_env": "UNSET_ID"}"#).unwrap();
+        let err = config.resolve_key_prefix_with(lookup).unwrap_err();
+        assert!(err.contains("UNSET_ID is not set"));
+        assert_eq!(config.namespace, "/");
+
+        // invalid characters
+        for var in ["BAD_ROBOT_ID", "ROBOT_PATH", "ROBOT_WILD", "EMPTY"] {
+            let mut config =
+                serde_json::from_str::<Config>(&format!(r#"{{"key_prefix_from_env": "{var}"}}"#))
+                    .unwrap();
+            let err = config.resolve_key_prefix_with(lookup).unwrap_err();
+            assert!(err.contains("invalid value"), "{var}: {err}");
+            assert_eq!(config.get_key_prefix(), None);
+        }
+    }
+
+    #[test]
+    fn test_scopes() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.scope_out.is_none());
+        assert!(config.scope_in.is_none());
+        let config =
+            serde_json::from_str::<Config>(r#"{"scope_out": "dds/site_a", "scope_in": "dds"}"#)
+                .unwrap();
+        assert_eq!(
+            config.scope_out.as_deref().map(|k| k.as_str()),
+            Some("dds/site_a")
+        );
+        assert_eq!(config.scope_in.as_deref().map(|k| k.as_str()), Some("dds"));
+        assert!(serde_json::from_str::<Config>(r#"{"scope_out": "/dds"}"#).is_err());
+    }
+
+    #[test]
+    fn test_bridge_hidden_topics() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(!config.bridge_hidden_topics);
+        assert!(config.is_name_bridged("/cmd_vel"));
+        assert!(!config.is_name_bridged("/_internal/status"));
+        assert!(!config.is_name_bridged("/ns/_debug"));
+        assert!(config.is_name_bridged("/add_two_ints/_service_event"));
+        assert!(!config.is_name_bridged("/_internal/trigger/_service_event"));
+
+        let config = serde_json::from_str::<Config>(r#"{"bridge_hidden_topics": true}"#).unwrap();
+        assert!(config.is_name_bridged("/cmd_vel"));
+        assert!(config.is_name_bridged("/_internal/status"));
+        assert!(config.is_name_bridged("/ns/_debug"));
+    }
+
+    #[test]
+    fn test_bridge_parameters() {
+        use super::{DurabilityOverride, HistoryOverride, ReliabilityOverride};
+
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(!config.is_parameters_interface("/talker/get_parameters"));
+        assert!(!config.is_parameters_interface("/parameter_events"));
+        assert_eq!(config.get_topic_qos("/parameter_events"), None);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"bridge_parameters": ["/talker", "/ns/*"], "topic_qos": {"/rosout": {"reliability": "best_effort"}}}"#,
+        )
+        .unwrap();
+        assert!(config.is_node_parameters_bridged("/talker"));
+        assert!(config.is_node_parameters_bridged("/ns/listener"));
+        assert!(!config.is_node_parameters_bridged("/talker2"));
+        assert!(config.is_parameters_interface("/talker/get_parameters"));
+        assert!(config.is_parameters_interface("/ns/listener/set_parameters"));
+        assert!(!config.is_parameters_interface("/talker/add_two_ints"));
+        assert!(!config.is_parameters_interface("/listener/get_parameters"));
+        assert!(config.is_parameters_interface("/parameter_events"));
+        // the parameter events QoS, unless configured in topic_qos
+        let qos = config.get_topic_qos("/parameter_events").unwrap();
+        assert_eq!(qos.reliability, Some(ReliabilityOverride::Reliable));
+        assert_eq!(qos.durability, Some(DurabilityOverride::Volatile));
+        assert_eq!(qos.history, Some(HistoryOverride::KeepLast(1000)));
+        assert_eq!(config.get_topic_qos("/chatter"), None);
+
+        // not absolute Node names
+        assert!(serde_json::from_str::<Config>(r#"{"bridge_parameters": ["talker"]}"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{"bridge_parameters": ["/"]}"#).is_err());
+    }
+
+    #[test]
+    fn test_reliability_override() {
+        use super::ReliabilityOverride;
+
+        // not configured
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_reliability_override("/scan"), None);
+
+        // single value
+        let config = serde_json::from_str::<Config>(r#"{"reliability": "best_effort"}"#).unwrap();
+        assert_eq!(
+            config.get_reliability_override("/scan"),
+            Some(ReliabilityOverride::BestEffort)
+        );
+
+        // list and map syntaxes
+        let config = serde_json::from_str::<Config>(
+            r#"{"reliability": ["/sensors/.*=best_effort", "/cmd_vel=reliable"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_reliability_override("/sensors/lidar"),
+            Some(ReliabilityOverride::BestEffort)
+        );
+        assert_eq!(
+            config.get_reliability_override("/cmd_vel"),
+            Some(ReliabilityOverride::Reliable)
+        );
+        assert_eq!(config.get_reliability_override("/tf"), None);
+        let config = serde_json::from_str::<Config>(
+            r#"{"reliability": {"/sensors/.*": "best_effort", ".*": "reliable"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_reliability_override("/sensors/lidar"),
+            Some(ReliabilityOverride::BestEffort)
+        );
+        assert_eq!(
+            config.get_reliability_override("/tf"),
+            Some(ReliabilityOverride::Reliable)
+        );
+        // serialized back as a list of "<regex>=<reliability>" strings
+        assert_eq!(
+            serde_json::to_value(&config).unwrap()["reliability"],
+            serde_json::json!(["/sensors/.*=best_effort", ".*=reliable"])
+        );
+
+        // invalid values
+        assert!(serde_json::from_str::<Config>(r#"{"reliability": "RELIABLE"}"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{"reliability": ["/scan=fast"]}"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{"reliability": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_zenoh_reliability() {
+        // not configured: the default applies
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.is_zenoh_reliable("/scan", true));
+        assert!(!config.is_zenoh_reliable("/scan", false));
+
+        // configured for some topics, independently of the DDS reliability
+        let config = serde_json::from_str::<Config>(
+            r#"{"zenoh_reliability": {"/cmd_echo": "reliable", "/camera/.*": "best_effort"}}"#,
+        )
+        .unwrap();
+        assert!(config.is_zenoh_reliable("/cmd_echo", false));
+        assert!(!config.is_zenoh_reliable("/camera/image", true));
+        assert!(!config.is_zenoh_reliable("/scan", false));
+        assert!(config.is_zenoh_reliable("/scan", true));
+        // the DDS reliability override is not affected
+        assert_eq!(config.get_reliability_override("/cmd_echo"), None);
+
+        assert!(serde_json::from_str::<Config>(r#"{"zenoh_reliability": "RELIABLE"}"#).is_err());
+    }
+
+    #[test]
+    fn test_keyed() {
+        // not configured: the discovered keyless flag applies
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.is_keyless("/objects", true));
+        assert!(!config.is_keyless("/objects", false));
+
+        // forced in both directions for some topics
+        let config = serde_json::from_str::<Config>(
+            r#"{"keyed": {"/vendor/objects": true, "/vendor/.*": false}}"#,
+        )
+        .unwrap();
+        assert!(!config.is_keyless("/vendor/objects", true));
+        assert!(!config.is_keyless("/vendor/objects", false));
+        assert!(config.is_keyless("/vendor/status", false));
+        assert!(config.is_keyless("/vendor/status", true));
+        assert!(config.is_keyless("/objects", true));
+        assert!(!config.is_keyless("/objects", false));
+
+        let config =
+            serde_json::from_str::<Config>(r#"{"keyed": ["/vendor/objects=true"]}"#).unwrap();
+        assert!(!config.is_keyless("/vendor/objects", true));
+        assert!(serde_json::from_str::<Config>(r#"{"keyed": {"/objects": "yes"}}"#).is_err());
+    }
+
+    #[test]
+    fn test_startup_grace_period() {
+        use std::time::Duration;
+
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_startup_grace_period(), None);
+
+        let config = serde_json::from_str::<Config>(r#"{"startup_grace_period": 10}"#).unwrap();
+        assert_eq!(
+            config.get_startup_grace_period(),
+            Some(Duration::from_secs(10))
+        );
+        let config = serde_json::from_str::<Config>(r#"{"startup_grace_period": 0.5}"#).unwrap();
+        assert_eq!(
+            config.get_startup_grace_period(),
+            Some(Duration::from_millis(500))
+        );
+        let config = serde_json::from_str::<Config>(r#"{"startup_grace_period": 0}"#).unwrap();
+        assert_eq!(config.get_startup_grace_period(), None);
+    }
+
+    #[test]
+    fn test_route_linger() {
+        use std::time::Duration;
+
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_route_linger(), None);
+        let config = serde_json::from_str::<Config>(r#"{"route_linger": 2.5}"#).unwrap();
+        assert_eq!(config.get_route_linger(), Some(Duration::from_millis(2500)));
+        let config = serde_json::from_str::<Config>(r#"{"route_linger": -1}"#).unwrap();
+        assert_eq!(config.get_route_linger(), None);
+    }
+
+    #[test]
+    fn test_route_idle_timeout() {
+        use crate::forward_discovery::RouteKind::*;
+        use std::time::Duration;
+
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_route_idle_timeout(Publisher), None);
+
+        let config = serde_json::from_str::<Config>(r#"{"route_idle_timeout": 30}"#).unwrap();
+        for kind in [
+            Publisher, Subscriber, ServiceSrv, ServiceCli, ActionSrv, ActionCli,
+        ] {
+            assert_eq!(
+                config.get_route_idle_timeout(kind),
+                Some(Duration::from_secs(30))
+            );
+        }
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"route_idle_timeout": {"default": 60, "publishers": 5.5, "action_servers": 0}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_route_idle_timeout(Publisher),
+            Some(Duration::from_millis(5500))
+        );
+        assert_eq!(
+            config.get_route_idle_timeout(Subscriber),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(config.get_route_idle_timeout(ActionSrv), None);
+
+        let config =
+            serde_json::from_str::<Config>(r#"{"route_idle_timeout": {"subscribers": 10}}"#)
+                .unwrap();
+        assert_eq!(config.get_route_idle_timeout(Publisher), None);
+        assert_eq!(
+            config.get_route_idle_timeout(Subscriber),
+            Some(Duration::from_secs(10))
+        );
+
+        assert!(
+            serde_json::from_str::<Config>(r#"{"route_idle_timeout": {"topics": 10}}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn test_reconnect() {
+        use std::time::Duration;
+
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.reconnect.max_attempts, 3);
+        assert_eq!(config.reconnect.get_period(), Duration::from_secs(1));
+
+        let config =
+            serde_json::from_str::<Config>(r#"{"reconnect": {"max_attempts": 10, "period": 0.5}}"#)
+                .unwrap();
+        assert_eq!(config.reconnect.max_attempts, 10);
+        assert_eq!(config.reconnect.get_period(), Duration::from_millis(500));
+
+        let config =
+            serde_json::from_str::<Config>(r#"{"reconnect": {"max_attempts": 0}}"#).unwrap();
+        assert_eq!(config.reconnect.max_attempts, 0);
+        assert_eq!(config.reconnect.get_period(), Duration::from_secs(1));
+
+        assert!(serde_json::from_str::<Config>(r#"{"reconnect": {"max_attempt": 10}}"#).is_err());
+    }
+
+    #[test]
+    fn test_dds_retry() {
+        use std::time::Duration;
+
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.dds_retry.max_attempts, 10);
+        assert_eq!(
+            config.dds_retry.get_initial_period(),
+            Duration::from_millis(100)
+        );
+        assert_eq!(config.dds_retry.get_max_period(), Duration::from_secs(10));
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"dds_retry": {"max_attempts": 0, "max_period": 60.0}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.dds_retry.max_attempts, 0);
+        assert_eq!(
+            config.dds_retry.get_initial_period(),
+            Duration::from_millis(100)
+        );
+        assert_eq!(config.dds_retry.get_max_period(), Duration::from_secs(60));
+
+        assert!(serde_json::from_str::<Config>(r#"{"dds_retry": {"period": 1.0}}"#).is_err());
+    }
+
+    fn config_with_profile(json: &str) -> Result<Config, String> {
+        let mut value: serde_json::Value = serde_json::from_str(json).unwrap();
+        super::apply_profile(&mut value)?;
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_profiles() {
+        use super::{Profile, ReliabilityOverride};
+        use std::time::Duration;
+        use zenoh::prelude::Priority;
+
+        // lan: everything default
+        let config = config_with_profile(r#"{"profile": "lan"}"#).unwrap();
+        assert_eq!(config.profile, Some(Profile::Lan));
+        assert!(config.reliability.is_empty());
+        assert!(config.pub_max_frequencies.is_empty());
+        assert!(config.pub_priorities.is_empty());
+        assert!(config.reliable_routes_blocking);
+        assert_eq!(
+            config.get_queries_timeout_service("/add"),
+            Duration::from_secs(5)
+        );
+
+        // wan-lowbw
+        let config = config_with_profile(r#"{"profile": "wan-lowbw"}"#).unwrap();
+        assert_eq!(config.profile, Some(Profile::WanLowbw));
+        assert_eq!(
+            config.get_reliability_override("/camera/image_raw"),
+            Some(ReliabilityOverride::BestEffort)
+        );
+        assert_eq!(
+            config.get_reliability_override("/robot/scan"),
+            Some(ReliabilityOverride::BestEffort)
+        );
+        assert_eq!(config.get_reliability_override("/cmd_vel"), None);
+        assert_eq!(
+            config.get_pub_max_frequencies("/camera/image_raw"),
+            Some(2.0)
+        );
+        assert_eq!(config.get_pub_max_frequencies("/lidar/points"), Some(2.0));
+        assert_eq!(config.get_pub_max_frequencies("/tf"), None);
+        assert_eq!(
+            config.get_pub_priorities("/rosout"),
+            Some(Priority::Background)
+        );
+        assert!(!config.reliable_routes_blocking);
+        assert_eq!(
+            config.get_queries_timeout_service("/add"),
+            Duration::from_secs(10)
+        );
+
+        // teleop
+        let config = config_with_profile(r#"{"profile": "teleop"}"#).unwrap();
+        assert_eq!(config.profile, Some(Profile::Teleop));
+        assert_eq!(
+            config.get_pub_priorities("/robot/cmd_vel"),
+            Some(Priority::RealTime)
+        );
+        assert_eq!(config.get_pub_priorities("/joy"), Some(Priority::RealTime));
+        assert_eq!(config.get_pub_priorities("/tf"), None);
+        assert!(!config.reliable_routes_blocking);
+        assert_eq!(
+            config.get_queries_timeout_service("/add"),
+            Duration::from_secs(1)
+        );
+        assert!(config.reliability.is_empty());
+
+        // no profile
+        let config = config_with_profile(r#"{}"#).unwrap();
+        assert_eq!(config.profile, None);
+        assert!(config.reliable_routes_blocking);
+
+        assert!(config_with_profile(r#"{"profile": "wan"}"#).is_err());
+        assert!(config_with_profile(r#"{"profile": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_profile_overrides() {
+        use std::time::Duration;
+
+        // explicit user fields win
+        let config = config_with_profile(
+            r#"{
+                "profile": "wan-lowbw",
+                "reliable_routes_blocking": true,
+                "pub_max_frequencies": ["/camera/image_raw=10"],
+                "queries_timeout": { "services": 3.0 }
+            }"#,
+        )
+        .unwrap();
+        assert!(config.reliable_routes_blocking);
+        assert_eq!(
+            config.get_pub_max_frequencies("/camera/image_raw"),
+            Some(10.0)
+        );
+        assert_eq!(config.get_pub_max_frequencies("/lidar/points"), None);
+        // objects are merged
+        assert_eq!(
+            config.get_queries_timeout_service("/add"),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            config.get_queries_timeout_tl_sub("/map"),
+            Duration::from_secs(10)
+        );
+        // other profile's fields are kept
+        assert!(config
+            .get_reliability_override("/camera/image_raw")
+            .is_some());
+
+        // the effective configuration (as reported in admin space) includes the profile's fields
+        let effective = serde_json::to_value(&config).unwrap();
+        assert_eq!(effective["profile"], "wan-lowbw");
+        assert_eq!(effective["reliable_routes_blocking"], true);
+        assert_eq!(
+            effective["pub_max_frequencies"],
+            serde_json::json!(["/camera/image_raw=10"])
+        );
+    }
+
+    #[test]
+    fn test_discovery_batch_window() {
+        use std::time::Duration;
+
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(
+            config.get_discovery_batch_window(),
+            Some(Duration::from_millis(50))
+        );
+        let config = serde_json::from_str::<Config>(r#"{"discovery_batch_window": 200}"#).unwrap();
+        assert_eq!(
+            config.get_discovery_batch_window(),
+            Some(Duration::from_millis(200))
+        );
+        let config = serde_json::from_str::<Config>(r#"{"discovery_batch_window": 0}"#).unwrap();
+        assert_eq!(config.get_discovery_batch_window(), None);
+        assert!(serde_json::from_str::<Config>(r#"{"discovery_batch_window": -1}"#).is_err());
+    }
+
+    #[test]
+    fn test_stats_rate_half_life() {
+        use std::time::Duration;
+
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_stats_rate_half_life(), Duration::from_secs(5));
+        let config = serde_json::from_str::<Config>(r#"{"stats_rate_half_life": 0.5}"#).unwrap();
+        assert_eq!(
+            config.get_stats_rate_half_life(),
+            Duration::from_millis(500)
+        );
+        // a null half-life would make the rates meaningless
+        let config = serde_json::from_str::<Config>(r#"{"stats_rate_half_life": 0}"#).unwrap();
+        assert_eq!(config.get_stats_rate_half_life(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_max_concurrent_route_creations() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.max_concurrent_route_creations, 16);
+        let config =
+            serde_json::from_str::<Config>(r#"{"max_concurrent_route_creations": 1}"#).unwrap();
+        assert_eq!(config.max_concurrent_route_creations, 1);
+        assert!(
+            serde_json::from_str::<Config>(r#"{"max_concurrent_route_creations": -1}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn test_topic_types() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_topic_type("/cmd_vel"), None);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"topic_types": {"/cmd_vel": "geometry_msgs/msg/Twist"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_topic_type("/cmd_vel"),
+            Some("geometry_msgs/msg/Twist")
+        );
+        // the topic name must match exactly
+        assert_eq!(config.get_topic_type("/cmd_vel_2"), None);
+
+        assert!(serde_json::from_str::<Config>(r#"{"topic_types": ["/cmd_vel"]}"#).is_err());
+    }
+
+    #[test]
+    fn test_sample_transforms() {
+        let config = serde_json::from_str::<Config>(
+            r#"{"sample_transforms": {"**/image_raw": "/opt/transforms/libdecimate.so"}}"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config
+                .sample_transforms
+                .get("**/image_raw")
+                .map(String::as_str),
+            Some("/opt/transforms/libdecimate.so")
+        );
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"sample_transforms": {"/camera/image_raw": "libdecimate.so"}}"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_raw_dds_topics() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(!config.is_raw_dds_topic("SensorRaw"));
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"raw_dds_topics": ["SensorRaw", "Status*", "*_debug", "Cam*/image*"]}"#,
+        )
+        .unwrap();
+        assert!(config.is_raw_dds_topic("SensorRaw"));
+        assert!(!config.is_raw_dds_topic("SensorRaw2"));
+        assert!(!config.is_raw_dds_topic("MySensorRaw"));
+        assert!(config.is_raw_dds_topic("Status"));
+        assert!(config.is_raw_dds_topic("StatusMotor"));
+        assert!(!config.is_raw_dds_topic("MotorStatus"));
+        assert!(config.is_raw_dds_topic("motor_debug"));
+        assert!(config.is_raw_dds_topic("Cam1/image_raw"));
+        assert!(!config.is_raw_dds_topic("Cam1/depth"));
+        // the ROS 2 topics are never raw
+        assert!(!config.is_raw_dds_topic("rt/Status"));
+        assert!(!config.is_raw_dds_topic("rt/x_debug"));
+
+        assert!(serde_json::from_str::<Config>(r#"{"raw_dds_topics": [""]}"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{"raw_dds_topics": ["*"]}"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{"raw_dds_topics": ["rt/*"]}"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{"raw_dds_topics": "Status"}"#).is_err());
+    }
+
+    #[test]
+    fn test_segment_separators() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.segment_separators.is_empty());
+        let config = serde_json::from_str::<Config>(r#"{"segment_separators": [".", "::"]}"#);
+        assert!(config.is_err());
+        let config =
+            serde_json::from_str::<Config>(r#"{"segment_separators": [".", ":"]}"#).unwrap();
+        assert_eq!(config.segment_separators, vec!['.', ':']);
+
+        for json in [
+            r#"["/"]"#,
+            r#"["*"]"#,
+            r#"["@"]"#,
+            r#"[" "]"#,
+            r#"[".", "."]"#,
+            r#"".""#,
+        ] {
+            assert!(
+                serde_json::from_str::<Config>(&format!(r#"{{"segment_separators": {json}}}"#))
+                    .is_err(),
+                "{json} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_glob_match() {
+        use super::glob_match;
+
+        assert!(glob_match("abc", "abc"));
+        assert!(!glob_match("abc", "abcd"));
+        assert!(glob_match("a*", "a"));
+        assert!(glob_match("a*c", "abbbc"));
+        assert!(!glob_match("a*c", "abbbcd"));
+        assert!(glob_match("a*b*c", "a_b_b_c"));
+        assert!(!glob_match("a*b*c", "a_c"));
+        // the suffix doesn't overlap the prefix
+        assert!(!glob_match("ab*ba", "aba"));
+        assert!(glob_match("ab*ba", "abba"));
+    }
+
+    #[test]
+    fn test_typed_key_exprs() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(!config.typed_key_exprs);
+        let config = serde_json::from_str::<Config>(r#"{"typed_key_exprs": true}"#).unwrap();
+        assert!(config.typed_key_exprs);
+        assert!(serde_json::from_str::<Config>(r#"{"typed_key_exprs": "yes"}"#).is_err());
+    }
+
+    #[test]
+    fn test_long_name_policy() {
+        use super::LongNamePolicy;
+
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.long_name_policy, LongNamePolicy::Reject);
+
+        let config = serde_json::from_str::<Config>(r#"{"long_name_policy": "hash"}"#).unwrap();
+        assert_eq!(config.long_name_policy, LongNamePolicy::Hash);
+
+        assert!(serde_json::from_str::<Config>(r#"{"long_name_policy": "truncate"}"#).is_err());
+        assert_eq!("hash".parse::<LongNamePolicy>(), Ok(LongNamePolicy::Hash));
+        assert_eq!(LongNamePolicy::Reject.to_string(), "reject");
+    }
+
+    #[test]
+    fn test_write_suppression() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.write_suppression);
+        let config = serde_json::from_str::<Config>(r#"{"write_suppression": false}"#).unwrap();
+        assert!(!config.write_suppression);
+    }
+
+    #[test]
+    fn test_forward_invalid_cdr() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(!config.forward_invalid_cdr);
+        let config = serde_json::from_str::<Config>(r#"{"forward_invalid_cdr": true}"#).unwrap();
+        assert!(config.forward_invalid_cdr);
+    }
+
+    #[test]
+    fn test_sequence_tracking() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(!config.sequence_tracking);
+        assert_eq!(config.sequence_gap_log_threshold, None);
+        let config = serde_json::from_str::<Config>(
+            r#"{"sequence_tracking": true, "sequence_gap_log_threshold": 5}"#,
+        )
+        .unwrap();
+        assert!(config.sequence_tracking);
+        assert_eq!(config.sequence_gap_log_threshold, Some(5));
+        assert!(serde_json::from_str::<Config>(r#"{"sequence_gap_log_threshold": -1}"#).is_err());
+    }
+
+    #[test]
+    fn test_propagate_deadline() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.propagate_deadline);
+        let config = serde_json::from_str::<Config>(r#"{"propagate_deadline": false}"#).unwrap();
+        assert!(!config.propagate_deadline);
+    }
+
+    #[test]
+    fn test_propagate_time_based_filter() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.propagate_time_based_filter);
+        let config =
+            serde_json::from_str::<Config>(r#"{"propagate_time_based_filter": false}"#).unwrap();
+        assert!(!config.propagate_time_based_filter);
+    }
+
+    #[test]
+    fn test_auto_adapt_qos() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(!config.auto_adapt_qos);
+        let config = serde_json::from_str::<Config>(r#"{"auto_adapt_qos": true}"#).unwrap();
+        assert!(config.auto_adapt_qos);
+    }
+
+    #[test]
+    fn test_route_to() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_route_to("/incident_report"), None);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"route_to": {"/incident_report": ["control_center", "0a0b0c"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_route_to("/incident_report"),
+            Some(&vec!["control_center".to_string(), "0a0b0c".to_string()])
+        );
+        assert_eq!(config.get_route_to("/chatter"), None);
+
+        // invalid values
+        assert!(
+            serde_json::from_str::<Config>(r#"{"route_to": {"/incident_report": []}}"#).is_err()
+        );
+        assert!(
+            serde_json::from_str::<Config>(r#"{"route_to": {"/incident_report": ["a/b"]}}"#)
+                .is_err()
+        );
+        assert!(
+            serde_json::from_str::<Config>(r#"{"route_to": {"/incident_report": ["*"]}}"#).is_err()
+        );
+        assert!(serde_json::from_str::<Config>(r#"{"route_to": ["control_center"]}"#).is_err());
+    }
+
+    #[test]
+    fn test_dds_partition() {
+        // not configured
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_dds_partition("/cmd_vel"), None);
+
+        // list of partitions for all topics
+        let config = serde_json::from_str::<Config>(r#"{"dds_partition": ["prod"]}"#).unwrap();
+        assert_eq!(
+            config.get_dds_partition("/cmd_vel"),
+            Some(&vec!["prod".to_string()])
+        );
+
+        // per-topic partitions, the first matching regex applies
+        let config = serde_json::from_str::<Config>(
+            r#"{"dds_partition": {"/diag/.*": ["diag", "prod"], "/test": "", ".*": "prod"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_dds_partition("/diag/cpu"),
+            Some(&vec!["diag".to_string(), "prod".to_string()])
+        );
+        assert_eq!(
+            config.get_dds_partition("/test"),
+            Some(&vec![String::new()])
+        );
+        assert_eq!(
+            config.get_dds_partition("/cmd_vel"),
+            Some(&vec!["prod".to_string()])
+        );
+
+        // invalid values
+        assert!(serde_json::from_str::<Config>(r#"{"dds_partition": "prod"}"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{"dds_partition": {"(": "prod"}}"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{"dds_partition": {".*": 1}}"#).is_err());
+    }
+
+    #[test]
+    fn test_congestion_control() {
+        use super::CongestionControlOverride;
+
+        let config = serde_json::from_str::<Config>(
+            r#"{
+                "qos_presets": {"command": {"congestion_control": "block"}},
+                "topic_qos": {
+                    "/cmd_vel": {"preset": "command"},
+                    "/camera/.*": {"congestion_control": "drop"},
+                    "/teleop": {"preset": "command", "congestion_control": "drop"},
+                    "/map": {"history": 1}
+                }
+            }"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.get_congestion_control("/cmd_vel"),
+            Some(CongestionControlOverride::Block)
+        );
+        assert_eq!(
+            config.get_congestion_control("/camera/image"),
+            Some(CongestionControlOverride::Drop)
+        );
+        assert_eq!(
+            config.get_congestion_control("/teleop"),
+            Some(CongestionControlOverride::Drop)
+        );
+        assert_eq!(config.get_congestion_control("/map"), None);
+        assert_eq!(config.get_congestion_control("/tf"), None);
+
+        assert!(serde_json::from_str::<Config>(
+            r#"{"topic_qos": {"/scan": {"congestion_control": "wait"}}}"#
+        )
+        .is_err());
+    }
 
-fn default_nodename() -> OwnedKeyExpr {
-    unsafe { OwnedKeyExpr::from_string_unchecked(DEFAULT_NODENAME.into()) }
-}
+    #[test]
+    fn test_topic_qos() {
+        use super::{DurabilityOverride, HistoryOverride, ReliabilityOverride, TopicQos};
+        use std::time::Duration;
+        use zenoh::prelude::Priority;
 
-fn default_domain() -> u32 {
-    if let Ok(s) = env::var("ROS_DOMAIN_ID") {
-        s.parse::<u32>().unwrap_or(DEFAULT_DOMAIN)
-    } else {
-        DEFAULT_DOMAIN
+        // not configured
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.get_topic_qos("/cmd_vel"), None);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{
+                "qos_presets": {
+                    "sensor": {"reliability": "best_effort", "durability": "volatile", "history": 5,
+                               "zenoh_reliability": "best_effort", "zenoh_priority": 5},
+                    "command": {"reliability": "reliable", "history": 1, "deadline": 0.1,
+                                "lifespan": 0.5, "zenoh_priority": 1}
+                },
+                "topic_qos": {
+                    "/cmd_vel": {"preset": "command"},
+                    ".*/scan": {"preset": "sensor", "history": "keep_all", "durability": "transient_local"},
+                    "/map": {"durability": "transient_local", "history": 1}
+                },
+                "reliability": [".*=best_effort"],
+                "pub_priorities": [".*=7"]
+            }"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+
+        // the preset's fields
+        let cmd_vel = config.get_topic_qos("/cmd_vel").unwrap();
+        assert_eq!(
+            cmd_vel,
+            TopicQos {
+                reliability: Some(ReliabilityOverride::Reliable),
+                history: Some(HistoryOverride::KeepLast(1)),
+                deadline: Some(0.1),
+                lifespan: Some(0.5),
+                zenoh_priority: Some(Priority::RealTime),
+                ..Default::default()
+            }
+        );
+        assert_eq!(cmd_vel.get_deadline(), Some(Duration::from_millis(100)));
+        assert_eq!(cmd_vel.get_lifespan(), Some(Duration::from_millis(500)));
+
+        // the topic's fields override the preset's ones
+        let scan = config.get_topic_qos("/robot/scan").unwrap();
+        assert_eq!(scan.preset, None);
+        assert_eq!(scan.reliability, Some(ReliabilityOverride::BestEffort));
+        assert_eq!(scan.durability, Some(DurabilityOverride::TransientLocal));
+        assert_eq!(scan.history, Some(HistoryOverride::KeepAll));
+        assert_eq!(
+            scan.zenoh_reliability,
+            Some(ReliabilityOverride::BestEffort)
+        );
+        assert_eq!(scan.get_deadline(), None);
+
+        // without preset
+        let map = config.get_topic_qos("/map").unwrap();
+        assert_eq!(map.history, Some(HistoryOverride::KeepLast(1)));
+        assert_eq!(map.reliability, None);
+        assert_eq!(config.get_topic_qos("/tf"), None);
+
+        // precedence over the other options configuring the same QoS
+        assert_eq!(
+            config.get_reliability_override("/cmd_vel"),
+            Some(ReliabilityOverride::Reliable)
+        );
+        assert_eq!(
+            config.get_reliability_override("/map"),
+            Some(ReliabilityOverride::BestEffort)
+        );
+        assert_eq!(
+            config.get_pub_priorities("/cmd_vel"),
+            Some(Priority::RealTime)
+        );
+        assert_eq!(
+            config.get_pub_priorities("/robot/scan"),
+            Some(Priority::Data)
+        );
+        assert_eq!(config.get_pub_priorities("/tf"), Some(Priority::Background));
+        assert!(!config.is_zenoh_reliable("/robot/scan", true));
+        assert!(config.is_zenoh_reliable("/cmd_vel", true));
+        assert!(config.is_transient_local_forced("/robot/scan"));
+        assert!(config.is_transient_local_forced("/map"));
+        assert!(!config.is_transient_local_forced("/cmd_vel"));
+
+        // an undefined preset is rejected, as a preset referencing another one
+        let config = serde_json::from_str::<Config>(
+            r#"{"qos_presets": {"sensor": {"history": 5}}, "topic_qos": {"/scan": {"preset": "sensors"}}}"#,
+        )
+        .unwrap();
+        let err = config.validate().unwrap_err();
+        assert!(err.contains(r#"undefined preset "sensors""#), "{err}");
+        let config = serde_json::from_str::<Config>(
+            r#"{"qos_presets": {"sensor": {"history": 5}, "lidar": {"preset": "sensor"}}}"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_err());
+        let config =
+            serde_json::from_str::<Config>(r#"{"topic_qos": {"/scan": {"preset": "sensor"}}}"#)
+                .unwrap();
+        assert!(config.validate().is_err());
+
+        // invalid values
+        for json in [
+            r#"{"topic_qos": ["/scan"]}"#,
+            r#"{"topic_qos": {"(": {"history": 1}}}"#,
+            r#"{"topic_qos": {"/scan": {"histori": 1}}}"#,
+            r#"{"topic_qos": {"/scan": {"history": 0}}}"#,
+            r#"{"topic_qos": {"/scan": {"history": -1}}}"#,
+            r#"{"topic_qos": {"/scan": {"history": "keep_last"}}}"#,
+            r#"{"topic_qos": {"/scan": {"durability": "transient"}}}"#,
+            r#"{"topic_qos": {"/scan": {"deadline": 0}}}"#,
+            r#"{"topic_qos": {"/scan": {"lifespan": -1.0}}}"#,
+            r#"{"topic_qos": {"/scan": {"zenoh_priority": 8}}}"#,
+            r#"{"qos_presets": {"sensor": {"reliability": "reliabel"}}}"#,
+        ] {
+            assert!(serde_json::from_str::<Config>(json).is_err(), "{json}");
+        }
     }
-}
 
-fn default_queries_timeout() -> f32 {
-    DEFAULT_QUERIES_TIMEOUT
-}
+    #[test]
+    fn test_keep_all_max_depth() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.keep_all_max_depth, None);
+        let config = serde_json::from_str::<Config>(r#"{"keep_all_max_depth": 1000}"#).unwrap();
+        assert_eq!(config.keep_all_max_depth, Some(1000));
+        assert!(serde_json::from_str::<Config>(r#"{"keep_all_max_depth": -1}"#).is_err());
+    }
 
-fn deserialize_path<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    deserializer.deserialize_option(OptPathVisitor)
-}
+    #[test]
+    fn test_on_deadline_miss() {
+        use super::DeadlineMissPolicy;
 
-struct OptPathVisitor;
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_deadline_miss_policy("/cmd_vel"), None);
 
-impl<'de> serde::de::Visitor<'de> for OptPathVisitor {
-    type Value = Option<Vec<String>>;
+        let config = serde_json::from_str::<Config>(r#"{"on_deadline_miss": "log"}"#).unwrap();
+        assert_eq!(
+            config.get_deadline_miss_policy("/cmd_vel"),
+            Some(DeadlineMissPolicy::Log)
+        );
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(formatter, "none or a string or an array of strings")
+        let config = serde_json::from_str::<Config>(
+            r#"{"on_deadline_miss": {"/cmd_vel": "teardown", "/heartbeat": "republish_last", ".*": "none"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_deadline_miss_policy("/cmd_vel"),
+            Some(DeadlineMissPolicy::Teardown)
+        );
+        assert_eq!(
+            config.get_deadline_miss_policy("/heartbeat"),
+            Some(DeadlineMissPolicy::RepublishLast)
+        );
+        assert_eq!(
+            config.get_deadline_miss_policy("/scan"),
+            Some(DeadlineMissPolicy::None)
+        );
+        assert_eq!(
+            serde_json::to_value(&config).unwrap()["on_deadline_miss"],
+            serde_json::json!(["/cmd_vel=teardown", "/heartbeat=republish_last", ".*=none"])
+        );
+
+        assert!(serde_json::from_str::<Config>(r#"{"on_deadline_miss": "restart"}"#).is_err());
     }
 
-    fn visit_none<E>(self) -> Result<Self::Value, E>
-    where
-        E: de::Error,
-    {
-        Ok(None)
+    #[test]
+    fn test_rate_limit() {
+        use super::RateLimitPolicy;
+
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_sub_max_frequencies("/cmd_vel"), None);
+        assert_eq!(
+            config.get_rate_limit_policy("/cmd_vel"),
+            RateLimitPolicy::Periodic
+        );
+
+        let config = serde_json::from_str::<Config>(
+            r#"{
+                "sub_max_frequencies": ["/mcu/.*=10", "/cmd_vel=20.5"],
+                "rate_limit_policy": {"/cmd_vel": "drop_excess"}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.get_sub_max_frequencies("/mcu/imu"), Some(10.0));
+        assert_eq!(config.get_sub_max_frequencies("/cmd_vel"), Some(20.5));
+        assert_eq!(config.get_sub_max_frequencies("/tf"), None);
+        assert_eq!(config.get_pub_max_frequencies("/mcu/imu"), None);
+        assert_eq!(
+            config.get_rate_limit_policy("/cmd_vel"),
+            RateLimitPolicy::DropExcess
+        );
+        assert_eq!(
+            config.get_rate_limit_policy("/mcu/imu"),
+            RateLimitPolicy::Periodic
+        );
+        assert_eq!(
+            serde_json::to_value(&config).unwrap()["rate_limit_policy"],
+            serde_json::json!(["/cmd_vel=drop_excess"])
+        );
+
+        assert!(serde_json::from_str::<Config>(r#"{"rate_limit_policy": "keep_all"}"#).is_err());
     }
 
-    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        deserializer.deserialize_any(PathVisitor).map(Some)
+    #[test]
+    fn test_feedback_max_frequency() {
+        let config = serde_json::from_str::<Config>(
+            r#"{"feedback_max_frequency": ["/navigate_.*=2", ".*=10.5"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_feedback_max_frequency("/navigate_to_pose/_action/feedback"),
+            Some(2.0)
+        );
+        assert_eq!(
+            config.get_feedback_max_frequency("/dock/_action/feedback"),
+            Some(10.5)
+        );
+        // only the feedback topics of the Actions are throttled
+        assert_eq!(
+            config.get_feedback_max_frequency("/navigate_to_pose/_action/status"),
+            None
+        );
+        assert_eq!(config.get_feedback_max_frequency("/navigate_to_pose"), None);
+        assert_eq!(config.get_feedback_max_frequency("/feedback"), None);
+        assert_eq!(
+            config.get_pub_max_frequencies("/navigate_to_pose/_action/feedback"),
+            None
+        );
     }
-}
 
-struct PathVisitor;
+    #[test]
+    fn test_service_retries() {
+        use super::ServiceRetryPolicy;
+        use std::time::Duration;
 
-impl<'de> serde::de::Visitor<'de> for PathVisitor {
-    type Value = Vec<String>;
+        // never retrying by default
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_service_retry_policy("/add_two_ints"), None);
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(formatter, "a string or an array of strings")
+        let config = serde_json::from_str::<Config>(
+            r#"{"service_retries": {".*/add_two_ints": {"retries": 3, "retry_backoff": 0.2}, "/get_state": {"retries": 1}}}"#,
+        )
+        .unwrap();
+        let policy = config
+            .get_service_retry_policy("/robot/add_two_ints")
+            .unwrap();
+        assert_eq!(policy.retries, 3);
+        assert_eq!(policy.get_retry_backoff(0), Duration::from_millis(200));
+        assert_eq!(policy.get_retry_backoff(2), Duration::from_millis(800));
+        assert_eq!(
+            config.get_service_retry_policy("/get_state"),
+            Some(&ServiceRetryPolicy {
+                retries: 1,
+                retry_backoff: 0.1
+            })
+        );
+        assert_eq!(config.get_service_retry_policy("/set_state"), None);
+
+        assert!(
+            serde_json::from_str::<Config>(r#"{"service_retries": {"/get_state": {}}}"#).is_err()
+        );
+        assert!(serde_json::from_str::<Config>(
+            r#"{"service_retries": {"/get_state": {"retries": 1, "retry_backoff": 0}}}"#
+        )
+        .is_err());
     }
 
-    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
-    where
-        E: de::Error,
-    {
-        Ok(vec![v.into()])
+    #[test]
+    fn test_on_service_unavailable() {
+        use super::ServiceUnavailablePolicy;
+
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_service_unavailable_policy("/get_state"), None);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"on_service_unavailable": {".*/get_state": "error_reply", "/load_map": "drop"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_service_unavailable_policy("/robot/get_state"),
+            Some(ServiceUnavailablePolicy::ErrorReply)
+        );
+        assert_eq!(
+            config.get_service_unavailable_policy("/load_map"),
+            Some(ServiceUnavailablePolicy::Drop)
+        );
+        assert_eq!(config.get_service_unavailable_policy("/add_two_ints"), None);
+        assert_eq!(
+            serde_json::to_value(&config).unwrap()["on_service_unavailable"],
+            serde_json::json!([".*/get_state=error_reply", "/load_map=drop"])
+        );
+
+        assert!(serde_json::from_str::<Config>(
+            r#"{"on_service_unavailable": ["/get_state=wait"]}"#
+        )
+        .is_err());
     }
 
-    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-    where
-        A: de::SeqAccess<'de>,
-    {
-        let mut v = if let Some(l) = seq.size_hint() {
-            Vec::with_capacity(l)
-        } else {
-            Vec::new()
-        };
-        while let Some(s) = seq.next_element()? {
-            v.push(s);
-        }
-        Ok(v)
+    #[test]
+    fn test_long_calls() {
+        use std::time::Duration;
+
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_long_call_duration("/merge_maps"), None);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"long_calls": {".*/merge_maps": 300, "/disabled": 0}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_long_call_duration("/robot/merge_maps"),
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(config.get_long_call_duration("/disabled"), None);
+        assert_eq!(config.get_long_call_duration("/get_state"), None);
+
+        let config =
+            serde_json::from_str::<Config>(r#"{"long_calls": ["/merge_maps=12.5"]}"#).unwrap();
+        assert_eq!(
+            config.get_long_call_duration("/merge_maps"),
+            Some(Duration::from_millis(12500))
+        );
     }
-}
 
-fn default_reliable_routes_blocking() -> bool {
-    DEFAULT_RELIABLE_ROUTES_BLOCKING
-}
+    #[test]
+    fn test_max_concurrent_requests() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_max_concurrent_requests("/merge_maps"), None);
 
-fn default_localhost_only() -> bool {
-    env::var("ROS_LOCALHOST_ONLY").as_deref() == Ok("1")
-}
+        let config = serde_json::from_str::<Config>(
+            r#"{"max_concurrent_requests": {"/merge_maps": 1, "/unbounded": 0, ".*": 8}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.get_max_concurrent_requests("/merge_maps"), Some(1));
+        assert_eq!(config.get_max_concurrent_requests("/unbounded"), None);
+        assert_eq!(config.get_max_concurrent_requests("/get_state"), Some(8));
 
-fn default_transient_local_cache_multiplier() -> usize {
-    DEFAULT_TRANSIENT_LOCAL_CACHE_MULTIPLIER
-}
+        let config =
+            serde_json::from_str::<Config>(r#"{"max_concurrent_requests": [".*/plan=2"]}"#)
+                .unwrap();
+        assert_eq!(config.get_max_concurrent_requests("/robot/plan"), Some(2));
+        assert!(
+            serde_json::from_str::<Config>(r#"{"max_concurrent_requests": {"/a": -1}}"#).is_err()
+        );
+    }
 
-fn serialize_regex<S>(r: &Option<Regex>, serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    match r {
-        Some(ex) => serializer.serialize_some(ex.as_str()),
-        None => serializer.serialize_none(),
+    #[test]
+    fn test_heavy_services() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.heavy_services.is_none());
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"heavy_services": {"services": [".*/get_map", "/snapshot"], "reply_size": 1000000}}"#,
+        )
+        .unwrap();
+        let heavy = config.heavy_services.unwrap();
+        assert!(heavy.is_heavy_service("/robot/get_map"));
+        assert!(heavy.is_heavy_service("/snapshot"));
+        assert!(!heavy.is_heavy_service("/get_state"));
+        assert_eq!(heavy.reply_size, Some(1000000));
+        assert_eq!(heavy.get_workers(), DEFAULT_HEAVY_SERVICES_WORKERS);
+        assert_eq!(heavy.get_queue_size(), DEFAULT_HEAVY_SERVICES_QUEUE_SIZE);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"heavy_services": {"reply_size": 65536, "workers": 0, "queue_size": 8}}"#,
+        )
+        .unwrap();
+        let heavy = config.heavy_services.unwrap();
+        assert!(!heavy.is_heavy_service("/robot/get_map"));
+        assert_eq!(heavy.get_workers(), 1);
+        assert_eq!(heavy.get_queue_size(), 8);
+        assert!(serde_json::from_str::<Config>(r#"{"heavy_services": {"threads": 2}}"#).is_err());
     }
-}
 
-fn deserialize_regex<'de, D>(deserializer: D) -> Result<Option<Regex>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    deserializer.deserialize_any(RegexVisitor)
-}
+    #[test]
+    fn test_batching() {
+        use super::BatchingConf;
+        use std::time::Duration;
 
-// Serde Visitor for Regex deserialization.
-// It accepts either a String, either a list of Strings (that are concatenated with `|`)
-struct RegexVisitor;
+        // never batching by default
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_batching("/joint_states"), None);
 
-impl<'de> Visitor<'de> for RegexVisitor {
-    type Value = Option<Regex>;
+        let config = serde_json::from_str::<Config>(
+            r#"{
+                "batching": {
+                    ".*/joint_states": {"max_batch_delay": 0.002, "max_batch_size": 4096},
+                    "/diagnostics": {}
+                }
+            }"#,
+        )
+        .unwrap();
+        let joint_states = config.get_batching("/robot/joint_states").unwrap();
+        assert_eq!(joint_states.get_max_batch_delay(), Duration::from_millis(2));
+        assert_eq!(joint_states.max_batch_size, 4096);
+        assert_eq!(
+            config.get_batching("/diagnostics"),
+            Some(&BatchingConf {
+                max_batch_delay: 0.005,
+                max_batch_size: 8192
+            })
+        );
+        assert_eq!(config.get_batching("/cmd_vel"), None);
+        assert_eq!(
+            serde_json::to_value(&config).unwrap()["batching"][".*/joint_states"],
+            serde_json::json!({"max_batch_delay": 0.002, "max_batch_size": 4096})
+        );
 
-    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str(r#"either a string or a list of strings"#)
+        assert!(
+            serde_json::from_str::<Config>(r#"{"batching": {"/tf": {"max_batch_delay": 0}}}"#)
+                .is_err()
+        );
+        assert!(
+            serde_json::from_str::<Config>(r#"{"batching": {"/tf": {"max_delay": 0.1}}}"#).is_err()
+        );
     }
 
-    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
-    where
-        E: de::Error,
-    {
-        Regex::new(&format!("^{value}$"))
-            .map(Some)
-            .map_err(|e| de::Error::custom(format!("Invalid regex '{value}': {e}")))
+    #[test]
+    fn test_compression() {
+        use super::Compression;
+
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_compression("/points"), Compression::None);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"compression": [".*/points=zstd", "/camera/.*=lz4", "/camera/info=none"]}"#,
+        )
+        .unwrap();
+        assert_eq!(config.get_compression("/lidar/points"), Compression::Zstd);
+        assert_eq!(config.get_compression("/camera/image"), Compression::Lz4);
+        assert_eq!(config.get_compression("/tf"), Compression::None);
+        assert_eq!(
+            serde_json::to_value(&config).unwrap()["compression"],
+            serde_json::json!([".*/points=zstd", "/camera/.*=lz4", "/camera/info=none"])
+        );
+
+        assert!(serde_json::from_str::<Config>(r#"{"compression": ["/points=gzip"]}"#).is_err());
     }
 
-    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-    where
-        A: de::SeqAccess<'de>,
-    {
-        let mut vec: Vec<String> = Vec::new();
-        while let Some(s) = seq.next_element::<String>()? {
-            vec.push(format!("^{s}$"));
-        }
-        if vec.is_empty() {
-            return Ok(None);
-        };
+    #[test]
+    fn test_service_priorities() {
+        use zenoh::prelude::Priority;
 
-        let s: String = vec.join("|");
-        Regex::new(&s)
-            .map(Some)
-            .map_err(|e| de::Error::custom(format!("Invalid regex '{s}': {e}")))
+        let config = serde_json::from_str::<Config>(
+            r#"{
+                "pub_priorities": ["/estop=1", "/navigate_to_pose=2", ".*/camera/.*=6"],
+                "topic_qos": {"/set_mode": {"zenoh_priority": 3}}
+            }"#,
+        )
+        .unwrap();
+        // the same table applies to Services and Actions
+        assert_eq!(
+            config.get_pub_priorities("/estop"),
+            Some(Priority::RealTime)
+        );
+        assert_eq!(
+            config.get_pub_priorities("/navigate_to_pose"),
+            Some(Priority::InteractiveHigh)
+        );
+        assert_eq!(
+            config.get_pub_priorities("/set_mode"),
+            Some(Priority::InteractiveLow)
+        );
+        assert_eq!(
+            config.get_pub_priorities("/robot/camera/set_info"),
+            Some(Priority::DataLow)
+        );
+        // the default priority for the others
+        assert_eq!(
+            config
+                .get_pub_priorities("/add_two_ints")
+                .unwrap_or_default(),
+            Priority::Data
+        );
     }
-}
 
-fn deserialize_vec_regex_f32<'de, D>(deserializer: D) -> Result<Vec<(Regex, f32)>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum AcceptedValues {
-        Float(f32),
-        List(Vec<String>),
-    }
-
-    let values: AcceptedValues = Deserialize::deserialize(deserializer).unwrap();
-    match values {
-        AcceptedValues::Float(f) => {
-            // same float for any string (i.e. matching ".*")
-            Ok(vec![(Regex::new(".*").unwrap(), f)])
-        }
-        AcceptedValues::List(strs) => {
-            let mut result: Vec<(Regex, f32)> = Vec::with_capacity(strs.len());
-            for s in strs {
-                let i = s.find('=').ok_or_else(|| {
-                    de::Error::custom(format!(
-                        r#"Invalid list of "<regex>=<float>" elements": {s}"#
-                    ))
-                })?;
-                let regex = Regex::new(&s[0..i])
-                    .map_err(|e| de::Error::custom(format!("Invalid regex in '{s}': {e}")))?;
-                let frequency: f32 = s[i + 1..]
-                    .parse()
-                    .map_err(|e| de::Error::custom(format!("Invalid float value in '{s}': {e}")))?;
-                result.push((regex, frequency));
-            }
-            Ok(result)
-        }
-    }
-}
+    #[test]
+    fn test_max_bandwidth() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_max_bandwidth(), None);
+        assert_eq!(config.get_bandwidth_shedding_priority(), Priority::DataHigh);
 
-fn serialize_vec_regex_f32<S>(v: &Vec<(Regex, f32)>, serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    let mut seq = serializer.serialize_seq(Some(v.len()))?;
-    for (r, f) in v {
-        let s = format!("{}={}", r.as_str(), f);
-        seq.serialize_element(&s)?;
+        let config = serde_json::from_str::<Config>(
+            r#"{"max_bandwidth": 625000, "bandwidth_shedding_priority": 2}"#,
+        )
+        .unwrap();
+        assert_eq!(config.get_max_bandwidth(), Some(625000));
+        assert_eq!(
+            config.get_bandwidth_shedding_priority(),
+            Priority::InteractiveHigh
+        );
+        let config = serde_json::from_str::<Config>(r#"{"max_bandwidth": 0}"#).unwrap();
+        assert_eq!(config.get_max_bandwidth(), None);
+        assert!(serde_json::from_str::<Config>(r#"{"bandwidth_shedding_priority": 9}"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{"max_bandwidth": -1}"#).is_err());
     }
-    seq.end()
-}
 
-fn deserialize_vec_regex_prio<'de, D>(deserializer: D) -> Result<Vec<(Regex, Priority)>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let strs: Vec<String> = Deserialize::deserialize(deserializer).unwrap();
-    let mut result: Vec<(Regex, Priority)> = Vec::with_capacity(strs.len());
-    for s in strs {
-        let i = s.find('=').ok_or_else(|| {
-            de::Error::custom(format!(r#"Invalid list of "<regex>=<int>" elements": {s}"#))
-        })?;
-        let regex = Regex::new(&s[0..i])
-            .map_err(|e| de::Error::custom(format!("Invalid regex in '{s}': {e}")))?;
-        let i: u8 = s[i + 1..].parse().map_err(|e| {
-            de::Error::custom(format!("Invalid priority (not an integer) in '{s}': {e}"))
-        })?;
-        let priority = Priority::try_from(i)
-            .map_err(|e| de::Error::custom(format!("Invalid priority in '{s}': {e}")))?;
-        result.push((regex, priority));
+    #[test]
+    fn test_max_payload_size() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_max_payload_size("/scan"), None);
+
+        // global value
+        let config = serde_json::from_str::<Config>(r#"{"max_payload_size": 1000000}"#).unwrap();
+        assert_eq!(config.get_max_payload_size("/scan"), Some(1000000));
+
+        // overriden per topic
+        let config = serde_json::from_str::<Config>(
+            r#"{"max_payload_size": ["/camera/.*=50000000", "/map=0", ".*=1000000"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_max_payload_size("/camera/image_raw"),
+            Some(50000000)
+        );
+        assert_eq!(config.get_max_payload_size("/map"), Some(0));
+        assert_eq!(config.get_max_payload_size("/scan"), Some(1000000));
+
+        assert!(serde_json::from_str::<Config>(r#"{"max_payload_size": -1}"#).is_err());
     }
-    Ok(result)
-}
 
-fn serialize_vec_regex_prio<S>(v: &Vec<(Regex, Priority)>, serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    let mut seq = serializer.serialize_seq(Some(v.len()))?;
-    for (r, p) in v {
-        let s = format!("{}={}", r.as_str(), *p as u8);
-        seq.serialize_element(&s)?;
+    #[test]
+    fn test_instance_key_exprs() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(!config.is_instance_keyed("/fleet_status"));
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"instance_key_exprs": ["/fleet_status", "/robots/.*"]}"#,
+        )
+        .unwrap();
+        assert!(config.is_instance_keyed("/fleet_status"));
+        assert!(config.is_instance_keyed("/robots/status"));
+        assert!(!config.is_instance_keyed("/fleet_status_old"));
+        assert!(!config.is_instance_keyed("/cmd_vel"));
+
+        assert!(serde_json::from_str::<Config>(r#"{"instance_key_exprs": ["/a("]}"#).is_err());
     }
-    seq.end()
-}
 
-pub fn serialize_duration_as_f32<S>(d: &Duration, serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    serializer.serialize_f32(d.as_secs_f32())
-}
+    #[test]
+    fn test_latched_aggregate_topics() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(config.is_latched_aggregate("/tf_static"));
+        assert!(!config.is_latched_aggregate("/tf"));
+        assert!(!config.is_latched_aggregate("/robot1/tf_static"));
 
-#[cfg(test)]
-mod tests {
-    use super::Config;
+        let config = serde_json::from_str::<Config>(
+            r#"{"latched_aggregate_topics": [".*/tf_static", "/markers"]}"#,
+        )
+        .unwrap();
+        assert!(config.is_latched_aggregate("/robot1/tf_static"));
+        assert!(config.is_latched_aggregate("/markers"));
+        assert!(!config.is_latched_aggregate("/markers_array"));
+
+        let config = serde_json::from_str::<Config>(r#"{"latched_aggregate_topics": []}"#).unwrap();
+        assert!(!config.is_latched_aggregate("/tf_static"));
+    }
 
     #[test]
-    fn test_allowance() {
-        use super::*;
+    fn test_force_transient_local() {
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert!(!config.is_transient_local_forced("/tf_static"));
 
-        let allow: Allowance = serde_json::from_str(
-            r#"{
-                "allow": {
-                    "publishers": ["/tf", ".*/pose"],
-                    "subscribers": [],
-                    "service_servers": [".*"],
-                    "action_servers": [".*/rotate_absolute"],
-                    "action_clients": [ "" ]
-                }
-            }"#,
+        let config = serde_json::from_str::<Config>(
+            r#"{"force_transient_local": ["/tf_static", "/robot_description"]}"#,
         )
         .unwrap();
-        println!("allow: {}", serde_json::to_string(&allow).unwrap());
+        assert!(config.is_transient_local_forced("/tf_static"));
+        assert!(config.is_transient_local_forced("/robot_description"));
+        // the full name must match
+        assert!(!config.is_transient_local_forced("/tf"));
+        assert!(!config.is_transient_local_forced("/tf_static_2"));
 
-        assert!(matches!(
-            allow,
-            Allowance::Allow(ROS2InterfacesRegex {
-                publishers: Some(_),
-                subscribers: None,
-                service_servers: Some(_),
-                service_clients: None,
-                action_servers: Some(_),
-                action_clients: Some(_),
-            })
-        ));
+        let config =
+            serde_json::from_str::<Config>(r#"{"force_transient_local": "/maps/.*"}"#).unwrap();
+        assert!(config.is_transient_local_forced("/maps/level1"));
+        assert!(!config.is_transient_local_forced("/map"));
 
-        assert!(allow.is_publisher_allowed("/tf"));
-        assert!(allow.is_publisher_allowed("/x/y/pose"));
-        assert!(!allow.is_publisher_allowed("/abc/rotate_absolute"));
-        assert!(!allow.is_publisher_allowed("/cmd_vel"));
-        assert!(!allow.is_service_cli_allowed("/some_pseudo_random_name"));
+        assert!(serde_json::from_str::<Config>(r#"{"force_transient_local": ["/tf("]}"#).is_err());
+    }
 
-        assert!(!allow.is_subscriber_allowed("/tf"));
-        assert!(!allow.is_subscriber_allowed("/x/y/pose"));
-        assert!(!allow.is_publisher_allowed("/abc/rotate_absolute"));
-        assert!(!allow.is_subscriber_allowed("/cmd_vel"));
-        assert!(!allow.is_service_cli_allowed("/some_pseudo_random_name"));
+    #[test]
+    fn test_routing_mode() {
+        use super::RoutingMode;
 
-        assert!(allow.is_service_srv_allowed("/tf"));
-        assert!(allow.is_service_srv_allowed("/x/y/pose"));
-        assert!(allow.is_service_srv_allowed("/abc/rotate_absolute"));
-        assert!(allow.is_service_srv_allowed("/cmd_vel"));
-        assert!(allow.is_service_srv_allowed("/some_pseudo_random_name"));
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.mode, RoutingMode::LocalRouting);
+        let config = serde_json::from_str::<Config>(r#"{"mode": "local_routing"}"#).unwrap();
+        assert_eq!(config.mode, RoutingMode::LocalRouting);
+        let config = serde_json::from_str::<Config>(r#"{"mode": "forward_discovery"}"#).unwrap();
+        assert_eq!(config.mode, RoutingMode::ForwardDiscovery);
+        assert_eq!(
+            serde_json::to_value(&config).unwrap()["mode"],
+            serde_json::json!("forward_discovery")
+        );
+        assert!(serde_json::from_str::<Config>(r#"{"mode": "forward"}"#).is_err());
+    }
 
-        assert!(!allow.is_service_cli_allowed("/tf"));
-        assert!(!allow.is_service_cli_allowed("/x/y/pose"));
-        assert!(!allow.is_service_cli_allowed("/abc/rotate_absolute"));
-        assert!(!allow.is_service_cli_allowed("/cmd_vel"));
-        assert!(!allow.is_service_cli_allowed("/some_pseudo_random_name"));
+    #[test]
+    fn test_transient_local_cache() {
+        // not configured
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(config.get_transient_local_cache_history("/map"), None);
+        assert_eq!(config.get_transient_local_cache_max_history("/map"), None);
+        assert_eq!(config.get_transient_local_cache_max_bytes("/map"), None);
 
-        assert!(!allow.is_action_srv_allowed("/tf"));
-        assert!(!allow.is_action_srv_allowed("/x/y/pose"));
-        assert!(allow.is_action_srv_allowed("/abc/rotate_absolute"));
-        assert!(!allow.is_action_srv_allowed("/cmd_vel"));
-        assert!(!allow.is_action_srv_allowed("/some_pseudo_random_name"));
+        // single values
+        let config = serde_json::from_str::<Config>(
+            r#"{"transient_local_cache": {"history": 5, "max_history": 100, "max_bytes": 1000}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.get_transient_local_cache_history("/map"), Some(5));
+        assert_eq!(
+            config.get_transient_local_cache_max_history("/map"),
+            Some(100)
+        );
+        assert_eq!(
+            config.get_transient_local_cache_max_bytes("/map"),
+            Some(1000)
+        );
 
-        assert!(!allow.is_action_cli_allowed("/tf"));
-        assert!(!allow.is_action_cli_allowed("/x/y/pose"));
-        assert!(!allow.is_action_cli_allowed("/abc/rotate_absolute"));
-        assert!(!allow.is_action_cli_allowed("/cmd_vel"));
-        assert!(!allow.is_action_cli_allowed("/some_pseudo_random_name"));
+        // list and map syntaxes
+        let config = serde_json::from_str::<Config>(
+            r#"{"transient_local_cache": {
+                "history": ["/map=1", ".*=10"],
+                "max_history": {"/tf_static": 1000},
+                "max_bytes": {"/map": 50000000}
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_transient_local_cache_max_history("/tf_static"),
+            Some(1000)
+        );
+        assert_eq!(config.get_transient_local_cache_max_history("/map"), None);
+        assert_eq!(config.get_transient_local_cache_history("/map"), Some(1));
+        assert_eq!(
+            config.get_transient_local_cache_history("/tf_static"),
+            Some(10)
+        );
+        assert_eq!(
+            config.get_transient_local_cache_max_bytes("/map"),
+            Some(50000000)
+        );
+        assert_eq!(
+            config.get_transient_local_cache_max_bytes("/tf_static"),
+            None
+        );
 
-        let deny: Allowance = serde_json::from_str(
-            r#"{
-                "deny": {
-                    "publishers": ["/tf", ".*/pose"],
-                    "subscribers": [],
-                    "service_servers": [".*"],
-                    "action_servers": [".*/rotate_absolute"],
-                    "action_clients": [ "" ]
-                }
-            }"#,
+        // 0 means no caching, even if the DDS Writer is TRANSIENT_LOCAL
+        let config = serde_json::from_str::<Config>(
+            r#"{"transient_local_cache": {"history": {"/map": 1, "/robot_description": 0}}}"#,
         )
         .unwrap();
-        println!("deny: {}", serde_json::to_string(&allow).unwrap());
+        assert_eq!(
+            config.get_transient_local_cache_history("/robot_description"),
+            Some(0)
+        );
+        assert_eq!(config.get_transient_local_cache_history("/map"), Some(1));
+        assert_eq!(config.get_transient_local_cache_history("/tf_static"), None);
 
-        assert!(matches!(
-            deny,
-            Allowance::Deny(ROS2InterfacesRegex {
-                publishers: Some(_),
-                subscribers: None,
-                service_servers: Some(_),
-                service_clients: None,
-                action_servers: Some(_),
-                action_clients: Some(_),
+        // invalid values
+        assert!(
+            serde_json::from_str::<Config>(r#"{"transient_local_cache": {"history": -1}}"#)
+                .is_err()
+        );
+        assert!(serde_json::from_str::<Config>(
+            r#"{"transient_local_cache": {"history": ["/map=abc"]}}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<Config>(
+            r#"{"transient_local_cache": {"max_history": -1}}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        use serde_json::json;
+
+        // serialize -> deserialize -> serialize must give the same JSON (including the default values)
+        let round_trip = |json: serde_json::Value| {
+            let config: Config = serde_json::from_value(json).unwrap();
+            let serialized = serde_json::to_value(&config).unwrap();
+            let config2: Config = serde_json::from_value(serialized.clone())
+                .unwrap_or_else(|e| panic!("Failed to deserialize {serialized}: {e}"));
+            assert_eq!(serde_json::to_value(&config2).unwrap(), serialized);
+            serialized
+        };
+
+        let default = round_trip(json!({}));
+        assert_eq!(default["nodename"], json!("zenoh_bridge_ros2dds"));
+        assert_eq!(default["discovery_batch_window"], json!(50));
+        assert_eq!(default["reconnect"]["max_attempts"], json!(3));
+
+        round_trip(json!({
+            "id": "robot-1",
+            "alias": "robot-07",
+            "namespace": "/robot1",
+            "namespace_rules": [
+                {"pattern": "/tf.*", "namespace": "/"},
+                {"pattern": "/cmd_vel", "namespace": "/fleet"},
+            ],
+            "remappings": {"/old_cmd_vel": "/cmd_vel", "/ns/**": "/other_ns/**"},
+            "mode": "forward_discovery",
+            "pub_max_frequencies": [".*/laser_scan=5", "/tf=10.5"],
+            "feedback_max_frequency": ["/navigate_to_pose=2"],
+            "force_transient_local": ["/map", "/robot_description"],
+            "instance_key_exprs": ["/fleet_status"],
+            "max_payload_size": 65536,
+            "pub_priorities": ["/pose=2", "/rosout=7"],
+            "reliability": ["/scan=best_effort"],
+            "zenoh_reliability": ["/cmd_echo=reliable"],
+            "keyed": ["/vendor/objects=true", "/vendor/status=false"],
+            "on_deadline_miss": ["/cmd_vel=teardown"],
+            "topic_types": {"/cmd_vel": "geometry_msgs/msg/Twist"},
+            "sample_transforms": {"**/image_raw": "/opt/transforms/libdecimate.so"},
+            "startup_grace_period": 2.0,
+            "route_linger": 0.5,
+            "route_idle_timeout": {"default": 60.0, "publishers": 5.0},
+            "deduplication": true,
+            "source_info": true,
+            "sequence_tracking": true,
+            "sequence_gap_log_threshold": 10,
+            "write_suppression": false,
+            "forward_invalid_cdr": true,
+            "latched_aggregate_topics": [".*/tf_static"],
+            "reconnect": {"max_attempts": 5, "period": 0.1},
+            "max_concurrent_route_creations": 4,
+            "stats_rate_half_life": 10.0,
+            "max_bandwidth": 625000,
+            "bandwidth_shedding_priority": 5,
+            "dds_retry": {"max_attempts": 5, "initial_period": 0.5, "max_period": 30.0},
+            "transient_local_cache": {"history": ["/map=1"], "max_bytes": [".*=1000000"]},
+            "dds_partition": {"/diag/.*": ["diag"], ".*": ["prod"]},
+            "route_to": {"/incident_report": ["control_center"]},
+            "service_error_replies": {"/get_state": "0001000000000000"},
+            "service_retries": {"/get_state": {"retries": 2, "retry_backoff": 0.5}},
+            "on_service_unavailable": ["/get_state=error_reply", ".*=drop"],
+            "long_calls": ["/merge_maps=300"],
+            "max_concurrent_requests": ["/merge_maps=1"],
+            "heavy_services": {"services": [".*/get_map"], "reply_size": 1000000, "workers": 2, "queue_size": 64},
+            "qos_presets": {
+                "sensor": {"reliability": "best_effort", "history": 5, "zenoh_priority": 5},
+                "command": {"durability": "volatile", "history": "keep_all", "deadline": 0.1, "lifespan": 1.5},
+            },
+            "topic_qos": {"/cmd_vel": {"preset": "command"}, "/scan": {"preset": "sensor", "history": 1}},
+            "queries_timeout": {
+                "default": 1.0,
+                "services": ["/add_two_ints=0.5"],
+                "actions": {"get_result": [".*=300.0"]},
+            },
+        }));
+        let allow = round_trip(json!({
+            "allow": {
+                "publishers": ["/tf", ".*/pose"],
+                "subscribers": "/cmd_vel",
+                "service_servers": ["/a|/b"],
+            },
+        }));
+        assert_eq!(
+            allow["allow"],
+            json!({
+                "publishers": ["/tf", ".*/pose"],
+                "subscribers": ["/cmd_vel"],
+                "service_servers": ["/a|/b"],
             })
-        ));
+        );
+        round_trip(json!({"deny": {"action_clients": [".*"]}}));
+        round_trip(json!({"queries_timeout": 3.0}));
+        // the default `latched_aggregate_topics` disabled
+        let disabled = round_trip(json!({"latched_aggregate_topics": []}));
+        assert!(disabled["latched_aggregate_topics"].is_null());
+        round_trip(json!({"profile": "wan-lowbw"}));
+    }
 
-        assert!(!deny.is_publisher_allowed("/tf"));
-        assert!(!deny.is_publisher_allowed("/x/y/pose"));
-        assert!(deny.is_publisher_allowed("/abc/rotate_absolute"));
-        assert!(deny.is_publisher_allowed("/cmd_vel"));
-        assert!(deny.is_service_cli_allowed("/some_pseudo_random_name"));
+    #[test]
+    fn test_redact_json() {
+        use super::redact_json;
+        use serde_json::json;
 
-        assert!(deny.is_subscriber_allowed("/tf"));
-        assert!(deny.is_subscriber_allowed("/x/y/pose"));
-        assert!(deny.is_publisher_allowed("/abc/rotate_absolute"));
-        assert!(deny.is_subscriber_allowed("/cmd_vel"));
-        assert!(deny.is_service_cli_allowed("/some_pseudo_random_name"));
+        let mut value = json!({"id": "robot-1", "password": "secret", "token": null});
+        redact_json(&mut value, &["password", "token"]);
+        assert_eq!(
+            value,
+            json!({"id": "robot-1", "password": "<redacted>", "token": null})
+        );
+    }
 
-        assert!(!deny.is_service_srv_allowed("/tf"));
-        assert!(!deny.is_service_srv_allowed("/x/y/pose"));
-        assert!(!deny.is_service_srv_allowed("/abc/rotate_absolute"));
-        assert!(!deny.is_service_srv_allowed("/cmd_vel"));
-        assert!(!deny.is_service_srv_allowed("/some_pseudo_random_name"));
+    #[test]
+    fn test_expand_vars() {
+        use super::expand_vars_with;
+        use serde_json::json;
 
-        assert!(deny.is_service_cli_allowed("/tf"));
-        assert!(deny.is_service_cli_allowed("/x/y/pose"));
-        assert!(deny.is_service_cli_allowed("/abc/rotate_absolute"));
-        assert!(deny.is_service_cli_allowed("/cmd_vel"));
-        assert!(deny.is_service_cli_allowed("/some_pseudo_random_name"));
+        let lookup = |name: &str| match name {
+            "ROBOT_NAME" => Some("robot1".to_string()),
+            "DOMAIN" => Some("42".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        };
+        let expand = |mut v: serde_json::Value| expand_vars_with(&mut v, &lookup).map(|_| v);
 
-        assert!(deny.is_action_srv_allowed("/tf"));
-        assert!(deny.is_action_srv_allowed("/x/y/pose"));
-        assert!(!deny.is_action_srv_allowed("/abc/rotate_absolute"));
-        assert!(deny.is_action_srv_allowed("/cmd_vel"));
-        assert!(deny.is_action_srv_allowed("/some_pseudo_random_name"));
+        // simple and multiple expansions
+        assert_eq!(expand(json!("${ROBOT_NAME}")), Ok(json!("robot1")));
+        assert_eq!(
+            expand(json!("/${ROBOT_NAME}/status_${DOMAIN}")),
+            Ok(json!("/robot1/status_42"))
+        );
+        assert_eq!(expand(json!("${EMPTY}")), Ok(json!("")));
+        // strings without variables and non-string values are untouched
+        assert_eq!(expand(json!("/tf")), Ok(json!("/tf")));
+        assert_eq!(
+            expand(json!([1, 2.5, true, null])),
+            Ok(json!([1, 2.5, true, null]))
+        );
 
-        assert!(deny.is_action_cli_allowed("/tf"));
-        assert!(deny.is_action_cli_allowed("/x/y/pose"));
-        assert!(deny.is_action_cli_allowed("/abc/rotate_absolute"));
-        assert!(deny.is_action_cli_allowed("/cmd_vel"));
-        assert!(deny.is_action_cli_allowed("/some_pseudo_random_name"));
+        // default values
+        assert_eq!(expand(json!("${UNSET:-robot0}")), Ok(json!("robot0")));
+        assert_eq!(expand(json!("${ROBOT_NAME:-robot0}")), Ok(json!("robot1")));
+        assert_eq!(expand(json!("${UNSET:-}")), Ok(json!("")));
+        // a set but empty variable doesn't use the default
+        assert_eq!(expand(json!("${EMPTY:-default}")), Ok(json!("")));
+        // default containing ":-"
+        assert_eq!(expand(json!("${UNSET:-a:-b}")), Ok(json!("a:-b")));
 
-        let invalid = serde_json::from_str::<Allowance>(
-            r#"{
+        // nested braces in default values
+        assert_eq!(
+            expand(json!("${UNSET:-${ROBOT_NAME}}")),
+            Ok(json!("robot1"))
+        );
+        assert_eq!(
+            expand(json!("${UNSET:-/${UNSET2:-${ROBOT_NAME}_x}/y}")),
+            Ok(json!("/robot1_x/y"))
+        );
+        // default not expanded if the variable is set
+        assert_eq!(
+            expand(json!("${ROBOT_NAME:-${UNSET}}")),
+            Ok(json!("robot1"))
+        );
+
+        // "$$" escape and lone '$'
+        assert_eq!(expand(json!("$${ROBOT_NAME}")), Ok(json!("${ROBOT_NAME}")));
+        assert_eq!(expand(json!("cost$$")), Ok(json!("cost$")));
+        assert_eq!(expand(json!("$$$${DOMAIN}")), Ok(json!("$${DOMAIN}")));
+        assert_eq!(expand(json!("$$${DOMAIN}")), Ok(json!("$42")));
+        assert_eq!(expand(json!(".*/pose$")), Ok(json!(".*/pose$")));
+        assert_eq!(expand(json!("$a")), Ok(json!("$a")));
+
+        // expansion inside arrays and maps (values and keys)
+        assert_eq!(
+            expand(json!({
+                "namespace": "/${ROBOT_NAME}",
+                "domain": 0,
                 "allow": {
-                    "publishers": ["/tf", ".*/pose"],
-                    "subscribers": [],
-                    "service_servers": [".*"],
-                    "action_servers": [".*/rotate_absolute"],
-                    "action_clients": [ "" ]
+                    "publishers": ["/${ROBOT_NAME}/status", ".*/pose"],
                 },
-                "deny": {
-                    "subscribers": ["/tf", ".*/pose"],
-                    "service_clients": [".*"],
-                    "action_servers": [""],
-                    "action_clients": [ ".*/rotate_absolute" ]
+                "pub_priorities": { "/${ROBOT_NAME}/cmd_vel": 1 },
+            })),
+            Ok(json!({
+                "namespace": "/robot1",
+                "domain": 0,
+                "allow": {
+                    "publishers": ["/robot1/status", ".*/pose"],
                 },
-            }"#,
+                "pub_priorities": { "/robot1/cmd_vel": 1 },
+            }))
         );
-        assert!(invalid.is_err());
+
+        // unset variables without default are all listed (once)
+        let err = expand(json!({
+            "namespace": "/${UNSET_A}",
+            "allow": { "publishers": ["/${UNSET_B}/x", "/${UNSET_A}/y"] },
+            "nodename": "${UNSET:-${UNSET_C}}",
+        }))
+        .unwrap_err();
+        assert!(err.contains("UNSET_A"), "{err}");
+        assert!(err.contains("UNSET_B"), "{err}");
+        assert!(err.contains("UNSET_C"), "{err}");
+        assert_eq!(err.matches("UNSET_A").count(), 1, "{err}");
+
+        // invalid syntaxes
+        assert!(expand(json!("${ROBOT_NAME")).is_err());
+        assert!(expand(json!("${UNSET:-${ROBOT_NAME}")).is_err());
+        assert!(expand(json!("${}")).is_err());
+        assert!(expand(json!("${1VAR}")).is_err());
+        assert!(expand(json!("${ROBOT-NAME}")).is_err());
     }
 
     #[test]
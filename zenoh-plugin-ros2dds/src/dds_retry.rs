@@ -0,0 +1,151 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::{Serialize, Serializer};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zenoh_core::zlock;
+
+/// The retries of the (re-)creation of a route's DDS entity after a failure (e.g. on resource limits),
+/// with an exponential backoff from `initial_period` up to `max_period`.
+/// The route is "degraded" until a retry succeeds, and "failed" after `max_attempts` failures in a row
+/// (0 for no limit), the entity creation being then given up.
+pub struct DdsRetry {
+    max_attempts: u32,
+    initial_period: Duration,
+    max_period: Duration,
+    // the count of failures in a row
+    failures: u32,
+    // the time of the next allowed attempt (if any failure)
+    next_attempt: Option<Instant>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DdsRetryState {
+    Ok,
+    Degraded,
+    Failed,
+}
+
+impl DdsRetry {
+    pub fn new(max_attempts: u32, initial_period: Duration, max_period: Duration) -> DdsRetry {
+        DdsRetry {
+            max_attempts,
+            initial_period,
+            max_period: max_period.max(initial_period),
+            failures: 0,
+            next_attempt: None,
+        }
+    }
+
+    pub fn state(&self) -> DdsRetryState {
+        if self.failures == 0 {
+            DdsRetryState::Ok
+        } else if self.max_attempts > 0 && self.failures >= self.max_attempts {
+            DdsRetryState::Failed
+        } else {
+            DdsRetryState::Degraded
+        }
+    }
+
+    /// Return true if an attempt is allowed now (i.e. not failed, and the backoff delay elapsed)
+    pub fn may_attempt(&self, now: Instant) -> bool {
+        self.state() != DdsRetryState::Failed && self.next_attempt.map_or(true, |t| now >= t)
+    }
+
+    /// Report a successful attempt. Returns true if the route recovers from a degraded state.
+    pub fn on_success(&mut self) -> bool {
+        let recovered = self.failures > 0;
+        self.failures = 0;
+        self.next_attempt = None;
+        recovered
+    }
+
+    /// Report a failed attempt, scheduling the next one with an exponential backoff.
+    /// Returns None if it failed for the last allowed attempt, or else the delay before the next one.
+    pub fn on_failure(&mut self, now: Instant) -> Option<Duration> {
+        self.failures = self.failures.saturating_add(1);
+        if self.state() == DdsRetryState::Failed {
+            self.next_attempt = None;
+            return None;
+        }
+        let factor = 2u32.saturating_pow(self.failures - 1);
+        let backoff = self
+            .initial_period
+            .saturating_mul(factor)
+            .min(self.max_period);
+        self.next_attempt = Some(now + backoff);
+        Some(backoff)
+    }
+}
+
+pub fn serialize_dds_retry<S>(retry: &Arc<Mutex<DdsRetry>>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    zlock!(retry).state().serialize(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DdsRetry, DdsRetryState};
+    use std::time::{Duration, Instant};
+
+    const INITIAL: Duration = Duration::from_millis(100);
+    const MAX: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn test_backoff_and_recovery() {
+        let t0 = Instant::now();
+        let mut retry = DdsRetry::new(10, INITIAL, MAX);
+        assert_eq!(retry.state(), DdsRetryState::Ok);
+        assert!(retry.may_attempt(t0));
+
+        // the DDS Writer creation fails 5 times in a row (e.g. resource limits)
+        let mut now = t0;
+        for expected in [100, 200, 400, 800, 1000] {
+            assert_eq!(retry.on_failure(now), Some(Duration::from_millis(expected)));
+            assert_eq!(retry.state(), DdsRetryState::Degraded);
+            assert!(!retry.may_attempt(now));
+            assert!(!retry.may_attempt(now + Duration::from_millis(expected - 1)));
+            now += Duration::from_millis(expected);
+            assert!(retry.may_attempt(now));
+        }
+
+        // then succeeds
+        assert!(retry.on_success());
+        assert_eq!(retry.state(), DdsRetryState::Ok);
+        assert!(!retry.on_success());
+        // the backoff restarts from the initial period
+        assert_eq!(retry.on_failure(now), Some(INITIAL));
+    }
+
+    #[test]
+    fn test_give_up() {
+        let t0 = Instant::now();
+        let mut retry = DdsRetry::new(3, INITIAL, MAX);
+        assert!(retry.on_failure(t0).is_some());
+        assert!(retry.on_failure(t0 + MAX).is_some());
+        assert_eq!(retry.on_failure(t0 + MAX * 2), None);
+        assert_eq!(retry.state(), DdsRetryState::Failed);
+        assert!(!retry.may_attempt(t0 + MAX * 100));
+
+        // no limit
+        let mut retry = DdsRetry::new(0, INITIAL, MAX);
+        for _ in 0..100 {
+            assert!(retry.on_failure(t0).is_some());
+        }
+        assert_eq!(retry.state(), DdsRetryState::Degraded);
+    }
+}
@@ -15,9 +15,7 @@
 use cyclors::*;
 use std::fmt;
 use std::slice;
-use zenoh::buffers::ZBuf;
-#[cfg(feature = "dds_shm")]
-use zenoh::buffers::ZSlice;
+use zenoh::buffers::{ZBuf, ZSlice};
 use zenoh::prelude::*;
 
 use crate::dds_utils::ddsrt_iov_len_to_usize;
@@ -63,11 +61,61 @@ impl IoxChunk {
     }
 }
 
+// The "statusinfo" flags of a serdata, marking a dispose or an unregister
+// (see NN_STATUSINFO_* in https://github.com/eclipse-cyclonedds/cyclonedds/blob/master/src/core/ddsi/include/dds/ddsi/ddsi_protocol.h)
+const NN_STATUSINFO_DISPOSE: u32 = 0x1;
+const NN_STATUSINFO_UNREGISTER: u32 = 0x2;
+
+/// The state of the instance a DDS sample is about
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DDSInstanceState {
+    Alive = 0,
+    Disposed = 1,
+    Unregistered = 2,
+}
+
+impl DDSInstanceState {
+    /// The instance state of a sample without valid data (i.e. notifying a change of instance state), if any
+    pub fn from_sample_info(si: &dds_sample_info_t) -> Option<DDSInstanceState> {
+        #[allow(non_upper_case_globals)]
+        match si.instance_state {
+            dds_instance_state_DDS_IST_NOT_ALIVE_DISPOSED => Some(DDSInstanceState::Disposed),
+            dds_instance_state_DDS_IST_NOT_ALIVE_NO_WRITERS => Some(DDSInstanceState::Unregistered),
+            _ => None,
+        }
+    }
+
+    /// The "statusinfo" flags of a serdata written with this instance state
+    pub fn statusinfo(&self) -> u32 {
+        match self {
+            DDSInstanceState::Alive => 0,
+            DDSInstanceState::Disposed => NN_STATUSINFO_DISPOSE,
+            DDSInstanceState::Unregistered => NN_STATUSINFO_UNREGISTER,
+        }
+    }
+}
+
+impl TryFrom<u8> for DDSInstanceState {
+    type Error = String;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(DDSInstanceState::Alive),
+            1 => Ok(DDSInstanceState::Disposed),
+            2 => Ok(DDSInstanceState::Unregistered),
+            _ => Err(format!("Invalid instance state: {value}")),
+        }
+    }
+}
+
 pub struct DDSRawSample {
     sdref: *mut ddsi_serdata,
     data: ddsrt_iovec_t,
     #[cfg(feature = "dds_shm")]
     iox_chunk: Option<IoxChunk>,
+    instance_state: DDSInstanceState,
+    // the instance handle of the DDS Writer of the sample (relative to the Reader), and its source timestamp
+    publication_handle: dds_instance_handle_t,
+    source_timestamp: dds_time_t,
 }
 
 impl DDSRawSample {
@@ -123,9 +171,60 @@ impl DDSRawSample {
             sdref,
             data,
             iox_chunk,
+            instance_state: DDSInstanceState::Alive,
+            publication_handle: 0,
+            source_timestamp: 0,
         };
         #[cfg(not(feature = "dds_shm"))]
-        return DDSRawSample { sdref, data };
+        return DDSRawSample {
+            sdref,
+            data,
+            instance_state: DDSInstanceState::Alive,
+            publication_handle: 0,
+            source_timestamp: 0,
+        };
+    }
+
+    /// Set the origin of the sample (its Writer and source timestamp) from its sample info
+    pub fn with_sample_info(mut self, si: &dds_sample_info_t) -> DDSRawSample {
+        self.publication_handle = si.publication_handle;
+        self.source_timestamp = si.source_timestamp;
+        self
+    }
+
+    /// The instance handle of the DDS Writer of the sample, relative to the Reader (0 if unknown)
+    #[inline]
+    pub fn publication_handle(&self) -> dds_instance_handle_t {
+        self.publication_handle
+    }
+
+    /// The source timestamp of the sample, set by its DDS Writer
+    #[inline]
+    pub fn source_timestamp(&self) -> dds_time_t {
+        self.source_timestamp
+    }
+
+    /// Mark the sample as notifying a change of instance state (its payload is then only the key)
+    pub fn with_instance_state(mut self, instance_state: DDSInstanceState) -> DDSRawSample {
+        self.instance_state = instance_state;
+        self
+    }
+
+    #[inline]
+    pub fn instance_state(&self) -> DDSInstanceState {
+        self.instance_state
+    }
+
+    /// The key hash of the sample's instance, always computed as MD5 for a fixed size
+    /// and a same value on all hosts (for a keyless topic, all samples have the same key hash)
+    pub fn keyhash(&self) -> [u8; 16] {
+        let mut keyhash = ddsi_keyhash { value: [0u8; 16] };
+        unsafe {
+            if let Some(get_keyhash) = (*(*self.sdref).ops).get_keyhash {
+                get_keyhash(self.sdref, &mut keyhash, true);
+            }
+        }
+        keyhash.value
     }
 
     fn data_as_slice(&self) -> &[u8] {
@@ -224,6 +323,21 @@ impl From<&DDSRawSample> for ZBuf {
     }
 }
 
+// The serialized sample in a single contiguous slice (e.g. to split its header from its body without further copy)
+impl From<&DDSRawSample> for ZSlice {
+    fn from(buf: &DDSRawSample) -> Self {
+        #[cfg(feature = "dds_shm")]
+        {
+            // Where data was received via Iceoryx concatenate the header (contained in buf.data) and
+            // payload (contained in buf.iox_chunk).
+            if let Some(iox_chunk) = buf.iox_chunk {
+                return [buf.data_as_slice(), iox_chunk.as_slice()].concat().into();
+            }
+        }
+        buf.data_as_slice().to_vec().into()
+    }
+}
+
 impl From<&DDSRawSample> for Value {
     fn from(buf: &DDSRawSample) -> Self {
         ZBuf::from(buf).into()
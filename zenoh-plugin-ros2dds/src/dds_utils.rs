@@ -18,16 +18,18 @@ use cyclors::{
 };
 use serde::Serializer;
 use std::{
+    collections::HashMap,
     ffi::{CStr, CString},
     mem::MaybeUninit,
-    sync::{atomic::AtomicI32, Arc},
+    sync::{atomic::AtomicI32, Arc, Mutex},
     time::Duration,
 };
 #[cfg(feature = "dds_shm")]
 use zenoh::buffers::ZSlice;
+use zenoh_core::zlock;
 
 use crate::{
-    dds_types::{DDSRawSample, TypeInfo},
+    dds_types::{DDSInstanceState, DDSRawSample, TypeInfo},
     gid::Gid,
     vec_into_raw_parts,
 };
@@ -39,6 +41,9 @@ pub const DDS_ENTITY_NULL: dds_entity_t = 0;
 pub const CDR_HEADER_LE: [u8; 4] = [0, 1, 0, 0];
 pub const CDR_HEADER_BE: [u8; 4] = [0, 0, 0, 0];
 
+// The key of the USER_DATA entry tagging the DDS Writers created by a bridge (see tag_bridge_writer())
+const USER_DATA_KEY_BRIDGE: &str = "zenoh_bridge";
+
 /// Return None if the buffer is shorter than a CDR header (4 bytes).
 /// Otherwise, return true if the encoding flag (last bit of 2nd byte) corresponds little endian
 pub fn is_cdr_little_endian(cdr_buffer: &[u8]) -> Option<bool> {
@@ -91,6 +96,64 @@ pub fn get_guid(entity: &dds_entity_t) -> Result<Gid, String> {
     }
 }
 
+/// The GUID of a DDS Writer matched by a Reader, from its instance handle relative to the Reader
+pub fn get_matched_publication_guid(
+    reader: dds_entity_t,
+    publication_handle: dds_instance_handle_t,
+) -> Result<Gid, String> {
+    unsafe {
+        let endpoint = dds_get_matched_publication_data(reader, publication_handle);
+        if endpoint.is_null() {
+            return Err(format!(
+                "Error getting GUID of DDS Writer matched with handle {publication_handle}"
+            ));
+        }
+        let guid = Gid::from((*endpoint).key.v);
+        dds_builtintopic_free_endpoint(endpoint);
+        Ok(guid)
+    }
+}
+
+/// Tag a DDS Writer's QoS as created by a bridge, appending an entry to its USER_DATA
+/// (preserving the entries already set, e.g. "serviceid" for a Service route).
+/// The samples published by such a Writer are routed from zenoh, and are ignored by
+/// the DDS Readers of all the bridges (see take_samples()), to not be routed back to zenoh.
+pub fn tag_bridge_writer(qos: &mut Qos) {
+    let mut user_data = qos.user_data.take().unwrap_or_default();
+    user_data.extend_from_slice(format!("{USER_DATA_KEY_BRIDGE}= ros2dds;").as_bytes());
+    qos.user_data = Some(user_data);
+}
+
+/// Return true if a USER_DATA (as a list of "key= value;" entries) tags a DDS Writer created by a bridge
+pub fn is_bridge_user_data(user_data: &[u8]) -> bool {
+    String::from_utf8_lossy(user_data)
+        .split(';')
+        .filter_map(|entry| entry.split_once('='))
+        .any(|(key, _)| key.trim() == USER_DATA_KEY_BRIDGE)
+}
+
+// Return true if the DDS Writer matched with a Reader, with this publication handle, was created by a bridge
+unsafe fn is_matched_bridge_writer(
+    reader: dds_entity_t,
+    publication_handle: dds_instance_handle_t,
+) -> Result<bool, String> {
+    let endpoint = dds_get_matched_publication_data(reader, publication_handle);
+    if endpoint.is_null() {
+        return Err(format!(
+            "Error getting QoS of DDS Writer matched with handle {publication_handle}"
+        ));
+    }
+    let qos = Qos::from_qos_native((*endpoint).qos);
+    dds_builtintopic_free_endpoint(endpoint);
+    Ok(qos.user_data.as_deref().map_or(false, is_bridge_user_data))
+}
+
+/// Return false if the DDS entity was deleted (e.g. with its participant)
+pub fn is_dds_entity_alive(entity: dds_entity_t) -> bool {
+    let mut handle: dds_instance_handle_t = 0;
+    unsafe { dds_get_instance_handle(entity, &mut handle) == DDS_RETCODE_OK as i32 }
+}
+
 pub fn serialize_entity_guid<S>(entity: &dds_entity_t, s: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -168,28 +231,89 @@ pub fn create_dds_writer(
     keyless: bool,
     qos: Qos,
 ) -> Result<dds_entity_t, String> {
+    unsafe {
+        create_dds_writer_with_listener(
+            dp,
+            topic_name,
+            type_name,
+            keyless,
+            qos,
+            std::ptr::null_mut(),
+        )
+    }
+}
+
+unsafe extern "C" fn publication_matched_to_callback<F>(
+    _dw: dds_entity_t,
+    status: dds_publication_matched_status_t,
+    arg: *mut std::os::raw::c_void,
+) where
+    F: Fn(u32),
+{
+    let on_matched = arg as *mut F;
+    (*on_matched)(status.current_count);
+}
+
+/// Create a DDS Writer as per create_dds_writer(), calling `on_matched` with the count of matched Readers
+/// each time it changes. `on_matched` is called by a DDS listener thread: it must not block.
+pub fn create_dds_writer_with_matching<F>(
+    dp: dds_entity_t,
+    topic_name: String,
+    type_name: String,
+    keyless: bool,
+    qos: Qos,
+    on_matched: F,
+) -> Result<dds_entity_t, String>
+where
+    F: Fn(u32) + std::marker::Send + 'static,
+{
+    unsafe {
+        let arg = Box::new(on_matched);
+        let listener = dds_create_listener(Box::into_raw(arg) as *mut std::os::raw::c_void);
+        dds_lset_publication_matched(listener, Some(publication_matched_to_callback::<F>));
+        create_dds_writer_with_listener(dp, topic_name, type_name, keyless, qos, listener)
+    }
+}
+
+unsafe fn create_dds_writer_with_listener(
+    dp: dds_entity_t,
+    topic_name: String,
+    type_name: String,
+    keyless: bool,
+    mut qos: Qos,
+    listener: *mut dds_listener_t,
+) -> Result<dds_entity_t, String> {
+    tag_bridge_writer(&mut qos);
     let cton = CString::new(topic_name).unwrap().into_raw();
     let ctyn = CString::new(type_name).unwrap().into_raw();
 
-    unsafe {
-        let t = cdds_create_blob_topic(dp, cton, ctyn, keyless);
-        let qos_native = qos.to_qos_native();
-        let writer: i32 = dds_create_writer(dp, t, qos_native, std::ptr::null_mut());
-        Qos::delete_qos_native(qos_native);
-        if writer >= 0 {
-            Ok(writer)
-        } else {
-            Err(format!(
-                "Error creating DDS Writer: {}",
-                CStr::from_ptr(dds_strretcode(-writer))
-                    .to_str()
-                    .unwrap_or("unrecoverable DDS retcode")
-            ))
-        }
+    let t = cdds_create_blob_topic(dp, cton, ctyn, keyless);
+    let qos_native = qos.to_qos_native();
+    let writer: i32 = dds_create_writer(dp, t, qos_native, listener);
+    Qos::delete_qos_native(qos_native);
+    if writer >= 0 {
+        Ok(writer)
+    } else {
+        Err(format!(
+            "Error creating DDS Writer: {}",
+            CStr::from_ptr(dds_strretcode(-writer))
+                .to_str()
+                .unwrap_or("unrecoverable DDS retcode")
+        ))
     }
 }
 
 pub fn dds_write(data_writer: dds_entity_t, data: Vec<u8>) -> Result<(), String> {
+    dds_write_with_instance_state(data_writer, data, DDSInstanceState::Alive)
+}
+
+/// Write a message with an instance state: if not `Alive`, the message's key is used to dispose or unregister
+/// its instance (the message being the last one written for this instance).
+pub fn dds_write_with_instance_state(
+    data_writer: dds_entity_t,
+    data: Vec<u8>,
+    instance_state: DDSInstanceState,
+) -> Result<(), String> {
     unsafe {
         // As per the Vec documentation (see https://doc.rust-lang.org/std/vec/struct.Vec.html#method.into_raw_parts)
         // the only way to correctly releasing it is to create a vec using from_raw_parts
@@ -224,8 +348,20 @@ pub fn dds_write(data_writer: dds_entity_t, data: Vec<u8>) -> Result<(), String>
             &data_out,
             size as usize,
         );
+        if fwdp.is_null() {
+            drop(Vec::from_raw_parts(ptr, len, capacity));
+            return Err("DDS write failed: failed to deserialize the message".into());
+        }
 
-        let ret = dds_writecdr(data_writer, fwdp);
+        let ret = match instance_state {
+            DDSInstanceState::Alive => dds_writecdr(data_writer, fwdp),
+            _ => {
+                // dds_writecdr() resets the statusinfo, while dds_forwardcdr() keeps it (as well as the timestamp)
+                (*fwdp).statusinfo = instance_state.statusinfo();
+                (*fwdp).timestamp.v = dds_time();
+                dds_forwardcdr(data_writer, fwdp)
+            }
+        };
         if ret < 0 {
             drop(Vec::from_raw_parts(ptr, len, capacity));
             return Err(format!(
@@ -241,11 +377,48 @@ pub fn dds_write(data_writer: dds_entity_t, data: Vec<u8>) -> Result<(), String>
     }
 }
 
-unsafe extern "C" fn listener_to_callback<F>(dr: dds_entity_t, arg: *mut std::os::raw::c_void)
+// The callback of a DDS Reader, with its option to be notified of the changes of instance state
+struct ReaderCallback<F> {
+    callback: F,
+    instance_states: bool,
+    // for each matched DDS Writer (by publication handle), true if created by a bridge
+    bridge_writers: Mutex<HashMap<dds_instance_handle_t, bool>>,
+}
+
+impl<F> ReaderCallback<F> {
+    // Return true if the sample was published by a DDS Writer created by a bridge (this one or another one
+    // connected to the same DDS domain). Whatever the creation order of the routes, this is checked
+    // for each sample, the result being cached per Writer.
+    unsafe fn is_from_bridge(
+        &self,
+        dr: dds_entity_t,
+        publication_handle: dds_instance_handle_t,
+    ) -> bool {
+        if publication_handle == 0 {
+            return false;
+        }
+        let mut bridge_writers = zlock!(self.bridge_writers);
+        if let Some(from_bridge) = bridge_writers.get(&publication_handle) {
+            return *from_bridge;
+        }
+        match is_matched_bridge_writer(dr, publication_handle) {
+            Ok(from_bridge) => {
+                bridge_writers.insert(publication_handle, from_bridge);
+                from_bridge
+            }
+            Err(e) => {
+                tracing::debug!("{e} - assume it's not a bridge's Writer");
+                false
+            }
+        }
+    }
+}
+
+// Take all the available samples from a DDS Reader, passing them to the callback
+unsafe fn take_samples<F>(dr: dds_entity_t, reader_callback: &ReaderCallback<F>)
 where
     F: Fn(&DDSRawSample),
 {
-    let callback = arg as *mut F;
     let mut zp: *mut ddsi_serdata = std::ptr::null_mut();
     #[allow(clippy::uninit_assumed_init)]
     let mut si = MaybeUninit::<[dds_sample_info_t; 1]>::uninit();
@@ -258,15 +431,34 @@ where
     ) > 0
     {
         let si = si.assume_init();
-        if si[0].valid_data {
-            let raw_sample = DDSRawSample::create(zp);
-
-            (*callback)(&raw_sample);
+        if reader_callback.is_from_bridge(dr, si[0].publication_handle) {
+            // routed from zenoh by a bridge: routing it back to zenoh would create a loop
+            tracing::trace!(
+                "Ignore sample from a bridge's DDS Writer (handle {})",
+                si[0].publication_handle
+            );
+        } else if si[0].valid_data {
+            let raw_sample = DDSRawSample::create(zp).with_sample_info(&si[0]);
+            (reader_callback.callback)(&raw_sample);
+        } else if reader_callback.instance_states {
+            // a sample without data notifying a dispose or an unregister: its serdata only contains the key
+            if let Some(state) = DDSInstanceState::from_sample_info(&si[0]) {
+                let raw_sample = DDSRawSample::create(zp).with_instance_state(state);
+                (reader_callback.callback)(&raw_sample);
+            }
         }
         ddsi_serdata_unref(zp);
     }
 }
 
+unsafe extern "C" fn listener_to_callback<F>(dr: dds_entity_t, arg: *mut std::os::raw::c_void)
+where
+    F: Fn(&DDSRawSample),
+{
+    let reader_callback = arg as *mut ReaderCallback<F>;
+    take_samples(dr, &*reader_callback);
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn create_dds_reader<F>(
     dp: dds_entity_t,
@@ -276,17 +468,24 @@ pub fn create_dds_reader<F>(
     keyless: bool,
     mut qos: Qos,
     read_period: Option<Duration>,
+    instance_states: bool,
     callback: F,
 ) -> Result<dds_entity_t, String>
 where
     F: Fn(&DDSRawSample) + std::marker::Send + 'static,
 {
+    // if `instance_states` the callback is also called for the samples notifying a dispose or an unregister
+    let reader_callback = ReaderCallback {
+        callback,
+        instance_states,
+        bridge_writers: Mutex::new(HashMap::new()),
+    };
     unsafe {
         let t = create_topic(dp, &topic_name, &type_name, type_info, keyless);
         match read_period {
             None => {
                 // Use a Listener to route data as soon as it arrives
-                let arg = Box::new(callback);
+                let arg = Box::new(reader_callback);
                 let sub_listener =
                     dds_create_listener(Box::into_raw(arg) as *mut std::os::raw::c_void);
                 dds_lset_data_available(sub_listener, Some(listener_to_callback::<F>));
@@ -334,24 +533,7 @@ where
                         }
 
                         async_std::task::sleep(period).await;
-                        let mut zp: *mut ddsi_serdata = std::ptr::null_mut();
-                        #[allow(clippy::uninit_assumed_init)]
-                        let mut si = MaybeUninit::<[dds_sample_info_t; 1]>::uninit();
-                        while dds_takecdr(
-                            reader,
-                            &mut zp,
-                            1,
-                            si.as_mut_ptr() as *mut dds_sample_info_t,
-                            DDS_ANY_STATE,
-                        ) > 0
-                        {
-                            let si = si.assume_init();
-                            if si[0].valid_data {
-                                let raw_sample = DDSRawSample::create(zp);
-                                callback(&raw_sample);
-                            }
-                            ddsi_serdata_unref(zp);
-                        }
+                        take_samples(reader, &reader_callback);
                     }
                 });
                 Ok(reader)
@@ -359,3 +541,31 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{is_bridge_user_data, tag_bridge_writer};
+    use cyclors::qos::Qos;
+
+    #[test]
+    fn test_bridge_writer_tag() {
+        let mut qos = Qos::default();
+        tag_bridge_writer(&mut qos);
+        assert!(is_bridge_user_data(qos.user_data.as_ref().unwrap()));
+
+        // a Service route's Writer, keeping its "serviceid"
+        let mut qos = Qos {
+            user_data: Some(b"serviceid= 01.0f.5c.9a;".to_vec()),
+            ..Default::default()
+        };
+        assert!(!is_bridge_user_data(qos.user_data.as_ref().unwrap()));
+        tag_bridge_writer(&mut qos);
+        let user_data = qos.user_data.unwrap();
+        assert!(user_data.starts_with(b"serviceid= 01.0f.5c.9a;"));
+        assert!(is_bridge_user_data(&user_data));
+
+        // a ROS Node's Writer
+        assert!(!is_bridge_user_data(b"enclave=/;"));
+        assert!(!is_bridge_user_data(b"zenoh_bridge"));
+    }
+}
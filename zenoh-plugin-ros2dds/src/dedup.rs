@@ -0,0 +1,286 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hasher;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zenoh::buffers::ZBuf;
+use zenoh::prelude::SplitBuffer;
+use zenoh_core::zlock;
+
+use cyclors::{dds_entity_t, dds_instance_handle_t, dds_time_t};
+use serde::{Serialize, Serializer};
+
+use crate::dds_types::DDSRawSample;
+use crate::dds_utils::get_matched_publication_guid;
+use crate::gid::Gid;
+
+// The version of the binary layout of an Origin, as its 1st byte:
+//   1: [version (1 byte)][Writer GUID (16 bytes)][source timestamp (i64 LE, 8 bytes)]
+const ORIGIN_LAYOUT_VERSION: u8 = 1;
+const ORIGIN_LAYOUT_SIZE: usize = 25;
+
+// The count of the latest messages remembered per origin
+const WINDOW_SIZE: usize = 256;
+// The delay after which an origin without any message is forgotten
+const ORIGIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The origin of a message routed by a Publisher route: the GUID of its DDS Writer and its source timestamp.
+/// Cyclone DDS doesn't expose the RTPS sequence number of a sample: for deduplication, its source timestamp
+/// is used instead, being the same for all the bridges receiving the sample (see [`Deduplicator`]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Origin {
+    pub gid: Gid,
+    pub source_timestamp: dds_time_t,
+}
+
+impl Origin {
+    pub fn to_bytes(self) -> [u8; ORIGIN_LAYOUT_SIZE] {
+        let mut bytes = [0u8; ORIGIN_LAYOUT_SIZE];
+        bytes[0] = ORIGIN_LAYOUT_VERSION;
+        bytes[1..17].copy_from_slice(&*self.gid);
+        bytes[17..].copy_from_slice(&self.source_timestamp.to_le_bytes());
+        bytes
+    }
+
+    /// Return None if the bytes are not an Origin with a known layout version
+    /// (e.g. from a more recent bridge)
+    pub fn from_bytes(bytes: &[u8]) -> Option<Origin> {
+        if bytes.len() != ORIGIN_LAYOUT_SIZE || bytes[0] != ORIGIN_LAYOUT_VERSION {
+            return None;
+        }
+        let gid: [u8; 16] = bytes[1..17].try_into().ok()?;
+        let source_timestamp = i64::from_le_bytes(bytes[17..].try_into().ok()?);
+        Some(Origin {
+            gid: gid.into(),
+            source_timestamp,
+        })
+    }
+}
+
+/// The tagging of the messages received by the DDS Reader of a Publisher route with their origin
+/// (with `deduplication` or `source_info`), caching the GUIDs of the matched DDS Writers
+#[derive(Default)]
+pub struct OriginTagger {
+    writers: Mutex<HashMap<dds_instance_handle_t, Gid>>,
+}
+
+impl OriginTagger {
+    pub fn origin(&self, reader: dds_entity_t, sample: &DDSRawSample) -> Option<Origin> {
+        let handle = sample.publication_handle();
+        if handle == 0 {
+            return None;
+        }
+        let mut writers = zlock!(self.writers);
+        let gid = match writers.get(&handle) {
+            Some(gid) => *gid,
+            None => match get_matched_publication_guid(reader, handle) {
+                Ok(gid) => *writers.entry(handle).or_insert(gid),
+                Err(e) => {
+                    tracing::debug!("{e} - message routed without origin");
+                    return None;
+                }
+            },
+        };
+        Some(Origin {
+            gid,
+            source_timestamp: sample.source_timestamp(),
+        })
+    }
+}
+
+// The latest messages received from an origin, by source timestamp and payload hash
+struct Window {
+    seqs: BTreeSet<(i64, u64)>,
+    last_seen: Instant,
+}
+
+struct DedupState {
+    origins: HashMap<Gid, Window>,
+    last_purge: Instant,
+}
+
+/// The deduplication of the messages received by a Subscriber route from several bridges routing
+/// the same DDS samples (i.e. connected to the same DDS domain), relying on their origin.
+/// A window of the latest messages is kept per origin: a message already received, or older than the window,
+/// is a duplicate. A message is identified by its source timestamp and the hash of its payload, since
+/// a Writer can publish distinct messages with the same source timestamp (e.g. within the clock's resolution).
+pub struct Deduplicator {
+    state: Mutex<DedupState>,
+    duplicates: AtomicU64,
+}
+
+impl Deduplicator {
+    pub fn new(now: Instant) -> Deduplicator {
+        Deduplicator {
+            state: Mutex::new(DedupState {
+                origins: HashMap::new(),
+                last_purge: now,
+            }),
+            duplicates: AtomicU64::new(0),
+        }
+    }
+
+    /// Return true if the message is not a duplicate, and thus must be routed
+    pub fn accept(&self, origin: Origin, payload: &ZBuf, now: Instant) -> bool {
+        let mut state = zlock!(self.state);
+        if now.saturating_duration_since(state.last_purge) >= ORIGIN_TIMEOUT {
+            state
+                .origins
+                .retain(|_, w| now.saturating_duration_since(w.last_seen) < ORIGIN_TIMEOUT);
+            state.last_purge = now;
+        }
+        let window = state.origins.entry(origin.gid).or_insert_with(|| Window {
+            seqs: BTreeSet::new(),
+            last_seen: now,
+        });
+        window.last_seen = now;
+        let seq = (origin.source_timestamp, payload_hash(payload));
+        let older = window.seqs.len() >= WINDOW_SIZE
+            && window.seqs.first().map_or(false, |min| seq.0 < min.0);
+        if older || !window.seqs.insert(seq) {
+            self.duplicates.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        if window.seqs.len() > WINDOW_SIZE {
+            window.seqs.pop_first();
+        }
+        true
+    }
+
+    pub fn duplicates(&self) -> u64 {
+        self.duplicates.load(Ordering::Relaxed)
+    }
+}
+
+// The hash of a payload, identical for the copies of a message routed by several bridges
+// (only compared within this process)
+fn payload_hash(payload: &ZBuf) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for slice in payload.slices() {
+        hasher.write(slice);
+    }
+    hasher.finish()
+}
+
+pub fn serialize_deduplicator<S>(dedup: &Arc<Deduplicator>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.serialize_u64(dedup.duplicates())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Deduplicator, Origin, ORIGIN_TIMEOUT, WINDOW_SIZE};
+    use std::time::{Duration, Instant};
+    use zenoh::buffers::ZBuf;
+
+    fn origin(writer: u8, seq: i64) -> Origin {
+        Origin {
+            gid: [writer; 16].into(),
+            source_timestamp: seq,
+        }
+    }
+
+    fn payload(seq: i64) -> ZBuf {
+        ZBuf::from(seq.to_le_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_origin_bytes() {
+        let o = origin(7, 1_700_000_000_123_456_789);
+        assert_eq!(Origin::from_bytes(&o.to_bytes()), Some(o));
+        assert_eq!(
+            Origin::from_bytes(&o.to_bytes()).map(|o| o.source_timestamp),
+            Some(1_700_000_000_123_456_789)
+        );
+        assert_eq!(Origin::from_bytes(&o.to_bytes()[..20]), None);
+        // a layout version unknown to this bridge
+        let mut bytes = o.to_bytes();
+        bytes[0] = 2;
+        assert_eq!(Origin::from_bytes(&bytes), None);
+    }
+
+    #[test]
+    fn test_two_bridges_same_samples() {
+        // 2 bridges on the same DDS domain route the same samples of 2 Writers, interleaved
+        let dedup = Deduplicator::new(Instant::now());
+        let now = Instant::now();
+        let mut routed = Vec::new();
+        for seq in 0..100 {
+            for writer in [1, 2] {
+                // from bridge A, then from bridge B
+                for _bridge in 0..2 {
+                    if dedup.accept(origin(writer, seq * 1000), &payload(seq), now) {
+                        routed.push((writer, seq));
+                    }
+                }
+            }
+        }
+        assert_eq!(routed.len(), 200);
+        assert_eq!(dedup.duplicates(), 200);
+    }
+
+    #[test]
+    fn test_late_duplicates() {
+        let dedup = Deduplicator::new(Instant::now());
+        let now = Instant::now();
+        // bridge B lags behind bridge A
+        for seq in 0..10 {
+            assert!(dedup.accept(origin(1, seq), &payload(seq), now));
+        }
+        for seq in 0..10 {
+            assert!(!dedup.accept(origin(1, seq), &payload(seq), now));
+        }
+        // reordered, but not a duplicate
+        assert!(dedup.accept(origin(1, 20), &payload(20), now));
+        assert!(dedup.accept(origin(1, 15), &payload(15), now));
+        // older than the window
+        for seq in 100..100 + WINDOW_SIZE as i64 {
+            assert!(dedup.accept(origin(1, seq), &payload(seq), now));
+        }
+        assert!(!dedup.accept(origin(1, 50), &payload(50), now));
+    }
+
+    #[test]
+    fn test_same_source_timestamp() {
+        let dedup = Deduplicator::new(Instant::now());
+        let now = Instant::now();
+        // 2 distinct messages published by a Writer with the same source timestamp are both routed
+        let (first, second) = (ZBuf::from(vec![1u8, 2, 3]), ZBuf::from(vec![4u8, 5, 6]));
+        assert!(dedup.accept(origin(1, 1000), &first, now));
+        assert!(dedup.accept(origin(1, 1000), &second, now));
+        // but their copies routed by another bridge are duplicates
+        assert!(!dedup.accept(origin(1, 1000), &first, now));
+        assert!(!dedup.accept(origin(1, 1000), &second, now));
+        assert_eq!(dedup.duplicates(), 2);
+        // the same payload with another source timestamp is another message
+        assert!(dedup.accept(origin(1, 2000), &first, now));
+    }
+
+    #[test]
+    fn test_origin_forgotten() {
+        let t0 = Instant::now();
+        let dedup = Deduplicator::new(t0);
+        assert!(dedup.accept(origin(1, 5), &payload(5), t0));
+        assert!(dedup.accept(origin(2, 5), &payload(5), t0 + ORIGIN_TIMEOUT / 2));
+        // the 1st Writer is forgotten, not the 2nd one
+        let t1 = t0 + ORIGIN_TIMEOUT + Duration::from_millis(1);
+        assert!(!dedup.accept(origin(2, 5), &payload(5), t1));
+        assert!(dedup.accept(origin(1, 5), &payload(5), t1));
+    }
+}
@@ -224,6 +224,15 @@ impl DiscoveredEntities {
         self.readers.get(gid)
     }
 
+    /// True if both the Request Reader and the Reply Writer of a Service Server are discovered
+    /// (i.e. the Server is still live in DDS)
+    #[inline]
+    pub fn is_service_srv_live(&self, entities: &ServiceSrvEntities) -> bool {
+        entities.is_complete()
+            && self.readers.contains_key(&entities.req_reader)
+            && self.writers.contains_key(&entities.rep_writer)
+    }
+
     #[inline]
     pub fn remove_reader(&mut self, gid: &Gid) -> Option<ROS2DiscoveryEvent> {
         if let Some(reader) = self.readers.remove(gid) {
@@ -486,3 +495,44 @@ fn remove_null_qos_values(
         Err(error) => Err(error),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DiscoveredEntities;
+    use crate::dds_discovery::DdsEntity;
+    use crate::gid::Gid;
+    use crate::node_info::ServiceSrvEntities;
+
+    fn entity(id: u8, topic_name: &str) -> DdsEntity {
+        DdsEntity {
+            key: Gid::from([id; 16]),
+            participant_key: Gid::from([0u8; 16]),
+            topic_name: topic_name.into(),
+            type_name: "example_interfaces::srv::dds_::AddTwoInts_".into(),
+            type_info: None,
+            keyless: true,
+            qos: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_service_srv_live() {
+        let mut entities = DiscoveredEntities::default();
+        let server = ServiceSrvEntities {
+            req_reader: Gid::from([1u8; 16]),
+            rep_writer: Gid::from([2u8; 16]),
+        };
+        assert!(!entities.is_service_srv_live(&server));
+        assert!(!entities.is_service_srv_live(&ServiceSrvEntities::default()));
+
+        // live only with both its Request Reader and Reply Writer discovered
+        entities.add_reader(entity(1, "rq/add_two_intsRequest"));
+        assert!(!entities.is_service_srv_live(&server));
+        entities.add_writer(entity(2, "rr/add_two_intsReply"));
+        assert!(entities.is_service_srv_live(&server));
+
+        // the Server dying, its entities are undiscovered
+        entities.remove_writer(&Gid::from([2u8; 16]));
+        assert!(!entities.is_service_srv_live(&server));
+    }
+}
@@ -0,0 +1,148 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::time::{Duration, Instant};
+
+use crate::forward_discovery::RouteKind;
+
+/// The discovery events received during a `discovery_batch_window`, to be processed at once
+/// by the routes manager when the window ends. Within the window, a discovery followed by the
+/// undiscovery of the same interface by the same Node cancel each other, and a discovery
+/// replaces a pending one for the same interface and Node.
+pub struct DiscoveryBatch<E> {
+    window: Duration,
+    events: Vec<((RouteKind, String, String), bool, E)>,
+    // end of the current window (started by its 1st event)
+    deadline: Option<Instant>,
+}
+
+impl<E> DiscoveryBatch<E> {
+    pub fn new(window: Duration) -> DiscoveryBatch<E> {
+        DiscoveryBatch {
+            window,
+            events: Vec::new(),
+            deadline: None,
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        kind: RouteKind,
+        ros2_name: &str,
+        node: &str,
+        discovered: bool,
+        event: E,
+        now: Instant,
+    ) {
+        self.deadline.get_or_insert(now + self.window);
+        let key = (kind, ros2_name.to_string(), node.to_string());
+        // the last pending event for the same interface and Node, if it's a discovery
+        let pending_discovery = self
+            .events
+            .iter()
+            .rposition(|(k, _, _)| k == &key)
+            .filter(|i| self.events[*i].1);
+        match pending_discovery {
+            // discovery and undiscovery cancel each other
+            Some(i) if !discovered => {
+                self.events.remove(i);
+            }
+            // the new discovery replaces the pending one
+            Some(i) => {
+                self.events.remove(i);
+                self.events.push((key, discovered, event));
+            }
+            None => self.events.push((key, discovered, event)),
+        }
+    }
+
+    /// The end of the current window (if any event was pushed)
+    #[inline]
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Take the coalesced events, in their reception order, ending the current window
+    pub fn take(&mut self) -> Vec<E> {
+        self.deadline = None;
+        std::mem::take(&mut self.events)
+            .into_iter()
+            .map(|(_, _, e)| e)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiscoveryBatch;
+    use crate::forward_discovery::RouteKind::*;
+    use std::time::{Duration, Instant};
+
+    const WINDOW: Duration = Duration::from_millis(50);
+
+    #[test]
+    fn test_coalescing() {
+        let mut batch: DiscoveryBatch<&str> = DiscoveryBatch::new(WINDOW);
+        let t0 = Instant::now();
+        assert_eq!(batch.deadline(), None);
+
+        batch.push(Publisher, "/chatter", "/talker", true, "pub1", t0);
+        assert_eq!(batch.deadline(), Some(t0 + WINDOW));
+        batch.push(Subscriber, "/chatter", "/listener", true, "sub1", t0);
+        // discovery + undiscovery cancel each other
+        batch.push(Publisher, "/chatter", "/talker", false, "unpub1", t0);
+        // an undiscovery of an interface discovered in a previous window is kept
+        batch.push(ServiceSrv, "/add", "/server", false, "unsrv1", t0);
+        // undiscovery + discovery are both kept (e.g. restarted Node with other QoS)
+        batch.push(ServiceSrv, "/add", "/server", true, "srv1", t0);
+        // a new discovery replaces the pending one
+        batch.push(Subscriber, "/chatter", "/listener", true, "sub1bis", t0);
+        assert_eq!(batch.deadline(), Some(t0 + WINDOW));
+
+        assert_eq!(batch.take(), vec!["unsrv1", "srv1", "sub1bis"]);
+        assert_eq!(batch.deadline(), None);
+        assert!(batch.take().is_empty());
+    }
+
+    #[test]
+    fn test_1000_events() {
+        let mut batch: DiscoveryBatch<usize> = DiscoveryBatch::new(WINDOW);
+        let t0 = Instant::now();
+        let mut invocations = 0;
+        let mut routed_events = 0;
+
+        // 1000 events, 1 per ms: 250 Nodes each declaring a Publisher, undeclaring it,
+        // and re-declaring it with a Subscriber (e.g. QoS stabilizing at startup)
+        for i in 0..1000 {
+            let now = t0 + Duration::from_millis(i as u64);
+            // the routes manager is invoked once per window
+            if batch.deadline().map_or(false, |d| d <= now) {
+                invocations += 1;
+                routed_events += batch.take().len();
+            }
+            let node = format!("/node_{}", i / 4);
+            match i % 4 {
+                0 | 2 => batch.push(Publisher, "/topic", &node, true, i, now),
+                1 => batch.push(Publisher, "/topic", &node, false, i, now),
+                _ => batch.push(Subscriber, "/topic", &node, true, i, now),
+            }
+        }
+        invocations += 1;
+        routed_events += batch.take().len();
+
+        // 1000 events over 1 second, in windows of 50ms
+        assert_eq!(invocations, 20);
+        // the discover/undiscover pairs (never crossing a window's end here) are not routed
+        assert_eq!(routed_events, 500);
+    }
+}
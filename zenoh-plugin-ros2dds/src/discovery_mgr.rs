@@ -14,7 +14,9 @@
 use crate::dds_discovery::*;
 use crate::discovered_entities::DiscoveredEntities;
 use crate::events::ROS2DiscoveryEvent;
+use crate::node_info::{MsgPub, MsgSub};
 use crate::ros_discovery::*;
+use crate::Config;
 use async_std::task;
 use cyclors::dds_entity_t;
 use flume::{unbounded, Receiver, Sender};
@@ -32,6 +34,7 @@ use crate::ChannelEvent;
 use crate::ROS_DISCOVERY_INFO_POLL_INTERVAL_MS;
 
 pub struct DiscoveryMgr {
+    pub config: Arc<Config>,
     pub participant: dds_entity_t,
     pub ros_discovery_mgr: Arc<RosDiscoveryInfoMgr>,
     pub discovered_entities: Arc<RwLock<DiscoveredEntities>>,
@@ -39,10 +42,12 @@ pub struct DiscoveryMgr {
 
 impl DiscoveryMgr {
     pub fn create(
+        config: Arc<Config>,
         participant: dds_entity_t,
         ros_discovery_mgr: Arc<RosDiscoveryInfoMgr>,
     ) -> DiscoveryMgr {
         DiscoveryMgr {
+            config,
             participant,
             ros_discovery_mgr,
             discovered_entities: Arc::new(RwLock::new(Default::default())),
@@ -59,6 +64,7 @@ impl DiscoveryMgr {
 
         let ros_discovery_mgr = self.ros_discovery_mgr.clone();
         let discovered_entities = self.discovered_entities.clone();
+        let config = self.config.clone();
 
         task::spawn(async move {
             // Timer for periodic read of "ros_discovery_info" topic
@@ -86,15 +92,21 @@ impl DiscoveryMgr {
                                 }
                             },
                             DDSDiscoveryEvent::DiscoveredPublication{entity} => {
+                                let raw = raw_dds_topic_event(&config, &entity, true, true);
                                 let e = zwrite!(discovered_entities).add_writer(entity);
-                                if let Some(e) = e {
+                                if let Some(e) = e.or(raw) {
                                     if let Err(err) = evt_sender.try_send(e) {
                                         tracing::error!("Internal error: failed to send DDSDiscoveryEvent to main loop: {err}");
                                     }
                                 }
                             },
                             DDSDiscoveryEvent::UndiscoveredPublication{key} => {
-                                let e = zwrite!(discovered_entities).remove_writer(&key);
+                                let e = {
+                                    let mut entities = zwrite!(discovered_entities);
+                                    let raw = entities.get_writer(&key)
+                                        .and_then(|w| raw_dds_topic_event(&config, w, true, false));
+                                    entities.remove_writer(&key).or(raw)
+                                };
                                 if let Some(e) = e {
                                     if let Err(err) = evt_sender.try_send(e) {
                                         tracing::error!("Internal error: failed to send DDSDiscoveryEvent to main loop: {err}");
@@ -102,15 +114,21 @@ impl DiscoveryMgr {
                                 }
                             },
                             DDSDiscoveryEvent::DiscoveredSubscription {entity} => {
+                                let raw = raw_dds_topic_event(&config, &entity, false, true);
                                 let e = zwrite!(discovered_entities).add_reader(entity);
-                                if let Some(e) = e {
+                                if let Some(e) = e.or(raw) {
                                     if let Err(err) = evt_sender.try_send(e) {
                                         tracing::error!("Internal error: failed to send DDSDiscoveryEvent to main loop: {err}");
                                     }
                                 }
                             },
                             DDSDiscoveryEvent::UndiscoveredSubscription {key} => {
-                                let e = zwrite!(discovered_entities).remove_reader(&key);
+                                let e = {
+                                    let mut entities = zwrite!(discovered_entities);
+                                    let raw = entities.get_reader(&key)
+                                        .and_then(|r| raw_dds_topic_event(&config, r, false, false));
+                                    entities.remove_reader(&key).or(raw)
+                                };
                                 if let Some(e) = e {
                                     if let Err(err) = evt_sender.try_send(e) {
                                         tracing::error!("Internal error: failed to send DDSDiscoveryEvent to main loop: {err}");
@@ -146,3 +164,26 @@ impl DiscoveryMgr {
         );
     }
 }
+
+// For a DDS Writer or Reader on a raw DDS topic (configured in "raw_dds_topics"), the event of
+// its discovery/undiscovery as a Publisher or Subscriber. Not being part of any ROS Node, each entity
+// is considered as a distinct pseudo Node named "@raw/<gid>".
+fn raw_dds_topic_event(
+    config: &Config,
+    entity: &DdsEntity,
+    is_writer: bool,
+    discovered: bool,
+) -> Option<ROS2DiscoveryEvent> {
+    use ROS2DiscoveryEvent::*;
+    if !config.is_raw_dds_topic(&entity.topic_name) {
+        return None;
+    }
+    let node = format!("@raw/{}", entity.key);
+    let (topic, typ) = (&entity.topic_name, entity.type_name.clone());
+    Some(match (is_writer, discovered) {
+        (true, true) => DiscoveredMsgPub(node, MsgPub::create_raw(topic, typ, entity.key)),
+        (true, false) => UndiscoveredMsgPub(node, MsgPub::create_raw(topic, typ, entity.key)),
+        (false, true) => DiscoveredMsgSub(node, MsgSub::create_raw(topic, typ, entity.key)),
+        (false, false) => UndiscoveredMsgSub(node, MsgSub::create_raw(topic, typ, entity.key)),
+    })
+}
@@ -0,0 +1,142 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zenoh_core::zlock;
+
+/// The downsampling of the messages routed by a Route Publisher, to the minimum separation of the
+/// TimeBasedFilter announced by the remote Readers (see `propagate_time_based_filter` config).
+/// As a DDS TimeBasedFilter, it applies per instance. Without interval, all the messages are routed.
+#[derive(Default)]
+pub struct Downsampling {
+    interval: Mutex<Option<Duration>>,
+    // the time of the last routed message of each instance (per key hash)
+    last_routed: Mutex<HashMap<[u8; 16], Instant>>,
+    dropped: AtomicU64,
+}
+
+impl Downsampling {
+    #[inline]
+    pub fn interval(&self) -> Option<Duration> {
+        *zlock!(self.interval)
+    }
+
+    pub fn set_interval(&self, interval: Option<Duration>) {
+        *zlock!(self.interval) = interval;
+        if interval.is_none() {
+            zlock!(self.last_routed).clear();
+        }
+    }
+
+    /// Return true if a message of the `instance` received at `now` can be routed, recording it as
+    /// the last routed one. Otherwise (i.e. within the interval since the last routed one), count it as dropped.
+    pub fn check(&self, instance: [u8; 16], now: Instant) -> bool {
+        let Some(interval) = self.interval() else {
+            return true;
+        };
+        let mut last_routed = zlock!(self.last_routed);
+        match last_routed.get(&instance) {
+            Some(last) if now.saturating_duration_since(*last) < interval => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+            _ => {
+                last_routed.insert(instance, now);
+                true
+            }
+        }
+    }
+
+    /// Forget an instance (e.g. disposed or unregistered)
+    pub fn forget(&self, instance: &[u8; 16]) {
+        zlock!(self.last_routed).remove(instance);
+    }
+
+    #[inline]
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl Serialize for Downsampling {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("Downsampling", 2)?;
+        s.serialize_field(
+            "downsampling_interval",
+            &self.interval().map(|i| i.as_secs_f64()),
+        )?;
+        s.serialize_field("dropped_downsampled", &self.dropped())?;
+        s.end()
+    }
+}
+
+pub fn serialize_downsampling<S>(downsampling: &Arc<Downsampling>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    downsampling.as_ref().serialize(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Downsampling;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_downsampling() {
+        // a 10 Hz source for 2s, with a 2 Hz filter
+        let downsampling = Downsampling::default();
+        downsampling.set_interval(Some(Duration::from_millis(500)));
+        let start = Instant::now();
+        let routed = (0..20)
+            .filter(|i| downsampling.check([0; 16], start + Duration::from_millis(i * 100)))
+            .count();
+        assert_eq!(routed, 4);
+        assert_eq!(downsampling.dropped(), 16);
+        assert_eq!(
+            serde_json::to_value(&downsampling).unwrap(),
+            serde_json::json!({"downsampling_interval": 0.5, "dropped_downsampled": 16})
+        );
+
+        // the interval applies per instance
+        assert!(downsampling.check([1; 16], start + Duration::from_millis(1950)));
+        assert!(!downsampling.check([0; 16], start + Duration::from_millis(1950)));
+        downsampling.forget(&[0; 16]);
+        assert!(downsampling.check([0; 16], start + Duration::from_millis(1960)));
+    }
+
+    #[test]
+    fn test_no_downsampling() {
+        let downsampling = Downsampling::default();
+        let now = Instant::now();
+        assert!(downsampling.check([0; 16], now));
+        assert!(downsampling.check([0; 16], now));
+        downsampling.set_interval(Some(Duration::from_secs(1)));
+        assert!(downsampling.check([0; 16], now));
+        assert!(!downsampling.check([0; 16], now));
+        downsampling.set_interval(None);
+        assert!(downsampling.check([0; 16], now));
+        assert_eq!(downsampling.dropped(), 1);
+        assert_eq!(
+            serde_json::to_value(&downsampling).unwrap(),
+            serde_json::json!({"downsampling_interval": null, "dropped_downsampled": 1})
+        );
+    }
+}
@@ -20,7 +20,7 @@ use zenoh::prelude::OwnedKeyExpr;
 use crate::node_info::*;
 
 /// A (local) discovery event of a ROS2 interface
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum ROS2DiscoveryEvent {
     DiscoveredMsgPub(String, MsgPub),
     UndiscoveredMsgPub(String, MsgPub),
@@ -57,7 +57,7 @@ impl std::fmt::Display for ROS2DiscoveryEvent {
 }
 
 /// A (remote) announcement/retirement of a ROS2 interface
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum ROS2AnnouncementEvent {
     AnnouncedMsgPub {
         plugin_id: OwnedKeyExpr,
@@ -0,0 +1,206 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::HashMap;
+
+/// The kind of a local route, which is also the kind of the local ROS interface it serves.
+/// A remote announcement is associated to the kind of its local counterpart
+/// (e.g. a remote Publisher announcement is associated to a local Subscriber route).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RouteKind {
+    Publisher,
+    Subscriber,
+    ServiceSrv,
+    ServiceCli,
+    ActionSrv,
+    ActionCli,
+}
+
+/// The bookkeeping of the "forward_discovery" mode, where a route is created only when
+/// a local ROS interface and a matching remote counterpart both exist.
+/// For each (route kind, ROS name) it keeps the last discovery event of each local Node (`L`),
+/// and the last announcement of each remote route (`R`), so they can be replayed when matched.
+pub struct ForwardDiscovery<L, R> {
+    locals: HashMap<(RouteKind, String), HashMap<String, L>>,
+    remotes: HashMap<(RouteKind, String), HashMap<String, R>>,
+}
+
+impl<L, R> Default for ForwardDiscovery<L, R> {
+    fn default() -> Self {
+        ForwardDiscovery {
+            locals: HashMap::new(),
+            remotes: HashMap::new(),
+        }
+    }
+}
+
+impl<L: Clone, R: Clone> ForwardDiscovery<L, R> {
+    /// Add a local Node's interface. Returns true if it's the first local Node for this interface.
+    pub fn add_local(&mut self, kind: RouteKind, ros2_name: &str, node: &str, event: L) -> bool {
+        let nodes = self
+            .locals
+            .entry((kind, ros2_name.to_string()))
+            .or_default();
+        let first = nodes.is_empty();
+        nodes.insert(node.to_string(), event);
+        first
+    }
+
+    /// Remove a local Node's interface. Returns true if there is no more local Node for this interface.
+    pub fn remove_local(&mut self, kind: RouteKind, ros2_name: &str, node: &str) -> bool {
+        remove_from(&mut self.locals, kind, ros2_name, node)
+    }
+
+    /// Add a remote route. Returns true if it's the first remote route for this interface.
+    pub fn add_remote(
+        &mut self,
+        kind: RouteKind,
+        ros2_name: &str,
+        remote_id: &str,
+        event: R,
+    ) -> bool {
+        let routes = self
+            .remotes
+            .entry((kind, ros2_name.to_string()))
+            .or_default();
+        let first = routes.is_empty();
+        routes.insert(remote_id.to_string(), event);
+        first
+    }
+
+    /// Remove a remote route. Returns true if there is no more remote route for this interface.
+    pub fn remove_remote(&mut self, kind: RouteKind, ros2_name: &str, remote_id: &str) -> bool {
+        remove_from(&mut self.remotes, kind, ros2_name, remote_id)
+    }
+
+    /// True if the interface has both a local Node and a remote route, and thus must be routed.
+    pub fn is_matched(&self, kind: RouteKind, ros2_name: &str) -> bool {
+        let key = (kind, ros2_name.to_string());
+        self.locals.contains_key(&key) && self.remotes.contains_key(&key)
+    }
+
+    /// The discovery events of all the local Nodes for this interface
+    pub fn local_events(&self, kind: RouteKind, ros2_name: &str) -> Vec<L> {
+        self.locals
+            .get(&(kind, ros2_name.to_string()))
+            .map(|m| m.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The announcement events of all the remote routes for this interface
+    pub fn remote_events(&self, kind: RouteKind, ros2_name: &str) -> Vec<R> {
+        self.remotes
+            .get(&(kind, ros2_name.to_string()))
+            .map(|m| m.values().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+fn remove_from<T>(
+    map: &mut HashMap<(RouteKind, String), HashMap<String, T>>,
+    kind: RouteKind,
+    ros2_name: &str,
+    id: &str,
+) -> bool {
+    let key = (kind, ros2_name.to_string());
+    match map.get_mut(&key) {
+        Some(ids) => {
+            ids.remove(id);
+            if ids.is_empty() {
+                map.remove(&key);
+                true
+            } else {
+                false
+            }
+        }
+        // nothing was known for this interface: there is no more entry for it
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ForwardDiscovery, RouteKind::*};
+
+    #[test]
+    fn test_local_then_remote() {
+        let mut fd: ForwardDiscovery<&str, &str> = ForwardDiscovery::default();
+
+        // a local Publisher alone is not routed
+        assert!(fd.add_local(Publisher, "/chatter", "/talker", "pub1"));
+        assert!(!fd.is_matched(Publisher, "/chatter"));
+        // a remote Subscriber on another topic or for another kind doesn't match
+        assert!(fd.add_remote(Publisher, "/other", "bridge2:other", "sub_other"));
+        assert!(fd.add_remote(Subscriber, "/chatter", "bridge2:chatter", "pub2"));
+        assert!(!fd.is_matched(Publisher, "/chatter"));
+
+        // a matching remote Subscriber
+        assert!(fd.add_remote(Publisher, "/chatter", "bridge2:chatter", "sub2"));
+        assert!(fd.is_matched(Publisher, "/chatter"));
+        assert_eq!(fd.local_events(Publisher, "/chatter"), vec!["pub1"]);
+        assert_eq!(fd.remote_events(Publisher, "/chatter"), vec!["sub2"]);
+        // a 2nd remote Subscriber
+        assert!(!fd.add_remote(Publisher, "/chatter", "bridge3:chatter", "sub3"));
+
+        // retirement of the remote Subscribers: unmatched only after the last one
+        assert!(!fd.remove_remote(Publisher, "/chatter", "bridge2:chatter"));
+        assert!(fd.is_matched(Publisher, "/chatter"));
+        assert!(fd.remove_remote(Publisher, "/chatter", "bridge3:chatter"));
+        assert!(!fd.is_matched(Publisher, "/chatter"));
+        // the local Publisher is still known, to be matched again later
+        assert_eq!(fd.local_events(Publisher, "/chatter"), vec!["pub1"]);
+        assert!(fd.add_remote(Publisher, "/chatter", "bridge2:chatter", "sub2"));
+        assert!(fd.is_matched(Publisher, "/chatter"));
+    }
+
+    #[test]
+    fn test_remote_then_local() {
+        let mut fd: ForwardDiscovery<&str, &str> = ForwardDiscovery::default();
+
+        // a remote Service Client alone is not routed
+        assert!(fd.add_remote(ServiceSrv, "/add", "bridge2:add", "cli2"));
+        assert!(!fd.is_matched(ServiceSrv, "/add"));
+
+        // a local Service Server declared by 2 Nodes
+        assert!(fd.add_local(ServiceSrv, "/add", "/node1", "srv1"));
+        assert!(fd.is_matched(ServiceSrv, "/add"));
+        assert!(!fd.add_local(ServiceSrv, "/add", "/node2", "srv2"));
+        let mut locals = fd.local_events(ServiceSrv, "/add");
+        locals.sort();
+        assert_eq!(locals, vec!["srv1", "srv2"]);
+
+        // undiscovery of the local Nodes: unmatched only after the last one
+        assert!(!fd.remove_local(ServiceSrv, "/add", "/node1"));
+        assert!(fd.is_matched(ServiceSrv, "/add"));
+        assert!(fd.remove_local(ServiceSrv, "/add", "/node2"));
+        assert!(!fd.is_matched(ServiceSrv, "/add"));
+        assert!(fd.local_events(ServiceSrv, "/add").is_empty());
+        // the remote Service Client is still known
+        assert_eq!(fd.remote_events(ServiceSrv, "/add"), vec!["cli2"]);
+    }
+
+    #[test]
+    fn test_unknown_removals() {
+        let mut fd: ForwardDiscovery<&str, &str> = ForwardDiscovery::default();
+        assert!(fd.remove_local(ActionSrv, "/fibonacci", "/node1"));
+        assert!(fd.remove_remote(ActionCli, "/fibonacci", "bridge2:fibonacci"));
+
+        // re-discovery of a same Node replaces its event
+        assert!(fd.add_local(ActionCli, "/fibonacci", "/node1", "cli1"));
+        assert!(!fd.add_local(ActionCli, "/fibonacci", "/node1", "cli1bis"));
+        assert_eq!(fd.local_events(ActionCli, "/fibonacci"), vec!["cli1bis"]);
+        // removal of an unknown Node doesn't remove the others
+        assert!(!fd.remove_local(ActionCli, "/fibonacci", "/node2"));
+        assert_eq!(fd.local_events(ActionCli, "/fibonacci"), vec!["cli1bis"]);
+    }
+}
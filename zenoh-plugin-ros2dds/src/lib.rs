@@ -15,12 +15,16 @@ use async_trait::async_trait;
 use cyclors::*;
 use events::ROS2AnnouncementEvent;
 use flume::{unbounded, Receiver, Sender};
+use futures::future::{Fuse, FutureExt};
 use futures::select;
 use serde::Serializer;
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::mem::ManuallyDrop;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use std::time::Instant;
 use zenoh::liveliness::LivelinessToken;
 use zenoh::plugins::{RunningPlugin, RunningPluginTrait, ZenohPlugin};
 use zenoh::prelude::r#async::AsyncResolve;
@@ -34,34 +38,73 @@ use zenoh_ext::SubscriberBuilderExt;
 use zenoh_plugin_trait::{plugin_long_version, plugin_version, Plugin, PluginControl};
 use zenoh_util::Timed;
 
+mod action_feedback;
+mod bandwidth_cap;
+mod batcher;
+mod cdr_check;
+mod compression;
+mod concurrency_limit;
 pub mod config;
 mod dds_discovery;
+mod dds_retry;
 mod dds_types;
 mod dds_utils;
+mod dedup;
 mod discovered_entities;
+mod discovery_batch;
 mod discovery_mgr;
+mod downsampling;
 mod events;
+mod forward_discovery;
 mod gid;
 mod liveliness_mgt;
 mod node_info;
+mod payload_limit;
 mod qos_helpers;
+mod rate_limiter;
+mod reconnect;
+mod remote_bridges;
+mod ros2_names_cache;
 mod ros2_utils;
 mod ros_discovery;
 mod route_action_cli;
 mod route_action_srv;
+mod route_events;
+mod route_idle;
+mod route_linger;
 mod route_publisher;
 mod route_service_cli;
 mod route_service_srv;
+mod route_stats;
 mod route_subscriber;
 mod routes_mgr;
+pub mod sample_transform;
+mod sequence_gaps;
+mod service_events;
+mod service_metrics;
+mod startup_grace;
+#[cfg(test)]
+mod test_utils;
+mod transient_local_cache;
+mod worker_pool;
+mod write_suppression;
+
 use config::Config;
+pub use qos_helpers::{qos_from_json, qos_to_json};
 
 use crate::dds_utils::get_guid;
+use crate::discovery_batch::DiscoveryBatch;
 use crate::discovery_mgr::DiscoveryMgr;
 use crate::events::ROS2DiscoveryEvent;
+use crate::forward_discovery::RouteKind;
 use crate::liveliness_mgt::*;
+use crate::reconnect::ReconnectMonitor;
+use crate::remote_bridges::RemoteBridges;
+use crate::ros2_utils::{canonical_ros2_name, ros2_name_to_raw_dds_topic};
 use crate::ros_discovery::RosDiscoveryInfoMgr;
-use crate::routes_mgr::RoutesMgr;
+use crate::routes_mgr::{
+    discovery_event_key, is_parameter_service_event, route_key_expr, route_ros2_name, RoutesMgr,
+};
 
 #[macro_export]
 macro_rules! ke_for_sure {
@@ -80,6 +123,9 @@ lazy_static::lazy_static!(
     static ref KE_ANY_N_SEGMENT: &'static keyexpr = ke_for_sure!("**");
 
     static ref KE_PREFIX_PUB_CACHE: &'static keyexpr = ke_for_sure!("@ros2_pub_cache");
+    static ref KE_PREFIX_ROUTE_TO: &'static keyexpr = ke_for_sure!("@ros2_to");
+    static ref KE_ROUTE_TO_SEPARATOR: &'static keyexpr = ke_for_sure!("@");
+    static ref KE_PREFIX_LONG_CALL_REPLY: &'static keyexpr = ke_for_sure!("@ros2_reply");
 );
 
 zenoh::kedefine!(
@@ -95,6 +141,35 @@ zenoh::kedefine!(
 // Empty configuration fragments are ignored, so it is safe to unconditionally append a comma.
 const CYCLONEDDS_CONFIG_LOCALHOST_ONLY: &str = r#"<CycloneDDS><Domain><General><Interfaces><NetworkInterface address="127.0.0.1"/></Interfaces></General></Domain></CycloneDDS>,"#;
 
+// CycloneDDS' network interfaces selection: each interface is either a name (e.g. "eth0"), either an IP address
+fn cyclonedds_config_network_interfaces(config: &Config) -> Option<String> {
+    if config.ros_localhost_only {
+        return Some(CYCLONEDDS_CONFIG_LOCALHOST_ONLY.to_string());
+    }
+    if config.dds_network_interfaces.is_empty() {
+        return None;
+    }
+    let interfaces: String = config
+        .dds_network_interfaces
+        .iter()
+        .map(|iface| match iface.parse::<std::net::IpAddr>() {
+            Ok(_) => format!(r#"<NetworkInterface address="{iface}"/>"#),
+            Err(_) => format!(r#"<NetworkInterface name="{}"/>"#, xml_escape(iface)),
+        })
+        .collect();
+    Some(format!(
+        "<CycloneDDS><Domain><General><Interfaces>{interfaces}</Interfaces></General></Domain></CycloneDDS>,"
+    ))
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
 // CycloneDDS' enable-shm: enable usage of Iceoryx shared memory
 #[cfg(feature = "dds_shm")]
 const CYCLONEDDS_CONFIG_ENABLE_SHM: &str = r#"<CycloneDDS><Domain><SharedMemory><Enable>true</Enable></SharedMemory></Domain></CycloneDDS>,"#;
@@ -128,7 +203,18 @@ impl Plugin for ROS2Plugin {
         let plugin_conf = runtime_conf
             .plugin(name)
             .ok_or_else(|| zerror!("Plugin `{}`: missing config", name))?;
-        let config: Config = serde_json::from_value(plugin_conf.clone())
+        let mut plugin_conf = plugin_conf.clone();
+        config::expand_env_vars(&mut plugin_conf)
+            .map_err(|e| zerror!("Plugin `{}` configuration error: {}", name, e))?;
+        config::apply_profile(&mut plugin_conf)
+            .map_err(|e| zerror!("Plugin `{}` configuration error: {}", name, e))?;
+        let mut config: Config = serde_json::from_value(plugin_conf)
+            .map_err(|e| zerror!("Plugin `{}` configuration error: {}", name, e))?;
+        config
+            .validate()
+            .map_err(|e| zerror!("Plugin `{}` configuration error: {}", name, e))?;
+        config
+            .resolve_key_prefix()
             .map_err(|e| zerror!("Plugin `{}` configuration error: {}", name, e))?;
         async_std::task::spawn(run(runtime.clone(), config));
         Ok(Box::new(ROS2Plugin))
@@ -146,15 +232,33 @@ pub async fn run(runtime: Runtime, config: Config) {
     tracing::info!("ROS2 plugin {:?}", config);
 
     // Check config validity
-    if !regex::Regex::new("/[A-Za-z0-9_/]*")
-        .unwrap()
-        .is_match(&config.namespace)
-    {
-        tracing::error!(
-            r#"Configuration error: invalid namespace "{}" must contain only alphanumeric, '_' or '/' characters and start with '/'"#,
-            config.namespace
-        );
-        return;
+    let namespace_regex = regex::Regex::new("^/([A-Za-z0-9_]+/?)*$").unwrap();
+    for (field, namespace) in [
+        ("namespace", Some(&config.namespace)),
+        ("namespace_to_zenoh", config.namespace_to_zenoh.as_ref()),
+        ("namespace_from_zenoh", config.namespace_from_zenoh.as_ref()),
+    ] {
+        if let Some(namespace) = namespace {
+            if !namespace_regex.is_match(namespace) {
+                tracing::error!(
+                    r#"Configuration error: invalid {field} "{namespace}" must contain only alphanumeric, '_' or non-consecutive '/' characters and start with '/'"#
+                );
+                return;
+            }
+        }
+    }
+    for (field, scope) in [
+        ("scope_out", &config.scope_out),
+        ("scope_in", &config.scope_in),
+    ] {
+        if let Some(scope) = scope {
+            if scope.is_wild() {
+                tracing::error!(
+                    r#"Configuration error: invalid {field} "{scope}" must not contain any wildcard"#
+                );
+                return;
+            }
+        }
     }
     if !regex::Regex::new("[A-Za-z0-9_]+")
         .unwrap()
@@ -167,6 +271,17 @@ pub async fn run(runtime: Runtime, config: Config) {
         return;
     }
 
+    // load the sample transforms from their dynamic libraries (before any route is created)
+    for (pattern, path) in &config.sample_transforms {
+        if let Err(e) = keyexpr::new(pattern.as_str())
+            .map_err(|e| e.to_string())
+            .and_then(|pattern| sample_transform::load_sample_transform(pattern.into(), path))
+        {
+            tracing::error!("Configuration error: sample_transforms: {e}");
+            return;
+        }
+    }
+
     // open zenoh-net Session
     let zsession = match zenoh::init(runtime).res_async().await {
         Ok(session) => Arc::new(session),
@@ -205,13 +320,47 @@ pub async fn run(runtime: Runtime, config: Config) {
         }
     };
 
-    // if "ros_localhost_only" is set, configure CycloneDDS to use only localhost interface
-    if config.ros_localhost_only {
+    // Declare plugin's alias liveliness token (if an alias is configured)
+    let alias_token = match config.alias {
+        Some(ref alias) => {
+            let ke_alias = match new_ke_liveliness_alias(&plugin_id, alias) {
+                Ok(ke) => ke,
+                Err(e) => {
+                    tracing::error!("Configuration error: {e}");
+                    return;
+                }
+            };
+            match zsession
+                .liveliness()
+                .declare_token(ke_alias)
+                .res_async()
+                .await
+            {
+                Ok(token) => Some(token),
+                Err(e) => {
+                    tracing::error!(
+                        "Unable to declare alias liveliness token for DDS plugin : {:?}",
+                        e
+                    );
+                    return;
+                }
+            }
+        }
+        None => None,
+    };
+
+    // if "ros_localhost_only" or "dds_network_interfaces" is set, configure CycloneDDS to use only those interfaces
+    if config.ros_localhost_only && !config.dds_network_interfaces.is_empty() {
+        tracing::warn!(
+            "Both 'ros_localhost_only' and 'dds_network_interfaces' are configured: 'dds_network_interfaces' is ignored and only localhost interface will be used"
+        );
+    }
+    if let Some(cyclonedds_config) = cyclonedds_config_network_interfaces(&config) {
         env::set_var(
             "CYCLONEDDS_URI",
             format!(
                 "{}{}",
-                CYCLONEDDS_CONFIG_LOCALHOST_ONLY,
+                cyclonedds_config,
                 env::var("CYCLONEDDS_URI").unwrap_or_default()
             ),
         );
@@ -251,7 +400,11 @@ pub async fn run(runtime: Runtime, config: Config) {
         zsession,
         participant,
         _member: member,
+        _alias_token: alias_token,
         plugin_id,
+        remote_bridges: RemoteBridges::default(),
+        typed_key_exprs_mismatches: HashSet::new(),
+        newer_format_bridges: HashSet::new(),
         admin_space: HashMap::<OwnedKeyExpr, AdminRef>::new(),
     };
 
@@ -265,7 +418,14 @@ pub struct ROS2PluginRuntime<'a> {
     zsession: Arc<Session>,
     participant: dds_entity_t,
     _member: LivelinessToken<'a>,
+    _alias_token: Option<LivelinessToken<'a>>,
     plugin_id: OwnedKeyExpr,
+    // the remote bridges detected via liveliness, with their alias
+    remote_bridges: RemoteBridges,
+    // the remote bridges which Publishers/Subscribers use another "typed_key_exprs" mode (and are not routed)
+    typed_key_exprs_mismatches: HashSet<String>,
+    // the remote bridges using a newer liveliness tokens format than supported (their routes are not discovered)
+    newer_format_bridges: HashSet<String>,
     // admin space: index is the admin_keyexpr
     // value is the JSon string to return to queries.
     admin_space: HashMap<OwnedKeyExpr, AdminRef>,
@@ -276,6 +436,10 @@ pub struct ROS2PluginRuntime<'a> {
 enum AdminRef {
     Config,
     Version,
+    Alias,
+    KeyPrefix,
+    RemoteBridges,
+    TypedKeyExprsMismatches,
 }
 
 impl<'a> ROS2PluginRuntime<'a> {
@@ -297,6 +461,20 @@ impl<'a> ROS2PluginRuntime<'a> {
             .await
             .expect("Failed to create Liveliness Subscriber");
 
+        // Subscribe to the session's transport events, to detect the reconnections to the Zenoh infrastructure
+        let transport_events_ke = format!("@/session/{}/transport/unicast/*", self.zsession.zid());
+        let transport_subscriber = self
+            .zsession
+            .declare_subscriber(&transport_events_ke)
+            .with(flume::unbounded())
+            .res_async()
+            .await
+            .expect("Failed to create Subscriber on transport events");
+        let mut reconnect_monitor = ReconnectMonitor::new(
+            self.config.reconnect.max_attempts,
+            self.config.reconnect.get_period(),
+        );
+
         // declare admin space queryable
         let admin_prefix =
             zenoh::keformat!(ke_admin_prefix::formatter(), plugin_id = &self.plugin_id).unwrap();
@@ -309,11 +487,37 @@ impl<'a> ROS2PluginRuntime<'a> {
             .await
             .expect("Failed to create AdminSpace queryable");
 
+        // declare admin space subscriber, for the routes pause/resume operations
+        let admin_subscriber = self
+            .zsession
+            .declare_subscriber((&admin_prefix) / ke_for_sure!("route/**"))
+            .res_async()
+            .await
+            .expect("Failed to create AdminSpace subscriber");
+
         // add plugin's config and version in admin space
         self.admin_space
             .insert(&admin_prefix / ke_for_sure!("config"), AdminRef::Config);
         self.admin_space
             .insert(&admin_prefix / ke_for_sure!("version"), AdminRef::Version);
+        if self.config.alias.is_some() {
+            self.admin_space
+                .insert(&admin_prefix / ke_for_sure!("alias"), AdminRef::Alias);
+        }
+        if self.config.get_key_prefix().is_some() {
+            self.admin_space.insert(
+                &admin_prefix / ke_for_sure!("key_prefix"),
+                AdminRef::KeyPrefix,
+            );
+        }
+        self.admin_space.insert(
+            &admin_prefix / ke_for_sure!("remote_bridges"),
+            AdminRef::RemoteBridges,
+        );
+        self.admin_space.insert(
+            &admin_prefix / ke_for_sure!("typed_key_exprs_mismatches"),
+            AdminRef::TypedKeyExprsMismatches,
+        );
 
         // Create and start the RosDiscoveryInfoMgr (managing ros_discovery_info topic)
         let ros_discovery_mgr = Arc::new(
@@ -329,7 +533,11 @@ impl<'a> ROS2PluginRuntime<'a> {
         // Create and start DiscoveryManager
         let (tx, discovery_rcv): (Sender<ROS2DiscoveryEvent>, Receiver<ROS2DiscoveryEvent>) =
             unbounded();
-        let mut discovery_mgr = DiscoveryMgr::create(self.participant, ros_discovery_mgr.clone());
+        let mut discovery_mgr = DiscoveryMgr::create(
+            self.config.clone(),
+            self.participant,
+            ros_discovery_mgr.clone(),
+        );
         discovery_mgr.run(tx).await;
 
         // Create RoutesManager
@@ -343,15 +551,117 @@ impl<'a> ROS2PluginRuntime<'a> {
             admin_prefix.clone(),
         );
 
+        // The routes failing to re-create their DDS entities after "dds_retry.max_attempts", to be torn down
+        let route_failures = routes_mgr.route_failures();
+
+        // Batch of discovery events to be coalesced (if "discovery_batch_window" is not 0)
+        let batch_window = self.config.get_discovery_batch_window();
+        let mut discovery_batch = DiscoveryBatch::new(batch_window.unwrap_or_default());
+
+        // Timer for the end of the startup grace period (if configured)
+        let startup_grace_end = match self.config.get_startup_grace_period() {
+            Some(period) => async_std::task::sleep(period).fuse(),
+            None => Fuse::terminated(),
+        };
+        futures::pin_mut!(startup_grace_end);
+
         loop {
+            // Timer for the next expiry of a lingering route (if any)
+            let mut linger_timer = match routes_mgr.next_linger_expiry() {
+                Some(expiry) => {
+                    async_std::task::sleep(expiry.saturating_duration_since(Instant::now()))
+                        .boxed()
+                        .fuse()
+                }
+                None => Fuse::terminated(),
+            };
+
+            // Timer for the next check of the idle routes (if "route_idle_timeout" is configured)
+            let mut idle_timer = match routes_mgr.next_idle_check() {
+                Some(check) => {
+                    async_std::task::sleep(check.saturating_duration_since(Instant::now()))
+                        .boxed()
+                        .fuse()
+                }
+                None => Fuse::terminated(),
+            };
+
+            // Timer for the end of the current discovery batch window (if any)
+            let mut batch_timer = match discovery_batch.deadline() {
+                Some(deadline) => {
+                    async_std::task::sleep(deadline.saturating_duration_since(Instant::now()))
+                        .boxed()
+                        .fuse()
+                }
+                None => Fuse::terminated(),
+            };
+
+            // Timer for the next attempt to restore the routes after a reconnection (if any)
+            let mut restore_timer = match reconnect_monitor.next_attempt() {
+                Some(attempt) => {
+                    async_std::task::sleep(attempt.saturating_duration_since(Instant::now()))
+                        .boxed()
+                        .fuse()
+                }
+                None => Fuse::terminated(),
+            };
+
             select!(
+                _ = startup_grace_end => routes_mgr.end_startup_grace_period().await,
+
+                _ = restore_timer => {
+                    let result = self.restore(&mut routes_mgr).await;
+                    if let Err(e) = &result {
+                        tracing::warn!("Error restoring routes after reconnection: {e}");
+                    }
+                    if !reconnect_monitor.on_attempt_result(result.is_ok(), Instant::now()) {
+                        tracing::error!(
+                            "Failed to restore routes after {} attempts: some routes might not be working until the bridge is restarted",
+                            self.config.reconnect.max_attempts
+                        );
+                    }
+                },
+
+                transport_event = transport_subscriber.recv_async() => {
+                    match transport_event {
+                        Ok(evt) => {
+                            // the key is "@/session/<zid>/transport/unicast/<peer_zid>"
+                            if let Some(peer) = evt.key_expr.as_str().rsplit('/').next() {
+                                let opened = evt.kind == SampleKind::Put;
+                                tracing::debug!("Transport with {peer} {}", if opened { "opened" } else { "closed" });
+                                if reconnect_monitor.on_transport_event(peer, opened, Instant::now()) {
+                                    tracing::info!("Reconnected to Zenoh (via {peer}): restoring routes");
+                                }
+                            }
+                        },
+                        Err(e) => tracing::warn!("Error receiving transport event: {e}")
+                    }
+                },
+
+                _ = linger_timer => routes_mgr.expire_lingering_routes(),
+
+                _ = idle_timer => routes_mgr.expire_idle_routes(),
+
+                failed_route = route_failures.recv_async() => {
+                    if let Ok((kind, ros2_name)) = failed_route {
+                        routes_mgr.on_route_failed(kind, &ros2_name);
+                    }
+                },
+
+                _ = batch_timer => routes_mgr.on_ros_discovery_events(discovery_batch.take()).await,
+
                 evt = discovery_rcv.recv_async() => {
                     match evt {
                         Ok(evt) => {
                             if self.is_allowed(&evt) {
                                 tracing::info!("{evt} - Allowed");
-                                // pass ROS2DiscoveryEvent to RoutesMgr
-                                if let Err(e) = routes_mgr.on_ros_discovery_event(evt).await {
+                                if batch_window.is_some() && !is_parameter_service_event(&self.config, &evt) {
+                                    // add ROS2DiscoveryEvent to the batch for RoutesMgr
+                                    let (kind, ros2_name, node, discovered) = discovery_event_key(&evt);
+                                    let (ros2_name, node) = (ros2_name.to_string(), node.to_string());
+                                    discovery_batch.push(kind, &ros2_name, &node, discovered, evt, Instant::now());
+                                } else if let Err(e) = routes_mgr.on_ros_discovery_event(evt).await {
+                                    // pass ROS2DiscoveryEvent to RoutesMgr
                                     tracing::warn!("Error updating route: {e}");
                                 }
                             } else {
@@ -373,21 +683,67 @@ impl<'a> ROS2PluginRuntime<'a> {
                                     // ignore own announcements
                                     continue;
                                 }
-                                match (parsed.remaining(), evt.kind)  {
+                                // the version of the token's layout, and the part following the version chunk
+                                let (format_version, remaining) = parsed
+                                    .remaining()
+                                    .map(|r| split_format_version(r.as_str()))
+                                    .unzip();
+                                match (remaining, evt.kind)  {
                                     // New remote bridge detected
                                     (None, SampleKind::Put) => {
-                                        tracing::info!("New ROS 2 bridge detected: {}", plugin_id);
+                                        self.remote_bridges.add_bridge(plugin_id);
+                                        tracing::info!("New ROS 2 bridge detected: {}", self.remote_bridges.display_name(plugin_id));
                                         // make each routes for a TRANSIENT_LOCAL Subscriber to query historical publications from this new plugin
                                         routes_mgr.query_all_historical_publications(plugin_id).await;
                                     }
                                     // New remote bridge left
-                                    (None, SampleKind::Delete) => tracing::info!("Remote ROS 2 bridge left: {}", plugin_id),
+                                    (None, SampleKind::Delete) => {
+                                        tracing::info!("Remote ROS 2 bridge left: {}", self.remote_bridges.display_name(plugin_id));
+                                        self.remote_bridges.remove_bridge(plugin_id);
+                                        self.typed_key_exprs_mismatches.remove(plugin_id.as_str());
+                                        self.newer_format_bridges.remove(plugin_id.as_str());
+                                    }
+                                    // the liveliness token has a newer layout than supported by this bridge: it can't be parsed
+                                    (Some(_), _) if format_version > Some(LIVELINESS_FORMAT_VERSION) => {
+                                        if evt.kind == SampleKind::Put && self.newer_format_bridges.insert(plugin_id.to_string()) {
+                                            tracing::warn!(
+                                                "Remote bridge {} uses a newer liveliness tokens format (version {}, while this bridge supports up to {LIVELINESS_FORMAT_VERSION}): its routes are not discovered - please upgrade this bridge",
+                                                self.remote_bridges.display_name(plugin_id),
+                                                format_version.unwrap_or_default()
+                                            );
+                                        }
+                                    }
+                                    // the liveliness token corresponds to the alias of a remote bridge
+                                    (Some(remaining), _) if remaining.starts_with("AL/") => {
+                                        match (parse_ke_liveliness_alias(ke), evt.kind) {
+                                            (Ok((_, alias)), SampleKind::Put) => {
+                                                tracing::debug!("Remote bridge {plugin_id} has alias '{alias}'");
+                                                self.remote_bridges.set_alias(plugin_id, &alias);
+                                            }
+                                            (Ok(_), SampleKind::Delete) => self.remote_bridges.unset_alias(plugin_id),
+                                            (Err(e), _) =>
+                                                tracing::warn!("Received unexpected liveliness key expression '{ke}': {e}")
+                                        }
+                                    }
+                                    // the liveliness token corresponds to a Publisher/Subscriber of a remote bridge
+                                    // using another "typed_key_exprs" mode: it can't be routed
+                                    (Some(remaining), _) if is_typed_liveliness_token(remaining)
+                                        .is_some_and(|typed| typed != self.config.typed_key_exprs) =>
+                                    {
+                                        if evt.kind == SampleKind::Put && self.typed_key_exprs_mismatches.insert(plugin_id.to_string()) {
+                                            tracing::error!(
+                                                "Remote bridge {} {} 'typed_key_exprs' mode, unlike this bridge: its Publishers and Subscribers are not routed",
+                                                self.remote_bridges.display_name(plugin_id),
+                                                if self.config.typed_key_exprs { "doesn't use" } else { "uses" }
+                                            );
+                                        }
+                                    }
                                     // the liveliness token corresponds to a ROS2 announcement
                                     (Some(remaining), _) => {
                                         // parse it and pass ROS2AnnouncementEvent to RoutesMgr
-                                        match self.parse_announcement_event(ke, &remaining.as_str()[..3], evt.kind) {
+                                        match parse_announcement_event(ke, &remaining[..3], evt.kind) {
                                             Ok(evt) => {
-                                                tracing::info!("Remote bridge {plugin_id} {evt}");
+                                                tracing::info!("Remote bridge {} {evt}", self.remote_bridges.display_name(plugin_id));
                                                 routes_mgr.on_ros_announcement_event(evt).await
                                                     .unwrap_or_else(|e| tracing::warn!("Error treating announcement event: {e}"));
                                             },
@@ -404,6 +760,14 @@ impl<'a> ROS2PluginRuntime<'a> {
                     }
                 },
 
+                put_request = admin_subscriber.recv_async() => {
+                    match put_request {
+                        Ok(sample) if sample.kind == SampleKind::Put => routes_mgr.treat_admin_put(&sample.key_expr).await,
+                        Ok(_) => (),
+                        Err(_) => tracing::warn!("AdminSpace subscriber was closed!"),
+                    }
+                },
+
                 get_request = admin_queryable.recv_async() => {
                     if let Ok(query) = get_request {
                         self.treat_admin_query(&query).await;
@@ -419,114 +783,49 @@ impl<'a> ROS2PluginRuntime<'a> {
         }
     }
 
-    fn parse_announcement_event(
-        &self,
-        liveliness_ke: &keyexpr,
-        iface_kind: &str,
-        sample_kind: SampleKind,
-    ) -> Result<ROS2AnnouncementEvent, String> {
-        use ROS2AnnouncementEvent::*;
-        tracing::debug!("Received liveliness event: {sample_kind} on {liveliness_ke}");
-        match (iface_kind, sample_kind) {
-            ("MP/", SampleKind::Put) => parse_ke_liveliness_pub(liveliness_ke)
-                .map_err(|e| format!("Received invalid liveliness token: {e}"))
-                .map(
-                    |(plugin_id, zenoh_key_expr, ros2_type, keyless, writer_qos)| AnnouncedMsgPub {
-                        plugin_id,
-                        zenoh_key_expr,
-                        ros2_type,
-                        keyless,
-                        writer_qos,
-                    },
-                ),
-            ("MP/", SampleKind::Delete) => parse_ke_liveliness_pub(liveliness_ke)
-                .map_err(|e| format!("Received invalid liveliness token: {e}"))
-                .map(|(plugin_id, zenoh_key_expr, ..)| RetiredMsgPub {
-                    plugin_id,
-                    zenoh_key_expr,
-                }),
-            ("MS/", SampleKind::Put) => parse_ke_liveliness_sub(liveliness_ke)
-                .map_err(|e| format!("Received invalid liveliness token: {e}"))
-                .map(
-                    |(plugin_id, zenoh_key_expr, ros2_type, keyless, reader_qos)| AnnouncedMsgSub {
-                        plugin_id,
-                        zenoh_key_expr,
-                        ros2_type,
-                        keyless,
-                        reader_qos,
-                    },
-                ),
-            ("MS/", SampleKind::Delete) => parse_ke_liveliness_sub(liveliness_ke)
-                .map_err(|e| format!("Received invalid liveliness token: {e}"))
-                .map(|(plugin_id, zenoh_key_expr, ..)| RetiredMsgSub {
-                    plugin_id,
-                    zenoh_key_expr,
-                }),
-            ("SS/", SampleKind::Put) => parse_ke_liveliness_service_srv(liveliness_ke)
-                .map_err(|e| format!("Received invalid liveliness token: {e}"))
-                .map(
-                    |(plugin_id, zenoh_key_expr, ros2_type)| AnnouncedServiceSrv {
-                        plugin_id,
-                        zenoh_key_expr,
-                        ros2_type,
-                    },
-                ),
-            ("SS/", SampleKind::Delete) => parse_ke_liveliness_service_srv(liveliness_ke)
-                .map_err(|e| format!("Received invalid liveliness token: {e}"))
-                .map(|(plugin_id, zenoh_key_expr, ..)| RetiredServiceSrv {
-                    plugin_id,
-                    zenoh_key_expr,
-                }),
-            ("SC/", SampleKind::Put) => parse_ke_liveliness_service_cli(liveliness_ke)
-                .map_err(|e| format!("Received invalid liveliness token: {e}"))
-                .map(
-                    |(plugin_id, zenoh_key_expr, ros2_type)| AnnouncedServiceCli {
-                        plugin_id,
-                        zenoh_key_expr,
-                        ros2_type,
-                    },
-                ),
-            ("SC/", SampleKind::Delete) => parse_ke_liveliness_service_cli(liveliness_ke)
-                .map_err(|e| format!("Received invalid liveliness token: {e}"))
-                .map(|(plugin_id, zenoh_key_expr, ..)| RetiredServiceCli {
-                    plugin_id,
-                    zenoh_key_expr,
-                }),
-            ("AS/", SampleKind::Put) => parse_ke_liveliness_action_srv(liveliness_ke)
-                .map_err(|e| format!("Received invalid liveliness token: {e}"))
-                .map(
-                    |(plugin_id, zenoh_key_expr, ros2_type)| AnnouncedActionSrv {
-                        plugin_id,
-                        zenoh_key_expr,
-                        ros2_type,
-                    },
-                ),
-            ("AS/", SampleKind::Delete) => parse_ke_liveliness_action_srv(liveliness_ke)
-                .map_err(|e| format!("Received invalid liveliness token: {e}"))
-                .map(|(plugin_id, zenoh_key_expr, ..)| RetiredActionSrv {
-                    plugin_id,
-                    zenoh_key_expr,
-                }),
-            ("AC/", SampleKind::Put) => parse_ke_liveliness_action_cli(liveliness_ke)
-                .map_err(|e| format!("Received invalid liveliness token: {e}"))
-                .map(
-                    |(plugin_id, zenoh_key_expr, ros2_type)| AnnouncedActionCli {
-                        plugin_id,
-                        zenoh_key_expr,
-                        ros2_type,
-                    },
-                ),
-            ("AC/", SampleKind::Delete) => parse_ke_liveliness_action_cli(liveliness_ke)
-                .map_err(|e| format!("Received invalid liveliness token: {e}"))
-                .map(|(plugin_id, zenoh_key_expr, ..)| RetiredActionCli {
-                    plugin_id,
-                    zenoh_key_expr,
-                }),
-            _ => Err(format!("invalid ROS2 interface kind: {iface_kind}")),
+    // Re-assert the plugin's LivelinessToken, and restore all the routes
+    async fn restore(&mut self, routes_mgr: &mut RoutesMgr<'_>) -> Result<(), String> {
+        let ke_liveliness = zenoh::keformat!(
+            ke_liveliness_plugin::formatter(),
+            plugin_id = &self.plugin_id
+        )
+        .unwrap();
+        self._member = self
+            .zsession
+            .liveliness()
+            .declare_token(ke_liveliness)
+            .res_async()
+            .await
+            .map_err(|e| format!("Failed to re-declare plugin's liveliness token: {e}"))?;
+        if let Some(alias) = &self.config.alias {
+            let ke_alias = new_ke_liveliness_alias(&self.plugin_id, alias)?;
+            self._alias_token = Some(
+                self.zsession
+                    .liveliness()
+                    .declare_token(ke_alias)
+                    .res_async()
+                    .await
+                    .map_err(|e| {
+                        format!("Failed to re-declare plugin's alias liveliness token: {e}")
+                    })?,
+            );
         }
+        routes_mgr.restore_routes().await
     }
 
     fn is_allowed(&self, evt: &ROS2DiscoveryEvent) -> bool {
+        let (_, ros2_name, ..) = discovery_event_key(evt);
+        // the raw DDS topics are explicitly configured in "raw_dds_topics"
+        if ros2_name_to_raw_dds_topic(ros2_name).is_some() {
+            return true;
+        }
+        // the parameters of the Nodes configured in "bridge_parameters" are bridged whatever "allow"/"deny"
+        if self.config.is_parameters_interface(ros2_name) {
+            return true;
+        }
+        if !self.config.is_name_bridged(ros2_name) {
+            return false;
+        }
         if let Some(allowance) = &self.config.allowance {
             use ROS2DiscoveryEvent::*;
             match evt {
@@ -576,7 +875,25 @@ impl<'a> ROS2PluginRuntime<'a> {
     async fn send_admin_reply(&self, query: &Query, key_expr: &keyexpr, admin_ref: &AdminRef) {
         let value: Value = match admin_ref {
             AdminRef::Version => VERSION_JSON_VALUE.clone(),
-            AdminRef::Config => match serde_json::to_value(&*self.config) {
+            AdminRef::Alias => self.config.alias.clone().unwrap_or_default().into(),
+            AdminRef::KeyPrefix => self.config.get_key_prefix().unwrap_or_default().into(),
+            AdminRef::RemoteBridges => match serde_json::to_value(&self.remote_bridges) {
+                Ok(v) => v.into(),
+                Err(e) => {
+                    tracing::error!("INTERNAL ERROR serializing remote bridges as JSON: {}", e);
+                    return;
+                }
+            },
+            AdminRef::TypedKeyExprsMismatches => {
+                match serde_json::to_value(&self.typed_key_exprs_mismatches) {
+                    Ok(v) => v.into(),
+                    Err(e) => {
+                        tracing::error!("INTERNAL ERROR serializing mismatches as JSON: {}", e);
+                        return;
+                    }
+                }
+            }
+            AdminRef::Config => match self.config.to_redacted_json() {
                 Ok(v) => v.into(),
                 Err(e) => {
                     tracing::error!("INTERNAL ERROR serializing config as JSON: {}", e);
@@ -594,6 +911,212 @@ impl<'a> ROS2PluginRuntime<'a> {
     }
 }
 
+// The announcement event of a remote bridge's liveliness token for an interface of kind `iface_kind`
+// (e.g. "MP/" for a Publisher), put or deleted
+pub(crate) fn parse_announcement_event(
+    liveliness_ke: &keyexpr,
+    iface_kind: &str,
+    sample_kind: SampleKind,
+) -> Result<ROS2AnnouncementEvent, String> {
+    use ROS2AnnouncementEvent::*;
+    tracing::debug!("Received liveliness event: {sample_kind} on {liveliness_ke}");
+    match (iface_kind, sample_kind) {
+        ("MP/", SampleKind::Put) => parse_ke_liveliness_pub(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(
+                |(plugin_id, zenoh_key_expr, ros2_type, keyless, writer_qos)| AnnouncedMsgPub {
+                    plugin_id,
+                    zenoh_key_expr,
+                    ros2_type,
+                    keyless,
+                    writer_qos,
+                },
+            ),
+        ("MP/", SampleKind::Delete) => parse_ke_liveliness_pub(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(|(plugin_id, zenoh_key_expr, ..)| RetiredMsgPub {
+                plugin_id,
+                zenoh_key_expr,
+            }),
+        ("TP/", SampleKind::Put) => parse_ke_liveliness_typed_pub(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(
+                |(plugin_id, zenoh_key_expr, ros2_type, keyless, writer_qos)| AnnouncedMsgPub {
+                    plugin_id,
+                    zenoh_key_expr,
+                    ros2_type,
+                    keyless,
+                    writer_qos,
+                },
+            ),
+        ("TP/", SampleKind::Delete) => parse_ke_liveliness_typed_pub(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(|(plugin_id, zenoh_key_expr, ..)| RetiredMsgPub {
+                plugin_id,
+                zenoh_key_expr,
+            }),
+        ("MS/", SampleKind::Put) => parse_ke_liveliness_sub(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(
+                |(plugin_id, zenoh_key_expr, ros2_type, keyless, reader_qos)| AnnouncedMsgSub {
+                    plugin_id,
+                    zenoh_key_expr,
+                    ros2_type,
+                    keyless,
+                    reader_qos,
+                },
+            ),
+        ("MS/", SampleKind::Delete) => parse_ke_liveliness_sub(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(|(plugin_id, zenoh_key_expr, ..)| RetiredMsgSub {
+                plugin_id,
+                zenoh_key_expr,
+            }),
+        ("TS/", SampleKind::Put) => parse_ke_liveliness_typed_sub(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(
+                |(plugin_id, zenoh_key_expr, ros2_type, keyless, reader_qos)| AnnouncedMsgSub {
+                    plugin_id,
+                    zenoh_key_expr,
+                    ros2_type,
+                    keyless,
+                    reader_qos,
+                },
+            ),
+        ("TS/", SampleKind::Delete) => parse_ke_liveliness_typed_sub(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(|(plugin_id, zenoh_key_expr, ..)| RetiredMsgSub {
+                plugin_id,
+                zenoh_key_expr,
+            }),
+        ("SS/", SampleKind::Put) => parse_ke_liveliness_service_srv(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(
+                |(plugin_id, zenoh_key_expr, ros2_type)| AnnouncedServiceSrv {
+                    plugin_id,
+                    zenoh_key_expr,
+                    ros2_type,
+                },
+            ),
+        ("SS/", SampleKind::Delete) => parse_ke_liveliness_service_srv(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(|(plugin_id, zenoh_key_expr, ..)| RetiredServiceSrv {
+                plugin_id,
+                zenoh_key_expr,
+            }),
+        ("SC/", SampleKind::Put) => parse_ke_liveliness_service_cli(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(
+                |(plugin_id, zenoh_key_expr, ros2_type)| AnnouncedServiceCli {
+                    plugin_id,
+                    zenoh_key_expr,
+                    ros2_type,
+                },
+            ),
+        ("SC/", SampleKind::Delete) => parse_ke_liveliness_service_cli(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(|(plugin_id, zenoh_key_expr, ..)| RetiredServiceCli {
+                plugin_id,
+                zenoh_key_expr,
+            }),
+        ("AS/", SampleKind::Put) => parse_ke_liveliness_action_srv(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(
+                |(plugin_id, zenoh_key_expr, ros2_type)| AnnouncedActionSrv {
+                    plugin_id,
+                    zenoh_key_expr,
+                    ros2_type,
+                },
+            ),
+        ("AS/", SampleKind::Delete) => parse_ke_liveliness_action_srv(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(|(plugin_id, zenoh_key_expr, ..)| RetiredActionSrv {
+                plugin_id,
+                zenoh_key_expr,
+            }),
+        ("AC/", SampleKind::Put) => parse_ke_liveliness_action_cli(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(
+                |(plugin_id, zenoh_key_expr, ros2_type)| AnnouncedActionCli {
+                    plugin_id,
+                    zenoh_key_expr,
+                    ros2_type,
+                },
+            ),
+        ("AC/", SampleKind::Delete) => parse_ke_liveliness_action_cli(liveliness_ke)
+            .map_err(|e| format!("Received invalid liveliness token: {e}"))
+            .map(|(plugin_id, zenoh_key_expr, ..)| RetiredActionCli {
+                plugin_id,
+                zenoh_key_expr,
+            }),
+        _ => Err(format!("invalid ROS2 interface kind: {iface_kind}")),
+    }
+}
+
+/// The key expression used by a bridge configured with `config` to route to Zenoh the ROS 2 interface
+/// named `ros2_name` (i.e. a local Publisher, Service Server or Action Server), applying in order:
+/// the remappings, the namespace (or the one of the 1st matching `namespace_rules`), the escaping of
+/// the characters not allowed in key expressions and the `scope_out`.
+///
+/// `ros2_name` must be a fully qualified name (starting with '/'). Duplicate or trailing '/' are ignored,
+/// as the bridge does for the discovered interfaces. A raw DDS topic is named "@raw/<topic>".
+/// The `config` is expected as used by the bridge, i.e. after [`Config::resolve_key_prefix()`].
+/// With `typed_key_exprs` or `instance_key_exprs`, the type or instance chunks appended by the bridge
+/// are not included.
+///
+/// # Examples
+/// ```
+/// use zenoh_plugin_ros2dds::{config::Config, ros2_name_to_key_expr};
+///
+/// let config: Config =
+///     serde_json::from_str(r#"{"namespace": "/robot1", "remappings": {"/old_cmd_vel": "/cmd_vel"}}"#)
+///         .unwrap();
+/// assert_eq!(ros2_name_to_key_expr("/old_cmd_vel", &config).unwrap().as_str(), "robot1/cmd_vel");
+/// assert_eq!(ros2_name_to_key_expr("/ns//odom/", &config).unwrap().as_str(), "robot1/ns/odom");
+/// assert!(ros2_name_to_key_expr("cmd_vel", &config).is_err());
+/// ```
+pub fn ros2_name_to_key_expr(ros2_name: &str, config: &Config) -> ZResult<OwnedKeyExpr> {
+    let ros2_name = match ros2_name_to_raw_dds_topic(ros2_name) {
+        Some("") => return Err(zerror!("Invalid raw DDS topic name: '{ros2_name}'").into()),
+        Some(_) => Cow::Borrowed(ros2_name),
+        None if !ros2_name.starts_with('/') => {
+            return Err(zerror!("Invalid ROS 2 name '{ros2_name}': not starting with '/'").into())
+        }
+        None => canonical_ros2_name(ros2_name),
+    };
+    if ros2_name == "/" {
+        return Err(zerror!("Invalid ROS 2 name '/': no interface name").into());
+    }
+    let key_expr = route_key_expr(config, RouteKind::Publisher, &ros2_name);
+    // the key expression is built from unchecked chunks
+    keyexpr::new(key_expr.as_str())?;
+    Ok(key_expr)
+}
+
+/// The name of the ROS 2 interface that a bridge configured with `config` routes from Zenoh for
+/// the key expression `key_expr` (i.e. for a local Subscriber, Service Client or Action Client),
+/// reverting in order: the `scope_in`, the namespace (or the one of the `namespace_rules` that produced it),
+/// the escaping and the remappings.
+///
+/// It's the inverse of [`ros2_name_to_key_expr()`] if `namespace_to_zenoh` and `namespace_from_zenoh`
+/// are the same (e.g. only `namespace` is configured), and if `scope_out` and `scope_in` are the same.
+/// A key expression not starting with the `scope_in` is converted as if it was already unscoped.
+///
+/// # Examples
+/// ```
+/// use zenoh::prelude::keyexpr;
+/// use zenoh_plugin_ros2dds::{config::Config, key_expr_to_ros2_name};
+///
+/// let config: Config =
+///     serde_json::from_str(r#"{"namespace": "/robot1", "remappings": {"/old_cmd_vel": "/cmd_vel"}}"#)
+///         .unwrap();
+/// let key_expr = keyexpr::new("robot1/cmd_vel").unwrap();
+/// assert_eq!(key_expr_to_ros2_name(key_expr, &config), "/old_cmd_vel");
+/// ```
+pub fn key_expr_to_ros2_name(key_expr: &keyexpr, config: &Config) -> String {
+    route_ros2_name(config, RouteKind::Subscriber, key_expr)
+}
+
 //TODO replace when stable https://github.com/rust-lang/rust/issues/65816
 #[inline]
 pub fn vec_into_raw_parts<T>(v: Vec<T>) -> (*mut T, usize, usize) {
@@ -620,3 +1143,148 @@ where
 {
     s.serialize_bool(opt.is_some())
 }
+
+pub(crate) fn serialize_atomic_bool<S>(b: &Arc<AtomicBool>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.serialize_bool(b.load(std::sync::atomic::Ordering::Relaxed))
+}
+
+pub(crate) fn serialize_priority<S>(p: &Priority, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.serialize_u8(*p as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cyclonedds_config_network_interfaces, key_expr_to_ros2_name, ros2_name_to_key_expr, Config,
+    };
+    use zenoh::prelude::keyexpr;
+
+    // The behavior of the public conversions between ROS 2 names and key expressions:
+    // for each config, the (ROS 2 name, key expression) pairs converted in both directions
+    #[test]
+    fn test_ros2_name_key_expr_conversions() {
+        for (json, conversions) in [
+            (
+                r#"{}"#,
+                vec![("/cmd_vel", "cmd_vel"), ("/ns/cmd_vel", "ns/cmd_vel")],
+            ),
+            (
+                r#"{"namespace": "/robot1"}"#,
+                vec![
+                    ("/cmd_vel", "robot1/cmd_vel"),
+                    ("/ns/odom", "robot1/ns/odom"),
+                ],
+            ),
+            (
+                r#"{"namespace": "/fleet/robot1"}"#,
+                vec![("/cmd_vel", "fleet/robot1/cmd_vel")],
+            ),
+            (
+                r#"{"remappings": {"/old_cmd_vel": "/cmd_vel", "/ns/**": "/other_ns/**"}}"#,
+                vec![("/old_cmd_vel", "cmd_vel"), ("/ns/a/b", "other_ns/a/b")],
+            ),
+            (
+                r#"{"remappings": {"/navigate_to_pose": "/nav"}}"#,
+                vec![
+                    ("/navigate_to_pose", "nav"),
+                    ("/navigate_to_pose/_action/status", "nav/_action/status"),
+                ],
+            ),
+            (
+                r#"{"scope_out": "dds/site_a", "scope_in": "dds/site_a"}"#,
+                vec![("/cmd_vel", "dds/site_a/cmd_vel")],
+            ),
+            (
+                r#"{"raw_dds_topics": ["Sensor*"]}"#,
+                vec![("@raw/SensorRaw", "raw/SensorRaw")],
+            ),
+            // the characters with a special meaning in key expressions are escaped
+            (
+                r#"{}"#,
+                vec![
+                    ("/a@b", "a%40b"),
+                    ("/cmd*vel", "cmd%2Avel"),
+                    ("/a%2Ab", "a%252Ab"),
+                ],
+            ),
+        ] {
+            let config: Config = serde_json::from_str(json).unwrap();
+            for (ros2_name, key_expr) in conversions {
+                assert_eq!(
+                    ros2_name_to_key_expr(ros2_name, &config).unwrap().as_str(),
+                    key_expr,
+                    "{ros2_name} with {json}"
+                );
+                assert_eq!(
+                    key_expr_to_ros2_name(keyexpr::new(key_expr).unwrap(), &config),
+                    ros2_name,
+                    "{key_expr} with {json}"
+                );
+            }
+        }
+
+        // names with duplicate or trailing '/' are routed as their canonical form
+        let config: Config = serde_json::from_str(r#"{"namespace": "/robot1"}"#).unwrap();
+        assert_eq!(
+            ros2_name_to_key_expr("/ns//odom/", &config)
+                .unwrap()
+                .as_str(),
+            "robot1/ns/odom"
+        );
+        // a key expression not in the namespace is converted as such
+        assert_eq!(
+            key_expr_to_ros2_name(keyexpr::new("robot10/odom").unwrap(), &config),
+            "/robot10/odom"
+        );
+        // "raw/..." is a raw DDS topic only if configured as such
+        assert_eq!(
+            key_expr_to_ros2_name(keyexpr::new("raw/SensorRaw").unwrap(), &config),
+            "/raw/SensorRaw"
+        );
+
+        // invalid names
+        for ros2_name in ["", "cmd_vel", "/", "//", "@raw/"] {
+            assert!(
+                ros2_name_to_key_expr(ros2_name, &config).is_err(),
+                "{ros2_name} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cyclonedds_config_network_interfaces() {
+        let config: Config = serde_json::from_str(r#"{"ros_localhost_only": false}"#).unwrap();
+        assert_eq!(cyclonedds_config_network_interfaces(&config), None);
+
+        let config: Config = serde_json::from_str(r#"{"ros_localhost_only": true}"#).unwrap();
+        assert_eq!(
+            cyclonedds_config_network_interfaces(&config).unwrap(),
+            r#"<CycloneDDS><Domain><General><Interfaces><NetworkInterface address="127.0.0.1"/></Interfaces></General></Domain></CycloneDDS>,"#
+        );
+
+        let config: Config = serde_json::from_str(
+            r#"{"ros_localhost_only": false, "dds_network_interfaces": ["eth0", "192.168.1.10", "fe80::1", "wl<p>"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            cyclonedds_config_network_interfaces(&config).unwrap(),
+            r#"<CycloneDDS><Domain><General><Interfaces><NetworkInterface name="eth0"/><NetworkInterface address="192.168.1.10"/><NetworkInterface address="fe80::1"/><NetworkInterface name="wl&lt;p&gt;"/></Interfaces></General></Domain></CycloneDDS>,"#
+        );
+
+        // localhost only has precedence
+        let config: Config = serde_json::from_str(
+            r#"{"ros_localhost_only": true, "dds_network_interfaces": ["eth0"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            cyclonedds_config_network_interfaces(&config).unwrap(),
+            r#"<CycloneDDS><Domain><General><Interfaces><NetworkInterface address="127.0.0.1"/></Interfaces></General></Domain></CycloneDDS>,"#
+        );
+    }
+}
@@ -13,43 +13,123 @@
 //
 
 use cyclors::qos::{
-    Durability, DurabilityKind, History, HistoryKind, Qos, Reliability, ReliabilityKind,
-    DDS_100MS_DURATION,
+    Deadline, Durability, DurabilityKind, DurabilityService, History, HistoryKind, LatencyBudget,
+    Lifespan, Liveliness, LivelinessKind, Ownership, OwnershipKind, OwnershipStrength, Qos,
+    Reliability, ReliabilityKind, TimeBasedFilter, DDS_100MS_DURATION, DDS_INFINITE_TIME,
 };
 use zenoh::prelude::{keyexpr, OwnedKeyExpr};
 
+use crate::ke_for_sure;
+use crate::qos_helpers::{
+    get_deadline_period, get_exclusive_ownership_strength, get_latency_budget,
+    get_lifespan_duration, get_time_based_filter_separation, is_transient_local,
+};
+use crate::ros2_utils::typed_key_expr;
+
 const SLASH_REPLACEMSNT_CHAR: &str = "§";
 
+/// The latest version of the layout of the liveliness tokens announcing the routes and the alias of a bridge
+/// understood by this bridge, set in their 1st chunk after the plugin_id (e.g. "@ros2_lv/<plugin_id>/v2/MP/...").
+/// The version 1 (without version chunk) is still accepted when parsing.
+/// The version 2 only adds the version chunk to the version 1: this bridge builds its tokens in the version 1
+/// layout (the `*_v1` key expressions), to be discovered by the older bridges which don't parse the version chunk.
+/// A future layout will be built with its version chunk, the bridges of version 2 then warning about it.
+pub const LIVELINESS_FORMAT_VERSION: u32 = 2;
+
 zenoh::kedefine!(
-    // Liveliness tokens key expressions
+    // Liveliness tokens key expressions, as parsed
+    // (a token of version 1 has no ${version} chunk: it's added before parsing - see with_format_version())
     pub ke_liveliness_all: "@ros2_lv/${plugin_id:*}/${remaining:**}",
     pub ke_liveliness_plugin: "@ros2_lv/${plugin_id:*}",
-    pub(crate) ke_liveliness_pub: "@ros2_lv/${plugin_id:*}/MP/${ke:*}/${typ:*}/${qos_ke:*}",
-    pub(crate) ke_liveliness_sub: "@ros2_lv/${plugin_id:*}/MS/${ke:*}/${typ:*}/${qos_ke:*}",
-    pub(crate) ke_liveliness_service_srv: "@ros2_lv/${plugin_id:*}/SS/${ke:*}/${typ:*}",
-    pub(crate) ke_liveliness_service_cli: "@ros2_lv/${plugin_id:*}/SC/${ke:*}/${typ:*}",
-    pub(crate) ke_liveliness_action_srv: "@ros2_lv/${plugin_id:*}/AS/${ke:*}/${typ:*}",
-    pub(crate) ke_liveliness_action_cli: "@ros2_lv/${plugin_id:*}/AC/${ke:*}/${typ:*}",
+    pub(crate) ke_liveliness_alias: "@ros2_lv/${plugin_id:*}/${version:*}/AL/${alias:*}",
+    pub(crate) ke_liveliness_pub: "@ros2_lv/${plugin_id:*}/${version:*}/MP/${ke:*}/${typ:*}/${qos_ke:*}",
+    pub(crate) ke_liveliness_sub: "@ros2_lv/${plugin_id:*}/${version:*}/MS/${ke:*}/${typ:*}/${qos_ke:*}",
+    // in "typed_key_exprs" mode, the ke includes the type chunk
+    pub(crate) ke_liveliness_typed_pub: "@ros2_lv/${plugin_id:*}/${version:*}/TP/${ke:*}/${typ:*}/${qos_ke:*}",
+    pub(crate) ke_liveliness_typed_sub: "@ros2_lv/${plugin_id:*}/${version:*}/TS/${ke:*}/${typ:*}/${qos_ke:*}",
+    pub(crate) ke_liveliness_service_srv: "@ros2_lv/${plugin_id:*}/${version:*}/SS/${ke:*}/${typ:*}",
+    pub(crate) ke_liveliness_service_cli: "@ros2_lv/${plugin_id:*}/${version:*}/SC/${ke:*}/${typ:*}",
+    pub(crate) ke_liveliness_action_srv: "@ros2_lv/${plugin_id:*}/${version:*}/AS/${ke:*}/${typ:*}",
+    pub(crate) ke_liveliness_action_cli: "@ros2_lv/${plugin_id:*}/${version:*}/AC/${ke:*}/${typ:*}",
+);
+
+zenoh::kedefine!(
+    // Liveliness tokens key expressions, as built by this bridge (version 1 - see LIVELINESS_FORMAT_VERSION)
+    ke_liveliness_alias_v1: "@ros2_lv/${plugin_id:*}/AL/${alias:*}",
+    ke_liveliness_pub_v1: "@ros2_lv/${plugin_id:*}/MP/${ke:*}/${typ:*}/${qos_ke:*}",
+    ke_liveliness_sub_v1: "@ros2_lv/${plugin_id:*}/MS/${ke:*}/${typ:*}/${qos_ke:*}",
+    ke_liveliness_typed_pub_v1: "@ros2_lv/${plugin_id:*}/TP/${ke:*}/${typ:*}/${qos_ke:*}",
+    ke_liveliness_typed_sub_v1: "@ros2_lv/${plugin_id:*}/TS/${ke:*}/${typ:*}/${qos_ke:*}",
+    ke_liveliness_service_srv_v1: "@ros2_lv/${plugin_id:*}/SS/${ke:*}/${typ:*}",
+    ke_liveliness_service_cli_v1: "@ros2_lv/${plugin_id:*}/SC/${ke:*}/${typ:*}",
+    ke_liveliness_action_srv_v1: "@ros2_lv/${plugin_id:*}/AS/${ke:*}/${typ:*}",
+    ke_liveliness_action_cli_v1: "@ros2_lv/${plugin_id:*}/AC/${ke:*}/${typ:*}",
 );
 
+pub(crate) fn new_ke_liveliness_alias(
+    plugin_id: &keyexpr,
+    alias: &str,
+) -> Result<OwnedKeyExpr, String> {
+    let alias = keyexpr::new(alias)
+        .ok()
+        .filter(|ke| !ke.as_str().contains('/') && !ke.is_wild())
+        .ok_or_else(|| {
+            format!(
+                "invalid alias \"{alias}\": must be a single chunk without '*', '$', '?' or '#'"
+            )
+        })?;
+    zenoh::keformat!(ke_liveliness_alias_v1::formatter(), plugin_id, alias)
+        .map_err(|e| e.to_string())
+}
+
+pub(crate) fn parse_ke_liveliness_alias(ke: &keyexpr) -> Result<(OwnedKeyExpr, String), String> {
+    let versioned = with_format_version(ke);
+    let parsed = ke_liveliness_alias::parse(&versioned)
+        .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
+    check_format_version(parsed.version())
+        .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
+    Ok((parsed.plugin_id().to_owned(), parsed.alias().to_string()))
+}
+
 pub(crate) fn new_ke_liveliness_pub(
     plugin_id: &keyexpr,
     zenoh_key_expr: &keyexpr,
     ros2_type: &str,
     keyless: bool,
     qos: &Qos,
+    typed: bool,
 ) -> Result<OwnedKeyExpr, String> {
-    let ke = escape_slashes(zenoh_key_expr);
     let typ = escape_slashes(ros2_type);
     let qos_ke = qos_to_key_expr(keyless, qos);
-    zenoh::keformat!(ke_liveliness_pub::formatter(), plugin_id, ke, typ, qos_ke)
-        .map_err(|e| e.to_string())
+    if typed {
+        let ke = escape_slashes(&typed_key_expr(zenoh_key_expr, ros2_type)?);
+        zenoh::keformat!(
+            ke_liveliness_typed_pub_v1::formatter(),
+            plugin_id,
+            ke,
+            typ,
+            qos_ke
+        )
+    } else {
+        let ke = escape_slashes(zenoh_key_expr);
+        zenoh::keformat!(
+            ke_liveliness_pub_v1::formatter(),
+            plugin_id,
+            ke,
+            typ,
+            qos_ke
+        )
+    }
+    .map_err(|e| e.to_string())
 }
 
 pub(crate) fn parse_ke_liveliness_pub(
     ke: &keyexpr,
 ) -> Result<(OwnedKeyExpr, OwnedKeyExpr, String, bool, Qos), String> {
-    let parsed = ke_liveliness_pub::parse(ke)
+    let versioned = with_format_version(ke);
+    let parsed = ke_liveliness_pub::parse(&versioned)
+        .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
+    check_format_version(parsed.version())
         .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
     let plugin_id = parsed.plugin_id().to_owned();
     let zenoh_key_expr = unescape_slashes(parsed.ke());
@@ -65,24 +145,71 @@ pub(crate) fn parse_ke_liveliness_pub(
     ))
 }
 
+pub(crate) fn parse_ke_liveliness_typed_pub(
+    ke: &keyexpr,
+) -> Result<(OwnedKeyExpr, OwnedKeyExpr, String, bool, Qos), String> {
+    let versioned = with_format_version(ke);
+    let parsed = ke_liveliness_typed_pub::parse(&versioned)
+        .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
+    check_format_version(parsed.version())
+        .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
+    let plugin_id = parsed.plugin_id().to_owned();
+    let zenoh_key_expr = strip_type_chunk(parsed.ke(), parsed.typ()).ok_or_else(|| {
+        format!(
+            "failed to parse liveliness keyexpr {ke}: the key expression doesn't end with the type"
+        )
+    })?;
+    let ros2_type = unescape_slashes(parsed.typ());
+    let (keyless, qos) = key_expr_to_qos(parsed.qos_ke())
+        .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
+    Ok((
+        plugin_id,
+        zenoh_key_expr,
+        ros2_type.to_string(),
+        keyless,
+        qos,
+    ))
+}
+
 pub(crate) fn new_ke_liveliness_sub(
     plugin_id: &keyexpr,
     zenoh_key_expr: &keyexpr,
     ros2_type: &str,
     keyless: bool,
     qos: &Qos,
+    typed: bool,
 ) -> Result<OwnedKeyExpr, String> {
-    let ke = escape_slashes(zenoh_key_expr);
     let typ = escape_slashes(ros2_type);
     let qos_ke = qos_to_key_expr(keyless, qos);
-    zenoh::keformat!(ke_liveliness_sub::formatter(), plugin_id, ke, typ, qos_ke)
-        .map_err(|e| e.to_string())
+    if typed {
+        let ke = escape_slashes(&typed_key_expr(zenoh_key_expr, ros2_type)?);
+        zenoh::keformat!(
+            ke_liveliness_typed_sub_v1::formatter(),
+            plugin_id,
+            ke,
+            typ,
+            qos_ke
+        )
+    } else {
+        let ke = escape_slashes(zenoh_key_expr);
+        zenoh::keformat!(
+            ke_liveliness_sub_v1::formatter(),
+            plugin_id,
+            ke,
+            typ,
+            qos_ke
+        )
+    }
+    .map_err(|e| e.to_string())
 }
 
 pub(crate) fn parse_ke_liveliness_sub(
     ke: &keyexpr,
 ) -> Result<(OwnedKeyExpr, OwnedKeyExpr, String, bool, Qos), String> {
-    let parsed = ke_liveliness_sub::parse(ke)
+    let versioned = with_format_version(ke);
+    let parsed = ke_liveliness_sub::parse(&versioned)
+        .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
+    check_format_version(parsed.version())
         .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
     let plugin_id = parsed.plugin_id().to_owned();
     let zenoh_key_expr = unescape_slashes(parsed.ke());
@@ -98,6 +225,32 @@ pub(crate) fn parse_ke_liveliness_sub(
     ))
 }
 
+pub(crate) fn parse_ke_liveliness_typed_sub(
+    ke: &keyexpr,
+) -> Result<(OwnedKeyExpr, OwnedKeyExpr, String, bool, Qos), String> {
+    let versioned = with_format_version(ke);
+    let parsed = ke_liveliness_typed_sub::parse(&versioned)
+        .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
+    check_format_version(parsed.version())
+        .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
+    let plugin_id = parsed.plugin_id().to_owned();
+    let zenoh_key_expr = strip_type_chunk(parsed.ke(), parsed.typ()).ok_or_else(|| {
+        format!(
+            "failed to parse liveliness keyexpr {ke}: the key expression doesn't end with the type"
+        )
+    })?;
+    let ros2_type = unescape_slashes(parsed.typ());
+    let (keyless, qos) = key_expr_to_qos(parsed.qos_ke())
+        .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
+    Ok((
+        plugin_id,
+        zenoh_key_expr,
+        ros2_type.to_string(),
+        keyless,
+        qos,
+    ))
+}
+
 pub(crate) fn new_ke_liveliness_service_srv(
     plugin_id: &keyexpr,
     zenoh_key_expr: &keyexpr,
@@ -105,14 +258,22 @@ pub(crate) fn new_ke_liveliness_service_srv(
 ) -> Result<OwnedKeyExpr, String> {
     let ke = escape_slashes(zenoh_key_expr);
     let typ = escape_slashes(ros2_type);
-    zenoh::keformat!(ke_liveliness_service_srv::formatter(), plugin_id, ke, typ)
-        .map_err(|e| e.to_string())
+    zenoh::keformat!(
+        ke_liveliness_service_srv_v1::formatter(),
+        plugin_id,
+        ke,
+        typ
+    )
+    .map_err(|e| e.to_string())
 }
 
 pub(crate) fn parse_ke_liveliness_service_srv(
     ke: &keyexpr,
 ) -> Result<(OwnedKeyExpr, OwnedKeyExpr, String), String> {
-    let parsed = ke_liveliness_service_srv::parse(ke)
+    let versioned = with_format_version(ke);
+    let parsed = ke_liveliness_service_srv::parse(&versioned)
+        .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
+    check_format_version(parsed.version())
         .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
     let plugin_id = parsed.plugin_id().to_owned();
     let zenoh_key_expr = unescape_slashes(parsed.ke());
@@ -127,14 +288,22 @@ pub(crate) fn new_ke_liveliness_service_cli(
 ) -> Result<OwnedKeyExpr, String> {
     let ke = escape_slashes(zenoh_key_expr);
     let typ = escape_slashes(ros2_type);
-    zenoh::keformat!(ke_liveliness_service_cli::formatter(), plugin_id, ke, typ)
-        .map_err(|e| e.to_string())
+    zenoh::keformat!(
+        ke_liveliness_service_cli_v1::formatter(),
+        plugin_id,
+        ke,
+        typ
+    )
+    .map_err(|e| e.to_string())
 }
 
 pub(crate) fn parse_ke_liveliness_service_cli(
     ke: &keyexpr,
 ) -> Result<(OwnedKeyExpr, OwnedKeyExpr, String), String> {
-    let parsed = ke_liveliness_service_cli::parse(ke)
+    let versioned = with_format_version(ke);
+    let parsed = ke_liveliness_service_cli::parse(&versioned)
+        .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
+    check_format_version(parsed.version())
         .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
     let plugin_id = parsed.plugin_id().to_owned();
     let zenoh_key_expr = unescape_slashes(parsed.ke());
@@ -149,14 +318,17 @@ pub(crate) fn new_ke_liveliness_action_srv(
 ) -> Result<OwnedKeyExpr, String> {
     let ke = escape_slashes(zenoh_key_expr);
     let typ = escape_slashes(ros2_type);
-    zenoh::keformat!(ke_liveliness_action_srv::formatter(), plugin_id, ke, typ)
+    zenoh::keformat!(ke_liveliness_action_srv_v1::formatter(), plugin_id, ke, typ)
         .map_err(|e| e.to_string())
 }
 
 pub(crate) fn parse_ke_liveliness_action_srv(
     ke: &keyexpr,
 ) -> Result<(OwnedKeyExpr, OwnedKeyExpr, String), String> {
-    let parsed = ke_liveliness_action_srv::parse(ke)
+    let versioned = with_format_version(ke);
+    let parsed = ke_liveliness_action_srv::parse(&versioned)
+        .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
+    check_format_version(parsed.version())
         .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
     let plugin_id = parsed.plugin_id().to_owned();
     let zenoh_key_expr = unescape_slashes(parsed.ke());
@@ -171,14 +343,17 @@ pub(crate) fn new_ke_liveliness_action_cli(
 ) -> Result<OwnedKeyExpr, String> {
     let ke = escape_slashes(zenoh_key_expr);
     let typ = escape_slashes(ros2_type);
-    zenoh::keformat!(ke_liveliness_action_cli::formatter(), plugin_id, ke, typ)
+    zenoh::keformat!(ke_liveliness_action_cli_v1::formatter(), plugin_id, ke, typ)
         .map_err(|e| e.to_string())
 }
 
 pub(crate) fn parse_ke_liveliness_action_cli(
     ke: &keyexpr,
 ) -> Result<(OwnedKeyExpr, OwnedKeyExpr, String), String> {
-    let parsed = ke_liveliness_action_cli::parse(ke)
+    let versioned = with_format_version(ke);
+    let parsed = ke_liveliness_action_cli::parse(&versioned)
+        .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
+    check_format_version(parsed.version())
         .map_err(|e| format!("failed to parse liveliness keyexpr {ke}: {e}"))?;
     let plugin_id = parsed.plugin_id().to_owned();
     let zenoh_key_expr = unescape_slashes(parsed.ke());
@@ -186,6 +361,72 @@ pub(crate) fn parse_ke_liveliness_action_cli(
     Ok((plugin_id, zenoh_key_expr, ros2_type.to_string()))
 }
 
+/// For the liveliness token of a remote route or alias (i.e. the part following the plugin_id):
+/// the version of its layout and the part following the version chunk (unchanged for a version 1 token).
+pub(crate) fn split_format_version(remaining: &str) -> (u32, &str) {
+    remaining
+        .split_once('/')
+        .and_then(|(chunk, rest)| Some((parse_format_version(chunk)?, rest)))
+        .unwrap_or((1, remaining))
+}
+
+// The version of a version chunk (e.g. 2 for "v2")
+fn parse_format_version(chunk: &str) -> Option<u32> {
+    chunk
+        .strip_prefix('v')
+        .filter(|v| !v.is_empty() && v.bytes().all(|b| b.is_ascii_digit()))?
+        .parse()
+        .ok()
+}
+
+// Add the version chunk to a liveliness token of version 1, so it can be parsed as the current layout
+fn with_format_version(ke: &keyexpr) -> OwnedKeyExpr {
+    match ke_liveliness_all::parse(ke) {
+        Ok(parsed) => match parsed.remaining() {
+            Some(remaining)
+                if split_format_version(remaining.as_str()).1.len() == remaining.len() =>
+            {
+                let plugin_id = parsed.plugin_id();
+                let remaining = ke_for_sure!("v1") / remaining;
+                zenoh::keformat!(ke_liveliness_all::formatter(), plugin_id, remaining)
+                    .unwrap_or_else(|_| ke.to_owned())
+            }
+            _ => ke.to_owned(),
+        },
+        Err(_) => ke.to_owned(),
+    }
+}
+
+// Check that the version chunk of a parsed liveliness token is supported (i.e. not newer)
+fn check_format_version(chunk: &keyexpr) -> Result<(), String> {
+    match parse_format_version(chunk) {
+        Some(v) if v <= LIVELINESS_FORMAT_VERSION => Ok(()),
+        Some(v) => Err(format!(
+            "unsupported format version {v} (this bridge supports up to {LIVELINESS_FORMAT_VERSION})"
+        )),
+        None => Err(format!("invalid format version chunk '{chunk}'")),
+    }
+}
+
+/// For the liveliness token of a remote route (i.e. the part following the plugin_id),
+/// if it's for a Publisher or a Subscriber: whether it's in "typed_key_exprs" mode or not.
+pub(crate) fn is_typed_liveliness_token(remaining: &str) -> Option<bool> {
+    match remaining.get(..3) {
+        Some("MP/" | "MS/") => Some(false),
+        Some("TP/" | "TS/") => Some(true),
+        _ => None,
+    }
+}
+
+// Remove the type chunk of a typed key expression, both being escaped (i.e. "<ke>§<typ>")
+fn strip_type_chunk(ke: &keyexpr, typ: &keyexpr) -> Option<OwnedKeyExpr> {
+    let ke = ke
+        .as_str()
+        .strip_suffix(typ.as_str())?
+        .strip_suffix(SLASH_REPLACEMSNT_CHAR)?;
+    keyexpr::new(ke).ok().map(unescape_slashes)
+}
+
 fn escape_slashes(s: &str) -> OwnedKeyExpr {
     OwnedKeyExpr::try_from(s.replace('/', SLASH_REPLACEMSNT_CHAR)).unwrap()
 }
@@ -198,8 +439,13 @@ fn unescape_slashes(ke: &keyexpr) -> OwnedKeyExpr {
 // NOTE: only significant Qos for ROS2 are serialized
 // See https://docs.ros.org/en/rolling/Concepts/Intermediate/About-Quality-of-Service-Settings.html
 //
-// format: "<keyless>:<ReliabilityKind>:<DurabilityKind>:<HistoryKid>,<HistoryDepth>"
-// where each element is "" if default QoS, or an integer in case of enum, and 'K' for !keyless
+// format: "<keyless>:<ReliabilityKind>:<DurabilityKind>:<HistoryKid>,<HistoryDepth>[:<DeadlinePeriod>[:<LifespanDuration>[:<LivelinessKind>,<LeaseDuration>[:<Partitions>[:<OwnershipKind>,<OwnershipStrength>[:<DurabilityService>]]]]]]"
+// where each element is "" if default QoS, or an integer in case of enum, and 'K' for !keyless.
+// The durations are in nanoseconds (the lease duration being "" if infinite). The Partitions are
+// separated by ',' with their characters other than [A-Za-z0-9_.-] percent-encoded. The DurabilityService of a
+// TRANSIENT_LOCAL Writer is "<HistoryKind>,<HistoryDepth>,<MaxSamples>,<MaxInstances>,<MaxSamplesPerInstance>"
+// (its service_cleanup_delay being irrelevant for TRANSIENT_LOCAL). The optional elements
+// are added only up to the last one that is set, not to change the format for the other QoS.
 pub fn qos_to_key_expr(keyless: bool, qos: &Qos) -> OwnedKeyExpr {
     use std::io::Write;
     let mut w: Vec<u8> = Vec::new();
@@ -219,6 +465,59 @@ pub fn qos_to_key_expr(keyless: bool, qos: &Qos) -> OwnedKeyExpr {
     if let Some(History { kind, depth }) = &qos.history {
         write!(&mut w, "{},{}", *kind as isize, depth).unwrap();
     }
+    let mut optionals: Vec<String> = vec![
+        get_deadline_period(qos).map_or(String::new(), |p| p.as_nanos().to_string()),
+        get_lifespan_duration(qos).map_or(String::new(), |d| d.as_nanos().to_string()),
+        match &qos.liveliness {
+            // the default Liveliness is AUTOMATIC with an infinite lease
+            Some(Liveliness {
+                kind,
+                lease_duration,
+            }) if *kind != LivelinessKind::AUTOMATIC || *lease_duration != DDS_INFINITE_TIME => {
+                match *lease_duration {
+                    DDS_INFINITE_TIME => format!("{},", *kind as isize),
+                    lease => format!("{},{lease}", *kind as isize),
+                }
+            }
+            _ => String::new(),
+        },
+        match &qos.partition {
+            // the default partition is a single empty name
+            Some(partitions) if partitions.iter().any(|p| !p.is_empty()) => partitions
+                .iter()
+                .map(|p| encode_partition(p))
+                .collect::<Vec<String>>()
+                .join(","),
+            _ => String::new(),
+        },
+        // only an EXCLUSIVE Ownership (the default being SHARED)
+        match get_exclusive_ownership_strength(qos) {
+            Some(strength) => format!("{},{strength}", OwnershipKind::EXCLUSIVE as isize),
+            None => String::new(),
+        },
+        // only for a TRANSIENT_LOCAL Writer, as sizing its historical data
+        match &qos.durability_service {
+            Some(ds) if is_transient_local(qos) => format!(
+                "{},{},{},{},{}",
+                ds.history_kind as isize,
+                ds.history_depth,
+                ds.max_samples,
+                ds.max_instances,
+                ds.max_samples_per_instance
+            ),
+            _ => String::new(),
+        },
+        // only for a Reader
+        get_time_based_filter_separation(qos).map_or(String::new(), |s| s.as_nanos().to_string()),
+        // only if not zero (the default)
+        get_latency_budget(qos).map_or(String::new(), |l| l.as_nanos().to_string()),
+    ];
+    while optionals.last().map_or(false, String::is_empty) {
+        optionals.pop();
+    }
+    for optional in optionals {
+        write!(w, ":{optional}").unwrap();
+    }
 
     unsafe {
         let s: String = String::from_utf8_unchecked(w);
@@ -228,8 +527,8 @@ pub fn qos_to_key_expr(keyless: bool, qos: &Qos) -> OwnedKeyExpr {
 
 fn key_expr_to_qos(ke: &keyexpr) -> Result<(bool, Qos), String> {
     let elts: Vec<&str> = ke.split(':').collect();
-    if elts.len() != 4 {
-        return Err(format!("Internal Error: unexpected QoS expression: '{ke}' - 4 elements between : were expected"));
+    if !(4..=12).contains(&elts.len()) {
+        return Err(format!("Internal Error: unexpected QoS expression: '{ke}' - 4 to 12 elements between : were expected"));
     }
     let mut qos = Qos::default();
     let keyless = elts[0].is_empty();
@@ -256,11 +555,396 @@ fn key_expr_to_qos(ke: &keyexpr) -> Result<(bool, Qos), String> {
             _ => return Err(format!("Internal Error: unexpected QoS expression: '{ke}' - failed to parse History in 4th element")),
         }
     }
+    if let Some(period) = elts.get(4).filter(|s| !s.is_empty()) {
+        match period.parse::<i64>() {
+            Ok(period) if period > 0 => qos.deadline = Some(Deadline { period }),
+            _ => return Err(format!("Internal Error: unexpected QoS expression: '{ke}' - failed to parse Deadline in 5th element")),
+        }
+    }
+    if let Some(duration) = elts.get(5).filter(|s| !s.is_empty()) {
+        match duration.parse::<i64>() {
+            Ok(duration) if duration > 0 => qos.lifespan = Some(Lifespan { duration }),
+            _ => return Err(format!("Internal Error: unexpected QoS expression: '{ke}' - failed to parse Lifespan in 6th element")),
+        }
+    }
+    if let Some(liveliness) = elts.get(6).filter(|s| !s.is_empty()) {
+        match liveliness.split_once(',').map(|(s1, s2)|
+            (
+                s1.parse::<cyclors::dds_liveliness_kind_t>(),
+                if s2.is_empty() { Ok(DDS_INFINITE_TIME) } else { s2.parse::<i64>() },
+            )
+        ) {
+            Some((Ok(k), Ok(lease_duration))) if lease_duration > 0 => qos.liveliness = Some(Liveliness {kind: LivelinessKind::from(&k), lease_duration }),
+            _ => return Err(format!("Internal Error: unexpected QoS expression: '{ke}' - failed to parse Liveliness in 7th element")),
+        }
+    }
+    if let Some(partitions) = elts.get(7).filter(|s| !s.is_empty()) {
+        match partitions.split(',').map(decode_partition).collect() {
+            Some(partitions) => qos.partition = Some(partitions),
+            None => return Err(format!("Internal Error: unexpected QoS expression: '{ke}' - failed to parse Partitions in 8th element")),
+        }
+    }
+    if let Some(ownership) = elts.get(8).filter(|s| !s.is_empty()) {
+        match ownership.split_once(',').map(|(s1, s2)|
+            (
+                s1.parse::<cyclors::dds_ownership_kind_t>(),
+                s2.parse::<i32>(),
+            )
+        ) {
+            Some((Ok(k), Ok(value))) => {
+                qos.ownership = Some(Ownership { kind: OwnershipKind::from(&k) });
+                qos.ownership_strength = Some(OwnershipStrength { value });
+            }
+            _ => return Err(format!("Internal Error: unexpected QoS expression: '{ke}' - failed to parse Ownership in 9th element")),
+        }
+    }
+    if let Some(durability_service) = elts.get(9).filter(|s| !s.is_empty()) {
+        let values: Vec<&str> = durability_service.split(',').collect();
+        match (
+            values.len(),
+            values[0].parse::<cyclors::dds_history_kind_t>(),
+            values[1..].iter().map(|s| s.parse::<i32>()).collect::<Result<Vec<i32>, _>>(),
+        ) {
+            (5, Ok(k), Ok(v)) => qos.durability_service = Some(DurabilityService {
+                service_cleanup_delay: 0,
+                history_kind: HistoryKind::from(&k),
+                history_depth: v[0],
+                max_samples: v[1],
+                max_instances: v[2],
+                max_samples_per_instance: v[3],
+            }),
+            _ => return Err(format!("Internal Error: unexpected QoS expression: '{ke}' - failed to parse DurabilityService in 10th element")),
+        }
+    }
+    if let Some(separation) = elts.get(10).filter(|s| !s.is_empty()) {
+        match separation.parse::<i64>() {
+            Ok(minimum_separation) if minimum_separation > 0 => qos.time_based_filter = Some(TimeBasedFilter { minimum_separation }),
+            _ => return Err(format!("Internal Error: unexpected QoS expression: '{ke}' - failed to parse TimeBasedFilter in 11th element")),
+        }
+    }
+    if let Some(duration) = elts.get(11).filter(|s| !s.is_empty()) {
+        match duration.parse::<i64>() {
+            Ok(duration) if duration > 0 => qos.latency_budget = Some(LatencyBudget { duration }),
+            _ => return Err(format!("Internal Error: unexpected QoS expression: '{ke}' - failed to parse LatencyBudget in 12th element")),
+        }
+    }
 
     Ok((keyless, qos))
 }
 
+// Percent-encode the characters of a DDS Partition name other than [A-Za-z0-9_.-]
+// (e.g. the ',' separator, the ':' of the QoS elements or the '*' wildcard)
+fn encode_partition(partition: &str) -> String {
+    let mut result = String::with_capacity(partition.len());
+    for b in partition.bytes() {
+        if b.is_ascii_alphanumeric() || b == b'_' || b == b'.' || b == b'-' {
+            result.push(b as char);
+        } else {
+            result.push_str(&format!("%{b:02X}"));
+        }
+    }
+    result
+}
+
+// The inverse of encode_partition(), or None if not a valid encoding
+fn decode_partition(s: &str) -> Option<String> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            let hex = tail
+                .get(..2)
+                .filter(|h| h.iter().all(u8::is_ascii_hexdigit))?;
+            bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
 mod tests {
+    #[test]
+    fn test_alias_key_expr() {
+        use super::*;
+        use crate::ke_for_sure;
+
+        let plugin_id = ke_for_sure!("0123456789abcdef");
+        let ke = new_ke_liveliness_alias(plugin_id, "robot-07").unwrap();
+        assert_eq!(ke.as_str(), "@ros2_lv/0123456789abcdef/AL/robot-07");
+        assert_eq!(
+            parse_ke_liveliness_alias(&ke),
+            Ok((plugin_id.to_owned(), "robot-07".to_string()))
+        );
+        // an alias token is not a plugin token
+        assert!(ke_liveliness_plugin::parse(&ke).is_err());
+
+        assert!(new_ke_liveliness_alias(plugin_id, "robot/07").is_err());
+        assert!(new_ke_liveliness_alias(plugin_id, "robot*").is_err());
+        assert!(new_ke_liveliness_alias(plugin_id, "").is_err());
+    }
+
+    #[test]
+    fn test_typed_key_expr() {
+        use super::*;
+        use crate::ke_for_sure;
+
+        let plugin_id = ke_for_sure!("0123456789abcdef");
+        let typ = "geometry_msgs/msg/Twist";
+        let q = Qos::default();
+        for zenoh_key_expr in ["cmd_vel", "robot1/cmd_vel"] {
+            let ke = ke_for_sure!(zenoh_key_expr);
+            let expected = (
+                plugin_id.to_owned(),
+                ke.to_owned(),
+                typ.to_string(),
+                true,
+                q.clone(),
+            );
+
+            // without the mode: usual token format
+            let untyped = new_ke_liveliness_pub(plugin_id, ke, typ, true, &q, false).unwrap();
+            assert_eq!(
+                untyped.as_str(),
+                format!(
+                    "@ros2_lv/0123456789abcdef/MP/{}/geometry_msgs§msg§Twist/:::",
+                    zenoh_key_expr.replace('/', "§")
+                )
+            );
+            assert_eq!(parse_ke_liveliness_pub(&untyped), Ok(expected.clone()));
+
+            // with the mode: the type chunk is part of the key expression, and stripped on parsing
+            let typed = new_ke_liveliness_pub(plugin_id, ke, typ, true, &q, true).unwrap();
+            assert_eq!(
+                typed.as_str(),
+                format!(
+                    "@ros2_lv/0123456789abcdef/TP/{}§geometry_msgs§msg§Twist/geometry_msgs§msg§Twist/:::",
+                    zenoh_key_expr.replace('/', "§")
+                )
+            );
+            assert_eq!(parse_ke_liveliness_typed_pub(&typed), Ok(expected.clone()));
+            let typed = new_ke_liveliness_sub(plugin_id, ke, typ, true, &q, true).unwrap();
+            assert_eq!(parse_ke_liveliness_typed_sub(&typed), Ok(expected));
+
+            // a mismatch of mode is detected from the tokens format
+            assert!(parse_ke_liveliness_pub(&typed).is_err());
+            assert!(parse_ke_liveliness_typed_pub(&untyped).is_err());
+        }
+
+        assert_eq!(is_typed_liveliness_token("MP/cmd_vel/x/:::"), Some(false));
+        assert_eq!(is_typed_liveliness_token("MS/cmd_vel/x/:::"), Some(false));
+        assert_eq!(is_typed_liveliness_token("TP/cmd_vel§x/x/:::"), Some(true));
+        assert_eq!(is_typed_liveliness_token("TS/cmd_vel§x/x/:::"), Some(true));
+        assert_eq!(is_typed_liveliness_token("SS/add_two_ints/x"), None);
+        assert_eq!(is_typed_liveliness_token("AL"), None);
+
+        // a typed token which key expression doesn't end with the type is invalid
+        let ke = ke_for_sure!(
+            "@ros2_lv/0123456789abcdef/TP/cmd_vel§std_msgs§msg§String/geometry_msgs§msg§Twist/:::"
+        );
+        assert!(parse_ke_liveliness_typed_pub(ke).is_err());
+        let ke = ke_for_sure!(
+            "@ros2_lv/0123456789abcdef/TP/geometry_msgs§msg§Twist/geometry_msgs§msg§Twist/:::"
+        );
+        assert!(parse_ke_liveliness_typed_pub(ke).is_err());
+    }
+
+    #[test]
+    fn test_escaped_key_expr() {
+        use super::*;
+        use crate::ke_for_sure;
+        use crate::ros2_utils::{key_expr_to_ros2_name, ros2_name_to_key_expr};
+
+        // the names with characters escaped in key expressions are restored from the tokens
+        let plugin_id = ke_for_sure!("0123456789abcdef");
+        let typ = "std_msgs/msg/String";
+        let q = Qos::default();
+        for name in [
+            "/vendor@stack/status",
+            "/@x",
+            "/price$/eur",
+            "/what?",
+            "/tag#1",
+            "/a§b/c",
+        ] {
+            let zenoh_key_expr = ros2_name_to_key_expr(name, "/", &[], &[]);
+            let ke =
+                new_ke_liveliness_pub(plugin_id, &zenoh_key_expr, typ, true, &q, false).unwrap();
+            let (_, parsed_ke, ..) = parse_ke_liveliness_pub(&ke).unwrap();
+            assert_eq!(parsed_ke, zenoh_key_expr);
+            assert_eq!(key_expr_to_ros2_name(&parsed_ke, "/", &[], &[]), name);
+
+            let ke = new_ke_liveliness_service_srv(plugin_id, &zenoh_key_expr, typ).unwrap();
+            let (_, parsed_ke, _) = parse_ke_liveliness_service_srv(&ke).unwrap();
+            assert_eq!(key_expr_to_ros2_name(&parsed_ke, "/", &[], &[]), name);
+        }
+    }
+
+    #[test]
+    fn test_format_version() {
+        use super::*;
+        use crate::ke_for_sure;
+
+        let plugin_id = ke_for_sure!("0123456789abcdef");
+        let typ = "geometry_msgs/msg/Twist";
+        let q = Qos::default();
+
+        // the tokens built by this bridge are of version 1 (without version chunk)
+        let ke = new_ke_liveliness_pub(plugin_id, ke_for_sure!("cmd_vel"), typ, true, &q, false)
+            .unwrap();
+        let remaining = ke
+            .as_str()
+            .strip_prefix("@ros2_lv/0123456789abcdef/")
+            .unwrap();
+        assert_eq!(
+            split_format_version(remaining),
+            (1, "MP/cmd_vel/geometry_msgs§msg§Twist/:::")
+        );
+        assert_eq!(remaining, "MP/cmd_vel/geometry_msgs§msg§Twist/:::");
+        assert_eq!(split_format_version("AL/robot-07"), (1, "AL/robot-07"));
+        assert_eq!(split_format_version("v12/MP/x/y/:::"), (12, "MP/x/y/:::"));
+        assert_eq!(split_format_version("v/MP/x/y/:::"), (1, "v/MP/x/y/:::"));
+        assert_eq!(
+            split_format_version("v+2/MP/x/y/:::"),
+            (1, "v+2/MP/x/y/:::")
+        );
+
+        // tokens of version 1 (without version chunk), as announced by older bridges
+        let expected = (
+            plugin_id.to_owned(),
+            ke_for_sure!("robot1/cmd_vel").to_owned(),
+            typ.to_string(),
+            false,
+            Qos {
+                reliability: Some(Reliability {
+                    kind: ReliabilityKind::RELIABLE,
+                    max_blocking_time: DDS_100MS_DURATION,
+                }),
+                ..Default::default()
+            },
+        );
+        let v1 = ke_for_sure!(
+            "@ros2_lv/0123456789abcdef/MP/robot1§cmd_vel/geometry_msgs§msg§Twist/K:1::"
+        );
+        assert_eq!(parse_ke_liveliness_pub(v1), Ok(expected.clone()));
+        let v1 = ke_for_sure!(
+            "@ros2_lv/0123456789abcdef/MS/robot1§cmd_vel/geometry_msgs§msg§Twist/K:1::"
+        );
+        assert_eq!(parse_ke_liveliness_sub(v1), Ok(expected.clone()));
+        let v1 = ke_for_sure!(
+            "@ros2_lv/0123456789abcdef/SS/add_two_ints/example_interfaces§srv§AddTwoInts"
+        );
+        assert_eq!(
+            parse_ke_liveliness_service_srv(v1),
+            Ok((
+                plugin_id.to_owned(),
+                ke_for_sure!("add_two_ints").to_owned(),
+                "example_interfaces/srv/AddTwoInts".to_string()
+            ))
+        );
+        let v1 = ke_for_sure!(
+            "@ros2_lv/0123456789abcdef/AC/fibonacci/example_interfaces§action§Fibonacci"
+        );
+        assert!(parse_ke_liveliness_action_cli(v1).is_ok());
+        let v1 = ke_for_sure!("@ros2_lv/0123456789abcdef/AL/robot-07");
+        assert_eq!(
+            parse_ke_liveliness_alias(v1),
+            Ok((plugin_id.to_owned(), "robot-07".to_string()))
+        );
+
+        // the same tokens of the version 2, re-built in the version 1 layout
+        let v2 = ke_for_sure!(
+            "@ros2_lv/0123456789abcdef/v2/MP/robot1§cmd_vel/geometry_msgs§msg§Twist/K:1::"
+        );
+        assert_eq!(parse_ke_liveliness_pub(v2), Ok(expected));
+        assert_eq!(
+            new_ke_liveliness_pub(
+                plugin_id,
+                ke_for_sure!("robot1/cmd_vel"),
+                typ,
+                false,
+                &parse_ke_liveliness_pub(v2).unwrap().4,
+                false
+            )
+            .unwrap()
+            .as_str(),
+            "@ros2_lv/0123456789abcdef/MP/robot1§cmd_vel/geometry_msgs§msg§Twist/K:1::"
+        );
+
+        // a newer version is rejected, even with a known layout
+        let v3 = ke_for_sure!(
+            "@ros2_lv/0123456789abcdef/v3/MP/robot1§cmd_vel/geometry_msgs§msg§Twist/K:1::"
+        );
+        let err = parse_ke_liveliness_pub(v3).unwrap_err();
+        assert!(err.contains("unsupported format version 3"));
+        // the kind of interface is not mistaken for a version
+        assert!(parse_ke_liveliness_sub(ke_for_sure!(
+            "@ros2_lv/0123456789abcdef/v2/MP/robot1§cmd_vel/geometry_msgs§msg§Twist/K:1::"
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_v1_parser_accepts_emitted_tokens() {
+        use super::*;
+        use crate::ke_for_sure;
+
+        // The liveliness tokens layout of the bridges not supporting the version chunk
+        zenoh::kedefine!(
+            ke_v1_pub: "@ros2_lv/${plugin_id:*}/MP/${ke:*}/${typ:*}/${qos_ke:*}",
+            ke_v1_sub: "@ros2_lv/${plugin_id:*}/MS/${ke:*}/${typ:*}/${qos_ke:*}",
+            ke_v1_service_srv: "@ros2_lv/${plugin_id:*}/SS/${ke:*}/${typ:*}",
+            ke_v1_service_cli: "@ros2_lv/${plugin_id:*}/SC/${ke:*}/${typ:*}",
+            ke_v1_action_srv: "@ros2_lv/${plugin_id:*}/AS/${ke:*}/${typ:*}",
+            ke_v1_action_cli: "@ros2_lv/${plugin_id:*}/AC/${ke:*}/${typ:*}",
+        );
+
+        // a mixed fleet: the tokens built by this bridge are discovered by the bridges parsing the version 1 only
+        let plugin_id = ke_for_sure!("0123456789abcdef");
+        let ke = ke_for_sure!("robot1/cmd_vel");
+        let typ = "geometry_msgs/msg/Twist";
+        let qos = Qos {
+            reliability: Some(Reliability {
+                kind: ReliabilityKind::RELIABLE,
+                max_blocking_time: DDS_100MS_DURATION,
+            }),
+            ..Default::default()
+        };
+        let token = new_ke_liveliness_pub(plugin_id, ke, typ, false, &qos, false).unwrap();
+        let parsed = ke_v1_pub::parse(&token).unwrap();
+        assert_eq!(parsed.plugin_id(), plugin_id);
+        assert_eq!(parsed.ke().as_str(), "robot1§cmd_vel");
+        assert_eq!(parsed.typ().as_str(), "geometry_msgs§msg§Twist");
+        assert_eq!(parsed.qos_ke().as_str(), "K:1::");
+        let token = new_ke_liveliness_sub(plugin_id, ke, typ, true, &qos, false).unwrap();
+        let parsed = ke_v1_sub::parse(&token).unwrap();
+        assert_eq!(parsed.ke().as_str(), "robot1§cmd_vel");
+        assert_eq!(parsed.qos_ke().as_str(), ":1::");
+
+        let typ = "example_interfaces/srv/AddTwoInts";
+        let token = new_ke_liveliness_service_srv(plugin_id, ke, typ).unwrap();
+        assert_eq!(
+            ke_v1_service_srv::parse(&token).unwrap().typ().as_str(),
+            "example_interfaces§srv§AddTwoInts"
+        );
+        let token = new_ke_liveliness_service_cli(plugin_id, ke, typ).unwrap();
+        assert!(ke_v1_service_cli::parse(&token).is_ok());
+        let typ = "example_interfaces/action/Fibonacci";
+        let token = new_ke_liveliness_action_srv(plugin_id, ke, typ).unwrap();
+        assert!(ke_v1_action_srv::parse(&token).is_ok());
+        let token = new_ke_liveliness_action_cli(plugin_id, ke, typ).unwrap();
+        assert!(ke_v1_action_cli::parse(&token).is_ok());
+
+        // and still by this bridge
+        let token = new_ke_liveliness_action_cli(plugin_id, ke, typ).unwrap();
+        assert_eq!(
+            parse_ke_liveliness_action_cli(&token),
+            Ok((plugin_id.to_owned(), ke.to_owned(), typ.to_string()))
+        );
+    }
+
     #[test]
     fn test_qos_key_expr() {
         use super::*;
@@ -321,5 +1005,229 @@ mod tests {
             Ok((true, q.clone()))
         );
         q.reliability = None;
+
+        // the Deadline is appended only if set
+        q.deadline = Some(Deadline {
+            period: DDS_100MS_DURATION,
+        });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(":::{},3:100000000", HistoryKind::KEEP_LAST as u8)
+        );
+        assert_eq!(
+            key_expr_to_qos(&qos_to_key_expr(true, &q)),
+            Ok((true, q.clone()))
+        );
+        q.deadline = Some(Deadline {
+            period: cyclors::qos::DDS_INFINITE_TIME,
+        });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(":::{},3", HistoryKind::KEEP_LAST as u8)
+        );
+        assert!(key_expr_to_qos(ke_for_sure!(":::1,3:0")).is_err());
+        assert!(key_expr_to_qos(ke_for_sure!(":::1,3:100:1:1")).is_err());
+        assert!(key_expr_to_qos(ke_for_sure!(":::1,3:100:1:1,1:p:1,1:1,1,1,1,1:1:1:1")).is_err());
+
+        // the Lifespan follows the Deadline (empty if not set)
+        q.deadline = None;
+        q.lifespan = Some(Lifespan {
+            duration: 2 * DDS_100MS_DURATION,
+        });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(":::{},3::200000000", HistoryKind::KEEP_LAST as u8)
+        );
+        assert_eq!(
+            key_expr_to_qos(&qos_to_key_expr(true, &q)),
+            Ok((true, q.clone()))
+        );
+        q.deadline = Some(Deadline {
+            period: DDS_100MS_DURATION,
+        });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(":::{},3:100000000:200000000", HistoryKind::KEEP_LAST as u8)
+        );
+        assert_eq!(
+            key_expr_to_qos(&qos_to_key_expr(true, &q)),
+            Ok((true, q.clone()))
+        );
+        assert!(key_expr_to_qos(ke_for_sure!(":::1,3::0")).is_err());
+
+        // the Liveliness follows the Lifespan (empty if not set), only if not the default one
+        q.deadline = None;
+        q.lifespan = None;
+        q.liveliness = Some(Liveliness {
+            kind: LivelinessKind::AUTOMATIC,
+            lease_duration: DDS_INFINITE_TIME,
+        });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(":::{},3", HistoryKind::KEEP_LAST as u8)
+        );
+        q.liveliness = Some(Liveliness {
+            kind: LivelinessKind::MANUAL_BY_TOPIC,
+            lease_duration: cyclors::qos::DDS_1S_DURATION,
+        });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(
+                ":::{},3:::{},1000000000",
+                HistoryKind::KEEP_LAST as u8,
+                LivelinessKind::MANUAL_BY_TOPIC as u8
+            )
+        );
+        assert_eq!(
+            key_expr_to_qos(&qos_to_key_expr(true, &q)),
+            Ok((true, q.clone()))
+        );
+        q.liveliness = Some(Liveliness {
+            kind: LivelinessKind::MANUAL_BY_PARTICIPANT,
+            lease_duration: DDS_INFINITE_TIME,
+        });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(
+                ":::{},3:::{},",
+                HistoryKind::KEEP_LAST as u8,
+                LivelinessKind::MANUAL_BY_PARTICIPANT as u8
+            )
+        );
+        assert_eq!(
+            key_expr_to_qos(&qos_to_key_expr(true, &q)),
+            Ok((true, q.clone()))
+        );
+        assert!(key_expr_to_qos(ke_for_sure!(":::1,3:::1")).is_err());
+        assert!(key_expr_to_qos(ke_for_sure!(":::1,3:::1,0")).is_err());
+
+        // the Partitions follow the Liveliness (empty if not set), only if not the default one
+        q.liveliness = None;
+        q.partition = Some(vec![String::new()]);
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(":::{},3", HistoryKind::KEEP_LAST as u8)
+        );
+        q.partition = Some(vec!["prod".into(), "site*/a:b,c".into(), "é".into()]);
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(
+                ":::{},3::::prod,site%2A%2Fa%3Ab%2Cc,%C3%A9",
+                HistoryKind::KEEP_LAST as u8
+            )
+        );
+        assert_eq!(
+            key_expr_to_qos(&qos_to_key_expr(true, &q)),
+            Ok((true, q.clone()))
+        );
+        assert!(key_expr_to_qos(ke_for_sure!(":::1,3::::prod%2")).is_err());
+        assert!(key_expr_to_qos(ke_for_sure!(":::1,3::::prod%ZZ")).is_err());
+        assert!(key_expr_to_qos(ke_for_sure!(":::1,3::::%FF")).is_err());
+
+        // the EXCLUSIVE Ownership follows the Partitions (empty if not set)
+        q.partition = None;
+        q.ownership = Some(Ownership {
+            kind: OwnershipKind::SHARED,
+        });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(":::{},3", HistoryKind::KEEP_LAST as u8)
+        );
+        q.ownership = Some(Ownership {
+            kind: OwnershipKind::EXCLUSIVE,
+        });
+        q.ownership_strength = Some(OwnershipStrength { value: 10 });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(
+                ":::{},3:::::{},10",
+                HistoryKind::KEEP_LAST as u8,
+                OwnershipKind::EXCLUSIVE as u8
+            )
+        );
+        assert_eq!(
+            key_expr_to_qos(&qos_to_key_expr(true, &q)),
+            Ok((true, q.clone()))
+        );
+        assert!(key_expr_to_qos(ke_for_sure!(":::1,3:::::1")).is_err());
+        assert!(key_expr_to_qos(ke_for_sure!(":::1,3:::::1,a")).is_err());
+
+        // the DurabilityService follows the Ownership (empty if not set), only if TRANSIENT_LOCAL
+        q.ownership = None;
+        q.ownership_strength = None;
+        q.durability_service = Some(DurabilityService {
+            service_cleanup_delay: 0,
+            history_kind: HistoryKind::KEEP_LAST,
+            history_depth: 5,
+            max_samples: 100,
+            max_instances: -1,
+            max_samples_per_instance: 5,
+        });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(":::{},3", HistoryKind::KEEP_LAST as u8)
+        );
+        q.durability = Some(Durability {
+            kind: DurabilityKind::TRANSIENT_LOCAL,
+        });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(
+                "::{}:{},3::::::{},5,100,-1,5",
+                DurabilityKind::TRANSIENT_LOCAL as u8,
+                HistoryKind::KEEP_LAST as u8,
+                HistoryKind::KEEP_LAST as u8
+            )
+        );
+        assert_eq!(
+            key_expr_to_qos(&qos_to_key_expr(true, &q)),
+            Ok((true, q.clone()))
+        );
+        assert!(key_expr_to_qos(ke_for_sure!(":1:1:1,3::::::1,5")).is_err());
+        assert!(key_expr_to_qos(ke_for_sure!(":1:1:1,3::::::1,5,a,1,1")).is_err());
+
+        // the TimeBasedFilter follows the DurabilityService (empty if not set)
+        q.durability = None;
+        q.durability_service = None;
+        q.time_based_filter = Some(TimeBasedFilter {
+            minimum_separation: 0,
+        });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(":::{},3", HistoryKind::KEEP_LAST as u8)
+        );
+        q.time_based_filter = Some(TimeBasedFilter {
+            minimum_separation: 5 * DDS_100MS_DURATION,
+        });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(":::{},3:::::::500000000", HistoryKind::KEEP_LAST as u8)
+        );
+        assert_eq!(
+            key_expr_to_qos(&qos_to_key_expr(true, &q)),
+            Ok((true, q.clone()))
+        );
+        assert!(key_expr_to_qos(ke_for_sure!(":::1,3:::::::0")).is_err());
+        assert!(key_expr_to_qos(ke_for_sure!(":::1,3:::::::a")).is_err());
+
+        // the LatencyBudget follows the TimeBasedFilter (empty if not set), only if not zero
+        q.time_based_filter = None;
+        q.latency_budget = Some(LatencyBudget { duration: 0 });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(":::{},3", HistoryKind::KEEP_LAST as u8)
+        );
+        q.latency_budget = Some(LatencyBudget {
+            duration: DDS_100MS_DURATION,
+        });
+        assert_eq!(
+            qos_to_key_expr(true, &q).to_string(),
+            format!(":::{},3::::::::100000000", HistoryKind::KEEP_LAST as u8)
+        );
+        assert_eq!(
+            key_expr_to_qos(&qos_to_key_expr(true, &q)),
+            Ok((true, q.clone()))
+        );
+        assert!(key_expr_to_qos(ke_for_sure!(":::1,3::::::::-1")).is_err());
     }
 }
@@ -13,6 +13,7 @@
 //
 use serde::ser::SerializeSeq;
 use serde::{Serialize, Serializer};
+use std::borrow::Cow;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::ops::Range;
@@ -49,6 +50,15 @@ impl MsgPub {
         })
     }
 
+    /// For a raw DDS topic (i.e. not following the ROS 2 conventions): its DDS type is kept verbatim
+    pub fn create_raw(dds_topic: &str, dds_type: String, writer: Gid) -> MsgPub {
+        MsgPub {
+            name: raw_dds_topic_to_ros2_name(dds_topic),
+            typ: dds_type,
+            writers: HashSet::from([writer]),
+        }
+    }
+
     pub fn name_as_keyexpr(&self) -> &keyexpr {
         ke_for_sure!(&self.name[1..])
     }
@@ -86,6 +96,15 @@ impl MsgSub {
         })
     }
 
+    /// For a raw DDS topic (i.e. not following the ROS 2 conventions): its DDS type is kept verbatim
+    pub fn create_raw(dds_topic: &str, dds_type: String, reader: Gid) -> MsgSub {
+        MsgSub {
+            name: raw_dds_topic_to_ros2_name(dds_topic),
+            typ: dds_type,
+            readers: HashSet::from([reader]),
+        }
+    }
+
     pub fn name_as_keyexpr(&self) -> &keyexpr {
         ke_for_sure!(&self.name[1..])
     }
@@ -395,6 +414,10 @@ pub struct NodeInfo {
     pub undiscovered_reader: Vec<Gid>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub undiscovered_writer: Vec<Gid>,
+    // the non-canonical names used by this node's DDS topics (e.g. "/ns//topic"), with their canonical
+    // form used for routing (e.g. "/ns/topic"). Kept for diagnostics until the node is undiscovered.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub non_canonical_names: HashMap<String, String>,
 }
 
 impl std::fmt::Display for NodeInfo {
@@ -464,6 +487,7 @@ impl NodeInfo {
             action_cli: HashMap::new(),
             undiscovered_reader: Vec::new(),
             undiscovered_writer: Vec::new(),
+            non_canonical_names: HashMap::new(),
         })
     }
 
@@ -489,14 +513,16 @@ impl NodeInfo {
 
     pub fn update_with_reader(&mut self, entity: &DdsEntity) -> Option<ROS2DiscoveryEvent> {
         let topic_prefix = &entity.topic_name[..3];
-        let topic_suffix = &entity.topic_name[2..];
+        let topic_suffix = self.canonical_name(&entity.topic_name[2..]);
+        let topic_suffix = topic_suffix.as_ref();
+        let is_action = is_action_dds_topic(topic_suffix, &entity.type_name);
         match topic_prefix {
-            "rt/" if topic_suffix.ends_with("/_action/status") => self
+            "rt/" if is_action && topic_suffix.ends_with("/_action/status") => self
                 .update_action_cli_status_reader(
                     &topic_suffix[..topic_suffix.len() - 15],
                     &entity.key,
                 ),
-            "rt/" if topic_suffix.ends_with("/_action/feedback") => self
+            "rt/" if is_action && topic_suffix.ends_with("/_action/feedback") => self
                 .update_action_cli_feedback_reader(
                     &topic_suffix[..topic_suffix.len() - 17],
                     dds_type_to_ros2_action_type(&entity.type_name),
@@ -507,18 +533,18 @@ impl NodeInfo {
                 dds_type_to_ros2_message_type(&entity.type_name),
                 &entity.key,
             ),
-            "rq/" if topic_suffix.ends_with("/_action/send_goalRequest") => self
+            "rq/" if is_action && topic_suffix.ends_with("/_action/send_goalRequest") => self
                 .update_action_srv_send_req_reader(
                     &topic_suffix[..topic_suffix.len() - 25],
                     dds_type_to_ros2_action_type(&entity.type_name),
                     &entity.key,
                 ),
-            "rq/" if topic_suffix.ends_with("/_action/cancel_goalRequest") => self
+            "rq/" if is_action && topic_suffix.ends_with("/_action/cancel_goalRequest") => self
                 .update_action_srv_cancel_req_reader(
                     &topic_suffix[..topic_suffix.len() - 27],
                     &entity.key,
                 ),
-            "rq/" if topic_suffix.ends_with("/_action/get_resultRequest") => self
+            "rq/" if is_action && topic_suffix.ends_with("/_action/get_resultRequest") => self
                 .update_action_srv_result_req_reader(
                     &topic_suffix[..topic_suffix.len() - 26],
                     dds_type_to_ros2_action_type(&entity.type_name),
@@ -529,18 +555,18 @@ impl NodeInfo {
                 dds_type_to_ros2_service_type(&entity.type_name),
                 &entity.key,
             ),
-            "rr/" if topic_suffix.ends_with("/_action/send_goalReply") => self
+            "rr/" if is_action && topic_suffix.ends_with("/_action/send_goalReply") => self
                 .update_action_cli_send_rep_reader(
                     &topic_suffix[..topic_suffix.len() - 23],
                     dds_type_to_ros2_action_type(&entity.type_name),
                     &entity.key,
                 ),
-            "rr/" if topic_suffix.ends_with("/_action/cancel_goalReply") => self
+            "rr/" if is_action && topic_suffix.ends_with("/_action/cancel_goalReply") => self
                 .update_action_cli_cancel_rep_reader(
                     &topic_suffix[..topic_suffix.len() - 25],
                     &entity.key,
                 ),
-            "rr/" if topic_suffix.ends_with("/_action/get_resultReply") => self
+            "rr/" if is_action && topic_suffix.ends_with("/_action/get_resultReply") => self
                 .update_action_cli_result_rep_reader(
                     &topic_suffix[..topic_suffix.len() - 24],
                     dds_type_to_ros2_action_type(&entity.type_name),
@@ -563,14 +589,16 @@ impl NodeInfo {
 
     pub fn update_with_writer(&mut self, entity: &DdsEntity) -> Option<ROS2DiscoveryEvent> {
         let topic_prefix = &entity.topic_name[..3];
-        let topic_suffix = &entity.topic_name[2..];
+        let topic_suffix = self.canonical_name(&entity.topic_name[2..]);
+        let topic_suffix = topic_suffix.as_ref();
+        let is_action = is_action_dds_topic(topic_suffix, &entity.type_name);
         match topic_prefix {
-            "rt/" if topic_suffix.ends_with("/_action/status") => self
+            "rt/" if is_action && topic_suffix.ends_with("/_action/status") => self
                 .update_action_srv_status_writer(
                     &topic_suffix[..topic_suffix.len() - 15],
                     &entity.key,
                 ),
-            "rt/" if topic_suffix.ends_with("/_action/feedback") => self
+            "rt/" if is_action && topic_suffix.ends_with("/_action/feedback") => self
                 .update_action_srv_feedback_writer(
                     &topic_suffix[..topic_suffix.len() - 17],
                     dds_type_to_ros2_action_type(&entity.type_name),
@@ -581,18 +609,18 @@ impl NodeInfo {
                 dds_type_to_ros2_message_type(&entity.type_name),
                 &entity.key,
             ),
-            "rq/" if topic_suffix.ends_with("/_action/send_goalRequest") => self
+            "rq/" if is_action && topic_suffix.ends_with("/_action/send_goalRequest") => self
                 .update_action_cli_send_req_writer(
                     &topic_suffix[..topic_suffix.len() - 25],
                     dds_type_to_ros2_action_type(&entity.type_name),
                     &entity.key,
                 ),
-            "rq/" if topic_suffix.ends_with("/_action/cancel_goalRequest") => self
+            "rq/" if is_action && topic_suffix.ends_with("/_action/cancel_goalRequest") => self
                 .update_action_cli_cancel_req_writer(
                     &topic_suffix[..topic_suffix.len() - 27],
                     &entity.key,
                 ),
-            "rq/" if topic_suffix.ends_with("/_action/get_resultRequest") => self
+            "rq/" if is_action && topic_suffix.ends_with("/_action/get_resultRequest") => self
                 .update_action_cli_result_req_writer(
                     &topic_suffix[..topic_suffix.len() - 26],
                     dds_type_to_ros2_action_type(&entity.type_name),
@@ -603,18 +631,18 @@ impl NodeInfo {
                 dds_type_to_ros2_service_type(&entity.type_name),
                 &entity.key,
             ),
-            "rr/" if topic_suffix.ends_with("/_action/send_goalReply") => self
+            "rr/" if is_action && topic_suffix.ends_with("/_action/send_goalReply") => self
                 .update_action_srv_send_rep_writer(
                     &topic_suffix[..topic_suffix.len() - 23],
                     dds_type_to_ros2_action_type(&entity.type_name),
                     &entity.key,
                 ),
-            "rr/" if topic_suffix.ends_with("/_action/cancel_goalReply") => self
+            "rr/" if is_action && topic_suffix.ends_with("/_action/cancel_goalReply") => self
                 .update_action_srv_cancel_rep_writer(
                     &topic_suffix[..topic_suffix.len() - 25],
                     &entity.key,
                 ),
-            "rr/" if topic_suffix.ends_with("/_action/get_resultReply") => self
+            "rr/" if is_action && topic_suffix.ends_with("/_action/get_resultReply") => self
                 .update_action_srv_result_rep_writer(
                     &topic_suffix[..topic_suffix.len() - 24],
                     dds_type_to_ros2_action_type(&entity.type_name),
@@ -635,6 +663,24 @@ impl NodeInfo {
         }
     }
 
+    // The canonical form of a name used by a DDS topic (see canonical_ros2_name()),
+    // recording the non-canonical one for diagnostics
+    fn canonical_name<'a>(&mut self, name: &'a str) -> Cow<'a, str> {
+        let canonical = canonical_ros2_name(name);
+        if let Cow::Owned(c) = &canonical {
+            if self
+                .non_canonical_names
+                .insert(name.to_string(), c.clone())
+                .is_none()
+            {
+                tracing::warn!(
+                    r#"ROS Node {self} uses a non-canonical name "{name}" - considered as "{c}""#
+                );
+            }
+        }
+        canonical
+    }
+
     // Update MsgPub, returing a ROS2DiscoveryEvent::DiscoveredMsgSub if new or changed
     fn update_msg_pub(
         &mut self,
@@ -1731,6 +1777,7 @@ impl NodeInfo {
         }
         self.undiscovered_reader.resize(0, Gid::NOT_DISCOVERED);
         self.undiscovered_writer.resize(0, Gid::NOT_DISCOVERED);
+        self.non_canonical_names.clear();
 
         events
     }
@@ -1883,3 +1930,54 @@ where
     }
     seq.end()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::NodeInfo;
+    use crate::dds_discovery::DdsEntity;
+    use crate::events::ROS2DiscoveryEvent;
+    use crate::gid::Gid;
+
+    fn writer(id: u8, topic_name: &str) -> DdsEntity {
+        DdsEntity {
+            key: Gid::from([id; 16]),
+            participant_key: Gid::from([0u8; 16]),
+            topic_name: topic_name.into(),
+            type_name: "std_msgs::msg::dds_::String_".into(),
+            type_info: None,
+            keyless: true,
+            qos: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_non_canonical_names_merge() {
+        let mut node = NodeInfo::create("/".into(), "talker".into(), Gid::from([0u8; 16])).unwrap();
+
+        // the 1st Writer leads to a Publisher with the canonical name
+        match node.update_with_writer(&writer(1, "rt/ns//topic")) {
+            Some(ROS2DiscoveryEvent::DiscoveredMsgPub(_, p)) => assert_eq!(p.name, "/ns/topic"),
+            e => panic!("Unexpected event: {e:?}"),
+        }
+        // the other variants are merged into the same Publisher (i.e. the same route)
+        assert!(node
+            .update_with_writer(&writer(2, "rt/ns/topic/"))
+            .is_none());
+        assert!(node.update_with_writer(&writer(3, "rt/ns/topic")).is_none());
+        assert_eq!(node.msg_pub.len(), 1);
+        assert_eq!(node.msg_pub["/ns/topic"].writers.len(), 3);
+
+        // the raw names are kept for diagnostics
+        assert_eq!(node.non_canonical_names.len(), 2);
+        assert_eq!(node.non_canonical_names["/ns//topic"], "/ns/topic");
+        assert_eq!(node.non_canonical_names["/ns/topic/"], "/ns/topic");
+
+        // the Publisher is undiscovered only with its last Writer
+        assert!(node.remove_writer(&Gid::from([1u8; 16])).is_none());
+        assert!(node.remove_writer(&Gid::from([3u8; 16])).is_none());
+        match node.remove_writer(&Gid::from([2u8; 16])) {
+            Some(ROS2DiscoveryEvent::UndiscoveredMsgPub(_, p)) => assert_eq!(p.name, "/ns/topic"),
+            e => panic!("Unexpected event: {e:?}"),
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use zenoh_core::zlock;
+
+// Minimal period between 2 warnings logged by a same route for dropped oversized payloads
+const WARNING_PERIOD: Duration = Duration::from_secs(1);
+
+/// The maximum payload size configured for a route (`max_payload_size`), with the counter of
+/// the oversized samples it dropped. A `max_size` of 0 disables the check.
+pub struct PayloadSizeLimit {
+    max_size: usize,
+    dropped: AtomicU64,
+    // time of the last logged warning, and number of drops since
+    last_warning: Mutex<(Option<Instant>, u64)>,
+}
+
+impl PayloadSizeLimit {
+    pub fn new(max_size: usize) -> PayloadSizeLimit {
+        PayloadSizeLimit {
+            max_size,
+            dropped: AtomicU64::new(0),
+            last_warning: Mutex::new((None, 0)),
+        }
+    }
+
+    /// Return true if a payload of `size` bytes can be routed.
+    /// Otherwise, count it as dropped and log a (rate-limited) warning.
+    pub fn check(&self, size: usize, route_id: &str) -> bool {
+        if self.max_size == 0 || size <= self.max_size {
+            return true;
+        }
+        let total = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut last_warning = zlock!(self.last_warning);
+        last_warning.1 += 1;
+        let now = Instant::now();
+        if last_warning
+            .0
+            .map_or(true, |t| now.duration_since(t) >= WARNING_PERIOD)
+        {
+            tracing::warn!(
+                "{route_id}: dropped {} message(s) exceeding max_payload_size={} (last one: {size} bytes, total dropped: {total})",
+                last_warning.1,
+                self.max_size
+            );
+            *last_warning = (Some(now), 0);
+        }
+        false
+    }
+
+    #[inline]
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl Serialize for PayloadSizeLimit {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("PayloadSizeLimit", 2)?;
+        s.serialize_field("max_payload_size", &self.max_size)?;
+        s.serialize_field("dropped_oversized", &self.dropped())?;
+        s.end()
+    }
+}
+
+pub fn serialize_payload_limit<S>(limit: &Arc<PayloadSizeLimit>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    limit.as_ref().serialize(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PayloadSizeLimit;
+
+    #[test]
+    fn test_oversized_payloads_dropped() {
+        let limit = PayloadSizeLimit::new(1024);
+        assert!(limit.check(0, "test"));
+        assert!(limit.check(1024, "test"));
+        assert_eq!(limit.dropped(), 0);
+        assert!(!limit.check(1025, "test"));
+        assert!(!limit.check(300 * 1024 * 1024, "test"));
+        assert!(limit.check(10, "test"));
+        assert_eq!(limit.dropped(), 2);
+        assert_eq!(
+            serde_json::to_value(&limit).unwrap(),
+            serde_json::json!({"max_payload_size": 1024, "dropped_oversized": 2})
+        );
+    }
+
+    #[test]
+    fn test_no_limit() {
+        let limit = PayloadSizeLimit::new(0);
+        assert!(limit.check(300 * 1024 * 1024, "test"));
+        assert_eq!(limit.dropped(), 0);
+    }
+}
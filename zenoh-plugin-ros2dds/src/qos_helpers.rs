@@ -13,6 +13,10 @@
 //
 use cyclors::{qos::*, DDS_LENGTH_UNLIMITED};
 
+use crate::config::{
+    CongestionControlOverride, DurabilityOverride, HistoryOverride, ReliabilityOverride, TopicQos,
+};
+
 pub fn get_history_or_default(qos: &Qos) -> History {
     match &qos.history {
         None => History::default(),
@@ -39,12 +43,108 @@ pub fn is_transient_local(qos: &Qos) -> bool {
     })
 }
 
+// Merge the QoS of several Writers of a same topic, independently of their order:
+//   - Reliability: the strongest (RELIABLE if any Writer is RELIABLE, which is the default for a Writer)
+//   - Durability: the strongest (e.g. TRANSIENT_LOCAL if any Writer is TRANSIENT_LOCAL)
+//   - History and DurabilityService: KEEP_ALL if any Writer is KEEP_ALL, otherwise KEEP_LAST with the max depth
+//   - Ownership: EXCLUSIVE with the max strength if any Writer is EXCLUSIVE
+//   - other QoS: those of the first QoS in a canonical order (i.e. sorted by their Debug representation)
+// None is returned if there is no QoS.
+pub fn merge_writers_qos<'a, I>(qos: I) -> Option<Qos>
+where
+    I: IntoIterator<Item = &'a Qos>,
+{
+    let mut all: Vec<&Qos> = qos.into_iter().collect();
+    all.sort_by_cached_key(|q| format!("{q:?}"));
+    let mut result = (*all.first()?).clone();
+    if all.iter().any(|q| q.reliability.is_some()) {
+        result.reliability = all
+            .iter()
+            .map(|q| {
+                q.reliability.clone().unwrap_or(Reliability {
+                    kind: ReliabilityKind::RELIABLE,
+                    max_blocking_time: DDS_100MS_DURATION,
+                })
+            })
+            .max_by_key(|r| (r.kind as isize, r.max_blocking_time));
+    }
+    if let Some(durability) = all
+        .iter()
+        .filter_map(|q| q.durability.clone())
+        .max_by_key(|d| d.kind as isize)
+    {
+        result.durability = Some(durability);
+    }
+    if let Some(history) = all
+        .iter()
+        .filter_map(|q| q.history.clone())
+        .max_by_key(|h| (h.kind as isize, h.depth))
+    {
+        result.history = Some(history);
+    }
+    if let Some(durability_service) = all
+        .iter()
+        .filter_map(|q| q.durability_service.clone())
+        .max_by_key(|d| (d.history_kind as isize, d.history_depth))
+    {
+        result.durability_service = Some(durability_service);
+    }
+    if let Some(strength) = all
+        .iter()
+        .filter_map(|q| get_exclusive_ownership_strength(q))
+        .max()
+    {
+        result = set_exclusive_ownership_strength(&result, Some(strength));
+    }
+    // the Reader of the route must request a LatencyBudget not shorter than any Writer offers
+    if let Some(latency_budget) = all
+        .iter()
+        .filter_map(|q| q.latency_budget.clone())
+        .max_by_key(|l| l.duration)
+    {
+        result.latency_budget = Some(latency_budget);
+    }
+    Some(result)
+}
+
+// Return the strength of an EXCLUSIVE Ownership (0 if not set), or None if the Ownership is SHARED (the default)
+pub fn get_exclusive_ownership_strength(qos: &Qos) -> Option<i32> {
+    match &qos.ownership {
+        Some(Ownership {
+            kind: OwnershipKind::EXCLUSIVE,
+        }) => Some(qos.ownership_strength.as_ref().map_or(0, |s| s.value)),
+        _ => None,
+    }
+}
+
+// Set an EXCLUSIVE Ownership with a strength, or the default SHARED Ownership if None
+pub fn set_exclusive_ownership_strength(qos: &Qos, strength: Option<i32>) -> Qos {
+    let mut result = qos.clone();
+    match strength {
+        Some(value) => {
+            result.ownership = Some(Ownership {
+                kind: OwnershipKind::EXCLUSIVE,
+            });
+            result.ownership_strength = Some(OwnershipStrength { value });
+        }
+        None => {
+            result.ownership = None;
+            result.ownership_strength = None;
+        }
+    }
+    result
+}
+
 // Copy and adapt Writer's QoS for creation of a matching Reader
 pub fn adapt_writer_qos_for_reader(qos: &Qos) -> Qos {
     let mut reader_qos = qos.clone();
 
-    // Unset any writer QoS that doesn't apply to data readers
-    reader_qos.durability_service = None;
+    // Unset any writer QoS that doesn't apply to data readers.
+    // Except the DurabilityService of a TRANSIENT_LOCAL Writer, sizing the historical data cached by the bridge
+    // (see transient_local_history()). It's unset on the DDS Reader's creation (see without_durability_service())
+    if !is_transient_local(qos) {
+        reader_qos.durability_service = None;
+    }
     reader_qos.ownership_strength = None;
     reader_qos.transport_priority = None;
     reader_qos.lifespan = None;
@@ -89,19 +189,7 @@ pub fn adapt_reader_qos_for_writer(qos: &Qos) -> Qos {
     // if Reader is TRANSIENT_LOCAL, configure durability_service QoS with same history as the Reader.
     // This is because CycloneDDS is actually using durability_service.history for transient_local historical data.
     if is_transient_local(qos) {
-        let history = qos
-            .history
-            .as_ref()
-            .map_or(History::default(), |history| history.clone());
-
-        writer_qos.durability_service = Some(DurabilityService {
-            service_cleanup_delay: 60 * DDS_1S_DURATION,
-            history_kind: history.kind,
-            history_depth: history.depth,
-            max_samples: DDS_LENGTH_UNLIMITED,
-            max_instances: DDS_LENGTH_UNLIMITED,
-            max_samples_per_instance: DDS_LENGTH_UNLIMITED,
-        });
+        writer_qos.durability_service = Some(durability_service_from_history(qos));
     }
     // Workaround for the DDS Writer to correctly match with a FastRTPS Reader
     writer_qos.reliability = match writer_qos.reliability {
@@ -118,3 +206,1631 @@ pub fn adapt_reader_qos_for_writer(qos: &Qos) -> Qos {
 
     writer_qos
 }
+
+// Apply a Reliability configured for a topic to a Writer's QoS.
+// This shall be called on the QoS resulting from the adaptation of a discovered Reader's QoS
+// (i.e. adapt_reader_qos_for_writer()), and thus takes precedence over it.
+// Note that a BEST_EFFORT Writer doesn't match a RELIABLE Reader.
+pub fn apply_reliability_override(qos: &Qos, reliability: Option<ReliabilityOverride>) -> Qos {
+    let mut result = qos.clone();
+    match reliability {
+        None => (),
+        Some(ReliabilityOverride::Reliable) => {
+            // keep the max_blocking_time if already RELIABLE
+            if !is_reliable(qos) {
+                result.reliability = Some(Reliability {
+                    kind: ReliabilityKind::RELIABLE,
+                    max_blocking_time: DDS_100MS_DURATION,
+                });
+            }
+        }
+        Some(ReliabilityOverride::BestEffort) => {
+            result.reliability = Some(Reliability {
+                kind: ReliabilityKind::BEST_EFFORT,
+                max_blocking_time: DDS_100MS_DURATION,
+            });
+        }
+    }
+    result
+}
+
+// Apply a Reliability configured for a topic to a Reader's QoS, resulting from the adaptation of a discovered
+// Writer's QoS (i.e. adapt_writer_qos_for_reader()). Only "best_effort" applies: a RELIABLE Reader doesn't match
+// a BEST_EFFORT Writer, thus the Reader's Reliability is never raised above the Writer's one.
+// A "reliable" override only applies to the zenoh leg of the route.
+pub fn apply_reader_reliability_override(
+    qos: &Qos,
+    reliability: Option<ReliabilityOverride>,
+) -> Qos {
+    match reliability {
+        Some(ReliabilityOverride::BestEffort) => {
+            apply_reliability_override(qos, Some(ReliabilityOverride::BestEffort))
+        }
+        Some(ReliabilityOverride::Reliable) | None => qos.clone(),
+    }
+}
+
+// Apply a congestion control configured for a topic to a Writer's QoS, via the max_blocking_time of its Reliability:
+// a write blocks up to this time when the Writer's History is full of messages not acknowledged by RELIABLE Readers,
+// and then fails with a TIMEOUT. A BEST_EFFORT Writer never blocks, and is thus unchanged.
+pub fn apply_congestion_control(
+    qos: &Qos,
+    congestion_control: Option<CongestionControlOverride>,
+) -> Qos {
+    let mut result = qos.clone();
+    // the default Reliability of a Writer is RELIABLE
+    let reliable = qos
+        .reliability
+        .as_ref()
+        .map_or(true, |r| r.kind == ReliabilityKind::RELIABLE);
+    let max_blocking_time = match congestion_control {
+        Some(_) if !reliable => return result,
+        None => return result,
+        Some(CongestionControlOverride::Block) => DDS_INFINITE_TIME,
+        Some(CongestionControlOverride::Drop) => 0,
+    };
+    result.reliability = Some(Reliability {
+        kind: ReliabilityKind::RELIABLE,
+        max_blocking_time,
+    });
+    result
+}
+
+// Return the Deadline period of a QoS, if set and not infinite
+pub fn get_deadline_period(qos: &Qos) -> Option<std::time::Duration> {
+    qos.deadline
+        .as_ref()
+        .filter(|d| d.period > 0 && d.period != DDS_INFINITE_TIME)
+        .map(|d| std::time::Duration::from_nanos(d.period as u64))
+}
+
+// Return the Lifespan duration of a QoS, if set and not infinite
+pub fn get_lifespan_duration(qos: &Qos) -> Option<std::time::Duration> {
+    qos.lifespan
+        .as_ref()
+        .filter(|l| l.duration > 0 && l.duration != DDS_INFINITE_TIME)
+        .map(|l| std::time::Duration::from_nanos(l.duration as u64))
+}
+
+// Return the LatencyBudget duration of a QoS, if set and not zero (the default)
+pub fn get_latency_budget(qos: &Qos) -> Option<std::time::Duration> {
+    qos.latency_budget
+        .as_ref()
+        .filter(|l| l.duration > 0 && l.duration != DDS_INFINITE_TIME)
+        .map(|l| std::time::Duration::from_nanos(l.duration as u64))
+}
+
+fn latency_budget_duration(qos: &Qos) -> i64 {
+    qos.latency_budget.as_ref().map_or(0, |l| l.duration)
+}
+
+// Return the minimum separation of the TimeBasedFilter of a Reader's QoS, if set and not zero
+pub fn get_time_based_filter_separation(qos: &Qos) -> Option<std::time::Duration> {
+    qos.time_based_filter
+        .as_ref()
+        .filter(|f| f.minimum_separation > 0 && f.minimum_separation != DDS_INFINITE_TIME)
+        .map(|f| std::time::Duration::from_nanos(f.minimum_separation as u64))
+}
+
+// The downsampling interval satisfying all the Readers with those minimum separations:
+// the smallest one, or None if there is no Reader or if a Reader has no TimeBasedFilter
+pub fn min_time_based_filter_separation<I>(separations: I) -> Option<std::time::Duration>
+where
+    I: IntoIterator<Item = Option<std::time::Duration>>,
+{
+    let mut result: Option<std::time::Duration> = None;
+    for separation in separations {
+        let separation = separation?;
+        result = Some(result.map_or(separation, |r| r.min(separation)));
+    }
+    result
+}
+
+// Return the lease duration of a manual Liveliness (MANUAL_BY_PARTICIPANT or MANUAL_BY_TOPIC),
+// if set and not infinite
+pub fn get_manual_liveliness_lease(qos: &Qos) -> Option<std::time::Duration> {
+    qos.liveliness
+        .as_ref()
+        .filter(|l| l.kind != LivelinessKind::AUTOMATIC)
+        .filter(|l| l.lease_duration > 0 && l.lease_duration != DDS_INFINITE_TIME)
+        .map(|l| std::time::Duration::from_nanos(l.lease_duration as u64))
+}
+
+// Adapt a manual Liveliness for a Writer created by the bridge: as the bridge's Participant is shared
+// by all the routes, its liveliness is asserted per Writer. Thus MANUAL_BY_PARTICIPANT is replaced
+// with MANUAL_BY_TOPIC (which still matches the Readers requesting MANUAL_BY_PARTICIPANT)
+pub fn manual_liveliness_by_topic(qos: &Qos) -> Qos {
+    let mut result = qos.clone();
+    if let Some(liveliness) = &mut result.liveliness {
+        if liveliness.kind == LivelinessKind::MANUAL_BY_PARTICIPANT {
+            liveliness.kind = LivelinessKind::MANUAL_BY_TOPIC;
+        }
+    }
+    result
+}
+
+// Unset the Deadline of a QoS announced to or by a remote bridge if its propagation is disabled
+// (see `propagate_deadline` config)
+pub fn filter_deadline(mut qos: Qos, propagate: bool) -> Qos {
+    if !propagate {
+        qos.deadline = None;
+    }
+    qos
+}
+
+// Unset the TimeBasedFilter of a QoS announced to or by a remote bridge if its propagation is disabled
+// (see `propagate_time_based_filter` config)
+pub fn filter_time_based_filter(mut qos: Qos, propagate: bool) -> Qos {
+    if !propagate {
+        qos.time_based_filter = None;
+    }
+    qos
+}
+
+// The Reliability, Durability, Deadline and Liveliness of a Writer's or a Reader's QoS, or their default value
+// (the default Reliability being RELIABLE for a Writer and BEST_EFFORT for a Reader)
+fn matching_policies(
+    qos: &Qos,
+    default_reliability: ReliabilityKind,
+) -> (ReliabilityKind, DurabilityKind, i64, LivelinessKind, i64) {
+    (
+        qos.reliability
+            .as_ref()
+            .map_or(default_reliability, |r| r.kind),
+        qos.durability
+            .as_ref()
+            .map_or(DurabilityKind::VOLATILE, |d| d.kind),
+        qos.deadline
+            .as_ref()
+            .map_or(DDS_INFINITE_TIME, |d| d.period),
+        qos.liveliness
+            .as_ref()
+            .map_or(LivelinessKind::AUTOMATIC, |l| l.kind),
+        qos.liveliness
+            .as_ref()
+            .map_or(DDS_INFINITE_TIME, |l| l.lease_duration),
+    )
+}
+
+// The incompatibilities between the QoS offered by a Writer and the QoS requested by a Reader,
+// preventing them to match (as per the Requested/Offered model of the DDS specification).
+// Only the Reliability, Durability, Deadline, Liveliness, Ownership and LatencyBudget are checked.
+pub fn qos_incompatibilities(offered: &Qos, requested: &Qos) -> Vec<String> {
+    let (o_reliability, o_durability, o_deadline, o_liveliness, o_lease) =
+        matching_policies(offered, ReliabilityKind::RELIABLE);
+    let (r_reliability, r_durability, r_deadline, r_liveliness, r_lease) =
+        matching_policies(requested, ReliabilityKind::BEST_EFFORT);
+    let mut result = Vec::new();
+    if (o_reliability as isize) < (r_reliability as isize) {
+        result.push(format!(
+            "Reliability: {r_reliability:?} requested by the Reader, but {o_reliability:?} offered by the Writer"
+        ));
+    }
+    if (o_durability as isize) < (r_durability as isize) {
+        result.push(format!(
+            "Durability: {r_durability:?} requested by the Reader, but {o_durability:?} offered by the Writer"
+        ));
+    }
+    if o_deadline > r_deadline {
+        result.push(format!(
+            "Deadline: {} requested by the Reader, but {} offered by the Writer",
+            dds_duration_to_string(r_deadline),
+            dds_duration_to_string(o_deadline)
+        ));
+    }
+    if (o_liveliness as isize) < (r_liveliness as isize) || o_lease > r_lease {
+        result.push(format!(
+            "Liveliness: {r_liveliness:?} with lease {} requested by the Reader, but {o_liveliness:?} with lease {} offered by the Writer",
+            dds_duration_to_string(r_lease),
+            dds_duration_to_string(o_lease)
+        ));
+    }
+    let (o_ownership, r_ownership) = (ownership_kind(offered), ownership_kind(requested));
+    if o_ownership != r_ownership {
+        result.push(format!(
+            "Ownership: {r_ownership:?} requested by the Reader, but {o_ownership:?} offered by the Writer"
+        ));
+    }
+    let (o_budget, r_budget) = (
+        latency_budget_duration(offered),
+        latency_budget_duration(requested),
+    );
+    if o_budget > r_budget {
+        result.push(format!(
+            "LatencyBudget: {} requested by the Reader, but {} offered by the Writer",
+            dds_duration_to_string(r_budget),
+            dds_duration_to_string(o_budget)
+        ));
+    }
+    result
+}
+
+fn ownership_kind(qos: &Qos) -> OwnershipKind {
+    qos.ownership
+        .as_ref()
+        .map_or(OwnershipKind::SHARED, |o| o.kind)
+}
+
+// Upgrade the QoS offered by a Writer to be compatible with the QoS requested by a Reader
+// (see qos_incompatibilities()). The other QoS are unchanged.
+pub fn upgrade_offered_qos(offered: &Qos, requested: &Qos) -> Qos {
+    let (o_reliability, o_durability, o_deadline, o_liveliness, o_lease) =
+        matching_policies(offered, ReliabilityKind::RELIABLE);
+    let (r_reliability, r_durability, r_deadline, r_liveliness, r_lease) =
+        matching_policies(requested, ReliabilityKind::BEST_EFFORT);
+    let mut result = offered.clone();
+    if (o_reliability as isize) < (r_reliability as isize) {
+        result.reliability = Some(Reliability {
+            kind: r_reliability,
+            max_blocking_time: DDS_100MS_DURATION,
+        });
+    }
+    if (o_durability as isize) < (r_durability as isize) {
+        result.durability = Some(Durability { kind: r_durability });
+    }
+    if o_deadline > r_deadline {
+        result.deadline = Some(Deadline { period: r_deadline });
+    }
+    if (o_liveliness as isize) < (r_liveliness as isize) || o_lease > r_lease {
+        result.liveliness = Some(Liveliness {
+            kind: if (o_liveliness as isize) < (r_liveliness as isize) {
+                r_liveliness
+            } else {
+                o_liveliness
+            },
+            lease_duration: o_lease.min(r_lease),
+        });
+    }
+    if ownership_kind(offered) != ownership_kind(requested) {
+        let strength = match ownership_kind(requested) {
+            OwnershipKind::EXCLUSIVE => {
+                Some(get_exclusive_ownership_strength(offered).unwrap_or(0))
+            }
+            OwnershipKind::SHARED => None,
+        };
+        result = set_exclusive_ownership_strength(&result, strength);
+    }
+    if latency_budget_duration(offered) > latency_budget_duration(requested) {
+        result.latency_budget = requested.latency_budget.clone();
+    }
+    result
+}
+
+fn dds_duration_to_string(d: i64) -> String {
+    if d == DDS_INFINITE_TIME {
+        "infinite".into()
+    } else {
+        format!("{:?}", std::time::Duration::from_nanos(d as u64))
+    }
+}
+
+// Return true if the History or the DurabilityService's history of a QoS is KEEP_ALL
+pub fn is_keep_all(qos: &Qos) -> bool {
+    matches!(
+        qos.history,
+        Some(History {
+            kind: HistoryKind::KEEP_ALL,
+            ..
+        })
+    ) || matches!(
+        qos.durability_service,
+        Some(DurabilityService {
+            history_kind: HistoryKind::KEEP_ALL,
+            ..
+        })
+    )
+}
+
+// Replace a KEEP_ALL History (and DurabilityService's history) with KEEP_LAST and the configured
+// maximum depth, if any (see `keep_all_max_depth` config). A KEEP_LAST History is kept as is.
+pub fn cap_keep_all_history(qos: &Qos, keep_all_max_depth: Option<usize>) -> Qos {
+    let mut result = qos.clone();
+    let Some(max_depth) = keep_all_max_depth else {
+        return result;
+    };
+    let max_depth = max_depth.clamp(1, i32::MAX as usize) as i32;
+    if let Some(history) = &mut result.history {
+        if history.kind == HistoryKind::KEEP_ALL {
+            history.kind = HistoryKind::KEEP_LAST;
+            history.depth = max_depth;
+        }
+    }
+    if let Some(durability_service) = &mut result.durability_service {
+        if durability_service.history_kind == HistoryKind::KEEP_ALL {
+            durability_service.history_kind = HistoryKind::KEEP_LAST;
+            durability_service.history_depth = max_depth;
+        }
+    }
+    result
+}
+
+// A DurabilityService with the same history than a QoS, and no resource limits
+pub fn durability_service_from_history(qos: &Qos) -> DurabilityService {
+    let history = get_history_or_default(qos);
+    DurabilityService {
+        service_cleanup_delay: 60 * DDS_1S_DURATION,
+        history_kind: history.kind,
+        history_depth: history.depth,
+        max_samples: DDS_LENGTH_UNLIMITED,
+        max_instances: DDS_LENGTH_UNLIMITED,
+        max_samples_per_instance: DDS_LENGTH_UNLIMITED,
+    }
+}
+
+// For a Writer created by the bridge: if TRANSIENT_LOCAL without DurabilityService (e.g. not announced
+// by an older bridge, or forced via `force_transient_local`), set it with the same history than the QoS
+pub fn with_durability_service(qos: &Qos) -> Qos {
+    let mut result = qos.clone();
+    if is_transient_local(qos) && qos.durability_service.is_none() {
+        result.durability_service = Some(durability_service_from_history(qos));
+    }
+    result
+}
+
+// Raise a KEEP_LAST History (and the one of the DurabilityService, if set) to at least `min_depth`
+pub fn with_min_history_depth(qos: &Qos, min_depth: i32) -> Qos {
+    let mut result = qos.clone();
+    let mut history = get_history_or_default(qos);
+    if history.kind == HistoryKind::KEEP_LAST && history.depth < min_depth {
+        history.depth = min_depth;
+        result.history = Some(history);
+    }
+    if let Some(ds) = &mut result.durability_service {
+        if ds.history_kind == HistoryKind::KEEP_LAST && ds.history_depth < min_depth {
+            ds.history_depth = min_depth;
+        }
+    }
+    result
+}
+
+// Unset the DurabilityService (that doesn't apply to a DDS Reader)
+pub fn without_durability_service(qos: &Qos) -> Qos {
+    let mut result = qos.clone();
+    result.durability_service = None;
+    result
+}
+
+// The history of the historical data provided by a TRANSIENT_LOCAL Writer to late joining Readers:
+// the one of its DurabilityService if set, or else its History
+pub fn transient_local_history(qos: &Qos) -> History {
+    match &qos.durability_service {
+        Some(ds) => History {
+            kind: ds.history_kind,
+            depth: ds.history_depth,
+        },
+        None => get_history_or_default(qos),
+    }
+}
+
+// Set the DDS Partitions configured for a topic (see `dds_partition` config) on the QoS of a Reader
+// or a Writer created by the bridge. If not configured, the Partitions of the discovered entity
+// (or announced by the remote bridge) are kept.
+pub fn apply_partition(qos: &Qos, partitions: Option<&Vec<String>>) -> Qos {
+    let mut result = qos.clone();
+    if let Some(partitions) = partitions {
+        result.partition = Some(partitions.clone());
+    }
+    result
+}
+
+// Apply the QoS configured for a topic via `topic_qos` (and its preset) on the QoS of a Reader or a Writer
+// created by the bridge: the VOLATILE Durability, the History, the Deadline and the Lifespan.
+// The Reliability and the TRANSIENT_LOCAL Durability are applied via Config::get_reliability_override()
+// and Config::is_transient_local_forced(), as for the `reliability` and `force_transient_local` options.
+pub fn apply_topic_qos(qos: &Qos, topic_qos: Option<&TopicQos>) -> Qos {
+    let mut result = qos.clone();
+    let Some(topic_qos) = topic_qos else {
+        return result;
+    };
+    if topic_qos.durability == Some(DurabilityOverride::Volatile) {
+        result.durability = Some(Durability {
+            kind: DurabilityKind::VOLATILE,
+        });
+        result.durability_service = None;
+    }
+    match topic_qos.history {
+        Some(HistoryOverride::KeepLast(depth)) => {
+            result.history = Some(History {
+                kind: HistoryKind::KEEP_LAST,
+                depth: depth.min(i32::MAX as usize) as i32,
+            })
+        }
+        Some(HistoryOverride::KeepAll) => {
+            result.history = Some(History {
+                kind: HistoryKind::KEEP_ALL,
+                depth: 1,
+            })
+        }
+        None => (),
+    }
+    if let Some(period) = topic_qos.get_deadline() {
+        result.deadline = Some(Deadline {
+            period: period.as_nanos().min(i64::MAX as u128) as i64,
+        });
+    }
+    if let Some(duration) = topic_qos.get_lifespan() {
+        result.lifespan = Some(Lifespan {
+            duration: duration.as_nanos().min(i64::MAX as u128) as i64,
+        });
+    }
+    result
+}
+
+// Render a QoS as JSON for the admin space: only the policies that are set,
+// with their durations as human readable strings (e.g. "100ms" or "infinite")
+pub fn qos_to_json(qos: &Qos) -> Result<serde_json::Value, serde_json::Error> {
+    let mut value = serde_json::to_value(qos)?;
+    if let serde_json::Value::Object(policies) = &mut value {
+        policies.retain(|_, v| !v.is_null());
+        for (policy, field) in [
+            ("reliability", "max_blocking_time"),
+            ("deadline", "period"),
+            ("lifespan", "duration"),
+            ("liveliness", "lease_duration"),
+            ("durability_service", "service_cleanup_delay"),
+            ("time_based_filter", "minimum_separation"),
+            ("latency_budget", "duration"),
+        ] {
+            if let Some(duration) = policies.get_mut(policy).and_then(|p| p.get_mut(field)) {
+                if let Some(d) = duration.as_i64() {
+                    *duration = dds_duration_to_string(d).into();
+                }
+            }
+        }
+    }
+    Ok(value)
+}
+
+// Parse a QoS from JSON, as rendered by qos_to_json(): an object with a field per policy that is set.
+// The durations are either a number of nanoseconds, either a string as rendered by qos_to_json()
+// (e.g. "100ms", "1.5s" or "infinite"). The omitted fields of a policy take their DDS default value
+// (e.g. depth 1 for a KEEP_LAST History, or an infinite lease for a Liveliness).
+pub fn qos_from_json(value: &serde_json::Value) -> Result<Qos, String> {
+    let policies = value
+        .as_object()
+        .ok_or_else(|| format!("Invalid QoS {value}: not a JSON object"))?;
+    let mut qos = Qos::default();
+    for (policy, v) in policies {
+        let p = JsonPolicy(policy, v);
+        match policy.as_str() {
+            "reliability" => {
+                qos.reliability = Some(Reliability {
+                    kind: p.kind(
+                        &[
+                            ("BEST_EFFORT", ReliabilityKind::BEST_EFFORT),
+                            ("RELIABLE", ReliabilityKind::RELIABLE),
+                        ],
+                        None,
+                    )?,
+                    max_blocking_time: p.duration("max_blocking_time", DDS_100MS_DURATION)?,
+                })
+            }
+            "durability" => {
+                qos.durability = Some(Durability {
+                    kind: p.kind(
+                        &[
+                            ("VOLATILE", DurabilityKind::VOLATILE),
+                            ("TRANSIENT_LOCAL", DurabilityKind::TRANSIENT_LOCAL),
+                            ("TRANSIENT", DurabilityKind::TRANSIENT),
+                            ("PERSISTENT", DurabilityKind::PERSISTENT),
+                        ],
+                        None,
+                    )?,
+                })
+            }
+            "history" => {
+                qos.history = Some(History {
+                    kind: p.kind(HISTORY_KINDS, None)?,
+                    depth: p.int("depth", 1)?,
+                })
+            }
+            "deadline" => {
+                qos.deadline = Some(Deadline {
+                    period: p.duration("period", DDS_INFINITE_TIME)?,
+                })
+            }
+            "lifespan" => {
+                qos.lifespan = Some(Lifespan {
+                    duration: p.duration("duration", DDS_INFINITE_TIME)?,
+                })
+            }
+            "liveliness" => {
+                qos.liveliness = Some(Liveliness {
+                    kind: p.kind(
+                        &[
+                            ("AUTOMATIC", LivelinessKind::AUTOMATIC),
+                            (
+                                "MANUAL_BY_PARTICIPANT",
+                                LivelinessKind::MANUAL_BY_PARTICIPANT,
+                            ),
+                            ("MANUAL_BY_TOPIC", LivelinessKind::MANUAL_BY_TOPIC),
+                        ],
+                        Some(LivelinessKind::AUTOMATIC),
+                    )?,
+                    lease_duration: p.duration("lease_duration", DDS_INFINITE_TIME)?,
+                })
+            }
+            "ownership" => {
+                qos.ownership = Some(Ownership {
+                    kind: p.kind(
+                        &[
+                            ("SHARED", OwnershipKind::SHARED),
+                            ("EXCLUSIVE", OwnershipKind::EXCLUSIVE),
+                        ],
+                        None,
+                    )?,
+                })
+            }
+            "ownership_strength" => {
+                qos.ownership_strength = Some(OwnershipStrength {
+                    value: p.int("value", 0)?,
+                })
+            }
+            "partition" => {
+                qos.partition = Some(
+                    v.as_array()
+                        .and_then(|a| {
+                            a.iter()
+                                .map(|s| s.as_str().map(String::from))
+                                .collect::<Option<Vec<String>>>()
+                        })
+                        .ok_or_else(|| {
+                            format!("Invalid QoS policy 'partition': {v} is not a list of strings")
+                        })?,
+                )
+            }
+            "durability_service" => {
+                let default = DurabilityService::default();
+                qos.durability_service = Some(DurabilityService {
+                    service_cleanup_delay: p
+                        .duration("service_cleanup_delay", default.service_cleanup_delay)?,
+                    history_kind: p.field_kind(
+                        "history_kind",
+                        HISTORY_KINDS,
+                        Some(default.history_kind),
+                    )?,
+                    history_depth: p.int("history_depth", default.history_depth)?,
+                    max_samples: p.int("max_samples", default.max_samples)?,
+                    max_instances: p.int("max_instances", default.max_instances)?,
+                    max_samples_per_instance: p
+                        .int("max_samples_per_instance", default.max_samples_per_instance)?,
+                })
+            }
+            "time_based_filter" => {
+                qos.time_based_filter = Some(TimeBasedFilter {
+                    minimum_separation: p.duration("minimum_separation", 0)?,
+                })
+            }
+            "latency_budget" => {
+                qos.latency_budget = Some(LatencyBudget {
+                    duration: p.duration("duration", 0)?,
+                })
+            }
+            "ignore_local" => {
+                qos.ignore_local = Some(IgnoreLocal {
+                    kind: p.kind(
+                        &[
+                            ("NONE", IgnoreLocalKind::NONE),
+                            ("PARTICIPANT", IgnoreLocalKind::PARTICIPANT),
+                            ("PROCESS", IgnoreLocalKind::PROCESS),
+                        ],
+                        None,
+                    )?,
+                })
+            }
+            _ => return Err(format!("Invalid QoS: unsupported policy '{policy}'")),
+        }
+    }
+    Ok(qos)
+}
+
+const HISTORY_KINDS: &[(&str, HistoryKind)] = &[
+    ("KEEP_LAST", HistoryKind::KEEP_LAST),
+    ("KEEP_ALL", HistoryKind::KEEP_ALL),
+];
+
+// A policy of a QoS in JSON (its name and its value), for parsing in qos_from_json()
+struct JsonPolicy<'a>(&'a str, &'a serde_json::Value);
+
+impl JsonPolicy<'_> {
+    fn field(&self, field: &str) -> Result<Option<&serde_json::Value>, String> {
+        match self.1 {
+            serde_json::Value::Object(fields) => Ok(fields.get(field)),
+            v => Err(format!(
+                "Invalid QoS policy '{}': {v} is not a JSON object",
+                self.0
+            )),
+        }
+    }
+
+    #[inline]
+    fn kind<T: Copy>(&self, kinds: &[(&str, T)], default: Option<T>) -> Result<T, String> {
+        self.field_kind("kind", kinds, default)
+    }
+
+    // without default, the field is mandatory
+    fn field_kind<T: Copy>(
+        &self,
+        field: &str,
+        kinds: &[(&str, T)],
+        default: Option<T>,
+    ) -> Result<T, String> {
+        match self.field(field)? {
+            None => {
+                default.ok_or_else(|| format!("Invalid QoS policy '{}': missing '{field}'", self.0))
+            }
+            Some(v) => v
+                .as_str()
+                .and_then(|s| kinds.iter().find(|(name, _)| *name == s))
+                .map(|(_, kind)| *kind)
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid QoS policy '{}': unexpected {field} {v} (expected one of {:?})",
+                        self.0,
+                        kinds.iter().map(|(name, _)| *name).collect::<Vec<&str>>()
+                    )
+                }),
+        }
+    }
+
+    fn int(&self, field: &str, default: i32) -> Result<i32, String> {
+        match self.field(field)? {
+            None => Ok(default),
+            Some(v) => v
+                .as_i64()
+                .and_then(|i| i32::try_from(i).ok())
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid QoS policy '{}': {field} {v} is not an integer",
+                        self.0
+                    )
+                }),
+        }
+    }
+
+    fn duration(&self, field: &str, default: i64) -> Result<i64, String> {
+        match self.field(field)? {
+            None => Ok(default),
+            Some(v) => v
+                .as_i64()
+                .filter(|d| *d >= 0)
+                .or_else(|| v.as_str().and_then(parse_dds_duration))
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid QoS policy '{}': {field} {v} is not a duration (e.g. 100000000, \"100ms\" or \"infinite\")",
+                        self.0
+                    )
+                }),
+        }
+    }
+}
+
+// Parse a duration as rendered by dds_duration_to_string() (e.g. "100ms", "1.5s", "250µs" or "infinite")
+// into nanoseconds
+fn parse_dds_duration(s: &str) -> Option<i64> {
+    if s == "infinite" {
+        return Some(DDS_INFINITE_TIME);
+    }
+    let (number, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit() && c != '.')?);
+    let scale = match unit {
+        "ns" => 0,
+        "µs" | "us" => 3,
+        "ms" => 6,
+        "s" => 9,
+        _ => return None,
+    };
+    let (int, frac) = number.split_once('.').unwrap_or((number, ""));
+    if int.is_empty() || frac.len() > scale || !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let frac = format!("{frac:0<scale$}");
+    int.parse::<i64>()
+        .ok()?
+        .checked_mul(10i64.pow(scale as u32))?
+        .checked_add(if frac.is_empty() {
+            0
+        } else {
+            frac.parse::<i64>().ok()?
+        })
+}
+
+// Force the TRANSIENT_LOCAL Durability in a QoS (for the topics configured in `force_transient_local`)
+pub fn force_transient_local(qos: &Qos) -> Qos {
+    let mut result = qos.clone();
+    result.durability = Some(Durability {
+        kind: DurabilityKind::TRANSIENT_LOCAL,
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qos_with_reliability(kind: ReliabilityKind, max_blocking_time: i64) -> Qos {
+        Qos {
+            reliability: Some(Reliability {
+                kind,
+                max_blocking_time,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_apply_congestion_control() {
+        let reliable = qos_with_reliability(ReliabilityKind::RELIABLE, DDS_100MS_DURATION);
+        assert_eq!(apply_congestion_control(&reliable, None), reliable);
+        assert_eq!(
+            apply_congestion_control(&reliable, Some(CongestionControlOverride::Drop)),
+            qos_with_reliability(ReliabilityKind::RELIABLE, 0)
+        );
+        assert_eq!(
+            apply_congestion_control(&reliable, Some(CongestionControlOverride::Block)),
+            qos_with_reliability(ReliabilityKind::RELIABLE, DDS_INFINITE_TIME)
+        );
+        // a Writer is RELIABLE by default
+        assert_eq!(
+            apply_congestion_control(&Qos::default(), Some(CongestionControlOverride::Drop)),
+            qos_with_reliability(ReliabilityKind::RELIABLE, 0)
+        );
+        // a BEST_EFFORT Writer never blocks
+        let best_effort = qos_with_reliability(ReliabilityKind::BEST_EFFORT, DDS_100MS_DURATION);
+        assert_eq!(
+            apply_congestion_control(&best_effort, Some(CongestionControlOverride::Block)),
+            best_effort
+        );
+    }
+
+    #[test]
+    fn test_no_reliability_override() {
+        let qos = qos_with_reliability(ReliabilityKind::RELIABLE, DDS_1S_DURATION);
+        let result = apply_reliability_override(&qos, None);
+        assert_eq!(result, qos);
+
+        let qos = Qos::default();
+        let result = apply_reliability_override(&qos, None);
+        assert_eq!(result.reliability, None);
+    }
+
+    #[test]
+    fn test_best_effort_override() {
+        // on a RELIABLE Writer's QoS adapted for a Reader
+        let writer_qos = qos_with_reliability(ReliabilityKind::RELIABLE, DDS_1S_DURATION);
+        let reader_qos = adapt_writer_qos_for_reader(&writer_qos);
+        let result = apply_reliability_override(&reader_qos, Some(ReliabilityOverride::BestEffort));
+        assert!(!is_reliable(&result));
+        assert_eq!(
+            result.reliability.as_ref().map(|r| r.kind),
+            Some(ReliabilityKind::BEST_EFFORT)
+        );
+        // other QoS are untouched
+        assert_eq!(result.ignore_local, reader_qos.ignore_local);
+        assert_eq!(result.durability, reader_qos.durability);
+
+        // on a RELIABLE Reader's QoS adapted for a Writer
+        let reader_qos = qos_with_reliability(ReliabilityKind::RELIABLE, DDS_100MS_DURATION);
+        let writer_qos = adapt_reader_qos_for_writer(&reader_qos);
+        let result = apply_reliability_override(&writer_qos, Some(ReliabilityOverride::BestEffort));
+        assert_eq!(
+            result.reliability.as_ref().map(|r| r.kind),
+            Some(ReliabilityKind::BEST_EFFORT)
+        );
+    }
+
+    #[test]
+    fn test_reliable_override() {
+        // on a BEST_EFFORT Reader's QoS adapted for a Writer
+        let reader_qos = qos_with_reliability(ReliabilityKind::BEST_EFFORT, DDS_100MS_DURATION);
+        let writer_qos = adapt_reader_qos_for_writer(&reader_qos);
+        let result = apply_reliability_override(&writer_qos, Some(ReliabilityOverride::BestEffort));
+        assert!(!is_reliable(&result));
+        let result = apply_reliability_override(&result, Some(ReliabilityOverride::Reliable));
+        assert!(is_reliable(&result));
+        assert_eq!(
+            result.reliability.as_ref().map(|r| r.max_blocking_time),
+            Some(DDS_100MS_DURATION)
+        );
+
+        // on an already RELIABLE QoS, the max_blocking_time is preserved
+        let qos = qos_with_reliability(ReliabilityKind::RELIABLE, DDS_1S_DURATION);
+        let result = apply_reliability_override(&qos, Some(ReliabilityOverride::Reliable));
+        assert!(is_reliable(&result));
+        assert_eq!(
+            result.reliability.as_ref().map(|r| r.max_blocking_time),
+            Some(DDS_1S_DURATION)
+        );
+    }
+
+    #[test]
+    fn test_reader_reliability_override() {
+        // a BEST_EFFORT Writer's QoS adapted for a Reader is never raised to RELIABLE (it wouldn't match the Writer)
+        let writer_qos = qos_with_reliability(ReliabilityKind::BEST_EFFORT, DDS_100MS_DURATION);
+        let reader_qos = adapt_writer_qos_for_reader(&writer_qos);
+        let result =
+            apply_reader_reliability_override(&reader_qos, Some(ReliabilityOverride::Reliable));
+        assert_eq!(result, reader_qos);
+        assert!(!is_reliable(&result));
+        let reader_qos = adapt_writer_qos_for_reader(&Qos::default());
+        let result =
+            apply_reader_reliability_override(&reader_qos, Some(ReliabilityOverride::Reliable));
+        assert!(!is_reliable(&result));
+
+        // a RELIABLE Writer's QoS adapted for a Reader can be lowered to BEST_EFFORT
+        let writer_qos = qos_with_reliability(ReliabilityKind::RELIABLE, DDS_1S_DURATION);
+        let reader_qos = adapt_writer_qos_for_reader(&writer_qos);
+        let result =
+            apply_reader_reliability_override(&reader_qos, Some(ReliabilityOverride::Reliable));
+        assert_eq!(result, reader_qos);
+        let result =
+            apply_reader_reliability_override(&reader_qos, Some(ReliabilityOverride::BestEffort));
+        assert_eq!(
+            result.reliability.as_ref().map(|r| r.kind),
+            Some(ReliabilityKind::BEST_EFFORT)
+        );
+        assert_eq!(
+            apply_reader_reliability_override(&reader_qos, None),
+            reader_qos
+        );
+    }
+
+    #[test]
+    fn test_force_transient_local() {
+        // on a VOLATILE Writer's QoS
+        let qos = Qos {
+            durability: Some(Durability {
+                kind: DurabilityKind::VOLATILE,
+            }),
+            ..qos_with_reliability(ReliabilityKind::RELIABLE, DDS_1S_DURATION)
+        };
+        assert!(!is_transient_local(&qos));
+        let result = force_transient_local(&qos);
+        assert!(is_transient_local(&result));
+        // other QoS are untouched
+        assert_eq!(result.reliability, qos.reliability);
+
+        // on a QoS without Durability (defaults to VOLATILE)
+        assert!(is_transient_local(&force_transient_local(&Qos::default())));
+    }
+
+    #[test]
+    fn test_deadline_period() {
+        assert_eq!(get_deadline_period(&Qos::default()), None);
+        let mut qos = Qos {
+            deadline: Some(Deadline {
+                period: DDS_INFINITE_TIME,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(get_deadline_period(&qos), None);
+        qos.deadline = Some(Deadline {
+            period: DDS_100MS_DURATION,
+        });
+        assert_eq!(
+            get_deadline_period(&qos),
+            Some(std::time::Duration::from_millis(100))
+        );
+    }
+
+    #[test]
+    fn test_lifespan_duration() {
+        assert_eq!(get_lifespan_duration(&Qos::default()), None);
+        let mut qos = Qos {
+            lifespan: Some(Lifespan {
+                duration: DDS_INFINITE_TIME,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(get_lifespan_duration(&qos), None);
+        qos.lifespan = Some(Lifespan {
+            duration: 2 * DDS_100MS_DURATION,
+        });
+        assert_eq!(
+            get_lifespan_duration(&qos),
+            Some(std::time::Duration::from_millis(200))
+        );
+        // the Lifespan is kept for the Writer created for a remote Writer, but not for a Reader
+        assert_eq!(
+            adapt_reader_qos_for_writer(&qos).lifespan,
+            qos.lifespan.clone()
+        );
+        assert_eq!(adapt_writer_qos_for_reader(&qos).lifespan, None);
+    }
+
+    #[test]
+    fn test_manual_liveliness() {
+        assert_eq!(get_manual_liveliness_lease(&Qos::default()), None);
+        let mut qos = Qos {
+            liveliness: Some(Liveliness {
+                kind: LivelinessKind::AUTOMATIC,
+                lease_duration: DDS_1S_DURATION,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(get_manual_liveliness_lease(&qos), None);
+        assert_eq!(manual_liveliness_by_topic(&qos), qos);
+
+        qos.liveliness = Some(Liveliness {
+            kind: LivelinessKind::MANUAL_BY_TOPIC,
+            lease_duration: DDS_INFINITE_TIME,
+        });
+        assert_eq!(get_manual_liveliness_lease(&qos), None);
+
+        qos.liveliness = Some(Liveliness {
+            kind: LivelinessKind::MANUAL_BY_PARTICIPANT,
+            lease_duration: DDS_1S_DURATION,
+        });
+        assert_eq!(
+            get_manual_liveliness_lease(&qos),
+            Some(std::time::Duration::from_secs(1))
+        );
+        let result = manual_liveliness_by_topic(&qos);
+        assert_eq!(
+            result.liveliness.as_ref().map(|l| l.kind),
+            Some(LivelinessKind::MANUAL_BY_TOPIC)
+        );
+        assert_eq!(
+            result.liveliness.as_ref().map(|l| l.lease_duration),
+            Some(DDS_1S_DURATION)
+        );
+        // the Liveliness is kept for the Writer created for a remote Writer
+        assert_eq!(adapt_reader_qos_for_writer(&qos).liveliness, qos.liveliness);
+    }
+
+    #[test]
+    fn test_qos_incompatibilities() {
+        // default QoS are compatible
+        assert!(qos_incompatibilities(&Qos::default(), &Qos::default()).is_empty());
+
+        // a BEST_EFFORT Writer with a RELIABLE Reader
+        let writer_qos = qos_with_reliability(ReliabilityKind::BEST_EFFORT, DDS_100MS_DURATION);
+        let reader_qos = qos_with_reliability(ReliabilityKind::RELIABLE, DDS_100MS_DURATION);
+        assert_eq!(
+            qos_incompatibilities(&writer_qos, &reader_qos),
+            vec!["Reliability: RELIABLE requested by the Reader, but BEST_EFFORT offered by the Writer"]
+        );
+        // the reverse is compatible
+        assert!(qos_incompatibilities(&reader_qos, &writer_qos).is_empty());
+
+        // a VOLATILE Writer with a TRANSIENT_LOCAL Reader, and a Deadline requested by the Reader only
+        let reader_qos = Qos {
+            durability: Some(Durability {
+                kind: DurabilityKind::TRANSIENT_LOCAL,
+            }),
+            deadline: Some(Deadline {
+                period: DDS_100MS_DURATION,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            qos_incompatibilities(&Qos::default(), &reader_qos),
+            vec![
+                "Durability: TRANSIENT_LOCAL requested by the Reader, but VOLATILE offered by the Writer",
+                "Deadline: 100ms requested by the Reader, but infinite offered by the Writer"
+            ]
+        );
+
+        // a Liveliness lease longer than requested
+        let liveliness = |kind, lease_duration| Qos {
+            liveliness: Some(Liveliness {
+                kind,
+                lease_duration,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            qos_incompatibilities(
+                &liveliness(LivelinessKind::MANUAL_BY_TOPIC, DDS_1S_DURATION),
+                &liveliness(LivelinessKind::AUTOMATIC, DDS_100MS_DURATION)
+            ),
+            vec!["Liveliness: AUTOMATIC with lease 100ms requested by the Reader, but MANUAL_BY_TOPIC with lease 1s offered by the Writer"]
+        );
+        assert!(qos_incompatibilities(
+            &liveliness(LivelinessKind::MANUAL_BY_TOPIC, DDS_100MS_DURATION),
+            &liveliness(LivelinessKind::MANUAL_BY_PARTICIPANT, DDS_1S_DURATION)
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_latency_budget() {
+        let latency_budget = |duration| Qos {
+            latency_budget: Some(LatencyBudget { duration }),
+            ..Default::default()
+        };
+        assert_eq!(get_latency_budget(&Qos::default()), None);
+        assert_eq!(get_latency_budget(&latency_budget(0)), None);
+        assert_eq!(
+            get_latency_budget(&latency_budget(DDS_100MS_DURATION)),
+            Some(std::time::Duration::from_millis(100))
+        );
+
+        // copied onto the Reader and the Writer created by the bridge
+        let qos = latency_budget(DDS_100MS_DURATION);
+        assert_eq!(
+            adapt_writer_qos_for_reader(&qos).latency_budget,
+            qos.latency_budget
+        );
+        assert_eq!(
+            adapt_reader_qos_for_writer(&qos).latency_budget,
+            qos.latency_budget
+        );
+
+        // a Writer offering a longer LatencyBudget than requested doesn't match
+        assert_eq!(
+            qos_incompatibilities(&qos, &Qos::default()),
+            vec!["LatencyBudget: 0ns requested by the Reader, but 100ms offered by the Writer"]
+        );
+        assert!(qos_incompatibilities(&Qos::default(), &qos).is_empty());
+        assert!(qos_incompatibilities(&qos, &latency_budget(DDS_1S_DURATION)).is_empty());
+        let upgraded = upgrade_offered_qos(&qos, &Qos::default());
+        assert_eq!(upgraded.latency_budget, None);
+        assert!(qos_incompatibilities(&upgraded, &Qos::default()).is_empty());
+
+        // the route's Reader requests the longest LatencyBudget of the merged Writers
+        let merged = merge_writers_qos([&Qos::default(), &latency_budget(DDS_1S_DURATION), &qos]);
+        assert_eq!(
+            merged.map(|q| q.latency_budget),
+            Some(Some(LatencyBudget {
+                duration: DDS_1S_DURATION
+            }))
+        );
+    }
+
+    #[test]
+    fn test_upgrade_offered_qos() {
+        let writer_qos = Qos {
+            history: Some(History {
+                kind: HistoryKind::KEEP_LAST,
+                depth: 5,
+            }),
+            ..qos_with_reliability(ReliabilityKind::BEST_EFFORT, DDS_100MS_DURATION)
+        };
+        let reader_qos = Qos {
+            durability: Some(Durability {
+                kind: DurabilityKind::TRANSIENT_LOCAL,
+            }),
+            deadline: Some(Deadline {
+                period: DDS_100MS_DURATION,
+            }),
+            liveliness: Some(Liveliness {
+                kind: LivelinessKind::MANUAL_BY_TOPIC,
+                lease_duration: DDS_1S_DURATION,
+            }),
+            ..qos_with_reliability(ReliabilityKind::RELIABLE, DDS_100MS_DURATION)
+        };
+        assert_eq!(qos_incompatibilities(&writer_qos, &reader_qos).len(), 4);
+        let result = upgrade_offered_qos(&writer_qos, &reader_qos);
+        assert!(qos_incompatibilities(&result, &reader_qos).is_empty());
+        assert!(is_reliable(&result));
+        assert!(is_transient_local(&result));
+        // other QoS are untouched
+        assert_eq!(result.history, writer_qos.history);
+
+        // compatible QoS are unchanged
+        assert_eq!(upgrade_offered_qos(&result, &reader_qos), result);
+    }
+
+    #[test]
+    fn test_merge_writers_qos() {
+        assert_eq!(merge_writers_qos(&[]), None);
+
+        let qos1 = Qos {
+            durability: Some(Durability {
+                kind: DurabilityKind::TRANSIENT_LOCAL,
+            }),
+            history: Some(History {
+                kind: HistoryKind::KEEP_LAST,
+                depth: 1,
+            }),
+            ..qos_with_reliability(ReliabilityKind::RELIABLE, DDS_100MS_DURATION)
+        };
+        let qos2 = Qos {
+            durability: Some(Durability {
+                kind: DurabilityKind::VOLATILE,
+            }),
+            history: Some(History {
+                kind: HistoryKind::KEEP_LAST,
+                depth: 10,
+            }),
+            deadline: Some(Deadline {
+                period: DDS_1S_DURATION,
+            }),
+            ..qos_with_reliability(ReliabilityKind::BEST_EFFORT, DDS_100MS_DURATION)
+        };
+        let qos3 = Qos::default();
+
+        // a single QoS is unchanged
+        assert_eq!(merge_writers_qos(&[qos2.clone()]), Some(qos2.clone()));
+
+        // the strongest policies
+        let merged = merge_writers_qos(&[qos1.clone(), qos2.clone()]).unwrap();
+        assert!(is_reliable(&merged));
+        assert!(is_transient_local(&merged));
+        assert_eq!(
+            merged.history,
+            Some(History {
+                kind: HistoryKind::KEEP_LAST,
+                depth: 10
+            })
+        );
+        // a Writer without Reliability is RELIABLE
+        let merged = merge_writers_qos(&[qos2.clone(), qos3.clone()]).unwrap();
+        assert!(is_reliable(&merged));
+        assert!(merge_writers_qos(&[qos3.clone(), qos3.clone()])
+            .unwrap()
+            .reliability
+            .is_none());
+
+        // independent of the order
+        let expected = merge_writers_qos(&[qos1.clone(), qos2.clone(), qos3.clone()]);
+        for permutation in [
+            [&qos1, &qos3, &qos2],
+            [&qos2, &qos1, &qos3],
+            [&qos2, &qos3, &qos1],
+            [&qos3, &qos1, &qos2],
+            [&qos3, &qos2, &qos1],
+        ] {
+            assert_eq!(merge_writers_qos(permutation), expected);
+        }
+    }
+
+    #[test]
+    fn test_deadline_propagation() {
+        let deadline = Some(Deadline {
+            period: DDS_100MS_DURATION,
+        });
+        let qos = Qos {
+            deadline: deadline.clone(),
+            ..qos_with_reliability(ReliabilityKind::RELIABLE, DDS_100MS_DURATION)
+        };
+        // the Deadline is kept for the matching Reader or Writer created by the bridge
+        assert_eq!(adapt_writer_qos_for_reader(&qos).deadline, deadline);
+        assert_eq!(adapt_reader_qos_for_writer(&qos).deadline, deadline);
+
+        let result = filter_deadline(qos.clone(), true);
+        assert_eq!(result, qos);
+        let result = filter_deadline(qos.clone(), false);
+        assert_eq!(result.deadline, None);
+        // other QoS are untouched
+        assert_eq!(result.reliability, qos.reliability);
+    }
+
+    #[test]
+    fn test_time_based_filter() {
+        use std::time::Duration;
+
+        let mut qos = Qos::default();
+        assert_eq!(get_time_based_filter_separation(&qos), None);
+        qos.time_based_filter = Some(TimeBasedFilter {
+            minimum_separation: 0,
+        });
+        assert_eq!(get_time_based_filter_separation(&qos), None);
+        qos.time_based_filter = Some(TimeBasedFilter {
+            minimum_separation: 5 * DDS_100MS_DURATION,
+        });
+        assert_eq!(
+            get_time_based_filter_separation(&qos),
+            Some(Duration::from_millis(500))
+        );
+        // not applicable to the Writer created by the bridge
+        assert_eq!(adapt_reader_qos_for_writer(&qos).time_based_filter, None);
+        assert_eq!(filter_time_based_filter(qos.clone(), true), qos);
+        assert_eq!(
+            filter_time_based_filter(qos.clone(), false).time_based_filter,
+            None
+        );
+
+        // the smallest separation satisfies all the Readers, unless one has no TimeBasedFilter
+        let (s1, s2) = (Duration::from_millis(500), Duration::from_millis(200));
+        assert_eq!(min_time_based_filter_separation([]), None);
+        assert_eq!(min_time_based_filter_separation([Some(s1)]), Some(s1));
+        assert_eq!(
+            min_time_based_filter_separation([Some(s1), Some(s2)]),
+            Some(s2)
+        );
+        assert_eq!(
+            min_time_based_filter_separation([Some(s1), None, Some(s2)]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_keep_all_history() {
+        let keep_last_50 = History {
+            kind: HistoryKind::KEEP_LAST,
+            depth: 50,
+        };
+        let keep_all = History {
+            kind: HistoryKind::KEEP_ALL,
+            depth: 1,
+        };
+        let with_history = |history: Option<History>| Qos {
+            history,
+            durability: Some(Durability {
+                kind: DurabilityKind::TRANSIENT_LOCAL,
+            }),
+            ..Default::default()
+        };
+
+        for history in [None, Some(keep_last_50.clone()), Some(keep_all.clone())] {
+            let qos = with_history(history.clone());
+            let is_all = history.as_ref().map(|h| h.kind) == Some(HistoryKind::KEEP_ALL);
+            assert_eq!(is_keep_all(&qos), is_all);
+
+            // the History is propagated as is to the matching Reader or Writer created by the bridge
+            assert_eq!(adapt_writer_qos_for_reader(&qos).history, history);
+            let writer_qos = adapt_reader_qos_for_writer(&qos);
+            assert_eq!(writer_qos.history, history);
+            let history_or_default = history.clone().unwrap_or_default();
+            let durability_service = writer_qos.durability_service.clone().unwrap();
+            assert_eq!(durability_service.history_kind, history_or_default.kind);
+            assert_eq!(durability_service.history_depth, history_or_default.depth);
+            assert_eq!(is_keep_all(&writer_qos), is_all);
+
+            // no cap configured: unchanged
+            assert_eq!(cap_keep_all_history(&writer_qos, None), writer_qos);
+
+            for (max_depth, expected_depth) in [(100, 100), (0, 1), (usize::MAX, i32::MAX)] {
+                let capped = cap_keep_all_history(&writer_qos, Some(max_depth));
+                assert!(!is_keep_all(&capped));
+                if is_all {
+                    let expected = History {
+                        kind: HistoryKind::KEEP_LAST,
+                        depth: expected_depth,
+                    };
+                    assert_eq!(capped.history, Some(expected));
+                    let durability_service = capped.durability_service.unwrap();
+                    assert_eq!(durability_service.history_kind, HistoryKind::KEEP_LAST);
+                    assert_eq!(durability_service.history_depth, expected_depth);
+                } else {
+                    // KEEP_LAST (or default) History is kept as is, whatever the cap
+                    assert_eq!(capped, writer_qos);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_qos_to_json() {
+        use serde_json::json;
+
+        assert_eq!(qos_to_json(&Qos::default()).unwrap(), json!({}));
+
+        let qos = Qos {
+            history: Some(History {
+                kind: HistoryKind::KEEP_LAST,
+                depth: 10,
+            }),
+            durability: Some(Durability {
+                kind: DurabilityKind::TRANSIENT_LOCAL,
+            }),
+            deadline: Some(Deadline {
+                period: DDS_100MS_DURATION,
+            }),
+            liveliness: Some(Liveliness {
+                kind: LivelinessKind::MANUAL_BY_TOPIC,
+                lease_duration: DDS_INFINITE_TIME,
+            }),
+            ..qos_with_reliability(ReliabilityKind::RELIABLE, DDS_1S_DURATION)
+        };
+        assert_eq!(
+            qos_to_json(&qos).unwrap(),
+            json!({
+                "reliability": {"kind": "RELIABLE", "max_blocking_time": "1s"},
+                "durability": {"kind": "TRANSIENT_LOCAL"},
+                "history": {"kind": "KEEP_LAST", "depth": 10},
+                "deadline": {"period": "100ms"},
+                "liveliness": {"kind": "MANUAL_BY_TOPIC", "lease_duration": "infinite"},
+            })
+        );
+    }
+
+    #[test]
+    fn test_qos_from_json() {
+        use serde_json::json;
+
+        assert_eq!(qos_from_json(&json!({})), Ok(Qos::default()));
+
+        // round-trip of each policy
+        for qos in [
+            qos_with_reliability(ReliabilityKind::BEST_EFFORT, DDS_100MS_DURATION),
+            qos_with_reliability(ReliabilityKind::RELIABLE, DDS_INFINITE_TIME),
+            Qos {
+                durability: Some(Durability {
+                    kind: DurabilityKind::TRANSIENT_LOCAL,
+                }),
+                ..Default::default()
+            },
+            Qos {
+                history: Some(History {
+                    kind: HistoryKind::KEEP_LAST,
+                    depth: 10,
+                }),
+                ..Default::default()
+            },
+            Qos {
+                deadline: Some(Deadline {
+                    period: 1_500_000_000,
+                }),
+                ..Default::default()
+            },
+            Qos {
+                lifespan: Some(Lifespan {
+                    duration: 2_000_000_001,
+                }),
+                ..Default::default()
+            },
+            Qos {
+                liveliness: Some(Liveliness {
+                    kind: LivelinessKind::MANUAL_BY_TOPIC,
+                    lease_duration: 250_000,
+                }),
+                ..Default::default()
+            },
+            set_exclusive_ownership_strength(&Qos::default(), Some(10)),
+            Qos {
+                partition: Some(vec!["prod".into(), "site*".into()]),
+                ..Default::default()
+            },
+            Qos {
+                durability_service: Some(DurabilityService {
+                    service_cleanup_delay: 0,
+                    history_kind: HistoryKind::KEEP_ALL,
+                    history_depth: 5,
+                    max_samples: 100,
+                    max_instances: DDS_LENGTH_UNLIMITED,
+                    max_samples_per_instance: 5,
+                }),
+                ..Default::default()
+            },
+        ] {
+            assert_eq!(qos_from_json(&qos_to_json(&qos).unwrap()), Ok(qos));
+        }
+
+        // the omitted fields take their default value, and the durations can be numbers of nanoseconds
+        let qos = qos_from_json(&json!({
+            "reliability": {"kind": "RELIABLE"},
+            "history": {"kind": "KEEP_LAST"},
+            "liveliness": {},
+            "deadline": {"period": 100000000},
+            "durability_service": {"history_depth": 3},
+        }))
+        .unwrap();
+        assert_eq!(
+            qos.reliability,
+            Some(Reliability {
+                kind: ReliabilityKind::RELIABLE,
+                max_blocking_time: DDS_100MS_DURATION
+            })
+        );
+        assert_eq!(get_history_or_default(&qos).depth, 1);
+        assert_eq!(
+            qos.liveliness,
+            Some(Liveliness {
+                kind: LivelinessKind::AUTOMATIC,
+                lease_duration: DDS_INFINITE_TIME
+            })
+        );
+        assert_eq!(
+            get_deadline_period(&qos),
+            Some(std::time::Duration::from_millis(100))
+        );
+        assert_eq!(
+            qos.durability_service
+                .map(|d| (d.history_depth, d.max_samples)),
+            Some((3, DurabilityService::default().max_samples))
+        );
+
+        for invalid in [
+            json!([]),
+            json!({"reliability": {}}),
+            json!({"reliability": "RELIABLE"}),
+            json!({"durability": {"kind": "DURABLE"}}),
+            json!({"history": {"kind": "KEEP_LAST", "depth": "10"}}),
+            json!({"deadline": {"period": "100 ms"}}),
+            json!({"deadline": {"period": "1.0000000001s"}}),
+            json!({"deadline": {"period": -1}}),
+            json!({"partition": "prod"}),
+            json!({"presentation": {}}),
+        ] {
+            assert!(qos_from_json(&invalid).is_err(), "{invalid} accepted");
+        }
+    }
+
+    #[test]
+    fn test_min_history_depth() {
+        let keep_last = |depth| Qos {
+            history: Some(History {
+                kind: HistoryKind::KEEP_LAST,
+                depth,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(with_min_history_depth(&keep_last(1), 100), keep_last(100));
+        assert_eq!(with_min_history_depth(&keep_last(500), 100), keep_last(500));
+        // default History: KEEP_LAST(1)
+        assert_eq!(with_min_history_depth(&Qos::default(), 100), keep_last(100));
+        let keep_all = Qos {
+            history: Some(History {
+                kind: HistoryKind::KEEP_ALL,
+                depth: 0,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(with_min_history_depth(&keep_all, 100), keep_all);
+
+        // the DurabilityService announced by the remote bridge is raised too
+        let mut qos = with_durability_service(&force_transient_local(&keep_last(1)));
+        qos = with_min_history_depth(&qos, 100);
+        assert_eq!(transient_local_history(&qos).depth, 100);
+        assert_eq!(get_history_or_default(&qos).depth, 100);
+    }
+
+    #[test]
+    fn test_apply_partition() {
+        let prod = vec!["prod".to_string()];
+        let qos = qos_with_reliability(ReliabilityKind::RELIABLE, DDS_100MS_DURATION);
+        assert_eq!(apply_partition(&qos, None), qos);
+        let result = apply_partition(&qos, Some(&prod));
+        assert_eq!(result.partition, Some(prod.clone()));
+        assert_eq!(result.reliability, qos.reliability);
+
+        // the configured Partitions replace the discovered ones
+        let qos = Qos {
+            partition: Some(vec!["test".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(apply_partition(&qos, None), qos);
+        assert_eq!(apply_partition(&qos, Some(&prod)).partition, Some(prod));
+        // and are kept through the adaptation for a matching Reader or Writer
+        assert_eq!(adapt_writer_qos_for_reader(&qos).partition, qos.partition);
+        assert_eq!(adapt_reader_qos_for_writer(&qos).partition, qos.partition);
+    }
+
+    #[test]
+    fn test_ownership() {
+        let shared = Qos::default();
+        assert_eq!(get_exclusive_ownership_strength(&shared), None);
+        let exclusive = Qos {
+            ownership: Some(Ownership {
+                kind: OwnershipKind::EXCLUSIVE,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(get_exclusive_ownership_strength(&exclusive), Some(0));
+        let strong = set_exclusive_ownership_strength(&shared, Some(10));
+        assert_eq!(get_exclusive_ownership_strength(&strong), Some(10));
+        assert_eq!(set_exclusive_ownership_strength(&strong, None), shared);
+
+        // the strength doesn't apply to a Reader, but its kind must be the Writer's one
+        let reader_qos = adapt_writer_qos_for_reader(&strong);
+        assert_eq!(reader_qos.ownership, strong.ownership);
+        assert_eq!(reader_qos.ownership_strength, None);
+        assert!(qos_incompatibilities(&strong, &reader_qos).is_empty());
+        assert_eq!(
+            qos_incompatibilities(&shared, &reader_qos),
+            vec!["Ownership: EXCLUSIVE requested by the Reader, but SHARED offered by the Writer"]
+        );
+        assert_eq!(
+            qos_incompatibilities(&strong, &Qos::default()),
+            vec!["Ownership: SHARED requested by the Reader, but EXCLUSIVE offered by the Writer"]
+        );
+        let upgraded = upgrade_offered_qos(&shared, &reader_qos);
+        assert_eq!(get_exclusive_ownership_strength(&upgraded), Some(0));
+        let upgraded = upgrade_offered_qos(&strong, &Qos::default());
+        assert_eq!(get_exclusive_ownership_strength(&upgraded), None);
+
+        // the strongest Writer gives the merged strength
+        let weak = set_exclusive_ownership_strength(&shared, Some(1));
+        let merged = merge_writers_qos(&[weak.clone(), strong.clone()]).unwrap();
+        assert_eq!(get_exclusive_ownership_strength(&merged), Some(10));
+        let merged = merge_writers_qos(&[strong, weak.clone()]).unwrap();
+        assert_eq!(get_exclusive_ownership_strength(&merged), Some(10));
+        let merged = merge_writers_qos(&[shared.clone(), shared]).unwrap();
+        assert_eq!(get_exclusive_ownership_strength(&merged), None);
+    }
+
+    #[test]
+    fn test_durability_service() {
+        let history = History {
+            kind: HistoryKind::KEEP_LAST,
+            depth: 5,
+        };
+        let durability_service = DurabilityService {
+            service_cleanup_delay: 0,
+            history_kind: HistoryKind::KEEP_LAST,
+            history_depth: 20,
+            max_samples: 100,
+            max_instances: 5,
+            max_samples_per_instance: 20,
+        };
+        let volatile = Qos {
+            history: Some(history.clone()),
+            durability_service: Some(durability_service.clone()),
+            ..Default::default()
+        };
+        let transient_local = force_transient_local(&volatile);
+
+        // kept for a TRANSIENT_LOCAL Writer only, to size the historical data
+        assert_eq!(
+            adapt_writer_qos_for_reader(&volatile).durability_service,
+            None
+        );
+        let reader_qos = adapt_writer_qos_for_reader(&transient_local);
+        assert_eq!(
+            reader_qos.durability_service,
+            Some(durability_service.clone())
+        );
+        assert_eq!(transient_local_history(&reader_qos).depth, 20);
+        assert_eq!(
+            without_durability_service(&reader_qos).durability_service,
+            None
+        );
+        assert_eq!(transient_local_history(&volatile).depth, 20);
+        assert_eq!(
+            transient_local_history(&without_durability_service(&reader_qos)),
+            history
+        );
+
+        // set for a TRANSIENT_LOCAL Writer only if not announced
+        assert_eq!(with_durability_service(&transient_local), transient_local);
+        let announced = without_durability_service(&transient_local);
+        let writer_qos = with_durability_service(&announced);
+        let ds = writer_qos.durability_service.unwrap();
+        assert_eq!(
+            (ds.history_kind, ds.history_depth),
+            (HistoryKind::KEEP_LAST, 5)
+        );
+        assert_eq!(ds.max_instances, DDS_LENGTH_UNLIMITED);
+        let volatile = without_durability_service(&volatile);
+        assert_eq!(with_durability_service(&volatile), volatile);
+    }
+
+    #[test]
+    fn test_apply_topic_qos() {
+        let qos = Qos {
+            durability: Some(Durability {
+                kind: DurabilityKind::TRANSIENT_LOCAL,
+            }),
+            durability_service: Some(durability_service_from_history(&Qos::default())),
+            history: Some(History {
+                kind: HistoryKind::KEEP_LAST,
+                depth: 10,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(apply_topic_qos(&qos, None), qos);
+        assert_eq!(apply_topic_qos(&qos, Some(&TopicQos::default())), qos);
+
+        let topic_qos = TopicQos {
+            durability: Some(DurabilityOverride::Volatile),
+            history: Some(HistoryOverride::KeepAll),
+            deadline: Some(0.1),
+            lifespan: Some(2.0),
+            ..Default::default()
+        };
+        let result = apply_topic_qos(&qos, Some(&topic_qos));
+        assert!(!is_transient_local(&result));
+        assert_eq!(result.durability_service, None);
+        assert!(is_keep_all(&result));
+        assert_eq!(
+            get_deadline_period(&result),
+            Some(std::time::Duration::from_millis(100))
+        );
+        assert_eq!(
+            get_lifespan_duration(&result),
+            Some(std::time::Duration::from_secs(2))
+        );
+
+        // TRANSIENT_LOCAL is not forced here, but via Config::is_transient_local_forced()
+        let topic_qos = TopicQos {
+            durability: Some(DurabilityOverride::TransientLocal),
+            history: Some(HistoryOverride::KeepLast(3)),
+            ..Default::default()
+        };
+        let result = apply_topic_qos(&Qos::default(), Some(&topic_qos));
+        assert!(!is_transient_local(&result));
+        assert_eq!(get_history_or_default(&result).depth, 3);
+    }
+}
@@ -0,0 +1,327 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use zenoh_core::zlock;
+
+use crate::config::RateLimitPolicy;
+
+// The period between 2 checks of a RateLimiter without maximum frequency (in case one is set)
+const IDLE_PERIOD: Duration = Duration::from_secs(1);
+
+/// The limitation of the messages routed by a route to a maximum frequency, per instance
+/// (see `pub_max_frequencies` and `sub_max_frequencies` config). The messages that can't be routed
+/// immediately are kept (only the latest per instance), and routed by a timer calling [`RateLimiter::tick()`].
+/// The maximum frequency can be changed without re-creating the route.
+pub struct RateLimiter<T> {
+    policy: RateLimitPolicy,
+    max_frequency: Mutex<Option<f32>>,
+    instances: Mutex<HashMap<[u8; 16], InstanceState<T>>>,
+    // the time of the next tick with the Periodic policy
+    next_tick: Mutex<Option<Instant>>,
+    dropped: AtomicU64,
+}
+
+struct InstanceState<T> {
+    last_routed: Option<Instant>,
+    pending: Option<T>,
+}
+
+impl<T> RateLimiter<T> {
+    pub fn new(policy: RateLimitPolicy, max_frequency: Option<f32>) -> RateLimiter<T> {
+        RateLimiter {
+            policy,
+            max_frequency: Mutex::new(max_frequency.filter(|f| *f > 0.0)),
+            instances: Mutex::new(HashMap::new()),
+            next_tick: Mutex::new(None),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    #[inline]
+    pub fn max_frequency(&self) -> Option<f32> {
+        *zlock!(self.max_frequency)
+    }
+
+    /// Change the maximum frequency (`None` or 0 to route all the messages)
+    pub fn set_max_frequency(&self, max_frequency: Option<f32>) {
+        *zlock!(self.max_frequency) = max_frequency.filter(|f| *f > 0.0);
+        *zlock!(self.next_tick) = None;
+    }
+
+    #[inline]
+    pub fn period(&self) -> Option<Duration> {
+        self.max_frequency()
+            .map(|f| Duration::from_secs_f64(1.0 / f as f64))
+    }
+
+    /// Offer a message of `instance` received at `now`: return it if it can be routed immediately.
+    /// Otherwise, it's kept to be returned by a next tick (replacing and dropping the previously kept one).
+    pub fn offer(&self, instance: [u8; 16], msg: T, now: Instant) -> Option<T> {
+        let Some(period) = self.period() else {
+            return Some(msg);
+        };
+        let mut instances = zlock!(self.instances);
+        let state = instances.entry(instance).or_insert(InstanceState {
+            last_routed: None,
+            pending: None,
+        });
+        if self.policy == RateLimitPolicy::DropExcess
+            && state
+                .last_routed
+                .map_or(true, |last| now.saturating_duration_since(last) >= period)
+        {
+            state.last_routed = Some(now);
+            if state.pending.take().is_some() {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            return Some(msg);
+        }
+        if state.pending.replace(msg).is_some() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        None
+    }
+
+    /// Return the kept messages that are due at `now`:
+    ///  - with the Periodic policy: all of them, if a period elapsed since the previous tick
+    ///  - with the DropExcess policy: the ones of the instances which last message was routed a period ago
+    /// Without maximum frequency (e.g. it was reset), all the kept messages are returned.
+    pub fn tick(&self, now: Instant) -> Vec<T> {
+        let period = self.period();
+        if let (Some(period), RateLimitPolicy::Periodic) = (period, self.policy) {
+            let mut next_tick = zlock!(self.next_tick);
+            match *next_tick {
+                Some(t) if now < t => return Vec::new(),
+                // keep the cadence, unless late of more than a period
+                Some(t) if now < t + period => *next_tick = Some(t + period),
+                _ => *next_tick = Some(now + period),
+            }
+        }
+        let mut due = Vec::new();
+        for state in zlock!(self.instances).values_mut() {
+            let is_due = match (period, self.policy) {
+                (Some(period), RateLimitPolicy::DropExcess) => state
+                    .last_routed
+                    .map_or(true, |last| now.saturating_duration_since(last) >= period),
+                _ => true,
+            };
+            if is_due {
+                if let Some(msg) = state.pending.take() {
+                    state.last_routed = Some(now);
+                    due.push(msg);
+                }
+            }
+        }
+        due
+    }
+
+    /// The delay until the next call to [`RateLimiter::tick()`] from `now`
+    pub fn next_tick_delay(&self, now: Instant) -> Duration {
+        let Some(period) = self.period() else {
+            return IDLE_PERIOD;
+        };
+        match self.policy {
+            RateLimitPolicy::Periodic => {
+                zlock!(self.next_tick).map_or(period, |t| t.saturating_duration_since(now))
+            }
+            RateLimitPolicy::DropExcess => zlock!(self.instances)
+                .values()
+                .filter(|s| s.pending.is_some())
+                .filter_map(|s| s.last_routed)
+                .map(|last| (last + period).saturating_duration_since(now))
+                .min()
+                .unwrap_or(period),
+        }
+    }
+
+    /// Forget an instance (e.g. disposed or unregistered), dropping its kept message
+    pub fn forget(&self, instance: &[u8; 16]) {
+        if let Some(state) = zlock!(self.instances).remove(instance) {
+            if state.pending.is_some() {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Forget an instance (e.g. an Action's goal that terminated), returning its kept message to be routed at once
+    pub fn flush(&self, instance: &[u8; 16]) -> Option<T> {
+        zlock!(self.instances)
+            .remove(instance)
+            .and_then(|state| state.pending)
+    }
+
+    #[inline]
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<T: Send + 'static> RateLimiter<T> {
+    /// Spawn the timer routing the kept messages via `route`, until the RateLimiter is dropped
+    pub fn spawn_timer<F>(self: &Arc<Self>, route: F)
+    where
+        F: Fn(T) + Send + 'static,
+    {
+        let limiter: Weak<RateLimiter<T>> = Arc::downgrade(self);
+        async_std::task::spawn(async move {
+            let mut delay = IDLE_PERIOD;
+            loop {
+                async_std::task::sleep(delay).await;
+                let Some(limiter) = limiter.upgrade() else {
+                    break;
+                };
+                let now = Instant::now();
+                for msg in limiter.tick(now) {
+                    route(msg);
+                }
+                delay = limiter.next_tick_delay(Instant::now());
+            }
+        });
+    }
+}
+
+impl<T> Serialize for RateLimiter<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("RateLimiter", 3)?;
+        s.serialize_field("max_frequency", &self.max_frequency())?;
+        s.serialize_field("rate_limit_policy", &self.policy)?;
+        s.serialize_field("dropped_rate_limited", &self.dropped())?;
+        s.end()
+    }
+}
+
+pub fn serialize_rate_limiter<S, T>(limiter: &Arc<RateLimiter<T>>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    limiter.as_ref().serialize(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RateLimiter;
+    use crate::config::RateLimitPolicy;
+    use std::time::{Duration, Instant};
+
+    // A 100 Hz source during 1s, limited to 10 Hz (ticking as the timer would),
+    // returning the routed messages with their routing time (in ms since start)
+    fn run_100hz_source(limiter: &RateLimiter<u32>) -> Vec<(u32, u64)> {
+        let start = Instant::now();
+        let mut routed = Vec::new();
+        let mut next_tick = start + limiter.next_tick_delay(start);
+        for i in 0..100u32 {
+            let now = start + Duration::from_millis(i as u64 * 10);
+            while next_tick <= now {
+                for msg in limiter.tick(next_tick) {
+                    routed.push((msg, (next_tick - start).as_millis() as u64));
+                }
+                next_tick += limiter
+                    .next_tick_delay(next_tick)
+                    .max(Duration::from_millis(1));
+            }
+            if let Some(msg) = limiter.offer([0; 16], i, now) {
+                routed.push((msg, (now - start).as_millis() as u64));
+            }
+        }
+        routed
+    }
+
+    #[test]
+    fn test_rate_limiter_periodic() {
+        let limiter = RateLimiter::new(RateLimitPolicy::Periodic, Some(10.0));
+        let routed = run_100hz_source(&limiter);
+        // on each 100ms tick, the last message received during the period
+        assert_eq!(
+            routed,
+            (1..10)
+                .map(|i| (i * 10 - 1, i as u64 * 100))
+                .collect::<Vec<_>>()
+        );
+        // the 10 messages of a period but the last one are dropped (the last period isn't ticked yet)
+        assert_eq!(limiter.dropped(), 9 * 9 + 9);
+        assert_eq!(
+            serde_json::to_value(&limiter).unwrap(),
+            serde_json::json!({"max_frequency": 10.0, "rate_limit_policy": "periodic", "dropped_rate_limited": 90})
+        );
+    }
+
+    #[test]
+    fn test_rate_limiter_drop_excess() {
+        let limiter = RateLimiter::new(RateLimitPolicy::DropExcess, Some(10.0));
+        let routed = run_100hz_source(&limiter);
+        // the 1st message immediately, then the latest one every 100ms
+        assert_eq!(routed[0], (0, 0));
+        assert_eq!(routed.len(), 10);
+        for (i, (msg, t)) in routed.iter().enumerate().skip(1) {
+            assert_eq!(*t, i as u64 * 100);
+            assert_eq!(*msg, i as u32 * 10 - 1);
+        }
+        assert_eq!(limiter.dropped(), 100 - 10 - 1);
+    }
+
+    #[test]
+    fn test_rate_limiter_per_instance() {
+        let limiter = RateLimiter::new(RateLimitPolicy::DropExcess, Some(1.0));
+        let now = Instant::now();
+        assert_eq!(limiter.offer([1; 16], 1, now), Some(1));
+        assert_eq!(limiter.offer([2; 16], 2, now), Some(2));
+        assert_eq!(limiter.offer([1; 16], 3, now), None);
+        assert!(limiter.tick(now + Duration::from_millis(500)).is_empty());
+        assert_eq!(limiter.tick(now + Duration::from_secs(1)), vec![3]);
+        // a disposed instance drops its kept message
+        assert_eq!(limiter.offer([2; 16], 4, now), None);
+        limiter.forget(&[2; 16]);
+        assert!(limiter.tick(now + Duration::from_secs(2)).is_empty());
+        assert_eq!(limiter.dropped(), 1);
+    }
+
+    #[test]
+    fn test_rate_limiter_flush() {
+        let limiter = RateLimiter::new(RateLimitPolicy::Periodic, Some(1.0));
+        let now = Instant::now();
+        assert_eq!(limiter.offer([1; 16], 1, now), None);
+        assert_eq!(limiter.offer([1; 16], 2, now), None);
+        assert_eq!(limiter.offer([2; 16], 3, now), None);
+        // the kept message of a flushed instance is returned at once, not dropped
+        assert_eq!(limiter.flush(&[1; 16]), Some(2));
+        assert_eq!(limiter.flush(&[1; 16]), None);
+        assert_eq!(limiter.tick(now), vec![3]);
+        assert_eq!(limiter.dropped(), 1);
+    }
+
+    #[test]
+    fn test_rate_limiter_change_frequency() {
+        let limiter = RateLimiter::new(RateLimitPolicy::DropExcess, None);
+        let now = Instant::now();
+        assert_eq!(limiter.offer([0; 16], 1, now), Some(1));
+        assert_eq!(limiter.offer([0; 16], 2, now), Some(2));
+        // a maximum frequency set without re-creating the route
+        limiter.set_max_frequency(Some(2.0));
+        assert_eq!(limiter.period(), Some(Duration::from_millis(500)));
+        assert_eq!(limiter.offer([0; 16], 3, now), Some(3));
+        assert_eq!(limiter.offer([0; 16], 4, now), None);
+        // and reset: the kept message is routed on next tick
+        limiter.set_max_frequency(Some(0.0));
+        assert_eq!(limiter.max_frequency(), None);
+        assert_eq!(limiter.tick(now), vec![4]);
+        assert_eq!(limiter.offer([0; 16], 5, now), Some(5));
+    }
+}
@@ -0,0 +1,155 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// Tracks the Zenoh transports of the session to detect the reconnections to the Zenoh infrastructure
+/// (i.e. a new transport opened after all of them were closed, e.g. on a router restart),
+/// and schedules the attempts to restore the routes (up to `max_attempts`, every `period`).
+pub struct ReconnectMonitor {
+    max_attempts: u32,
+    period: Duration,
+    // the ids of the peers with an open transport
+    transports: HashSet<String>,
+    // true if all the transports were closed since the last connection
+    disconnected: bool,
+    // the count of failed restoration attempts since the last reconnection
+    failed_attempts: u32,
+    // the time of the next restoration attempt (if any)
+    next_attempt: Option<Instant>,
+}
+
+impl ReconnectMonitor {
+    pub fn new(max_attempts: u32, period: Duration) -> ReconnectMonitor {
+        ReconnectMonitor {
+            max_attempts,
+            period,
+            transports: HashSet::new(),
+            disconnected: false,
+            failed_attempts: 0,
+            next_attempt: None,
+        }
+    }
+
+    /// Update with a transport opened or closed with a peer. Returns true on reconnection.
+    pub fn on_transport_event(&mut self, peer: &str, opened: bool, now: Instant) -> bool {
+        if opened {
+            if self.transports.insert(peer.to_string()) && self.disconnected {
+                self.disconnected = false;
+                if self.max_attempts > 0 {
+                    self.failed_attempts = 0;
+                    self.next_attempt = Some(now);
+                }
+                return true;
+            }
+        } else if self.transports.remove(peer) && self.transports.is_empty() {
+            self.disconnected = true;
+            // no restoration attempt while disconnected
+            self.next_attempt = None;
+        }
+        false
+    }
+
+    /// The time of the next restoration attempt (if any)
+    #[inline]
+    pub fn next_attempt(&self) -> Option<Instant> {
+        self.next_attempt
+    }
+
+    /// Report the result of a restoration attempt, scheduling the next one if it failed.
+    /// Returns false if it failed for the last allowed attempt.
+    pub fn on_attempt_result(&mut self, success: bool, now: Instant) -> bool {
+        if success {
+            self.next_attempt = None;
+            return true;
+        }
+        self.failed_attempts += 1;
+        if self.failed_attempts >= self.max_attempts {
+            self.next_attempt = None;
+            false
+        } else {
+            self.next_attempt = Some(now + self.period);
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReconnectMonitor;
+    use std::time::{Duration, Instant};
+
+    const PERIOD: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn test_reconnection_detection() {
+        let mut monitor = ReconnectMonitor::new(3, PERIOD);
+        let t0 = Instant::now();
+        // initial connection is not a reconnection
+        assert!(!monitor.on_transport_event("router1", true, t0));
+        assert!(!monitor.on_transport_event("peer2", true, t0));
+        assert_eq!(monitor.next_attempt(), None);
+        // losing only some transports is not a disconnection
+        assert!(!monitor.on_transport_event("peer2", false, t0));
+        assert!(!monitor.on_transport_event("peer2", true, t0));
+        assert_eq!(monitor.next_attempt(), None);
+
+        // router restart (with a new id)
+        assert!(!monitor.on_transport_event("router1", false, t0));
+        assert!(!monitor.on_transport_event("peer2", false, t0));
+        assert!(monitor.on_transport_event("router1bis", true, t0 + PERIOD));
+        assert_eq!(monitor.next_attempt(), Some(t0 + PERIOD));
+        // a duplicated event is ignored
+        assert!(!monitor.on_transport_event("router1bis", true, t0 + PERIOD));
+        assert!(monitor.on_attempt_result(true, t0 + PERIOD));
+        assert_eq!(monitor.next_attempt(), None);
+    }
+
+    #[test]
+    fn test_restoration_attempts() {
+        let mut monitor = ReconnectMonitor::new(3, PERIOD);
+        let t0 = Instant::now();
+        monitor.on_transport_event("router", true, t0);
+        monitor.on_transport_event("router", false, t0);
+        assert!(monitor.on_transport_event("router", true, t0));
+
+        // 3 failed attempts, separated by PERIOD
+        assert!(monitor.on_attempt_result(false, t0));
+        assert_eq!(monitor.next_attempt(), Some(t0 + PERIOD));
+        assert!(monitor.on_attempt_result(false, t0 + PERIOD));
+        assert_eq!(monitor.next_attempt(), Some(t0 + PERIOD * 2));
+        assert!(!monitor.on_attempt_result(false, t0 + PERIOD * 2));
+        assert_eq!(monitor.next_attempt(), None);
+
+        // a new reconnection resets the attempts
+        monitor.on_transport_event("router", false, t0);
+        assert!(monitor.on_transport_event("router", true, t0 + PERIOD * 3));
+        assert_eq!(monitor.next_attempt(), Some(t0 + PERIOD * 3));
+        assert!(monitor.on_attempt_result(false, t0 + PERIOD * 3));
+        // a disconnection cancels the pending attempt
+        monitor.on_transport_event("router", false, t0 + PERIOD * 3);
+        assert_eq!(monitor.next_attempt(), None);
+    }
+
+    #[test]
+    fn test_no_restoration() {
+        let mut monitor = ReconnectMonitor::new(0, PERIOD);
+        let t0 = Instant::now();
+        monitor.on_transport_event("router", true, t0);
+        monitor.on_transport_event("router", false, t0);
+        // the reconnection is detected, but no restoration is attempted
+        assert!(monitor.on_transport_event("router", true, t0));
+        assert_eq!(monitor.next_attempt(), None);
+    }
+}
@@ -0,0 +1,91 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// The remote bridges detected via their liveliness tokens. They are keyed by their unique id
+/// (their Zenoh id, unless configured), and associated to their alias (if any) which is only
+/// informational: several bridges might be configured with the same alias.
+#[derive(Default, Serialize)]
+#[serde(transparent)]
+pub struct RemoteBridges {
+    bridges: HashMap<String, Option<String>>,
+}
+
+impl RemoteBridges {
+    pub fn add_bridge(&mut self, plugin_id: &str) {
+        self.bridges.entry(plugin_id.to_string()).or_default();
+    }
+
+    pub fn remove_bridge(&mut self, plugin_id: &str) {
+        self.bridges.remove(plugin_id);
+    }
+
+    // Note: the alias token might be received before the bridge's one
+    pub fn set_alias(&mut self, plugin_id: &str, alias: &str) {
+        self.bridges
+            .insert(plugin_id.to_string(), Some(alias.to_string()));
+    }
+
+    pub fn unset_alias(&mut self, plugin_id: &str) {
+        if let Some(alias) = self.bridges.get_mut(plugin_id) {
+            *alias = None;
+        }
+    }
+
+    #[inline]
+    pub fn get_alias(&self, plugin_id: &str) -> Option<&str> {
+        self.bridges.get(plugin_id)?.as_deref()
+    }
+
+    /// A name of a bridge for the logs: "<alias> (<plugin_id>)" if it has an alias, "<plugin_id>" otherwise
+    pub fn display_name(&self, plugin_id: &str) -> String {
+        match self.get_alias(plugin_id) {
+            Some(alias) => format!("{alias} ({plugin_id})"),
+            None => plugin_id.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RemoteBridges;
+
+    #[test]
+    fn test_same_alias() {
+        let mut bridges = RemoteBridges::default();
+        bridges.add_bridge("0a0b");
+        bridges.set_alias("0a0b", "robot-07");
+        // alias received before the bridge's token
+        bridges.set_alias("0c0d", "robot-07");
+        bridges.add_bridge("0c0d");
+        bridges.add_bridge("0e0f");
+
+        assert_eq!(bridges.get_alias("0a0b"), Some("robot-07"));
+        assert_eq!(bridges.get_alias("0c0d"), Some("robot-07"));
+        assert_eq!(bridges.get_alias("0e0f"), None);
+        assert_eq!(bridges.display_name("0a0b"), "robot-07 (0a0b)");
+        assert_eq!(bridges.display_name("0e0f"), "0e0f");
+
+        // a bridge leaving doesn't affect the other one with the same alias
+        bridges.unset_alias("0a0b");
+        bridges.remove_bridge("0a0b");
+        assert_eq!(bridges.get_alias("0a0b"), None);
+        assert_eq!(bridges.get_alias("0c0d"), Some("robot-07"));
+        assert_eq!(
+            serde_json::to_value(&bridges).unwrap(),
+            serde_json::json!({"0c0d": "robot-07", "0e0f": null})
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+/// A cache of the ROS 2 names computed from the key expressions announced by remote bridges,
+/// evicting the Least Recently Used entry when full.
+/// As the names depend on the configuration `C` (namespaces, remappings...), the cache is cleared
+/// whenever it's used with another configuration instance (e.g. after a reload).
+pub struct Ros2NamesCache<K, C> {
+    capacity: usize,
+    config: Option<Arc<C>>,
+    // the cached names, with the tick of their last use
+    entries: HashMap<K, (String, u64)>,
+    tick: u64,
+}
+
+impl<K: Eq + Hash + Clone, C> Ros2NamesCache<K, C> {
+    pub fn new(capacity: usize) -> Ros2NamesCache<K, C> {
+        assert!(capacity > 0);
+        Ros2NamesCache {
+            capacity,
+            config: None,
+            entries: HashMap::with_capacity(capacity),
+            tick: 0,
+        }
+    }
+
+    /// The cached name for `key` with `config`, or the one returned by `compute` (which is then cached)
+    pub fn get_or_compute<F>(&mut self, config: &Arc<C>, key: &K, compute: F) -> String
+    where
+        F: FnOnce(&C) -> String,
+    {
+        if !self.config.as_ref().is_some_and(|c| Arc::ptr_eq(c, config)) {
+            self.entries.clear();
+            self.config = Some(config.clone());
+        }
+        self.tick += 1;
+        if let Some((name, last_used)) = self.entries.get_mut(key) {
+            *last_used = self.tick;
+            return name.clone();
+        }
+        let name = compute(config);
+        if self.entries.len() >= self.capacity {
+            let lru = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(k, _)| k.clone());
+            if let Some(lru) = lru {
+                self.entries.remove(&lru);
+            }
+        }
+        self.entries.insert(key.clone(), (name.clone(), self.tick));
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_eviction() {
+        let config = Arc::new("/");
+        let mut cache: Ros2NamesCache<&str, &str> = Ros2NamesCache::new(2);
+        let computed = std::cell::Cell::new(0);
+        let get = |cache: &mut Ros2NamesCache<&str, &str>, key: &'static str| {
+            cache.get_or_compute(&config, &key, |ns| {
+                computed.set(computed.get() + 1);
+                format!("{ns}{key}")
+            })
+        };
+
+        assert_eq!(get(&mut cache, "a"), "/a");
+        assert_eq!(get(&mut cache, "b"), "/b");
+        assert_eq!(computed.get(), 2);
+        // cached
+        assert_eq!(get(&mut cache, "a"), "/a");
+        assert_eq!(computed.get(), 2);
+        // "b" is the least recently used: evicted
+        assert_eq!(get(&mut cache, "c"), "/c");
+        assert_eq!(cache.entries.len(), 2);
+        assert!(cache.entries.contains_key("a"));
+        assert!(!cache.entries.contains_key("b"));
+        assert_eq!(get(&mut cache, "b"), "/b");
+        assert_eq!(computed.get(), 4);
+    }
+
+    #[test]
+    fn test_config_change() {
+        let mut cache: Ros2NamesCache<&str, &str> = Ros2NamesCache::new(8);
+        let config1 = Arc::new("/robot1");
+        assert_eq!(
+            cache.get_or_compute(&config1, &"a", |ns| format!("{ns}/a")),
+            "/robot1/a"
+        );
+        // another instance, even if equal, invalidates the cache
+        let config2 = Arc::new("/robot2");
+        assert_eq!(
+            cache.get_or_compute(&config2, &"a", |ns| format!("{ns}/a")),
+            "/robot2/a"
+        );
+        assert_eq!(cache.entries.len(), 1);
+        let config3 = Arc::new("/robot2");
+        assert_eq!(
+            cache.get_or_compute(&config3, &"a", |_| "recomputed".into()),
+            "recomputed"
+        );
+    }
+}
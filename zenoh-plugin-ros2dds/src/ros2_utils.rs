@@ -21,14 +21,26 @@ use cyclors::{
     },
 };
 use std::{
+    borrow::Cow,
     env::VarError,
     sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
 };
+use zenoh::buffers::{ZBuf, ZSlice};
 use zenoh::prelude::{keyexpr, KeyExpr, OwnedKeyExpr};
 use zenoh::sample::Attachment;
 use zenoh_core::{bail, zresult::ZError};
 
-use crate::{config::Config, dds_utils::get_guid, ke_for_sure};
+use crate::{
+    cdr_check::check_cdr_header,
+    config::{LongNamePolicy, NamespaceRule, Remapping},
+    dds_types::DDSInstanceState,
+    dds_utils::{get_guid, is_cdr_little_endian},
+    dedup::Origin,
+    ke_for_sure,
+    sequence_gaps::SequenceNumber,
+    KE_ANY_N_SEGMENT, KE_PREFIX_LONG_CALL_REPLY, KE_PREFIX_ROUTE_TO, KE_ROUTE_TO_SEPARATOR,
+};
 
 pub const ROS2_ACTION_CANCEL_GOAL_SRV_TYPE: &str = "action_msgs/srv/CancelGoal";
 pub const ROS2_ACTION_STATUS_MSG_TYPE: &str = "action_msgs/msg/GoalStatusArray";
@@ -44,6 +56,7 @@ lazy_static::lazy_static!(
     pub static ref KE_SUFFIX_ACTION_GET_RESULT: &'static keyexpr = ke_for_sure!("_action/get_result");
     pub static ref KE_SUFFIX_ACTION_FEEDBACK: &'static keyexpr = ke_for_sure!("_action/feedback");
     pub static ref KE_SUFFIX_ACTION_STATUS: &'static keyexpr = ke_for_sure!("_action/status");
+    pub static ref KE_SUFFIX_SERVICE_EVENT: &'static keyexpr = ke_for_sure!("_service_event");
 
     pub static ref QOS_DEFAULT_SERVICE: Qos = ros2_service_default_qos();
     pub static ref QOS_DEFAULT_ACTION_FEEDBACK: Qos = ros2_action_feedback_default_qos();
@@ -82,32 +95,439 @@ pub fn ros_distro_is_less_than(distro: &str) -> bool {
     ROS_DISTRO.chars().next() < distro.chars().next()
 }
 
-/// Convert ROS2 interface name to a Zenoh key expression,
-/// prefixing with "namespace" if configured
-pub fn ros2_name_to_key_expr(ros2_name: &str, config: &Config) -> OwnedKeyExpr {
+/// Convert ROS2 interface name to a Zenoh key expression, after its remapping (if any matches),
+/// prefixing with the namespace of the 1st matching rule (rules being sorted most-specific-first),
+/// or with "namespace" if no rule matches (and if not "/").
+/// The namespace to use depends on the direction of the route (see `Config::get_namespace_to_zenoh()`
+/// and `Config::get_namespace_from_zenoh()`).
+pub fn ros2_name_to_key_expr(
+    ros2_name: &str,
+    namespace: &str,
+    rules: &[NamespaceRule],
+    remappings: &[Remapping],
+) -> OwnedKeyExpr {
+    let remapped = remap_ros2_name(ros2_name, remappings);
+    let ros2_name = remapped.as_deref().unwrap_or(ros2_name);
+    let namespace = rules
+        .iter()
+        .find(|r| r.is_match(ros2_name))
+        .map_or(namespace, NamespaceRule::namespace);
     // ros2_name as discovered by the bridge starts with a '/'
-    // config.namespace starts with a '/'
+    // namespace starts with a '/'
     // But a Zenoh key_expr shall not start with a '/'
-    if config.namespace == "/" {
-        ke_for_sure!(&ros2_name[1..]).to_owned()
+    let escaped = escape_ros2_name(ros2_name.strip_prefix('/').unwrap_or(ros2_name));
+    let prefix = namespace_to_ke_prefix(namespace);
+    if prefix.is_empty() {
+        ke_for_sure!(&escaped).to_owned()
     } else {
-        ke_for_sure!(&config.namespace[1..]) / ke_for_sure!(&ros2_name[1..])
+        ke_for_sure!(prefix) / ke_for_sure!(&escaped)
     }
 }
 
+// The key expression prefix corresponding to a namespace, i.e. without its leading and trailing '/'
+// (e.g. "fleet/r1" for "/fleet/r1" or "/fleet/r1/", and "" for "/")
+#[inline]
+fn namespace_to_ke_prefix(namespace: &str) -> &str {
+    namespace.trim_matches('/')
+}
+
 /// Convert a Zenoh key expression to a ROS2 full interface name,
-/// removing "namespace" prefix if configured and present in the key expr
-pub fn key_expr_to_ros2_name(key_expr: &keyexpr, config: &Config) -> String {
-    // Zenoh key_expr never starts with a '/'
-    // But the full ROS2 name that is returned shall (full == with a namespace, even if just '/')
-    if config.namespace == "/" {
-        format!("/{key_expr}")
+/// removing the namespace prefix of the rule (or "namespace" if no rule) that produced it,
+/// and reverting the remapping (if any) that produced the resulting name.
+/// The namespaces of the rules are tried in order, then "namespace". A namespace is selected only
+/// if [`ros2_name_to_key_expr()`] maps the resulting name back to the same key expression.
+/// If none is selected, the key expression is returned as such, prefixed with a '/'.
+/// The namespace to use depends on the direction of the route (see `Config::get_namespace_to_zenoh()`
+/// and `Config::get_namespace_from_zenoh()`).
+pub fn key_expr_to_ros2_name(
+    key_expr: &keyexpr,
+    namespace: &str,
+    rules: &[NamespaceRule],
+    remappings: &[Remapping],
+) -> String {
+    for ns in rules
+        .iter()
+        .map(NamespaceRule::namespace)
+        .chain(std::iter::once(namespace))
+    {
+        // Zenoh key_expr never starts with a '/'
+        // But the full ROS2 name that is returned shall (full == with a namespace, even if just '/')
+        let prefix = namespace_to_ke_prefix(ns);
+        let ros2_name = if prefix.is_empty() {
+            format!("/{}", unescape_ros2_name(key_expr))
+        } else {
+            match key_expr.as_str().strip_prefix(prefix) {
+                // the namespace prefix must be followed by a '/' (e.g. "robot1" is not a prefix of "robot10/x")
+                Some(s) if s.starts_with('/') => unescape_ros2_name(s),
+                _ => continue,
+            }
+        };
+        let ros2_name = unremap_ros2_name(ros2_name, remappings);
+        if ros2_name_to_key_expr(&ros2_name, namespace, rules, remappings).as_str()
+            == key_expr.as_str()
+        {
+            return ros2_name;
+        }
+    }
+    unremap_ros2_name(format!("/{}", unescape_ros2_name(key_expr)), remappings)
+}
+
+/// Prefix a key expression with a scope (if any)
+pub fn scope_key_expr(scope: Option<&keyexpr>, key_expr: OwnedKeyExpr) -> OwnedKeyExpr {
+    match scope {
+        Some(scope) => scope / &key_expr,
+        None => key_expr,
+    }
+}
+
+/// Remove the scope (if any) prefixing a key expression, that can have several chunks.
+/// None is returned if the key expression is not in this scope.
+pub fn unscope_key_expr<'a>(scope: Option<&keyexpr>, key_expr: &'a keyexpr) -> Option<&'a keyexpr> {
+    match scope {
+        Some(scope) => key_expr
+            .as_str()
+            .strip_prefix(scope.as_str())?
+            .strip_prefix('/')
+            .map(|s| ke_for_sure!(s)),
+        None => Some(key_expr),
+    }
+}
+
+/// The key expression of a topic in "typed_key_exprs" mode: its ROS2 type is appended as a last chunk,
+/// with the '/' replaced by '§' (e.g. "cmd_vel/geometry_msgs§msg§Twist")
+pub fn typed_key_expr(key_expr: &keyexpr, ros2_type: &str) -> Result<OwnedKeyExpr, String> {
+    let mangled_type = ros2_type.replace('/', "§");
+    match keyexpr::new(mangled_type.as_str()) {
+        Ok(typ) if !typ.is_wild() => Ok(key_expr / typ),
+        _ => Err(format!(
+            "ROS2 type '{ros2_type}' can't be used in a key expression"
+        )),
+    }
+}
+
+/// A bridge id or alias listed in `route_to`, as a key expression chunk
+pub fn route_to_target(target: &str) -> Result<&keyexpr, String> {
+    match keyexpr::new(target) {
+        Ok(ke) if !ke.as_str().contains('/') && !ke.is_wild() && !target.starts_with('@') => Ok(ke),
+        _ => Err(format!(
+            "invalid bridge '{target}' in route_to: must be a single chunk without '*', '$', '?', '#' or leading '@'"
+        )),
+    }
+}
+
+/// The key expression of the publications of a topic configured in `route_to`, scoped to its target bridges
+/// (ids or aliases): "@ros2_to/<target_1>/.../<target_n>/@/<key_expr>"
+pub fn route_to_key_expr(targets: &[String], key_expr: &keyexpr) -> Result<OwnedKeyExpr, String> {
+    let mut scoped = KE_PREFIX_ROUTE_TO.to_owned();
+    for target in targets {
+        scoped = scoped / route_to_target(target)?;
+    }
+    Ok(scoped / *KE_ROUTE_TO_SEPARATOR / key_expr)
+}
+
+/// The key expression a Subscriber route subscribes to for the publications of a topic scoped to its bridge
+/// (`target` being its id or alias): "@ros2_to/**/<target>/**/@/<key_expr>". It only intersects the
+/// key expressions of [`route_to_key_expr()`] listing `target`, the '@' chunk not being a valid target.
+pub fn route_to_subscription_key_expr(target: &keyexpr, key_expr: &keyexpr) -> OwnedKeyExpr {
+    *KE_PREFIX_ROUTE_TO
+        / *KE_ANY_N_SEGMENT
+        / target
+        / *KE_ANY_N_SEGMENT
+        / *KE_ROUTE_TO_SEPARATOR
+        / key_expr
+}
+
+/// The key expression of an instance of a keyed topic in "instance_key_exprs" mode: the instance's key hash
+/// is appended as a last chunk, in hexadecimal (e.g. "fleet_status/9a3c...")
+pub fn instance_key_expr(key_expr: &keyexpr, keyhash: &[u8; 16]) -> OwnedKeyExpr {
+    key_expr / ke_for_sure!(&hex::encode(keyhash))
+}
+
+/// The instance chunk (i.e. the hexadecimal key hash) of a key expression in "instance_key_exprs" mode,
+/// or None if its last chunk is not a key hash
+pub fn instance_chunk(key_expr: &keyexpr) -> Option<&str> {
+    let chunk = key_expr
+        .as_str()
+        .rsplit_once('/')
+        .map_or(key_expr.as_str(), |(_, chunk)| chunk);
+    (chunk.len() == 32
+        && chunk
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)))
+    .then_some(chunk)
+}
+
+// The prefix of the names given to the raw DDS topics (i.e. not following the ROS 2 naming conventions)
+// configured in "raw_dds_topics". The '@' being invalid in ROS 2 names, they can't conflict with a ROS 2 name.
+const RAW_DDS_TOPIC_NAME_PREFIX: &str = "@raw/";
+
+// The chunk prefixing the key expressions of the raw DDS topics
+const RAW_DDS_TOPIC_KE_PREFIX: &str = "raw";
+
+/// Check if a DDS topic follows the ROS 2 naming conventions (i.e. it's not a raw DDS topic)
+pub fn is_ros2_dds_topic(dds_topic: &str) -> bool {
+    dds_topic.starts_with("rt/")
+        || dds_topic.starts_with("rq/")
+        || dds_topic.starts_with("rr/")
+        || dds_topic == "ros_discovery_info"
+}
+
+/// The name used to route a raw DDS topic (e.g. "@raw/SensorRaw")
+pub fn raw_dds_topic_to_ros2_name(dds_topic: &str) -> String {
+    format!("{RAW_DDS_TOPIC_NAME_PREFIX}{dds_topic}")
+}
+
+/// The raw DDS topic if `ros2_name` is the name of one (see raw_dds_topic_to_ros2_name())
+pub fn ros2_name_to_raw_dds_topic(ros2_name: &str) -> Option<&str> {
+    ros2_name.strip_prefix(RAW_DDS_TOPIC_NAME_PREFIX)
+}
+
+/// The key expression of a raw DDS topic: "raw/<topic>" (escaped as a ROS 2 name).
+/// If some `separators` are configured (see "segment_separators"), they're converted into chunk separators
+/// (see split_segments()).
+pub fn raw_dds_topic_to_key_expr(dds_topic: &str, separators: &[char]) -> OwnedKeyExpr {
+    let escaped = if separators.is_empty() {
+        escape_ros2_name(dds_topic)
     } else {
-        match key_expr.as_str().strip_prefix(&config.namespace[1..]) {
-            Some(s) => s.to_string(),
-            None => format!("/{key_expr}"),
+        split_segments(dds_topic, separators)
+    };
+    ke_for_sure!(RAW_DDS_TOPIC_KE_PREFIX) / ke_for_sure!(&escaped)
+}
+
+/// The raw DDS topic of a key expression with the "raw/" prefix (None otherwise).
+/// If a `separator` is specified, the chunks are joined with it (inverse of split_segments()).
+pub fn key_expr_to_raw_dds_topic(key_expr: &keyexpr, separator: Option<char>) -> Option<String> {
+    let escaped = key_expr
+        .as_str()
+        .strip_prefix(RAW_DDS_TOPIC_KE_PREFIX)?
+        .strip_prefix('/')?;
+    match separator {
+        Some(sep) => Some(
+            escaped
+                .split('/')
+                .map(unescape_ros2_name)
+                .collect::<Vec<_>>()
+                .join(&sep.to_string()),
+        ),
+        None => Some(unescape_ros2_name(escaped)),
+    }
+}
+
+// Escape a name, converting each of the `separators` characters into a chunk separator, except if it
+// would make an empty chunk (i.e. at the start, at the end or followed by another separator).
+// To keep it reversible when the name also contains some '/', those are all escaped (i.e. not chunk separators).
+// Thus, with a single separator the conversion is reverted by joining the chunks with it.
+fn split_segments(name: &str, separators: &[char]) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut segment_start = 0;
+    for (i, c) in name.char_indices() {
+        let next = &name[i + c.len_utf8()..];
+        if separators.contains(&c)
+            && i > segment_start
+            && next
+                .chars()
+                .next()
+                .is_some_and(|n| !separators.contains(&n))
+        {
+            result.push_str(&escape_ros2_name(&name[segment_start..i]).replace('/', "%2F"));
+            result.push('/');
+            segment_start = i + c.len_utf8();
         }
     }
+    result.push_str(&escape_ros2_name(&name[segment_start..]).replace('/', "%2F"));
+    result
+}
+
+/// The DDS topic name and type for a ROS2 topic: for a raw DDS topic they're used verbatim,
+/// otherwise they're converted as per ROS 2 conventions (see dds_topic_name()).
+pub fn message_dds_topic_and_type(
+    ros2_name: &str,
+    ros2_type: &str,
+    policy: LongNamePolicy,
+) -> Result<(String, String), TopicNameTooLong> {
+    match ros2_name_to_raw_dds_topic(ros2_name) {
+        Some(dds_topic) => Ok((dds_topic.to_string(), ros2_type.to_string())),
+        None => Ok((
+            dds_topic_name("rt", ros2_name, "", policy)?,
+            ros2_message_type_to_dds_type(ros2_type),
+        )),
+    }
+}
+
+// The name remapped by the 1st matching remapping, if any. The name of an Action's sub-interface
+// (e.g. "/navigate_to_pose/_action/feedback") that is not remapped as such gets the remapping of the Action's name.
+fn remap_ros2_name(ros2_name: &str, remappings: &[Remapping]) -> Option<String> {
+    remappings
+        .iter()
+        .find_map(|r| r.apply(ros2_name))
+        .or_else(|| {
+            let (action_name, suffix) = split_action_suffix(ros2_name)?;
+            remappings
+                .iter()
+                .find_map(|r| r.apply(action_name))
+                .map(|remapped| format!("{remapped}/{suffix}"))
+        })
+}
+
+// Revert the remapping which destination matches `ros2_name` (the remappings destinations can't overlap),
+// or which destination matches the Action's name for the name of an Action's sub-interface
+fn unremap_ros2_name(ros2_name: String, remappings: &[Remapping]) -> String {
+    remappings
+        .iter()
+        .find_map(|r| r.apply_inverse(&ros2_name))
+        .or_else(|| {
+            let (action_name, suffix) = split_action_suffix(&ros2_name)?;
+            remappings
+                .iter()
+                .find_map(|r| r.apply_inverse(action_name))
+                .map(|original| format!("{original}/{suffix}"))
+        })
+        .unwrap_or(ros2_name)
+}
+
+// Split the name of an Action's sub-interface into the Action's name and the sub-interface suffix
+// (e.g. ("/navigate_to_pose", "_action/feedback") for "/navigate_to_pose/_action/feedback")
+pub(crate) fn split_action_suffix(ros2_name: &str) -> Option<(&str, &str)> {
+    [
+        *KE_SUFFIX_ACTION_SEND_GOAL,
+        *KE_SUFFIX_ACTION_CANCEL_GOAL,
+        *KE_SUFFIX_ACTION_GET_RESULT,
+        *KE_SUFFIX_ACTION_FEEDBACK,
+        *KE_SUFFIX_ACTION_STATUS,
+    ]
+    .into_iter()
+    .find_map(|suffix| {
+        let action_name = ros2_name.strip_suffix(suffix.as_str())?.strip_suffix('/')?;
+        (!action_name.is_empty()).then_some((action_name, suffix.as_str()))
+    })
+}
+
+// The characters of a ROS2 name that are escaped in a Zenoh key expression, with their escape sequence
+const ROS2_NAME_ESCAPES: [(char, &str); 8] = [
+    ('*', "%2A"),
+    ('$', "%24"),
+    ('?', "%3F"),
+    ('#', "%23"),
+    ('@', "%40"),
+    ('§', "%A7"),
+    ('/', "%2F"),
+    ('%', "%25"),
+];
+
+// Escape the characters of a ROS2 name (without its leading '/') that are not valid in a Zenoh key expression:
+//   - '*', '$', '?' and '#'
+//   - '@' (starting a verbatim chunk in a key expression)
+//   - '§' (replacing the '/' of the key expressions in the liveliness tokens)
+//   - a '/' that would make an empty chunk (i.e. at the start, at the end or followed by another '/')
+//   - a '%' starting an escape sequence (so it's not unescaped as another character)
+// A name without such characters is unchanged (preserving the interoperability with older bridges).
+fn escape_ros2_name(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    for (i, c) in name.char_indices() {
+        let escape = match c {
+            '*' | '$' | '?' | '#' | '@' | '§' => true,
+            '/' => i == 0 || name[i + 1..].is_empty() || name[i + 1..].starts_with('/'),
+            '%' => escape_sequence_at(&name[i..]).is_some(),
+            _ => false,
+        };
+        match ROS2_NAME_ESCAPES.iter().find(|(e, _)| escape && *e == c) {
+            Some((_, seq)) => result.push_str(seq),
+            None => result.push(c),
+        }
+    }
+    result
+}
+
+// The inverse of escape_ros2_name()
+fn unescape_ros2_name(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('%') {
+        result.push_str(&rest[..i]);
+        match escape_sequence_at(&rest[i..]) {
+            Some(c) => {
+                result.push(c);
+                rest = &rest[i + 3..];
+            }
+            None => {
+                result.push('%');
+                rest = &rest[i + 1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+// The escaped character if `s` starts with an escape sequence
+fn escape_sequence_at(s: &str) -> Option<char> {
+    ROS2_NAME_ESCAPES
+        .iter()
+        .find(|(_, seq)| s.starts_with(seq))
+        .map(|(c, _)| *c)
+}
+
+// Maximum length of a DDS topic name (as supported by CycloneDDS and most DDS implementations)
+pub const DDS_TOPIC_NAME_MAX_LEN: usize = 255;
+
+/// Error for a DDS topic name exceeding DDS_TOPIC_NAME_MAX_LEN
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicNameTooLong {
+    pub dds_topic: String,
+}
+
+impl std::fmt::Display for TopicNameTooLong {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DDS topic name '{}' is {} characters long, exceeding the maximum of {} (see 'long_name_policy' configuration)",
+            self.dds_topic,
+            self.dds_topic.len(),
+            DDS_TOPIC_NAME_MAX_LEN
+        )
+    }
+}
+
+impl From<TopicNameTooLong> for String {
+    fn from(e: TopicNameTooLong) -> Self {
+        e.to_string()
+    }
+}
+
+/// The DDS topic name for a ROS2 interface: `prefix` ("rt", "rq" or "rr") + `ros2_name` + `suffix` ("", "Request" or "Reply").
+/// If it exceeds DDS_TOPIC_NAME_MAX_LEN, depending on `policy`:
+///  - `Reject`: a TopicNameTooLong error is returned
+///  - `Hash`: the name is truncated and completed with "_" + a hash of the full topic name, followed by `suffix`.
+///    The hash (FNV-1a 64 bits) is deterministic: the same name always gives the same DDS topic name.
+pub fn dds_topic_name(
+    prefix: &str,
+    ros2_name: &str,
+    suffix: &str,
+    policy: LongNamePolicy,
+) -> Result<String, TopicNameTooLong> {
+    let dds_topic = format!("{prefix}{ros2_name}{suffix}");
+    if dds_topic.len() <= DDS_TOPIC_NAME_MAX_LEN {
+        return Ok(dds_topic);
+    }
+    match policy {
+        LongNamePolicy::Reject => Err(TopicNameTooLong { dds_topic }),
+        LongNamePolicy::Hash => {
+            let hash = format!("_{:016x}", fnv1a_64(dds_topic.as_bytes()));
+            let mut keep = DDS_TOPIC_NAME_MAX_LEN - hash.len() - suffix.len();
+            let head = &dds_topic[..dds_topic.len() - suffix.len()];
+            while !head.is_char_boundary(keep) {
+                keep -= 1;
+            }
+            Ok(format!("{}{hash}{suffix}", &head[..keep]))
+        }
+    }
+}
+
+// FNV-1a 64 bits hash (not using std's DefaultHasher which is not guaranteed to be stable across Rust releases)
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |h, b| {
+        (h ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
 }
 
 /// Convert DDS Topic type to ROS2 Message type
@@ -166,12 +586,171 @@ pub fn dds_type_to_ros2_action_type(dds_topic: &str) -> String {
 }
 
 const ATTACHMENT_KEY_REQUEST_HEADER: [u8; 3] = [0x72, 0x71, 0x68]; // "rqh" in ASCII
+const ATTACHMENT_KEY_INSTANCE_STATE: [u8; 3] = [0x69, 0x73, 0x74]; // "ist" in ASCII
+const ATTACHMENT_KEY_INSTANCE_KEYHASH: [u8; 3] = [0x69, 0x6b, 0x68]; // "ikh" in ASCII
+const ATTACHMENT_KEY_ORIGIN: [u8; 3] = [0x6f, 0x72, 0x67]; // "org" in ASCII
+const ATTACHMENT_KEY_SEQUENCE_NUMBER: [u8; 3] = [0x73, 0x65, 0x71]; // "seq" in ASCII
+const ATTACHMENT_KEY_IDEMPOTENCY_KEY: [u8; 3] = [0x69, 0x64, 0x6b]; // "idk" in ASCII
+const ATTACHMENT_KEY_LONG_CALL: [u8; 3] = [0x6c, 0x63, 0x6c]; // "lcl" in ASCII
+const ATTACHMENT_KEY_CALLER: [u8; 3] = [0x63, 0x6c, 0x72]; // "clr" in ASCII
+const ATTACHMENT_KEY_TIMEOUT: [u8; 3] = [0x74, 0x6d, 0x6f]; // "tmo" in ASCII
+
+/// The Attachment of a Zenoh delete routing the dispose or the unregister of an instance
+/// in "instance_key_exprs" mode
+pub fn instance_state_as_attachment(state: DDSInstanceState) -> Attachment {
+    let mut attach = Attachment::new();
+    attach.insert(&ATTACHMENT_KEY_INSTANCE_STATE, &[state as u8]);
+    attach
+}
+
+/// The Attachment of a Zenoh put routing a message of a keyed topic (not in "instance_key_exprs" mode),
+/// identifying its instance by its key hash
+pub fn instance_keyhash_as_attachment(keyhash: &[u8; 16]) -> Attachment {
+    let mut attach = Attachment::new();
+    attach.insert(&ATTACHMENT_KEY_INSTANCE_KEYHASH, keyhash);
+    attach
+}
+
+/// The Attachment of a Zenoh delete routing the dispose or the unregister of an instance
+/// of a keyed topic (not in "instance_key_exprs" mode)
+pub fn instance_state_and_keyhash_as_attachment(
+    state: DDSInstanceState,
+    keyhash: &[u8; 16],
+) -> Attachment {
+    let mut attach = instance_state_as_attachment(state);
+    attach.insert(&ATTACHMENT_KEY_INSTANCE_KEYHASH, keyhash);
+    attach
+}
+
+/// The instance key hash carried by the Attachment of a Zenoh put or delete, if any
+pub fn instance_keyhash_from_attachment(attachment: &Attachment) -> Option<[u8; 16]> {
+    match attachment.get(&ATTACHMENT_KEY_INSTANCE_KEYHASH) {
+        Some(buf) if buf.len() == 16 => Some(std::array::from_fn(|i| buf[i])),
+        _ => None,
+    }
+}
+
+/// Add to the Attachment of a Zenoh put the origin of the routed message (with `deduplication` or `source_info`)
+pub fn add_origin_to_attachment(attachment: &mut Attachment, origin: Origin) {
+    attachment.insert(&ATTACHMENT_KEY_ORIGIN, &origin.to_bytes());
+}
+
+/// The origin carried by the Attachment of a Zenoh put, if any
+pub fn origin_from_attachment(attachment: &Attachment) -> Option<Origin> {
+    Origin::from_bytes(&attachment.get(&ATTACHMENT_KEY_ORIGIN)?)
+}
+
+/// Add to the Attachment of a Zenoh put the sequence number of the routed message (with `sequence_tracking`)
+pub fn add_sequence_number_to_attachment(attachment: &mut Attachment, sn: SequenceNumber) {
+    attachment.insert(&ATTACHMENT_KEY_SEQUENCE_NUMBER, &sn.to_bytes());
+}
+
+/// The sequence number carried by the Attachment of a Zenoh put, if any
+pub fn sequence_number_from_attachment(attachment: &Attachment) -> Option<SequenceNumber> {
+    SequenceNumber::from_bytes(&attachment.get(&ATTACHMENT_KEY_SEQUENCE_NUMBER)?)
+}
+
+/// Add to the Attachment of a Service query the key identifying a request and its retries (with `service_retries`)
+pub fn add_idempotency_key_to_attachment(attachment: &mut Attachment, key: &[u8]) {
+    attachment.insert(&ATTACHMENT_KEY_IDEMPOTENCY_KEY, key);
+}
+
+/// The idempotency key carried by the Attachment of a Service query, if any
+pub fn idempotency_key_from_attachment(attachment: &Attachment) -> Option<[u8; 16]> {
+    match attachment.get(&ATTACHMENT_KEY_IDEMPOTENCY_KEY) {
+        Some(buf) if buf.len() == 16 => Some(std::array::from_fn(|i| buf[i])),
+        _ => None,
+    }
+}
+
+/// Add to the Attachment of a Service query the request for a long call (see `long_calls`): the query is
+/// replied at once with an empty payload, and the Response is later published on `long_call_reply_key_expr()`
+pub fn add_long_call_to_attachment(attachment: &mut Attachment, max_duration: Duration) {
+    attachment.insert(
+        &ATTACHMENT_KEY_LONG_CALL,
+        &(max_duration.as_millis() as u64).to_le_bytes(),
+    );
+}
+
+/// The maximum duration of the long call requested by the Attachment of a Service query, if any
+pub fn long_call_from_attachment(attachment: &Attachment) -> Option<Duration> {
+    match attachment.get(&ATTACHMENT_KEY_LONG_CALL) {
+        Some(buf) if buf.len() == 8 => Some(Duration::from_millis(u64::from_le_bytes(
+            buf[..]
+                .try_into()
+                .expect("Shouldn't happen: buf is 8 bytes"),
+        ))),
+        _ => None,
+    }
+}
+
+/// Add to the Attachment of a Service query the timeout after which its caller gives up on it,
+/// for the remote Route Service Server not to wait longer for the Service Server's reply
+pub fn add_timeout_to_attachment(attachment: &mut Attachment, timeout: Duration) {
+    attachment.insert(
+        &ATTACHMENT_KEY_TIMEOUT,
+        &(timeout.as_millis() as u64).to_le_bytes(),
+    );
+}
+
+/// The timeout of the caller of a Service query carried by its Attachment, if any
+pub fn timeout_from_attachment(attachment: &Attachment) -> Option<Duration> {
+    match attachment.get(&ATTACHMENT_KEY_TIMEOUT) {
+        Some(buf) if buf.len() == 8 => Some(Duration::from_millis(u64::from_le_bytes(
+            buf[..]
+                .try_into()
+                .expect("Shouldn't happen: buf is 8 bytes"),
+        ))),
+        _ => None,
+    }
+}
+
+/// Add to the Attachment of a Service query the identity (id or alias) of the bridge routing it,
+/// identifying the caller with the client GUID of the request header
+pub fn add_caller_to_attachment(attachment: &mut Attachment, bridge: &str) {
+    attachment.insert(&ATTACHMENT_KEY_CALLER, bridge.as_bytes());
+}
+
+/// The caller of a Service query, as "<bridge id or alias>:<client GUID>" (in hexadecimal), if its
+/// Attachment carries the calling bridge's identity and the request header
+pub fn caller_from_attachment(attachment: &Attachment) -> Option<String> {
+    let bridge = attachment.get(&ATTACHMENT_KEY_CALLER)?;
+    let (client_id, _) = CddsRequestHeader::try_from(attachment)
+        .ok()?
+        .client_id_and_seq_num();
+    Some(format!(
+        "{}:{client_id:016x}",
+        String::from_utf8_lossy(&bridge)
+    ))
+}
+
+/// The key expression on which the Response of a long call is published:
+/// "@ros2_reply/<service key expr>/<client GUID><sequence number>" (in hexadecimal, whatever the endianness)
+pub fn long_call_reply_key_expr(
+    zenoh_key_expr: &keyexpr,
+    request_id: &CddsRequestHeader,
+) -> OwnedKeyExpr {
+    let (client_id, seq_num) = request_id.client_id_and_seq_num();
+    let id = OwnedKeyExpr::try_from(format!("{client_id:016x}{seq_num:016x}"))
+        .expect("Shouldn't happen: an hexadecimal string is a valid key expression");
+    &(*KE_PREFIX_LONG_CALL_REPLY / zenoh_key_expr) / &id
+}
+
+/// The instance state carried by the Attachment of a Zenoh delete, if any
+pub fn instance_state_from_attachment(attachment: &Attachment) -> Option<DDSInstanceState> {
+    match attachment.get(&ATTACHMENT_KEY_INSTANCE_STATE) {
+        Some(buf) if buf.len() == 1 => DDSInstanceState::try_from(buf[0]).ok(),
+        _ => None,
+    }
+}
 
 /// In rmw_cyclonedds_cpp a cdds_request_header sent within each request and reply payload.
 /// See https://github.com/ros2/rmw_cyclonedds/blob/2263814fab142ac19dd3395971fb1f358d22a653/rmw_cyclonedds_cpp/src/serdata.hpp#L73
 /// Note that it's different from the rmw_request_id_t defined in RMW interfaces in
 /// https://github.com/ros2/rmw/blob/9b3d9d0e3021b7a6e75d8886e3e061a53c36c789/rmw/include/rmw/types.h#L360
-#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+/// Two headers are equal if they have the same client GUID and sequence number, whatever their endianness
+/// (a Service Server may reply with another endianness than its client's request).
+#[derive(Clone, Copy)]
 pub struct CddsRequestHeader {
     // The header contains a u64 GUID (Client's) and a i64 sequence number.
     // Keep those as a single buffer, as it's transfered as such between DDS and Zenoh.
@@ -207,6 +786,30 @@ impl CddsRequestHeader {
         self.is_little_endian
     }
 
+    /// The client GUID and the sequence number, decoded according to the header's endianness
+    pub fn client_id_and_seq_num(&self) -> (u64, u64) {
+        let client_id: [u8; 8] = self.header[..8]
+            .try_into()
+            .expect("Shouldn't happen: self.header is 16 bytes");
+        let seq_num: [u8; 8] = self.header[8..]
+            .try_into()
+            .expect("Shouldn't happen: self.header is 16 bytes");
+        if self.is_little_endian {
+            (u64::from_le_bytes(client_id), u64::from_le_bytes(seq_num))
+        } else {
+            (u64::from_be_bytes(client_id), u64::from_be_bytes(seq_num))
+        }
+    }
+
+    /// The same header, encoded with the given endianness (e.g. the one of the payload it's sent with)
+    pub fn with_endianness(&self, is_little_endian: bool) -> CddsRequestHeader {
+        if is_little_endian == self.is_little_endian {
+            return *self;
+        }
+        let (client_id, seq_num) = self.client_id_and_seq_num();
+        CddsRequestHeader::create(client_id, seq_num, is_little_endian)
+    }
+
     pub fn as_slice(&self) -> &[u8] {
         &self.header
     }
@@ -246,6 +849,20 @@ impl TryFrom<&Attachment> for CddsRequestHeader {
     }
 }
 
+impl PartialEq for CddsRequestHeader {
+    fn eq(&self, other: &Self) -> bool {
+        self.client_id_and_seq_num() == other.client_id_and_seq_num()
+    }
+}
+
+impl Eq for CddsRequestHeader {}
+
+impl std::hash::Hash for CddsRequestHeader {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.client_id_and_seq_num().hash(state);
+    }
+}
+
 impl std::fmt::Display for CddsRequestHeader {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // a request header is made of 8 bytes client guid + 8 bytes sequence number
@@ -254,21 +871,71 @@ impl std::fmt::Display for CddsRequestHeader {
         for i in &self.header[0..8] {
             write!(f, "{i:02x}")?;
         }
-        let seq_num = if self.is_little_endian {
-            u64::from_le_bytes(
-                self.header[8..]
-                    .try_into()
-                    .expect("Shouldn't happen: self.header is 16 bytes"),
-            )
-        } else {
-            u64::from_be_bytes(
-                self.header[8..]
-                    .try_into()
-                    .expect("Shouldn't happen: self.header is 16 bytes"),
-            )
-        };
-        write!(f, ",{seq_num})",)
+        write!(f, ",{})", self.client_id_and_seq_num().1)
+    }
+}
+
+// The lengths of the CDR encapsulation header and of a cdds_request_header
+const CDR_ENCAPSULATION_LEN: usize = 4;
+const CDDS_REQUEST_HEADER_LEN: usize = 16;
+
+/// Split the payload of a Request or a Reply published by a rmw_cyclonedds Client or Server into its
+/// request header and the Request or Response type as routed over Zenoh (encapsulation header + CDR body).
+/// The request header follows the encapsulation header and is encoded with the endianness of its
+/// representation identifier. Being 16 bytes (a multiple of the maximal alignment: 8 in XCDR1, 4 in XCDR2),
+/// its removal keeps the alignment of each member of the body relative to the CDR origin, as well as the
+/// count of padding bytes of the encapsulation options. The body is not copied.
+pub fn split_request_header(payload: ZSlice) -> Result<(CddsRequestHeader, ZBuf), String> {
+    let buf = payload.as_slice();
+    check_cdr_header(buf)?;
+    let padding = (buf[3] & 0x03) as usize;
+    if buf.len() < CDR_ENCAPSULATION_LEN + CDDS_REQUEST_HEADER_LEN + padding {
+        return Err(format!(
+            "{} bytes, shorter than its CDR encapsulation header, a request header and {padding} padding bytes",
+            buf.len()
+        ));
     }
+    let is_little_endian =
+        is_cdr_little_endian(buf).expect("Shouldn't happen: buf is longer than 4 bytes");
+    let request_id = CddsRequestHeader::from_slice(
+        buf[CDR_ENCAPSULATION_LEN..CDR_ENCAPSULATION_LEN + CDDS_REQUEST_HEADER_LEN]
+            .try_into()
+            .expect("Shouldn't happen: slice is 16 bytes"),
+        is_little_endian,
+    );
+    let mut body = ZBuf::empty();
+    body.push_zslice(
+        payload
+            .subslice(0, CDR_ENCAPSULATION_LEN)
+            .expect("Shouldn't happen: payload is longer than 4 bytes"),
+    );
+    body.push_zslice(
+        payload
+            .subslice(
+                CDR_ENCAPSULATION_LEN + CDDS_REQUEST_HEADER_LEN,
+                payload.len(),
+            )
+            .expect("Shouldn't happen: payload is longer than 20 bytes"),
+    );
+    Ok((request_id, body))
+}
+
+/// The payload of a Request or a Reply to publish to a rmw_cyclonedds Server or Client: the Request or
+/// Response type received from Zenoh (encapsulation header + CDR body) with the request header inserted
+/// after its encapsulation header, encoded with the endianness of its representation identifier
+/// (see split_request_header() for the alignment).
+pub fn insert_request_header(
+    payload: &[u8],
+    request_id: &CddsRequestHeader,
+) -> Result<Vec<u8>, String> {
+    check_cdr_header(payload)?;
+    let is_little_endian =
+        is_cdr_little_endian(payload).expect("Shouldn't happen: payload is longer than 4 bytes");
+    let mut buf = Vec::with_capacity(payload.len() + CDDS_REQUEST_HEADER_LEN);
+    buf.extend_from_slice(&payload[..CDR_ENCAPSULATION_LEN]);
+    buf.extend_from_slice(request_id.with_endianness(is_little_endian).as_slice());
+    buf.extend_from_slice(&payload[CDR_ENCAPSULATION_LEN..]);
+    Ok(buf)
 }
 
 fn ros2_service_default_qos() -> Qos {
@@ -360,6 +1027,84 @@ pub fn is_message_for_action(ros2_message_name: &str) -> bool {
         || ros2_message_name.ends_with(KE_SUFFIX_ACTION_STATUS.as_str())
 }
 
+/// The name of the Service which introspection events are published on a topic
+/// (e.g. "/add_two_ints" for "/add_two_ints/_service_event"), if it's such a topic
+pub fn service_of_event_topic(ros2_message_name: &str) -> Option<&str> {
+    ros2_message_name
+        .strip_suffix(KE_SUFFIX_SERVICE_EVENT.as_str())
+        .and_then(|service| service.strip_suffix('/'))
+        .filter(|service| !service.is_empty())
+}
+
+/// The name of the topic on which the introspection events of a Service are published
+pub fn service_event_topic(ros2_service_name: &str) -> String {
+    format!("{ros2_service_name}/{}", *KE_SUFFIX_SERVICE_EVENT)
+}
+
+/// The topic on which the ROS Nodes publish the changes of their parameters
+pub const ROS2_PARAMETER_EVENTS_TOPIC: &str = "/parameter_events";
+
+// The names of the Services each ROS Node serves for its parameters (e.g. "/talker/get_parameters")
+const ROS2_PARAMETER_SERVICES: [&str; 6] = [
+    "describe_parameters",
+    "get_parameters",
+    "get_parameter_types",
+    "list_parameters",
+    "set_parameters",
+    "set_parameters_atomically",
+];
+
+/// The Node and the name (without the Node prefix) of a parameter Service
+/// (e.g. ("/ns/talker", "get_parameters") for "/ns/talker/get_parameters"), if it's such a Service
+pub fn parameter_service_node(ros2_service_name: &str) -> Option<(&str, &str)> {
+    let (node, service) = ros2_service_name.rsplit_once('/')?;
+    (!node.is_empty() && ROS2_PARAMETER_SERVICES.contains(&service)).then_some((node, service))
+}
+
+// The suffixes of the DDS topics used by an Action, with the suffix of their DDS type
+const ACTION_DDS_TOPICS: [(&str, &str); 8] = [
+    ("/_action/status", "::GoalStatusArray_"),
+    ("/_action/feedback", "_FeedbackMessage_"),
+    ("/_action/send_goalRequest", "_SendGoal_Request_"),
+    ("/_action/send_goalReply", "_SendGoal_Response_"),
+    ("/_action/cancel_goalRequest", "::CancelGoal_Request_"),
+    ("/_action/cancel_goalReply", "::CancelGoal_Response_"),
+    ("/_action/get_resultRequest", "_GetResult_Request_"),
+    ("/_action/get_resultReply", "_GetResult_Response_"),
+];
+
+/// Check if a DDS topic (without its "rt", "rq" or "rr" prefix) is used by an Action, i.e. if it has
+/// an Action's suffix (e.g. "/_action/status") and the corresponding type. So a topic or service
+/// that only has an Action-like name (e.g. a hidden topic) is not mistaken for an Action's one.
+pub fn is_action_dds_topic(topic_suffix: &str, dds_type: &str) -> bool {
+    ACTION_DDS_TOPICS
+        .iter()
+        .any(|(topic, typ)| topic_suffix.ends_with(topic) && dds_type.ends_with(typ))
+}
+
+/// The canonical form of a ROS name: with a leading '/', and without duplicate or trailing '/'
+/// (e.g. "/ns//topic", "/ns/topic/" or "ns/topic" are all "/ns/topic"). Borrowed if already canonical.
+pub fn canonical_ros2_name(name: &str) -> Cow<'_, str> {
+    if name.starts_with('/') && !name.contains("//") && (name == "/" || !name.ends_with('/')) {
+        return Cow::Borrowed(name);
+    }
+    let mut canonical = String::with_capacity(name.len() + 1);
+    for chunk in name.split('/').filter(|chunk| !chunk.is_empty()) {
+        canonical.push('/');
+        canonical.push_str(chunk);
+    }
+    if canonical.is_empty() {
+        canonical.push('/');
+    }
+    Cow::Owned(canonical)
+}
+
+/// Check if a ROS name is hidden, i.e. if one of its tokens starts with '_' (e.g. "/_internal/status")
+#[inline]
+pub fn is_hidden_ros2_name(ros2_name: &str) -> bool {
+    ros2_name.split('/').any(|token| token.starts_with('_'))
+}
+
 /// Check if name is a ROS name: starting with '/' and useable as a key expression (removing 1st '/')
 #[inline]
 pub fn check_ros_name(name: &str) -> Result<(), String> {
@@ -466,4 +1211,1139 @@ mod tests {
             "example_interfaces/action/Fibonacci"
         );
     }
+
+    #[test]
+    fn test_namespace_mapping() {
+        use crate::config::Config;
+        use crate::ros2_utils::*;
+        use zenoh::prelude::keyexpr;
+
+        // no namespace
+        assert_eq!(
+            ros2_name_to_key_expr("/chatter", "/", &[], &[]).as_str(),
+            "chatter"
+        );
+        assert_eq!(
+            key_expr_to_ros2_name(keyexpr::new("chatter").unwrap(), "/", &[], &[]),
+            "/chatter"
+        );
+
+        // with a namespace
+        assert_eq!(
+            ros2_name_to_key_expr("/ns/chatter", "/robot1", &[], &[]).as_str(),
+            "robot1/ns/chatter"
+        );
+        assert_eq!(
+            key_expr_to_ros2_name(
+                keyexpr::new("robot1/ns/chatter").unwrap(),
+                "/robot1",
+                &[],
+                &[]
+            ),
+            "/ns/chatter"
+        );
+        // key expr not prefixed with the namespace
+        assert_eq!(
+            key_expr_to_ros2_name(keyexpr::new("robot2/chatter").unwrap(), "/robot1", &[], &[]),
+            "/robot2/chatter"
+        );
+        assert_eq!(
+            key_expr_to_ros2_name(
+                keyexpr::new("robot10/chatter").unwrap(),
+                "/robot1",
+                &[],
+                &[]
+            ),
+            "/robot10/chatter"
+        );
+
+        // namespace_to_zenoh and namespace_from_zenoh default to namespace
+        let config: Config = serde_json::from_str(r#"{"namespace": "/robot1"}"#).unwrap();
+        assert_eq!(config.get_namespace_to_zenoh(), "/robot1");
+        assert_eq!(config.get_namespace_from_zenoh(), "/robot1");
+        let config: Config =
+            serde_json::from_str(r#"{"namespace": "/robot1", "namespace_from_zenoh": "/fleet"}"#)
+                .unwrap();
+        assert_eq!(config.get_namespace_to_zenoh(), "/robot1");
+        assert_eq!(config.get_namespace_from_zenoh(), "/fleet");
+
+        // Round-trip across 2 bridges with different mappings:
+        //  - robot:  publishes under "robot1/**", receives from "fleet/**" in its root namespace
+        //  - fleet:  publishes under "fleet/**", receives from "robot1/**" in its root namespace
+        let robot: Config = serde_json::from_str(
+            r#"{"namespace_to_zenoh": "/robot1", "namespace_from_zenoh": "/fleet"}"#,
+        )
+        .unwrap();
+        let fleet: Config = serde_json::from_str(
+            r#"{"namespace_to_zenoh": "/fleet", "namespace_from_zenoh": "/robot1"}"#,
+        )
+        .unwrap();
+        for name in ["/status", "/tf", "/ns/cmd_vel", "/a/b/c"] {
+            // DDS (robot) -> zenoh -> DDS (fleet)
+            let ke = ros2_name_to_key_expr(name, robot.get_namespace_to_zenoh(), &[], &[]);
+            assert_eq!(ke.as_str(), format!("robot1{name}"));
+            assert_eq!(
+                key_expr_to_ros2_name(&ke, fleet.get_namespace_from_zenoh(), &[], &[]),
+                name
+            );
+            // DDS (fleet) -> zenoh -> DDS (robot)
+            let ke = ros2_name_to_key_expr(name, fleet.get_namespace_to_zenoh(), &[], &[]);
+            assert_eq!(ke.as_str(), format!("fleet{name}"));
+            assert_eq!(
+                key_expr_to_ros2_name(&ke, robot.get_namespace_from_zenoh(), &[], &[]),
+                name
+            );
+            // a local Subscriber on the fleet bridge matches the key expr published by the robot bridge
+            assert_eq!(
+                ros2_name_to_key_expr(name, fleet.get_namespace_from_zenoh(), &[], &[]),
+                ros2_name_to_key_expr(name, robot.get_namespace_to_zenoh(), &[], &[])
+            );
+        }
+    }
+
+    #[test]
+    fn test_namespace_rules() {
+        use crate::config::Config;
+        use crate::ros2_utils::*;
+
+        const CORPUS: [&str; 14] = [
+            "/tf",
+            "/tf_static",
+            "/tf2",
+            "/map",
+            "/map_updates",
+            "/status",
+            "/cmd_vel",
+            "/rosout",
+            "/ns/cmd_vel",
+            "/robot1/odom",
+            "/robot1/robot1/odom",
+            "/robot10/odom",
+            "/fleet/status",
+            "/a/b/c/d",
+        ];
+
+        // rules sorted most-specific-first, whatever their order in config
+        let config: Config = serde_json::from_str(
+            r#"{"namespace": "/robot1", "namespace_rules": [
+                {"pattern": ".*", "namespace": "/fleet"},
+                {"pattern": "/robot1/.*", "namespace": "/robot1"},
+                {"pattern": "/map", "namespace": "/"}
+            ]}"#,
+        )
+        .unwrap();
+        let rules = &config.namespace_rules;
+        assert_eq!(
+            rules.iter().map(|r| r.namespace()).collect::<Vec<_>>(),
+            vec!["/", "/robot1", "/fleet"]
+        );
+        assert_eq!(
+            ros2_name_to_key_expr("/map", "/robot1", rules, &[]).as_str(),
+            "map"
+        );
+        assert_eq!(
+            ros2_name_to_key_expr("/map_updates", "/robot1", rules, &[]).as_str(),
+            "fleet/map_updates"
+        );
+        assert_eq!(
+            ros2_name_to_key_expr("/robot1/odom", "/robot1", rules, &[]).as_str(),
+            "robot1/robot1/odom"
+        );
+
+        // rule sets without collision: all names survive the round-trip
+        for json in [
+            // "/tf" and "/tf_static" shared un-prefixed across robots, everything else prefixed
+            r#"{"namespace": "/robot1", "namespace_rules": [
+                {"pattern": "/tf|/tf_static", "namespace": "/"}
+            ]}"#,
+            r#"{"namespace_rules": [
+                {"pattern": ".*", "namespace": "/fleet"},
+                {"pattern": "/robot1/.*", "namespace": "/robot1"},
+                {"pattern": "/map", "namespace": "/"}
+            ]}"#,
+            r#"{"namespace": "/a", "namespace_rules": [
+                {"pattern": "/ns/.*", "namespace": "/a/b"},
+                {"pattern": "/tf.*", "namespace": "/b"}
+            ]}"#,
+        ] {
+            let config: Config = serde_json::from_str(json).unwrap();
+            let ns = config.get_namespace_to_zenoh();
+            let rules = &config.namespace_rules;
+            for name in CORPUS {
+                let ke = ros2_name_to_key_expr(name, ns, rules, &[]);
+                assert_eq!(
+                    key_expr_to_ros2_name(&ke, ns, rules, &[]),
+                    name,
+                    "round-trip failed for {name} via {ke} with {json}"
+                );
+            }
+        }
+
+        // rule set with collisions ("/robot1/odom" and "/odom" are both mapped to "robot1/odom"):
+        // the inverse mapping is deterministic and always consistent with the forward mapping
+        let config: Config = serde_json::from_str(
+            r#"{"namespace": "/robot1", "namespace_rules": [
+                {"pattern": "/robot1/.*", "namespace": "/"}
+            ]}"#,
+        )
+        .unwrap();
+        let rules = &config.namespace_rules;
+        for name in CORPUS {
+            let ke = ros2_name_to_key_expr(name, "/robot1", rules, &[]);
+            let inverse = key_expr_to_ros2_name(&ke, "/robot1", rules, &[]);
+            assert_eq!(ros2_name_to_key_expr(&inverse, "/robot1", rules, &[]), ke);
+        }
+        assert_eq!(
+            key_expr_to_ros2_name(
+                &ros2_name_to_key_expr("/odom", "/robot1", rules),
+                "/robot1",
+                rules,
+                &[]
+            ),
+            "/robot1/odom"
+        );
+    }
+
+    #[test]
+    fn test_escape_ros2_name() {
+        use crate::ros2_utils::*;
+        use zenoh::prelude::keyexpr;
+
+        // clean names are unchanged
+        for name in ["/odom", "/robot1/cmd_vel", "/a%b", "/100%", "/x%41"] {
+            assert_eq!(
+                ros2_name_to_key_expr(name, "/", &[], &[]).as_str(),
+                &name[1..]
+            );
+        }
+        assert_eq!(
+            ros2_name_to_key_expr("/rt/*/a$b?#", "/", &[], &[]).as_str(),
+            "rt/%2A/a%24b%3F%23"
+        );
+        assert_eq!(
+            ros2_name_to_key_expr("//a//b/", "/", &[], &[]).as_str(),
+            "%2Fa%2F/b%2F"
+        );
+        assert_eq!(
+            ros2_name_to_key_expr("/%2A", "/", &[], &[]).as_str(),
+            "%252A"
+        );
+        assert_eq!(
+            key_expr_to_ros2_name(keyexpr::new("%252A").unwrap(), "/", &[], &[]),
+            "/%2A"
+        );
+
+        // '@' would start a verbatim chunk
+        for (name, ke) in [
+            ("/vendor@stack/status", "vendor%40stack/status"),
+            ("/@internal/x", "%40internal/x"),
+            ("/a/@", "a/%40"),
+            ("/$$", "%24%24"),
+            ("/q?/x#y", "q%3F/x%23y"),
+            ("/a§b", "a%A7b"),
+            ("/%40", "%2540"),
+        ] {
+            let result = ros2_name_to_key_expr(name, "/", &[], &[]);
+            assert_eq!(result.as_str(), ke);
+            assert!(keyexpr::new(ke).is_ok_and(|k| !k.is_wild()));
+            assert_eq!(key_expr_to_ros2_name(&result, "/", &[], &[]), name);
+        }
+        assert_eq!(
+            raw_dds_topic_to_key_expr("Vendor@Sensor", &[]).as_str(),
+            "raw/Vendor%40Sensor"
+        );
+
+        // round-trip over random names (using a xorshift PRNG for reproducibility)
+        const ALPHABET: &[char] = &[
+            'a', 'Z', '0', '2', 'A', 'F', '4', '5', '_', '/', '/', '%', '%', '*', '$', '?', '#',
+            '-', '~', 'é', '@', '§',
+        ];
+        let mut seed: u64 = 0x2545F4914F6CDD1D;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed as usize
+        };
+        for _ in 0..10000 {
+            let len = 1 + next() % 12;
+            let name: String = std::iter::once('/')
+                .chain((0..len).map(|_| ALPHABET[next() % ALPHABET.len()]))
+                .collect();
+            for ns in ["/", "/robot1"] {
+                let ke = ros2_name_to_key_expr(&name, ns, &[], &[]);
+                let checked = keyexpr::new(ke.as_str())
+                    .unwrap_or_else(|e| panic!("invalid key expr {ke} for {name}: {e}"));
+                assert!(!checked.is_wild(), "wild key expr {ke} for {name}");
+                assert_eq!(key_expr_to_ros2_name(&ke, ns, &[], &[]), name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_remappings() {
+        use crate::config::Config;
+        use crate::ros2_utils::*;
+
+        // Round-trip across 2 bridges with complementary remappings
+        let site_a: Config = serde_json::from_str(
+            r#"{"remappings": {"/old_cmd_vel": "/cmd_vel", "/ns/**": "/shared/**"}}"#,
+        )
+        .unwrap();
+        let site_b: Config = serde_json::from_str(
+            r#"{"namespace": "/b", "remappings": {"/teleop/cmd_vel": "/cmd_vel", "/other_ns/**": "/shared/**"}}"#,
+        )
+        .unwrap();
+        for (name_a, name_b, ke) in [
+            ("/old_cmd_vel", "/teleop/cmd_vel", "cmd_vel"),
+            ("/ns/odom", "/other_ns/odom", "shared/odom"),
+            ("/ns/a/b", "/other_ns/a/b", "shared/a/b"),
+        ] {
+            // DDS (site A) -> zenoh -> DDS (site B), and the reverse
+            let ke_a = ros2_name_to_key_expr(name_a, "/", &[], &site_a.remappings);
+            assert_eq!(ke_a.as_str(), ke);
+            assert_eq!(
+                key_expr_to_ros2_name(&ke_a, "/", &[], &site_b.remappings),
+                name_b
+            );
+            let ke_b = ros2_name_to_key_expr(name_b, "/", &[], &site_b.remappings);
+            assert_eq!(ke_b, ke_a);
+            assert_eq!(
+                key_expr_to_ros2_name(&ke_b, "/", &[], &site_a.remappings),
+                name_a
+            );
+        }
+
+        // remapping before namespace prefixing
+        let ke = ros2_name_to_key_expr(
+            "/teleop/cmd_vel",
+            &site_b.namespace,
+            &[],
+            &site_b.remappings,
+        );
+        assert_eq!(ke.as_str(), "b/cmd_vel");
+        assert_eq!(
+            key_expr_to_ros2_name(&ke, &site_b.namespace, &[], &site_b.remappings),
+            "/teleop/cmd_vel"
+        );
+
+        // not remapped names are unchanged
+        let ke = ros2_name_to_key_expr("/odom", "/", &[], &site_a.remappings);
+        assert_eq!(ke.as_str(), "odom");
+        assert_eq!(
+            key_expr_to_ros2_name(&ke, "/", &[], &site_a.remappings),
+            "/odom"
+        );
+    }
+
+    #[test]
+    fn test_action_remappings() {
+        use crate::config::Config;
+        use crate::ros2_utils::*;
+
+        // An Action Server on site A and an Action Client on site B, with complementary remappings
+        // of the Action's name only
+        let site_a: Config =
+            serde_json::from_str(r#"{"remappings": {"/navigate_to_pose": "/nav/navigate"}}"#)
+                .unwrap();
+        let site_b: Config = serde_json::from_str(
+            r#"{"namespace": "/b", "remappings": {"/navigate": "/nav/navigate"}}"#,
+        )
+        .unwrap();
+        for suffix in [
+            "",
+            "/_action/send_goal",
+            "/_action/cancel_goal",
+            "/_action/get_result",
+            "/_action/feedback",
+            "/_action/status",
+        ] {
+            let name_a = format!("/navigate_to_pose{suffix}");
+            let name_b = format!("/navigate{suffix}");
+            let ke = format!("nav/navigate{suffix}");
+            // DDS (site A) -> zenoh -> DDS (site B), and the reverse
+            let ke_a = ros2_name_to_key_expr(&name_a, "/", &[], &site_a.remappings);
+            assert_eq!(ke_a.as_str(), ke);
+            assert_eq!(
+                key_expr_to_ros2_name(&ke_a, "/", &[], &site_b.remappings),
+                name_b
+            );
+            let ke_b = ros2_name_to_key_expr(&name_b, "/", &[], &site_b.remappings);
+            assert_eq!(ke_b, ke_a);
+            assert_eq!(
+                key_expr_to_ros2_name(&ke_b, "/", &[], &site_a.remappings),
+                name_a
+            );
+            // with site B's namespace
+            let ke_b = ros2_name_to_key_expr(&name_b, &site_b.namespace, &[], &site_b.remappings);
+            assert_eq!(ke_b.as_str(), format!("b/{ke}"));
+            assert_eq!(
+                key_expr_to_ros2_name(&ke_b, &site_b.namespace, &[], &site_b.remappings),
+                name_b
+            );
+        }
+
+        // an explicit remapping of a sub-interface has precedence
+        let site: Config = serde_json::from_str(
+            r#"{"remappings": {"/navigate_to_pose": "/nav/navigate", "/navigate_to_pose/_action/status": "/nav/status"}}"#,
+        )
+        .unwrap();
+        let ke = ros2_name_to_key_expr(
+            "/navigate_to_pose/_action/status",
+            "/",
+            &[],
+            &site.remappings,
+        );
+        assert_eq!(ke.as_str(), "nav/status");
+        assert_eq!(
+            key_expr_to_ros2_name(&ke, "/", &[], &site.remappings),
+            "/navigate_to_pose/_action/status"
+        );
+
+        // the suffix must be a full sub-interface name
+        for name in [
+            "/navigate_to_pose_action/status",
+            "/navigate_to_pose/_action/status2",
+        ] {
+            let ke = ros2_name_to_key_expr(name, "/", &[], &site_a.remappings);
+            assert_eq!(ke.as_str(), &name[1..]);
+        }
+    }
+
+    #[test]
+    fn test_hidden_names() {
+        use crate::ros2_utils::*;
+
+        assert!(!is_hidden_ros2_name("/cmd_vel"));
+        assert!(!is_hidden_ros2_name("/robot_1/cmd_vel_"));
+        assert!(is_hidden_ros2_name("/_internal/status"));
+        assert!(is_hidden_ros2_name("/ns/_status"));
+
+        // a hidden name round-trips as is
+        let ke = ros2_name_to_key_expr("/_internal/status", "/robot1", &[], &[]);
+        assert_eq!(ke.as_str(), "robot1/_internal/status");
+        assert_eq!(
+            key_expr_to_ros2_name(&ke, "/robot1", &[], &[]),
+            "/_internal/status"
+        );
+
+        // Action's topics are recognized with their type
+        assert!(is_action_dds_topic(
+            "/fibonacci/_action/status",
+            "action_msgs::msg::dds_::GoalStatusArray_"
+        ));
+        assert!(is_action_dds_topic(
+            "/fibonacci/_action/send_goalRequest",
+            "example_interfaces::action::dds_::Fibonacci_SendGoal_Request_"
+        ));
+        assert!(is_action_dds_topic(
+            "/fibonacci/_action/cancel_goalReply",
+            "action_msgs::srv::dds_::CancelGoal_Response_"
+        ));
+        // but not a hidden topic with an Action-like name and another type
+        assert!(!is_action_dds_topic(
+            "/debug/_action/status",
+            "std_msgs::msg::dds_::String_"
+        ));
+        assert!(!is_action_dds_topic(
+            "/debug/_action/feedback",
+            "std_msgs::msg::dds_::String_"
+        ));
+        assert!(!is_action_dds_topic(
+            "/fibonacci/status",
+            "action_msgs::msg::dds_::GoalStatusArray_"
+        ));
+    }
+
+    #[test]
+    fn test_service_event_topic() {
+        use crate::ros2_utils::*;
+
+        assert_eq!(
+            service_event_topic("/add_two_ints"),
+            "/add_two_ints/_service_event"
+        );
+        assert_eq!(
+            service_of_event_topic("/robot1/add_two_ints/_service_event"),
+            Some("/robot1/add_two_ints")
+        );
+        assert_eq!(service_of_event_topic("/add_two_ints"), None);
+        assert_eq!(service_of_event_topic("/my_service_event"), None);
+        assert_eq!(service_of_event_topic("/_service_event"), None);
+    }
+
+    #[test]
+    fn test_parameter_service_node() {
+        use crate::ros2_utils::*;
+
+        assert_eq!(
+            parameter_service_node("/talker/get_parameters"),
+            Some(("/talker", "get_parameters"))
+        );
+        assert_eq!(
+            parameter_service_node("/ns/talker/set_parameters_atomically"),
+            Some(("/ns/talker", "set_parameters_atomically"))
+        );
+        assert_eq!(parameter_service_node("/get_parameters"), None);
+        assert_eq!(parameter_service_node("/talker/add_two_ints"), None);
+        assert_eq!(parameter_service_node("/talker/get_parameters_x"), None);
+    }
+
+    #[test]
+    fn test_nested_namespaces() {
+        use crate::ros2_utils::*;
+        use zenoh::prelude::keyexpr;
+
+        // (namespace, ROS name, key expr)
+        const TABLE: [(&str, &str, &str); 16] = [
+            ("/", "/tf", "tf"),
+            ("/", "/sensors/imu/data", "sensors/imu/data"),
+            ("/", "/fleet/r1/odom", "fleet/r1/odom"),
+            ("/fleet", "/tf", "fleet/tf"),
+            ("/fleet", "/sensors/imu/data", "fleet/sensors/imu/data"),
+            ("/fleet", "/fleet/odom", "fleet/fleet/odom"),
+            ("/fleet/r1", "/tf", "fleet/r1/tf"),
+            (
+                "/fleet/r1",
+                "/sensors/imu/data",
+                "fleet/r1/sensors/imu/data",
+            ),
+            ("/fleet/r1", "/fleet/r1/odom", "fleet/r1/fleet/r1/odom"),
+            ("/fleet/r1", "/r1/odom", "fleet/r1/r1/odom"),
+            (
+                "/fleet/r1/",
+                "/sensors/imu/data",
+                "fleet/r1/sensors/imu/data",
+            ),
+            ("/fleet/r1/", "/tf", "fleet/r1/tf"),
+            ("/a/b/c/d", "/e", "a/b/c/d/e"),
+            ("/a/b/c/d", "/a/b/c/d/e", "a/b/c/d/a/b/c/d/e"),
+            ("/robot_1/arm", "/joint_states", "robot_1/arm/joint_states"),
+            ("/robot_1/arm", "/_hidden/x", "robot_1/arm/_hidden/x"),
+        ];
+        for (namespace, name, ke) in TABLE {
+            let result = ros2_name_to_key_expr(name, namespace, &[], &[]);
+            assert_eq!(result.as_str(), ke, "{name} in {namespace}");
+            assert!(keyexpr::new(result.as_str()).is_ok(), "{result} is invalid");
+            assert_eq!(
+                key_expr_to_ros2_name(&result, namespace, &[], &[]),
+                name,
+                "{ke} in {namespace}"
+            );
+        }
+
+        // a key expression not in the namespace (or only partially) keeps all its chunks
+        for (namespace, ke, name) in [
+            ("/fleet/r1", "fleet/r10/odom", "/fleet/r10/odom"),
+            ("/fleet/r1", "fleet/odom", "/fleet/odom"),
+            ("/fleet/r1", "fleet/r1", "/fleet/r1"),
+        ] {
+            assert_eq!(
+                key_expr_to_ros2_name(keyexpr::new(ke).unwrap(), namespace, &[], &[]),
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_scopes() {
+        use crate::ros2_utils::*;
+        use zenoh::prelude::keyexpr;
+
+        fn ke(s: &str) -> &keyexpr {
+            keyexpr::new(s).unwrap()
+        }
+        assert_eq!(
+            unscope_key_expr(Some(ke("dds/site_a")), ke("dds/site_a/tf")),
+            Some(ke("tf"))
+        );
+        assert_eq!(
+            unscope_key_expr(Some(ke("dds")), ke("dds/site_a/tf")),
+            Some(ke("site_a/tf"))
+        );
+        assert_eq!(unscope_key_expr(Some(ke("dds")), ke("dds2/tf")), None);
+        assert_eq!(
+            unscope_key_expr(Some(ke("dds/site_a")), ke("dds/site_a")),
+            None
+        );
+        assert_eq!(
+            unscope_key_expr(Some(ke("dds/site_a")), ke("dds/site_b/tf")),
+            None
+        );
+        assert_eq!(unscope_key_expr(None, ke("dds/tf")), Some(ke("dds/tf")));
+
+        // 2 bridges with asymmetric scopes:
+        //  - site A: publishes under "dds/site_a/**" and receives from "dds/**"
+        //  - site B: publishes under "dds/site_b/**" and receives from "dds/**"
+        let (a_out, a_in) = (Some(ke("dds/site_a")), Some(ke("dds")));
+        let (b_out, b_in) = (Some(ke("dds/site_b")), Some(ke("dds")));
+        // site A's Publisher route on "/tf", announced via liveliness with its key expression
+        let a_pub = scope_key_expr(a_out, ros2_name_to_key_expr("/tf", "/", &[], &[]));
+        assert_eq!(a_pub.as_str(), "dds/site_a/tf");
+        // site B receives the announcement (in its "scope_in") and creates a Subscriber route
+        // for "/site_a/tf", with the same key expression than site A's Publisher
+        let b_name = key_expr_to_ros2_name(unscope_key_expr(b_in, &a_pub).unwrap(), "/", &[], &[]);
+        assert_eq!(b_name, "/site_a/tf");
+        let b_sub = scope_key_expr(b_in, ros2_name_to_key_expr(&b_name, "/", &[], &[]));
+        assert_eq!(b_sub, a_pub);
+        // the reverse: site B's Publisher on "/odom"
+        let b_pub = scope_key_expr(b_out, ros2_name_to_key_expr("/odom", "/", &[], &[]));
+        let a_name = key_expr_to_ros2_name(unscope_key_expr(a_in, &b_pub).unwrap(), "/", &[], &[]);
+        assert_eq!(a_name, "/site_b/odom");
+        assert_eq!(
+            scope_key_expr(a_in, ros2_name_to_key_expr(&a_name, "/", &[], &[])),
+            b_pub
+        );
+        // site A's Subscriber route for "/site_b/odom" is announced with its key expression:
+        // site B maps it (in its "scope_out") to its Publisher route on "/odom"
+        let a_sub = b_pub;
+        let name = key_expr_to_ros2_name(unscope_key_expr(b_out, &a_sub).unwrap(), "/", &[], &[]);
+        assert_eq!(name, "/odom");
+        // but site A ignores it as it's out of its own "scope_out"
+        assert_eq!(unscope_key_expr(a_out, &a_sub), None);
+    }
+
+    #[test]
+    fn test_dds_topic_name() {
+        use crate::config::LongNamePolicy::*;
+        use crate::ros2_utils::*;
+
+        // at the boundary: the name is unchanged, whatever the policy
+        let name = format!("/{}", "a".repeat(252));
+        for policy in [Reject, Hash] {
+            let topic = dds_topic_name("rt", &name, "", policy).unwrap();
+            assert_eq!(topic.len(), DDS_TOPIC_NAME_MAX_LEN);
+            assert_eq!(topic, format!("rt{name}"));
+        }
+
+        // 1 character more: rejected or hashed
+        let name = format!("/{}", "a".repeat(253));
+        assert_eq!(
+            dds_topic_name("rt", &name, "", Reject),
+            Err(TopicNameTooLong {
+                dds_topic: format!("rt{name}")
+            })
+        );
+        // the hash is stable (i.e. across restarts and bridges): check against its expected value
+        let topic = dds_topic_name("rt", &name, "", Hash).unwrap();
+        assert_eq!(topic.len(), DDS_TOPIC_NAME_MAX_LEN);
+        assert_eq!(topic, format!("rt/{}_cf8d3b5fa2f8ce1b", "a".repeat(235)));
+        // the suffix is preserved
+        let topic = dds_topic_name("rq", &name, "Request", Hash).unwrap();
+        assert_eq!(topic.len(), DDS_TOPIC_NAME_MAX_LEN);
+        assert!(topic.ends_with("_1f3b6e0ab15f7035Request"));
+        // names differing after the truncation have distinct hashes
+        let other = format!("/{}b", "a".repeat(252));
+        assert_ne!(
+            dds_topic_name("rt", &other, "", Hash),
+            dds_topic_name("rt", &name, "", Hash)
+        );
+        // the truncation doesn't split a multi-bytes character
+        let name = format!("/{}é{}", "a".repeat(234), "a".repeat(30));
+        let topic = dds_topic_name("rt", &name, "", Hash).unwrap();
+        assert!(topic.len() <= DDS_TOPIC_NAME_MAX_LEN);
+        assert!(topic.starts_with(&format!("rt/{}_", "a".repeat(234))));
+    }
+
+    #[test]
+    fn test_typed_key_expr() {
+        use crate::ros2_utils::*;
+
+        // round trip: ROS name -> typed key expression -> ROS name (with the type chunk stripped)
+        let ke = ros2_name_to_key_expr("/robot1/cmd_vel", "/", &[], &[]);
+        let typed = typed_key_expr(&ke, "geometry_msgs/msg/Twist").unwrap();
+        assert_eq!(typed.as_str(), "robot1/cmd_vel/geometry_msgs§msg§Twist");
+        let untyped = typed
+            .as_str()
+            .strip_suffix("/geometry_msgs§msg§Twist")
+            .unwrap();
+        assert_eq!(
+            key_expr_to_ros2_name(keyexpr::new(untyped).unwrap(), "/", &[], &[]),
+            "/robot1/cmd_vel"
+        );
+        assert!(typed_key_expr(&ke, "geometry_msgs/msg/*").is_err());
+        assert!(typed_key_expr(&ke, "").is_err());
+    }
+
+    #[test]
+    fn test_route_to_key_expr() {
+        use crate::ros2_utils::*;
+
+        let ke = keyexpr::new("incident_report").unwrap();
+        let scoped = route_to_key_expr(&["control_center".into(), "hq".into()], ke).unwrap();
+        assert_eq!(
+            scoped.as_str(),
+            "@ros2_to/control_center/hq/@/incident_report"
+        );
+
+        // only the listed bridges' subscriptions match, whatever their position in the list
+        let sub = |target: &str| route_to_subscription_key_expr(keyexpr::new(target).unwrap(), ke);
+        assert!(sub("control_center").intersects(&scoped));
+        assert!(sub("hq").intersects(&scoped));
+        assert!(!sub("robot1").intersects(&scoped));
+        assert!(!sub("incident_report").intersects(&scoped));
+        // nor the other topics
+        let other = route_to_key_expr(
+            &["hq".into()],
+            keyexpr::new("robot1/incident_report").unwrap(),
+        )
+        .unwrap();
+        assert!(!sub("hq").intersects(&other));
+        assert!(!sub("robot1").intersects(&other));
+        // nor the unscoped subscriptions
+        assert!(!ke.intersects(&scoped));
+
+        assert!(route_to_key_expr(&["*".into()], ke).is_err());
+        assert!(route_to_key_expr(&["a/b".into()], ke).is_err());
+        assert!(route_to_key_expr(&["@".into()], ke).is_err());
+        assert!(route_to_target("").is_err());
+    }
+
+    #[async_std::test]
+    async fn test_route_to_selective_delivery() {
+        use crate::ros2_utils::*;
+        use std::time::Duration;
+        use zenoh::prelude::r#async::*;
+
+        // a robot's bridge routing "/incident_report" to the control center only,
+        // while both the control center and another robot have a Subscriber route for it
+        let mut sessions = crate::test_utils::connected_sessions(3).await;
+        let robot2 = sessions.pop().unwrap();
+        let control_center = sessions.pop().unwrap();
+        let robot1 = sessions.pop().unwrap();
+        let ke = keyexpr::new("incident_report").unwrap();
+        let subscribe = |session: &zenoh::Session, target: &str| {
+            session
+                .declare_subscriber(route_to_subscription_key_expr(
+                    keyexpr::new(target).unwrap(),
+                    ke,
+                ))
+                .res_async()
+        };
+        let cc_sub = subscribe(&control_center, "control_center").await.unwrap();
+        let robot2_sub = subscribe(&robot2, "robot2").await.unwrap();
+        async_std::task::sleep(Duration::from_millis(500)).await;
+
+        let scoped = route_to_key_expr(&["control_center".into()], ke).unwrap();
+        for i in 0..3 {
+            robot1
+                .put(&scoped, format!("incident {i}"))
+                .res_async()
+                .await
+                .unwrap();
+        }
+        async_std::task::sleep(Duration::from_millis(200)).await;
+
+        let mut received = Vec::new();
+        while let Ok(sample) = cc_sub.try_recv() {
+            received.push(String::from_utf8(sample.payload.contiguous().into_owned()).unwrap());
+        }
+        assert_eq!(received, vec!["incident 0", "incident 1", "incident 2"]);
+        // the unlisted bridge gets nothing
+        assert!(robot2_sub.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_canonical_ros2_name() {
+        use crate::ros2_utils::*;
+        use std::borrow::Cow;
+
+        // already canonical names are borrowed
+        for name in ["/", "/topic", "/ns/topic", "/_action/status"] {
+            assert!(matches!(canonical_ros2_name(name), Cow::Borrowed(n) if n == name));
+        }
+        // duplicate and trailing slashes, missing leading slash
+        for name in [
+            "/ns//topic",
+            "/ns/topic/",
+            "ns/topic",
+            "//ns///topic//",
+            "ns//topic/",
+        ] {
+            assert!(
+                matches!(canonical_ros2_name(name), Cow::Owned(n) if n == "/ns/topic"),
+                "{name}"
+            );
+        }
+        assert_eq!(canonical_ros2_name("//"), "/");
+        assert_eq!(canonical_ros2_name(""), "/");
+    }
+
+    #[test]
+    fn test_instance_key_expr() {
+        use crate::dds_types::DDSInstanceState;
+        use crate::ros2_utils::*;
+
+        // 2 instances of the same keyed topic are routed on distinct key expressions
+        let ke = ke_for_sure!("fleet_status");
+        let kh1 = [0x01u8; 16];
+        let mut kh2 = [0x01u8; 16];
+        kh2[15] = 0xfe;
+        let ke1 = instance_key_expr(ke, &kh1);
+        let ke2 = instance_key_expr(ke, &kh2);
+        assert_eq!(
+            ke1.as_str(),
+            "fleet_status/01010101010101010101010101010101"
+        );
+        assert_eq!(
+            ke2.as_str(),
+            "fleet_status/010101010101010101010101010101fe"
+        );
+        assert_ne!(ke1, ke2);
+        assert!(ke_for_sure!("fleet_status/*").intersects(&ke1));
+
+        // the instance chunk is stripped on the other side
+        assert_eq!(
+            instance_chunk(&ke1),
+            Some("01010101010101010101010101010101")
+        );
+        assert_eq!(
+            instance_chunk(&ke2),
+            Some("010101010101010101010101010101fe")
+        );
+        assert_eq!(
+            instance_chunk(&instance_key_expr(ke_for_sure!("a/b"), &kh2)),
+            Some("010101010101010101010101010101fe")
+        );
+        assert_eq!(instance_chunk(ke), None);
+        assert_eq!(
+            instance_chunk(ke_for_sure!("fleet_status/0101010101010101010101010101010")),
+            None
+        );
+        assert_eq!(
+            instance_chunk(ke_for_sure!(
+                "fleet_status/0101010101010101010101010101010G"
+            )),
+            None
+        );
+
+        // the instance state is carried by the attachment of the Zenoh delete
+        for state in [DDSInstanceState::Disposed, DDSInstanceState::Unregistered] {
+            assert_eq!(
+                instance_state_from_attachment(&instance_state_as_attachment(state)),
+                Some(state)
+            );
+        }
+        assert_eq!(
+            instance_state_from_attachment(&CddsRequestHeader::create(1, 2, true).as_attachment()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_instance_keyhash_attachment() {
+        use crate::dds_types::DDSInstanceState;
+        use crate::ros2_utils::*;
+
+        // out of "instance_key_exprs" mode, the instance of a keyed topic is carried by the attachments
+        let kh = [0x01u8, 0xfe, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x7f];
+        let put = instance_keyhash_as_attachment(&kh);
+        assert_eq!(instance_keyhash_from_attachment(&put), Some(kh));
+        assert_eq!(instance_state_from_attachment(&put), None);
+
+        let delete = instance_state_and_keyhash_as_attachment(DDSInstanceState::Unregistered, &kh);
+        assert_eq!(instance_keyhash_from_attachment(&delete), Some(kh));
+        assert_eq!(
+            instance_state_from_attachment(&delete),
+            Some(DDSInstanceState::Unregistered)
+        );
+
+        assert_eq!(
+            instance_keyhash_from_attachment(&instance_state_as_attachment(
+                DDSInstanceState::Disposed
+            )),
+            None
+        );
+        assert_eq!(
+            instance_keyhash_from_attachment(
+                &CddsRequestHeader::create(1, 2, true).as_attachment()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_origin_attachment() {
+        use crate::dedup::Origin;
+        use crate::ros2_utils::*;
+
+        let origin = Origin {
+            gid: [0x42u8; 16].into(),
+            source_timestamp: 1_700_000_000_000_000_001,
+        };
+        let kh = [0x01u8; 16];
+        let mut put = instance_keyhash_as_attachment(&kh);
+        assert_eq!(origin_from_attachment(&put), None);
+        add_origin_to_attachment(&mut put, origin);
+        assert_eq!(origin_from_attachment(&put), Some(origin));
+        assert_eq!(instance_keyhash_from_attachment(&put), Some(kh));
+        // the source timestamp to be restored when writing to DDS
+        assert_eq!(
+            origin_from_attachment(&put).map(|o| o.source_timestamp),
+            Some(1_700_000_000_000_000_001)
+        );
+
+        let mut put = Attachment::new();
+        add_origin_to_attachment(&mut put, origin);
+        assert_eq!(origin_from_attachment(&put), Some(origin));
+        assert_eq!(instance_keyhash_from_attachment(&put), None);
+    }
+
+    #[test]
+    fn test_request_header() {
+        use crate::ros2_utils::*;
+        use std::collections::HashSet;
+
+        let le = CddsRequestHeader::create(0x0102_0304_0506_0708, 7, true);
+        let be = CddsRequestHeader::create(0x0102_0304_0506_0708, 7, false);
+        assert_ne!(le.as_slice(), be.as_slice());
+        assert_eq!(le.client_id_and_seq_num(), (0x0102_0304_0506_0708, 7));
+        assert_eq!(be.client_id_and_seq_num(), (0x0102_0304_0506_0708, 7));
+        // the same request, whatever the endianness
+        assert!(le == be);
+        assert_eq!(le.with_endianness(false).as_slice(), be.as_slice());
+        assert_eq!(be.with_endianness(true).as_slice(), le.as_slice());
+        assert_eq!(le.to_string(), be.with_endianness(true).to_string());
+        // but distinct clients with the same sequence number are distinct requests
+        let other = CddsRequestHeader::create(0x0102_0304_0506_0709, 7, true);
+        assert!(le != other);
+        let set: HashSet<CddsRequestHeader> = [le, be, other].into_iter().collect();
+        assert_eq!(set.len(), 2);
+
+        let attachment = be.as_attachment();
+        let header = CddsRequestHeader::try_from(&attachment).unwrap();
+        assert_eq!(header.as_slice(), be.as_slice());
+        assert!(!header.is_little_endian());
+    }
+
+    #[test]
+    fn test_request_header_codec() {
+        use crate::ros2_utils::*;
+        use zenoh::buffers::ZSlice;
+
+        let request_id = CddsRequestHeader::create(0x0102_0304_0506_0708, 42, true);
+        // (payload, its encapsulation header, its body) from a rmw_cyclonedds Client or Server
+        let split = |encapsulation: [u8; 4], body: &[u8], le: bool| {
+            let mut payload = encapsulation.to_vec();
+            payload.extend_from_slice(request_id.with_endianness(le).as_slice());
+            payload.extend_from_slice(body);
+            let mut zenoh_payload = encapsulation.to_vec();
+            zenoh_payload.extend_from_slice(body);
+            (payload, zenoh_payload)
+        };
+
+        // Request { string name; sequence<int32> values; float64 ratio } in XCDR1 little endian:
+        // the float64 is aligned to 8 bytes from the CDR origin, with or without the request header
+        let body: &[u8] = &[
+            4, 0, 0, 0, b'a', b'b', b'c', 0, // name
+            2, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, // values
+            0, 0, 0, 0, // alignment
+            0, 0, 0, 0, 0, 0, 0xf8, 0x3f, // ratio
+        ];
+        // Response { wstring msg; sequence<sequence<uint8>> data } in XCDR1 big endian
+        let body_be: &[u8] = &[
+            0, 0, 0, 2, 0, b'o', 0, b'k', // msg
+            0, 0, 0, 2, 0, 0, 0, 1, 7, 0, 0, 0, 0, 0, 0, 3, 1, 2, 3, // data
+        ];
+        // Request { string name } in XCDR2 little endian, with 1 padding byte at the end
+        let body_xcdr2: &[u8] = &[3, 0, 0, 0, b'a', b'b', 0, 0];
+        for (encapsulation, body, le) in [
+            ([0x00, 0x01, 0x00, 0x00], body, true),
+            ([0x00, 0x00, 0x00, 0x00], body_be, false),
+            ([0x00, 0x11, 0x00, 0x01], body_xcdr2, true),
+            // an empty Request (e.g. std_srvs/srv/Trigger), only containing a uint8 placeholder
+            ([0x00, 0x01, 0x00, 0x00], &[0], true),
+        ] {
+            let (payload, zenoh_payload) = split(encapsulation, body, le);
+            let (header, zbuf) = split_request_header(ZSlice::from(payload.clone())).unwrap();
+            assert!(header == request_id);
+            assert_eq!(header.is_little_endian(), le);
+            assert_eq!(zbuf.contiguous().as_ref(), zenoh_payload.as_slice());
+            // the header is re-inserted with the payload's endianness, whatever the request's one
+            assert_eq!(
+                insert_request_header(&zenoh_payload, &request_id.with_endianness(!le)).unwrap(),
+                payload
+            );
+        }
+
+        // invalid payloads
+        let (payload, zenoh_payload) = split([0x00, 0x01, 0x00, 0x00], body, true);
+        assert!(split_request_header(ZSlice::from(payload[..19].to_vec())).is_err());
+        let mut unknown = payload.clone();
+        unknown[1] = 0x07;
+        assert!(split_request_header(ZSlice::from(unknown)).is_err());
+        let (payload, _) = split([0x00, 0x11, 0x00, 0x03], &[0, 0], true);
+        assert!(split_request_header(ZSlice::from(payload)).is_err());
+        assert!(insert_request_header(&zenoh_payload[..3], &request_id).is_err());
+        assert!(insert_request_header(&[0x01, 0x01, 0x00, 0x00], &request_id).is_err());
+    }
+
+    #[test]
+    fn test_long_call() {
+        use crate::ros2_utils::*;
+        use std::time::Duration;
+
+        let mut attachment = CddsRequestHeader::create(1, 2, true).as_attachment();
+        assert_eq!(long_call_from_attachment(&attachment), None);
+        add_long_call_to_attachment(&mut attachment, Duration::from_secs(300));
+        assert_eq!(
+            long_call_from_attachment(&attachment),
+            Some(Duration::from_secs(300))
+        );
+
+        // the same reply key for the client and the server, whatever their endianness
+        let ke = long_call_reply_key_expr(
+            ke_for_sure!("robot/merge_maps"),
+            &CddsRequestHeader::create(0x0102, 7, true),
+        );
+        assert_eq!(
+            ke.as_str(),
+            "@ros2_reply/robot/merge_maps/00000000000001020000000000000007"
+        );
+        assert_eq!(
+            long_call_reply_key_expr(
+                ke_for_sure!("robot/merge_maps"),
+                &CddsRequestHeader::create(0x0102, 7, false)
+            ),
+            ke
+        );
+    }
+
+    #[test]
+    fn test_timeout_attachment() {
+        use crate::ros2_utils::*;
+        use std::time::Duration;
+
+        let mut attachment = CddsRequestHeader::create(1, 2, true).as_attachment();
+        assert_eq!(timeout_from_attachment(&attachment), None);
+        add_timeout_to_attachment(&mut attachment, Duration::from_millis(1500));
+        assert_eq!(
+            timeout_from_attachment(&attachment),
+            Some(Duration::from_millis(1500))
+        );
+    }
+
+    #[test]
+    fn test_caller_attachment() {
+        use crate::ros2_utils::*;
+
+        let mut attachment = CddsRequestHeader::create(0x0102, 7, false).as_attachment();
+        assert_eq!(caller_from_attachment(&attachment), None);
+        add_caller_to_attachment(&mut attachment, "robot1");
+        assert_eq!(
+            caller_from_attachment(&attachment).as_deref(),
+            Some("robot1:0000000000000102")
+        );
+        // without request header, the caller can't be identified
+        let mut attachment = zenoh::sample::Attachment::new();
+        add_caller_to_attachment(&mut attachment, "robot1");
+        assert_eq!(caller_from_attachment(&attachment), None);
+    }
+
+    #[test]
+    fn test_raw_dds_topics() {
+        use crate::config::LongNamePolicy;
+        use crate::ros2_utils::*;
+
+        assert!(is_ros2_dds_topic("rt/chatter"));
+        assert!(is_ros2_dds_topic("rq/add_two_intsRequest"));
+        assert!(is_ros2_dds_topic("rr/add_two_intsReply"));
+        assert!(is_ros2_dds_topic("ros_discovery_info"));
+        assert!(!is_ros2_dds_topic("SensorRaw"));
+        assert!(!is_ros2_dds_topic("rtSensor"));
+
+        // name used for the route, distinct from any ROS 2 name
+        let name = raw_dds_topic_to_ros2_name("SensorRaw");
+        assert_eq!(name, "@raw/SensorRaw");
+        assert!(check_ros_name(&name).is_err());
+        assert_eq!(ros2_name_to_raw_dds_topic(&name), Some("SensorRaw"));
+        assert_eq!(ros2_name_to_raw_dds_topic("/raw/SensorRaw"), None);
+
+        // key expressions round trip, including with characters invalid in key expressions
+        for topic in ["SensorRaw", "Status/Motor", "/Status", "Status*", "a//b"] {
+            let ke = raw_dds_topic_to_key_expr(topic, &[]);
+            assert!(ke.as_str().starts_with("raw/"));
+            assert_eq!(key_expr_to_raw_dds_topic(&ke, None).as_deref(), Some(topic));
+        }
+        assert_eq!(
+            raw_dds_topic_to_key_expr("SensorRaw", &[]).as_str(),
+            "raw/SensorRaw"
+        );
+        assert_eq!(
+            key_expr_to_raw_dds_topic(ke_for_sure!("rawx/Sensor"), None),
+            None
+        );
+
+        // the DDS topic and type are used verbatim for raw DDS topics, converted otherwise
+        assert_eq!(
+            message_dds_topic_and_type(&name, "SensorModule::SensorRaw", LongNamePolicy::Reject),
+            Ok(("SensorRaw".into(), "SensorModule::SensorRaw".into()))
+        );
+        assert_eq!(
+            message_dds_topic_and_type(
+                "/cmd_vel",
+                "geometry_msgs/msg/Twist",
+                LongNamePolicy::Reject
+            ),
+            Ok((
+                "rt/cmd_vel".into(),
+                "geometry_msgs::msg::dds_::Twist_".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_segment_separators() {
+        // the separators are converted into chunk separators
+        assert_eq!(
+            raw_dds_topic_to_key_expr("plant.line1.speed", &['.']).as_str(),
+            "raw/plant/line1/speed"
+        );
+        // unless it would make an empty chunk
+        assert_eq!(
+            raw_dds_topic_to_key_expr(".a..b.", &['.']).as_str(),
+            "raw/.a./b."
+        );
+        // with separators, a '/' is always escaped, so a name containing both is not ambiguous
+        assert_eq!(
+            raw_dds_topic_to_key_expr("plant/line1.speed", &['.']).as_str(),
+            "raw/plant%2Fline1/speed"
+        );
+        assert_ne!(
+            raw_dds_topic_to_key_expr("plant/line1.speed", &['.']),
+            raw_dds_topic_to_key_expr("plant.line1.speed", &['.'])
+        );
+        assert_eq!(
+            raw_dds_topic_to_key_expr("a::b::c", &[':']).as_str(),
+            "raw/a:/b:/c"
+        );
+
+        // round trip, joining the chunks with the separator
+        for topic in [
+            "plant.line1.speed",
+            "plant/line1.speed",
+            "plant.line1/speed",
+            "/plant.line1/",
+            ".a..b.",
+            "..",
+            "a.*.b",
+            "a.%2F.b",
+            "Status",
+            "é.ü",
+        ] {
+            let ke = raw_dds_topic_to_key_expr(topic, &['.']);
+            assert_eq!(
+                key_expr_to_raw_dds_topic(&ke, Some('.')).as_deref(),
+                Some(topic),
+                "{topic} converted to {ke}"
+            );
+        }
+        // with several separators, the chunks are joined with the one specified for the inverse
+        let ke = raw_dds_topic_to_key_expr("line1-speed", &['.', '-']);
+        assert_eq!(ke.as_str(), "raw/line1/speed");
+        assert_eq!(
+            key_expr_to_raw_dds_topic(&ke, Some('-')).as_deref(),
+            Some("line1-speed")
+        );
+    }
 }
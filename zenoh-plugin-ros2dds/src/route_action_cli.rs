@@ -11,14 +11,15 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use serde::Serialize;
+use serde::{Serialize, Serializer};
 use std::{collections::HashSet, fmt};
 use zenoh::{liveliness::LivelinessToken, prelude::*};
 use zenoh_core::AsyncResolve;
 
 use crate::{
-    liveliness_mgt::new_ke_liveliness_action_cli, ros2_utils::*,
-    route_action_srv::serialize_action_zenoh_key_expr, route_service_cli::RouteServiceCli,
+    liveliness_mgt::new_ke_liveliness_action_cli, node_info::ActionCliEntities, ros2_utils::*,
+    route_action_srv::serialize_action_zenoh_key_expr, route_events::RouteEvent,
+    route_service_cli::RouteServiceCli, route_stats::RouteStatsSnapshot,
     route_subscriber::RouteSubscriber, routes_mgr::Context,
 };
 
@@ -44,7 +45,11 @@ pub struct RouteActionCli<'a> {
     route_cancel_goal: RouteServiceCli<'a>,
     #[serde(skip)]
     route_get_result: RouteServiceCli<'a>,
-    #[serde(skip)]
+    // the rate limiting of the feedback (if configured in `feedback_max_frequency`)
+    #[serde(
+        rename = "feedback_throttle",
+        serialize_with = "serialize_feedback_throttle"
+    )]
     route_feedback: RouteSubscriber<'a>,
     #[serde(skip)]
     route_status: RouteSubscriber<'a>,
@@ -189,6 +194,23 @@ impl RouteActionCli<'_> {
         self.liveliness_token = None;
     }
 
+    /// Re-assert the announcements over Zenoh of this route and of its sub-routes
+    /// (e.g. after a reconnection), if announced.
+    pub async fn restore_announcement(&mut self) -> Result<(), String> {
+        let (r1, r2, r3, r4, r5) = futures::join!(
+            self.route_send_goal.restore_announcement(),
+            self.route_cancel_goal.restore_announcement(),
+            self.route_get_result.restore_announcement(),
+            self.route_feedback.restore_announcement(),
+            self.route_status.restore_announcement(),
+        );
+        r1.and(r2).and(r3).and(r4).and(r5)?;
+        if self.is_active {
+            self.announce_route().await?;
+        }
+        Ok(())
+    }
+
     #[inline]
     pub fn add_remote_route(&mut self, plugin_id: &str, zenoh_key_expr_prefix: &keyexpr) {
         self.route_send_goal.add_remote_route(
@@ -266,10 +288,13 @@ impl RouteActionCli<'_> {
     }
 
     #[inline]
-    pub fn remove_local_node(&mut self, node: &str) {
-        self.route_send_goal.remove_local_node(node);
-        self.route_cancel_goal.remove_local_node(node);
-        self.route_get_result.remove_local_node(node);
+    pub fn remove_local_node(&mut self, node: &str, entities: &ActionCliEntities) {
+        self.route_send_goal
+            .remove_local_node(node, &entities.send_goal);
+        self.route_cancel_goal
+            .remove_local_node(node, &entities.cancel_goal);
+        self.route_get_result
+            .remove_local_node(node, &entities.get_result);
         self.route_feedback.remove_local_node(node);
         self.route_status.remove_local_node(node);
 
@@ -281,6 +306,11 @@ impl RouteActionCli<'_> {
         }
     }
 
+    #[inline]
+    pub fn is_serving_local_node(&self) -> bool {
+        !self.local_nodes.is_empty()
+    }
+
     pub fn is_unused(&self) -> bool {
         self.route_send_goal.is_unused()
             && self.route_cancel_goal.is_unused()
@@ -288,4 +318,40 @@ impl RouteActionCli<'_> {
             && self.route_status.is_unused()
             && self.route_feedback.is_unused()
     }
+
+    /// The statistics of the Action, aggregated from its Services and topics routes
+    pub fn stats(&self) -> RouteStatsSnapshot {
+        [
+            self.route_send_goal.stats(),
+            self.route_cancel_goal.stats(),
+            self.route_get_result.stats(),
+            self.route_status.stats(),
+            self.route_feedback.stats(),
+        ]
+        .into_iter()
+        .fold(RouteStatsSnapshot::default(), RouteStatsSnapshot::merge)
+    }
+
+    /// The latest events of the Action, merged from its Services and topics routes in chronological order
+    pub fn events(&self) -> Vec<RouteEvent> {
+        let mut events: Vec<RouteEvent> = [
+            self.route_send_goal.events(),
+            self.route_cancel_goal.events(),
+            self.route_get_result.events(),
+            self.route_status.events(),
+            self.route_feedback.events(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        events.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+        events
+    }
+}
+
+fn serialize_feedback_throttle<S>(route_feedback: &RouteSubscriber, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    route_feedback.serialize_rate_limiter(s)
 }
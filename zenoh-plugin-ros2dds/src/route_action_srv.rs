@@ -17,8 +17,9 @@ use zenoh::{liveliness::LivelinessToken, prelude::*};
 use zenoh_core::AsyncResolve;
 
 use crate::{
-    liveliness_mgt::new_ke_liveliness_action_srv, ros2_utils::*, route_publisher::RoutePublisher,
-    route_service_srv::RouteServiceSrv, routes_mgr::Context,
+    liveliness_mgt::new_ke_liveliness_action_srv, node_info::ActionSrvEntities, ros2_utils::*,
+    route_events::RouteEvent, route_publisher::RoutePublisher, route_service_srv::RouteServiceSrv,
+    route_stats::RouteStatsSnapshot, routes_mgr::Context,
 };
 
 #[derive(Serialize)]
@@ -36,6 +37,9 @@ pub struct RouteActionSrv<'a> {
     // the context
     #[serde(skip)]
     context: Context,
+    // the Zenoh Priority of the replies to the goal, cancel and result long calls (see `RouteServiceSrv`)
+    #[serde(serialize_with = "crate::serialize_priority")]
+    priority: Priority,
     is_active: bool,
     #[serde(skip)]
     route_send_goal: RouteServiceSrv<'a>,
@@ -43,7 +47,11 @@ pub struct RouteActionSrv<'a> {
     route_cancel_goal: RouteServiceSrv<'a>,
     #[serde(skip)]
     route_get_result: RouteServiceSrv<'a>,
-    #[serde(skip)]
+    // the rate limiting of the feedback (if configured in `feedback_max_frequency`)
+    #[serde(
+        rename = "feedback_throttle",
+        serialize_with = "serialize_feedback_throttle"
+    )]
     route_feedback: RoutePublisher<'a>,
     #[serde(skip)]
     route_status: RoutePublisher<'a>,
@@ -74,29 +82,52 @@ impl RouteActionSrv<'_> {
         zenoh_key_expr_prefix: OwnedKeyExpr,
         context: Context,
     ) -> Result<RouteActionSrv<'a>, String> {
+        // configured priority for the replies to goal, cancel and result long calls
+        let priority = context
+            .config
+            .get_pub_priorities(&ros2_name)
+            .unwrap_or_default();
+        // configured queries timeout for calls to send_goal service (the queries without reply expire after it)
+        let send_goal_queries_timeout = context
+            .config
+            .get_queries_timeout_action_send_goal(&ros2_name);
         let route_send_goal = RouteServiceSrv::create(
             format!("{ros2_name}/{}", *KE_SUFFIX_ACTION_SEND_GOAL),
             format!("{ros2_type}_SendGoal"),
             &zenoh_key_expr_prefix / *KE_SUFFIX_ACTION_SEND_GOAL,
             &None,
+            send_goal_queries_timeout,
+            priority,
             context.clone(),
         )
         .await?;
 
+        // configured queries timeout for calls to cancel_goal service (the queries without reply expire after it)
+        let cancel_goal_queries_timeout = context
+            .config
+            .get_queries_timeout_action_cancel_goal(&ros2_name);
         let route_cancel_goal = RouteServiceSrv::create(
             format!("{ros2_name}/{}", *KE_SUFFIX_ACTION_CANCEL_GOAL),
             ROS2_ACTION_CANCEL_GOAL_SRV_TYPE.to_string(),
             &zenoh_key_expr_prefix / *KE_SUFFIX_ACTION_CANCEL_GOAL,
             &None,
+            cancel_goal_queries_timeout,
+            priority,
             context.clone(),
         )
         .await?;
 
+        // configured queries timeout for calls to get_result service (the queries without reply expire after it)
+        let get_result_queries_timeout = context
+            .config
+            .get_queries_timeout_action_get_result(&ros2_name);
         let route_get_result = RouteServiceSrv::create(
             format!("{ros2_name}/{}", *KE_SUFFIX_ACTION_GET_RESULT),
             format!("{ros2_type}_GetResult"),
             &zenoh_key_expr_prefix / *KE_SUFFIX_ACTION_GET_RESULT,
             &None,
+            get_result_queries_timeout,
+            priority,
             context.clone(),
         )
         .await?;
@@ -128,6 +159,7 @@ impl RouteActionSrv<'_> {
             ros2_type,
             zenoh_key_expr_prefix,
             context,
+            priority,
             is_active: false,
             route_send_goal,
             route_cancel_goal,
@@ -175,6 +207,23 @@ impl RouteActionSrv<'_> {
         self.liveliness_token = None;
     }
 
+    /// Re-assert the announcements over Zenoh of this route and of its sub-routes
+    /// (e.g. after a reconnection), if announced.
+    pub async fn restore_announcement(&mut self) -> Result<(), String> {
+        let (r1, r2, r3, r4, r5) = futures::join!(
+            self.route_send_goal.restore_announcement(),
+            self.route_cancel_goal.restore_announcement(),
+            self.route_get_result.restore_announcement(),
+            self.route_feedback.restore_announcement(),
+            self.route_status.restore_announcement(),
+        );
+        r1.and(r2).and(r3).and(r4).and(r5)?;
+        if self.is_active {
+            self.announce_route().await?;
+        }
+        Ok(())
+    }
+
     #[inline]
     pub fn add_remote_route(&mut self, plugin_id: &str, zenoh_key_expr_prefix: &keyexpr) {
         self.route_send_goal.add_remote_route(
@@ -192,10 +241,12 @@ impl RouteActionSrv<'_> {
         self.route_feedback.add_remote_route(
             plugin_id,
             &(zenoh_key_expr_prefix / *KE_SUFFIX_ACTION_FEEDBACK),
+            None,
         );
         self.route_status.add_remote_route(
             plugin_id,
             &(zenoh_key_expr_prefix / *KE_SUFFIX_ACTION_STATUS),
+            None,
         );
         self.remote_routes
             .insert(format!("{plugin_id}:{zenoh_key_expr_prefix}"));
@@ -230,11 +281,14 @@ impl RouteActionSrv<'_> {
     }
 
     #[inline]
-    pub async fn add_local_node(&mut self, node: String) {
+    pub async fn add_local_node(&mut self, node: String, entities: &ActionSrvEntities) {
         futures::join!(
-            self.route_send_goal.add_local_node(node.clone()),
-            self.route_cancel_goal.add_local_node(node.clone()),
-            self.route_get_result.add_local_node(node.clone()),
+            self.route_send_goal
+                .add_local_node(node.clone(), entities.send_goal),
+            self.route_cancel_goal
+                .add_local_node(node.clone(), entities.cancel_goal),
+            self.route_get_result
+                .add_local_node(node.clone(), entities.get_result),
             self.route_feedback
                 .add_local_node(node.clone(), &QOS_DEFAULT_ACTION_FEEDBACK),
             self.route_status
@@ -267,6 +321,11 @@ impl RouteActionSrv<'_> {
         }
     }
 
+    #[inline]
+    pub fn is_serving_local_node(&self) -> bool {
+        !self.local_nodes.is_empty()
+    }
+
     pub fn is_unused(&self) -> bool {
         self.route_send_goal.is_unused()
             && self.route_cancel_goal.is_unused()
@@ -274,6 +333,42 @@ impl RouteActionSrv<'_> {
             && self.route_status.is_unused()
             && self.route_feedback.is_unused()
     }
+
+    /// The statistics of the Action, aggregated from its Services and topics routes
+    pub fn stats(&self) -> RouteStatsSnapshot {
+        [
+            self.route_send_goal.stats(),
+            self.route_cancel_goal.stats(),
+            self.route_get_result.stats(),
+            self.route_status.stats(),
+            self.route_feedback.stats(),
+        ]
+        .into_iter()
+        .fold(RouteStatsSnapshot::default(), RouteStatsSnapshot::merge)
+    }
+
+    /// The latest events of the Action, merged from its Services and topics routes in chronological order
+    pub fn events(&self) -> Vec<RouteEvent> {
+        let mut events: Vec<RouteEvent> = [
+            self.route_send_goal.events(),
+            self.route_cancel_goal.events(),
+            self.route_get_result.events(),
+            self.route_status.events(),
+            self.route_feedback.events(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        events.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+        events
+    }
+}
+
+fn serialize_feedback_throttle<S>(route_feedback: &RoutePublisher, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    route_feedback.serialize_rate_limiter(s)
 }
 
 pub fn serialize_action_zenoh_key_expr<S>(
@@ -0,0 +1,146 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use zenoh_core::zlock;
+
+// The count of the latest events kept per route
+pub const ROUTE_EVENTS_CAPACITY: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteEventKind {
+    Created,
+    QosAdapted,
+    DdsError,
+    Dropped,
+    Paused,
+    Resumed,
+    CallFailed,
+    StatsReset,
+    PendingFlushed,
+}
+
+/// The kind of the event of a route paused (or resumed)
+pub fn pause_event_kind(paused: bool) -> RouteEventKind {
+    if paused {
+        RouteEventKind::Paused
+    } else {
+        RouteEventKind::Resumed
+    }
+}
+
+/// An event of a route, as reported in admin space (with the "events" selector parameter).
+/// The same event repeated in a row is reported once, with its `count` and the `timestamp` of its last occurrence
+/// (in seconds since UNIX_EPOCH).
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RouteEvent {
+    pub timestamp: f64,
+    pub kind: RouteEventKind,
+    pub message: String,
+    pub count: u64,
+}
+
+/// The ring buffer of the latest events of a route (e.g. DDS errors, drops), to troubleshoot it without
+/// searching the interleaved logs. The events being rare (or repeated, and then coalesced),
+/// a Mutex is held only on their recording.
+#[derive(Default)]
+pub struct RouteEvents {
+    events: Mutex<VecDeque<RouteEvent>>,
+}
+
+impl RouteEvents {
+    pub fn record(&self, kind: RouteEventKind, message: String) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |d| d.as_secs_f64());
+        let mut events = zlock!(self.events);
+        if let Some(last) = events.back_mut() {
+            if last.kind == kind && last.message == message {
+                last.count += 1;
+                last.timestamp = timestamp;
+                return;
+            }
+        }
+        if events.len() >= ROUTE_EVENTS_CAPACITY {
+            events.pop_front();
+        }
+        events.push_back(RouteEvent {
+            timestamp,
+            kind,
+            message,
+            count: 1,
+        });
+    }
+
+    /// The events, from the oldest to the latest
+    pub fn snapshot(&self) -> Vec<RouteEvent> {
+        zlock!(self.events).iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RouteEventKind::*, RouteEvents, ROUTE_EVENTS_CAPACITY};
+
+    #[test]
+    fn test_route_events() {
+        let events = RouteEvents::default();
+        assert!(events.snapshot().is_empty());
+        events.record(Created, "DDS Writer created".into());
+        events.record(
+            Dropped,
+            "payload of 2000 bytes exceeding max_payload_size".into(),
+        );
+        events.record(
+            Dropped,
+            "payload of 2000 bytes exceeding max_payload_size".into(),
+        );
+        events.record(Paused, "paused via admin space".into());
+        events.record(DdsError, "failed to write: DDS_RETCODE_ERROR".into());
+
+        let snapshot = events.snapshot();
+        assert_eq!(
+            snapshot.iter().map(|e| e.kind).collect::<Vec<_>>(),
+            vec![Created, Dropped, Paused, DdsError]
+        );
+        assert_eq!(snapshot[1].count, 2);
+        assert!(snapshot
+            .windows(2)
+            .all(|w| w[0].timestamp <= w[1].timestamp));
+
+        let json = serde_json::to_value(&snapshot[3]).unwrap();
+        assert_eq!(json["kind"], "dds_error");
+        assert_eq!(json["message"], "failed to write: DDS_RETCODE_ERROR");
+        assert_eq!(json["count"], 1);
+    }
+
+    #[test]
+    fn test_route_events_bounded() {
+        let events = RouteEvents::default();
+        for i in 0..ROUTE_EVENTS_CAPACITY + 10 {
+            events.record(QosAdapted, format!("QoS adaptation #{i}"));
+        }
+        let snapshot = events.snapshot();
+        assert_eq!(snapshot.len(), ROUTE_EVENTS_CAPACITY);
+        // the oldest events were evicted
+        assert_eq!(snapshot[0].message, "QoS adaptation #10");
+        assert_eq!(
+            snapshot[ROUTE_EVENTS_CAPACITY - 1].message,
+            format!("QoS adaptation #{}", ROUTE_EVENTS_CAPACITY + 9)
+        );
+    }
+}
@@ -0,0 +1,225 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::forward_discovery::RouteKind;
+
+// The bounds of the period between 2 checks of the idle routes
+const MIN_CHECK_PERIOD: Duration = Duration::from_millis(100);
+const MAX_CHECK_PERIOD: Duration = Duration::from_secs(10);
+
+/// A route to be checked for idleness: it serves no local Node, and has no traffic since `inactivity`
+pub struct IdleCandidate {
+    pub kind: RouteKind,
+    pub ros2_name: String,
+    pub timeout: Duration,
+    pub inactivity: Duration,
+}
+
+/// The bookkeeping of the `route_idle_timeout`: a route serving no local Node is torn down once it
+/// stayed so, without traffic, during the timeout of its kind. The announcements of the remote routes (`A`)
+/// are kept, to be replayed when a torn down route is re-created by a discovery or another announcement.
+pub struct RouteIdle<A> {
+    check_period: Duration,
+    next_check: Instant,
+    // the routes serving no local Node, with the time since when they do so, indexed by (route kind, ROS name)
+    unattended: HashMap<(RouteKind, String), Instant>,
+    // the announcement of each remote route, indexed by (route kind, ROS name) and remote route
+    announcements: HashMap<(RouteKind, String), HashMap<String, A>>,
+    // the routes torn down because idle
+    torn_down: HashSet<(RouteKind, String)>,
+}
+
+impl<A: Clone> RouteIdle<A> {
+    /// A new bookkeeping, checking the routes at a fraction of the shortest timeout
+    pub fn new(min_timeout: Duration, now: Instant) -> RouteIdle<A> {
+        let check_period = (min_timeout / 4).clamp(MIN_CHECK_PERIOD, MAX_CHECK_PERIOD);
+        RouteIdle {
+            check_period,
+            next_check: now + check_period,
+            unattended: HashMap::new(),
+            announcements: HashMap::new(),
+            torn_down: HashSet::new(),
+        }
+    }
+
+    /// The time of the next check of the idle routes
+    pub fn next_check(&self) -> Instant {
+        self.next_check
+    }
+
+    /// Record the announcement of a remote route
+    pub fn on_announced(&mut self, kind: RouteKind, ros2_name: &str, remote_id: &str, event: A) {
+        self.announcements
+            .entry((kind, ros2_name.to_string()))
+            .or_default()
+            .insert(remote_id.to_string(), event);
+    }
+
+    /// Forget the announcement of a retired remote route
+    pub fn on_retired(&mut self, kind: RouteKind, ros2_name: &str, remote_id: &str) {
+        let key = (kind, ros2_name.to_string());
+        if let Some(routes) = self.announcements.get_mut(&key) {
+            routes.remove(remote_id);
+            if routes.is_empty() {
+                self.announcements.remove(&key);
+                self.torn_down.remove(&key);
+            }
+        }
+    }
+
+    /// Check the routes serving no local Node (the other ones are no longer tracked), and return
+    /// the ones which are idle for their timeout, as (route kind, ROS name). Those must be torn down.
+    pub fn take_idle(
+        &mut self,
+        candidates: Vec<IdleCandidate>,
+        now: Instant,
+    ) -> Vec<(RouteKind, String)> {
+        self.next_check = now + self.check_period;
+        let mut unattended = HashMap::with_capacity(candidates.len());
+        let mut idle = Vec::new();
+        for c in candidates {
+            let key = (c.kind, c.ros2_name);
+            let since = self.unattended.get(&key).copied().unwrap_or(now);
+            if now.saturating_duration_since(since) >= c.timeout && c.inactivity >= c.timeout {
+                self.torn_down.insert(key.clone());
+                idle.push(key);
+            } else {
+                unattended.insert(key, since);
+            }
+        }
+        self.unattended = unattended;
+        idle
+    }
+
+    /// If the route was torn down because idle, the announcements of the remote routes
+    /// it served, to be replayed on its re-creation
+    pub fn take_torn_down(&mut self, kind: RouteKind, ros2_name: &str) -> Vec<A> {
+        let key = (kind, ros2_name.to_string());
+        if self.torn_down.remove(&key) {
+            self.announcements
+                .get(&key)
+                .map(|m| m.values().cloned().collect())
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IdleCandidate, RouteIdle};
+    use crate::forward_discovery::RouteKind::{self, *};
+    use std::time::{Duration, Instant};
+
+    const TIMEOUT: Duration = Duration::from_secs(30);
+
+    fn candidate(kind: RouteKind, ros2_name: &str, inactivity: Duration) -> IdleCandidate {
+        IdleCandidate {
+            kind,
+            ros2_name: ros2_name.into(),
+            timeout: TIMEOUT,
+            inactivity,
+        }
+    }
+
+    #[test]
+    fn test_discover_undiscover_idle_expiry() {
+        let t0 = Instant::now();
+        let mut idle: RouteIdle<&str> = RouteIdle::new(TIMEOUT, t0);
+        assert_eq!(idle.next_check(), t0 + TIMEOUT / 4);
+
+        // a remote rviz subscribes to /scan, while a local Node publishes it
+        idle.on_announced(Publisher, "/scan", "bridge2:scan", "rviz");
+        // the local Node is undiscovered: the route serves no more local Node
+        assert!(idle
+            .take_idle(vec![candidate(Publisher, "/scan", TIMEOUT)], t0)
+            .is_empty());
+        assert!(idle
+            .take_idle(
+                vec![candidate(Publisher, "/scan", TIMEOUT)],
+                t0 + TIMEOUT / 2
+            )
+            .is_empty());
+        // the timeout expires: the route is torn down
+        assert_eq!(
+            idle.take_idle(vec![candidate(Publisher, "/scan", TIMEOUT)], t0 + TIMEOUT),
+            vec![(Publisher, "/scan".to_string())]
+        );
+        assert_eq!(idle.next_check(), t0 + TIMEOUT + TIMEOUT / 4);
+
+        // the local Node is re-discovered: the route is re-created, with the remote route it served
+        assert_eq!(idle.take_torn_down(Publisher, "/scan"), vec!["rviz"]);
+        assert!(idle.take_torn_down(Publisher, "/scan").is_empty());
+    }
+
+    #[test]
+    fn test_traffic_or_local_node_keeps_route() {
+        let t0 = Instant::now();
+        let mut idle: RouteIdle<&str> = RouteIdle::new(TIMEOUT, t0);
+        idle.take_idle(vec![candidate(Subscriber, "/cmd_vel", TIMEOUT)], t0);
+        // recent traffic
+        assert!(idle
+            .take_idle(
+                vec![candidate(Subscriber, "/cmd_vel", Duration::from_secs(1))],
+                t0 + TIMEOUT
+            )
+            .is_empty());
+        // a local Node is discovered (the route is no longer a candidate), then undiscovered
+        idle.take_idle(vec![], t0 + TIMEOUT);
+        assert!(idle
+            .take_idle(
+                vec![candidate(Subscriber, "/cmd_vel", TIMEOUT * 2)],
+                t0 + TIMEOUT * 3 / 2
+            )
+            .is_empty());
+        assert_eq!(
+            idle.take_idle(
+                vec![candidate(Subscriber, "/cmd_vel", TIMEOUT * 3)],
+                t0 + TIMEOUT * 5 / 2
+            ),
+            vec![(Subscriber, "/cmd_vel".to_string())]
+        );
+        // no remote route to replay
+        assert!(idle.take_torn_down(Subscriber, "/cmd_vel").is_empty());
+    }
+
+    #[test]
+    fn test_retired_announcements_not_replayed() {
+        let t0 = Instant::now();
+        let mut idle: RouteIdle<&str> = RouteIdle::new(Duration::from_secs(1000), t0);
+        assert_eq!(idle.next_check(), t0 + Duration::from_secs(10));
+
+        idle.on_announced(ServiceSrv, "/add_two_ints", "bridge2:add_two_ints", "cli2");
+        idle.on_announced(ServiceSrv, "/add_two_ints", "bridge3:add_two_ints", "cli3");
+        let c = || IdleCandidate {
+            kind: ServiceSrv,
+            ros2_name: "/add_two_ints".into(),
+            timeout: Duration::from_secs(1000),
+            inactivity: Duration::MAX,
+        };
+        idle.take_idle(vec![c()], t0);
+        assert_eq!(
+            idle.take_idle(vec![c()], t0 + Duration::from_secs(1000)),
+            vec![(ServiceSrv, "/add_two_ints".to_string())]
+        );
+        idle.on_retired(ServiceSrv, "/add_two_ints", "bridge2:add_two_ints");
+        assert_eq!(
+            idle.take_torn_down(ServiceSrv, "/add_two_ints"),
+            vec!["cli3"]
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::forward_discovery::RouteKind;
+
+/// The bookkeeping of the `route_linger` delay: when the last local Node of a Publisher or
+/// Subscriber route is undiscovered, it's kept as lingering in the route until the delay expires.
+/// If the interface is re-discovered meanwhile with the same QoS (`Q`), the route is reused as is.
+pub struct RouteLinger<Q> {
+    linger: Duration,
+    // the QoS of each discovered local interface, indexed by (route kind, ROS name, Node)
+    discovered: HashMap<(RouteKind, String, String), Q>,
+    // the lingering Nodes, with their QoS and their expiry time, indexed by (route kind, ROS name)
+    lingering: HashMap<(RouteKind, String), (String, Q, Instant)>,
+}
+
+/// The outcome of the re-discovery of an interface
+#[derive(Debug, PartialEq, Eq)]
+pub enum Rediscovery {
+    // the interface was not lingering
+    NotLingering,
+    // the interface was lingering with the same QoS: the lingering Node can be replaced in the route
+    Reuse(String),
+    // the interface was lingering with another QoS: the lingering Node must be removed from the route first
+    QosChanged(String),
+}
+
+impl<Q: PartialEq> RouteLinger<Q> {
+    pub fn new(linger: Duration) -> RouteLinger<Q> {
+        RouteLinger {
+            linger,
+            discovered: HashMap::new(),
+            lingering: HashMap::new(),
+        }
+    }
+
+    /// Record a discovered local interface with its QoS, and return if it was lingering
+    pub fn on_discovered(
+        &mut self,
+        kind: RouteKind,
+        ros2_name: &str,
+        node: &str,
+        qos: Q,
+    ) -> Rediscovery {
+        let outcome = match self.lingering.remove(&(kind, ros2_name.to_string())) {
+            Some((lingering_node, lingering_qos, _)) if lingering_qos == qos => {
+                Rediscovery::Reuse(lingering_node)
+            }
+            Some((lingering_node, _, _)) => Rediscovery::QosChanged(lingering_node),
+            None => Rediscovery::NotLingering,
+        };
+        self.discovered
+            .insert((kind, ros2_name.to_string(), node.to_string()), qos);
+        outcome
+    }
+
+    /// Forget an undiscovered local interface. If it was the last local Node of its route
+    /// (`is_last_node`), it becomes lingering and true is returned: its removal from the route
+    /// must be deferred until it expires.
+    pub fn on_undiscovered(
+        &mut self,
+        kind: RouteKind,
+        ros2_name: &str,
+        node: &str,
+        is_last_node: bool,
+        now: Instant,
+    ) -> bool {
+        match self
+            .discovered
+            .remove(&(kind, ros2_name.to_string(), node.to_string()))
+        {
+            Some(qos) if is_last_node && !self.linger.is_zero() => {
+                self.lingering.insert(
+                    (kind, ros2_name.to_string()),
+                    (node.to_string(), qos, now + self.linger),
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Remove and return the expired lingering interfaces, as (route kind, ROS name, Node)
+    pub fn take_expired(&mut self, now: Instant) -> Vec<(RouteKind, String, String)> {
+        let expired: Vec<(RouteKind, String)> = self
+            .lingering
+            .iter()
+            .filter(|(_, (_, _, expiry))| *expiry <= now)
+            .map(|(k, _)| k.clone())
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|k| {
+                self.lingering
+                    .remove(&k)
+                    .map(|(node, _, _)| (k.0, k.1, node))
+            })
+            .collect()
+    }
+
+    /// The time of the next expiry of a lingering interface (if any)
+    pub fn next_expiry(&self) -> Option<Instant> {
+        self.lingering.values().map(|(_, _, expiry)| *expiry).min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rediscovery, RouteLinger};
+    use crate::forward_discovery::RouteKind::*;
+    use std::time::{Duration, Instant};
+
+    const LINGER: Duration = Duration::from_secs(2);
+
+    #[test]
+    fn test_rapid_rediscovery_reuses_route() {
+        let mut linger = RouteLinger::new(LINGER);
+        let t0 = Instant::now();
+        assert_eq!(
+            linger.on_discovered(Publisher, "/chatter", "/talker", "reliable"),
+            Rediscovery::NotLingering
+        );
+        // the talker is restarted by a supervisor
+        assert!(linger.on_undiscovered(Publisher, "/chatter", "/talker", true, t0));
+        assert_eq!(linger.next_expiry(), Some(t0 + LINGER));
+        assert_eq!(
+            linger.on_discovered(Publisher, "/chatter", "/talker", "reliable"),
+            Rediscovery::Reuse("/talker".into())
+        );
+        assert_eq!(linger.next_expiry(), None);
+        assert!(linger.take_expired(t0 + LINGER * 2).is_empty());
+
+        // restarted with another Node name
+        assert!(linger.on_undiscovered(Publisher, "/chatter", "/talker", true, t0));
+        assert_eq!(
+            linger.on_discovered(Publisher, "/chatter", "/talker_2", "reliable"),
+            Rediscovery::Reuse("/talker".into())
+        );
+    }
+
+    #[test]
+    fn test_rediscovery_with_other_qos() {
+        let mut linger = RouteLinger::new(LINGER);
+        let t0 = Instant::now();
+        linger.on_discovered(Subscriber, "/map", "/listener", "volatile");
+        assert!(linger.on_undiscovered(Subscriber, "/map", "/listener", true, t0));
+        assert_eq!(
+            linger.on_discovered(Subscriber, "/map", "/listener", "transient_local"),
+            Rediscovery::QosChanged("/listener".into())
+        );
+        assert_eq!(linger.next_expiry(), None);
+    }
+
+    #[test]
+    fn test_expiry_removes_route() {
+        let mut linger = RouteLinger::new(LINGER);
+        let t0 = Instant::now();
+        linger.on_discovered(Publisher, "/chatter", "/talker", "reliable");
+        linger.on_discovered(Subscriber, "/chatter", "/listener", "reliable");
+        assert!(linger.on_undiscovered(Publisher, "/chatter", "/talker", true, t0));
+        assert!(linger.on_undiscovered(
+            Subscriber,
+            "/chatter",
+            "/listener",
+            true,
+            t0 + Duration::from_secs(1)
+        ));
+
+        assert!(linger
+            .take_expired(t0 + Duration::from_millis(1999))
+            .is_empty());
+        assert_eq!(
+            linger.take_expired(t0 + LINGER),
+            vec![(Publisher, "/chatter".to_string(), "/talker".to_string())]
+        );
+        assert_eq!(
+            linger.next_expiry(),
+            Some(t0 + Duration::from_secs(1) + LINGER)
+        );
+        assert_eq!(
+            linger.take_expired(t0 + LINGER * 2),
+            vec![(Subscriber, "/chatter".to_string(), "/listener".to_string())]
+        );
+        // a re-discovery after expiry creates a new route
+        assert_eq!(
+            linger.on_discovered(Publisher, "/chatter", "/talker", "reliable"),
+            Rediscovery::NotLingering
+        );
+    }
+
+    #[test]
+    fn test_no_linger() {
+        let mut linger = RouteLinger::new(LINGER);
+        let t0 = Instant::now();
+        // not the last Node of the route
+        linger.on_discovered(Publisher, "/chatter", "/talker", "reliable");
+        assert!(!linger.on_undiscovered(Publisher, "/chatter", "/talker", false, t0));
+        // unknown interface
+        assert!(!linger.on_undiscovered(Publisher, "/chatter", "/talker", true, t0));
+        // linger disabled
+        let mut linger = RouteLinger::new(Duration::ZERO);
+        linger.on_discovered(Publisher, "/chatter", "/talker", "reliable");
+        assert!(!linger.on_undiscovered(Publisher, "/chatter", "/talker", true, t0));
+        assert_eq!(linger.next_expiry(), None);
+    }
+}
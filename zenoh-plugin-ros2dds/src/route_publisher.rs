@@ -13,36 +13,66 @@
 //
 
 use cyclors::qos::{HistoryKind, Qos};
-use cyclors::DDS_LENGTH_UNLIMITED;
+use cyclors::{dds_entity_t, DDS_LENGTH_UNLIMITED};
 use serde::{Serialize, Serializer};
 use std::ops::Deref;
-use std::sync::atomic::Ordering;
-use std::sync::Arc;
-use std::time::Duration;
-use std::{collections::HashSet, fmt};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+};
+use zenoh::buffers::ZBuf;
 use zenoh::liveliness::LivelinessToken;
 use zenoh::prelude::r#async::AsyncResolve;
 use zenoh::prelude::*;
 use zenoh::publication::Publisher;
+use zenoh::sample::Attachment;
+use zenoh::Session;
 use zenoh_core::SyncResolve;
-use zenoh_ext::{PublicationCache, SessionExt};
 
-use crate::dds_types::{DDSRawSample, TypeInfo};
+use crate::action_feedback::{
+    feedback_goal_id, is_action_feedback_topic, is_action_status_topic, FeedbackThrottle,
+};
+use crate::bandwidth_cap::{serialize_route_bandwidth, RouteBandwidth};
+use crate::batcher::{serialize_batcher, Batcher};
+use crate::cdr_check::{serialize_cdr_check, CdrCheck};
+use crate::compression::compress_zbuf;
+use crate::config::{Compression, CongestionControlOverride, ReliabilityOverride};
+use crate::dds_types::{DDSInstanceState, DDSRawSample, TypeInfo};
 use crate::dds_utils::{
-    create_dds_reader, delete_dds_entity, get_guid, serialize_atomic_entity_guid, AtomicDDSEntity,
-    DDS_ENTITY_NULL,
+    create_dds_reader, delete_dds_entity, get_guid, get_matched_publication_guid,
+    serialize_atomic_entity_guid, AtomicDDSEntity, DDS_ENTITY_NULL,
 };
+use crate::dedup::{Origin, OriginTagger};
+use crate::downsampling::{serialize_downsampling, Downsampling};
+use crate::forward_discovery::RouteKind;
 use crate::liveliness_mgt::new_ke_liveliness_pub;
-use crate::ros2_utils::{is_message_for_action, ros2_message_type_to_dds_type};
+use crate::payload_limit::{serialize_payload_limit, PayloadSizeLimit};
+use crate::rate_limiter::{serialize_rate_limiter, RateLimiter};
+use crate::ros2_utils::{
+    add_origin_to_attachment, add_sequence_number_to_attachment, instance_key_expr,
+    instance_keyhash_as_attachment, instance_state_and_keyhash_as_attachment,
+    instance_state_as_attachment, is_message_for_action, message_dds_topic_and_type,
+    ros2_name_to_raw_dds_topic, route_to_key_expr, typed_key_expr,
+};
 use crate::ros_discovery::RosDiscoveryInfoMgr;
+use crate::route_events::{pause_event_kind, RouteEvent, RouteEventKind};
+use crate::route_stats::{RouteStats, RouteStatsSnapshot};
 use crate::routes_mgr::Context;
+use crate::sample_transform::{serialize_route_transform, RouteTransform};
+use crate::sequence_gaps::Sequencer;
+use crate::transient_local_cache::TransientLocalCache;
 use crate::{qos_helpers::*, Config};
-use crate::{KE_PREFIX_PUB_CACHE, LOG_PAYLOAD};
+use crate::{
+    serialize_atomic_bool, serialize_option_as_bool, serialize_priority, KE_ANY_1_SEGMENT,
+    LOG_PAYLOAD,
+};
 
 pub struct ZPublisher {
     publisher: Arc<Publisher<'static>>,
     _matching_listener: zenoh::publication::MatchingListener<'static, ()>,
-    _cache: Option<PublicationCache<'static>>,
     cache_size: usize,
 }
 
@@ -54,6 +84,15 @@ impl Deref for ZPublisher {
     }
 }
 
+// In "instance_key_exprs" mode: the publication of each instance of a keyed topic on its own key expression
+// (the Zenoh Publisher being then declared on "<key_expr>/*", only to detect the matching Subscribers)
+struct InstancesPublication {
+    zsession: Arc<Session>,
+    key_expr: OwnedKeyExpr,
+    congestion_ctrl: CongestionControl,
+    priority: Priority,
+}
+
 // a route from DDS to Zenoh
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Serialize)]
@@ -64,6 +103,22 @@ pub struct RoutePublisher<'a> {
     ros2_type: String,
     // the Zenoh key expression used for routing
     zenoh_key_expr: OwnedKeyExpr,
+    // in "typed_key_exprs" mode: the Zenoh key expression with the type chunk, used for publications
+    #[serde(skip_serializing_if = "Option::is_none")]
+    typed_key_expr: Option<OwnedKeyExpr>,
+    // if configured in `route_to`: the key expression scoped to the bridges the topic is exclusively routed to,
+    // used for publications
+    #[serde(skip_serializing_if = "Option::is_none")]
+    route_to_key_expr: Option<OwnedKeyExpr>,
+    // in "instance_key_exprs" mode: the publication of each instance on its own key expression
+    #[serde(
+        rename = "instance_key_exprs",
+        serialize_with = "serialize_option_as_bool"
+    )]
+    instances: Option<Arc<InstancesPublication>>,
+    // for a raw DDS topic (configured in "raw_dds_topics"): its DDS topic name, used verbatim
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw_dds_topic: Option<String>,
     // the context
     #[serde(skip)]
     context: Context,
@@ -74,12 +129,71 @@ pub struct RoutePublisher<'a> {
         serialize_with = "serialize_pub_cache"
     )]
     zenoh_publisher: ZPublisher,
+    // the cache of publications for TRANSIENT_LOCAL topics, replying to queries for historical data
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_transient_local_cache"
+    )]
+    transient_local_cache: Option<Arc<TransientLocalCache>>,
+    // if the topic is configured in `force_transient_local` while the Reader's QoS is VOLATILE
+    transient_local_forced: bool,
+    // the maximum size of routed payloads, and the count of dropped oversized ones
+    #[serde(serialize_with = "serialize_payload_limit")]
+    payload_limit: Arc<PayloadSizeLimit>,
+    // the check of the CDR encapsulation header of the received samples, and the count of dropped invalid ones
+    #[serde(rename = "dropped_invalid_cdr", serialize_with = "serialize_cdr_check")]
+    cdr_check: Arc<CdrCheck>,
+    // the share of the `max_bandwidth` budget (if configured), and the count of messages shed when exceeded
+    #[serde(
+        rename = "dropped_over_bandwidth",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_route_bandwidth"
+    )]
+    bandwidth: Option<Arc<RouteBandwidth>>,
+    // the downsampling to the TimeBasedFilter of the remote Readers, and the count of messages it dropped
+    #[serde(serialize_with = "serialize_downsampling")]
+    downsampling: Arc<Downsampling>,
+    // the limitation of the routed messages to a maximum frequency (if configured in `pub_max_frequencies`,
+    // or in `feedback_max_frequency` for an Action's feedback topic), and the count of messages it dropped
+    #[serde(serialize_with = "serialize_rate_limiter")]
+    rate_limiter: Arc<RateLimiter<DDSMessage>>,
+    // for an Action's feedback topic: the routing of the last feedback kept by the RateLimiter for a goal,
+    // on its terminal status
+    #[serde(skip)]
+    _feedback_throttle: Option<Arc<FeedbackThrottle<DDSMessage>>>,
+    // the transformation of the routed payloads (if one is registered for the key expression)
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_route_transform"
+    )]
+    sample_transform: Option<Arc<RouteTransform>>,
+    // the compression of the payloads published over Zenoh (if configured in `compression`)
+    compression: Compression,
+    // the batching of the messages published over Zenoh (if configured in `batching`),
+    // and the count of batches it published
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_batcher"
+    )]
+    batcher: Option<Arc<Batcher>>,
+    // the statistics of the messages routed from DDS to Zenoh
+    #[serde(skip)]
+    stats: Arc<RouteStats>,
+    // if the route is paused via the admin space: the messages received by the DDS Reader are not routed
+    #[serde(serialize_with = "serialize_atomic_bool")]
+    paused: Arc<AtomicBool>,
     // the local DDS Reader created to serve the route (i.e. re-publish to zenoh message coming from DDS)
     #[serde(serialize_with = "serialize_atomic_entity_guid")]
     dds_reader: Arc<AtomicDDSEntity>,
     // the Zenoh Priority for publications
     #[serde(serialize_with = "serialize_priority")]
     priority: Priority,
+    // the DDS Partitions of the DDS Reader (if not the default one)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dds_partition: Option<Vec<String>>,
+    // the Zenoh CongestionControl for publications
+    #[serde(skip)]
+    congestion_ctrl: CongestionControl,
     // TypeInfo for Reader creation (if available)
     #[serde(skip)]
     _type_info: Option<Arc<TypeInfo>>,
@@ -90,14 +204,37 @@ pub struct RoutePublisher<'a> {
     // those are either the QoS announced by a remote bridge on a Reader discovery,
     // either the QoS adapted from a local disovered Writer
     #[serde(skip)]
-    _reader_qos: Qos,
+    reader_qos: Qos,
+    // the QoS the route was created with, before its adaptation by the bridge
+    // (i.e. as announced by a remote bridge or adapted from a local discovered Writer)
+    #[serde(skip)]
+    received_qos: Qos,
     // a liveliness token associated to this route, for announcement to other plugins
     #[serde(skip)]
     liveliness_token: Option<LivelinessToken<'a>>,
     // the list of remote routes served by this route ("<plugin_id>:<zenoh_key_expr>"")
     remote_routes: HashSet<String>,
+    // the minimum separation of the TimeBasedFilter announced by each remote route
+    // (None for a remote Reader without TimeBasedFilter)
+    #[serde(skip)]
+    remote_routes_separation: HashMap<String, Option<Duration>>,
     // the list of nodes served by this route
     local_nodes: HashSet<String>,
+    // the QoS of the discovered Writer the route is announced with (while serving local nodes),
+    // merged from the QoS of all the local Writers (see merge_writers_qos())
+    #[serde(skip)]
+    discovered_writer_qos: Option<Qos>,
+    // the QoS of the local Writers (merged per served node)
+    #[serde(skip)]
+    local_writers_qos: BTreeMap<String, Qos>,
+    // the QoS merged from the local Writers when the 1st one was served by this route,
+    // kept while they're undiscovered: a re-discovered Writer with other QoS requires to re-create the route
+    #[serde(skip)]
+    served_writers_qos: Option<Qos>,
+    // the incompatibilities of the local Writers' QoS with the DDS Reader (per served node),
+    // preventing their messages to be routed
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    qos_incompatibilities: HashMap<String, Vec<String>>,
 }
 
 impl Drop for RoutePublisher<'_> {
@@ -127,91 +264,299 @@ impl RoutePublisher<'_> {
         reader_qos: Qos,
         context: Context,
     ) -> Result<RoutePublisher<'_>, String> {
+        let received_qos = reader_qos.clone();
+        let reader_qos = route_reader_qos(&context.config, &ros2_name, &reader_qos);
+        // bound a KEEP_ALL History (for the Reader and the TRANSIENT_LOCAL cache), if configured
+        let reader_qos = match context.config.keep_all_max_depth {
+            Some(max_depth) if is_keep_all(&reader_qos) => {
+                tracing::warn!(
+                    "Route Publisher ({ros2_name} -> {zenoh_key_expr}): KEEP_ALL History of the discovered Writer capped to KEEP_LAST({max_depth}) as configured by 'keep_all_max_depth'"
+                );
+                cap_keep_all_history(&reader_qos, Some(max_depth))
+            }
+            _ => reader_qos,
+        };
+
         tracing::debug!(
             "Route Publisher ({ros2_name} -> {zenoh_key_expr}): creation with type {ros2_type}"
         );
 
-        // create the zenoh Publisher
-        // if Reader shall be TRANSIENT_LOCAL, use a PublicationCache to store historical messages
-        let transient_local = is_transient_local(&reader_qos);
-        let (cache, cache_size): (Option<PublicationCache>, usize) = if transient_local {
+        // in "typed_key_exprs" mode, publish with the type appended to the key expression
+        // (not for the topics of Actions, which are announced via the Action's liveliness token)
+        let typed_key_expr = if context.config.typed_key_exprs && !is_message_for_action(&ros2_name)
+        {
+            Some(typed_key_expr(&zenoh_key_expr, &ros2_type)?)
+        } else {
+            None
+        };
+        // if configured in `route_to`, publish only to the listed bridges, on a key expression scoped to them
+        let route_to_key_expr = context
+            .config
+            .get_route_to(&ros2_name)
+            .map(|targets| {
+                route_to_key_expr(targets, typed_key_expr.as_ref().unwrap_or(&zenoh_key_expr))
+            })
+            .transpose()?;
+        let publication_key_expr = route_to_key_expr
+            .clone()
+            .or_else(|| typed_key_expr.clone())
+            .unwrap_or_else(|| zenoh_key_expr.clone());
+        // in "instance_key_exprs" mode, each instance of a keyed topic is published with its key hash
+        // appended to the key expression: the Publisher and the cache are declared for all the instances
+        let instance_keyed = !keyless && context.config.is_instance_keyed(&ros2_name);
+        let declared_key_expr = if instance_keyed {
+            &*publication_key_expr / *KE_ANY_1_SEGMENT
+        } else {
+            publication_key_expr.clone()
+        };
+
+        // if Reader shall be TRANSIENT_LOCAL, use a TransientLocalCache to store historical messages.
+        // Same if the topic is configured in `force_transient_local` (but without changing the Reader's QoS
+        // since a TRANSIENT_LOCAL Reader doesn't match a VOLATILE Writer)
+        let transient_local_forced = !is_transient_local(&reader_qos)
+            && context.config.is_transient_local_forced(&ros2_name);
+        let transient_local = is_transient_local(&reader_qos) || transient_local_forced;
+        // for a latched aggregate topic (e.g. /tf_static), the last message of each Writer is cached
+        let latched_aggregate = transient_local && context.config.is_latched_aggregate(&ros2_name);
+        let (cache, cache_size): (Option<Arc<TransientLocalCache>>, usize) = if transient_local {
             #[allow(non_upper_case_globals)]
-            let history_qos = get_history_or_default(&reader_qos);
+            let history_qos = transient_local_history(&reader_qos);
             let durability_service_qos = get_durability_service_or_default(&reader_qos);
-            let mut history = match (history_qos.kind, history_qos.depth) {
-                (HistoryKind::KEEP_LAST, n) => {
-                    if keyless {
-                        // only 1 instance => history=n
-                        n as usize
-                    } else if durability_service_qos.max_instances == DDS_LENGTH_UNLIMITED {
-                        // No limit! => history=MAX
-                        usize::MAX
-                    } else if durability_service_qos.max_instances > 0 {
-                        // Compute cache size as history.depth * durability_service.max_instances
-                        // This makes the assumption that the frequency of publication is the same for all instances...
-                        // But as we have no way to have 1 cache per-instance, there is no other choice.
-                        n.saturating_mul(durability_service_qos.max_instances) as usize
-                    } else {
-                        n as usize
+            let history = match context.config.get_transient_local_cache_history(&ros2_name) {
+                // history explicitly configured for this topic
+                Some(history) => history,
+                // 1 message per Writer, bounded by the number of Writers (and transient_local_cache_max_bytes)
+                None if latched_aggregate => usize::MAX,
+                None => {
+                    let history = match (history_qos.kind, history_qos.depth) {
+                        (HistoryKind::KEEP_LAST, n) => {
+                            if keyless {
+                                // only 1 instance => history=n
+                                n as usize
+                            } else if durability_service_qos.max_instances == DDS_LENGTH_UNLIMITED {
+                                // No limit! => history=MAX
+                                usize::MAX
+                            } else if durability_service_qos.max_instances > 0 {
+                                // Compute cache size as history.depth * durability_service.max_instances
+                                // This makes the assumption that the frequency of publication is the same for all instances...
+                                // But as we have no way to have 1 cache per-instance, there is no other choice.
+                                n.saturating_mul(durability_service_qos.max_instances) as usize
+                            } else {
+                                n as usize
+                            }
+                        }
+                        (HistoryKind::KEEP_ALL, _) => usize::MAX,
+                    };
+                    // In case there are several Writers served by this route, increase the cache size
+                    let history =
+                        history.saturating_mul(context.config.transient_local_cache_multiplier);
+                    // cap the history computed from the QoS (e.g. KEEP_ALL or unlimited instances)
+                    match context
+                        .config
+                        .get_transient_local_cache_max_history(&ros2_name)
+                    {
+                        Some(max_history) => history.min(max_history),
+                        None => history,
                     }
                 }
-                (HistoryKind::KEEP_ALL, _) => usize::MAX,
             };
-            // In case there are several Writers served by this route, increase the cache size
-            history = history.saturating_mul(context.config.transient_local_cache_multiplier);
-            tracing::debug!(
-                "Route Publisher ({ros2_name} -> {zenoh_key_expr}): caching TRANSIENT_LOCAL publications via a PublicationCache with history={history} (computed from Reader's QoS: history=({:?},{}), durability_service.max_instances={})",
-                history_qos.kind, history_qos.depth, durability_service_qos.max_instances
-            );
-            (
-                Some(
-                    context
-                        .zsession
-                        .declare_publication_cache(&zenoh_key_expr)
-                        .history(history)
-                        .queryable_prefix(*KE_PREFIX_PUB_CACHE / &context.plugin_id)
-                        .queryable_allowed_origin(Locality::Remote) // Note: don't reply to queries from local QueryingSubscribers
-                        .res_async()
-                        .await
-                        .map_err(|e| {
-                            format!("Failed create PublicationCache for key {zenoh_key_expr}: {e}",)
-                        })?,
-                ),
-                history,
-            )
+            if history == 0 {
+                tracing::debug!(
+                    "Route Publisher ({ros2_name} -> {zenoh_key_expr}): no caching of TRANSIENT_LOCAL publications, as configured with depth 0"
+                );
+                (None, 0)
+            } else {
+                let max_bytes = context
+                    .config
+                    .get_transient_local_cache_max_bytes(&ros2_name)
+                    .unwrap_or(0);
+                tracing::debug!(
+                    "Route Publisher ({ros2_name} -> {zenoh_key_expr}): caching TRANSIENT_LOCAL publications with history={history} and max_bytes={max_bytes} (Reader's QoS: history=({:?},{}), durability_service.max_instances={})",
+                    history_qos.kind, history_qos.depth, durability_service_qos.max_instances
+                );
+                (
+                    Some(Arc::new(
+                        TransientLocalCache::declare(
+                            &context.zsession,
+                            &context.plugin_id,
+                            &declared_key_expr,
+                            history,
+                            max_bytes,
+                            latched_aggregate,
+                        )
+                        .await?,
+                    )),
+                    history,
+                )
+            }
         } else {
             (None, 0)
         };
 
-        // CongestionControl to be used when re-publishing over zenoh: Blocking if Writer is RELIABLE (since we don't know what is remote Reader's QoS)
-        let congestion_ctrl = match (
-            context.config.reliable_routes_blocking,
-            is_reliable(&reader_qos),
-        ) {
-            (true, true) => CongestionControl::Block,
-            _ => CongestionControl::Drop,
-        };
+        let congestion_ctrl = zenoh_congestion_control(&context.config, &ros2_name, &reader_qos);
+
+        // Maximum payload size if configured for this topic
+        let payload_limit = Arc::new(PayloadSizeLimit::new(
+            context.config.get_max_payload_size(&ros2_name).unwrap_or(0),
+        ));
+        let cdr_check = Arc::new(CdrCheck::new(context.config.forward_invalid_cdr));
+        let sample_transform = RouteTransform::for_route(&zenoh_key_expr);
+        let compression = context.config.get_compression(&ros2_name);
+        // with "deduplication" or "source_info", each routed message is tagged with its origin
+        // (also required for a latched aggregate topic, its cache keeping the last message of each origin,
+        // and with "sequence_tracking", its messages being numbered per origin)
+        let origin_tagger = (context.config.deduplication
+            || context.config.source_info
+            || context.config.sequence_tracking
+            || latched_aggregate)
+            .then(|| Arc::new(OriginTagger::default()));
+        let sequencer = context
+            .config
+            .sequence_tracking
+            .then(|| Arc::new(Sequencer::new()));
 
         // Priority if configured for this topic
         let priority = context
             .config
             .get_pub_priorities(&ros2_name)
             .unwrap_or_default();
+        let bandwidth = context
+            .bandwidth_cap
+            .as_ref()
+            .map(|cap| Arc::new(RouteBandwidth::new(cap.clone(), priority)));
 
         let publisher: Arc<Publisher<'static>> = context
             .zsession
-            .declare_publisher(zenoh_key_expr.clone())
+            .declare_publisher(declared_key_expr.clone())
             .allowed_destination(Locality::Remote)
             .congestion_control(congestion_ctrl)
             .priority(priority)
             .res_async()
             .await
-            .map_err(|e| format!("Failed create Publisher for key {zenoh_key_expr}: {e}",))?
+            .map_err(|e| format!("Failed create Publisher for key {declared_key_expr}: {e}",))?
             .into_arc();
 
+        let downsampling = Arc::new(Downsampling::default());
+        let stats = Arc::new(RouteStats::new(context.config.get_stats_rate_half_life()));
+        stats.on_event(
+            RouteEventKind::Created,
+            format!("created with type {ros2_type} (Zenoh key: {zenoh_key_expr})"),
+        );
+
+        let instances = instance_keyed.then(|| {
+            Arc::new(InstancesPublication {
+                zsession: context.zsession.clone(),
+                key_expr: publication_key_expr.clone(),
+                congestion_ctrl,
+                priority,
+            })
+        });
+
+        // Batching if configured for this topic, the batches being published by its timer when due.
+        // Only for a keyless topic, the messages of a keyed topic being published with their instance's key hash
+        let batcher = match context.config.get_batching(&ros2_name) {
+            Some(conf) if keyless => {
+                let mut conf = *conf;
+                if let Some(max_payload_size) = context.config.get_max_payload_size(&ros2_name) {
+                    conf.max_batch_size = conf.max_batch_size.min(max_payload_size);
+                }
+                let batcher = Arc::new(Batcher::new(&conf));
+                let publisher = Arc::downgrade(&publisher);
+                let cache = cache.as_ref().map(Arc::downgrade);
+                let payload_limit = payload_limit.clone();
+                let stats = stats.clone();
+                let route_id =
+                    format!("Route Publisher (ROS:{ros2_name} -> Zenoh:{zenoh_key_expr})");
+                batcher.spawn_timer(move |payload: Vec<u8>| {
+                    let Some(publisher) = publisher.upgrade() else {
+                        return;
+                    };
+                    publish_dds_message(
+                        DDSMessage::alive(payload.into()),
+                        &publisher,
+                        &None,
+                        keyless,
+                        &cache.as_ref().and_then(Weak::upgrade),
+                        &payload_limit,
+                        compression,
+                        &None,
+                        &stats,
+                        &route_id,
+                    );
+                });
+                Some(batcher)
+            }
+            Some(_) => {
+                tracing::warn!(
+                    "Route Publisher (ROS:{ros2_name} -> Zenoh:{zenoh_key_expr}): the topic is keyed, its messages are not batched as configured by 'batching'"
+                );
+                None
+            }
+            None => None,
+        };
+
+        // Maximum frequency if configured for this topic, the messages kept by the RateLimiter
+        // being routed by its timer (while the route exists)
+        let rate_limiter = Arc::new(RateLimiter::new(
+            context.config.get_rate_limit_policy(&ros2_name),
+            context
+                .config
+                .get_pub_max_frequencies(&ros2_name)
+                .or_else(|| context.config.get_feedback_max_frequency(&ros2_name)),
+        ));
+        let route_kept = {
+            let publisher = Arc::downgrade(&publisher);
+            let instances = instances.as_ref().map(Arc::downgrade);
+            let cache = cache.as_ref().map(Arc::downgrade);
+            let payload_limit = payload_limit.clone();
+            let bandwidth = bandwidth.clone();
+            let sample_transform = sample_transform.clone();
+            let batcher = batcher.clone();
+            let sequencer = sequencer.clone();
+            let stats = stats.clone();
+            let route_id = format!("Route Publisher (ROS:{ros2_name} -> Zenoh:{zenoh_key_expr})");
+            Arc::new(move |message: DDSMessage| {
+                let Some(publisher) = publisher.upgrade() else {
+                    return;
+                };
+                route_dds_message(
+                    message,
+                    &publisher,
+                    &instances.as_ref().and_then(Weak::upgrade),
+                    keyless,
+                    &cache.as_ref().and_then(Weak::upgrade),
+                    &payload_limit,
+                    &bandwidth,
+                    &sample_transform,
+                    compression,
+                    &sequencer,
+                    &batcher,
+                    &stats,
+                    &route_id,
+                );
+            })
+        };
+        rate_limiter.spawn_timer({
+            let route_kept = route_kept.clone();
+            move |message: DDSMessage| route_kept(message)
+        });
+        // for an Action's feedback topic, the messages are rate limited per goal,
+        // the last one kept for a goal being routed as soon as the goal terminates
+        let feedback_throttle = is_action_feedback_topic(&ros2_name).then(|| {
+            let throttle = Arc::new(FeedbackThrottle::new(
+                rate_limiter.clone(),
+                move |message: DDSMessage| route_kept(message),
+            ));
+            context
+                .feedback_throttles
+                .register(RouteKind::Publisher, &ros2_name, &throttle);
+            throttle
+        });
+
         // activate/deactivate DDS Reader on detection/undetection of matching Subscribers
         // (copy/move all required args for the callback)
         let dds_reader: Arc<AtomicDDSEntity> = Arc::new(DDS_ENTITY_NULL.into());
+        let paused = Arc::new(AtomicBool::new(false));
 
         let matching_listener = {
             publisher
@@ -227,6 +572,19 @@ impl RoutePublisher<'_> {
                     let reader_qos = reader_qos.clone();
                     let type_info = type_info.clone();
                     let publisher = publisher.clone();
+                    let instances = instances.clone();
+                    let cache = cache.clone();
+                    let payload_limit = payload_limit.clone();
+                    let cdr_check = cdr_check.clone();
+                    let bandwidth = bandwidth.clone();
+                    let downsampling = downsampling.clone();
+                    let rate_limiter = rate_limiter.clone();
+                    let sample_transform = sample_transform.clone();
+                    let batcher = batcher.clone();
+                    let stats = stats.clone();
+                    let paused = paused.clone();
+                    let origin_tagger = origin_tagger.clone();
+                    let sequencer = sequencer.clone();
 
                     move |status| {
                         tracing::debug!("{route_id} MatchingStatus changed: {status:?}");
@@ -241,8 +599,26 @@ impl RoutePublisher<'_> {
                                 &reader_qos,
                                 &type_info,
                                 &publisher,
+                                &instances,
+                                &cache,
+                                &payload_limit,
+                                &cdr_check,
+                                &bandwidth,
+                                &downsampling,
+                                &rate_limiter,
+                                &sample_transform,
+                                compression,
+                                &sequencer,
+                                &batcher,
+                                &stats,
+                                &paused,
+                                &origin_tagger,
                             ) {
                                 tracing::error!("{route_id}: failed to activate DDS Reader: {e}");
+                                stats.on_event(
+                                    RouteEventKind::DdsError,
+                                    format!("failed to activate DDS Reader: {e}"),
+                                );
                             }
                         } else {
                             deactivate_dds_reader(
@@ -259,24 +635,48 @@ impl RoutePublisher<'_> {
         };
 
         Ok(RoutePublisher {
+            raw_dds_topic: ros2_name_to_raw_dds_topic(&ros2_name).map(String::from),
             ros2_name,
             ros2_type,
             zenoh_key_expr,
+            typed_key_expr,
+            route_to_key_expr,
+            instances,
             context,
             zenoh_publisher: ZPublisher {
                 publisher,
                 _matching_listener: matching_listener,
-                _cache: cache,
                 cache_size,
             },
+            transient_local_cache: cache,
+            transient_local_forced,
+            payload_limit,
+            cdr_check,
+            bandwidth,
+            downsampling,
+            rate_limiter,
+            _feedback_throttle: feedback_throttle,
+            sample_transform,
+            compression,
+            batcher,
+            stats,
+            paused,
             dds_reader,
             priority,
+            dds_partition: reader_qos.partition.clone(),
+            congestion_ctrl,
             _type_info: type_info.clone(),
-            _reader_qos: reader_qos,
+            reader_qos,
+            received_qos,
             keyless,
             liveliness_token: None,
             remote_routes: HashSet::new(),
+            remote_routes_separation: HashMap::new(),
             local_nodes: HashSet::new(),
+            discovered_writer_qos: None,
+            local_writers_qos: BTreeMap::new(),
+            served_writers_qos: None,
+            qos_incompatibilities: HashMap::new(),
         })
     }
 
@@ -298,12 +698,21 @@ impl RoutePublisher<'_> {
         // only if not for an Action (since actions declare their own liveliness)
         if !is_message_for_action(&self.ros2_name) {
             // create associated LivelinessToken
+            // (announcing a TRANSIENT_LOCAL Writer if forced, for remote routes to query the cache)
+            let announced_qos = if self.transient_local_forced {
+                force_transient_local(discovered_writer_qos)
+            } else {
+                discovered_writer_qos.clone()
+            };
+            let announced_qos =
+                filter_deadline(announced_qos, self.context.config.propagate_deadline);
             let liveliness_ke = new_ke_liveliness_pub(
                 &self.context.plugin_id,
                 &self.zenoh_key_expr,
                 &self.ros2_type,
                 self.keyless,
-                discovered_writer_qos,
+                &announced_qos,
+                self.typed_key_expr.is_some(),
             )?;
             let ros2_name = self.ros2_name.clone();
             self.liveliness_token = Some(self.context.zsession
@@ -323,26 +732,79 @@ impl RoutePublisher<'_> {
 
     fn retire_route(&mut self) {
         self.liveliness_token = None;
+        self.discovered_writer_qos = None;
+    }
+
+    /// Re-assert the route announcement over Zenoh (e.g. after a reconnection), if announced.
+    /// The new LivelinessToken is declared before the old one is dropped, so the remote bridges
+    /// don't see the route retired if it was still known.
+    pub async fn restore_announcement(&mut self) -> Result<(), String> {
+        match self.discovered_writer_qos.clone() {
+            Some(qos) => self.announce_route(&qos).await,
+            None => Ok(()),
+        }
     }
 
     #[inline]
-    pub fn add_remote_route(&mut self, plugin_id: &str, zenoh_key_expr: &keyexpr) {
-        self.remote_routes
-            .insert(format!("{plugin_id}:{zenoh_key_expr}"));
+    pub fn ros2_name(&self) -> &str {
+        &self.ros2_name
+    }
+
+    #[inline]
+    pub fn ros2_type(&self) -> &str {
+        &self.ros2_type
+    }
+
+    #[inline]
+    pub fn zenoh_key_expr(&self) -> &OwnedKeyExpr {
+        &self.zenoh_key_expr
+    }
+
+    #[inline]
+    pub fn is_keyless(&self) -> bool {
+        self.keyless
+    }
+
+    // `separation` is the minimum separation of the TimeBasedFilter announced by the remote route, if any
+    #[inline]
+    pub fn add_remote_route(
+        &mut self,
+        plugin_id: &str,
+        zenoh_key_expr: &keyexpr,
+        separation: Option<Duration>,
+    ) {
+        let remote_route = format!("{plugin_id}:{zenoh_key_expr}");
+        self.remote_routes_separation
+            .insert(remote_route.clone(), separation);
+        self.remote_routes.insert(remote_route);
         tracing::debug!("{self} now serving remote routes {:?}", self.remote_routes);
+        self.update_downsampling();
     }
 
     #[inline]
     pub fn remove_remote_route(&mut self, plugin_id: &str, zenoh_key_expr: &keyexpr) {
-        self.remote_routes
-            .remove(&format!("{plugin_id}:{zenoh_key_expr}"));
+        let remote_route = format!("{plugin_id}:{zenoh_key_expr}");
+        self.remote_routes_separation.remove(&remote_route);
+        self.remote_routes.remove(&remote_route);
         tracing::debug!("{self} now serving remote routes {:?}", self.remote_routes);
+        self.update_downsampling();
         // if last remote route removed, deactivate the DDS Reader
         if self.remote_routes.is_empty() {
             self.deactivate_dds_reader();
         }
     }
 
+    // Downsample the routed messages to the smallest minimum separation of the remote Readers
+    // (none if one of them has no TimeBasedFilter)
+    fn update_downsampling(&self) {
+        let interval =
+            min_time_based_filter_separation(self.remote_routes_separation.values().copied());
+        if interval != self.downsampling.interval() {
+            tracing::debug!("{self}: downsampling interval set to {interval:?}");
+            self.downsampling.set_interval(interval);
+        }
+    }
+
     #[inline]
     pub fn is_serving_remote_route(&self) -> bool {
         !self.remote_routes.is_empty()
@@ -350,19 +812,76 @@ impl RoutePublisher<'_> {
 
     #[inline]
     pub async fn add_local_node(&mut self, node: String, discovered_writer_qos: &Qos) {
+        let incompatibilities = qos_incompatibilities(discovered_writer_qos, &self.reader_qos);
+        if !incompatibilities.is_empty() && !self.local_nodes.contains(&node) {
+            tracing::warn!(
+                "{self}: the QoS of the Writer of {node} is incompatible with the DDS Reader, none of its messages will be routed - {}",
+                incompatibilities.join(" ; ")
+            );
+            self.qos_incompatibilities
+                .insert(node.clone(), incompatibilities);
+        }
+        self.local_writers_qos
+            .insert(node.clone(), discovered_writer_qos.clone());
+        if self.served_writers_qos.is_none() {
+            self.served_writers_qos = self.merged_writers_qos();
+        }
         if self.local_nodes.insert(node) {
             tracing::debug!("{self} now serving local nodes {:?}", self.local_nodes);
             // if 1st local node added, announce the route
             if self.local_nodes.len() == 1 {
-                if let Err(e) = self.announce_route(discovered_writer_qos).await {
+                let qos = self
+                    .merged_writers_qos()
+                    .unwrap_or_else(|| discovered_writer_qos.clone());
+                if let Err(e) = self.announce_route(&qos).await {
                     tracing::error!("{self} announcement failed: {e}");
                 }
+                self.discovered_writer_qos = Some(qos);
             }
         }
     }
 
+    // Set the QoS of the local Writers of the nodes to be added (e.g. on the re-creation of the route),
+    // so the route is announced with their merged QoS when the 1st node is added
+    pub fn preset_local_writers_qos(&mut self, local_writers_qos: BTreeMap<String, Qos>) {
+        self.local_writers_qos = local_writers_qos;
+    }
+
+    #[inline]
+    pub fn local_writers_qos(&self) -> &BTreeMap<String, Qos> {
+        &self.local_writers_qos
+    }
+
+    /// The QoS merged from all the local Writers (see merge_writers_qos())
+    #[inline]
+    pub fn merged_writers_qos(&self) -> Option<Qos> {
+        merge_writers_qos(self.local_writers_qos.values())
+    }
+
+    /// The QoS merged from all the local Writers, if the route was announced or first served with other QoS
+    /// (i.e. the route must be re-created with those, e.g. if a Writer restarted with another Durability)
+    pub fn outdated_writers_qos(&self) -> Option<Qos> {
+        let merged = self.merged_writers_qos()?;
+        match (&self.discovered_writer_qos, &self.served_writers_qos) {
+            (Some(qos), _) | (None, Some(qos)) if *qos != merged => Some(merged),
+            _ => None,
+        }
+    }
+
+    /// The remote routes served by this route, as (plugin_id, zenoh_key_expr, TimeBasedFilter separation)
+    pub fn remote_routes(&self) -> impl Iterator<Item = (&str, &keyexpr, Option<Duration>)> {
+        self.remote_routes_separation
+            .iter()
+            .filter_map(|(r, separation)| {
+                let (plugin_id, ke) = r.split_once(':')?;
+                keyexpr::new(ke).ok().map(|ke| (plugin_id, ke, *separation))
+            })
+    }
+
     #[inline]
     pub fn remove_local_node(&mut self, node: &str) {
+        self.qos_incompatibilities.remove(node);
+        self.local_writers_qos.remove(node);
         if self.local_nodes.remove(node) {
             tracing::debug!("{self} now serving local nodes {:?}", self.local_nodes);
             // if last local node removed, retire the route
@@ -377,6 +896,60 @@ impl RoutePublisher<'_> {
         !self.local_nodes.is_empty()
     }
 
+    /// Pause or resume the routing of the messages received by the DDS Reader
+    /// (the DDS Reader, the Zenoh Publisher and the liveliness token are kept)
+    #[inline]
+    pub fn set_paused(&self, paused: bool) {
+        if self.paused.swap(paused, Ordering::Relaxed) != paused {
+            self.stats
+                .on_event(pause_event_kind(paused), "via admin space".into());
+        }
+    }
+
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// The serialization of the RateLimiter (e.g. for the admin space of the Action routes)
+    pub fn serialize_rate_limiter<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.rate_limiter.serialize(s)
+    }
+
+    #[inline]
+    pub fn stats(&self) -> RouteStatsSnapshot {
+        let mut stats = self.stats.snapshot();
+        stats.dropped += self.rate_limiter.dropped();
+        stats
+    }
+
+    #[inline]
+    pub fn events(&self) -> Vec<RouteEvent> {
+        self.stats.events()
+    }
+
+    /// The QoS of the route for the admin space (with the "detail=qos" selector parameter)
+    pub fn qos_details(&self) -> Result<serde_json::Value, serde_json::Error> {
+        Ok(serde_json::json!({
+            "received": qos_to_json(&self.received_qos)?,
+            "dds_reader": qos_to_json(&self.reader_qos)?,
+            "announced": self.discovered_writer_qos.as_ref().map(qos_to_json).transpose()?,
+            "zenoh_publisher": {
+                "priority": self.priority as u8,
+                "congestion_control": format!("{:?}", self.congestion_ctrl),
+                "express": false,
+            },
+        }))
+    }
+
+    #[inline]
+    pub fn is_last_local_node(&self, node: &str) -> bool {
+        self.local_nodes.len() == 1 && self.local_nodes.contains(node)
+    }
+
     #[inline]
     pub fn is_unused(&self) -> bool {
         !self.is_serving_local_node() && !self.is_serving_remote_route()
@@ -390,18 +963,58 @@ where
     s.serialize_u64(zpub.cache_size as u64)
 }
 
-fn serialize_priority<S>(p: &Priority, s: S) -> Result<S::Ok, S::Error>
+fn serialize_transient_local_cache<S>(
+    cache: &Option<Arc<TransientLocalCache>>,
+    s: S,
+) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
-    s.serialize_u8(*p as u8)
+    match cache {
+        Some(c) => c.as_ref().serialize(s),
+        None => s.serialize_none(),
+    }
 }
 
-// Return the read period if name matches one of the "pub_max_frequencies" option
-fn get_read_period(config: &Config, ros2_name: &str) -> Option<Duration> {
-    config
-        .get_pub_max_frequencies(ros2_name)
-        .map(|f| Duration::from_secs_f32(1f32 / f))
+// The QoS of the DDS Reader created for a route, from the discovered Writers' QoS adapted for a Reader:
+// with the Reliability, the QoS configured via `topic_qos` and the DDS Partitions configured for this topic, if any.
+// The Reliability is never raised to RELIABLE (it wouldn't match a BEST_EFFORT Writer): a "reliable" override
+// only applies to the zenoh leg (see zenoh_congestion_control())
+fn route_reader_qos(config: &Config, ros2_name: &str, reader_qos: &Qos) -> Qos {
+    let reader_qos =
+        apply_reader_reliability_override(reader_qos, config.get_reliability_override(ros2_name));
+    let reader_qos = apply_topic_qos(&reader_qos, config.get_topic_qos(ros2_name).as_ref());
+    let mut reader_qos = apply_partition(&reader_qos, config.get_dds_partition(ros2_name));
+    // the TimeBasedFilter announced by a remote Reader is applied by the downsampling of the route
+    reader_qos.time_based_filter = None;
+    reader_qos
+}
+
+// CongestionControl to be used when re-publishing over zenoh: as configured for this topic via `topic_qos`, or else
+// Blocking if Writer is RELIABLE (since we don't know what is remote Reader's QoS), or if the zenoh leg is configured
+// as reliable for this topic via `zenoh_reliability` or `reliability`
+fn zenoh_congestion_control(
+    config: &Config,
+    ros2_name: &str,
+    reader_qos: &Qos,
+) -> CongestionControl {
+    match config.get_congestion_control(ros2_name) {
+        Some(CongestionControlOverride::Block) => return CongestionControl::Block,
+        Some(CongestionControlOverride::Drop) => return CongestionControl::Drop,
+        None => (),
+    }
+    match (
+        config.reliable_routes_blocking,
+        config.is_zenoh_reliable(
+            ros2_name,
+            is_reliable(reader_qos)
+                || config.get_reliability_override(ros2_name)
+                    == Some(ReliabilityOverride::Reliable),
+        ),
+    ) {
+        (true, true) => CongestionControl::Block,
+        _ => CongestionControl::Drop,
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -415,11 +1028,24 @@ fn activate_dds_reader(
     reader_qos: &Qos,
     type_info: &Option<Arc<TypeInfo>>,
     publisher: &Arc<Publisher<'static>>,
+    instances: &Option<Arc<InstancesPublication>>,
+    cache: &Option<Arc<TransientLocalCache>>,
+    payload_limit: &Arc<PayloadSizeLimit>,
+    cdr_check: &Arc<CdrCheck>,
+    bandwidth: &Option<Arc<RouteBandwidth>>,
+    downsampling: &Arc<Downsampling>,
+    rate_limiter: &Arc<RateLimiter<DDSMessage>>,
+    sample_transform: &Option<Arc<RouteTransform>>,
+    compression: Compression,
+    sequencer: &Option<Arc<Sequencer>>,
+    batcher: &Option<Arc<Batcher>>,
+    stats: &Arc<RouteStats>,
+    paused: &Arc<AtomicBool>,
+    origin_tagger: &Option<Arc<OriginTagger>>,
 ) -> Result<(), String> {
     tracing::debug!("{route_id}: create Reader with {reader_qos:?}");
-    let topic_name: String = format!("rt{}", ros2_name);
-    let type_name = ros2_message_type_to_dds_type(ros2_type);
-    let read_period = get_read_period(&context.config, ros2_name);
+    let (topic_name, type_name) =
+        message_dds_topic_and_type(ros2_name, ros2_type, context.config.long_name_policy)?;
 
     // create matching DDS Reader that forwards message coming from DDS to Zenoh
     let reader = create_dds_reader(
@@ -428,13 +1054,108 @@ fn activate_dds_reader(
         type_name,
         type_info,
         keyless,
-        reader_qos.clone(),
-        read_period,
+        without_durability_service(reader_qos),
+        // the `pub_max_frequencies` are applied by the RateLimiter
+        None,
+        // the instance lifecycle of a keyed topic is routed, even out of "instance_key_exprs" mode
+        !keyless,
         {
             let route_id = route_id.to_string();
+            let ros2_name = ros2_name.to_string();
+            let feedback_topic = is_action_feedback_topic(&ros2_name);
+            let status_topic = is_action_status_topic(&ros2_name);
+            let feedback_throttles = context.feedback_throttles.clone();
             let publisher = publisher.clone();
+            let instances = instances.clone();
+            let cache = cache.clone();
+            let payload_limit = payload_limit.clone();
+            let cdr_check = cdr_check.clone();
+            let bandwidth = bandwidth.clone();
+            let downsampling = downsampling.clone();
+            let rate_limiter = rate_limiter.clone();
+            let sample_transform = sample_transform.clone();
+            let batcher = batcher.clone();
+            let stats = stats.clone();
+            let paused = paused.clone();
+            let origin_tagger = origin_tagger.clone();
+            let sequencer = sequencer.clone();
+            let dds_reader = dds_reader.clone();
             move |sample: &DDSRawSample| {
-                route_dds_message_to_zenoh(sample, &publisher, &route_id);
+                if paused.load(Ordering::Relaxed) {
+                    return;
+                }
+                stats.on_received(sample.len());
+                let mut message = DDSMessage::new(sample, keyless);
+                // the CDR header is checked on the whole serialized sample
+                // (the disposes/unregistrations may have no payload)
+                if message.instance_state == DDSInstanceState::Alive
+                    && !cdr_check.check(&message.payload.contiguous(), &route_id, || {
+                        writer_gid(dds_reader.load(Ordering::Relaxed), sample)
+                    })
+                {
+                    stats.on_dropped();
+                    stats.on_event(
+                        RouteEventKind::Dropped,
+                        "sample with an invalid CDR header".into(),
+                    );
+                    return;
+                }
+                if let Some(tagger) = &origin_tagger {
+                    message.origin = tagger.origin(dds_reader.load(Ordering::Relaxed), sample);
+                }
+                if !check_downsampling(&message, &downsampling, &route_id) {
+                    stats.on_dropped();
+                    return;
+                }
+                // the last feedback kept for the goals terminated by an Action's status are routed before it
+                if status_topic {
+                    feedback_throttles.on_status(
+                        RouteKind::Publisher,
+                        &ros2_name,
+                        &message.payload.contiguous(),
+                    );
+                }
+                // the disposes/unregistrations are not rate limited
+                let message = if message.instance_state == DDSInstanceState::Alive {
+                    // the feedback of an Action is rate limited per goal
+                    let instance = feedback_topic
+                        .then(|| feedback_goal_id(&message.payload.contiguous()))
+                        .flatten()
+                        .unwrap_or(message.keyhash);
+                    let rate_limited = rate_limiter.dropped();
+                    let offered = rate_limiter.offer(instance, message, Instant::now());
+                    if rate_limiter.dropped() > rate_limited {
+                        stats.on_event(
+                            RouteEventKind::Dropped,
+                            "message replaced by a newer one in the rate limiter".into(),
+                        );
+                    }
+                    match offered {
+                        Some(message) => message,
+                        None => {
+                            tracing::trace!("{route_id}: message kept by the rate limiter");
+                            return;
+                        }
+                    }
+                } else {
+                    rate_limiter.forget(&message.keyhash);
+                    message
+                };
+                route_dds_message(
+                    message,
+                    &publisher,
+                    &instances,
+                    keyless,
+                    &cache,
+                    &payload_limit,
+                    &bandwidth,
+                    &sample_transform,
+                    compression,
+                    &sequencer,
+                    &batcher,
+                    &stats,
+                    &route_id,
+                );
             }
         },
     )?;
@@ -471,13 +1192,503 @@ fn deactivate_dds_reader(
     }
 }
 
-fn route_dds_message_to_zenoh(sample: &DDSRawSample, publisher: &Arc<Publisher>, route_id: &str) {
+// A message received by the DDS Reader, owning its payload to be routed later
+// if kept by the RateLimiter (with an all zeros key hash for a keyless topic)
+struct DDSMessage {
+    payload: ZBuf,
+    keyhash: [u8; 16],
+    instance_state: DDSInstanceState,
+    // the origin of the message, with "deduplication" or "source_info"
+    origin: Option<Origin>,
+}
+
+impl DDSMessage {
+    fn new(sample: &DDSRawSample, keyless: bool) -> DDSMessage {
+        DDSMessage {
+            payload: sample.into(),
+            keyhash: if keyless { [0u8; 16] } else { sample.keyhash() },
+            instance_state: sample.instance_state(),
+            origin: None,
+        }
+    }
+
+    // A message of a keyless topic (e.g. a batch of messages)
+    fn alive(payload: ZBuf) -> DDSMessage {
+        DDSMessage {
+            payload,
+            keyhash: [0u8; 16],
+            instance_state: DDSInstanceState::Alive,
+            origin: None,
+        }
+    }
+}
+
+// The GUID of the DDS Writer of a sample, for logging
+fn writer_gid(reader: dds_entity_t, sample: &DDSRawSample) -> String {
+    match get_matched_publication_guid(reader, sample.publication_handle()) {
+        Ok(gid) => gid.to_string(),
+        Err(_) => "an unknown DDS Writer".into(),
+    }
+}
+
+// Return false if a message must be dropped by the downsampling (per instance),
+// forgetting the instances that are disposed or unregistered
+fn check_downsampling(message: &DDSMessage, downsampling: &Downsampling, route_id: &str) -> bool {
+    if message.instance_state != DDSInstanceState::Alive {
+        downsampling.forget(&message.keyhash);
+        return true;
+    }
+    let routed = downsampling.check(message.keyhash, Instant::now());
+    if !routed {
+        tracing::trace!("{route_id}: message dropped by downsampling");
+    }
+    routed
+}
+
+#[allow(clippy::too_many_arguments)]
+fn route_dds_message(
+    mut message: DDSMessage,
+    publisher: &Arc<Publisher>,
+    instances: &Option<Arc<InstancesPublication>>,
+    keyless: bool,
+    cache: &Option<Arc<TransientLocalCache>>,
+    payload_limit: &PayloadSizeLimit,
+    bandwidth: &Option<Arc<RouteBandwidth>>,
+    sample_transform: &Option<Arc<RouteTransform>>,
+    compression: Compression,
+    sequencer: &Option<Arc<Sequencer>>,
+    batcher: &Option<Arc<Batcher>>,
+    stats: &RouteStats,
+    route_id: &str,
+) {
+    if let (Some(transform), DDSInstanceState::Alive) = (sample_transform, message.instance_state) {
+        match transform.apply(message.payload) {
+            Ok(Some(payload)) => message.payload = payload,
+            Ok(None) => {
+                tracing::trace!("{route_id}: message dropped by sample transform");
+                stats.on_dropped();
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("{route_id}: {e} - drop message");
+                stats.on_error();
+                return;
+            }
+        }
+    }
+    // the disposes/unregistrations are not shed
+    if let (Some(bandwidth), DDSInstanceState::Alive) = (bandwidth, message.instance_state) {
+        if !bandwidth.admit(message.payload.len()) {
+            tracing::trace!("{route_id}: message dropped as exceeding max_bandwidth");
+            stats.on_dropped();
+            stats.on_event(
+                RouteEventKind::Dropped,
+                "message shed as exceeding max_bandwidth".into(),
+            );
+            return;
+        }
+    }
+    if let (Some(batcher), DDSInstanceState::Alive) = (batcher, message.instance_state) {
+        let payloads = batcher.push(message.payload.contiguous().into_owned(), Instant::now());
+        for payload in payloads {
+            publish_dds_message(
+                DDSMessage::alive(payload.into()),
+                publisher,
+                instances,
+                keyless,
+                cache,
+                payload_limit,
+                compression,
+                sequencer,
+                stats,
+                route_id,
+            );
+        }
+        return;
+    }
+    publish_dds_message(
+        message,
+        publisher,
+        instances,
+        keyless,
+        cache,
+        payload_limit,
+        compression,
+        sequencer,
+        stats,
+        route_id,
+    );
+}
+
+// Publish a message (or a batch of messages) over Zenoh, compressed if configured
+#[allow(clippy::too_many_arguments)]
+fn publish_dds_message(
+    mut message: DDSMessage,
+    publisher: &Arc<Publisher>,
+    instances: &Option<Arc<InstancesPublication>>,
+    keyless: bool,
+    cache: &Option<Arc<TransientLocalCache>>,
+    payload_limit: &PayloadSizeLimit,
+    compression: Compression,
+    sequencer: &Option<Arc<Sequencer>>,
+    stats: &RouteStats,
+    route_id: &str,
+) {
+    if compression != Compression::None && message.instance_state == DDSInstanceState::Alive {
+        match compress_zbuf(message.payload, compression) {
+            Ok(payload) => message.payload = payload,
+            Err(e) => {
+                tracing::warn!("{route_id}: {e} - drop message");
+                stats.on_error();
+                return;
+            }
+        }
+    }
+    match instances {
+        Some(instances) => {
+            route_dds_instance_to_zenoh(message, instances, cache, payload_limit, stats, route_id)
+        }
+        None => route_dds_message_to_zenoh(
+            message,
+            publisher,
+            keyless,
+            cache,
+            payload_limit,
+            sequencer,
+            stats,
+            route_id,
+        ),
+    }
+}
+
+// For a keyed topic, the instance's key hash is attached to each message, and the dispose/unregister
+// of an instance is routed as a delete with its state and key hash attached
+#[allow(clippy::too_many_arguments)]
+fn route_dds_message_to_zenoh(
+    message: DDSMessage,
+    publisher: &Arc<Publisher>,
+    keyless: bool,
+    cache: &Option<Arc<TransientLocalCache>>,
+    payload_limit: &PayloadSizeLimit,
+    sequencer: &Option<Arc<Sequencer>>,
+    stats: &RouteStats,
+    route_id: &str,
+) {
+    if !keyless {
+        let state = message.instance_state;
+        if state != DDSInstanceState::Alive {
+            tracing::debug!("{route_id}: routing {state:?} instance");
+            match publisher
+                .delete()
+                .with_attachment(instance_state_and_keyhash_as_attachment(
+                    state,
+                    &message.keyhash,
+                ))
+                .res_sync()
+            {
+                Ok(()) => stats.on_routed(0),
+                Err(e) => {
+                    tracing::error!("{route_id}: failed to route message: {e}");
+                    stats.on_error();
+                }
+            }
+            return;
+        }
+    }
+    let len = message.payload.len();
+    if !payload_limit.check(len, route_id) {
+        stats.on_dropped();
+        stats.on_event(
+            RouteEventKind::Dropped,
+            "payload exceeding max_payload_size".into(),
+        );
+        return;
+    }
     if *LOG_PAYLOAD {
-        tracing::debug!("{route_id}: routing message - payload: {:02x?}", sample);
+        tracing::debug!(
+            "{route_id}: routing message - payload: {:02x?}",
+            message.payload.contiguous()
+        );
     } else {
-        tracing::trace!("{route_id}: routing message - {} bytes", sample.len());
+        tracing::trace!("{route_id}: routing message - {len} bytes");
+    }
+    let zbuf = message.payload;
+    if let Some(cache) = cache {
+        cache.push(zbuf.clone(), message.origin);
+    }
+    let res = match (keyless, message.origin) {
+        (true, None) => publisher.put(zbuf).res_sync(),
+        (keyless, origin) => {
+            let mut attachment = if keyless {
+                Attachment::new()
+            } else {
+                instance_keyhash_as_attachment(&message.keyhash)
+            };
+            if let Some(origin) = origin {
+                add_origin_to_attachment(&mut attachment, origin);
+                if let Some(sequencer) = sequencer {
+                    let sn = sequencer.next(origin.gid, Instant::now());
+                    add_sequence_number_to_attachment(&mut attachment, sn);
+                }
+            }
+            publisher.put(zbuf).with_attachment(attachment).res_sync()
+        }
+    };
+    match res {
+        Ok(()) => stats.on_routed(len),
+        Err(e) => {
+            tracing::error!("{route_id}: failed to route message: {e}");
+            stats.on_error();
+        }
+    }
+}
+
+// In "instance_key_exprs" mode: route a message on its instance's key expression,
+// or the dispose/unregister of the instance as a delete on this key expression
+fn route_dds_instance_to_zenoh(
+    message: DDSMessage,
+    instances: &InstancesPublication,
+    cache: &Option<Arc<TransientLocalCache>>,
+    payload_limit: &PayloadSizeLimit,
+    stats: &RouteStats,
+    route_id: &str,
+) {
+    let key_expr = instance_key_expr(&instances.key_expr, &message.keyhash);
+    let len = message.payload.len();
+    let res = match message.instance_state {
+        DDSInstanceState::Alive => {
+            if !payload_limit.check(len, route_id) {
+                stats.on_dropped();
+                stats.on_event(
+                    RouteEventKind::Dropped,
+                    "payload exceeding max_payload_size".into(),
+                );
+                return;
+            }
+            if *LOG_PAYLOAD {
+                tracing::debug!(
+                    "{route_id}: routing message on {key_expr} - payload: {:02x?}",
+                    message.payload.contiguous()
+                );
+            } else {
+                tracing::trace!("{route_id}: routing message on {key_expr} - {len} bytes");
+            }
+            let zbuf = message.payload;
+            if let Some(cache) = cache {
+                cache.push_at(key_expr.clone(), zbuf.clone());
+            }
+            instances
+                .zsession
+                .put(&key_expr, zbuf)
+                .allowed_destination(Locality::Remote)
+                .congestion_control(instances.congestion_ctrl)
+                .priority(instances.priority)
+                .res_sync()
+                .map(|()| len)
+        }
+        state => {
+            tracing::debug!("{route_id}: routing {state:?} instance on {key_expr}");
+            if let Some(cache) = cache {
+                cache.forget(&key_expr);
+            }
+            instances
+                .zsession
+                .delete(&key_expr)
+                .with_attachment(instance_state_as_attachment(state))
+                .allowed_destination(Locality::Remote)
+                .congestion_control(instances.congestion_ctrl)
+                .priority(instances.priority)
+                .res_sync()
+                .map(|()| 0)
+        }
+    };
+    match res {
+        Ok(len) => stats.on_routed(len),
+        Err(e) => {
+            tracing::error!("{route_id}: failed to route message: {e}");
+            stats.on_error();
+        }
     }
-    if let Err(e) = publisher.put(sample).res_sync() {
-        tracing::error!("{route_id}: failed to route message: {e}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{route_reader_qos, zenoh_congestion_control};
+    use crate::config::Config;
+    use cyclors::qos::{
+        Deadline, Durability, DurabilityKind, History, HistoryKind, Qos, Reliability,
+        ReliabilityKind, DDS_100MS_DURATION, DDS_1S_DURATION,
+    };
+    use zenoh::prelude::{CongestionControl, Priority};
+
+    #[test]
+    fn test_zenoh_congestion_control() {
+        let best_effort = Qos {
+            reliability: Some(Reliability {
+                kind: ReliabilityKind::BEST_EFFORT,
+                max_blocking_time: DDS_100MS_DURATION,
+            }),
+            ..Default::default()
+        };
+        let reliable = Qos {
+            reliability: Some(Reliability {
+                kind: ReliabilityKind::RELIABLE,
+                max_blocking_time: DDS_100MS_DURATION,
+            }),
+            ..Default::default()
+        };
+
+        // default: depending on the DDS Reader's Reliability
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        assert_eq!(
+            zenoh_congestion_control(&config, "/scan", &reliable),
+            CongestionControl::Block
+        );
+        assert_eq!(
+            zenoh_congestion_control(&config, "/scan", &best_effort),
+            CongestionControl::Drop
+        );
+
+        // configured via `zenoh_reliability`, independently of the DDS QoS
+        let config = serde_json::from_str::<Config>(
+            r#"{"zenoh_reliability": ["/cmd_echo=reliable", "/camera/.*=best_effort"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            zenoh_congestion_control(&config, "/cmd_echo", &best_effort),
+            CongestionControl::Block
+        );
+        assert_eq!(
+            zenoh_congestion_control(&config, "/camera/image", &reliable),
+            CongestionControl::Drop
+        );
+        assert_eq!(
+            zenoh_congestion_control(&config, "/scan", &best_effort),
+            CongestionControl::Drop
+        );
+
+        // a "reliable" override applies to the zenoh leg of a BEST_EFFORT Writer's route, not to its DDS Reader
+        let config =
+            serde_json::from_str::<Config>(r#"{"reliability": ["/scan=reliable"]}"#).unwrap();
+        let qos = route_reader_qos(&config, "/scan", &best_effort);
+        assert_eq!(qos.reliability.unwrap().kind, ReliabilityKind::BEST_EFFORT);
+        assert_eq!(
+            zenoh_congestion_control(&config, "/scan", &qos),
+            CongestionControl::Block
+        );
+        assert_eq!(
+            zenoh_congestion_control(&config, "/tf", &best_effort),
+            CongestionControl::Drop
+        );
+
+        // never blocking if `reliable_routes_blocking` is false
+        let config = serde_json::from_str::<Config>(
+            r#"{"zenoh_reliability": "reliable", "reliable_routes_blocking": false}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            zenoh_congestion_control(&config, "/cmd_echo", &best_effort),
+            CongestionControl::Drop
+        );
+
+        // configured via `topic_qos`, taking precedence over the other options
+        let config = serde_json::from_str::<Config>(
+            r#"{
+                "topic_qos": {"/cmd_vel": {"congestion_control": "block"}, "/scan": {"congestion_control": "drop"}},
+                "reliable_routes_blocking": false
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            zenoh_congestion_control(&config, "/cmd_vel", &best_effort),
+            CongestionControl::Block
+        );
+        assert_eq!(
+            zenoh_congestion_control(&config, "/scan", &reliable),
+            CongestionControl::Drop
+        );
+        assert_eq!(
+            zenoh_congestion_control(&config, "/tf", &reliable),
+            CongestionControl::Drop
+        );
+    }
+
+    #[test]
+    fn test_route_reader_qos_with_preset() {
+        let config = serde_json::from_str::<Config>(
+            r#"{
+                "qos_presets": {
+                    "command": {"reliability": "best_effort", "durability": "volatile", "history": 1,
+                                "deadline": 0.1, "zenoh_reliability": "reliable", "zenoh_priority": 2}
+                },
+                "topic_qos": {
+                    "/cmd_vel": {"preset": "command", "history": 5, "deadline": 1},
+                    "/joy": {"preset": "command", "reliability": "reliable"}
+                },
+                "reliability": {".*": "best_effort"},
+                "pub_priorities": [".*=7"],
+                "dds_partition": ["prod"]
+            }"#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+        let discovered = Qos {
+            reliability: Some(Reliability {
+                kind: ReliabilityKind::RELIABLE,
+                max_blocking_time: DDS_100MS_DURATION,
+            }),
+            durability: Some(Durability {
+                kind: DurabilityKind::TRANSIENT_LOCAL,
+            }),
+            history: Some(History {
+                kind: HistoryKind::KEEP_LAST,
+                depth: 10,
+            }),
+            ..Default::default()
+        };
+
+        // the topic's fields override the preset's ones, that override the other options
+        let qos = route_reader_qos(&config, "/cmd_vel", &discovered);
+        assert_eq!(qos.reliability.unwrap().kind, ReliabilityKind::BEST_EFFORT);
+        assert_eq!(qos.durability.unwrap().kind, DurabilityKind::VOLATILE);
+        assert_eq!(
+            qos.history,
+            Some(History {
+                kind: HistoryKind::KEEP_LAST,
+                depth: 5
+            })
+        );
+        assert_eq!(
+            qos.deadline,
+            Some(Deadline {
+                period: DDS_1S_DURATION
+            })
+        );
+        assert_eq!(qos.partition, Some(vec!["prod".to_string()]));
+        assert!(!config.is_transient_local_forced("/cmd_vel"));
+        assert_eq!(
+            zenoh_congestion_control(&config, "/cmd_vel", &qos),
+            CongestionControl::Block
+        );
+        assert_eq!(
+            config.get_pub_priorities("/cmd_vel"),
+            Some(Priority::InteractiveHigh)
+        );
+
+        let qos = route_reader_qos(&config, "/joy", &discovered);
+        assert_eq!(qos.reliability.unwrap().kind, ReliabilityKind::RELIABLE);
+        assert_eq!(qos.history.unwrap().depth, 1);
+        assert_eq!(qos.deadline.unwrap().period, DDS_100MS_DURATION);
+
+        // a topic without `topic_qos` entry is unchanged, except by the other options
+        let qos = route_reader_qos(&config, "/scan", &discovered);
+        assert_eq!(qos.reliability.unwrap().kind, ReliabilityKind::BEST_EFFORT);
+        assert_eq!(qos.durability, discovered.durability);
+        assert_eq!(qos.history, discovered.history);
+        assert_eq!(qos.deadline, None);
+        assert_eq!(
+            config.get_pub_priorities("/scan"),
+            Some(Priority::Background)
+        );
     }
 }
@@ -13,32 +13,48 @@
 //
 
 use cyclors::dds_entity_t;
-use serde::Serialize;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
-use std::{collections::HashSet, fmt};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 use zenoh::buffers::{ZBuf, ZSlice};
 use zenoh::handlers::{Callback, Dyn};
 use zenoh::liveliness::LivelinessToken;
 use zenoh::prelude::r#async::AsyncResolve;
 use zenoh::prelude::*;
 use zenoh::query::Reply;
-use zenoh_core::SyncResolve;
+use zenoh::subscriber::Subscriber;
+use zenoh_core::{zlock, SyncResolve};
 
+use crate::concurrency_limit::{serialize_concurrency_limit, ConcurrencyLimit, Task};
+use crate::config::{ServiceRetryPolicy, ServiceUnavailablePolicy};
 use crate::dds_types::{DDSRawSample, TypeInfo};
+use crate::dds_utils::DDS_ENTITY_NULL;
 use crate::dds_utils::{
     create_dds_reader, create_dds_writer, dds_write, delete_dds_entity, get_guid,
-    serialize_atomic_entity_guid, AtomicDDSEntity,
+    get_matched_publication_guid, serialize_atomic_entity_guid, AtomicDDSEntity,
 };
-use crate::dds_utils::{is_cdr_little_endian, DDS_ENTITY_NULL};
+use crate::gid::Gid;
 use crate::liveliness_mgt::new_ke_liveliness_service_cli;
+use crate::node_info::ServiceCliEntities;
+use crate::qos_helpers::apply_partition;
 use crate::ros2_utils::{
-    is_service_for_action, new_service_id, ros2_service_type_to_reply_dds_type,
-    ros2_service_type_to_request_dds_type, CddsRequestHeader, QOS_DEFAULT_SERVICE,
+    add_caller_to_attachment, add_idempotency_key_to_attachment, add_long_call_to_attachment,
+    add_timeout_to_attachment, dds_topic_name, insert_request_header, is_service_for_action,
+    long_call_reply_key_expr, new_service_id, ros2_service_type_to_reply_dds_type,
+    ros2_service_type_to_request_dds_type, split_request_header, CddsRequestHeader,
+    QOS_DEFAULT_SERVICE,
 };
+use crate::route_events::{RouteEvent, RouteEventKind};
+use crate::route_stats::{RouteStats, RouteStatsSnapshot};
 use crate::routes_mgr::Context;
-use crate::LOG_PAYLOAD;
+use crate::service_metrics::{serialize_service_metrics, ServiceMetrics};
+use crate::worker_pool::{serialize_reply_dispatch, ReplyDispatch};
+use crate::{serialize_atomic_bool, LOG_PAYLOAD};
 
 // a route for a Service Client exposed in Zenoh as a Queryier
 #[allow(clippy::upper_case_acronyms)]
@@ -58,6 +74,9 @@ pub struct RouteServiceCli<'a> {
     context: Context,
     #[serde(serialize_with = "crate::config::serialize_duration_as_f32")]
     queries_timeout: Duration,
+    // the maximum duration of the calls, replied after the queries timeout (configured in `long_calls`)
+    #[serde(skip)]
+    long_call: Option<Duration>,
     is_active: bool,
     // the local DDS Reader receiving client's requests and routing them to Zenoh
     #[serde(serialize_with = "serialize_atomic_entity_guid")]
@@ -65,6 +84,25 @@ pub struct RouteServiceCli<'a> {
     // the local DDS Writer sending replies to the client
     #[serde(serialize_with = "serialize_atomic_entity_guid")]
     rep_writer: Arc<AtomicDDSEntity>,
+    // the statistics of the requests routed from DDS to Zenoh and of the replies routed back
+    #[serde(skip)]
+    stats: Arc<RouteStats>,
+    // the calls that failed (timed out or replied with an error), and the error replies sent for them
+    #[serde(serialize_with = "serialize_call_failures")]
+    failures: Arc<CallFailures>,
+    // the calls forwarded to Zenoh, replied or timed out, and their latency until the reply is written to DDS
+    #[serde(serialize_with = "serialize_service_metrics")]
+    calls: Arc<ServiceMetrics>,
+    // the calls in progress, and the ones waiting beyond `max_concurrent_requests`
+    #[serde(serialize_with = "serialize_concurrency_limit")]
+    concurrency: Arc<ConcurrencyLimit<Task>>,
+    // the dispatch of the replies to the heavy Services workers (if `heavy_services` is configured)
+    #[serde(serialize_with = "serialize_reply_dispatch")]
+    heavy_services: Option<Arc<ReplyDispatch>>,
+    // true while a remote Service Server is announced (i.e. some remote routes are served).
+    // Without, the calls fail fast according to `on_service_unavailable` (if configured)
+    #[serde(serialize_with = "serialize_atomic_bool")]
+    server_available: Arc<AtomicBool>,
     // a liveliness token associated to this route, for announcement to other plugins
     #[serde(skip)]
     liveliness_token: Option<LivelinessToken<'a>>,
@@ -103,6 +141,41 @@ impl RouteServiceCli<'_> {
         tracing::debug!(
             "Route Service Client (ROS:{ros2_name} <-> Zenoh:{zenoh_key_expr}): creation with type {ros2_type}"
         );
+        let stats = Arc::new(RouteStats::new(context.config.get_stats_rate_half_life()));
+        stats.on_event(
+            RouteEventKind::Created,
+            format!("created with type {ros2_type} (Zenoh key: {zenoh_key_expr})"),
+        );
+        let failures = Arc::new(CallFailures::new(
+            context
+                .config
+                .get_service_error_reply(&ros2_name)
+                .map(<[u8]>::to_vec),
+            context.config.get_service_retry_policy(&ros2_name).copied(),
+            context.config.get_service_unavailable_policy(&ros2_name),
+        ));
+        if failures.unavailable == Some(ServiceUnavailablePolicy::ErrorReply)
+            && failures.error_reply.is_none()
+        {
+            tracing::warn!(
+                "Route Service Client (ROS:{ros2_name} <-> Zenoh:{zenoh_key_expr}): 'on_service_unavailable' is \"error_reply\" but no Response is configured in 'service_error_replies' - the calls without Server will get no reply"
+            );
+        }
+        let long_call = context.config.get_long_call_duration(&ros2_name);
+        let concurrency = Arc::new(ConcurrencyLimit::new(
+            context.config.get_max_concurrent_requests(&ros2_name),
+        ));
+        let heavy_services = context
+            .heavy_services_pool
+            .as_ref()
+            .zip(context.config.heavy_services.as_ref())
+            .map(|(pool, heavy)| {
+                Arc::new(ReplyDispatch::new(
+                    pool.clone(),
+                    heavy.is_heavy_service(&ros2_name),
+                    heavy.reply_size,
+                ))
+            });
         Ok(RouteServiceCli {
             ros2_name,
             ros2_type,
@@ -110,9 +183,16 @@ impl RouteServiceCli<'_> {
             type_info,
             context,
             queries_timeout,
+            long_call,
             is_active: false,
             rep_writer: Arc::new(DDS_ENTITY_NULL.into()),
             req_reader: Arc::new(DDS_ENTITY_NULL.into()),
+            stats,
+            failures,
+            calls: Arc::new(ServiceMetrics::default()),
+            concurrency,
+            heavy_services,
+            server_available: Arc::new(AtomicBool::new(false)),
             liveliness_token: None,
             remote_routes: HashSet::new(),
             local_nodes: HashSet::new(),
@@ -154,10 +234,21 @@ impl RouteServiceCli<'_> {
         self.liveliness_token = None;
     }
 
+    /// Re-assert the route announcement over Zenoh (e.g. after a reconnection), if announced.
+    pub async fn restore_announcement(&mut self) -> Result<(), String> {
+        if self.is_serving_local_node() {
+            self.announce_route().await?;
+        }
+        Ok(())
+    }
+
     fn activate(&mut self) -> Result<(), String> {
         tracing::debug!("{self}: activate");
-        // Default Service QoS
-        let mut qos = QOS_DEFAULT_SERVICE.clone();
+        // Default Service QoS, with the DDS Partitions configured for this Service, if any
+        let mut qos = apply_partition(
+            &QOS_DEFAULT_SERVICE,
+            self.context.config.get_dds_partition(&self.ros2_name),
+        );
 
         // Add DATA_USER QoS similarly to rmw_cyclone_dds here:
         // https://github.com/ros2/rmw_cyclonedds/blob/2263814fab142ac19dd3395971fb1f358d22a653/rmw_cyclonedds_cpp/src/rmw_node.cpp#L5028C17-L5028C17
@@ -169,8 +260,12 @@ impl RouteServiceCli<'_> {
             qos.user_data.as_ref().unwrap()
         );
 
+        // DDS topic names (checked before any DDS entity creation)
+        let long_name_policy = self.context.config.long_name_policy;
+        let rep_topic_name = dds_topic_name("rr", &self.ros2_name, "Reply", long_name_policy)?;
+        let req_topic_name = dds_topic_name("rq", &self.ros2_name, "Request", long_name_policy)?;
+
         // create DDS Writer to send replies coming from Zenoh to the Client
-        let rep_topic_name = format!("rr{}Reply", self.ros2_name);
         let rep_type_name = ros2_service_type_to_reply_dds_type(&self.ros2_type);
         let rep_writer = create_dds_writer(
             self.context.participant,
@@ -195,12 +290,26 @@ impl RouteServiceCli<'_> {
             .add_dds_writer(get_guid(&rep_writer)?);
 
         // create DDS Reader to receive requests and route them to Zenoh
-        let route_id: String = self.to_string();
-        let req_topic_name = format!("rq{}Request", self.ros2_name);
         let req_type_name = ros2_service_type_to_request_dds_type(&self.ros2_type);
-        let zenoh_key_expr2 = self.zenoh_key_expr.clone();
-        let zsession2 = self.context.zsession.clone();
-        let queries_timeout = self.queries_timeout;
+        let ctx = Arc::new(ServiceCallCtx {
+            route_id: self.to_string(),
+            zenoh_key_expr: self.zenoh_key_expr.clone(),
+            zsession: self.context.zsession.clone(),
+            // this bridge's identity for the remote Service Servers to identify the callers
+            bridge_id: match &self.context.config.alias {
+                Some(alias) => alias.as_str().into(),
+                None => self.context.plugin_id.as_str().into(),
+            },
+            query_timeout: self.queries_timeout,
+            long_call: self.long_call,
+            server_available: self.server_available.clone(),
+            stats: self.stats.clone(),
+            failures: self.failures.clone(),
+            calls: self.calls.clone(),
+            concurrency: self.concurrency.clone(),
+            heavy_services: self.heavy_services.clone(),
+        });
+        let req_reader2 = self.req_reader.clone();
         let req_reader = create_dds_reader(
             self.context.participant,
             req_topic_name,
@@ -209,16 +318,8 @@ impl RouteServiceCli<'_> {
             true,
             qos,
             None,
-            move |sample| {
-                route_dds_request_to_zenoh(
-                    &route_id,
-                    sample,
-                    &zenoh_key_expr2,
-                    &zsession2,
-                    queries_timeout,
-                    rep_writer,
-                );
-            },
+            false,
+            move |sample| route_dds_request_to_zenoh(&ctx, sample, &req_reader2, rep_writer),
         )?;
         let old = self.req_reader.swap(req_reader, Ordering::Relaxed);
         if old != DDS_ENTITY_NULL {
@@ -277,7 +378,8 @@ impl RouteServiceCli<'_> {
         //       and will drop it, leading the Client to hang (see #62).
         // TODO: rather rely on a Querier MatchingStatus (in the same way that it's done for RoutePublisher)
         //       when available in zenoh...
-        if self.remote_routes.len() == 1 {
+        self.server_available.store(true, Ordering::Relaxed);
+        if !self.is_active {
             if let Err(e) = self.activate() {
                 tracing::error!("{self}: activation failed: {e}");
             }
@@ -290,8 +392,14 @@ impl RouteServiceCli<'_> {
             .remove(&format!("{plugin_id}:{zenoh_key_expr}"));
         tracing::debug!("{self}: now serving remote routes {:?}", self.remote_routes);
         // if last remote node removed, deactivate the route
+        // (unless the calls are configured to fail fast without Server, the local Client still seeing it)
         if self.remote_routes.is_empty() {
-            self.deactivate();
+            self.server_available.store(false, Ordering::Relaxed);
+            // the calls in flight won't be replied by the lost Server (e.g. restarting)
+            self.failures.on_server_lost(&self.to_string());
+            if self.failures.unavailable.is_none() {
+                self.deactivate();
+            }
         }
     }
 
@@ -310,10 +418,19 @@ impl RouteServiceCli<'_> {
                 tracing::error!("{self}: announcement failed: {e}");
             }
         }
+        // with `on_service_unavailable`, the route is active even before a remote Server is announced
+        if !self.is_active && self.failures.unavailable.is_some() {
+            if let Err(e) = self.activate() {
+                tracing::error!("{self}: activation failed: {e}");
+            }
+        }
     }
 
     #[inline]
-    pub fn remove_local_node(&mut self, node: &str) {
+    pub fn remove_local_node(&mut self, node: &str, entities: &ServiceCliEntities) {
+        // the calls in flight of the lost Service Client can't be replied anymore
+        self.failures
+            .on_client_lost(&self.to_string(), entities.req_writer);
         self.local_nodes.remove(node);
         tracing::debug!("{self}: now serving local nodes {:?}", self.local_nodes);
         // if last local node removed, retire the route
@@ -331,42 +448,79 @@ impl RouteServiceCli<'_> {
     pub fn is_unused(&self) -> bool {
         !self.is_serving_local_node() && !self.is_serving_remote_route()
     }
+
+    #[inline]
+    pub fn stats(&self) -> RouteStatsSnapshot {
+        self.stats.snapshot()
+    }
+
+    #[inline]
+    pub fn events(&self) -> Vec<RouteEvent> {
+        self.stats.events()
+    }
+
+    /// Reset the statistics, the calls metrics and the failures counters of the route (via the admin space)
+    pub fn reset_stats(&self) {
+        self.stats.reset();
+        self.calls.reset();
+        self.failures.reset_counters();
+        self.stats
+            .on_event(RouteEventKind::StatsReset, "statistics reset".into());
+    }
+
+    /// Fail at once the calls in flight (via the admin space), e.g. for a remote Server that doesn't
+    /// reply anymore. Their late replies are dropped. Returns the count of calls failed.
+    pub fn flush_pending(&self) -> usize {
+        let count = self.failures.flush_pending();
+        tracing::info!("{self}: {count} calls in flight flushed");
+        self.stats.on_event(
+            RouteEventKind::PendingFlushed,
+            format!("{count} calls in flight flushed"),
+        );
+        count
+    }
+}
+
+// The parameters shared by all the calls of a Service Client route, built once on its activation
+struct ServiceCallCtx {
+    route_id: String,
+    zenoh_key_expr: OwnedKeyExpr,
+    zsession: Arc<Session>,
+    // this bridge's id or alias, identifying the caller with the request's client GUID
+    bridge_id: Arc<str>,
+    query_timeout: Duration,
+    // the maximum duration of the calls, if long calls (see `long_calls`)
+    long_call: Option<Duration>,
+    // true while a remote Service Server is announced
+    server_available: Arc<AtomicBool>,
+    stats: Arc<RouteStats>,
+    failures: Arc<CallFailures>,
+    calls: Arc<ServiceMetrics>,
+    concurrency: Arc<ConcurrencyLimit<Task>>,
+    heavy_services: Option<Arc<ReplyDispatch>>,
 }
 
 fn route_dds_request_to_zenoh(
-    route_id: &str,
+    ctx: &Arc<ServiceCallCtx>,
     sample: &DDSRawSample,
-    zenoh_key_expr: &OwnedKeyExpr,
-    zsession: &Arc<Session>,
-    query_timeout: Duration,
+    req_reader: &AtomicDDSEntity,
     rep_writer: dds_entity_t,
 ) {
+    let (route_id, stats) = (&ctx.route_id, &ctx.stats);
+    stats.on_received(sample.len());
     // request payload is expected to be the Request type encoded as CDR, including a 4 bytes header,
     // the client guid (8 bytes) and a sequence_number (8 bytes). As per rmw_cyclonedds here:
     // https://github.com/ros2/rmw_cyclonedds/blob/2263814fab142ac19dd3395971fb1f358d22a653/rmw_cyclonedds_cpp/src/serdata.hpp#L73
-    if sample.len() < 20 {
-        tracing::warn!("{route_id}: received invalid request: {sample:0x?}");
-        return;
-    }
-
-    let zbuf: ZBuf = sample.into();
-    let dds_req_buf = zbuf.contiguous();
-    let is_little_endian =
-        is_cdr_little_endian(&dds_req_buf).expect("Shouldn't happen: sample.len >= 20");
-    let request_id = CddsRequestHeader::from_slice(
-        dds_req_buf[4..20]
-            .try_into()
-            .expect("Shouldn't happen: sample.len >= 20"),
-        is_little_endian,
-    );
-
     // route request buffer stripped from request_id (client_id + sequence_number)
-    let mut zenoh_req_buf = ZBuf::empty();
-    let slice: ZSlice = dds_req_buf.into_owned().into();
-    // copy CDR Header
-    zenoh_req_buf.push_zslice(slice.subslice(0, 4).unwrap());
-    // copy Request payload, skiping client_id + sequence_number
-    zenoh_req_buf.push_zslice(slice.subslice(20, slice.len()).unwrap());
+    let slice = ZSlice::from(sample);
+    let (request_id, zenoh_req_buf) = match split_request_header(slice) {
+        Ok(split) => split,
+        Err(e) => {
+            tracing::warn!("{route_id}: received invalid request ({e}): {sample:0x?}");
+            stats.on_error();
+            return;
+        }
+    };
 
     if *LOG_PAYLOAD {
         tracing::debug!("{route_id}: routing request {request_id} from DDS to Zenoh - payload: {zenoh_req_buf:02x?}");
@@ -377,41 +531,643 @@ fn route_dds_request_to_zenoh(
         );
     }
 
-    if let Err(e) = zsession
-        .get(zenoh_key_expr)
-        .with_value(zenoh_req_buf)
-        .with_attachment(request_id.as_attachment())
-        .allowed_destination(Locality::Remote)
-        .timeout(query_timeout)
-        .with({
-            let route_id1: String = route_id.to_string();
-            let route_id2 = route_id.to_string();
-            let reply_received1 = Arc::new(AtomicBool::new(false));
-            let reply_received2 = reply_received1.clone();
-            CallbackPair {
-                callback: move |reply| {
-                        if !reply_received1.swap(true, std::sync::atomic::Ordering::Relaxed) {
-                            route_zenoh_reply_to_dds(&route_id1, reply, request_id, rep_writer)
-                        } else {
-                            tracing::warn!("{route_id1}: received more than 1 reply for request {request_id} - dropping the extra replies");
+    // the DDS Writer of the client, for its calls to be abandoned if it's lost
+    let client = get_matched_publication_guid(
+        req_reader.load(Ordering::Relaxed),
+        sample.publication_handle(),
+    )
+    .ok();
+
+    call_zenoh_service(
+        ctx,
+        zenoh_req_buf,
+        request_id,
+        // the DDS requests don't carry the timeout of their client
+        None,
+        client,
+        move |dds_rep_buf| dds_write(rep_writer, dds_rep_buf),
+    );
+}
+
+// Call the Service over Zenoh, writing its reply to the DDS client via `write_reply`
+// (or the configured error reply if the call fails, after the retries configured in `service_retries`).
+// Without Service Server available, the call fails at once if `on_service_unavailable` is configured.
+// Beyond `max_concurrent_requests` calls in progress, the call waits for one to complete before being issued.
+// With a `timeout_hint` (e.g. the deadline of the client) shorter than the route's query timeout, the query times out after it.
+// The call is abandoned if the `client` (the DDS Writer of the request, if known) is lost before its reply.
+// For a heavy Service (see `heavy_services`), the reply is written by the shared workers.
+fn call_zenoh_service<W>(
+    ctx: &Arc<ServiceCallCtx>,
+    zenoh_req_buf: ZBuf,
+    request_id: CddsRequestHeader,
+    timeout_hint: Option<Duration>,
+    client: Option<Gid>,
+    write_reply: W,
+) where
+    W: Fn(Vec<u8>) -> Result<(), String> + Send + Sync + 'static,
+{
+    let failures = &ctx.failures;
+    if !ctx.server_available.load(Ordering::Relaxed) {
+        if let Some(policy) = failures.unavailable {
+            failures.on_unavailable(&ctx.route_id, request_id, policy, &write_reply, &ctx.stats);
+            return;
+        }
+    }
+    let query_timeout = timeout_hint.map_or(ctx.query_timeout, |hint| hint.min(ctx.query_timeout));
+    let call = Arc::new(ServiceCall {
+        id: failures.next_call_id.fetch_add(1, Ordering::Relaxed),
+        ctx: ctx.clone(),
+        zenoh_req_buf,
+        request_id,
+        query_timeout,
+        client,
+        write_reply,
+        received: Instant::now(),
+        completed: AtomicBool::new(false),
+        attempt: AtomicU32::new(0),
+        reply_subscriber: Mutex::new(None),
+        admission: Mutex::new(Admission::Waiting),
+    });
+    ctx.calls.on_request();
+    // registered while waiting too, to be failed on loss of the Server or flush, or abandoned on loss of its client
+    let in_flight: Arc<dyn InFlightCall> = call.clone();
+    failures.register(call.id, Arc::downgrade(&in_flight));
+    let task: Task = Box::new(move || {
+        if !call.admit() {
+            // completed while waiting (e.g. flushed): its slot goes to the next waiting call
+            call.ctx.concurrency.on_completed();
+            return;
+        }
+        if let Some(max_duration) = call.ctx.long_call {
+            call.subscribe_long_call_reply(max_duration);
+        }
+        call.issue(0);
+    });
+    if let Some(task) = ctx.concurrency.admit(task) {
+        task();
+    }
+}
+
+// A call of the Service over Zenoh, kept to be re-issued if its query fails
+struct ServiceCall<W> {
+    // the id of the call in the route's calls in flight
+    id: u64,
+    ctx: Arc<ServiceCallCtx>,
+    zenoh_req_buf: ZBuf,
+    request_id: CddsRequestHeader,
+    // the route's query timeout, or the client's one if shorter
+    query_timeout: Duration,
+    // the DDS Writer of the client's request, if known
+    client: Option<Gid>,
+    write_reply: W,
+    // the time the request was received from DDS
+    received: Instant,
+    // set once the call is replied or failed, any later reply being dropped
+    completed: AtomicBool,
+    // the current attempt, the replies and failures of the previous (superseded) ones being ignored
+    attempt: AtomicU32,
+    // for a long call, the Subscriber to the key expression on which the reply is published
+    reply_subscriber: Mutex<Option<Subscriber<'static, ()>>>,
+    // the admission of the call by the route's ConcurrencyLimit
+    admission: Mutex<Admission>,
+}
+
+// The admission of a call, holding a slot of the route's ConcurrencyLimit only once `Admitted`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Admission {
+    Waiting,
+    Admitted,
+    // completed (e.g. failed or abandoned) before or after its admission
+    Withdrawn,
+}
+
+impl<W> ServiceCall<W>
+where
+    W: Fn(Vec<u8>) -> Result<(), String> + Send + Sync + 'static,
+{
+    // Issue the query of an attempt (0 for the first one)
+    fn issue(self: Arc<Self>, attempt: u32) {
+        let (route_id, request_id) = (&self.ctx.route_id, self.request_id);
+        let len = self.zenoh_req_buf.len();
+        let mut attachment = request_id.as_attachment();
+        add_caller_to_attachment(&mut attachment, &self.ctx.bridge_id);
+        // with retries, the remote Route Service Server returns the result of the first execution
+        // of the request to its retries, instead of invoking the DDS Service again
+        if self.ctx.failures.retry.is_some() {
+            add_idempotency_key_to_attachment(&mut attachment, request_id.as_slice());
+        }
+        // the remote Route Service Server doesn't wait for the reply longer than this query
+        match self.ctx.long_call {
+            Some(max_duration) => add_long_call_to_attachment(&mut attachment, max_duration),
+            None => add_timeout_to_attachment(&mut attachment, self.query_timeout),
+        }
+        let call: Arc<dyn InFlightCall> = self.clone();
+        self.ctx.failures.register(self.id, Arc::downgrade(&call));
+        // the query is sent with the default priority, Zenoh 0.11 not allowing to set it (see `pub_priorities`)
+        match self
+            .ctx
+            .zsession
+            .get(&self.ctx.zenoh_key_expr)
+            .with_value(self.zenoh_req_buf.clone())
+            .with_attachment(attachment)
+            .allowed_destination(Locality::Remote)
+            .timeout(self.query_timeout)
+            .with({
+                let reply_received1 = Arc::new(AtomicBool::new(false));
+                let reply_received2 = reply_received1.clone();
+                let call1 = self.clone();
+                let call2 = self.clone();
+                CallbackPair {
+                    callback: move |reply| {
+                            if !reply_received1.swap(true, std::sync::atomic::Ordering::Relaxed) {
+                                call1.route_reply(reply, attempt)
+                            } else {
+                                tracing::warn!("{}: received more than 1 reply for request {} - dropping the extra replies", call1.ctx.route_id, call1.request_id);
+                                call1.ctx.stats.on_dropped();
+                            }
+                        },
+                    drop: move || {
+                        if !reply_received2.load(std::sync::atomic::Ordering::Relaxed) {
+                            call2.clone().on_failed("no reply", attempt);
                         }
                     },
-                drop: move || {
-                    if !reply_received2.load(std::sync::atomic::Ordering::Relaxed) {
-                        // There is no way to send an error message as a reply to a ROS Service Client !
-                        // (sending an invalid message will make it crash...)
-                        // We have no choice but to log the error and let the client hanging without reply, until a timeout (if set by the client)
-                        tracing::warn!("{route_id2}: received NO reply for request {request_id} - cannot reply to client, it will hang until timeout");
+                }
+            })
+            .res_sync()
+        {
+            Ok(()) if attempt == 0 => self.ctx.stats.on_routed(len),
+            Ok(()) => (),
+            // the reply callbacks being dropped, the failure is handled as a call without reply
+            Err(e) => {
+                tracing::warn!(
+                    "{route_id}: routing request {request_id} from DDS to Zenoh failed: {e}"
+                )
+            }
+        }
+    }
+
+    // For a long call, subscribe to the reply published by the remote Route Service Server
+    // (before the query, for the Subscriber to be declared when the reply is published),
+    // until the call completes or its maximum duration expires
+    fn subscribe_long_call_reply(self: &Arc<Self>, max_duration: Duration) {
+        let reply_ke = long_call_reply_key_expr(&self.ctx.zenoh_key_expr, &self.request_id);
+        let call = self.clone();
+        match self
+            .ctx
+            .zsession
+            .declare_subscriber(reply_ke)
+            .callback(move |sample| call.dispatch_reply_payload(sample.payload))
+            .res_sync()
+        {
+            Ok(subscriber) => *zlock!(self.reply_subscriber) = Some(subscriber),
+            Err(e) => tracing::warn!(
+                "{}: failed to subscribe to the reply of long call {}: {e}",
+                self.ctx.route_id,
+                self.request_id
+            ),
+        }
+        let call = self.clone();
+        async_std::task::spawn(async move {
+            async_std::task::sleep(max_duration).await;
+            if !call.completed.load(Ordering::Relaxed) {
+                call.complete_failed(&format!("no reply within {max_duration:?}"));
+            }
+        });
+    }
+
+    // Mark the call as admitted by the route's ConcurrencyLimit, returning false if it was completed while waiting
+    fn admit(&self) -> bool {
+        let mut admission = zlock!(self.admission);
+        if *admission != Admission::Waiting {
+            return false;
+        }
+        *admission = Admission::Admitted;
+        true
+    }
+
+    // Mark the call as completed, returning false if it already was
+    fn complete(&self) -> bool {
+        if self.completed.swap(true, Ordering::Relaxed) {
+            return false;
+        }
+        self.ctx.failures.unregister(self.id);
+        // a call completed while waiting holds no slot, its queued task passing it on once run (see `admit`)
+        let admission = std::mem::replace(&mut *zlock!(self.admission), Admission::Withdrawn);
+        if admission == Admission::Admitted {
+            self.ctx.concurrency.on_completed();
+        }
+        // undeclare the Subscriber of a long call out of its own callback
+        if let Some(subscriber) = zlock!(self.reply_subscriber).take() {
+            async_std::task::spawn(async move { drop(subscriber) });
+        }
+        true
+    }
+
+    // Complete the call as failed, returning false if it already was completed
+    fn complete_failed(&self, reason: &str) -> bool {
+        if !self.complete() {
+            return false;
+        }
+        self.ctx.calls.on_timeouts(1);
+        self.ctx.failures.on_failed(
+            &self.ctx.route_id,
+            self.request_id,
+            reason,
+            &self.write_reply,
+            &self.ctx.stats,
+        );
+        true
+    }
+
+    // On failure of an attempt, re-issue the query after the backoff if a retry is left,
+    // or else handle the failure of the call
+    fn on_failed(self: Arc<Self>, reason: &str, attempt: u32) {
+        let (route_id, request_id) = (&self.ctx.route_id, self.request_id);
+        // a completed call (e.g. abandoned on loss of its client) is not retried
+        if self.completed.load(Ordering::Relaxed) {
+            tracing::debug!("{route_id}: completed call {request_id} failed ({reason}) - ignored");
+            return;
+        }
+        // the failure of an attempt is handled once, superseding it
+        // (e.g. an attempt failed at once on loss of the Server, and later timing out)
+        if self
+            .attempt
+            .compare_exchange(attempt, attempt + 1, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            tracing::debug!(
+                "{route_id}: superseded attempt {attempt} of call {request_id} failed ({reason}) - ignored"
+            );
+            return;
+        }
+        match self.ctx.failures.retry {
+            Some(retry) if attempt < retry.retries => {
+                let backoff = retry.get_retry_backoff(attempt);
+                tracing::debug!(
+                    "{route_id}: call {request_id} failed ({reason}) - retrying in {backoff:?}"
+                );
+                self.ctx.failures.retried.fetch_add(1, Ordering::Relaxed);
+                async_std::task::spawn(async move {
+                    async_std::task::sleep(backoff).await;
+                    if !self.completed.load(Ordering::Relaxed) {
+                        self.issue(attempt + 1);
                     }
-                },
+                });
+            }
+            _ => {
+                self.complete_failed(reason);
+            }
+        }
+    }
+
+    fn route_reply(self: &Arc<Self>, reply: Reply, attempt: u32) {
+        let (route_id, request_id, stats) = (&self.ctx.route_id, self.request_id, &self.ctx.stats);
+        match reply.sample {
+            // a late reply to a superseded attempt (e.g. from a lost Server), not to be mismatched
+            // with the reply to the current one
+            Ok(sample) if attempt < self.attempt.load(Ordering::Acquire) => {
+                tracing::debug!(
+                    "{route_id}: reply to superseded attempt {attempt} of call {request_id} - dropping it"
+                );
+                stats.on_received(sample.payload.len());
+                stats.on_dropped();
+            }
+            // a long call accepted by the remote Route Service Server, to be replied on its reply key expression
+            Ok(sample) if self.ctx.long_call.is_some() && sample.payload.is_empty() => {
+                tracing::debug!("{route_id}: long call {request_id} accepted");
+            }
+            Ok(sample) => self.dispatch_reply_payload(sample.payload),
+            Err(val) => {
+                // e.g. the query timed out (see `queries_timeout`)
+                stats.on_received(val.payload.len());
+                self.clone()
+                    .on_failed(&format!("error reply: {val}"), attempt);
+            }
+        }
+    }
+
+    // Route the reply to DDS, by the shared workers if for a heavy Service (see `heavy_services`)
+    fn dispatch_reply_payload(self: &Arc<Self>, payload: ZBuf) {
+        let Some(heavy_services) = &self.ctx.heavy_services else {
+            return self.route_reply_payload(&payload.contiguous());
+        };
+        let call = self.clone();
+        heavy_services.dispatch(
+            payload.len(),
+            Box::new(move || call.route_reply_payload(&payload.contiguous())),
+        );
+    }
+
+    fn route_reply_payload(&self, zenoh_rep_buf: &[u8]) {
+        let (route_id, request_id, stats) = (&self.ctx.route_id, self.request_id, &self.ctx.stats);
+        stats.on_received(zenoh_rep_buf.len());
+        // route reply buffer re-inserting request_id (client_id + sequence_number)
+        let dds_rep_buf = match insert_request_header(zenoh_rep_buf, &request_id) {
+            Ok(buf) => buf,
+            Err(e) => {
+                tracing::warn!(
+                    "{route_id}: received invalid reply from Zenoh for {request_id} ({e}): {zenoh_rep_buf:0x?}"
+                );
+                stats.on_error();
+                // the call fails, not to hold its slot until the timeout of a query already replied
+                self.complete_failed(&format!("invalid reply ({e})"));
+                return;
+            }
+        };
+        if !self.complete() {
+            tracing::debug!("{route_id}: call {request_id} already completed - dropping its reply");
+            stats.on_dropped();
+            return;
+        }
+
+        if *LOG_PAYLOAD {
+            tracing::debug!("{route_id}: routing reply for {request_id} from Zenoh to DDS - payload: {dds_rep_buf:02x?}");
+        } else {
+            tracing::trace!(
+                "{route_id}: routing reply for {request_id} from Zenoh to DDS - {} bytes",
+                dds_rep_buf.len()
+            );
+        }
+
+        let len = dds_rep_buf.len();
+        match (self.write_reply)(dds_rep_buf) {
+            Ok(()) => {
+                stats.on_routed(len);
+                self.ctx.calls.on_reply(self.received.elapsed());
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "{route_id}: routing reply for {request_id} from Zenoh to DDS failed: {e}"
+                );
+                stats.on_error();
+            }
+        }
+    }
+}
+
+impl<W> InFlightCall for ServiceCall<W>
+where
+    W: Fn(Vec<u8>) -> Result<(), String> + Send + Sync + 'static,
+{
+    fn on_server_lost(self: Arc<Self>) -> bool {
+        if self.completed.load(Ordering::Relaxed) {
+            return false;
+        }
+        if *zlock!(self.admission) == Admission::Waiting {
+            // not issued yet: with retries it waits to be issued (e.g. to the restarted Server), or else fails at once
+            if self.ctx.failures.retry.is_some() {
+                let call: Arc<dyn InFlightCall> = self.clone();
+                self.ctx.failures.register(self.id, Arc::downgrade(&call));
+                return false;
             }
+            return self.complete_failed("Service Server lost");
+        }
+        let attempt = self.attempt.load(Ordering::Acquire);
+        self.on_failed("Service Server lost", attempt);
+        true
+    }
+
+    fn client(&self) -> Option<Gid> {
+        self.client
+    }
+
+    fn on_client_lost(&self) -> bool {
+        // neither the reply nor an error reply will be written to the lost client
+        self.complete()
+    }
+
+    fn on_flushed(&self) -> bool {
+        self.complete_failed("flushed via the admin space")
+    }
+}
+
+// A call in flight (i.e. its query is pending, or it waits for its admission), failed or retried at once
+// on loss of the Service Server, abandoned on loss of its client, and failed at once when flushed via the admin space
+trait InFlightCall: Send + Sync {
+    // Return false if the call was already completed, or is kept waiting for its admission
+    fn on_server_lost(self: Arc<Self>) -> bool;
+    fn client(&self) -> Option<Gid>;
+    // Return false if the call was already completed
+    fn on_client_lost(&self) -> bool;
+    // Return false if the call was already completed
+    fn on_flushed(&self) -> bool;
+}
+
+/// The handling of the failed calls of a Service Client: timed out, or replied with an error by Zenoh.
+/// With `service_retries`, a failed query is first re-issued up to the configured count of retries.
+/// There is no way to send an error as a reply to a ROS Service Client (sending an invalid message would
+/// make it crash). By default the client is left hanging without reply, until its own timeout (if any).
+/// With `service_error_replies`, it's sent the configured Response instead (e.g. with a failure status),
+/// and the failure is recorded as a route event.
+/// With `on_service_unavailable`, the calls made while no remote Server is announced fail at once.
+/// On loss of the remote Server (e.g. restarting), the calls in flight fail (or are retried) at once,
+/// the late replies to their superseded queries being dropped.
+/// On loss of a local Client (e.g. its Node died), its calls in flight are abandoned: their queries
+/// can't be cancelled with Zenoh 0.11, but their replies are dropped without writing to DDS.
+struct CallFailures {
+    // the Response payload (with its CDR header) replied on failure, if configured
+    error_reply: Option<Vec<u8>>,
+    retry: Option<ServiceRetryPolicy>,
+    unavailable: Option<ServiceUnavailablePolicy>,
+    failed: AtomicU64,
+    error_replies: AtomicU64,
+    retried: AtomicU64,
+    // the calls failed at once for lack of Server (also counted in `failed` if replied an error)
+    unavailable_calls: AtomicU64,
+    // the calls in flight, by id
+    in_flight: Mutex<HashMap<u64, Weak<dyn InFlightCall>>>,
+    next_call_id: AtomicU64,
+    // the calls in flight on loss of the Server (then counted in `retried` or `failed`)
+    server_lost: AtomicU64,
+    // the calls in flight abandoned on loss of their Client
+    abandoned: AtomicU64,
+}
+
+impl CallFailures {
+    fn new(
+        error_reply: Option<Vec<u8>>,
+        retry: Option<ServiceRetryPolicy>,
+        unavailable: Option<ServiceUnavailablePolicy>,
+    ) -> CallFailures {
+        CallFailures {
+            error_reply,
+            retry,
+            unavailable,
+            failed: AtomicU64::new(0),
+            error_replies: AtomicU64::new(0),
+            retried: AtomicU64::new(0),
+            unavailable_calls: AtomicU64::new(0),
+            in_flight: Mutex::new(HashMap::new()),
+            next_call_id: AtomicU64::new(0),
+            server_lost: AtomicU64::new(0),
+            abandoned: AtomicU64::new(0),
+        }
+    }
+
+    fn register(&self, id: u64, call: Weak<dyn InFlightCall>) {
+        zlock!(self.in_flight).insert(id, call);
+    }
+
+    fn unregister(&self, id: u64) {
+        zlock!(self.in_flight).remove(&id);
+    }
+
+    /// On loss of the remote Service Server, fail (or retry) at once all the calls in flight,
+    /// rather than leaving them until their queries timeout
+    fn on_server_lost(&self, route_id: &str) {
+        let calls: Vec<Arc<dyn InFlightCall>> = zlock!(self.in_flight)
+            .drain()
+            .filter_map(|(_, call)| call.upgrade())
+            .collect();
+        let lost = calls
+            .into_iter()
+            .filter(|call| call.clone().on_server_lost())
+            .count();
+        if lost > 0 {
+            tracing::info!("{route_id}: Service Server lost - failed {lost} calls in flight");
+            self.server_lost.fetch_add(lost as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// On loss of a local Service Client, abandon its calls in flight rather than
+    /// routing their replies to a DDS Reader that doesn't exist anymore
+    fn on_client_lost(&self, route_id: &str, client: Gid) {
+        let mut calls: Vec<Arc<dyn InFlightCall>> = Vec::new();
+        zlock!(self.in_flight).retain(|_, call| match call.upgrade() {
+            Some(call) if call.client() == Some(client) => {
+                calls.push(call);
+                false
+            }
+            Some(_) => true,
+            None => false,
+        });
+        let abandoned = calls.iter().filter(|call| call.on_client_lost()).count();
+        if abandoned > 0 {
+            tracing::debug!(
+                "{route_id}: Service Client {client} lost - abandoning {abandoned} calls in flight"
+            );
+            self.abandoned
+                .fetch_add(abandoned as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Fail at once all the calls in flight and the ones waiting for their admission (with the configured
+    /// error reply, if any) rather than waiting for their replies or their timeout, returning their count
+    fn flush_pending(&self) -> usize {
+        let calls: Vec<Arc<dyn InFlightCall>> = zlock!(self.in_flight)
+            .drain()
+            .filter_map(|(_, call)| call.upgrade())
+            .collect();
+        calls.iter().filter(|call| call.on_flushed()).count()
+    }
+
+    fn reset_counters(&self) {
+        for counter in [
+            &self.failed,
+            &self.error_replies,
+            &self.retried,
+            &self.unavailable_calls,
+            &self.server_lost,
+            &self.abandoned,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    fn on_unavailable<W>(
+        &self,
+        route_id: &str,
+        request_id: CddsRequestHeader,
+        policy: ServiceUnavailablePolicy,
+        write_reply: &W,
+        stats: &RouteStats,
+    ) where
+        W: Fn(Vec<u8>) -> Result<(), String>,
+    {
+        self.unavailable_calls.fetch_add(1, Ordering::Relaxed);
+        match policy {
+            ServiceUnavailablePolicy::ErrorReply => self.on_failed(
+                route_id,
+                request_id,
+                "no Service Server available",
+                write_reply,
+                stats,
+            ),
+            ServiceUnavailablePolicy::Drop => {
+                tracing::debug!(
+                    "{route_id}: no Service Server available - dropping request {request_id}"
+                );
+                stats.on_dropped();
+            }
+        }
+    }
+
+    /// The reply to a failed call, if an error reply is configured
+    fn error_reply(&self, request_id: CddsRequestHeader) -> Option<Vec<u8>> {
+        self.error_reply.as_ref().map(|payload| {
+            insert_request_header(payload, &request_id)
+                .expect("Shouldn't happen: the error replies are checked by the configuration")
         })
-        .res_sync()
+    }
+
+    fn on_failed<W>(
+        &self,
+        route_id: &str,
+        request_id: CddsRequestHeader,
+        reason: &str,
+        write_reply: &W,
+        stats: &RouteStats,
+    ) where
+        W: Fn(Vec<u8>) -> Result<(), String>,
     {
-        tracing::warn!("{route_id}: routing request {request_id} from DDS to Zenoh failed: {e}");
+        self.failed.fetch_add(1, Ordering::Relaxed);
+        stats.on_error();
+        stats.on_event(RouteEventKind::CallFailed, format!("call failed: {reason}"));
+        match self.error_reply(request_id) {
+            Some(reply) => {
+                tracing::warn!(
+                    "{route_id}: call {request_id} failed ({reason}) - sending the configured error reply"
+                );
+                match write_reply(reply) {
+                    Ok(()) => {
+                        self.error_replies.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Err(e) => {
+                        tracing::warn!("{route_id}: sending error reply for {request_id} failed: {e}")
+                    }
+                }
+            }
+            None => tracing::warn!(
+                "{route_id}: call {request_id} failed ({reason}) - cannot reply to client, it will hang until timeout"
+            ),
+        }
     }
 }
 
+fn serialize_call_failures<S>(
+    failures: &Arc<CallFailures>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut s = serializer.serialize_struct("CallFailures", 7)?;
+    s.serialize_field("retried", &failures.retried.load(Ordering::Relaxed))?;
+    s.serialize_field(
+        "unavailable",
+        &failures.unavailable_calls.load(Ordering::Relaxed),
+    )?;
+    s.serialize_field("server_lost", &failures.server_lost.load(Ordering::Relaxed))?;
+    s.serialize_field("abandoned", &failures.abandoned.load(Ordering::Relaxed))?;
+    s.serialize_field("failed", &failures.failed.load(Ordering::Relaxed))?;
+    s.serialize_field("error_reply", &failures.error_reply.is_some())?;
+    s.serialize_field(
+        "error_replies",
+        &failures.error_replies.load(Ordering::Relaxed),
+    )?;
+    s.end()
+}
+
 // TODO: remove and replace with Zenoh's CallbackPair when https://github.com/eclipse-zenoh/zenoh/pull/653 is available
 struct CallbackPair<Callback, DropFn>
 where
@@ -442,47 +1198,1241 @@ where
     }
 }
 
-fn route_zenoh_reply_to_dds(
-    route_id: &str,
-    reply: Reply,
-    request_id: CddsRequestHeader,
-    rep_writer: dds_entity_t,
-) {
-    match reply.sample {
-        Ok(sample) => {
-            let zenoh_rep_buf = sample.payload.contiguous();
-            if zenoh_rep_buf.len() < 4 || zenoh_rep_buf[1] > 1 {
-                tracing::warn!(
-                    "{route_id}: received invalid reply from Zenoh for {request_id}: {zenoh_rep_buf:0x?}"
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+    use zenoh::buffers::ZBuf;
+    use zenoh::prelude::r#async::*;
+    use zenoh::queryable::Query;
+    use zenoh::Session;
+    use zenoh_core::SyncResolve;
+
+    use super::{call_zenoh_service, CallFailures, ServiceCallCtx};
+    use crate::concurrency_limit::{ConcurrencyLimit, Task};
+    use crate::config::Config;
+    use crate::gid::Gid;
+    use crate::ros2_utils::{
+        caller_from_attachment, idempotency_key_from_attachment, long_call_from_attachment,
+        long_call_reply_key_expr, timeout_from_attachment, CddsRequestHeader,
+    };
+    use crate::route_stats::RouteStats;
+    use crate::service_metrics::ServiceMetrics;
+    use crate::test_utils::session_pair;
+    use crate::worker_pool::{ReplyDispatch, WorkerPool};
+
+    // The context of the calls of a test route to the Service `key_expr` via `zsession`, with a Server
+    // available and the default configuration (to be overridden with the struct update syntax)
+    fn call_ctx(
+        zsession: &Arc<Session>,
+        key_expr: &str,
+        query_timeout: Duration,
+    ) -> ServiceCallCtx {
+        ServiceCallCtx {
+            route_id: "test".into(),
+            zenoh_key_expr: OwnedKeyExpr::try_from(key_expr).unwrap(),
+            zsession: zsession.clone(),
+            bridge_id: Arc::from("robot1"),
+            query_timeout,
+            long_call: None,
+            server_available: Arc::new(AtomicBool::new(true)),
+            stats: Arc::default(),
+            failures: Arc::new(CallFailures::new(None, None, None)),
+            calls: Arc::default(),
+            concurrency: Arc::default(),
+            heavy_services: None,
+        }
+    }
+
+    #[test]
+    fn test_error_reply_payload() {
+        let request_id = CddsRequestHeader::create(0x0102, 7, true);
+        assert_eq!(
+            CallFailures::new(None, None, None).error_reply(request_id),
+            None
+        );
+        // a std_srvs/srv/Trigger Response with success=false and an empty message
+        let response = [0u8, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+        let reply = CallFailures::new(Some(response.to_vec()), None, None)
+            .error_reply(request_id)
+            .unwrap();
+        assert_eq!(reply.len(), response.len() + 16);
+        assert_eq!(&reply[..4], &response[..4]);
+        assert_eq!(&reply[4..20], request_id.as_slice());
+        assert_eq!(&reply[20..], &response[4..]);
+    }
+
+    #[async_std::test]
+    async fn test_never_answering_service() {
+        let config = serde_json::from_str::<Config>(
+            r#"{
+                "queries_timeout": { "services": { "/get_state": 0.3, ".*": 0.5 } },
+                "service_error_replies": { "/get_state": "00010000000000000100000000" }
+            }"#,
+        )
+        .unwrap();
+
+        // the remote Service Server keeps the queries without ever replying
+        let (server, bridge) = session_pair().await;
+        let bridge = Arc::new(bridge);
+        let pending_queries = Arc::new(Mutex::new(Vec::<Query>::new()));
+        let _queryable = {
+            let pending_queries = pending_queries.clone();
+            server
+                .declare_queryable("**")
+                .callback(move |query: Query| pending_queries.lock().unwrap().push(query))
+                .res_async()
+                .await
+                .unwrap()
+        };
+        async_std::task::sleep(Duration::from_millis(500)).await;
+
+        let call = |ros2_name: &str| {
+            let (tx, rx) = flume::unbounded();
+            let stats = Arc::new(RouteStats::default());
+            let failures = Arc::new(CallFailures::new(
+                config
+                    .get_service_error_reply(ros2_name)
+                    .map(<[u8]>::to_vec),
+                None,
+                None,
+            ));
+            let timeout = config.get_queries_timeout_service(ros2_name);
+            let request_id = CddsRequestHeader::create(42, 1, true);
+            call_zenoh_service(
+                &Arc::new(ServiceCallCtx {
+                    stats: stats.clone(),
+                    failures: failures.clone(),
+                    ..call_ctx(&bridge, &ros2_name[1..], timeout)
+                }),
+                ZBuf::from(vec![0u8, 1, 0, 0, 0]),
+                request_id,
+                None,
+                None,
+                move |reply| tx.send((reply, Instant::now())).map_err(|e| e.to_string()),
+            );
+            (rx, stats, failures, request_id, timeout)
+        };
+
+        // with an error reply configured, the client gets it once the timeout expired
+        let start = Instant::now();
+        let (rx, stats, failures, request_id, timeout) = call("/get_state");
+        let (reply, received) = rx.recv_async().await.unwrap();
+        let elapsed = received - start;
+        assert!(elapsed >= timeout, "replied after {elapsed:?}");
+        assert!(
+            elapsed < timeout + Duration::from_millis(300),
+            "replied after {elapsed:?}"
+        );
+        assert_eq!(&reply[4..20], request_id.as_slice());
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 1);
+        assert_eq!(failures.error_replies.load(Ordering::Relaxed), 1);
+        assert_eq!(stats.snapshot().errors, 1);
+        assert_eq!(stats.events().len(), 1);
+
+        // without error reply, the failure is only counted
+        let (rx, stats, failures, _, timeout) = call("/load_map");
+        async_std::task::sleep(timeout + Duration::from_millis(300)).await;
+        assert!(rx.try_recv().is_err());
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 1);
+        assert_eq!(failures.error_replies.load(Ordering::Relaxed), 0);
+        assert_eq!(stats.snapshot().errors, 1);
+        assert!(!pending_queries.lock().unwrap().is_empty());
+    }
+
+    #[async_std::test]
+    async fn test_timeout_hint() {
+        // the remote Service Server keeps the queries without ever replying
+        let (server, bridge) = session_pair().await;
+        let bridge = Arc::new(bridge);
+        let timeouts = Arc::new(Mutex::new(Vec::<Option<Duration>>::new()));
+        let pending_queries = Arc::new(Mutex::new(Vec::<Query>::new()));
+        let _queryable = {
+            let timeouts = timeouts.clone();
+            let pending_queries = pending_queries.clone();
+            server
+                .declare_queryable("**")
+                .callback(move |query: Query| {
+                    timeouts
+                        .lock()
+                        .unwrap()
+                        .push(query.attachment().and_then(timeout_from_attachment));
+                    pending_queries.lock().unwrap().push(query);
+                })
+                .res_async()
+                .await
+                .unwrap()
+        };
+        async_std::task::sleep(Duration::from_millis(500)).await;
+
+        let (tx, rx) = flume::unbounded();
+        let stats = Arc::new(RouteStats::default());
+        let failures = Arc::new(CallFailures::new(Some(vec![0u8, 1, 0, 0, 0]), None, None));
+        let start = Instant::now();
+        call_zenoh_service(
+            &Arc::new(ServiceCallCtx {
+                stats: stats.clone(),
+                failures: failures.clone(),
+                ..call_ctx(&bridge, "get_state", Duration::from_secs(5))
+            }),
+            ZBuf::from(vec![0u8, 1, 0, 0, 0]),
+            CddsRequestHeader::create(42, 1, true),
+            Some(Duration::from_millis(300)),
+            None,
+            move |reply| tx.send((reply, Instant::now())).map_err(|e| e.to_string()),
+        );
+
+        // the client's timeout shorter than the queries timeout wins
+        let (_, received) = async_std::future::timeout(Duration::from_secs(2), rx.recv_async())
+            .await
+            .unwrap()
+            .unwrap();
+        let elapsed = received - start;
+        assert!(
+            elapsed >= Duration::from_millis(300),
+            "failed after {elapsed:?}"
+        );
+        assert!(elapsed < Duration::from_secs(1), "failed after {elapsed:?}");
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 1);
+        // the call is not in flight anymore
+        assert!(failures.in_flight.lock().unwrap().is_empty());
+        // the remote Service Server is told the timeout of the query
+        assert_eq!(
+            *timeouts.lock().unwrap(),
+            vec![Some(Duration::from_millis(300))]
+        );
+    }
+
+    #[async_std::test]
+    async fn test_heavy_service_reply() {
+        let (server, bridge) = session_pair().await;
+        let bridge = Arc::new(bridge);
+        let _queryable = server
+            .declare_queryable("**")
+            .callback(|query: Query| {
+                let ke = query.key_expr().clone();
+                let payload = if ke.as_str() == "get_map" {
+                    let mut payload = vec![0u8, 1, 0, 0];
+                    payload.resize(4 + 100_000, 7);
+                    payload
+                } else {
+                    vec![0u8, 1, 0, 0, 1]
+                };
+                query
+                    .reply(Ok(Sample::new(ke, payload)))
+                    .res_sync()
+                    .unwrap();
+            })
+            .res_async()
+            .await
+            .unwrap();
+        async_std::task::sleep(Duration::from_millis(500)).await;
+
+        // the replies of the Services detected as heavy (beyond 10000 bytes) are written by the workers
+        let pool = Arc::new(WorkerPool::new("test-heavy", 1, 4));
+        let (tx, rx) = flume::unbounded::<(String, usize, String)>();
+        let call = |service: &'static str, seq_num: u64, dispatch: &Arc<ReplyDispatch>| {
+            let tx = tx.clone();
+            call_zenoh_service(
+                &Arc::new(ServiceCallCtx {
+                    heavy_services: Some(dispatch.clone()),
+                    ..call_ctx(&bridge, service, Duration::from_secs(2))
+                }),
+                ZBuf::from(vec![0u8, 1, 0, 0, 0]),
+                CddsRequestHeader::create(42, seq_num, true),
+                None,
+                None,
+                move |reply| {
+                    let thread = std::thread::current().name().unwrap_or("").to_string();
+                    tx.send((service.to_string(), reply.len(), thread))
+                        .map_err(|e| e.to_string())
+                },
+            );
+        };
+        let get_map = Arc::new(ReplyDispatch::new(pool.clone(), false, Some(10000)));
+        let get_state = Arc::new(ReplyDispatch::new(pool.clone(), false, Some(10000)));
+        for seq_num in 0..3 {
+            call("get_map", seq_num, &get_map);
+            let (service, len, thread) =
+                async_std::future::timeout(Duration::from_secs(2), rx.recv_async())
+                    .await
+                    .unwrap()
+                    .unwrap();
+            assert_eq!((service.as_str(), len), ("get_map", 4 + 16 + 100_000));
+            assert_eq!(thread, "test-heavy-0");
+            call("get_state", seq_num, &get_state);
+            let (service, len, thread) =
+                async_std::future::timeout(Duration::from_secs(2), rx.recv_async())
+                    .await
+                    .unwrap()
+                    .unwrap();
+            assert_eq!((service.as_str(), len), ("get_state", 4 + 16 + 1));
+            assert_ne!(thread, "test-heavy-0");
+        }
+        assert!(get_map.is_heavy());
+        assert!(!get_state.is_heavy());
+
+        // with the workers saturated, the reply of a heavy Service is written at once, the call not failing
+        let saturated = Arc::new(WorkerPool::new("test-saturated", 1, 1));
+        let (release_tx, release_rx) = flume::unbounded::<()>();
+        let blocked_job = || {
+            let release_rx = release_rx.clone();
+            Box::new(move || {
+                let _ = release_rx.recv();
+            })
+        };
+        // its single worker blocked on a job, another one waiting
+        assert!(saturated.submit(blocked_job()).is_ok());
+        while saturated.queued() > 0 {
+            async_std::task::sleep(Duration::from_millis(1)).await;
+        }
+        assert!(saturated.submit(blocked_job()).is_ok());
+        let get_map = Arc::new(ReplyDispatch::new(saturated.clone(), true, Some(10000)));
+        call("get_map", 3, &get_map);
+        let (service, len, thread) =
+            async_std::future::timeout(Duration::from_secs(2), rx.recv_async())
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!((service.as_str(), len), ("get_map", 4 + 16 + 100_000));
+        assert_ne!(thread, "test-saturated-0");
+        drop(release_tx);
+    }
+
+    #[async_std::test]
+    async fn test_retry_after_loss() {
+        let config = serde_json::from_str::<Config>(
+            r#"{
+                "queries_timeout": { "services": { ".*": 0.3 } },
+                "service_retries": { "/get_state": { "retries": 2, "retry_backoff": 0.05 } }
+            }"#,
+        )
+        .unwrap();
+
+        // the remote Service Server loses the 1st query (keeping it without reply), and replies the next ones
+        let (server, bridge) = session_pair().await;
+        let bridge = Arc::new(bridge);
+        let idempotency_keys = Arc::new(Mutex::new(Vec::new()));
+        let lost_queries = Arc::new(Mutex::new(Vec::<Query>::new()));
+        let _queryable = {
+            let idempotency_keys = idempotency_keys.clone();
+            let lost_queries = lost_queries.clone();
+            server
+                .declare_queryable("**")
+                .callback(move |query: Query| {
+                    let mut keys = idempotency_keys.lock().unwrap();
+                    keys.push(query.attachment().and_then(idempotency_key_from_attachment));
+                    if keys.len() == 1 {
+                        lost_queries.lock().unwrap().push(query);
+                    } else {
+                        let ke = query.key_expr().clone();
+                        query
+                            .reply(Ok(Sample::new(ke, vec![0u8, 1, 0, 0, 42])))
+                            .res_sync()
+                            .unwrap();
+                    }
+                })
+                .res_async()
+                .await
+                .unwrap()
+        };
+        async_std::task::sleep(Duration::from_millis(500)).await;
+
+        let (tx, rx) = flume::unbounded();
+        let stats = Arc::new(RouteStats::default());
+        let failures = Arc::new(CallFailures::new(
+            None,
+            config.get_service_retry_policy("/get_state").copied(),
+            None,
+        ));
+        let request_id = CddsRequestHeader::create(42, 1, true);
+        call_zenoh_service(
+            &Arc::new(ServiceCallCtx {
+                stats: stats.clone(),
+                failures: failures.clone(),
+                ..call_ctx(
+                    &bridge,
+                    "get_state",
+                    config.get_queries_timeout_service("/get_state"),
+                )
+            }),
+            ZBuf::from(vec![0u8, 1, 0, 0, 0]),
+            request_id,
+            None,
+            None,
+            move |reply| tx.send(reply).map_err(|e| e.to_string()),
+        );
+
+        // the client gets the reply to the retried query
+        let reply = async_std::future::timeout(Duration::from_secs(2), rx.recv_async())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&reply[4..20], request_id.as_slice());
+        assert_eq!(reply[20], 42);
+        assert_eq!(failures.retried.load(Ordering::Relaxed), 1);
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 0);
+        let snapshot = stats.snapshot();
+        assert_eq!((snapshot.messages_out, snapshot.errors), (2, 0));
+        // both queries carry the same idempotency key
+        let keys = idempotency_keys.lock().unwrap();
+        assert_eq!(keys.len(), 2);
+        assert!(keys[0].is_some());
+        assert_eq!(keys[0], keys[1]);
+    }
+
+    #[async_std::test]
+    async fn test_service_unavailable() {
+        let config = serde_json::from_str::<Config>(
+            r#"{
+                "queries_timeout": { "services": { ".*": 1.0 } },
+                "service_error_replies": { "/get_state": "00010000000000000100000000" },
+                "on_service_unavailable": { "/get_state": "error_reply", "/load_map": "drop" }
+            }"#,
+        )
+        .unwrap();
+
+        let (server, bridge) = session_pair().await;
+        let bridge = Arc::new(bridge);
+        // no Service Server announced yet
+        let server_available = Arc::new(AtomicBool::new(false));
+
+        let call = |ros2_name: &str, server_available: &Arc<AtomicBool>| {
+            let (tx, rx) = flume::unbounded();
+            let stats = Arc::new(RouteStats::default());
+            let failures = Arc::new(CallFailures::new(
+                config
+                    .get_service_error_reply(ros2_name)
+                    .map(<[u8]>::to_vec),
+                None,
+                config.get_service_unavailable_policy(ros2_name),
+            ));
+            let request_id = CddsRequestHeader::create(42, 1, true);
+            call_zenoh_service(
+                &Arc::new(ServiceCallCtx {
+                    server_available: server_available.clone(),
+                    stats: stats.clone(),
+                    failures: failures.clone(),
+                    ..call_ctx(
+                        &bridge,
+                        &ros2_name[1..],
+                        config.get_queries_timeout_service(ros2_name),
+                    )
+                }),
+                ZBuf::from(vec![0u8, 1, 0, 0, 0]),
+                request_id,
+                None,
+                None,
+                move |reply| tx.send((reply, Instant::now())).map_err(|e| e.to_string()),
+            );
+            (rx, stats, failures, request_id)
+        };
+
+        // without Server, the call fails at once with the error reply
+        let start = Instant::now();
+        let (rx, stats, failures, request_id) = call("/get_state", &server_available);
+        let (reply, received) = rx.recv_async().await.unwrap();
+        assert!(received - start < Duration::from_millis(100));
+        assert_eq!(&reply[4..20], request_id.as_slice());
+        assert_eq!(&reply[20..], &[0u8, 0, 0, 0, 1, 0, 0, 0, 0][..]);
+        assert_eq!(failures.unavailable_calls.load(Ordering::Relaxed), 1);
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 1);
+        assert_eq!(stats.snapshot().messages_out, 1);
+
+        // or the request is dropped at once
+        let (rx, stats, failures, _) = call("/load_map", &server_available);
+        assert!(rx.try_recv().is_err());
+        assert_eq!(failures.unavailable_calls.load(Ordering::Relaxed), 1);
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 0);
+        assert_eq!(stats.snapshot().dropped, 1);
+
+        // once a Server is announced, the calls are routed as usual
+        let _queryable = server
+            .declare_queryable("get_state")
+            .callback(|query: Query| {
+                let ke = query.key_expr().clone();
+                query
+                    .reply(Ok(Sample::new(ke, vec![0u8, 1, 0, 0, 42])))
+                    .res_sync()
+                    .unwrap();
+            })
+            .res_async()
+            .await
+            .unwrap();
+        async_std::task::sleep(Duration::from_millis(500)).await;
+        server_available.store(true, Ordering::Relaxed);
+        let (rx, _, failures, _) = call("/get_state", &server_available);
+        let (reply, _) = async_std::future::timeout(Duration::from_secs(1), rx.recv_async())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&reply[20..], &[42u8][..]);
+        assert_eq!(failures.unavailable_calls.load(Ordering::Relaxed), 0);
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 0);
+    }
+
+    #[async_std::test]
+    async fn test_concurrent_clients() {
+        const CLIENTS: u64 = 8;
+        const REQUESTS: u64 = 16;
+        // the remote Service Server (little endian) echoes the requests, in an order different from theirs
+        let (server, bridge) = session_pair().await;
+        let bridge = Arc::new(bridge);
+        let _queryable = server
+            .declare_queryable("add_two_ints")
+            .callback(|query: Query| {
+                async_std::task::spawn(async move {
+                    let payload = query.value().unwrap().payload.contiguous().to_vec();
+                    let delay = (payload[4] as u64 * 7 + payload[5] as u64 * 3) % 20;
+                    async_std::task::sleep(Duration::from_millis(delay)).await;
+                    let mut reply = vec![0u8, 1, 0, 0];
+                    reply.extend_from_slice(&payload[4..]);
+                    let ke = query.key_expr().clone();
+                    query
+                        .reply(Ok(Sample::new(ke, reply)))
+                        .res_async()
+                        .await
+                        .unwrap();
+                });
+            })
+            .res_async()
+            .await
+            .unwrap();
+        async_std::task::sleep(Duration::from_millis(500)).await;
+
+        // all the DDS clients of the Service read all the replies on the Reply topic,
+        // each client taking the ones with its GUID in their request header
+        let (tx, rx) = flume::unbounded();
+        let stats = Arc::new(RouteStats::default());
+        let failures = Arc::new(CallFailures::new(None, None, None));
+        let ctx = Arc::new(ServiceCallCtx {
+            stats: stats.clone(),
+            failures: failures.clone(),
+            ..call_ctx(&bridge, "add_two_ints", Duration::from_secs(5))
+        });
+        // the clients use the same sequence numbers, half of them in big endian
+        for seq_num in 1..=REQUESTS {
+            for client in 1..=CLIENTS {
+                let is_little_endian = client % 2 == 0;
+                let cdr_header = if is_little_endian { 1u8 } else { 0 };
+                let tx = tx.clone();
+                call_zenoh_service(
+                    &ctx,
+                    ZBuf::from(vec![0u8, cdr_header, 0, 0, client as u8, seq_num as u8]),
+                    CddsRequestHeader::create(client, seq_num, is_little_endian),
+                    None,
+                    None,
+                    move |reply| tx.send(reply).map_err(|e| e.to_string()),
                 );
-                return;
             }
-            // route reply buffer re-inserting request_id (client_id + sequence_number)
-            let mut dds_rep_buf: Vec<u8> = Vec::new();
-            // copy CDR header
-            dds_rep_buf.extend_from_slice(&zenoh_rep_buf[..4]);
-            // add request_id
-            dds_rep_buf.extend_from_slice(request_id.as_slice());
-            // add query payoad
-            dds_rep_buf.extend_from_slice(&zenoh_rep_buf[4..]);
-
-            if *LOG_PAYLOAD {
-                tracing::debug!("{route_id}: routing reply for {request_id} from Zenoh to DDS - payload: {dds_rep_buf:02x?}");
-            } else {
-                tracing::trace!(
-                    "{route_id}: routing reply for {request_id} from Zenoh to DDS - {} bytes",
-                    dds_rep_buf.len()
+        }
+
+        let mut replies = std::collections::HashSet::new();
+        for _ in 0..CLIENTS * REQUESTS {
+            let reply = async_std::future::timeout(Duration::from_secs(5), rx.recv_async())
+                .await
+                .unwrap()
+                .unwrap();
+            // the request header is encoded with the reply's endianness (little endian)
+            let header = CddsRequestHeader::from_slice(reply[4..20].try_into().unwrap(), true);
+            let (client, seq_num) = header.client_id_and_seq_num();
+            assert_eq!(
+                &reply[20..],
+                &[client as u8, seq_num as u8][..],
+                "reply {header} for another request"
+            );
+            assert!(
+                replies.insert((client, seq_num)),
+                "duplicate reply {header}"
+            );
+        }
+        assert_eq!(replies.len(), (CLIENTS * REQUESTS) as usize);
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 0);
+    }
+
+    #[async_std::test]
+    async fn test_long_call() {
+        let config = serde_json::from_str::<Config>(
+            r#"{
+                "queries_timeout": { "services": { ".*": 2.0 } },
+                "long_calls": { "/merge_maps": 15.0, "/abandoned": 1.0 }
+            }"#,
+        )
+        .unwrap();
+
+        // the remote Route Service Server accepts the long calls at once, and publishes the reply
+        // of the merge_maps Service after 10 seconds (the abandoned one never replies)
+        let (server, bridge) = session_pair().await;
+        let (server, bridge) = (Arc::new(server), Arc::new(bridge));
+        let _queryable = {
+            let server = server.clone();
+            server
+                .clone()
+                .declare_queryable("**")
+                .callback(move |query: Query| {
+                    let attachment = query.attachment().unwrap();
+                    assert!(long_call_from_attachment(attachment).is_some());
+                    let request_id = CddsRequestHeader::try_from(attachment).unwrap();
+                    let reply_ke = long_call_reply_key_expr(query.key_expr(), &request_id);
+                    let is_merge_maps = query.key_expr().as_str() == "merge_maps";
+                    let ke = query.key_expr().clone();
+                    query
+                        .reply(Ok(Sample::new(ke, Vec::<u8>::new())))
+                        .res_sync()
+                        .unwrap();
+                    if is_merge_maps {
+                        let server = server.clone();
+                        async_std::task::spawn(async move {
+                            async_std::task::sleep(Duration::from_secs(10)).await;
+                            server
+                                .put(reply_ke, vec![0u8, 1, 0, 0, 42])
+                                .res_async()
+                                .await
+                                .unwrap();
+                        });
+                    }
+                })
+                .res_async()
+                .await
+                .unwrap()
+        };
+        async_std::task::sleep(Duration::from_millis(500)).await;
+
+        let call = |ros2_name: &str| {
+            let (tx, rx) = flume::unbounded();
+            let stats = Arc::new(RouteStats::default());
+            let failures = Arc::new(CallFailures::new(None, None, None));
+            call_zenoh_service(
+                &Arc::new(ServiceCallCtx {
+                    long_call: config.get_long_call_duration(ros2_name),
+                    stats: stats.clone(),
+                    failures: failures.clone(),
+                    ..call_ctx(
+                        &bridge,
+                        &ros2_name[1..],
+                        config.get_queries_timeout_service(ros2_name),
+                    )
+                }),
+                ZBuf::from(vec![0u8, 1, 0, 0, 0]),
+                CddsRequestHeader::create(42, 1, true),
+                None,
+                None,
+                move |reply| tx.send(reply).map_err(|e| e.to_string()),
+            );
+            (rx, failures)
+        };
+
+        let start = Instant::now();
+        let (merge_maps_rx, merge_maps_failures) = call("/merge_maps");
+        let (abandoned_rx, abandoned_failures) = call("/abandoned");
+
+        // the abandoned long call fails after its maximum duration
+        async_std::task::sleep(Duration::from_millis(1500)).await;
+        assert!(abandoned_rx.try_recv().is_err());
+        assert_eq!(abandoned_failures.failed.load(Ordering::Relaxed), 1);
+
+        // the long call is replied after 10 seconds, despite the 2 seconds queries timeout
+        let reply = async_std::future::timeout(Duration::from_secs(12), merge_maps_rx.recv_async())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(start.elapsed() >= Duration::from_secs(10));
+        assert_eq!(
+            &reply[4..20],
+            CddsRequestHeader::create(42, 1, true).as_slice()
+        );
+        assert_eq!(reply[20], 42);
+        assert_eq!(merge_maps_failures.failed.load(Ordering::Relaxed), 0);
+    }
+
+    #[async_std::test]
+    async fn test_call_metrics() {
+        // the remote Service Server replies at once to "fast", after 300ms to "slow", and never to "lost"
+        let (server, bridge) = session_pair().await;
+        let bridge = Arc::new(bridge);
+        let pending_queries = Arc::new(Mutex::new(Vec::<Query>::new()));
+        let callers = Arc::new(Mutex::new(Vec::<Option<String>>::new()));
+        let _queryable = {
+            let pending_queries = pending_queries.clone();
+            let callers = callers.clone();
+            server
+                .declare_queryable("**")
+                .callback(move |query: Query| {
+                    callers
+                        .lock()
+                        .unwrap()
+                        .push(query.attachment().and_then(caller_from_attachment));
+                    let delay = match query.key_expr().as_str() {
+                        "fast" => Some(Duration::ZERO),
+                        "slow" => Some(Duration::from_millis(300)),
+                        _ => None,
+                    };
+                    let Some(delay) = delay else {
+                        pending_queries.lock().unwrap().push(query);
+                        return;
+                    };
+                    async_std::task::spawn(async move {
+                        async_std::task::sleep(delay).await;
+                        let ke = query.key_expr().clone();
+                        query
+                            .reply(Ok(Sample::new(ke, vec![0u8, 1, 0, 0, 42])))
+                            .res_async()
+                            .await
+                            .unwrap();
+                    });
+                })
+                .res_async()
+                .await
+                .unwrap()
+        };
+        async_std::task::sleep(Duration::from_millis(500)).await;
+
+        let (tx, rx) = flume::unbounded();
+        let stats = Arc::new(RouteStats::default());
+        let failures = Arc::new(CallFailures::new(None, None, None));
+        let calls = Arc::new(ServiceMetrics::default());
+        let call = |service: &str, seq_num: u64| {
+            let tx = tx.clone();
+            call_zenoh_service(
+                &Arc::new(ServiceCallCtx {
+                    stats: stats.clone(),
+                    failures: failures.clone(),
+                    calls: calls.clone(),
+                    ..call_ctx(&bridge, service, Duration::from_secs(1))
+                }),
+                ZBuf::from(vec![0u8, 1, 0, 0, 0]),
+                CddsRequestHeader::create(42, seq_num, true),
+                None,
+                None,
+                move |reply| tx.send(reply).map_err(|e| e.to_string()),
+            );
+        };
+        for seq_num in 0..10 {
+            call("fast", seq_num);
+        }
+        for seq_num in 10..13 {
+            call("slow", seq_num);
+        }
+        for seq_num in 13..15 {
+            call("lost", seq_num);
+        }
+        for _ in 0..13 {
+            async_std::future::timeout(Duration::from_secs(2), rx.recv_async())
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        // the lost calls time out
+        async_std::task::sleep(Duration::from_millis(1500)).await;
+
+        let metrics = calls.snapshot();
+        assert_eq!(metrics.requests, 15);
+        assert_eq!(metrics.replies, 13);
+        assert_eq!(metrics.timeouts, 2);
+        let counts = &metrics.latency.counts;
+        assert_eq!(counts.iter().sum::<u64>(), 13);
+        // the fast calls within 50ms, the slow ones between 200ms and 500ms
+        assert_eq!(counts[..=5].iter().sum::<u64>(), 10, "{counts:?}");
+        assert_eq!(counts[8], 3, "{counts:?}");
+        assert!(metrics.latency.mean_ms.unwrap() > 60.0);
+
+        // the caller is identified by the bridge and the client GUID
+        let callers = callers.lock().unwrap();
+        assert_eq!(callers.len(), 15);
+        assert!(callers
+            .iter()
+            .all(|c| c.as_deref() == Some("robot1:000000000000002a")));
+    }
+
+    #[async_std::test]
+    async fn test_server_lost() {
+        let config = serde_json::from_str::<Config>(
+            r#"{ "service_retries": { "/retried": { "retries": 1, "retry_backoff": 0.05 } } }"#,
+        )
+        .unwrap();
+        // the remote Service Server keeps the queries, to be replied by the test
+        let (server, bridge) = session_pair().await;
+        let bridge = Arc::new(bridge);
+        let pending_queries = Arc::new(Mutex::new(Vec::<Query>::new()));
+        let _queryable = {
+            let pending_queries = pending_queries.clone();
+            server
+                .declare_queryable("**")
+                .callback(move |query: Query| pending_queries.lock().unwrap().push(query))
+                .res_async()
+                .await
+                .unwrap()
+        };
+        async_std::task::sleep(Duration::from_millis(500)).await;
+        let reply_pending = |i: usize, value: u8| {
+            let query = pending_queries.lock().unwrap()[i].clone();
+            let ke = query.key_expr().clone();
+            query
+                .reply(Ok(Sample::new(ke, vec![0u8, 1, 0, 0, value])))
+                .res_sync()
+                .unwrap();
+        };
+
+        let (tx, rx) = flume::unbounded();
+        let stats = Arc::new(RouteStats::default());
+        let call = |failures: &Arc<CallFailures>, seq_num: u64| {
+            let tx = tx.clone();
+            call_zenoh_service(
+                &Arc::new(ServiceCallCtx {
+                    stats: stats.clone(),
+                    failures: failures.clone(),
+                    ..call_ctx(&bridge, "get_state", Duration::from_secs(10))
+                }),
+                ZBuf::from(vec![0u8, 1, 0, 0, 0]),
+                CddsRequestHeader::create(42, seq_num, true),
+                None,
+                None,
+                move |reply| tx.send(reply).map_err(|e| e.to_string()),
+            );
+        };
+        let recv = || async {
+            async_std::future::timeout(Duration::from_millis(500), rx.recv_async())
+                .await
+                .map(|reply| reply.unwrap()[20])
+        };
+
+        // without retry, the call in flight fails at once when the Server is lost (e.g. restarting)
+        let failures = Arc::new(CallFailures::new(
+            Some(vec![0u8, 1, 0, 0, 0xff]),
+            None,
+            None,
+        ));
+        call(&failures, 1);
+        async_std::task::sleep(Duration::from_millis(200)).await;
+        failures.on_server_lost("test");
+        assert_eq!(recv().await, Ok(0xff));
+        assert_eq!(failures.server_lost.load(Ordering::Relaxed), 1);
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 1);
+        assert!(failures.in_flight.lock().unwrap().is_empty());
+        // after the restart, a request with the same sequence number gets the reply of the new Server only
+        call(&failures, 1);
+        async_std::task::sleep(Duration::from_millis(200)).await;
+        reply_pending(0, 42);
+        reply_pending(1, 43);
+        assert_eq!(recv().await, Ok(43));
+        assert!(recv().await.is_err());
+
+        // with retry, the call is re-issued and the late reply of the lost Server is dropped
+        let failures = Arc::new(CallFailures::new(
+            None,
+            config.get_service_retry_policy("/retried").copied(),
+            None,
+        ));
+        call(&failures, 2);
+        async_std::task::sleep(Duration::from_millis(200)).await;
+        failures.on_server_lost("test");
+        async_std::task::sleep(Duration::from_millis(200)).await;
+        assert_eq!(pending_queries.lock().unwrap().len(), 4);
+        reply_pending(2, 42);
+        reply_pending(3, 43);
+        assert_eq!(recv().await, Ok(43));
+        assert!(recv().await.is_err());
+        assert_eq!(failures.retried.load(Ordering::Relaxed), 1);
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 0);
+        assert_eq!(stats.snapshot().dropped, 2);
+    }
+
+    #[async_std::test]
+    async fn test_flush_pending() {
+        // the remote Service Server is wedged: it keeps the queries without replying
+        let (server, bridge) = session_pair().await;
+        let bridge = Arc::new(bridge);
+        let pending_queries = Arc::new(Mutex::new(Vec::<Query>::new()));
+        let _queryable = {
+            let pending_queries = pending_queries.clone();
+            server
+                .declare_queryable("**")
+                .callback(move |query: Query| pending_queries.lock().unwrap().push(query))
+                .res_async()
+                .await
+                .unwrap()
+        };
+        async_std::task::sleep(Duration::from_millis(500)).await;
+
+        let (tx, rx) = flume::unbounded();
+        let stats = Arc::new(RouteStats::default());
+        let failures = Arc::new(CallFailures::new(
+            Some(vec![0u8, 1, 0, 0, 0xff]),
+            None,
+            None,
+        ));
+        let calls = Arc::new(ServiceMetrics::default());
+        let concurrency = Arc::new(ConcurrencyLimit::new(Some(4)));
+        for seq_num in 0..10 {
+            let tx = tx.clone();
+            call_zenoh_service(
+                &Arc::new(ServiceCallCtx {
+                    stats: stats.clone(),
+                    failures: failures.clone(),
+                    calls: calls.clone(),
+                    concurrency: concurrency.clone(),
+                    ..call_ctx(&bridge, "get_state", Duration::from_secs(10))
+                }),
+                ZBuf::from(vec![0u8, 1, 0, 0, 0]),
+                CddsRequestHeader::create(42, seq_num, true),
+                None,
+                None,
+                move |reply| tx.send(reply).map_err(|e| e.to_string()),
+            );
+        }
+        async_std::task::sleep(Duration::from_millis(200)).await;
+        assert_eq!(failures.in_flight.lock().unwrap().len(), 10);
+        assert_eq!((concurrency.in_progress(), concurrency.waiting()), (4, 6));
+
+        // the calls in flight and the waiting ones fail at once with the error reply,
+        // the waiting ones without being issued
+        assert_eq!(failures.flush_pending(), 10);
+        for _ in 0..10 {
+            let reply = async_std::future::timeout(Duration::from_millis(100), rx.recv_async())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(reply[20], 0xff);
+        }
+        async_std::task::sleep(Duration::from_millis(200)).await;
+        assert!(failures.in_flight.lock().unwrap().is_empty());
+        assert_eq!((concurrency.in_progress(), concurrency.waiting()), (0, 0));
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 10);
+        assert_eq!(calls.snapshot().timeouts, 10);
+
+        // the late replies of the Server are dropped
+        assert_eq!(pending_queries.lock().unwrap().len(), 4);
+        let query = pending_queries.lock().unwrap()[0].clone();
+        let ke = query.key_expr().clone();
+        query
+            .reply(Ok(Sample::new(ke, vec![0u8, 1, 0, 0, 42])))
+            .res_sync()
+            .unwrap();
+        async_std::task::sleep(Duration::from_millis(200)).await;
+        assert!(rx.try_recv().is_err());
+        assert_eq!(stats.snapshot().dropped, 1);
+
+        // the counters are reset
+        stats.reset();
+        calls.reset();
+        failures.reset_counters();
+        assert_eq!(stats.snapshot().dropped, 0);
+        assert_eq!(calls.snapshot().requests, 0);
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 0);
+        assert_eq!(failures.error_replies.load(Ordering::Relaxed), 0);
+    }
+
+    #[async_std::test]
+    async fn test_waiting_calls_server_lost() {
+        let config = serde_json::from_str::<Config>(
+            r#"{ "service_retries": { "/retried": { "retries": 1, "retry_backoff": 0.05 } } }"#,
+        )
+        .unwrap();
+        // the remote Service Server keeps the queries, to be replied by the test
+        let (server, bridge) = session_pair().await;
+        let bridge = Arc::new(bridge);
+        let pending_queries = Arc::new(Mutex::new(Vec::<Query>::new()));
+        let _queryable = {
+            let pending_queries = pending_queries.clone();
+            server
+                .declare_queryable("**")
+                .callback(move |query: Query| pending_queries.lock().unwrap().push(query))
+                .res_async()
+                .await
+                .unwrap()
+        };
+        async_std::task::sleep(Duration::from_millis(500)).await;
+        let reply_pending = |i: usize, value: u8| {
+            let query = pending_queries.lock().unwrap()[i].clone();
+            let ke = query.key_expr().clone();
+            query
+                .reply(Ok(Sample::new(ke, vec![0u8, 1, 0, 0, value])))
+                .res_sync()
+                .unwrap();
+        };
+
+        let (tx, rx) = flume::unbounded();
+        let call = |failures: &Arc<CallFailures>,
+                    concurrency: &Arc<ConcurrencyLimit<Task>>,
+                    seq_num: u64| {
+            let tx = tx.clone();
+            call_zenoh_service(
+                &Arc::new(ServiceCallCtx {
+                    failures: failures.clone(),
+                    concurrency: concurrency.clone(),
+                    ..call_ctx(&bridge, "get_state", Duration::from_secs(10))
+                }),
+                ZBuf::from(vec![0u8, 1, 0, 0, 0]),
+                CddsRequestHeader::create(42, seq_num, true),
+                None,
+                None,
+                move |reply| tx.send(reply).map_err(|e| e.to_string()),
+            );
+        };
+        let recv = || async {
+            async_std::future::timeout(Duration::from_millis(500), rx.recv_async())
+                .await
+                .map(|reply| reply.unwrap()[20])
+        };
+
+        // without retry, the waiting call fails at once with the call in flight, without being issued
+        let failures = Arc::new(CallFailures::new(
+            Some(vec![0u8, 1, 0, 0, 0xff]),
+            None,
+            None,
+        ));
+        let concurrency = Arc::new(ConcurrencyLimit::new(Some(1)));
+        call(&failures, &concurrency, 1);
+        call(&failures, &concurrency, 2);
+        async_std::task::sleep(Duration::from_millis(200)).await;
+        assert_eq!((concurrency.in_progress(), concurrency.waiting()), (1, 1));
+        failures.on_server_lost("test");
+        assert_eq!(recv().await, Ok(0xff));
+        assert_eq!(recv().await, Ok(0xff));
+        async_std::task::sleep(Duration::from_millis(200)).await;
+        assert_eq!((concurrency.in_progress(), concurrency.waiting()), (0, 0));
+        assert_eq!(failures.server_lost.load(Ordering::Relaxed), 2);
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 2);
+        assert!(failures.in_flight.lock().unwrap().is_empty());
+        assert_eq!(pending_queries.lock().unwrap().len(), 1);
+
+        // with retry, the call in flight is re-issued and the waiting one still waits for its admission
+        let failures = Arc::new(CallFailures::new(
+            None,
+            config.get_service_retry_policy("/retried").copied(),
+            None,
+        ));
+        let concurrency = Arc::new(ConcurrencyLimit::new(Some(1)));
+        call(&failures, &concurrency, 3);
+        call(&failures, &concurrency, 4);
+        async_std::task::sleep(Duration::from_millis(200)).await;
+        failures.on_server_lost("test");
+        async_std::task::sleep(Duration::from_millis(200)).await;
+        assert_eq!(failures.server_lost.load(Ordering::Relaxed), 1);
+        assert_eq!(failures.in_flight.lock().unwrap().len(), 2);
+        assert_eq!(pending_queries.lock().unwrap().len(), 3);
+        reply_pending(2, 43);
+        assert_eq!(recv().await, Ok(43));
+        async_std::task::sleep(Duration::from_millis(200)).await;
+        assert_eq!(pending_queries.lock().unwrap().len(), 4);
+        reply_pending(3, 44);
+        assert_eq!(recv().await, Ok(44));
+        assert_eq!((concurrency.in_progress(), concurrency.waiting()), (0, 0));
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 0);
+    }
+
+    #[async_std::test]
+    async fn test_invalid_reply() {
+        // the remote Service Server replies a payload without CDR header
+        let (server, bridge) = session_pair().await;
+        let bridge = Arc::new(bridge);
+        let _queryable = server
+            .declare_queryable("**")
+            .callback(move |query: Query| {
+                let ke = query.key_expr().clone();
+                query
+                    .reply(Ok(Sample::new(ke, vec![0u8, 1])))
+                    .res_sync()
+                    .unwrap();
+            })
+            .res_async()
+            .await
+            .unwrap();
+        async_std::task::sleep(Duration::from_millis(500)).await;
+
+        // the call fails at once with the error reply, releasing its slot to the waiting one
+        let (tx, rx) = flume::unbounded();
+        let failures = Arc::new(CallFailures::new(
+            Some(vec![0u8, 1, 0, 0, 0xff]),
+            None,
+            None,
+        ));
+        let concurrency = Arc::new(ConcurrencyLimit::new(Some(1)));
+        for seq_num in 1..=2 {
+            let tx = tx.clone();
+            call_zenoh_service(
+                &Arc::new(ServiceCallCtx {
+                    failures: failures.clone(),
+                    concurrency: concurrency.clone(),
+                    ..call_ctx(&bridge, "get_state", Duration::from_secs(10))
+                }),
+                ZBuf::from(vec![0u8, 1, 0, 0, 0]),
+                CddsRequestHeader::create(42, seq_num, true),
+                None,
+                None,
+                move |reply| tx.send(reply).map_err(|e| e.to_string()),
+            );
+        }
+        for _ in 0..2 {
+            let reply = async_std::future::timeout(Duration::from_millis(500), rx.recv_async())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(reply[20], 0xff);
+        }
+        async_std::task::sleep(Duration::from_millis(200)).await;
+        assert!(failures.in_flight.lock().unwrap().is_empty());
+        assert_eq!((concurrency.in_progress(), concurrency.waiting()), (0, 0));
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 2);
+    }
+
+    #[async_std::test]
+    async fn test_client_lost() {
+        let config = serde_json::from_str::<Config>(
+            r#"{ "service_retries": { "/get_state": { "retries": 1, "retry_backoff": 0.05 } } }"#,
+        )
+        .unwrap();
+        // the remote Service Server keeps the queries, to be replied by the test
+        let (server, bridge) = session_pair().await;
+        let bridge = Arc::new(bridge);
+        let pending_queries = Arc::new(Mutex::new(Vec::<Query>::new()));
+        let _queryable = {
+            let pending_queries = pending_queries.clone();
+            server
+                .declare_queryable("**")
+                .callback(move |query: Query| pending_queries.lock().unwrap().push(query))
+                .res_async()
+                .await
+                .unwrap()
+        };
+        async_std::task::sleep(Duration::from_millis(500)).await;
+        let reply_pending = |i: usize, value: u8| {
+            let query = pending_queries.lock().unwrap()[i].clone();
+            let ke = query.key_expr().clone();
+            query
+                .reply(Ok(Sample::new(ke, vec![0u8, 1, 0, 0, value])))
+                .res_sync()
+                .unwrap();
+        };
+
+        let (tx, rx) = flume::unbounded();
+        let stats = Arc::new(RouteStats::default());
+        let failures = Arc::new(CallFailures::new(
+            Some(vec![0u8, 1, 0, 0, 0xff]),
+            config.get_service_retry_policy("/get_state").copied(),
+            None,
+        ));
+        let concurrency = Arc::new(ConcurrencyLimit::new(Some(2)));
+        let client = |id: u8| Gid::from([id; 16]);
+        let call = |client: Gid, seq_num: u64| {
+            let tx = tx.clone();
+            call_zenoh_service(
+                &Arc::new(ServiceCallCtx {
+                    stats: stats.clone(),
+                    failures: failures.clone(),
+                    concurrency: concurrency.clone(),
+                    ..call_ctx(&bridge, "get_state", Duration::from_millis(500))
+                }),
+                ZBuf::from(vec![0u8, 1, 0, 0, 0]),
+                CddsRequestHeader::create(42, seq_num, true),
+                None,
+                Some(client),
+                move |reply| tx.send(reply).map_err(|e| e.to_string()),
+            );
+        };
+        call(client(1), 1);
+        call(client(2), 2);
+        async_std::task::sleep(Duration::from_millis(200)).await;
+
+        // the call of the lost client is abandoned, the other one still in flight
+        failures.on_client_lost("test", client(1));
+        assert_eq!(failures.abandoned.load(Ordering::Relaxed), 1);
+        assert_eq!(failures.in_flight.lock().unwrap().len(), 1);
+        assert_eq!(concurrency.in_progress(), 1);
+        reply_pending(0, 41);
+        reply_pending(1, 42);
+        let reply = async_std::future::timeout(Duration::from_millis(500), rx.recv_async())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reply[20], 42);
+
+        // the reply to the abandoned call is dropped, and it's neither retried nor failed
+        async_std::task::sleep(Duration::from_millis(800)).await;
+        assert!(rx.try_recv().is_err());
+        assert_eq!(pending_queries.lock().unwrap().len(), 2);
+        assert_eq!(failures.retried.load(Ordering::Relaxed), 0);
+        assert_eq!(failures.failed.load(Ordering::Relaxed), 0);
+        assert_eq!(stats.snapshot().errors, 0);
+        assert_eq!(stats.snapshot().dropped, 1);
+        assert!(failures.in_flight.lock().unwrap().is_empty());
+
+        // a lost client without call in flight
+        failures.on_client_lost("test", client(3));
+        assert_eq!(failures.abandoned.load(Ordering::Relaxed), 1);
+    }
+
+    #[async_std::test]
+    async fn test_max_concurrent_requests() {
+        // the remote (multi-threaded) Service Server replies 1 to "slow" after 300ms, and 2 to "fast" at once
+        let (server, bridge) = session_pair().await;
+        let bridge = Arc::new(bridge);
+        let _queryable = server
+            .declare_queryable("**")
+            .callback(move |query: Query| {
+                let (delay, value) = match query.key_expr().as_str() {
+                    "slow" => (Duration::from_millis(300), 1u8),
+                    _ => (Duration::ZERO, 2u8),
+                };
+                async_std::task::spawn(async move {
+                    async_std::task::sleep(delay).await;
+                    let ke = query.key_expr().clone();
+                    query
+                        .reply(Ok(Sample::new(ke, vec![0u8, 1, 0, 0, value])))
+                        .res_async()
+                        .await
+                        .unwrap();
+                });
+            })
+            .res_async()
+            .await
+            .unwrap();
+        async_std::task::sleep(Duration::from_millis(500)).await;
+
+        // a slow request followed by a fast one: with 1 request at a time, the fast one waits
+        // for the slow one to be replied; with 2, it's replied first
+        for (max, expected) in [(1, [1u8, 2]), (2, [2u8, 1])] {
+            let (tx, rx) = flume::unbounded();
+            let stats = Arc::new(RouteStats::default());
+            let failures = Arc::new(CallFailures::new(None, None, None));
+            let concurrency = Arc::new(ConcurrencyLimit::new(Some(max)));
+            for (service, seq_num) in [("slow", 1), ("fast", 2)] {
+                let tx = tx.clone();
+                call_zenoh_service(
+                    &Arc::new(ServiceCallCtx {
+                        stats: stats.clone(),
+                        failures: failures.clone(),
+                        concurrency: concurrency.clone(),
+                        ..call_ctx(&bridge, service, Duration::from_secs(2))
+                    }),
+                    ZBuf::from(vec![0u8, 1, 0, 0, 0]),
+                    CddsRequestHeader::create(42, seq_num, true),
+                    None,
+                    None,
+                    move |reply| tx.send(reply).map_err(|e| e.to_string()),
                 );
             }
-
-            if let Err(e) = dds_write(rep_writer, dds_rep_buf) {
-                tracing::warn!(
-                    "{route_id}: routing reply for {request_id} from Zenoh to DDS failed: {e}"
+            assert_eq!(
+                (concurrency.in_progress(), concurrency.waiting()),
+                (max, 2 - max)
+            );
+            let mut values = Vec::new();
+            for _ in 0..2 {
+                let reply = async_std::future::timeout(Duration::from_secs(2), rx.recv_async())
+                    .await
+                    .unwrap()
+                    .unwrap();
+                // each reply is correlated to its request, whatever their order
+                let seq_num = if reply[20] == 1 { 1 } else { 2 };
+                assert_eq!(
+                    &reply[4..20],
+                    CddsRequestHeader::create(42, seq_num, true).as_slice()
                 );
+                values.push(reply[20]);
             }
-        }
-        Err(val) => {
-            tracing::warn!("{route_id}: received error as reply for {request_id}: {val}");
+            assert_eq!(values, expected, "with max_concurrent_requests={max}");
+            assert_eq!((concurrency.in_progress(), concurrency.waiting()), (0, 0));
         }
     }
 }
@@ -13,19 +13,22 @@
 //
 
 use cyclors::dds_entity_t;
-use serde::Serialize;
-use std::collections::HashMap;
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::sync::RwLock;
+use std::time::{Duration, Instant};
 use std::{collections::HashSet, fmt};
 use zenoh::buffers::{ZBuf, ZSlice};
 use zenoh::liveliness::LivelinessToken;
 use zenoh::prelude::r#async::AsyncResolve;
 use zenoh::prelude::*;
 use zenoh::queryable::{Query, Queryable};
-use zenoh_core::zwrite;
+use zenoh_core::{zlock, zread, zwrite};
 
+use crate::concurrency_limit::{serialize_concurrency_limit, ConcurrencyLimit, Task};
 use crate::dds_types::{DDSRawSample, TypeInfo};
 use crate::dds_utils::{
     create_dds_reader, create_dds_writer, dds_write, delete_dds_entity, get_guid,
@@ -33,12 +36,32 @@ use crate::dds_utils::{
 };
 use crate::dds_utils::{is_cdr_little_endian, serialize_entity_guid};
 use crate::liveliness_mgt::new_ke_liveliness_service_srv;
+use crate::node_info::ServiceSrvEntities;
+use crate::qos_helpers::apply_partition;
 use crate::ros2_utils::{
-    is_service_for_action, new_service_id, ros2_service_type_to_reply_dds_type,
-    ros2_service_type_to_request_dds_type, CddsRequestHeader, QOS_DEFAULT_SERVICE,
+    caller_from_attachment, dds_topic_name, idempotency_key_from_attachment, insert_request_header,
+    is_service_for_action, long_call_from_attachment, long_call_reply_key_expr, new_service_id,
+    ros2_service_type_to_reply_dds_type, ros2_service_type_to_request_dds_type,
+    split_request_header, timeout_from_attachment, CddsRequestHeader, QOS_DEFAULT_SERVICE,
 };
+use crate::route_events::{RouteEvent, RouteEventKind};
+use crate::route_stats::{RouteStats, RouteStatsSnapshot};
 use crate::routes_mgr::Context;
-use crate::{serialize_option_as_bool, LOG_PAYLOAD};
+use crate::service_metrics::{
+    serialize_service_metrics, serialize_top_callers, ServiceMetrics, TopCallers,
+};
+use crate::{serialize_option_as_bool, serialize_priority, LOG_PAYLOAD};
+
+// The bounds of the cache of the calls identified by an idempotency key (see `service_retries`):
+// the calls older than IDEMPOTENCY_CACHE_TTL are forgotten, and the oldest ones beyond IDEMPOTENCY_CACHE_SIZE
+const IDEMPOTENCY_CACHE_SIZE: usize = 256;
+const IDEMPOTENCY_CACHE_TTL: Duration = Duration::from_secs(60);
+
+// The delay after the queries timeout before a query without reply from the Service Server is expired
+// (the remote client having given up on it), and the count of expired request ids remembered
+// to tell the late replies from the replies to the other clients of the Service
+const QUERIES_EXPIRY_GRACE: Duration = Duration::from_secs(1);
+const MAX_EXPIRED_REQUESTS: usize = 256;
 
 // a route for a Service Server exposed in Zenoh as a Queryable
 #[derive(Serialize)]
@@ -56,6 +79,10 @@ pub struct RouteServiceSrv<'a> {
     // `None` when route is created on a remote announcement and no local ROS2 Service Server discovered yet
     #[serde(rename = "is_active", serialize_with = "serialize_option_as_bool")]
     zenoh_queryable: Option<Queryable<'a, ()>>,
+    // the Zenoh Priority of the replies to long calls (configured in `pub_priorities` or `topic_qos`).
+    // The replies to queries are sent with the default priority, Zenoh 0.11 not allowing to set it.
+    #[serde(serialize_with = "serialize_priority")]
+    priority: Priority,
     // the local DDS Writer sending requests to the service server
     #[serde(serialize_with = "serialize_entity_guid")]
     req_writer: dds_entity_t,
@@ -68,16 +95,32 @@ pub struct RouteServiceSrv<'a> {
     // the ROS sequence number for requests
     #[serde(skip)]
     sequence_number: Arc<AtomicU64>,
-    // queries waiting for a reply
+    // queries waiting for a reply (with the time they were received), expired after the queries timeout
+    #[serde(serialize_with = "serialize_queries_in_progress")]
+    queries_in_progress: Arc<RwLock<QueriesInProgress<(PendingReply, Instant)>>>,
+    // the calls retried by remote Service Client routes, served only once
+    #[serde(skip)]
+    idempotency_cache: Arc<Mutex<IdempotencyCache>>,
+    // the statistics of the requests routed from Zenoh to DDS and of the replies routed back
     #[serde(skip)]
-    queries_in_progress: Arc<RwLock<HashMap<CddsRequestHeader, Query>>>,
+    stats: Arc<RouteStats>,
+    // the requests written to DDS, replied or expired, and their latency until the reply is sent to Zenoh
+    #[serde(serialize_with = "serialize_service_metrics")]
+    calls: Arc<ServiceMetrics>,
+    // the requests in progress, and the ones waiting beyond `max_concurrent_requests`
+    #[serde(serialize_with = "serialize_concurrency_limit")]
+    concurrency: Arc<ConcurrencyLimit<Task>>,
+    // the requests per caller (identified by the calling bridge and the client GUID)
+    #[serde(rename = "top_callers", serialize_with = "serialize_top_callers")]
+    callers: Arc<TopCallers>,
     // a liveliness token associated to this route, for announcement to other plugins
     #[serde(skip)]
     liveliness_token: Option<LivelinessToken<'a>>,
     // the list of remote routes served by this route ("<plugin_id>:<zenoh_key_expr>"")
     remote_routes: HashSet<String>,
-    // the list of nodes served by this route
-    local_nodes: HashSet<String>,
+    // the list of nodes served by this route, with the DDS entities of their Service Server
+    #[serde(serialize_with = "serialize_local_nodes")]
+    local_nodes: HashMap<String, ServiceSrvEntities>,
 }
 
 impl Drop for RouteServiceSrv<'_> {
@@ -119,13 +162,18 @@ impl RouteServiceSrv<'_> {
         ros2_type: String,
         zenoh_key_expr: OwnedKeyExpr,
         type_info: &Option<Arc<TypeInfo>>,
+        queries_timeout: Duration,
+        priority: Priority,
         context: Context,
     ) -> Result<RouteServiceSrv<'a>, String> {
         let route_id = format!("Route Service Server (ROS:{ros2_name} <-> Zenoh:{zenoh_key_expr})");
         tracing::debug!("{route_id}: creation with type {ros2_type}");
 
-        // Default Service QoS
-        let mut qos = QOS_DEFAULT_SERVICE.clone();
+        // Default Service QoS, with the DDS Partitions configured for this Service, if any
+        let mut qos = apply_partition(
+            &QOS_DEFAULT_SERVICE,
+            context.config.get_dds_partition(&ros2_name),
+        );
 
         // Add DATA_USER QoS similarly to rmw_cyclone_dds here:
         // https://github.com/ros2/rmw_cyclonedds/blob/2263814fab142ac19dd3395971fb1f358d22a653/rmw_cyclonedds_cpp/src/rmw_node.cpp#L5028C17-L5028C17
@@ -134,7 +182,8 @@ impl RouteServiceSrv<'_> {
         qos.user_data = Some(user_data.into_bytes());
 
         // create DDS Writer to send requests coming from Zenoh to the Service
-        let req_topic_name = format!("rq{ros2_name}Request");
+        let req_topic_name =
+            dds_topic_name("rq", &ros2_name, "Request", context.config.long_name_policy)?;
         let req_type_name = ros2_service_type_to_request_dds_type(&ros2_type);
         let req_writer = create_dds_writer(
             context.participant,
@@ -158,11 +207,23 @@ impl RouteServiceSrv<'_> {
         );
 
         // map of queries in progress
-        let queries_in_progress: Arc<RwLock<HashMap<CddsRequestHeader, Query>>> =
-            Arc::new(RwLock::new(HashMap::new()));
+        let queries_in_progress = Arc::new(RwLock::new(QueriesInProgress::new(
+            queries_timeout + QUERIES_EXPIRY_GRACE,
+        )));
+        let idempotency_cache = Arc::new(Mutex::new(IdempotencyCache::default()));
+        let stats = Arc::new(RouteStats::new(context.config.get_stats_rate_half_life()));
+        let calls = Arc::new(ServiceMetrics::default());
+        let concurrency = Arc::new(ConcurrencyLimit::new(
+            context.config.get_max_concurrent_requests(&ros2_name),
+        ));
+        stats.on_event(
+            RouteEventKind::Created,
+            format!("created with type {ros2_type} (Zenoh key: {zenoh_key_expr})"),
+        );
 
         // create DDS Reader to receive replies and route them to Zenoh
-        let rep_topic_name = format!("rr{ros2_name}Reply");
+        let rep_topic_name =
+            dds_topic_name("rr", &ros2_name, "Reply", context.config.long_name_policy)?;
         let rep_type_name = ros2_service_type_to_reply_dds_type(&ros2_type);
         let rep_reader = create_dds_reader(
             context.participant,
@@ -172,14 +233,26 @@ impl RouteServiceSrv<'_> {
             true,
             qos,
             None,
+            false,
             {
                 let queries_in_progress = queries_in_progress.clone();
+                let idempotency_cache = idempotency_cache.clone();
                 let zenoh_key_expr = zenoh_key_expr.clone();
+                let zsession = context.zsession.clone();
+                let stats = stats.clone();
+                let calls = calls.clone();
+                let concurrency = concurrency.clone();
                 move |sample| {
                     route_dds_reply_to_zenoh(
                         sample,
                         zenoh_key_expr.clone(),
+                        &zsession,
                         &mut zwrite!(queries_in_progress),
+                        &idempotency_cache,
+                        &stats,
+                        &calls,
+                        &concurrency,
+                        priority,
                         &route_id,
                     );
                 }
@@ -196,14 +269,20 @@ impl RouteServiceSrv<'_> {
             zenoh_key_expr,
             context,
             zenoh_queryable: None,
+            priority,
             req_writer,
             rep_reader,
             client_guid,
             sequence_number: Arc::new(AtomicU64::default()),
             queries_in_progress,
+            idempotency_cache,
+            stats,
+            calls,
+            concurrency,
+            callers: Arc::new(TopCallers::default()),
             liveliness_token: None,
             remote_routes: HashSet::new(),
-            local_nodes: HashSet::new(),
+            local_nodes: HashMap::new(),
         })
     }
 
@@ -225,10 +304,14 @@ impl RouteServiceSrv<'_> {
 
         // create the zenoh Queryable
         // if Reader is TRANSIENT_LOCAL, use a PublicationCache to store historical data
-        let queries_in_progress: Arc<RwLock<HashMap<CddsRequestHeader, Query>>> =
-            self.queries_in_progress.clone();
+        let queries_in_progress = self.queries_in_progress.clone();
+        let idempotency_cache = self.idempotency_cache.clone();
         let sequence_number: Arc<AtomicU64> = self.sequence_number.clone();
-        let route_id: String = self.to_string();
+        let stats = self.stats.clone();
+        let calls = self.calls.clone();
+        let concurrency = self.concurrency.clone();
+        let callers = self.callers.clone();
+        let route_id: Arc<str> = self.to_string().into();
         let client_guid = self.client_guid;
         let req_writer: i32 = self.req_writer;
         self.zenoh_queryable = Some(
@@ -236,14 +319,50 @@ impl RouteServiceSrv<'_> {
                 .zsession
                 .declare_queryable(&self.zenoh_key_expr)
                 .callback(move |query| {
-                    route_zenoh_request_to_dds(
-                        query,
-                        &mut zwrite!(queries_in_progress),
-                        &sequence_number,
-                        &route_id,
-                        client_guid,
-                        req_writer,
-                    )
+                    // beyond `max_concurrent_requests`, the request waits for a request in progress to complete
+                    let received = Instant::now();
+                    // the timeout of the remote client, if known
+                    let timeout_hint = query.attachment().and_then(timeout_from_attachment);
+                    let task: Task = Box::new({
+                        let queries_in_progress = queries_in_progress.clone();
+                        let idempotency_cache = idempotency_cache.clone();
+                        let sequence_number = sequence_number.clone();
+                        let stats = stats.clone();
+                        let calls = calls.clone();
+                        let concurrency = concurrency.clone();
+                        let callers = callers.clone();
+                        let route_id = route_id.clone();
+                        move || {
+                            let mut queries_in_progress = zwrite!(queries_in_progress);
+                            // the remote client gives up on a request waiting longer than its timeout
+                            if received.elapsed() >= queries_in_progress.ttl_with_hint(timeout_hint) {
+                                tracing::warn!("{route_id}: request waited too long for the requests in progress - dropping it");
+                                stats.on_received(query.value().map_or(0, |v| v.payload.len()));
+                                stats.on_dropped();
+                                concurrency.on_completed();
+                                return;
+                            }
+                            let in_progress = route_zenoh_request_to_dds(
+                                query,
+                                &mut queries_in_progress,
+                                &idempotency_cache,
+                                &sequence_number,
+                                &stats,
+                                &calls,
+                                &concurrency,
+                                &callers,
+                                &route_id,
+                                client_guid,
+                                req_writer,
+                            );
+                            if !in_progress {
+                                concurrency.on_completed();
+                            }
+                        }
+                    });
+                    if let Some(task) = concurrency.admit(task) {
+                        task();
+                    }
                 })
                 .res()
                 .await
@@ -289,6 +408,16 @@ impl RouteServiceSrv<'_> {
         self.liveliness_token = None;
     }
 
+    /// Re-declare the Zenoh Queryable and re-assert the route announcement over Zenoh
+    /// (e.g. after a reconnection), if announced.
+    pub async fn restore_announcement(&mut self) -> Result<(), String> {
+        if self.has_live_local_server() {
+            self.zenoh_queryable = None;
+            self.announce_route().await?;
+        }
+        Ok(())
+    }
+
     #[inline]
     pub fn add_remote_route(&mut self, plugin_id: &str, zenoh_key_expr: &keyexpr) {
         self.remote_routes
@@ -309,11 +438,14 @@ impl RouteServiceSrv<'_> {
     }
 
     #[inline]
-    pub async fn add_local_node(&mut self, node: String) {
-        self.local_nodes.insert(node);
-        tracing::debug!("{self} now serving local nodes {:?}", self.local_nodes);
-        // if 1st local node added, activate the route
-        if self.local_nodes.len() == 1 {
+    pub async fn add_local_node(&mut self, node: String, entities: ServiceSrvEntities) {
+        self.local_nodes.insert(node, entities);
+        tracing::debug!(
+            "{self} now serving local nodes {:?}",
+            self.local_nodes.keys()
+        );
+        // if a live local Server is now served, activate the route
+        if self.zenoh_queryable.is_none() && self.has_live_local_server() {
             if let Err(e) = self.announce_route().await {
                 tracing::error!("{self} activation failed: {e}");
             }
@@ -323,13 +455,26 @@ impl RouteServiceSrv<'_> {
     #[inline]
     pub fn remove_local_node(&mut self, node: &str) {
         self.local_nodes.remove(node);
-        tracing::debug!("{self} now serving local nodes {:?}", self.local_nodes);
-        // if last local node removed, deactivate the route
-        if self.local_nodes.is_empty() {
+        tracing::debug!(
+            "{self} now serving local nodes {:?}",
+            self.local_nodes.keys()
+        );
+        // if no live local Server remains, deactivate the route at once
+        // for the remote clients' queries to fail fast (without Queryable) rather than timing out
+        if self.zenoh_queryable.is_some() && !self.has_live_local_server() {
             self.retire_route();
         }
     }
 
+    // True if one of the Service Servers of the local nodes is still live in DDS
+    // (i.e. both its Request Reader and Reply Writer are discovered)
+    fn has_live_local_server(&self) -> bool {
+        let discovered_entities = zread!(self.context.discovered_entities);
+        self.local_nodes
+            .values()
+            .any(|entities| discovered_entities.is_service_srv_live(entities))
+    }
+
     #[inline]
     pub fn is_serving_local_node(&self) -> bool {
         !self.local_nodes.is_empty()
@@ -339,16 +484,317 @@ impl RouteServiceSrv<'_> {
     pub fn is_unused(&self) -> bool {
         !self.is_serving_local_node() && !self.is_serving_remote_route()
     }
+
+    #[inline]
+    pub fn stats(&self) -> RouteStatsSnapshot {
+        self.stats.snapshot()
+    }
+
+    #[inline]
+    pub fn events(&self) -> Vec<RouteEvent> {
+        self.stats.events()
+    }
+
+    /// Reset the statistics, the calls metrics and the callers of the route (via the admin space)
+    pub fn reset_stats(&self) {
+        self.stats.reset();
+        self.calls.reset();
+        self.callers.reset();
+        zwrite!(self.queries_in_progress).reset_counters();
+        self.stats
+            .on_event(RouteEventKind::StatsReset, "statistics reset".into());
+    }
+
+    /// Drop at once the requests waiting for the reply of the Service Server (via the admin space),
+    /// e.g. for a Server that doesn't reply anymore. Their queries being finalized without reply,
+    /// the remote clients' calls fail (or are retried) at once. Returns the count of requests dropped.
+    pub fn flush_pending(&self) -> usize {
+        let flushed = zwrite!(self.queries_in_progress).flush();
+        zlock!(self.idempotency_cache).forget_in_progress();
+        let count = flushed.len();
+        // the queries are finalized when dropped, out of the lock
+        drop(flushed);
+        for _ in 0..count {
+            self.concurrency.on_completed();
+        }
+        tracing::info!("{self}: {count} requests in progress flushed");
+        self.stats.on_event(
+            RouteEventKind::PendingFlushed,
+            format!("{count} requests in progress flushed"),
+        );
+        count
+    }
+}
+
+/// A call identified by an idempotency key, either waiting for the Server's reply or already replied
+enum IdempotentCall {
+    InProgress(CddsRequestHeader),
+    Done(ZBuf),
+}
+
+/// The calls of the remote Service Client routes configured with `service_retries`, by idempotency key:
+/// a retried call is not re-sent to the Service Server, but replied the cached Response if already received
+/// (or else, replied when it will be received).
+#[derive(Default)]
+struct IdempotencyCache {
+    calls: HashMap<[u8; 16], (IdempotentCall, Instant)>,
+}
+
+impl IdempotencyCache {
+    fn get(&self, key: &[u8; 16], now: Instant) -> Option<&IdempotentCall> {
+        match self.calls.get(key) {
+            Some((call, t)) if now.saturating_duration_since(*t) < IDEMPOTENCY_CACHE_TTL => {
+                Some(call)
+            }
+            _ => None,
+        }
+    }
+
+    fn on_request(&mut self, key: [u8; 16], request_id: CddsRequestHeader, now: Instant) {
+        if self.calls.len() >= IDEMPOTENCY_CACHE_SIZE && !self.calls.contains_key(&key) {
+            self.calls
+                .retain(|_, (_, t)| now.saturating_duration_since(*t) < IDEMPOTENCY_CACHE_TTL);
+            if self.calls.len() >= IDEMPOTENCY_CACHE_SIZE {
+                if let Some(oldest) = self
+                    .calls
+                    .iter()
+                    .min_by_key(|(_, (_, t))| *t)
+                    .map(|(k, _)| *k)
+                {
+                    self.calls.remove(&oldest);
+                }
+            }
+        }
+        self.calls
+            .insert(key, (IdempotentCall::InProgress(request_id), now));
+    }
+
+    fn on_reply(&mut self, request_id: &CddsRequestHeader, reply: &ZBuf) {
+        for (call, _) in self.calls.values_mut() {
+            if matches!(call, IdempotentCall::InProgress(id) if id == request_id) {
+                *call = IdempotentCall::Done(reply.clone());
+                return;
+            }
+        }
+    }
+
+    // Forget the calls still waiting for the Server's reply (e.g. flushed), for their retries
+    // to be sent to the Server again rather than waiting for a reply that will be dropped
+    fn forget_in_progress(&mut self) {
+        self.calls
+            .retain(|_, (call, _)| matches!(call, IdempotentCall::Done(_)));
+    }
+}
+
+/// The outcome of the lookup of the query a reply from the Service Server is for
+enum QueryLookup<Q> {
+    InProgress(Q),
+    // the query expired before the reply
+    Late,
+    // a reply to another client of the Service
+    Unknown,
+}
+
+/// A request waiting for the reply of the Service Server: either its query, or for a long call
+/// (already replied as accepted) the key expression on which the reply is to be published
+enum PendingReply {
+    Query(Query),
+    LongCall(OwnedKeyExpr),
+}
+
+/// The queries waiting for the reply of the Service Server, by request id. A query without reply within
+/// `ttl` (or the duration of a long call) is expired: it's dropped (the remote client already gave up on it)
+/// and its reply, if ever received, is dropped as late. The expiry happens on each request or reply,
+/// under the same lock.
+struct QueriesInProgress<Q> {
+    // the queries, with their expiry time
+    queries: HashMap<CddsRequestHeader, (Q, Instant)>,
+    ttl: Duration,
+    expired_ids: VecDeque<CddsRequestHeader>,
+    expired: u64,
+    late_replies: u64,
+}
+
+impl<Q> QueriesInProgress<Q> {
+    fn new(ttl: Duration) -> QueriesInProgress<Q> {
+        QueriesInProgress {
+            queries: HashMap::new(),
+            ttl,
+            expired_ids: VecDeque::new(),
+            expired: 0,
+            late_replies: 0,
+        }
+    }
+
+    /// Expire the queries older than their TTL, returning their count
+    fn expire(&mut self, now: Instant) -> usize {
+        let mut expired: Vec<(CddsRequestHeader, Instant)> = self
+            .queries
+            .iter()
+            .filter(|(_, (_, expiry))| now >= *expiry)
+            .map(|(id, (_, expiry))| (*id, *expiry))
+            .collect();
+        // the oldest ones are the first forgotten
+        expired.sort_by_key(|(_, t)| *t);
+        for (id, _) in &expired {
+            self.queries.remove(id);
+            self.remember_expired(*id);
+        }
+        self.expired += expired.len() as u64;
+        expired.len()
+    }
+
+    // Remember the id of a query removed before its reply, for this reply to be dropped as late
+    fn remember_expired(&mut self, request_id: CddsRequestHeader) {
+        if self.expired_ids.len() >= MAX_EXPIRED_REQUESTS {
+            self.expired_ids.pop_front();
+        }
+        self.expired_ids.push_back(request_id);
+    }
+
+    /// Remove all the queries (e.g. via the admin space), returning them.
+    /// Their replies, if ever received, are dropped as late.
+    fn flush(&mut self) -> Vec<Q> {
+        let mut flushed: Vec<(CddsRequestHeader, Q, Instant)> = self
+            .queries
+            .drain()
+            .map(|(id, (query, expiry))| (id, query, expiry))
+            .collect();
+        flushed.sort_by_key(|(_, _, t)| *t);
+        flushed
+            .into_iter()
+            .map(|(id, query, _)| {
+                self.remember_expired(id);
+                query
+            })
+            .collect()
+    }
+
+    fn reset_counters(&mut self) {
+        self.expired = 0;
+        self.late_replies = 0;
+    }
+
+    /// Insert a query, expired after the queries timeout or after the timeout of its caller if known and shorter
+    fn insert(
+        &mut self,
+        request_id: CddsRequestHeader,
+        query: Q,
+        now: Instant,
+        timeout_hint: Option<Duration>,
+    ) -> usize {
+        let ttl = self.ttl_with_hint(timeout_hint);
+        self.insert_with_ttl(request_id, query, now, ttl)
+    }
+
+    /// The TTL of a query which caller gives up after `timeout_hint` (if known), bounded by the queries timeout
+    fn ttl_with_hint(&self, timeout_hint: Option<Duration>) -> Duration {
+        timeout_hint.map_or(self.ttl, |hint| hint.min(self.ttl))
+    }
+
+    fn insert_with_ttl(
+        &mut self,
+        request_id: CddsRequestHeader,
+        query: Q,
+        now: Instant,
+        ttl: Duration,
+    ) -> usize {
+        let expired = self.expire(now);
+        self.queries.insert(request_id, (query, now + ttl));
+        expired
+    }
+
+    fn contains(&self, request_id: &CddsRequestHeader) -> bool {
+        self.queries.contains_key(request_id)
+    }
+
+    fn remove(&mut self, request_id: &CddsRequestHeader) {
+        self.queries.remove(request_id);
+    }
+
+    fn take(&mut self, request_id: &CddsRequestHeader, now: Instant) -> (QueryLookup<Q>, usize) {
+        let expired = self.expire(now);
+        let lookup = match self.queries.remove(request_id) {
+            Some((query, _)) => QueryLookup::InProgress(query),
+            None if self.expired_ids.contains(request_id) => {
+                self.late_replies += 1;
+                QueryLookup::Late
+            }
+            None => QueryLookup::Unknown,
+        };
+        (lookup, expired)
+    }
+
+    fn len(&self) -> usize {
+        self.queries.len()
+    }
+}
+
+fn serialize_local_nodes<S>(
+    local_nodes: &HashMap<String, ServiceSrvEntities>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_seq(local_nodes.keys())
+}
+
+fn serialize_queries_in_progress<S, Q>(
+    queries: &Arc<RwLock<QueriesInProgress<Q>>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let queries = zread!(queries);
+    let mut s = serializer.serialize_struct("QueriesInProgress", 3)?;
+    s.serialize_field("in_progress", &queries.len())?;
+    s.serialize_field("expired", &queries.expired)?;
+    s.serialize_field("late_replies", &queries.late_replies)?;
+    s.end()
+}
+
+// On expiry of requests in progress, count them as timed out and forward the waiting ones in their place
+fn on_expired(
+    route_id: &str,
+    expired: usize,
+    calls: &ServiceMetrics,
+    concurrency: &ConcurrencyLimit<Task>,
+) {
+    if expired > 0 {
+        calls.on_timeouts(expired as u64);
+        for _ in 0..expired {
+            concurrency.on_completed();
+        }
+        tracing::warn!(
+            "{route_id}: {expired} request(s) expired without reply from the Service Server"
+        );
+    }
 }
 
+// Route the request to DDS, returning true if it's a new request in progress (i.e. waiting for its reply
+// and counted in the route's concurrency)
+#[allow(clippy::too_many_arguments)]
 fn route_zenoh_request_to_dds(
     query: Query,
-    queries_in_progress: &mut HashMap<CddsRequestHeader, Query>,
+    queries_in_progress: &mut QueriesInProgress<(PendingReply, Instant)>,
+    idempotency_cache: &Mutex<IdempotencyCache>,
     sequence_number: &AtomicU64,
+    stats: &RouteStats,
+    calls: &ServiceMetrics,
+    concurrency: &ConcurrencyLimit<Task>,
+    callers: &TopCallers,
     route_id: &str,
     client_guid: u64,
     req_writer: i32,
-) {
+) -> bool {
+    let received = Instant::now();
+    // the caller identified by the remote Service Client route, if any
+    let caller = query.attachment().and_then(caller_from_attachment);
+    // the query is not kept beyond the timeout of the remote client, if shorter than the queries timeout
+    let timeout_hint = query.attachment().and_then(timeout_from_attachment);
+    stats.on_received(query.value().map_or(0, |v| v.payload.len()));
     // Get expected endianness from the query value:
     // if any and if long enoough it shall be the Request type encoded as CDR (including 4 bytes header)
     let is_little_endian = match query.value() {
@@ -358,6 +804,64 @@ fn route_zenoh_request_to_dds(
         _ => true,
     };
 
+    // A call retried by a remote Service Client route is served only once
+    let idempotency_key = query.attachment().and_then(idempotency_key_from_attachment);
+    if let Some(key) = &idempotency_key {
+        use zenoh_core::SyncResolve;
+        match zlock!(idempotency_cache).get(key, Instant::now()) {
+            Some(IdempotentCall::Done(reply)) => {
+                tracing::debug!("{route_id}: replying the cached Response to a retried request");
+                stats.on_suppressed();
+                let len = reply.len();
+                match query
+                    .reply(Ok(Sample::new(query.key_expr().clone(), reply.clone())))
+                    .res_sync()
+                {
+                    Ok(()) => stats.on_routed(len),
+                    Err(e) => {
+                        tracing::warn!("{route_id}: replying the cached Response failed: {e}");
+                        stats.on_error();
+                    }
+                }
+                return false;
+            }
+            Some(IdempotentCall::InProgress(request_id)) => {
+                // the previous attempt's query is given up by the client: reply to this one instead
+                tracing::debug!(
+                    "{route_id}: request {request_id} retried while in progress - waiting for its reply"
+                );
+                stats.on_suppressed();
+                let request_id = *request_id;
+                // it remains the same request in progress, unless expired meanwhile
+                let expired = queries_in_progress.expire(received);
+                let new_in_progress = !queries_in_progress.contains(&request_id);
+                on_expired(route_id, expired, calls, concurrency);
+                match query.attachment().and_then(long_call_from_attachment) {
+                    Some(max_duration) => {
+                        let reply_ke = long_call_reply_key_expr(query.key_expr(), &request_id);
+                        reply_long_call_accepted(route_id, query, &request_id);
+                        queries_in_progress.insert_with_ttl(
+                            request_id,
+                            (PendingReply::LongCall(reply_ke), received),
+                            received,
+                            max_duration,
+                        );
+                    }
+                    None => {
+                        queries_in_progress.insert(
+                            request_id,
+                            (PendingReply::Query(query), received),
+                            received,
+                            timeout_hint,
+                        );
+                    }
+                };
+                return new_in_progress;
+            }
+            None => (),
+        }
+    }
+
     // Try to get request_id from Query attachment (in case it comes from another bridge).
     // Otherwise, create one using client_guid + sequence_number
     let request_id = query
@@ -376,21 +880,22 @@ fn route_zenoh_request_to_dds(
     let dds_req_buf = if let Some(value) = query.value() {
         // The query comes with some payload. It's expected to be the Request type encoded as CDR (including 4 bytes header)
         let zenoh_req_buf = &*(value.payload.contiguous());
-        if zenoh_req_buf.len() < 4 || zenoh_req_buf[1] > 1 {
-            tracing::warn!("{route_id}: received invalid request: {zenoh_req_buf:0x?}");
-            return;
-        }
-
         // Send to DDS a buffer made of
         //  - the same CDR header coming with the query
         //  - the request_id as request header as per rmw_cyclonedds here:
         //    https://github.com/ros2/rmw_cyclonedds/blob/2263814fab142ac19dd3395971fb1f358d22a653/rmw_cyclonedds_cpp/src/serdata.hpp#L73
         //  - the remaining of query payload
-        let mut dds_req_buf: Vec<u8> = Vec::new();
-        dds_req_buf.extend_from_slice(&zenoh_req_buf[..4]);
-        dds_req_buf.extend_from_slice(request_id.as_slice());
-        dds_req_buf.extend_from_slice(&zenoh_req_buf[4..]);
-        dds_req_buf
+        match insert_request_header(zenoh_req_buf, &request_id) {
+            Ok(buf) => buf,
+            Err(e) => {
+                tracing::warn!(
+                    "{route_id}: received invalid request from {} ({e}): {zenoh_req_buf:0x?}",
+                    caller.as_deref().unwrap_or("unknown caller")
+                );
+                stats.on_error();
+                return false;
+            }
+        }
     } else {
         // No query payload - send a request containing just client_guid + sequence_number
         // Send to DDS a buffer made of
@@ -416,47 +921,108 @@ fn route_zenoh_request_to_dds(
         );
     }
 
-    queries_in_progress.insert(request_id, query);
-    if let Err(e) = dds_write(req_writer, dds_req_buf) {
-        tracing::warn!("{route_id}: routing request from Zenoh to DDS failed: {e}");
-        queries_in_progress.remove(&request_id);
+    // for a long call, the query is replied as accepted once the request is written,
+    // and the reply will be published on a dedicated key expression
+    let long_call = query.attachment().and_then(long_call_from_attachment);
+    let (expired, accepted_query) = match long_call {
+        Some(max_duration) => {
+            let reply_ke = long_call_reply_key_expr(query.key_expr(), &request_id);
+            let expired = queries_in_progress.insert_with_ttl(
+                request_id,
+                (PendingReply::LongCall(reply_ke), received),
+                received,
+                max_duration,
+            );
+            (expired, Some(query))
+        }
+        None => (
+            queries_in_progress.insert(
+                request_id,
+                (PendingReply::Query(query), received),
+                received,
+                timeout_hint,
+            ),
+            None,
+        ),
+    };
+    on_expired(route_id, expired, calls, concurrency);
+    let len = dds_req_buf.len();
+    match dds_write(req_writer, dds_req_buf) {
+        Ok(()) => {
+            stats.on_routed(len);
+            calls.on_request();
+            if let Some(caller) = &caller {
+                callers.on_request(caller);
+            }
+            if let Some(key) = idempotency_key {
+                zlock!(idempotency_cache).on_request(key, request_id, Instant::now());
+            }
+            if let Some(query) = accepted_query {
+                reply_long_call_accepted(route_id, query, &request_id);
+            }
+            true
+        }
+        Err(e) => {
+            tracing::warn!(
+                "{route_id}: routing request {request_id} from {} from Zenoh to DDS failed: {e}",
+                caller.as_deref().unwrap_or("unknown caller")
+            );
+            queries_in_progress.remove(&request_id);
+            stats.on_error();
+            false
+        }
+    }
+}
+
+// Reply to the query of a long call an empty payload, meaning the request is accepted
+fn reply_long_call_accepted(route_id: &str, query: Query, request_id: &CddsRequestHeader) {
+    use zenoh_core::SyncResolve;
+    tracing::debug!("{route_id}: long call {request_id} accepted");
+    if let Err(e) = query
+        .reply(Ok(Sample::new(query.key_expr().clone(), Vec::<u8>::new())))
+        .res_sync()
+    {
+        tracing::warn!("{route_id}: replying long call {request_id} as accepted failed: {e}");
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn route_dds_reply_to_zenoh(
     sample: &DDSRawSample,
     zenoh_key_expr: OwnedKeyExpr,
-    queries_in_progress: &mut HashMap<CddsRequestHeader, Query>,
+    zsession: &Arc<Session>,
+    queries_in_progress: &mut QueriesInProgress<(PendingReply, Instant)>,
+    idempotency_cache: &Mutex<IdempotencyCache>,
+    stats: &RouteStats,
+    calls: &ServiceMetrics,
+    concurrency: &ConcurrencyLimit<Task>,
+    priority: Priority,
     route_id: &str,
 ) {
     // reply payload is expected to be the Response type encoded as CDR, including a 4 bytes header,
     // the request id as header (16 bytes). As per rmw_cyclonedds here:
     // https://github.com/ros2/rmw_cyclonedds/blob/2263814fab142ac19dd3395971fb1f358d22a653/rmw_cyclonedds_cpp/src/serdata.hpp#L73
-    if sample.len() < 20 {
-        tracing::warn!("{route_id}: received invalid response from DDS: {sample:0x?}");
-        return;
-    }
-
-    let zbuf: ZBuf = sample.into();
-    let dds_rep_buf = zbuf.contiguous();
-    let cdr_header = &dds_rep_buf[..4];
-    let is_little_endian =
-        is_cdr_little_endian(cdr_header).expect("Shouldn't happen: cdr_header is 4 bytes");
-    let request_id = CddsRequestHeader::from_slice(
-        dds_rep_buf[4..20]
-            .try_into()
-            .expect("Shouldn't happen: slice is 16 bytes"),
-        is_little_endian,
-    );
+    let dds_rep_len = sample.len();
+    let slice = ZSlice::from(sample);
+    let (request_id, zenoh_rep_buf) = match split_request_header(slice) {
+        Ok(split) => split,
+        Err(e) => {
+            tracing::warn!("{route_id}: received invalid response from DDS ({e}): {sample:0x?}");
+            stats.on_received(dds_rep_len);
+            stats.on_error();
+            return;
+        }
+    };
 
     // Check if it's one of my queries in progress. Drop otherwise
-    match queries_in_progress.remove(&request_id) {
-        Some(query) => {
+    // (the replies to the other clients of the Service are not counted in the route's statistics)
+    let (lookup, expired) = queries_in_progress.take(&request_id, Instant::now());
+    on_expired(route_id, expired, calls, concurrency);
+    match lookup {
+        QueryLookup::InProgress((pending, received)) => {
             use zenoh_core::SyncResolve;
-            let slice: ZSlice = dds_rep_buf.into_owned().into();
-            let mut zenoh_rep_buf = ZBuf::empty();
-            zenoh_rep_buf.push_zslice(slice.subslice(0, 4).unwrap());
-            zenoh_rep_buf.push_zslice(slice.subslice(20, slice.len()).unwrap());
+            concurrency.on_completed();
+            stats.on_received(dds_rep_len);
 
             if *LOG_PAYLOAD {
                 tracing::debug!("{route_id}: routing reply {request_id} from DDS to Zenoh - payload: {zenoh_rep_buf:02x?}");
@@ -467,15 +1033,408 @@ fn route_dds_reply_to_zenoh(
                 );
             }
 
-            if let Err(e) = query
-                .reply(Ok(Sample::new(zenoh_key_expr, zenoh_rep_buf)))
-                .res_sync()
-            {
-                tracing::warn!("{route_id}: routing reply for request {request_id} from DDS to Zenoh failed: {e}");
+            zlock!(idempotency_cache).on_reply(&request_id, &zenoh_rep_buf);
+            let len = zenoh_rep_buf.len();
+            let result = match pending {
+                PendingReply::Query(query) => query
+                    .reply(Ok(Sample::new(zenoh_key_expr, zenoh_rep_buf)))
+                    .res_sync(),
+                PendingReply::LongCall(reply_ke) => {
+                    publish_long_call_reply(zsession, &reply_ke, zenoh_rep_buf, priority)
+                }
+            };
+            match result {
+                Ok(()) => {
+                    stats.on_routed(len);
+                    calls.on_reply(received.elapsed());
+                }
+                Err(e) => {
+                    tracing::warn!("{route_id}: routing reply for request {request_id} from DDS to Zenoh failed: {e}");
+                    stats.on_error();
+                }
             }
         }
-        None => tracing::trace!(
+        QueryLookup::Late => {
+            tracing::warn!(
+                "{route_id}: received response from DDS for the expired request {request_id} - drop it"
+            );
+            stats.on_received(dds_rep_len);
+            stats.on_dropped();
+        }
+        QueryLookup::Unknown => tracing::trace!(
             "{route_id}: received response from DDS an unknown query: {request_id} - ignore it"
         ),
     }
 }
+
+// Publish the reply of a long call on its reply key expression, with the route's priority
+// (the only reply of a Service Server routed with it, Zenoh 0.11 not allowing to set the priority of a query's reply).
+// Not to fail the call, the reply is not dropped on congestion.
+fn publish_long_call_reply(
+    zsession: &Session,
+    reply_ke: &OwnedKeyExpr,
+    payload: ZBuf,
+    priority: Priority,
+) -> zenoh::Result<()> {
+    use zenoh_core::SyncResolve;
+    zsession
+        .put(reply_ke, payload)
+        .priority(priority)
+        .congestion_control(CongestionControl::Block)
+        .res_sync()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+    use std::time::{Duration, Instant};
+    use zenoh::buffers::ZBuf;
+    use zenoh::prelude::r#async::*;
+    use zenoh::queryable::Query;
+
+    use super::{
+        publish_long_call_reply, IdempotencyCache, IdempotentCall, QueriesInProgress, QueryLookup,
+        IDEMPOTENCY_CACHE_SIZE, IDEMPOTENCY_CACHE_TTL, MAX_EXPIRED_REQUESTS,
+    };
+    use crate::ros2_utils::{long_call_reply_key_expr, CddsRequestHeader};
+    use crate::test_utils::session_pair;
+
+    fn key(i: usize) -> [u8; 16] {
+        let mut key = [0u8; 16];
+        key[..8].copy_from_slice(&(i as u64).to_le_bytes());
+        key
+    }
+
+    #[test]
+    fn test_idempotency_cache_duplicates() {
+        let now = Instant::now();
+        let mut cache = IdempotencyCache::default();
+        let request_id = CddsRequestHeader::create(42, 1, true);
+        assert!(cache.get(&key(1), now).is_none());
+
+        // a retry while the call is in progress waits for the same request's reply
+        cache.on_request(key(1), request_id, now);
+        assert!(
+            matches!(cache.get(&key(1), now), Some(IdempotentCall::InProgress(id)) if *id == request_id)
+        );
+
+        // a retry after the reply gets the cached reply, until it expires
+        let reply = ZBuf::from(vec![0u8, 1, 0, 0, 42]);
+        cache.on_reply(&CddsRequestHeader::create(42, 2, true), &reply);
+        assert!(matches!(
+            cache.get(&key(1), now),
+            Some(IdempotentCall::InProgress(_))
+        ));
+        cache.on_reply(&request_id, &reply);
+        assert!(matches!(cache.get(&key(1), now), Some(IdempotentCall::Done(r)) if *r == reply));
+        assert!(cache.get(&key(2), now).is_none());
+        assert!(cache
+            .get(
+                &key(1),
+                now + IDEMPOTENCY_CACHE_TTL + Duration::from_secs(1)
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn test_idempotency_cache_bound() {
+        let start = Instant::now();
+        let mut cache = IdempotencyCache::default();
+        for i in 0..IDEMPOTENCY_CACHE_SIZE + 10 {
+            let now = start + Duration::from_millis(i as u64);
+            cache.on_request(key(i), CddsRequestHeader::create(42, i as u64, true), now);
+        }
+        assert_eq!(cache.calls.len(), IDEMPOTENCY_CACHE_SIZE);
+        // the oldest calls are evicted first
+        let now = start + Duration::from_secs(1);
+        assert!(cache.get(&key(9), now).is_none());
+        assert!(cache.get(&key(10), now).is_some());
+        assert!(cache.get(&key(IDEMPOTENCY_CACHE_SIZE + 9), now).is_some());
+
+        // the expired calls are purged at once
+        let later = start + IDEMPOTENCY_CACHE_TTL + Duration::from_secs(1);
+        cache.on_request(key(0), CddsRequestHeader::create(42, 0, true), later);
+        assert_eq!(cache.calls.len(), 1);
+    }
+
+    #[test]
+    fn test_queries_in_progress_expiry() {
+        let start = Instant::now();
+        let ttl = Duration::from_secs(2);
+        let mut queries = QueriesInProgress::<u64>::new(ttl);
+        // a Service Server never replying
+        for i in 0..1000u64 {
+            let now = start + Duration::from_millis(i);
+            assert_eq!(
+                queries.insert(CddsRequestHeader::create(42, i, true), i, now, None),
+                0
+            );
+        }
+        assert_eq!(queries.len(), 1000);
+
+        // all expired once the TTL elapsed, but the last request
+        let later = start + ttl + Duration::from_secs(1);
+        assert_eq!(
+            queries.insert(CddsRequestHeader::create(42, 1000, true), 1000, later, None),
+            1000
+        );
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries.expired, 1000);
+        assert_eq!(queries.expired_ids.len(), MAX_EXPIRED_REQUESTS);
+
+        // a reply to a recently expired request is late, and to an older one (or another client's) unknown
+        let id = |seq| CddsRequestHeader::create(42, seq, true);
+        assert!(matches!(
+            queries.take(&id(999), later),
+            (QueryLookup::Late, 0)
+        ));
+        assert!(matches!(
+            queries.take(&id(1), later),
+            (QueryLookup::Unknown, 0)
+        ));
+        assert!(matches!(
+            queries.take(&id(1000), later),
+            (QueryLookup::InProgress(1000), 0)
+        ));
+        assert!(matches!(
+            queries.take(&id(1000), later),
+            (QueryLookup::Unknown, 0)
+        ));
+        assert_eq!(queries.late_replies, 1);
+        assert_eq!(queries.len(), 0);
+    }
+
+    #[test]
+    fn test_queries_in_progress_late_reply() {
+        let start = Instant::now();
+        let mut queries = QueriesInProgress::<u64>::new(Duration::from_secs(2));
+        let request_id = CddsRequestHeader::create(42, 1, true);
+        queries.insert(request_id, 1, start, None);
+        // the reply within the TTL is routed
+        assert!(matches!(
+            queries.take(&request_id, start + Duration::from_secs(1)),
+            (QueryLookup::InProgress(1), 0)
+        ));
+        // the reply after the TTL is late, even if the query was not expired yet
+        queries.insert(request_id, 1, start, None);
+        assert!(matches!(
+            queries.take(&request_id, start + Duration::from_secs(2)),
+            (QueryLookup::Late, 1)
+        ));
+        assert_eq!((queries.expired, queries.late_replies), (1, 1));
+    }
+
+    #[test]
+    fn test_queries_in_progress_long_call() {
+        let start = Instant::now();
+        let mut queries = QueriesInProgress::<u64>::new(Duration::from_secs(2));
+        let id = |seq| CddsRequestHeader::create(42, seq, true);
+        queries.insert_with_ttl(id(1), 1, start, Duration::from_secs(60));
+        queries.insert(id(2), 2, start, None);
+        // the long call outlives the queries TTL, up to its own duration
+        let later = start + Duration::from_secs(10);
+        assert!(matches!(
+            queries.take(&id(2), later),
+            (QueryLookup::Late, 1)
+        ));
+        assert!(matches!(
+            queries.take(&id(1), later),
+            (QueryLookup::InProgress(1), 0)
+        ));
+        queries.insert_with_ttl(id(3), 3, start, Duration::from_secs(60));
+        assert_eq!(queries.expire(start + Duration::from_secs(60)), 1);
+        assert_eq!(queries.len(), 0);
+    }
+
+    #[test]
+    fn test_queries_in_progress_timeout_hint() {
+        let start = Instant::now();
+        let mut queries = QueriesInProgress::<u64>::new(Duration::from_secs(10));
+        let id = |seq| CddsRequestHeader::create(42, seq, true);
+        // the caller gives up before the queries timeout: its query expires with it
+        queries.insert(id(1), 1, start, Some(Duration::from_secs(1)));
+        // a caller's timeout longer than the queries timeout is bounded by it
+        queries.insert(id(2), 2, start, Some(Duration::from_secs(60)));
+        queries.insert(id(3), 3, start, None);
+        assert_eq!(queries.expire(start + Duration::from_secs(1)), 1);
+        assert!(matches!(
+            queries.take(&id(1), start + Duration::from_secs(1)),
+            (QueryLookup::Late, 0)
+        ));
+        assert_eq!(queries.expire(start + Duration::from_secs(10)), 2);
+        assert_eq!(queries.len(), 0);
+    }
+
+    #[test]
+    fn test_queries_in_progress_concurrent_clients() {
+        let now = Instant::now();
+        let mut queries = QueriesInProgress::<(u64, u64)>::new(Duration::from_secs(5));
+        // several clients using the same sequence numbers, half of them in big endian
+        for seq_num in 1..=16u64 {
+            for client in 1..=8u64 {
+                let request_id = CddsRequestHeader::create(client, seq_num, client % 2 == 0);
+                queries.insert(request_id, (client, seq_num), now, None);
+            }
+        }
+        assert_eq!(queries.len(), 8 * 16);
+        // the replies come in another order, from a little endian Service Server
+        for client in (1..=8u64).rev() {
+            for seq_num in (1..=16u64).rev() {
+                let reply_id = CddsRequestHeader::create(client, seq_num, true);
+                assert!(matches!(
+                    queries.take(&reply_id, now),
+                    (QueryLookup::InProgress(q), 0) if q == (client, seq_num)
+                ));
+            }
+        }
+        assert_eq!(queries.len(), 0);
+    }
+
+    #[test]
+    fn test_queries_in_progress_flush() {
+        let start = Instant::now();
+        let queries = Arc::new(RwLock::new(QueriesInProgress::<u64>::new(
+            Duration::from_secs(60),
+        )));
+        // 4 clients issuing requests, half of them replied, while the queries are flushed
+        let clients: Vec<_> = (1..=4u64)
+            .map(|client| {
+                let queries = queries.clone();
+                std::thread::spawn(move || {
+                    let mut replied = 0;
+                    for seq_num in 0..1000u64 {
+                        let request_id = CddsRequestHeader::create(client, seq_num, true);
+                        queries
+                            .write()
+                            .unwrap()
+                            .insert(request_id, seq_num, start, None);
+                        if seq_num % 2 == 0 {
+                            if let (QueryLookup::InProgress(_), _) =
+                                queries.write().unwrap().take(&request_id, start)
+                            {
+                                replied += 1;
+                            }
+                        }
+                    }
+                    replied
+                })
+            })
+            .collect();
+        let mut flushed = 0;
+        for _ in 0..10 {
+            flushed += queries.write().unwrap().flush().len();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        let replied: usize = clients.into_iter().map(|c| c.join().unwrap()).sum();
+        let mut queries = queries.write().unwrap();
+        flushed += queries.flush().len();
+        assert_eq!(replied + flushed, 4000);
+        assert_eq!(queries.len(), 0);
+
+        // the reply to a flushed query is late
+        let request_id = CddsRequestHeader::create(5, 1, true);
+        queries.insert(request_id, 1, start, None);
+        assert_eq!(queries.flush(), vec![1]);
+        assert!(matches!(
+            queries.take(&request_id, start),
+            (QueryLookup::Late, 0)
+        ));
+        assert_eq!(queries.late_replies, 1);
+        queries.reset_counters();
+        assert_eq!((queries.expired, queries.late_replies), (0, 0));
+    }
+
+    #[test]
+    fn test_idempotency_cache_forget_in_progress() {
+        let now = Instant::now();
+        let mut cache = IdempotencyCache::default();
+        cache.on_request(key(1), CddsRequestHeader::create(42, 1, true), now);
+        cache.on_request(key(2), CddsRequestHeader::create(42, 2, true), now);
+        cache.on_reply(
+            &CddsRequestHeader::create(42, 2, true),
+            &ZBuf::from(vec![0u8, 1, 0, 0, 42]),
+        );
+        // the flushed call is sent again to the Server, the replied one still gets its cached reply
+        cache.forget_in_progress();
+        assert!(cache.get(&key(1), now).is_none());
+        assert!(matches!(
+            cache.get(&key(2), now),
+            Some(IdempotentCall::Done(_))
+        ));
+    }
+
+    #[async_std::test]
+    async fn test_no_local_server_fails_fast() {
+        const TIMEOUT: Duration = Duration::from_secs(10);
+        let mut zconfig = zenoh::config::peer();
+        zconfig.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let session = zenoh::open(zconfig).res_async().await.unwrap();
+
+        // a live local Server: the route's Queryable is declared and replies
+        let queryable = session
+            .declare_queryable("add_two_ints")
+            .callback(|query: Query| {
+                let ke = query.key_expr().clone();
+                async_std::task::spawn(async move {
+                    query
+                        .reply(Ok(Sample::new(ke, "reply")))
+                        .res_async()
+                        .await
+                        .unwrap();
+                });
+            })
+            .res_async()
+            .await
+            .unwrap();
+        let replies = session
+            .get("add_two_ints")
+            .timeout(TIMEOUT)
+            .res_async()
+            .await
+            .unwrap();
+        assert!(replies.recv_async().await.unwrap().sample.is_ok());
+
+        // the local Server undiscovered, the route is retired undeclaring its Queryable:
+        // the remote get receives no reply at once, rather than waiting for its timeout
+        drop(queryable);
+        let start = Instant::now();
+        let replies = session
+            .get("add_two_ints")
+            .timeout(TIMEOUT)
+            .res_async()
+            .await
+            .unwrap();
+        assert!(replies.recv_async().await.is_err());
+        assert!(start.elapsed() < TIMEOUT / 4);
+    }
+
+    #[async_std::test]
+    async fn test_long_call_reply_priority() {
+        let (client, bridge) = session_pair().await;
+        let reply_ke = long_call_reply_key_expr(
+            &OwnedKeyExpr::try_from("navigate").unwrap(),
+            &CddsRequestHeader::create(42, 1, true),
+        );
+        let subscriber = client
+            .declare_subscriber(&reply_ke)
+            .res_async()
+            .await
+            .unwrap();
+        async_std::task::sleep(Duration::from_millis(500)).await;
+
+        // the reply of a long call is published with the route's priority
+        publish_long_call_reply(
+            &bridge,
+            &reply_ke,
+            ZBuf::from(vec![0u8, 1, 0, 0, 42]),
+            Priority::InteractiveHigh,
+        )
+        .unwrap();
+        let sample = async_std::future::timeout(Duration::from_secs(1), subscriber.recv_async())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(sample.payload.contiguous().as_ref(), &[0u8, 1, 0, 0, 42]);
+        assert_eq!(sample.qos.priority(), Priority::InteractiveHigh);
+    }
+}
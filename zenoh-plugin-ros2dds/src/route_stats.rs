@@ -0,0 +1,403 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::Serialize;
+use std::f64::consts::LN_2;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use zenoh_core::zlock;
+
+use crate::config::DEFAULT_STATS_RATE_HALF_LIFE;
+use crate::route_events::{RouteEvent, RouteEventKind, RouteEvents};
+
+/// The statistics of a route, updated by its callbacks on each message (or request/reply):
+///   - received: the messages entering the route (from DDS or from Zenoh)
+///   - routed: the messages re-published on the other side
+///   - dropped: the messages discarded on purpose (e.g. `max_payload_size`, Lifespan, TimeBasedFilter)
+///   - errors: the messages that are invalid or failed to be re-published
+///   - suppressed: the messages not written to DDS since no DDS Reader matches (`write_suppression`),
+///     or the Service requests retried by a remote client and not re-sent to the Server (`service_retries`)
+///   - gaps, lost: the gaps in the sequence numbers of the messages received from Zenoh,
+///     and the count of messages missing in those (see `sequence_tracking`)
+///   - msgs_per_sec, kbytes_per_sec: the rates of the routed messages (see [`RateEstimator`])
+///
+/// The counters are atomics, so always on. They're reset with the route (i.e. on its re-creation),
+/// as are the route's latest events, also recorded here to be reachable from all its callbacks.
+/// The counters and rates (but not the events) can also be reset via the admin space (`reset_stats`).
+#[derive(Default)]
+pub struct RouteStats {
+    messages_in: AtomicU64,
+    messages_out: AtomicU64,
+    bytes_in: AtomicU64,
+    bytes_out: AtomicU64,
+    dropped: AtomicU64,
+    errors: AtomicU64,
+    suppressed: AtomicU64,
+    gaps: AtomicU64,
+    lost: AtomicU64,
+    // the time of the last received or routed message, in nanoseconds since UNIX_EPOCH (0 if none)
+    last_activity: AtomicU64,
+    rates: RateEstimator,
+    events: RouteEvents,
+}
+
+impl RouteStats {
+    /// The statistics of a route, with the rates estimated over `rate_half_life` (see `stats_rate_half_life`)
+    pub fn new(rate_half_life: Duration) -> RouteStats {
+        RouteStats {
+            rates: RateEstimator::new(rate_half_life),
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    pub fn on_received(&self, bytes: usize) {
+        self.messages_in.fetch_add(1, Ordering::Relaxed);
+        self.bytes_in.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.touch();
+    }
+
+    #[inline]
+    pub fn on_routed(&self, bytes: usize) {
+        self.messages_out.fetch_add(1, Ordering::Relaxed);
+        self.bytes_out.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.rates.on_message(bytes, Instant::now());
+        self.touch();
+    }
+
+    #[inline]
+    pub fn on_dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn on_error(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn on_suppressed(&self) {
+        self.suppressed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn on_gap(&self, lost: u64) {
+        self.gaps.fetch_add(1, Ordering::Relaxed);
+        self.lost.fetch_add(lost, Ordering::Relaxed);
+    }
+
+    pub fn on_event(&self, kind: RouteEventKind, message: String) {
+        self.events.record(kind, message);
+    }
+
+    pub fn events(&self) -> Vec<RouteEvent> {
+        self.events.snapshot()
+    }
+
+    /// Reset the counters and the rates, the messages being routed meanwhile counted or not
+    pub fn reset(&self) {
+        for counter in [
+            &self.messages_in,
+            &self.messages_out,
+            &self.bytes_in,
+            &self.bytes_out,
+            &self.dropped,
+            &self.errors,
+            &self.suppressed,
+            &self.gaps,
+            &self.lost,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+        self.rates.reset();
+    }
+
+    #[inline]
+    fn touch(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        self.last_activity.fetch_max(now, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> RouteStatsSnapshot {
+        let last_activity = self.last_activity.load(Ordering::Relaxed);
+        let (msgs_per_sec, bytes_per_sec) = self.rates.rates(Instant::now());
+        RouteStatsSnapshot {
+            messages_in: self.messages_in.load(Ordering::Relaxed),
+            messages_out: self.messages_out.load(Ordering::Relaxed),
+            bytes_in: self.bytes_in.load(Ordering::Relaxed),
+            bytes_out: self.bytes_out.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            suppressed: self.suppressed.load(Ordering::Relaxed),
+            gaps: self.gaps.load(Ordering::Relaxed),
+            lost: self.lost.load(Ordering::Relaxed),
+            msgs_per_sec,
+            kbytes_per_sec: bytes_per_sec / 1000.0,
+            last_activity: (last_activity != 0)
+                .then(|| Duration::from_nanos(last_activity).as_secs_f64()),
+        }
+    }
+}
+
+/// The values of a route's statistics, as reported in admin space (with `last_activity` in seconds
+/// since UNIX_EPOCH). The statistics of several routes are aggregated via [`RouteStatsSnapshot::merge()`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct RouteStatsSnapshot {
+    pub messages_in: u64,
+    pub messages_out: u64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    pub dropped: u64,
+    pub errors: u64,
+    pub suppressed: u64,
+    pub gaps: u64,
+    pub lost: u64,
+    pub msgs_per_sec: f64,
+    pub kbytes_per_sec: f64,
+    pub last_activity: Option<f64>,
+}
+
+impl RouteStatsSnapshot {
+    pub fn merge(mut self, other: RouteStatsSnapshot) -> RouteStatsSnapshot {
+        self.messages_in += other.messages_in;
+        self.messages_out += other.messages_out;
+        self.bytes_in += other.bytes_in;
+        self.bytes_out += other.bytes_out;
+        self.dropped += other.dropped;
+        self.errors += other.errors;
+        self.suppressed += other.suppressed;
+        self.gaps += other.gaps;
+        self.lost += other.lost;
+        self.msgs_per_sec += other.msgs_per_sec;
+        self.kbytes_per_sec += other.kbytes_per_sec;
+        self.last_activity = match (self.last_activity, other.last_activity) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        self
+    }
+}
+
+/// The rates (per second) of the routed messages and bytes, as exponentially weighted moving averages:
+/// each message accounts for `ln(2) / half_life` in the rate, decaying by half every `half_life`.
+/// For a steady traffic, the rates converge to the actual ones after a few half-lives.
+/// The decay since the last message is computed on read, so the rates fall to 0 when the traffic stops.
+pub struct RateEstimator {
+    half_life: f64,
+    // the rates of messages and bytes, as of the last message (if any)
+    state: Mutex<(f64, f64, Option<Instant>)>,
+}
+
+impl RateEstimator {
+    pub fn new(half_life: Duration) -> RateEstimator {
+        RateEstimator {
+            half_life: half_life.as_secs_f64(),
+            state: Mutex::new((0.0, 0.0, None)),
+        }
+    }
+
+    pub fn on_message(&self, bytes: usize, now: Instant) {
+        let weight = LN_2 / self.half_life;
+        let mut state = zlock!(self.state);
+        let (msgs, bytes_rate, last) = *state;
+        // the callbacks of a route can run concurrently: `now` might be slightly before the last message
+        let decay = last.map_or(0.0, |t| self.decay(now.saturating_duration_since(t)));
+        *state = (
+            msgs * decay + weight,
+            bytes_rate * decay + bytes as f64 * weight,
+            Some(last.map_or(now, |t| t.max(now))),
+        );
+    }
+
+    pub fn reset(&self) {
+        *zlock!(self.state) = (0.0, 0.0, None);
+    }
+
+    /// The rates of messages and bytes per second, decayed until `now`
+    pub fn rates(&self, now: Instant) -> (f64, f64) {
+        let (msgs, bytes, last) = *zlock!(self.state);
+        match last {
+            Some(t) => {
+                let decay = self.decay(now.saturating_duration_since(t));
+                (msgs * decay, bytes * decay)
+            }
+            None => (0.0, 0.0),
+        }
+    }
+
+    #[inline]
+    fn decay(&self, elapsed: Duration) -> f64 {
+        (-LN_2 * elapsed.as_secs_f64() / self.half_life).exp()
+    }
+}
+
+impl Default for RateEstimator {
+    fn default() -> Self {
+        RateEstimator::new(Duration::from_secs_f32(DEFAULT_STATS_RATE_HALF_LIFE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RateEstimator, RouteStats, RouteStatsSnapshot};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_route_stats() {
+        let stats = RouteStats::default();
+        assert_eq!(stats.snapshot(), RouteStatsSnapshot::default());
+
+        // 100 messages of 10 bytes: 90 routed, 6 dropped, 4 failed
+        for i in 0..100 {
+            stats.on_received(10);
+            match i % 10 {
+                0 if i < 60 => stats.on_dropped(),
+                1 if i < 40 => stats.on_error(),
+                _ => stats.on_routed(10),
+            }
+        }
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.messages_in, 100);
+        assert_eq!(snapshot.bytes_in, 1000);
+        assert_eq!(snapshot.messages_out, 90);
+        assert_eq!(snapshot.bytes_out, 900);
+        assert_eq!(snapshot.dropped, 6);
+        assert_eq!(snapshot.errors, 4);
+        assert_eq!(snapshot.suppressed, 0);
+        assert!(snapshot.last_activity.is_some());
+
+        let json = serde_json::to_value(snapshot).unwrap();
+        assert_eq!(json["messages_out"], 90);
+        assert_eq!(json["dropped"], 6);
+        assert!(json["last_activity"].is_f64());
+        assert!(
+            serde_json::to_value(RouteStatsSnapshot::default()).unwrap()["last_activity"].is_null()
+        );
+    }
+
+    #[test]
+    fn test_route_stats_reset() {
+        let stats = RouteStats::default();
+        for _ in 0..10 {
+            stats.on_received(10);
+            stats.on_routed(10);
+        }
+        stats.on_dropped();
+        stats.on_gap(2);
+        let last_activity = stats.snapshot().last_activity;
+        stats.reset();
+        let snapshot = stats.snapshot();
+        assert_eq!(
+            snapshot,
+            RouteStatsSnapshot {
+                last_activity,
+                ..Default::default()
+            }
+        );
+        // counting again from 0
+        stats.on_received(10);
+        stats.on_routed(10);
+        let snapshot = stats.snapshot();
+        assert_eq!((snapshot.messages_in, snapshot.messages_out), (1, 1));
+        assert!(snapshot.msgs_per_sec > 0.0);
+    }
+
+    #[test]
+    fn test_route_stats_merge() {
+        let a = RouteStats::default();
+        let b = RouteStats::default();
+        a.on_received(5);
+        a.on_routed(5);
+        b.on_received(7);
+        b.on_error();
+        b.on_suppressed();
+        b.on_gap(3);
+        let merged = [a.snapshot(), b.snapshot(), RouteStatsSnapshot::default()]
+            .into_iter()
+            .fold(RouteStatsSnapshot::default(), RouteStatsSnapshot::merge);
+        assert_eq!(merged.messages_in, 2);
+        assert_eq!(merged.bytes_in, 12);
+        assert_eq!(merged.messages_out, 1);
+        assert_eq!(merged.bytes_out, 5);
+        assert_eq!(merged.errors, 1);
+        assert_eq!(merged.suppressed, 1);
+        assert_eq!(merged.gaps, 1);
+        assert_eq!(merged.lost, 3);
+        // the last activity of all the routes
+        let (t_a, t_b) = (a.snapshot().last_activity, b.snapshot().last_activity);
+        assert_eq!(merged.last_activity, Some(t_a.unwrap().max(t_b.unwrap())));
+    }
+
+    #[test]
+    fn test_rate_estimator() {
+        let estimator = RateEstimator::new(Duration::from_secs(5));
+        let start = Instant::now();
+        assert_eq!(estimator.rates(start), (0.0, 0.0));
+
+        // 100 messages of 1000 bytes per second for 60s (12 half-lives): the rates converge
+        let mut now = start;
+        for _ in 0..6000 {
+            now += Duration::from_millis(10);
+            estimator.on_message(1000, now);
+        }
+        let (msgs, bytes) = estimator.rates(now);
+        assert!((msgs - 100.0).abs() < 1.0, "{msgs} msgs/s");
+        assert!((bytes - 100_000.0).abs() < 1000.0, "{bytes} bytes/s");
+
+        // halved traffic: the rate is about halfway after a half-life
+        for _ in 0..250 {
+            now += Duration::from_millis(20);
+            estimator.on_message(1000, now);
+        }
+        let (msgs, _) = estimator.rates(now);
+        assert!((msgs - 75.0).abs() < 2.0, "{msgs} msgs/s");
+    }
+
+    #[test]
+    fn test_rate_estimator_idle_decay() {
+        let estimator = RateEstimator::new(Duration::from_secs(2));
+        let start = Instant::now();
+        for i in 1..=200 {
+            estimator.on_message(10, start + Duration::from_millis(i * 50));
+        }
+        let last = start + Duration::from_millis(200 * 50);
+        let (msgs, bytes) = estimator.rates(last);
+        assert!((msgs - 20.0).abs() < 1.0, "{msgs} msgs/s");
+        // without any new message, the rates are halved every half-life when read
+        let (halved, halved_bytes) = estimator.rates(last + Duration::from_secs(2));
+        assert!((halved - msgs / 2.0).abs() < 1e-9);
+        assert!((halved_bytes - bytes / 2.0).abs() < 1e-9);
+        // and fall to 0 after a while
+        let (msgs, bytes) = estimator.rates(last + Duration::from_secs(120));
+        assert!(msgs < 1e-6 && bytes < 1e-6);
+        // reading the rates doesn't change them
+        assert!((estimator.rates(last + Duration::from_secs(2)).0 - halved).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_route_stats_rates() {
+        let stats = RouteStats::new(Duration::from_secs(1));
+        stats.on_received(2000);
+        stats.on_routed(2000);
+        let snapshot = stats.snapshot();
+        // a single message accounts for ln(2)/half_life
+        assert!(snapshot.msgs_per_sec > 0.0 && snapshot.msgs_per_sec <= std::f64::consts::LN_2);
+        assert!((snapshot.kbytes_per_sec - 2.0 * snapshot.msgs_per_sec).abs() < 1e-3);
+        let json = serde_json::to_value(snapshot).unwrap();
+        assert!(json["msgs_per_sec"].is_f64());
+        assert!(json["kbytes_per_sec"].is_f64());
+    }
+}
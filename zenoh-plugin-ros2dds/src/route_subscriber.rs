@@ -12,28 +12,74 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
+use cyclors::qos::TimeBasedFilter;
 use cyclors::{
-    dds_entity_t, dds_get_entity_sertype, dds_strretcode, dds_writecdr, ddsi_serdata_from_ser_iov,
-    ddsi_serdata_kind_SDK_DATA, ddsi_sertype, ddsrt_iov_len_t, ddsrt_iovec_t,
+    dds_assert_liveliness, dds_entity_t, dds_forwardcdr, dds_get_entity_sertype, dds_strretcode,
+    dds_writecdr, ddsi_serdata_from_ser_iov, ddsi_serdata_kind_SDK_DATA, ddsi_sertype,
+    ddsrt_iov_len_t, ddsrt_iovec_t, DDS_RETCODE_TIMEOUT,
 };
-use serde::Serialize;
-use std::collections::HashSet;
+use serde::{Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
 use std::{ffi::CStr, fmt, time::Duration};
+use zenoh::buffers::ZBuf;
 use zenoh::liveliness::LivelinessToken;
 use zenoh::prelude::*;
 use zenoh::query::ReplyKeyExpr;
+use zenoh::subscriber::Reliability;
+use zenoh::Session;
 use zenoh::{prelude::r#async::AsyncResolve, subscriber::Subscriber};
+use zenoh_core::zlock;
 use zenoh_ext::{FetchingSubscriber, SubscriberBuilderExt};
 
-use crate::dds_utils::{create_dds_writer, ddsrt_iov_len_from_usize, delete_dds_entity, get_guid};
-use crate::liveliness_mgt::new_ke_liveliness_sub;
-use crate::qos_helpers::is_transient_local;
-use crate::ros2_utils::{is_message_for_action, ros2_message_type_to_dds_type};
+use crate::action_feedback::{
+    feedback_goal_id, is_action_feedback_topic, is_action_status_topic, FeedbackThrottle,
+};
+use crate::batcher::{is_batch, split_batch};
+use crate::cdr_check::{serialize_cdr_check, CdrCheck};
+use crate::compression::decompress_zbuf;
+use crate::config::{Config, DeadlineMissPolicy, ReliabilityOverride};
+use crate::dds_retry::{serialize_dds_retry, DdsRetry};
+use crate::dds_types::DDSInstanceState;
+use crate::dds_utils::{
+    create_dds_writer, create_dds_writer_with_matching, dds_write_with_instance_state,
+    ddsrt_iov_len_from_usize, delete_dds_entity, get_guid, is_dds_entity_alive, DDS_ENTITY_NULL,
+};
+use crate::dedup::{serialize_deduplicator, Deduplicator};
+use crate::forward_discovery::RouteKind;
+use crate::liveliness_mgt::{new_ke_liveliness_sub, qos_to_key_expr};
+use crate::payload_limit::{serialize_payload_limit, PayloadSizeLimit};
+use crate::qos_helpers::{
+    apply_congestion_control, apply_partition, apply_reliability_override, apply_topic_qos,
+    cap_keep_all_history, filter_deadline, force_transient_local, get_deadline_period,
+    get_exclusive_ownership_strength, get_lifespan_duration, get_manual_liveliness_lease,
+    get_time_based_filter_separation, is_keep_all, is_transient_local, manual_liveliness_by_topic,
+    min_time_based_filter_separation, qos_incompatibilities, qos_to_json,
+    set_exclusive_ownership_strength, upgrade_offered_qos, with_durability_service,
+    with_min_history_depth,
+};
+use crate::rate_limiter::{serialize_rate_limiter, RateLimiter};
+use crate::ros2_utils::{
+    instance_chunk, instance_keyhash_from_attachment, instance_state_from_attachment,
+    is_message_for_action, message_dds_topic_and_type, origin_from_attachment,
+    ros2_name_to_raw_dds_topic, route_to_subscription_key_expr, route_to_target,
+    sequence_number_from_attachment, typed_key_expr,
+};
+use crate::route_events::{pause_event_kind, RouteEvent, RouteEventKind};
+use crate::route_stats::{RouteStats, RouteStatsSnapshot};
 use crate::routes_mgr::Context;
+use crate::sample_transform::{serialize_route_transform, RouteTransform};
+use crate::sequence_gaps::GapTracker;
+use crate::write_suppression::WriteSuppression;
 use crate::{
     dds_utils::serialize_entity_guid, qos::Qos, vec_into_raw_parts, KE_ANY_1_SEGMENT, LOG_PAYLOAD,
 };
-use crate::{serialize_option_as_bool, KE_PREFIX_PUB_CACHE};
+use crate::{serialize_atomic_bool, serialize_option_as_bool, KE_PREFIX_PUB_CACHE};
+
+// The minimal History depth of the DDS Writer of a topic in `latched_aggregate_topics`
+const LATCHED_AGGREGATE_MIN_DEPTH: i32 = 100;
 
 enum ZSubscriber<'a> {
     Subscriber(Subscriber<'a, ()>),
@@ -50,6 +96,15 @@ pub struct RouteSubscriber<'a> {
     ros2_type: String,
     // the Zenoh key expression used for routing
     zenoh_key_expr: OwnedKeyExpr,
+    // in "typed_key_exprs" mode: the Zenoh key expression with the type chunk, used for subscription
+    #[serde(skip_serializing_if = "Option::is_none")]
+    typed_key_expr: Option<OwnedKeyExpr>,
+    // in "instance_key_exprs" mode: the key expression of all the instances (with a '*' last chunk), used for subscription
+    #[serde(skip_serializing_if = "Option::is_none")]
+    instances_key_expr: Option<OwnedKeyExpr>,
+    // for a raw DDS topic (configured in "raw_dds_topics"): its DDS topic name, used verbatim
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw_dds_topic: Option<String>,
     // the context
     #[serde(skip)]
     context: Context,
@@ -57,13 +112,63 @@ pub struct RouteSubscriber<'a> {
     // `None` when route is created on a remote announcement and no local ROS2 Subscriber discovered yet
     #[serde(rename = "is_active", serialize_with = "serialize_option_as_bool")]
     zenoh_subscriber: Option<ZSubscriber<'a>>,
+    // the zenoh subscribers receiving the messages of the remote topics configured in `route_to`
+    // with this bridge's id or alias (on their scoped key expressions)
+    #[serde(skip)]
+    zenoh_route_to_subscribers: Vec<(OwnedKeyExpr, ZSubscriber<'a>)>,
     // the local DDS Writer created to serve the route (i.e. re-publish to DDS message coming from zenoh)
-    #[serde(serialize_with = "serialize_entity_guid")]
-    dds_writer: dds_entity_t,
+    #[serde(serialize_with = "serialize_route_dds_writer")]
+    dds_writer: Arc<RouteDdsWriter>,
+    // the state of the DDS Writer re-creation: "ok", "degraded" while retrying after failures, or "failed"
+    #[serde(rename = "dds_writer_state", serialize_with = "serialize_dds_retry")]
+    dds_writer_retry: Arc<Mutex<DdsRetry>>,
+    // the DDS Partitions of the DDS Writer (if not the default one)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dds_partition: Option<Vec<String>>,
     // if the Writer is TRANSIENT_LOCAL
     transient_local: bool,
+    // if the Writer is TRANSIENT_LOCAL only because the topic is configured in `force_transient_local`
+    transient_local_forced: bool,
     // queries timeout for historical publication (if TRANSIENT_LOCAL)
     queries_timeout: Duration,
+    // if the zenoh Subscriber is RELIABLE (false if "best_effort" is configured for this topic)
+    zenoh_reliable: bool,
+    // the maximum size of routed payloads, and the count of dropped oversized ones
+    #[serde(serialize_with = "serialize_payload_limit")]
+    payload_limit: Arc<PayloadSizeLimit>,
+    // the check of the CDR encapsulation header of the messages to write, and the count of dropped invalid ones
+    #[serde(rename = "dropped_invalid_cdr", serialize_with = "serialize_cdr_check")]
+    cdr_check: Arc<CdrCheck>,
+    // the limitation of the routed messages to a maximum frequency (if configured in `sub_max_frequencies`,
+    // or in `feedback_max_frequency` for an Action's feedback topic), and the count of messages it dropped
+    #[serde(serialize_with = "serialize_rate_limiter")]
+    rate_limiter: Arc<RateLimiter<Sample>>,
+    // for an Action's feedback topic: the writing of the last feedback kept by the RateLimiter for a goal,
+    // on its terminal status
+    #[serde(skip)]
+    _feedback_throttle: Option<Arc<FeedbackThrottle<Sample>>>,
+    // the transformation of the routed payloads (if one is registered for the key expression)
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_route_transform"
+    )]
+    sample_transform: Option<Arc<RouteTransform>>,
+    // if the route is paused via the admin space: the messages received from Zenoh are not written to DDS
+    #[serde(serialize_with = "serialize_atomic_bool")]
+    paused: Arc<AtomicBool>,
+    // the deduplication of the messages routed by several bridges from the same origin (see `deduplication`),
+    // and the count of dropped duplicates
+    #[serde(rename = "duplicates", serialize_with = "serialize_deduplicator")]
+    deduplicator: Arc<Deduplicator>,
+    // the detection of the messages lost on the Zenoh leg, if numbered by the remote Route Publisher
+    // (see `sequence_tracking`), and the count of lost messages from which a gap is logged
+    #[serde(skip)]
+    gap_tracker: Arc<GapTracker>,
+    #[serde(skip)]
+    gap_log_threshold: Option<u64>,
+    // the monitoring of the Deadline, if a policy is configured via `on_deadline_miss` for this topic
+    #[serde(skip)]
+    deadline_watch: Option<Arc<DeadlineWatch>>,
     // if the topic is keyless
     #[serde(skip)]
     keyless: bool,
@@ -72,23 +177,32 @@ pub struct RouteSubscriber<'a> {
     liveliness_token: Option<LivelinessToken<'a>>,
     // the list of remote routes served by this route ("<plugin_id>:<zenoh_key_expr>"")
     remote_routes: HashSet<String>,
+    // if the route serves some remote routes, i.e. if the liveliness tokens of their origin are alive.
+    // With a manual Liveliness, the DDS Writer's liveliness is asserted only while it's true.
+    #[serde(skip)]
+    origin_alive: Arc<AtomicBool>,
+    // the strength of the EXCLUSIVE Ownership announced by each remote route (if any).
+    // The DDS Writer (if EXCLUSIVE) takes the strongest one.
+    #[serde(skip)]
+    remote_ownership_strengths: HashMap<String, i32>,
     // the list of nodes served by this route
     local_nodes: HashSet<String>,
-}
-
-impl Drop for RouteSubscriber<'_> {
-    fn drop(&mut self) {
-        // remove writer's GID from ros_discovery_info message
-        match get_guid(&self.dds_writer) {
-            Ok(gid) => self.context.ros_discovery_mgr.remove_dds_writer(gid),
-            Err(e) => tracing::warn!("{self}: {e}"),
-        }
-
-        tracing::debug!("{self}: delete Writer");
-        if let Err(e) = delete_dds_entity(self.dds_writer) {
-            tracing::warn!("{}: error deleting DDS Reader:  {}", self, e);
-        }
-    }
+    // the minimum separation of the TimeBasedFilter of the local Readers (per served node),
+    // None for a Reader without TimeBasedFilter
+    #[serde(skip)]
+    local_readers_separation: HashMap<String, Option<Duration>>,
+    // the QoS of the discovered Reader the route is announced with (while serving local nodes),
+    // with the TimeBasedFilter satisfying all the local Readers
+    #[serde(skip)]
+    discovered_reader_qos: Option<Qos>,
+    // the QoS the route was created with, before its adaptation by the bridge
+    // (i.e. as announced by a remote bridge or adapted from a local discovered Reader)
+    #[serde(skip)]
+    received_qos: Qos,
+    // the incompatibilities of the DDS Writer's QoS with the local Readers (per served node),
+    // preventing the messages to be routed to those
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    qos_incompatibilities: HashMap<String, Vec<String>>,
 }
 
 impl fmt::Display for RouteSubscriber<'_> {
@@ -108,113 +222,378 @@ impl RouteSubscriber<'_> {
         ros2_type: String,
         zenoh_key_expr: OwnedKeyExpr,
         keyless: bool,
-        mut writer_qos: Qos,
+        writer_qos: Qos,
         context: Context,
     ) -> Result<RouteSubscriber<'a>, String> {
+        let received_qos = writer_qos.clone();
+        let (writer_qos, transient_local_forced) =
+            route_writer_qos(&context.config, &ros2_name, &zenoh_key_expr, &writer_qos);
         let transient_local = is_transient_local(&writer_qos);
         tracing::debug!("Route Subscriber ({zenoh_key_expr} -> {ros2_name}): creation with type {ros2_type} (transient_local:{transient_local})");
 
-        let topic_name = format!("rt{ros2_name}");
-        let type_name = ros2_message_type_to_dds_type(&ros2_type);
+        let (topic_name, type_name) =
+            message_dds_topic_and_type(&ros2_name, &ros2_type, context.config.long_name_policy)?;
+        // in "typed_key_exprs" mode, subscribe with the type appended to the key expression
+        // (not for the topics of Actions, which are announced via the Action's liveliness token)
+        let typed_key_expr = if context.config.typed_key_exprs && !is_message_for_action(&ros2_name)
+        {
+            Some(typed_key_expr(&zenoh_key_expr, &ros2_type)?)
+        } else {
+            None
+        };
+        // in "instance_key_exprs" mode, subscribe to all the instances of a keyed topic,
+        // each one being published with its key hash appended to the key expression
+        let instance_keyed = !keyless && context.config.is_instance_keyed(&ros2_name);
+        let instances_key_expr = instance_keyed
+            .then(|| &**typed_key_expr.as_ref().unwrap_or(&zenoh_key_expr) / *KE_ANY_1_SEGMENT);
         let queries_timeout = context.config.get_queries_timeout_tl_sub(&ros2_name);
 
-        // force RELIABLE QoS for Writers (#23)
-        if let Some(cyclors::qos::Reliability {
-            kind: cyclors::qos::ReliabilityKind::BEST_EFFORT,
-            ..
-        }) = &mut writer_qos.reliability
-        {
-            // Per DDS specification, the default Reliability value for DataWriters is RELIABLE with max_blocking_time=100ms
-            // Thus just use default value.
-            writer_qos.reliability = None;
-        }
+        let dds_partition = writer_qos.partition.clone();
+        let zenoh_reliable = zenoh_reliable(&context.config, &ros2_name);
+
+        // Maximum payload size if configured for this topic
+        let payload_limit = Arc::new(PayloadSizeLimit::new(
+            context.config.get_max_payload_size(&ros2_name).unwrap_or(0),
+        ));
+        let sample_transform = RouteTransform::for_route(&zenoh_key_expr);
 
         tracing::debug!(
             "Route Subscriber ({zenoh_key_expr} -> {ros2_name}): create Writer with {writer_qos:?}"
         );
-        let dds_writer = create_dds_writer(
-            context.participant,
+        let dds_writer = RouteDdsWriter::create(
+            format!("Route Subscriber (Zenoh:{zenoh_key_expr} -> ROS:{ros2_name})"),
+            context.clone(),
+            ros2_name.clone(),
             topic_name,
             type_name,
             keyless,
+            instance_keyed,
             writer_qos,
         )?;
-        // add writer's GID in ros_discovery_info message
-        context
-            .ros_discovery_mgr
-            .add_dds_writer(get_guid(&dds_writer)?);
+        dds_writer.stats.on_event(
+            RouteEventKind::Created,
+            format!("created with type {ros2_type} (Zenoh key: {zenoh_key_expr})"),
+        );
+
+        // Maximum frequency if configured for this topic, the messages kept by the RateLimiter
+        // being written by its timer
+        let rate_limiter = Arc::new(RateLimiter::new(
+            context.config.get_rate_limit_policy(&ros2_name),
+            context
+                .config
+                .get_sub_max_frequencies(&ros2_name)
+                .or_else(|| context.config.get_feedback_max_frequency(&ros2_name)),
+        ));
+        let write_kept = {
+            let dds_writer = Arc::downgrade(&dds_writer);
+            let ros2_name = ros2_name.clone();
+            Arc::new(move |s: Sample| {
+                if let Some(dds_writer) = dds_writer.upgrade() {
+                    dds_writer.write(s, &ros2_name);
+                }
+            })
+        };
+        rate_limiter.spawn_timer({
+            let write_kept = write_kept.clone();
+            move |s: Sample| write_kept(s)
+        });
+        // for an Action's feedback topic, the messages are rate limited per goal,
+        // the last one kept for a goal being written as soon as the goal terminates
+        let feedback_throttle = is_action_feedback_topic(&ros2_name).then(|| {
+            let throttle = Arc::new(FeedbackThrottle::new(
+                rate_limiter.clone(),
+                move |s: Sample| write_kept(s),
+            ));
+            context
+                .feedback_throttles
+                .register(RouteKind::Subscriber, &ros2_name, &throttle);
+            throttle
+        });
+
+        // with a manual Liveliness, assert the DDS Writer's liveliness while the origin is alive,
+        // letting its lease expire otherwise (as for the origin's Writer)
+        let origin_alive = Arc::new(AtomicBool::new(false));
+        if get_manual_liveliness_lease(&dds_writer.qos()).is_some() {
+            spawn_liveliness_asserter(&dds_writer, &origin_alive);
+        }
+        let cdr_check = Arc::new(CdrCheck::new(context.config.forward_invalid_cdr));
 
         Ok(RouteSubscriber {
+            raw_dds_topic: ros2_name_to_raw_dds_topic(&ros2_name).map(String::from),
             ros2_name,
             ros2_type,
             zenoh_key_expr,
+            typed_key_expr,
+            instances_key_expr,
             context,
             zenoh_subscriber: None,
+            zenoh_route_to_subscribers: Vec::new(),
+            dds_writer_retry: dds_writer.retry.clone(),
             dds_writer,
+            dds_partition,
             transient_local,
+            transient_local_forced,
             queries_timeout,
+            zenoh_reliable,
+            payload_limit,
+            cdr_check,
+            rate_limiter,
+            _feedback_throttle: feedback_throttle,
+            sample_transform,
+            paused: Arc::new(AtomicBool::new(false)),
+            deduplicator: Arc::new(Deduplicator::new(Instant::now())),
+            gap_tracker: Arc::new(GapTracker::default()),
+            gap_log_threshold: context.config.sequence_gap_log_threshold,
+            deadline_watch: None,
             keyless,
             liveliness_token: None,
             remote_routes: HashSet::new(),
+            origin_alive,
+            remote_ownership_strengths: HashMap::new(),
             local_nodes: HashSet::new(),
+            local_readers_separation: HashMap::new(),
+            discovered_reader_qos: None,
+            received_qos,
+            qos_incompatibilities: HashMap::new(),
         })
     }
 
+    // The key expression of the Zenoh Subscriber: with the type chunk in "typed_key_exprs" mode,
+    // and with a '*' chunk for all the instances in "instance_key_exprs" mode
+    #[inline]
+    fn subscription_key_expr(&self) -> &OwnedKeyExpr {
+        self.instances_key_expr
+            .as_ref()
+            .or(self.typed_key_expr.as_ref())
+            .unwrap_or(&self.zenoh_key_expr)
+    }
+
     // Announce the route over Zenoh via a LivelinessToken
     async fn announce_route(&mut self, discovered_reader_qos: &Qos) -> Result<(), String> {
         tracing::debug!("{self} activate");
         // Callback routing message received by Zenoh subscriber to DDS Writer (if set)
         let ros2_name = self.ros2_name.clone();
-        let dds_writer = self.dds_writer;
-        let subscriber_callback = move |s: Sample| {
-            route_zenoh_message_to_dds(s, &ros2_name, dds_writer);
+        let dds_writer = self.dds_writer.clone();
+        let payload_limit = self.payload_limit.clone();
+        let cdr_check = self.cdr_check.clone();
+        let rate_limiter = self.rate_limiter.clone();
+        let feedback_topic = is_action_feedback_topic(&ros2_name);
+        let status_topic = is_action_status_topic(&ros2_name);
+        let feedback_throttles = self.context.feedback_throttles.clone();
+        let sample_transform = self.sample_transform.clone();
+        let route_id = self.to_string();
+        self.deadline_watch = self.start_deadline_watch(discovered_reader_qos);
+        let deadline_watch = self.deadline_watch.clone();
+        let lifespan = get_lifespan_duration(&self.dds_writer.qos());
+        let paused = self.paused.clone();
+        let deduplicator = self.deduplicator.clone();
+        let gap_tracker = self.gap_tracker.clone();
+        let gap_log_threshold = self.gap_log_threshold;
+        // route a message to the DDS Writer (a batch being routed message per message)
+        let route_sample = {
+            let dds_writer = dds_writer.clone();
+            let route_id = route_id.clone();
+            move |mut s: Sample| {
+                if let (Some(transform), SampleKind::Put) = (&sample_transform, s.kind) {
+                    match transform.apply(s.value.payload) {
+                        Ok(Some(payload)) => s.value.payload = payload,
+                        Ok(None) => {
+                            tracing::trace!("{route_id}: message dropped by sample transform");
+                            dds_writer.stats.on_dropped();
+                            return;
+                        }
+                        Err(e) => {
+                            tracing::warn!("{route_id}: {e} - drop message");
+                            dds_writer.stats.on_error();
+                            return;
+                        }
+                    }
+                }
+                if s.kind == SampleKind::Put
+                    && !cdr_check.check(&s.value.payload.contiguous(), &route_id, || {
+                        match s.attachment().and_then(origin_from_attachment) {
+                            Some(origin) => origin.gid.to_string(),
+                            None => format!("{} (no origin attached)", s.key_expr),
+                        }
+                    })
+                {
+                    dds_writer.stats.on_dropped();
+                    dds_writer.stats.on_event(
+                        RouteEventKind::Dropped,
+                        "message with an invalid CDR header".into(),
+                    );
+                    return;
+                }
+                if payload_limit.check(s.value.payload.len(), &route_id) {
+                    let now = Instant::now();
+                    if let Some(watch) = &deadline_watch {
+                        watch.on_sample(&s, now);
+                    }
+                    // the last feedback kept for the goals terminated by an Action's status are written before it
+                    if status_topic {
+                        feedback_throttles.on_status(
+                            RouteKind::Subscriber,
+                            &ros2_name,
+                            &s.value.payload.contiguous(),
+                        );
+                    }
+                    // the disposes/unregistrations are not rate limited,
+                    // and the feedback of an Action is rate limited per goal
+                    let instance = feedback_topic
+                        .then(|| feedback_goal_id(&s.value.payload.contiguous()))
+                        .flatten()
+                        .unwrap_or_else(|| sample_instance(&s));
+                    if s.kind == SampleKind::Delete {
+                        rate_limiter.forget(&instance);
+                        dds_writer.write(s, &ros2_name);
+                    } else {
+                        let rate_limited = rate_limiter.dropped();
+                        if let Some(s) = rate_limiter.offer(instance, s, now) {
+                            dds_writer.write(s, &ros2_name);
+                        } else {
+                            tracing::trace!("{route_id}: message kept by the rate limiter");
+                        }
+                        if rate_limiter.dropped() > rate_limited {
+                            dds_writer.stats.on_event(
+                                RouteEventKind::Dropped,
+                                "message replaced by a newer one in the rate limiter".into(),
+                            );
+                        }
+                    }
+                } else {
+                    dds_writer.stats.on_dropped();
+                    dds_writer.stats.on_event(
+                        RouteEventKind::Dropped,
+                        "payload exceeding max_payload_size".into(),
+                    );
+                }
+            }
+        };
+        let subscriber_callback = move |mut s: Sample| {
+            // a message numbered by the remote Route Publisher (see `sequence_tracking`),
+            // tracked even while paused for the resumption not to appear as a gap
+            if let Some(attachment) = s.attachment() {
+                if let (Some(origin), Some(sn)) = (
+                    origin_from_attachment(attachment),
+                    sequence_number_from_attachment(attachment),
+                ) {
+                    let lost = gap_tracker.on_sequence(origin.gid, sn, Instant::now());
+                    if lost > 0 {
+                        dds_writer.stats.on_gap(lost);
+                        if gap_log_threshold.map_or(false, |threshold| lost >= threshold) {
+                            tracing::warn!(
+                                "{route_id}: {lost} messages from {} lost on the Zenoh leg",
+                                origin.gid
+                            );
+                        } else {
+                            tracing::debug!(
+                                "{route_id}: {lost} messages from {} lost on the Zenoh leg",
+                                origin.gid
+                            );
+                        }
+                    }
+                }
+            }
+            if paused.load(Ordering::Relaxed) {
+                return;
+            }
+            // a message tagged with its origin by the remote Route Publisher (see `deduplication`),
+            // already routed by another bridge connected to the same DDS domain
+            // (identified with its payload as routed, i.e. compressed alike by bridges configured alike)
+            if let Some(origin) = s.attachment().and_then(origin_from_attachment) {
+                if !deduplicator.accept(origin, &s.value.payload, Instant::now()) {
+                    tracing::trace!("{route_id}: drop duplicate message from {}", origin.gid);
+                    return;
+                }
+            }
+            dds_writer.stats.on_received(s.value.payload.len());
+            if let Some(lifespan) = lifespan {
+                if is_expired(&s, lifespan, SystemTime::now()) {
+                    tracing::debug!(
+                        "{route_id}: drop message older than its Lifespan ({lifespan:?})"
+                    );
+                    dds_writer.stats.on_dropped();
+                    return;
+                }
+            }
+            // a payload compressed by the remote Route Publisher (see `compression`)
+            if s.kind == SampleKind::Put {
+                match decompress_zbuf(s.value.payload) {
+                    Ok(payload) => s.value.payload = payload,
+                    Err(e) => {
+                        tracing::warn!("{route_id}: {e} - drop message");
+                        dds_writer.stats.on_error();
+                        return;
+                    }
+                }
+            }
+            // a batch of messages published by the remote Route Publisher (see `batching`),
+            // routed in their publication order
+            if s.kind == SampleKind::Put && is_batch(&s.value.payload.contiguous()) {
+                let messages: Result<Vec<ZBuf>, String> = {
+                    let payload = s.value.payload.contiguous();
+                    split_batch(&payload)
+                        .map(|messages| messages.into_iter().map(|m| m.to_vec().into()).collect())
+                };
+                match messages {
+                    Ok(messages) => {
+                        for payload in messages {
+                            let mut s = s.clone();
+                            s.value.payload = payload;
+                            route_sample(s);
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("{route_id}: {e} - drop message");
+                        dds_writer.stats.on_error();
+                    }
+                }
+                return;
+            }
+            route_sample(s);
         };
 
-        // create zenoh subscriber
-        // if Writer is TRANSIENT_LOCAL, use a QueryingSubscriber to fetch remote historical messages to write
-        self.zenoh_subscriber = if self.transient_local {
-            // query all PublicationCaches on "<KE_PREFIX_PUB_CACHE>/*/<routing_keyexpr>"
-            let query_selector: Selector =
-                (*KE_PREFIX_PUB_CACHE / *KE_ANY_1_SEGMENT / &self.zenoh_key_expr).into();
-            tracing::debug!("{self}: query historical messages from everybody for TRANSIENT_LOCAL Reader on {query_selector}");
-            let sub = self
-                .context
-                .zsession
-                .declare_subscriber(&self.zenoh_key_expr)
-                .callback(subscriber_callback)
-                .allowed_origin(Locality::Remote) // Allow only remote publications to avoid loops
-                .reliable()
-                .querying()
-                .query_timeout(self.queries_timeout)
-                .query_selector(query_selector)
-                .query_accept_replies(ReplyKeyExpr::Any)
-                .res()
-                .await
-                .map_err(|e| format!("{self}: failed to create FetchingSubscriber: {e}",))?;
-            Some(ZSubscriber::FetchingSubscriber(sub))
+        let reliability = if self.zenoh_reliable {
+            Reliability::Reliable
         } else {
-            let sub = self
-                .context
-                .zsession
-                .declare_subscriber(&self.zenoh_key_expr)
-                .callback(subscriber_callback)
-                .allowed_origin(Locality::Remote) // Allow only remote publications to avoid loops
-                .reliable()
-                .res()
-                .await
-                .map_err(|e| format!("{self}: failed to create Subscriber: {e}"))?;
-            Some(ZSubscriber::Subscriber(sub))
+            Reliability::BestEffort
         };
 
+        // create zenoh subscriber, and the ones for the remote topics scoped to this bridge (see `route_to`)
+        let subscriber_callback = Arc::new(subscriber_callback);
+        let key_expr = self.subscription_key_expr().clone();
+        self.zenoh_subscriber = Some(
+            self.declare_zenoh_subscriber(&key_expr, subscriber_callback.clone(), reliability)
+                .await?,
+        );
+        let mut route_to_subscribers = Vec::new();
+        for target in self.route_to_targets() {
+            let scoped_key_expr = route_to_subscription_key_expr(target, &key_expr);
+            let sub = self
+                .declare_zenoh_subscriber(
+                    &scoped_key_expr,
+                    subscriber_callback.clone(),
+                    reliability,
+                )
+                .await?;
+            route_to_subscribers.push((scoped_key_expr, sub));
+        }
+        self.zenoh_route_to_subscribers = route_to_subscribers;
+
         // if not for an Action (since actions declare their own liveliness)
         if !is_message_for_action(&self.ros2_name) {
             // create associated LivelinessToken
+            let announced_qos = filter_deadline(
+                discovered_reader_qos.clone(),
+                self.context.config.propagate_deadline,
+            );
             let liveliness_ke = new_ke_liveliness_sub(
                 &self.context.plugin_id,
                 &self.zenoh_key_expr,
                 &self.ros2_type,
                 self.keyless,
-                discovered_reader_qos,
+                &announced_qos,
+                self.typed_key_expr.is_some(),
             )?;
             let ros2_name = self.ros2_name.clone();
             self.liveliness_token = Some(
@@ -233,68 +612,317 @@ impl RouteSubscriber<'_> {
         Ok(())
     }
 
+    // Declare a zenoh subscriber on `key_expr`, calling `callback` for each received message.
+    // If Writer is TRANSIENT_LOCAL, use a QueryingSubscriber to fetch remote historical messages to write
+    async fn declare_zenoh_subscriber<F>(
+        &self,
+        key_expr: &keyexpr,
+        callback: Arc<F>,
+        reliability: Reliability,
+    ) -> Result<ZSubscriber<'a>, String>
+    where
+        F: Fn(Sample) + Send + Sync + 'static,
+    {
+        let callback = move |s: Sample| callback(s);
+        if self.transient_local {
+            // query all PublicationCaches on "<KE_PREFIX_PUB_CACHE>/*/<routing_keyexpr>"
+            let query_selector: Selector =
+                (*KE_PREFIX_PUB_CACHE / *KE_ANY_1_SEGMENT / key_expr).into();
+            tracing::debug!("{self}: query historical messages from everybody for TRANSIENT_LOCAL Reader on {query_selector}");
+            let sub = self
+                .context
+                .zsession
+                .declare_subscriber(key_expr.to_owned())
+                .callback(callback)
+                .allowed_origin(Locality::Remote) // Allow only remote publications to avoid loops
+                .reliability(reliability)
+                .querying()
+                .query_timeout(self.queries_timeout)
+                .query_selector(query_selector)
+                .query_accept_replies(ReplyKeyExpr::Any)
+                .res()
+                .await
+                .map_err(|e| format!("{self}: failed to create FetchingSubscriber: {e}",))?;
+            Ok(ZSubscriber::FetchingSubscriber(sub))
+        } else {
+            let sub = self
+                .context
+                .zsession
+                .declare_subscriber(key_expr.to_owned())
+                .callback(callback)
+                .allowed_origin(Locality::Remote) // Allow only remote publications to avoid loops
+                .reliability(reliability)
+                .res()
+                .await
+                .map_err(|e| format!("{self}: failed to create Subscriber: {e}"))?;
+            Ok(ZSubscriber::Subscriber(sub))
+        }
+    }
+
+    // The id and alias of this bridge, as the remote bridges may list them in `route_to`
+    fn route_to_targets(&self) -> Vec<&keyexpr> {
+        [
+            Some(self.context.plugin_id.as_str()),
+            self.context.config.alias.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|target| route_to_target(target).ok())
+        .collect()
+    }
+
+    fn undeclare_zenoh_subscribers(&mut self) {
+        self.zenoh_subscriber = None;
+        self.zenoh_route_to_subscribers.clear();
+    }
+
     // Retire the route over Zenoh removing the LivelinessToken
     fn retire_route(&mut self) {
         tracing::debug!("{self} deactivate");
-        // Drop Zenoh Subscriber and Liveliness token
+        // Drop Zenoh Subscriber, Liveliness token and Deadline monitoring
         // The DDS Writer remains to be discovered by local ROS nodes
-        self.zenoh_subscriber = None;
+        self.undeclare_zenoh_subscribers();
         self.liveliness_token = None;
+        self.deadline_watch = None;
+        self.discovered_reader_qos = None;
+        // re-create the DDS Writer if it was deleted on a Deadline miss
+        if let Err(e) = self.dds_writer.restore(&mut zlock!(self.dds_writer.entity)) {
+            tracing::warn!("{self}: {e}");
+        }
+    }
+
+    /// Re-declare the Zenoh Subscriber and re-assert the route announcement over Zenoh
+    /// (e.g. after a reconnection), if announced.
+    /// The old Subscriber is dropped first to not write twice the same message to DDS, while the
+    /// new LivelinessToken is declared before the old one is dropped, so the remote bridges
+    /// don't see the route retired if it was still known.
+    pub async fn restore_announcement(&mut self) -> Result<(), String> {
+        match self.discovered_reader_qos.clone() {
+            Some(qos) => {
+                self.undeclare_zenoh_subscribers();
+                self.announce_route(&qos).await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Re-create the DDS Writer if a remote Writer is announced with other QoS than the route was created with
+    /// (e.g. the only remote Publisher restarted with another Durability). The Zenoh Subscriber is kept,
+    /// unless the route switches to or from TRANSIENT_LOCAL (to query the historical publications).
+    pub async fn update_received_qos(&mut self, qos: Qos) {
+        if qos_to_key_expr(self.keyless, &qos) == qos_to_key_expr(self.keyless, &self.received_qos)
+        {
+            return;
+        }
+        let (writer_qos, transient_local_forced) = route_writer_qos(
+            &self.context.config,
+            &self.ros2_name,
+            &self.zenoh_key_expr,
+            &qos,
+        );
+        tracing::info!(
+            "{self}: re-create the DDS Writer with the QoS of the remote Writer: {writer_qos:?}"
+        );
+        let manual_liveliness = get_manual_liveliness_lease(&self.dds_writer.qos()).is_some();
+        if let Err(e) = self.dds_writer.upgrade_qos(writer_qos) {
+            tracing::warn!("{self}: failed to re-create the DDS Writer: {e}");
+            self.dds_writer.stats.on_event(
+                RouteEventKind::DdsError,
+                format!(
+                    "failed to re-create the DDS Writer with the QoS of the remote Writer: {e}"
+                ),
+            );
+            return;
+        }
+        self.dds_writer.stats.on_event(
+            RouteEventKind::QosAdapted,
+            "DDS Writer re-created with the QoS of the remote Writer".into(),
+        );
+        if !manual_liveliness && get_manual_liveliness_lease(&self.dds_writer.qos()).is_some() {
+            spawn_liveliness_asserter(&self.dds_writer, &self.origin_alive);
+        }
+        self.received_qos = qos;
+        self.dds_partition = self.dds_writer.qos().partition;
+        self.transient_local_forced = transient_local_forced;
+        let transient_local = is_transient_local(&self.dds_writer.qos());
+        if transient_local != self.transient_local {
+            self.transient_local = transient_local;
+            if let Err(e) = self.restore_announcement().await {
+                tracing::warn!("{self}: {e}");
+            }
+        }
+    }
+
+    // If a policy is configured via `on_deadline_miss` for this topic, and a Deadline is set
+    // (in priority by the discovered Reader, otherwise in the Writer's QoS), start its monitoring
+    fn start_deadline_watch(&self, discovered_reader_qos: &Qos) -> Option<Arc<DeadlineWatch>> {
+        let policy = self
+            .context
+            .config
+            .get_deadline_miss_policy(&self.ros2_name)?;
+        if policy == DeadlineMissPolicy::None {
+            return None;
+        }
+        let period = match get_deadline_period(discovered_reader_qos)
+            .or(get_deadline_period(&self.dds_writer.qos()))
+        {
+            Some(period) => period,
+            None => {
+                tracing::debug!("{self}: no Deadline QoS - ignore the configured '{policy}' policy on deadline miss");
+                return None;
+            }
+        };
+        tracing::debug!(
+            "{self}: apply '{policy}' policy on deadline miss, with deadline {period:?}"
+        );
+        let watch = Arc::new(DeadlineWatch::new(period, policy));
+        spawn_deadline_timer(
+            &watch,
+            &self.dds_writer,
+            self.ros2_name.clone(),
+            self.to_string(),
+        );
+        Some(watch)
     }
 
     /// If this route uses a FetchingSubscriber, query for historical publications
     /// using the specified Selector. Otherwise, do nothing.
     pub async fn query_historical_publications<'a>(&mut self, plugin_id: &keyexpr) {
+        let route_id = self.to_string();
+        let key_expr = self.subscription_key_expr().clone();
+        let session = &self.context.zsession;
         if let Some(ZSubscriber::FetchingSubscriber(sub)) = &mut self.zenoh_subscriber {
-            // query all PublicationCaches on "<KE_PREFIX_PUB_CACHE>/<plugin_id>/<routing_keyexpr>"
-            let query_selector: Selector =
-                (*KE_PREFIX_PUB_CACHE / plugin_id / &self.zenoh_key_expr).into();
-            tracing::debug!("Route Subscriber (Zenoh:{} -> ROS:{}): query historical messages from {plugin_id} for TRANSIENT_LOCAL Reader on {query_selector}",
-                self.zenoh_key_expr, self.ros2_name
-            );
-
-            if let Err(e) = sub
-                .fetch({
-                    let session = &self.context.zsession;
-                    let query_selector = query_selector.clone();
-                    let queries_timeout = self.queries_timeout;
-                    move |cb| {
-                        use zenoh_core::SyncResolve;
-                        session
-                            .get(&query_selector)
-                            .target(QueryTarget::All)
-                            .consolidation(ConsolidationMode::None)
-                            .accept_replies(ReplyKeyExpr::Any)
-                            .timeout(queries_timeout)
-                            .callback(cb)
-                            .res_sync()
-                    }
-                })
-                .res()
-                .await
-            {
-                tracing::warn!(
-                    "{}: query for historical publications on {} failed: {}",
-                    self,
-                    query_selector,
-                    e
-                );
+            fetch_historical_publications(
+                sub,
+                session,
+                *KE_PREFIX_PUB_CACHE / plugin_id / &key_expr,
+                self.queries_timeout,
+                &route_id,
+            )
+            .await;
+        }
+        // also for the remote topics scoped to this bridge (see `route_to`)
+        for (key_expr, sub) in &mut self.zenoh_route_to_subscribers {
+            if let ZSubscriber::FetchingSubscriber(sub) = sub {
+                fetch_historical_publications(
+                    sub,
+                    session,
+                    *KE_PREFIX_PUB_CACHE / plugin_id / &*key_expr,
+                    self.queries_timeout,
+                    &route_id,
+                )
+                .await;
             }
         }
     }
 
+    /// Pause or resume the writing to DDS of the messages received from Zenoh
+    /// (the DDS Writer, the Zenoh Subscriber and the liveliness token are kept).
+    /// On resume of a TRANSIENT_LOCAL route, the historical publications are queried again,
+    /// for the local Readers to get the latest messages published while paused.
+    pub async fn set_paused(&mut self, paused: bool) {
+        let was_paused = self.paused.swap(paused, Ordering::Relaxed);
+        if was_paused != paused {
+            self.dds_writer
+                .stats
+                .on_event(pause_event_kind(paused), "via admin space".into());
+        }
+        if was_paused && !paused {
+            self.query_historical_publications(*KE_ANY_1_SEGMENT).await;
+        }
+    }
+
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn ros2_name(&self) -> &str {
+        &self.ros2_name
+    }
+
+    #[inline]
+    pub fn ros2_type(&self) -> &str {
+        &self.ros2_type
+    }
+
+    #[inline]
+    pub fn zenoh_key_expr(&self) -> &OwnedKeyExpr {
+        &self.zenoh_key_expr
+    }
+
+    // The QoS of the 1st discovered local Reader served by this route (if any)
+    #[inline]
+    pub fn discovered_reader_qos(&self) -> Option<&Qos> {
+        self.discovered_reader_qos.as_ref()
+    }
+
     #[inline]
     pub fn add_remote_route(&mut self, plugin_id: &str, zenoh_key_expr: &keyexpr) {
         self.remote_routes
             .insert(format!("{plugin_id}:{zenoh_key_expr}"));
+        self.origin_alive.store(true, Ordering::Relaxed);
         tracing::debug!("{self} now serving remote routes {:?}", self.remote_routes);
     }
 
     #[inline]
     pub fn remove_remote_route(&mut self, plugin_id: &str, zenoh_key_expr: &keyexpr) {
-        self.remote_routes
-            .remove(&format!("{plugin_id}:{zenoh_key_expr}"));
+        let remote_route = format!("{plugin_id}:{zenoh_key_expr}");
+        self.remote_routes.remove(&remote_route);
+        self.origin_alive
+            .store(!self.remote_routes.is_empty(), Ordering::Relaxed);
         tracing::debug!("{self} now serving remote routes {:?}", self.remote_routes);
+        if self
+            .remote_ownership_strengths
+            .remove(&remote_route)
+            .is_some()
+        {
+            self.update_ownership_strength();
+        }
+    }
+
+    // Set the strength of the EXCLUSIVE Ownership announced by a remote route (None if SHARED)
+    pub fn set_remote_ownership_strength(
+        &mut self,
+        plugin_id: &str,
+        zenoh_key_expr: &keyexpr,
+        strength: Option<i32>,
+    ) {
+        let remote_route = format!("{plugin_id}:{zenoh_key_expr}");
+        match strength {
+            Some(strength) => self
+                .remote_ownership_strengths
+                .insert(remote_route, strength),
+            None => self.remote_ownership_strengths.remove(&remote_route),
+        };
+        self.update_ownership_strength();
+    }
+
+    // If the DDS Writer is EXCLUSIVE, re-create it with the strongest Ownership announced by the remote routes
+    // (the DDS Readers then take the messages from the bridge while it's stronger than the other Writers)
+    fn update_ownership_strength(&self) {
+        let writer_qos = self.dds_writer.qos();
+        let (Some(current), Some(strongest)) = (
+            get_exclusive_ownership_strength(&writer_qos),
+            self.remote_ownership_strengths.values().max().copied(),
+        ) else {
+            return;
+        };
+        if current != strongest {
+            tracing::debug!(
+                "{self}: update the DDS Writer's Ownership strength from {current} to {strongest}"
+            );
+            if let Err(e) = self
+                .dds_writer
+                .upgrade_qos(set_exclusive_ownership_strength(
+                    &writer_qos,
+                    Some(strongest),
+                ))
+            {
+                tracing::warn!("{self}: failed to update the DDS Writer's Ownership strength: {e}");
+            }
+        }
     }
 
     #[inline]
@@ -304,19 +932,61 @@ impl RouteSubscriber<'_> {
 
     #[inline]
     pub async fn add_local_node(&mut self, entity_key: String, discovered_reader_qos: &Qos) {
+        if !self.local_nodes.contains(&entity_key) {
+            self.check_qos_compatibility(&entity_key, discovered_reader_qos);
+        }
+        self.local_readers_separation.insert(
+            entity_key.clone(),
+            get_time_based_filter_separation(discovered_reader_qos),
+        );
         self.local_nodes.insert(entity_key);
         tracing::debug!("{self} now serving local nodes {:?}", self.local_nodes);
         // if 1st local node added, activate the route
         if self.local_nodes.len() == 1 {
-            if let Err(e) = self.announce_route(discovered_reader_qos).await {
+            let qos = self.with_local_readers_separation(discovered_reader_qos);
+            self.discovered_reader_qos = Some(qos.clone());
+            if let Err(e) = self.announce_route(&qos).await {
                 tracing::error!("{self} activation failed: {e}");
             }
+        } else if let Some(announced_qos) = self.discovered_reader_qos.clone() {
+            // re-announce the route if the TimeBasedFilter satisfying all the local Readers changed,
+            // retiring the previous announcement first (as it's for the same route)
+            let qos = self.with_local_readers_separation(&announced_qos);
+            if qos.time_based_filter != announced_qos.time_based_filter
+                && self.liveliness_token.is_some()
+            {
+                tracing::debug!(
+                    "{self}: re-announce the route with TimeBasedFilter {:?}",
+                    qos.time_based_filter
+                );
+                self.liveliness_token = None;
+                self.undeclare_zenoh_subscribers();
+                self.discovered_reader_qos = Some(qos.clone());
+                if let Err(e) = self.announce_route(&qos).await {
+                    tracing::error!("{self} re-activation failed: {e}");
+                }
+            }
         }
     }
 
+    // The QoS announced for the local Readers, with the TimeBasedFilter satisfying all of them
+    // (if propagated, see `propagate_time_based_filter` config)
+    fn with_local_readers_separation(&self, qos: &Qos) -> Qos {
+        let mut qos = qos.clone();
+        qos.time_based_filter =
+            min_time_based_filter_separation(self.local_readers_separation.values().copied())
+                .filter(|_| self.context.config.propagate_time_based_filter)
+                .map(|s| TimeBasedFilter {
+                    minimum_separation: s.as_nanos() as i64,
+                });
+        qos
+    }
+
     #[inline]
     pub fn remove_local_node(&mut self, entity_key: &str) {
         self.local_nodes.remove(entity_key);
+        self.local_readers_separation.remove(entity_key);
+        self.qos_incompatibilities.remove(entity_key);
         tracing::debug!("{self} now serving local nodes {:?}", self.local_nodes);
         // if last local node removed, deactivate the route
         if self.local_nodes.is_empty() {
@@ -329,13 +999,740 @@ impl RouteSubscriber<'_> {
         !self.local_nodes.is_empty()
     }
 
+    // Check that the DDS Writer's QoS is compatible with the QoS of a discovered local Reader.
+    // If not, the Writer's QoS is upgraded if `auto_adapt_qos` is configured.
+    // Otherwise, the incompatibilities are logged and reported in the admin space.
+    fn check_qos_compatibility(&mut self, entity_key: &str, reader_qos: &Qos) {
+        let writer_qos = self.dds_writer.qos();
+        let incompatibilities = qos_incompatibilities(&writer_qos, reader_qos);
+        if incompatibilities.is_empty() {
+            return;
+        }
+        if self.context.config.auto_adapt_qos {
+            tracing::info!(
+                "{self}: upgrade the DDS Writer's QoS to match the Reader of {entity_key} - {}",
+                incompatibilities.join(" ; ")
+            );
+            let upgraded =
+                manual_liveliness_by_topic(&upgrade_offered_qos(&writer_qos, reader_qos));
+            match self.dds_writer.upgrade_qos(upgraded) {
+                Ok(()) => {
+                    self.dds_writer.stats.on_event(
+                        RouteEventKind::QosAdapted,
+                        format!(
+                            "DDS Writer's QoS upgraded to match the Reader of {entity_key} - {}",
+                            incompatibilities.join(" ; ")
+                        ),
+                    );
+                    if get_manual_liveliness_lease(&writer_qos).is_none()
+                        && get_manual_liveliness_lease(&self.dds_writer.qos()).is_some()
+                    {
+                        spawn_liveliness_asserter(&self.dds_writer, &self.origin_alive);
+                    }
+                    return;
+                }
+                Err(e) => {
+                    tracing::warn!("{self}: failed to upgrade the DDS Writer's QoS: {e}");
+                    self.dds_writer.stats.on_event(
+                        RouteEventKind::DdsError,
+                        format!("failed to upgrade the DDS Writer's QoS: {e}"),
+                    );
+                }
+            }
+        }
+        tracing::warn!(
+            "{self}: the DDS Writer's QoS is incompatible with the Reader of {entity_key}, no message will be routed to it \
+            (set 'auto_adapt_qos: true' to upgrade the Writer's QoS) - {}",
+            incompatibilities.join(" ; ")
+        );
+        self.qos_incompatibilities
+            .insert(entity_key.to_string(), incompatibilities);
+    }
+
+    #[inline]
+    pub fn is_last_local_node(&self, node: &str) -> bool {
+        self.local_nodes.len() == 1 && self.local_nodes.contains(node)
+    }
+
     #[inline]
     pub fn is_unused(&self) -> bool {
         !self.is_serving_local_node() && !self.is_serving_remote_route()
     }
+
+    /// The serialization of the RateLimiter (e.g. for the admin space of the Action routes)
+    pub fn serialize_rate_limiter<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.rate_limiter.serialize(s)
+    }
+
+    #[inline]
+    pub fn stats(&self) -> RouteStatsSnapshot {
+        let mut stats = self.dds_writer.stats.snapshot();
+        stats.dropped += self.rate_limiter.dropped();
+        stats
+    }
+
+    #[inline]
+    pub fn events(&self) -> Vec<RouteEvent> {
+        self.dds_writer.stats.events()
+    }
+
+    /// The QoS of the route for the admin space (with the "detail=qos" selector parameter)
+    pub fn qos_details(&self) -> Result<serde_json::Value, serde_json::Error> {
+        Ok(serde_json::json!({
+            "received": qos_to_json(&self.received_qos)?,
+            "dds_writer": qos_to_json(&self.dds_writer.qos())?,
+            "announced": self.discovered_reader_qos.as_ref().map(qos_to_json).transpose()?,
+            "zenoh_subscriber": {
+                "reliability": if self.zenoh_reliable { "RELIABLE" } else { "BEST_EFFORT" },
+            },
+        }))
+    }
+}
+
+// The DDS Writer of a Route Subscriber.
+// It's deleted on a Deadline miss with the "teardown" policy, and then re-created on next routed message.
+// It's also re-created if deleted out from under the route (e.g. on a participant restart), or if its
+// re-creation failed, with the retries configured by `dds_retry`.
+// With `write_suppression`, the messages of a keyless topic are not written while no DDS Reader matches
+// (for a keyed topic, each message is required for the bookkeeping of its instance), unless TRANSIENT_LOCAL
+// (the Writer's history serving the late joining Readers).
+struct RouteDdsWriter {
+    route_id: String,
+    context: Context,
+    ros2_name: String,
+    topic_name: String,
+    type_name: String,
+    keyless: bool,
+    qos: Mutex<Qos>,
+    entity: Mutex<dds_entity_t>,
+    // if the topic is keyed: the last message written for each live instance (per hexadecimal key hash),
+    // required to dispose or unregister the instance
+    instances: Option<Mutex<HashMap<String, Vec<u8>>>>,
+    // if the instances are routed on distinct key expressions ("instance_key_exprs" mode)
+    instance_keyed: bool,
+    // the statistics of the messages routed from Zenoh to DDS (kept while the DDS Writer is re-created)
+    stats: RouteStats,
+    // the retries of the DDS Writer re-creation after failures
+    retry: Arc<Mutex<DdsRetry>>,
+    // the suppression of the writes while no DDS Reader matches (if `write_suppression` and keyless)
+    suppression: Option<Arc<WriteSuppression>>,
+}
+
+impl RouteDdsWriter {
+    #[allow(clippy::too_many_arguments)]
+    fn create(
+        route_id: String,
+        context: Context,
+        ros2_name: String,
+        topic_name: String,
+        type_name: String,
+        keyless: bool,
+        instance_keyed: bool,
+        qos: Qos,
+    ) -> Result<Arc<RouteDdsWriter>, String> {
+        let retry = DdsRetry::new(
+            context.config.dds_retry.max_attempts,
+            context.config.dds_retry.get_initial_period(),
+            context.config.dds_retry.get_max_period(),
+        );
+        let suppression = (context.config.write_suppression && keyless)
+            .then(|| Arc::new(WriteSuppression::default()));
+        let stats = RouteStats::new(context.config.get_stats_rate_half_life());
+        let writer = Arc::new(RouteDdsWriter {
+            route_id,
+            context,
+            ros2_name,
+            topic_name,
+            type_name,
+            keyless,
+            qos: Mutex::new(qos),
+            entity: Mutex::new(DDS_ENTITY_NULL),
+            instances: (!keyless).then(|| Mutex::new(HashMap::new())),
+            instance_keyed,
+            stats,
+            retry: Arc::new(Mutex::new(retry)),
+            suppression,
+        });
+        *zlock!(writer.entity) = writer.create_entity()?;
+        Ok(writer)
+    }
+
+    fn create_entity(&self) -> Result<dds_entity_t, String> {
+        let entity = match &self.suppression {
+            Some(suppression) => {
+                suppression.reset();
+                let suppression = suppression.clone();
+                create_dds_writer_with_matching(
+                    self.context.participant,
+                    self.topic_name.clone(),
+                    self.type_name.clone(),
+                    self.keyless,
+                    self.qos(),
+                    move |count| suppression.on_matched(count),
+                )?
+            }
+            None => create_dds_writer(
+                self.context.participant,
+                self.topic_name.clone(),
+                self.type_name.clone(),
+                self.keyless,
+                self.qos(),
+            )?,
+        };
+        // add writer's GID in ros_discovery_info message
+        self.context
+            .ros_discovery_mgr
+            .add_dds_writer(get_guid(&entity)?);
+        Ok(entity)
+    }
+
+    #[inline]
+    fn qos(&self) -> Qos {
+        zlock!(self.qos).clone()
+    }
+
+    // Re-create the DDS Writer with another QoS
+    fn upgrade_qos(&self, qos: Qos) -> Result<(), String> {
+        let mut entity = zlock!(self.entity);
+        self.delete_entity(&mut entity);
+        *zlock!(self.qos) = qos;
+        self.restore(&mut entity)
+    }
+
+    // Re-create the DDS Writer if it was deleted, by the route or out from under it.
+    // After a failure, the re-creation is retried with a backoff, and given up after `dds_retry.max_attempts`
+    // (the route being then reported to the RoutesMgr to be torn down).
+    fn restore(&self, entity: &mut dds_entity_t) -> Result<(), String> {
+        if *entity != DDS_ENTITY_NULL && !is_dds_entity_alive(*entity) {
+            tracing::warn!("{}: DDS Writer was deleted - re-create it", self.route_id);
+            self.stats.on_event(
+                RouteEventKind::DdsError,
+                "DDS Writer deleted out from under the route".into(),
+            );
+            *entity = DDS_ENTITY_NULL;
+        }
+        if *entity == DDS_ENTITY_NULL {
+            let mut retry = zlock!(self.retry);
+            let now = Instant::now();
+            if !retry.may_attempt(now) {
+                return Err(format!(
+                    "DDS Writer not re-created yet ({:?})",
+                    retry.state()
+                ));
+            }
+            tracing::debug!("{}: re-create Writer", self.route_id);
+            match self.create_entity() {
+                Ok(e) => {
+                    if retry.on_success() {
+                        tracing::info!(
+                            "{}: DDS Writer re-created - route recovered",
+                            self.route_id
+                        );
+                    }
+                    *entity = e;
+                }
+                Err(e) => {
+                    self.stats.on_event(
+                        RouteEventKind::DdsError,
+                        format!("failed to re-create DDS Writer: {e}"),
+                    );
+                    match retry.on_failure(now) {
+                        Some(backoff) => tracing::warn!(
+                            "{}: failed to re-create DDS Writer: {e} - retry in {backoff:?}",
+                            self.route_id
+                        ),
+                        None => {
+                            tracing::error!(
+                                "{}: failed to re-create DDS Writer after {} attempts: {e}",
+                                self.route_id,
+                                self.context.config.dds_retry.max_attempts
+                            );
+                            let _ = self
+                                .context
+                                .route_failures
+                                .send((RouteKind::Subscriber, self.ros2_name.clone()));
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Write a message routed from zenoh, re-creating the DDS Writer if it was deleted.
+    // The message is suppressed if no DDS Reader matches, unless the Writer is TRANSIENT_LOCAL
+    // (its history serving the late joining Readers, e.g. the historical publications routed
+    // before the matching of the local Reader is notified).
+    fn write(&self, s: Sample, ros2_name: &str) {
+        let mut entity = zlock!(self.entity);
+        match self.restore(&mut entity) {
+            Ok(()) => {
+                let s = match &self.suppression {
+                    Some(suppression) => {
+                        match suppression.offer(s, is_transient_local(&self.qos())) {
+                            Some(s) => s,
+                            None => {
+                                tracing::trace!(
+                                    "{}: no matching DDS Reader - message suppressed",
+                                    self.route_id
+                                );
+                                self.stats.on_suppressed();
+                                return;
+                            }
+                        }
+                    }
+                    None => s,
+                };
+                self.write_entity(s, ros2_name, *entity)
+            }
+            Err(e) => {
+                tracing::debug!("{}: can't route message; {e}", self.route_id);
+                self.stats.on_error();
+            }
+        }
+    }
+
+    fn write_entity(&self, s: Sample, ros2_name: &str, entity: dds_entity_t) {
+        match &self.instances {
+            Some(instances) if self.instance_keyed => {
+                route_zenoh_instance_to_dds(s, ros2_name, entity, instances, &self.stats)
+            }
+            Some(instances) => {
+                route_zenoh_keyed_message_to_dds(s, ros2_name, entity, instances, &self.stats)
+            }
+            None => route_zenoh_message_to_dds(s, ros2_name, entity, &self.stats),
+        }
+    }
+
+    // Assert the liveliness of the DDS Writer (if not deleted)
+    fn assert_liveliness(&self) {
+        let entity = zlock!(self.entity);
+        if *entity != DDS_ENTITY_NULL {
+            let ret = unsafe { dds_assert_liveliness(*entity) };
+            if ret < 0 {
+                tracing::warn!(
+                    "{}: failed to assert Writer's liveliness: {}",
+                    self.route_id,
+                    unsafe { CStr::from_ptr(dds_strretcode(ret)) }
+                        .to_str()
+                        .unwrap_or("unrecoverable DDS retcode")
+                );
+            }
+        }
+    }
+
+    // Delete the DDS Writer (if not already)
+    fn delete(&self) {
+        self.delete_entity(&mut zlock!(self.entity));
+    }
+
+    fn delete_entity(&self, entity: &mut dds_entity_t) {
+        if *entity != DDS_ENTITY_NULL {
+            // remove writer's GID from ros_discovery_info message
+            match get_guid(&entity) {
+                Ok(gid) => self.context.ros_discovery_mgr.remove_dds_writer(gid),
+                Err(e) => tracing::warn!("{}: {e}", self.route_id),
+            }
+
+            tracing::debug!("{}: delete Writer", self.route_id);
+            if let Err(e) = delete_dds_entity(*entity) {
+                tracing::warn!("{}: error deleting DDS Writer:  {}", self.route_id, e);
+            }
+            *entity = DDS_ENTITY_NULL;
+        }
+    }
+}
+
+impl Drop for RouteDdsWriter {
+    fn drop(&mut self) {
+        self.delete();
+    }
+}
+
+fn serialize_route_dds_writer<S>(writer: &Arc<RouteDdsWriter>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match *zlock!(writer.entity) {
+        DDS_ENTITY_NULL => s.serialize_str(""),
+        entity => serialize_entity_guid(&entity, s),
+    }
+}
+
+// The QoS of the DDS Writer of a route, from the QoS of the remote Writer (or adapted from a local Reader)
+// and the QoS configured for the topic. Also return if TRANSIENT_LOCAL is forced via `force_transient_local`.
+// Query the historical publications of the PublicationCaches on `selector` for a FetchingSubscriber
+async fn fetch_historical_publications(
+    sub: &mut FetchingSubscriber<'_, ()>,
+    session: &Arc<Session>,
+    selector: OwnedKeyExpr,
+    queries_timeout: Duration,
+    route_id: &str,
+) {
+    let query_selector: Selector = selector.into();
+    tracing::debug!(
+        "{route_id}: query historical messages for TRANSIENT_LOCAL Reader on {query_selector}"
+    );
+    if let Err(e) = sub
+        .fetch({
+            let query_selector = query_selector.clone();
+            move |cb| {
+                use zenoh_core::SyncResolve;
+                session
+                    .get(&query_selector)
+                    .target(QueryTarget::All)
+                    .consolidation(ConsolidationMode::None)
+                    .accept_replies(ReplyKeyExpr::Any)
+                    .timeout(queries_timeout)
+                    .callback(cb)
+                    .res_sync()
+            }
+        })
+        .res()
+        .await
+    {
+        tracing::warn!(
+            "{route_id}: query for historical publications on {query_selector} failed: {e}"
+        );
+    }
+}
+
+fn route_writer_qos(
+    config: &Config,
+    ros2_name: &str,
+    zenoh_key_expr: &keyexpr,
+    qos: &Qos,
+) -> (Qos, bool) {
+    // apply the QoS configured for this topic via `topic_qos`, if any (before the Durability is checked)
+    let mut writer_qos = apply_topic_qos(qos, config.get_topic_qos(ros2_name).as_ref());
+    // force TRANSIENT_LOCAL for the topics configured in `force_transient_local`
+    let transient_local_forced =
+        !is_transient_local(&writer_qos) && config.is_transient_local_forced(ros2_name);
+    if transient_local_forced {
+        writer_qos = force_transient_local(&writer_qos);
+    }
+    // size the historical data of a TRANSIENT_LOCAL Writer as the remote one, or else as its history
+    writer_qos = with_durability_service(&writer_qos);
+    // for a topic in `latched_aggregate_topics`, keep the last publications of all the remote Writers
+    // (e.g. "/tf_static" with a KEEP_LAST(1) history per broadcaster) for the late joining Readers
+    if config.is_latched_aggregate(ros2_name) && is_transient_local(&writer_qos) {
+        writer_qos = with_min_history_depth(&writer_qos, LATCHED_AGGREGATE_MIN_DEPTH);
+    }
+
+    // force RELIABLE QoS for Writers (#23)
+    if let Some(cyclors::qos::Reliability {
+        kind: cyclors::qos::ReliabilityKind::BEST_EFFORT,
+        ..
+    }) = &mut writer_qos.reliability
+    {
+        // Per DDS specification, the default Reliability value for DataWriters is RELIABLE with max_blocking_time=100ms
+        // Thus just use default value.
+        writer_qos.reliability = None;
+    }
+    // apply the Reliability configured for this topic, if any
+    let reliability_override = config.get_reliability_override(ros2_name);
+    let writer_qos = apply_reliability_override(&writer_qos, reliability_override);
+    // block or drop when the Writer is congested, if configured for this topic
+    let writer_qos =
+        apply_congestion_control(&writer_qos, config.get_congestion_control(ros2_name));
+    let writer_qos = manual_liveliness_by_topic(&writer_qos);
+    // bound a KEEP_ALL History, if configured
+    let writer_qos = match config.keep_all_max_depth {
+        Some(max_depth) if is_keep_all(&writer_qos) => {
+            tracing::warn!(
+                "Route Subscriber ({zenoh_key_expr} -> {ros2_name}): KEEP_ALL History of the Writer capped to KEEP_LAST({max_depth}) as configured by 'keep_all_max_depth'"
+            );
+            cap_keep_all_history(&writer_qos, Some(max_depth))
+        }
+        _ => writer_qos,
+    };
+    // apply the DDS Partitions configured for this topic, if any
+    let writer_qos = apply_partition(&writer_qos, config.get_dds_partition(ros2_name));
+    (writer_qos, transient_local_forced)
 }
 
-fn route_zenoh_message_to_dds(s: Sample, ros2_name: &str, data_writer: dds_entity_t) {
+// If the zenoh Subscriber is RELIABLE: as configured via `zenoh_reliability` for this topic,
+// or else unless "best_effort" is configured via `reliability` for this topic
+fn zenoh_reliable(config: &Config, ros2_name: &str) -> bool {
+    config.is_zenoh_reliable(
+        ros2_name,
+        config.get_reliability_override(ros2_name) != Some(ReliabilityOverride::BestEffort),
+    )
+}
+
+// The action to be applied after a check of the Deadline
+#[derive(Debug)]
+enum DeadlineAction {
+    Nothing,
+    Log(Duration),
+    Republish(Sample),
+    Teardown,
+}
+
+// The monitoring of the Deadline of a Route Subscriber, applying the configured policy on Deadline miss
+struct DeadlineWatch {
+    period: Duration,
+    policy: DeadlineMissPolicy,
+    state: Mutex<DeadlineState>,
+}
+
+#[derive(Default)]
+struct DeadlineState {
+    // time of the last routed (or re-published) message. None until a 1st message is routed
+    last_activity: Option<Instant>,
+    // the last routed message (kept only for the "republish_last" policy)
+    last_sample: Option<Sample>,
+    // if the Deadline was missed since the last routed message
+    missed: bool,
+}
+
+impl DeadlineWatch {
+    fn new(period: Duration, policy: DeadlineMissPolicy) -> DeadlineWatch {
+        DeadlineWatch {
+            period,
+            policy,
+            state: Mutex::new(DeadlineState::default()),
+        }
+    }
+
+    fn on_sample(&self, s: &Sample, now: Instant) {
+        let mut state = zlock!(self.state);
+        state.last_activity = Some(now);
+        state.missed = false;
+        if self.policy == DeadlineMissPolicy::RepublishLast {
+            state.last_sample = Some(s.clone());
+        }
+    }
+
+    // Check if the Deadline was missed, returning the action to apply and the delay before next check
+    fn check(&self, now: Instant) -> (DeadlineAction, Duration) {
+        let mut state = zlock!(self.state);
+        let elapsed = match state.last_activity {
+            Some(t) => now.saturating_duration_since(t),
+            None => return (DeadlineAction::Nothing, self.period),
+        };
+        if elapsed < self.period {
+            return (DeadlineAction::Nothing, self.period - elapsed);
+        }
+        let first_miss = !state.missed;
+        state.missed = true;
+        let action = match self.policy {
+            DeadlineMissPolicy::Log if first_miss => DeadlineAction::Log(elapsed),
+            DeadlineMissPolicy::RepublishLast => match state.last_sample.clone() {
+                Some(s) => {
+                    // the re-published message satisfies the Deadline for one more period
+                    state.last_activity = Some(now);
+                    DeadlineAction::Republish(s)
+                }
+                None => DeadlineAction::Nothing,
+            },
+            DeadlineMissPolicy::Teardown if first_miss => DeadlineAction::Teardown,
+            _ => DeadlineAction::Nothing,
+        };
+        (action, self.period)
+    }
+}
+
+// Spawn a task periodically checking the Deadline, until the DeadlineWatch or the Writer are dropped
+fn spawn_deadline_timer(
+    watch: &Arc<DeadlineWatch>,
+    dds_writer: &Arc<RouteDdsWriter>,
+    ros2_name: String,
+    route_id: String,
+) {
+    let mut delay = watch.period;
+    let watch = Arc::downgrade(watch);
+    let dds_writer = Arc::downgrade(dds_writer);
+    async_std::task::spawn(async move {
+        loop {
+            async_std::task::sleep(delay).await;
+            let (watch, dds_writer) = match (watch.upgrade(), dds_writer.upgrade()) {
+                (Some(w), Some(d)) => (w, d),
+                _ => break,
+            };
+            let (action, next_delay) = watch.check(Instant::now());
+            delay = next_delay;
+            match action {
+                DeadlineAction::Nothing => (),
+                DeadlineAction::Log(elapsed) => {
+                    tracing::warn!("{route_id}: deadline missed - no message routed since {elapsed:?} (deadline: {:?})", watch.period)
+                }
+                DeadlineAction::Republish(s) => {
+                    tracing::debug!("{route_id}: deadline missed - re-publish last message");
+                    dds_writer.write(s, &ros2_name);
+                }
+                DeadlineAction::Teardown => {
+                    tracing::info!(
+                        "{route_id}: deadline missed - delete DDS Writer until next message"
+                    );
+                    dds_writer.delete();
+                }
+            }
+        }
+    });
+}
+
+// Spawn a task asserting the liveliness of a DDS Writer with a manual Liveliness 3 times per lease duration,
+// while the origin of the route is alive, until the Writer is dropped (or its Liveliness is no longer manual)
+fn spawn_liveliness_asserter(dds_writer: &Arc<RouteDdsWriter>, origin_alive: &Arc<AtomicBool>) {
+    let dds_writer = Arc::downgrade(dds_writer);
+    let origin_alive = origin_alive.clone();
+    async_std::task::spawn(async move {
+        loop {
+            let period = {
+                let Some(dds_writer) = dds_writer.upgrade() else {
+                    break;
+                };
+                let Some(lease) = get_manual_liveliness_lease(&dds_writer.qos()) else {
+                    break;
+                };
+                if origin_alive.load(Ordering::Relaxed) {
+                    dds_writer.assert_liveliness();
+                }
+                lease / 3
+            };
+            async_std::task::sleep(period).await;
+        }
+    });
+}
+
+// The key hash of the instance of a message: attached to it, or in its key expression in "instance_key_exprs" mode
+// (all zeros for a keyless topic or if unknown)
+fn sample_instance(s: &Sample) -> [u8; 16] {
+    s.attachment()
+        .and_then(instance_keyhash_from_attachment)
+        .or_else(|| {
+            let mut keyhash = [0u8; 16];
+            instance_chunk(&s.key_expr)
+                .and_then(|chunk| hex::decode_to_slice(chunk, &mut keyhash).ok())
+                .map(|()| keyhash)
+        })
+        .unwrap_or([0u8; 16])
+}
+
+// In "instance_key_exprs" mode: route a message to DDS, keeping it as the last one of its instance,
+// or apply the dispose/unregister of an instance routed as a delete on its key expression
+fn route_zenoh_instance_to_dds(
+    s: Sample,
+    ros2_name: &str,
+    data_writer: dds_entity_t,
+    instances: &Mutex<HashMap<String, Vec<u8>>>,
+    stats: &RouteStats,
+) {
+    let Some(instance) = instance_chunk(&s.key_expr).map(String::from) else {
+        tracing::warn!(
+            "Route Subscriber (Zenoh:{} -> ROS:{ros2_name}): can't route message; no instance key hash in key expression",
+            s.key_expr
+        );
+        stats.on_error();
+        return;
+    };
+    match s.kind {
+        SampleKind::Put => {
+            zlock!(instances).insert(instance, s.value.payload.contiguous().into_owned());
+            route_zenoh_message_to_dds(s, ros2_name, data_writer, stats)
+        }
+        SampleKind::Delete => route_zenoh_instance_state_to_dds(
+            &s,
+            ros2_name,
+            data_writer,
+            instances,
+            &instance,
+            stats,
+        ),
+    }
+}
+
+// For a keyed topic out of "instance_key_exprs" mode: route a message to DDS, keeping it as the last one
+// of its instance (identified by the attached key hash), or apply the dispose/unregister of an instance
+// routed as a delete with its key hash attached
+fn route_zenoh_keyed_message_to_dds(
+    s: Sample,
+    ros2_name: &str,
+    data_writer: dds_entity_t,
+    instances: &Mutex<HashMap<String, Vec<u8>>>,
+    stats: &RouteStats,
+) {
+    let instance = s
+        .attachment()
+        .and_then(instance_keyhash_from_attachment)
+        .map(hex::encode);
+    match (s.kind, instance) {
+        (SampleKind::Put, Some(instance)) => {
+            zlock!(instances).insert(instance, s.value.payload.contiguous().into_owned());
+            route_zenoh_message_to_dds(s, ros2_name, data_writer, stats)
+        }
+        // a message without key hash (e.g. from an older bridge or from a cache): its instance is unknown
+        (SampleKind::Put, None) => route_zenoh_message_to_dds(s, ros2_name, data_writer, stats),
+        (SampleKind::Delete, Some(instance)) => route_zenoh_instance_state_to_dds(
+            &s,
+            ros2_name,
+            data_writer,
+            instances,
+            &instance,
+            stats,
+        ),
+        (SampleKind::Delete, None) => {
+            tracing::debug!(
+                "Route Subscriber (Zenoh:{} -> ROS:{ros2_name}): ignore delete without instance key hash",
+                s.key_expr
+            );
+            stats.on_dropped();
+        }
+    }
+}
+
+// Write the last message of an instance with the dispose/unregister flag, as its key is required
+fn route_zenoh_instance_state_to_dds(
+    s: &Sample,
+    ros2_name: &str,
+    data_writer: dds_entity_t,
+    instances: &Mutex<HashMap<String, Vec<u8>>>,
+    instance: &str,
+    stats: &RouteStats,
+) {
+    let state = s
+        .attachment()
+        .and_then(instance_state_from_attachment)
+        .unwrap_or(DDSInstanceState::Disposed);
+    match zlock!(instances).remove(instance) {
+        Some(data) => {
+            tracing::debug!(
+                "Route Subscriber (Zenoh:{} -> ROS:{ros2_name}): routing {state:?} instance",
+                s.key_expr
+            );
+            match dds_write_with_instance_state(data_writer, data, state) {
+                Ok(()) => stats.on_routed(0),
+                Err(e) => {
+                    tracing::warn!(
+                        "Route Subscriber (Zenoh:{} -> ROS:{ros2_name}): {e}",
+                        s.key_expr
+                    );
+                    stats.on_error();
+                    stats.on_event(RouteEventKind::DdsError, e);
+                }
+            }
+        }
+        None => {
+            tracing::debug!(
+                "Route Subscriber (Zenoh:{} -> ROS:{ros2_name}): ignore {state:?} of an unknown instance",
+                s.key_expr
+            );
+            stats.on_dropped();
+        }
+    }
+}
+
+fn route_zenoh_message_to_dds(
+    s: Sample,
+    ros2_name: &str,
+    data_writer: dds_entity_t,
+    stats: &RouteStats,
+) {
     if *LOG_PAYLOAD {
         tracing::debug!(
             "Route Subscriber (Zenoh:{} -> ROS:{}): routing message - payload: {:02x?}",
@@ -352,15 +1749,15 @@ fn route_zenoh_message_to_dds(s: Sample, ros2_name: &str, data_writer: dds_entit
         );
     }
 
+    // the DDS source timestamp of the message, if attached by the remote Route Publisher (see `source_info`)
+    let source_timestamp = s
+        .attachment()
+        .and_then(origin_from_attachment)
+        .map(|o| o.source_timestamp);
+
     unsafe {
         let bs = s.value.payload.contiguous().into_owned();
-        // As per the Vec documentation (see https://doc.rust-lang.org/std/vec/struct.Vec.html#method.into_raw_parts)
-        // the only way to correctly releasing it is to create a vec using from_raw_parts
-        // and then have its destructor do the cleanup.
-        // Thus, while tempting to just pass the raw pointer to cyclone and then free it from C,
-        // that is not necessarily safe or guaranteed to be leak free.
-        // TODO replace when stable https://github.com/rust-lang/rust/issues/65816
-        let (ptr, len, capacity) = vec_into_raw_parts(bs);
+        let len = bs.len();
         let size: ddsrt_iov_len_t = match ddsrt_iov_len_from_usize(len) {
             Ok(s) => s,
             Err(_) => {
@@ -370,15 +1767,11 @@ fn route_zenoh_message_to_dds(s: Sample, ros2_name: &str, data_writer: dds_entit
                     ros2_name,
                     len
                 );
+                stats.on_error();
                 return;
             }
         };
 
-        let data_out = ddsrt_iovec_t {
-            iov_base: ptr as *mut std::ffi::c_void,
-            iov_len: size,
-        };
-
         let mut sertype_ptr: *const ddsi_sertype = std::ptr::null_mut();
         let ret = dds_get_entity_sertype(data_writer, &mut sertype_ptr);
         if ret < 0 {
@@ -390,9 +1783,28 @@ fn route_zenoh_message_to_dds(s: Sample, ros2_name: &str, data_writer: dds_entit
                     .to_str()
                     .unwrap_or("unrecoverable DDS retcode")
             );
+            stats.on_error();
+            stats.on_event(
+                RouteEventKind::DdsError,
+                format!(
+                    "sertype lookup failed: {}",
+                    CStr::from_ptr(dds_strretcode(ret)).to_string_lossy()
+                ),
+            );
             return;
         }
 
+        // As per the Vec documentation (see https://doc.rust-lang.org/std/vec/struct.Vec.html#method.into_raw_parts)
+        // the only way to correctly releasing it is to create a vec using from_raw_parts
+        // and then have its destructor do the cleanup.
+        // Thus, while tempting to just pass the raw pointer to cyclone and then free it from C,
+        // that is not necessarily safe or guaranteed to be leak free.
+        // TODO replace when stable https://github.com/rust-lang/rust/issues/65816
+        let (ptr, len, capacity) = vec_into_raw_parts(bs);
+        let data_out = ddsrt_iovec_t {
+            iov_base: ptr as *mut std::ffi::c_void,
+            iov_len: size,
+        };
         let fwdp = ddsi_serdata_from_ser_iov(
             sertype_ptr,
             ddsi_serdata_kind_SDK_DATA,
@@ -400,8 +1812,44 @@ fn route_zenoh_message_to_dds(s: Sample, ros2_name: &str, data_writer: dds_entit
             &data_out,
             size as usize,
         );
+        // the serdata holds a copy of the payload: release it at once, whatever the outcome of the write
+        drop(Vec::from_raw_parts(ptr, len, capacity));
+        if fwdp.is_null() {
+            tracing::warn!(
+                "Route Subscriber (Zenoh:{} -> ROS:{}): can't route message; failed to deserialize it ({len} bytes)",
+                s.key_expr,
+                ros2_name
+            );
+            stats.on_error();
+            stats.on_event(
+                RouteEventKind::DdsError,
+                "failed to deserialize message".into(),
+            );
+            return;
+        }
 
-        let ret = dds_writecdr(data_writer, fwdp);
+        let ret = match source_timestamp {
+            // dds_writecdr() sets the timestamp to now, while dds_forwardcdr() keeps it
+            Some(timestamp) => {
+                (*fwdp).timestamp.v = timestamp;
+                dds_forwardcdr(data_writer, fwdp)
+            }
+            None => dds_writecdr(data_writer, fwdp),
+        };
+        if ret == DDS_RETCODE_TIMEOUT {
+            // the Writer is congested and its max_blocking_time elapsed (see `congestion_control`)
+            tracing::trace!(
+                "Route Subscriber (Zenoh:{} -> ROS:{}): DDS Writer congested, message dropped",
+                s.key_expr,
+                ros2_name
+            );
+            stats.on_dropped();
+            stats.on_event(
+                RouteEventKind::Dropped,
+                "DDS Writer congested (max_blocking_time elapsed)".into(),
+            );
+            return;
+        }
         if ret < 0 {
             tracing::warn!(
                 "Route Subscriber (Zenoh:{} -> ROS:{}): DDS write({data_writer}) failed: {}",
@@ -411,9 +1859,243 @@ fn route_zenoh_message_to_dds(s: Sample, ros2_name: &str, data_writer: dds_entit
                     .to_str()
                     .unwrap_or("unrecoverable DDS retcode")
             );
+            stats.on_error();
+            stats.on_event(
+                RouteEventKind::DdsError,
+                format!(
+                    "DDS write failed: {}",
+                    CStr::from_ptr(dds_strretcode(ret)).to_string_lossy()
+                ),
+            );
             return;
         }
 
-        drop(Vec::from_raw_parts(ptr, len, capacity));
+        stats.on_routed(len);
+    }
+}
+
+// Check if a message routed from Zenoh is older than the Lifespan of the DDS Writer, based on its timestamp
+// set by the source bridge (assuming the hosts' clocks are synchronized).
+// A message without timestamp, or with a timestamp in the future, is not expired.
+fn is_expired(s: &Sample, lifespan: Duration, now: SystemTime) -> bool {
+    match &s.timestamp {
+        Some(ts) => now
+            .duration_since(ts.get_time().to_system_time())
+            .map_or(false, |age| age > lifespan),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_expired, route_writer_qos, zenoh_reliable, DeadlineAction, DeadlineWatch};
+    use crate::config::{Config, DeadlineMissPolicy};
+    use crate::liveliness_mgt::qos_to_key_expr;
+    use crate::qos::{Durability, DurabilityKind, Qos};
+    use crate::qos_helpers::is_transient_local;
+    use std::time::{Duration, Instant};
+    use zenoh::prelude::*;
+    use zenoh::time::new_reception_timestamp;
+
+    const DEADLINE: Duration = Duration::from_millis(100);
+
+    fn sample(payload: &str) -> Sample {
+        Sample::new(crate::ke_for_sure!("test"), payload.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_zenoh_reliable() {
+        // default: reliable, unless "best_effort" is configured for the DDS Writer
+        let config =
+            serde_json::from_str::<Config>(r#"{"reliability": {"/scan": "best_effort"}}"#).unwrap();
+        assert!(zenoh_reliable(&config, "/cmd_vel"));
+        assert!(!zenoh_reliable(&config, "/scan"));
+
+        // configured via `zenoh_reliability`, independently of the DDS QoS
+        let config = serde_json::from_str::<Config>(
+            r#"{"reliability": {"/scan": "best_effort"}, "zenoh_reliability": {"/scan": "reliable", "/camera/.*": "best_effort"}}"#,
+        )
+        .unwrap();
+        assert!(zenoh_reliable(&config, "/scan"));
+        assert!(!zenoh_reliable(&config, "/camera/image"));
+        assert!(zenoh_reliable(&config, "/cmd_vel"));
+    }
+
+    #[test]
+    fn test_deadline_not_armed_before_1st_message() {
+        let watch = DeadlineWatch::new(DEADLINE, DeadlineMissPolicy::Teardown);
+        let (action, delay) = watch.check(Instant::now() + 10 * DEADLINE);
+        assert!(matches!(action, DeadlineAction::Nothing));
+        assert_eq!(delay, DEADLINE);
+    }
+
+    #[test]
+    fn test_deadline_log() {
+        let watch = DeadlineWatch::new(DEADLINE, DeadlineMissPolicy::Log);
+        let t0 = Instant::now();
+        watch.on_sample(&sample("1"), t0);
+        // within deadline: next check at the deadline
+        let (action, delay) = watch.check(t0 + Duration::from_millis(40));
+        assert!(matches!(action, DeadlineAction::Nothing));
+        assert_eq!(delay, Duration::from_millis(60));
+        // source paused: logged once
+        let (action, _) = watch.check(t0 + Duration::from_millis(150));
+        assert!(matches!(action, DeadlineAction::Log(d) if d == Duration::from_millis(150)));
+        let (action, _) = watch.check(t0 + Duration::from_millis(300));
+        assert!(matches!(action, DeadlineAction::Nothing));
+        // source resumed, then paused again: logged again
+        watch.on_sample(&sample("2"), t0 + Duration::from_millis(400));
+        let (action, _) = watch.check(t0 + Duration::from_millis(550));
+        assert!(matches!(action, DeadlineAction::Log(_)));
+    }
+
+    #[test]
+    fn test_deadline_republish_last() {
+        let watch = DeadlineWatch::new(DEADLINE, DeadlineMissPolicy::RepublishLast);
+        let t0 = Instant::now();
+        watch.on_sample(&sample("1"), t0);
+        watch.on_sample(&sample("2"), t0 + Duration::from_millis(50));
+        // source paused: the last message is re-published on each deadline period
+        for i in 1..4 {
+            let (action, delay) = watch.check(t0 + Duration::from_millis(50) + i * DEADLINE);
+            match action {
+                DeadlineAction::Republish(s) => {
+                    assert_eq!(s.value.payload.contiguous().as_ref(), b"2")
+                }
+                a => panic!("unexpected action: {a:?}"),
+            }
+            assert_eq!(delay, DEADLINE);
+        }
+    }
+
+    #[test]
+    fn test_deadline_teardown() {
+        let watch = DeadlineWatch::new(DEADLINE, DeadlineMissPolicy::Teardown);
+        let t0 = Instant::now();
+        watch.on_sample(&sample("1"), t0);
+        // source paused: torn down once
+        let (action, _) = watch.check(t0 + DEADLINE);
+        assert!(matches!(action, DeadlineAction::Teardown));
+        let (action, _) = watch.check(t0 + 2 * DEADLINE);
+        assert!(matches!(action, DeadlineAction::Nothing));
+        // source resumed (the Writer is re-created), then paused again
+        watch.on_sample(&sample("2"), t0 + 3 * DEADLINE);
+        let (action, _) = watch.check(t0 + 4 * DEADLINE);
+        assert!(matches!(action, DeadlineAction::Teardown));
+    }
+
+    #[test]
+    fn test_lifespan_expiry() {
+        const LIFESPAN: Duration = Duration::from_millis(200);
+        // without timestamp, a message never expires
+        let s = sample("1");
+        assert!(!is_expired(
+            &s,
+            LIFESPAN,
+            std::time::SystemTime::now() + 10 * LIFESPAN
+        ));
+
+        let ts = new_reception_timestamp();
+        let t0 = ts.get_time().to_system_time();
+        let s = sample("1").with_timestamp(ts);
+        assert!(!is_expired(&s, LIFESPAN, t0));
+        assert!(!is_expired(&s, LIFESPAN, t0 + Duration::from_millis(150)));
+        assert!(is_expired(&s, LIFESPAN, t0 + Duration::from_millis(250)));
+        // a timestamp in the future (clocks not synchronized) doesn't expire
+        assert!(!is_expired(&s, LIFESPAN, t0 - Duration::from_millis(250)));
+    }
+
+    #[test]
+    fn test_route_writer_qos_follows_remote_writer() {
+        let config =
+            serde_json::from_str::<Config>(r#"{"force_transient_local": ["/map"]}"#).unwrap();
+        let ke = crate::ke_for_sure!("chatter");
+        let volatile = Qos::default();
+        let transient_local = Qos {
+            durability: Some(Durability {
+                kind: DurabilityKind::TRANSIENT_LOCAL,
+            }),
+            ..Default::default()
+        };
+        // the remote Writer restarted with another Durability: the QoS changed
+        assert_ne!(
+            qos_to_key_expr(true, &volatile),
+            qos_to_key_expr(true, &transient_local)
+        );
+        let (qos, forced) = route_writer_qos(&config, "/chatter", ke, &volatile);
+        assert!(!is_transient_local(&qos) && !forced);
+        let (qos, forced) = route_writer_qos(&config, "/chatter", ke, &transient_local);
+        assert!(is_transient_local(&qos) && !forced);
+
+        // TRANSIENT_LOCAL forced by configuration, whatever the remote Writer
+        let (qos, forced) = route_writer_qos(&config, "/map", ke, &volatile);
+        assert!(is_transient_local(&qos) && forced);
+        let (qos, forced) = route_writer_qos(&config, "/map", ke, &transient_local);
+        assert!(is_transient_local(&qos) && !forced);
+    }
+
+    #[test]
+    fn test_route_writer_qos_congestion_control() {
+        use crate::qos::{Reliability, ReliabilityKind, DDS_INFINITE_TIME};
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"topic_qos": {"/camera/.*": {"congestion_control": "drop"}, "/cmd_vel": {"congestion_control": "block"}}}"#,
+        )
+        .unwrap();
+        let ke = crate::ke_for_sure!("camera/image");
+        // the remote Writer is BEST_EFFORT, but the route's Writer is RELIABLE
+        let best_effort = Qos {
+            reliability: Some(Reliability {
+                kind: ReliabilityKind::BEST_EFFORT,
+                max_blocking_time: 0,
+            }),
+            ..Default::default()
+        };
+        let (qos, _) = route_writer_qos(&config, "/camera/image", ke, &best_effort);
+        assert_eq!(
+            qos.reliability,
+            Some(Reliability {
+                kind: ReliabilityKind::RELIABLE,
+                max_blocking_time: 0
+            })
+        );
+        let (qos, _) = route_writer_qos(&config, "/cmd_vel", ke, &best_effort);
+        assert_eq!(
+            qos.reliability.map(|r| r.max_blocking_time),
+            Some(DDS_INFINITE_TIME)
+        );
+        // not configured: the Writer's default Reliability
+        let (qos, _) = route_writer_qos(&config, "/tf", ke, &best_effort);
+        assert_eq!(qos.reliability, None);
+    }
+
+    #[test]
+    fn test_route_writer_qos_latched_aggregate() {
+        use crate::qos_helpers::{get_history_or_default, transient_local_history};
+
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        let ke = crate::ke_for_sure!("tf_static");
+        // a static transform broadcaster: TRANSIENT_LOCAL with KEEP_LAST(1)
+        let transient_local = Qos {
+            durability: Some(Durability {
+                kind: DurabilityKind::TRANSIENT_LOCAL,
+            }),
+            ..Default::default()
+        };
+        let (qos, _) = route_writer_qos(&config, "/tf_static", ke, &transient_local);
+        assert_eq!(
+            get_history_or_default(&qos).depth,
+            LATCHED_AGGREGATE_MIN_DEPTH
+        );
+        assert_eq!(
+            transient_local_history(&qos).depth,
+            LATCHED_AGGREGATE_MIN_DEPTH
+        );
+        // not a latched aggregate topic
+        let (qos, _) = route_writer_qos(&config, "/map", ke, &transient_local);
+        assert_eq!(get_history_or_default(&qos).depth, 1);
+        // VOLATILE: no historical data
+        let (qos, _) = route_writer_qos(&config, "/tf_static", ke, &Qos::default());
+        assert_eq!(get_history_or_default(&qos).depth, 1);
     }
 }
@@ -11,29 +11,67 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use crate::config::Config;
+use crate::action_feedback::FeedbackThrottles;
+use crate::config::{Config, RoutingMode};
+use crate::dds_discovery::DdsEntity;
 use crate::discovered_entities::DiscoveredEntities;
 use crate::events::ROS2AnnouncementEvent;
 use crate::events::ROS2DiscoveryEvent;
+use crate::forward_discovery::{ForwardDiscovery, RouteKind};
+use crate::liveliness_mgt::{
+    new_ke_liveliness_action_cli, new_ke_liveliness_action_srv, new_ke_liveliness_pub,
+    new_ke_liveliness_service_cli, new_ke_liveliness_service_srv, new_ke_liveliness_sub,
+    qos_to_key_expr,
+};
+use crate::node_info::MsgPub;
 use crate::qos_helpers::adapt_reader_qos_for_writer;
 use crate::qos_helpers::adapt_writer_qos_for_reader;
+use crate::qos_helpers::filter_deadline;
+use crate::qos_helpers::force_transient_local;
+use crate::qos_helpers::get_exclusive_ownership_strength;
+use crate::qos_helpers::get_time_based_filter_separation;
+use crate::qos_helpers::is_transient_local;
+use crate::qos_helpers::merge_writers_qos;
+use crate::qos_helpers::qos_incompatibilities;
+use crate::qos_helpers::qos_to_json;
+use crate::ros2_names_cache::Ros2NamesCache;
+use crate::ros2_utils::dds_topic_name;
+use crate::ros2_utils::is_message_for_action;
+use crate::ros2_utils::is_service_for_action;
 use crate::ros2_utils::key_expr_to_ros2_name;
+use crate::ros2_utils::parameter_service_node;
 use crate::ros2_utils::ros2_name_to_key_expr;
+use crate::ros2_utils::{
+    key_expr_to_raw_dds_topic, raw_dds_topic_to_key_expr, raw_dds_topic_to_ros2_name,
+    ros2_name_to_raw_dds_topic,
+};
+use crate::ros2_utils::{scope_key_expr, unscope_key_expr};
+use crate::ros2_utils::{service_event_topic, service_of_event_topic, KE_SUFFIX_SERVICE_EVENT};
 use crate::ros_discovery::RosDiscoveryInfoMgr;
 use crate::route_action_cli::RouteActionCli;
 use crate::route_action_srv::RouteActionSrv;
+use crate::route_events::RouteEvent;
+use crate::route_idle::{IdleCandidate, RouteIdle};
+use crate::route_linger::{Rediscovery, RouteLinger};
 use crate::route_publisher::RoutePublisher;
 use crate::route_service_cli::RouteServiceCli;
 use crate::route_service_srv::RouteServiceSrv;
+use crate::route_stats::RouteStatsSnapshot;
 use crate::route_subscriber::RouteSubscriber;
+use crate::service_events::ServiceEvents;
+use crate::startup_grace::PendingDiscoveries;
 use cyclors::dds_entity_t;
 use cyclors::qos::IgnoreLocal;
 use cyclors::qos::Qos;
+use flume::{Receiver, Sender};
+use futures::{stream, Future, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::sync::RwLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use zenoh::liveliness::LivelinessToken;
 use zenoh::prelude::keyexpr;
 use zenoh::prelude::r#async::AsyncResolve;
 use zenoh::prelude::OwnedKeyExpr;
@@ -42,7 +80,16 @@ use zenoh::sample::Sample;
 use zenoh::Session;
 use zenoh_core::zread;
 
+use crate::bandwidth_cap::BandwidthCap;
 use crate::ke_for_sure;
+use crate::worker_pool::WorkerPool;
+
+// Maximum number of ROS2 names cached for the key expressions announced by the remote bridges
+const ROS2_NAMES_CACHE_CAPACITY: usize = 1024;
+// Number of routes reported in the "busiest_routes" of the admin space "stats"
+const BUSIEST_ROUTES_COUNT: usize = 10;
+// Rate (messages per second) under which a route is considered idle, and not reported as busy
+const BUSIEST_ROUTES_MIN_RATE: f64 = 0.01;
 
 lazy_static::lazy_static!(
     static ref KE_PREFIX_ROUTE_PUBLISHER: &'static keyexpr = ke_for_sure!("route/topic/pub");
@@ -51,6 +98,14 @@ lazy_static::lazy_static!(
     static ref KE_PREFIX_ROUTE_SERVICE_CLI: &'static keyexpr = ke_for_sure!("route/service/cli");
     static ref KE_PREFIX_ROUTE_ACTION_SRV: &'static keyexpr = ke_for_sure!("route/action/srv");
     static ref KE_PREFIX_ROUTE_ACTION_CLI: &'static keyexpr = ke_for_sure!("route/action/cli");
+    static ref KE_PREFIX_ROUTE_PARAMETERS: &'static keyexpr = ke_for_sure!("route/parameters");
+    static ref KE_SUFFIX_SRV: &'static keyexpr = ke_for_sure!("srv");
+    static ref KE_SUFFIX_CLI: &'static keyexpr = ke_for_sure!("cli");
+    static ref KE_ROUTE_TYPE_MISMATCHES: &'static keyexpr = ke_for_sure!("route/type_mismatches");
+    static ref KE_ROUTE_LONG_NAMES: &'static keyexpr = ke_for_sure!("route/long_names");
+    static ref KE_ROUTE_KEY_EXPR_COLLISIONS: &'static keyexpr = ke_for_sure!("route/key_expr_collisions");
+    static ref KE_ROUTE_CONFLICTS: &'static keyexpr = ke_for_sure!("route/conflicts");
+    static ref KE_STATS: &'static keyexpr = ke_for_sure!("stats");
 );
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -69,6 +124,36 @@ enum RouteRef {
     ServiceCli(String),
     ActionSrv(String),
     ActionCli(String),
+    TypeMismatches,
+    LongNames,
+    KeyExprCollisions,
+    Conflicts,
+    Stats,
+}
+
+// The kind of conflict between a topic announced by a remote bridge and the same topic discovered locally
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ConflictKind {
+    Type,
+    Qos,
+}
+
+// The record of a conflict between a topic announced by a remote bridge and the same topic discovered
+// locally, that are not connected. It's logged and reported in admin space under "route/conflicts".
+#[derive(Clone, Debug, Serialize)]
+struct TopicConflict {
+    topic: String,
+    kind: ConflictKind,
+    reasons: Vec<String>,
+    // the announced remote interface: "Publisher" or "Subscriber"
+    remote_interface: &'static str,
+    local_bridge: String,
+    local_type: String,
+    local_qos: serde_json::Value,
+    remote_bridge: String,
+    remote_type: String,
+    remote_qos: serde_json::Value,
 }
 
 // A Context struct to be shared as an Arc amongst all the code
@@ -82,6 +167,14 @@ pub struct Context {
     pub(crate) discovered_entities: Arc<RwLock<DiscoveredEntities>>,
     // ros_discovery_info read/write manager
     pub(crate) ros_discovery_mgr: Arc<RosDiscoveryInfoMgr>,
+    // to report the routes failing to re-create their DDS entities (see `dds_retry`), to be torn down
+    pub(crate) route_failures: Sender<(RouteKind, String)>,
+    // the budget shared by the Publisher routes (if `max_bandwidth` is configured)
+    pub(crate) bandwidth_cap: Option<Arc<BandwidthCap>>,
+    // the workers routing the replies of the heavy Services (if `heavy_services` is configured)
+    pub(crate) heavy_services_pool: Option<Arc<WorkerPool>>,
+    // the throttles of the Actions' feedback routes, flushed on the terminal status of their goals
+    pub(crate) feedback_throttles: Arc<FeedbackThrottles>,
 }
 
 pub struct RoutesMgr<'a> {
@@ -97,6 +190,35 @@ pub struct RoutesMgr<'a> {
     admin_prefix: OwnedKeyExpr,
     // admin space: index is the admin_keyexpr (relative to admin_prefix)
     admin_space: HashMap<OwnedKeyExpr, RouteRef>,
+    // in "forward_discovery" mode: the local interfaces and remote announcements, routed only when matching
+    forward_discovery: ForwardDiscovery<ROS2DiscoveryEvent, ROS2AnnouncementEvent>,
+    // in "forward_discovery" mode: the LivelinessTokens announcing the local interfaces (routed or not)
+    forward_tokens: HashMap<(RouteKind, String), LivelinessToken<'a>>,
+    // the ROS2 names of the key expressions announced by the remote bridges
+    ros2_names: Ros2NamesCache<(RouteKind, OwnedKeyExpr), Config>,
+    // count of the topics discovered or announced with a type mismatch (indexed by topic name)
+    type_mismatches: HashMap<String, u64>,
+    // the interfaces which DDS topic name exceeds the DDS maximum length (indexed by interface name),
+    // with the hashed DDS topic name or the rejection error, depending on "long_name_policy"
+    long_names: HashMap<String, String>,
+    // the interfaces not routed because their key expression is already used by the route of another
+    // interface (indexed by interface name), with the collision error
+    key_expr_collisions: HashMap<String, String>,
+    // the conflicts between the topics announced by remote bridges and the ones discovered locally
+    // (indexed by topic name), at most 1 per remote bridge and kind of route
+    conflicts: HashMap<String, Vec<TopicConflict>>,
+    // during the "startup_grace_period": the discovery events for which the routes creation is deferred
+    startup_pending: Option<PendingDiscoveries<ROS2DiscoveryEvent>>,
+    // with "route_linger": the QoS of the local Publishers and Subscribers (as announced), and the lingering ones
+    route_linger: Option<RouteLinger<OwnedKeyExpr>>,
+    // with "route_idle_timeout": the routes serving no local Node, and the remote routes announcements
+    route_idle: Option<RouteIdle<ROS2AnnouncementEvent>>,
+    // the Publisher and Subscriber routes paused via the admin space, kept paused when re-created
+    paused: HashSet<(RouteKind, String)>,
+    // the discovered Publishers of the Services introspection events, routed with their Service
+    service_events: ServiceEvents<MsgPub>,
+    // the routes reported as failing to re-create their DDS entities
+    route_failures: Receiver<(RouteKind, String)>,
 }
 
 impl<'a> RoutesMgr<'a> {
@@ -109,6 +231,21 @@ impl<'a> RoutesMgr<'a> {
         ros_discovery_mgr: Arc<RosDiscoveryInfoMgr>,
         admin_prefix: OwnedKeyExpr,
     ) -> RoutesMgr<'a> {
+        let (route_failures_tx, route_failures) = flume::unbounded();
+        let bandwidth_cap = config.get_max_bandwidth().map(|max_bandwidth| {
+            Arc::new(BandwidthCap::new(
+                max_bandwidth,
+                config.get_bandwidth_shedding_priority(),
+                Instant::now(),
+            ))
+        });
+        let heavy_services_pool = config.heavy_services.as_ref().map(|heavy| {
+            Arc::new(WorkerPool::new(
+                "ros2dds-heavy-srv",
+                heavy.get_workers(),
+                heavy.get_queue_size(),
+            ))
+        });
         let context = Context {
             plugin_id: Arc::new(plugin_id),
             config,
@@ -116,8 +253,46 @@ impl<'a> RoutesMgr<'a> {
             participant,
             discovered_entities,
             ros_discovery_mgr,
+            route_failures: route_failures_tx,
+            bandwidth_cap,
+            heavy_services_pool,
+            feedback_throttles: Arc::new(FeedbackThrottles::default()),
         };
 
+        let mut admin_space = HashMap::new();
+        admin_space.insert(
+            KE_ROUTE_TYPE_MISMATCHES.to_owned(),
+            RouteRef::TypeMismatches,
+        );
+        admin_space.insert(KE_ROUTE_LONG_NAMES.to_owned(), RouteRef::LongNames);
+        admin_space.insert(
+            KE_ROUTE_KEY_EXPR_COLLISIONS.to_owned(),
+            RouteRef::KeyExprCollisions,
+        );
+        admin_space.insert(KE_ROUTE_CONFLICTS.to_owned(), RouteRef::Conflicts);
+        admin_space.insert(KE_STATS.to_owned(), RouteRef::Stats);
+
+        // during the startup grace period, the routes creation is deferred
+        let startup_pending = context
+            .config
+            .get_startup_grace_period()
+            .map(|_| PendingDiscoveries::default());
+        let route_linger = context.config.get_route_linger().map(RouteLinger::new);
+        // the idle routes are torn down only in "local_routing" mode ("forward_discovery" does it by design)
+        let route_idle = [
+            RouteKind::Publisher,
+            RouteKind::Subscriber,
+            RouteKind::ServiceSrv,
+            RouteKind::ServiceCli,
+            RouteKind::ActionSrv,
+            RouteKind::ActionCli,
+        ]
+        .into_iter()
+        .filter_map(|kind| context.config.get_route_idle_timeout(kind))
+        .min()
+        .filter(|_| context.config.mode == RoutingMode::LocalRouting)
+        .map(|min_timeout| RouteIdle::new(min_timeout, Instant::now()));
+
         RoutesMgr {
             context,
             routes_publishers: HashMap::new(),
@@ -127,18 +302,450 @@ impl<'a> RoutesMgr<'a> {
             routes_action_srv: HashMap::new(),
             routes_action_cli: HashMap::new(),
             admin_prefix,
-            admin_space: HashMap::new(),
+            admin_space,
+            forward_discovery: ForwardDiscovery::default(),
+            forward_tokens: HashMap::new(),
+            ros2_names: Ros2NamesCache::new(ROS2_NAMES_CACHE_CAPACITY),
+            type_mismatches: HashMap::new(),
+            long_names: HashMap::new(),
+            key_expr_collisions: HashMap::new(),
+            conflicts: HashMap::new(),
+            startup_pending,
+            route_linger,
+            route_idle,
+            paused: HashSet::new(),
+            service_events: ServiceEvents::default(),
+            route_failures,
         }
     }
 
+    /// The reports of the routes failing to re-create their DDS entities, to be passed to on_route_failed()
+    pub fn route_failures(&self) -> Receiver<(RouteKind, String)> {
+        self.route_failures.clone()
+    }
+
+    /// Tear down a route which failed to re-create its DDS entities after "dds_retry.max_attempts".
+    /// It will be re-created on the next discovery or announcement requiring it.
+    pub fn on_route_failed(&mut self, kind: RouteKind, ros2_name: &str) {
+        tracing::error!(
+            "{kind:?} route for {ros2_name} failed to re-create its DDS entities: tear it down"
+        );
+        self.remove_route(kind, ros2_name);
+    }
+
     pub async fn on_ros_discovery_event(
         &mut self,
         event: ROS2DiscoveryEvent,
     ) -> Result<(), String> {
+        if let Some(pending) = self
+            .startup_pending
+            .as_mut()
+            .filter(|_| !is_parameter_service_event(&self.context.config, &event))
+        {
+            let (kind, ros2_name, node, discovered) = discovery_event_key(&event);
+            let (ros2_name, node) = (ros2_name.to_string(), node.to_string());
+            pending.push(kind, &ros2_name, &node, discovered, event);
+            return Ok(());
+        }
+        match self.context.config.mode {
+            RoutingMode::LocalRouting if self.route_idle.is_some() => {
+                let (kind, ros2_name, _, discovered) = discovery_event_key(&event);
+                let ros2_name = ros2_name.to_string();
+                self.route_discovery_event(event).await?;
+                if discovered {
+                    self.replay_torn_down_route(kind, &ros2_name).await?;
+                }
+                Ok(())
+            }
+            RoutingMode::LocalRouting => self.route_discovery_event(event).await,
+            RoutingMode::ForwardDiscovery => self.forward_discovery_event(event).await,
+        }
+    }
+
+    // At the end of the "startup_grace_period", create the routes for the consolidated discovered interfaces
+    pub async fn end_startup_grace_period(&mut self) {
+        if let Some(pending) = self.startup_pending.take() {
+            let events = pending.into_events();
+            tracing::info!(
+                "End of startup grace period: creating routes for {} discovered interfaces",
+                events.len()
+            );
+            self.on_ros_discovery_events(events).await;
+        }
+    }
+
+    /// Process a batch of discovery events (coalesced during a "discovery_batch_window")
+    pub async fn on_ros_discovery_events(&mut self, events: Vec<ROS2DiscoveryEvent>) {
+        let precreated = self.precreate_routes(&events).await;
+        for event in events {
+            if let Err(e) = self.on_ros_discovery_event(event).await {
+                tracing::warn!("Error updating route: {e}");
+            }
+        }
+        // a pre-created route finally serving nothing (e.g. on error processing its event) is removed
+        for (kind, ros2_name) in precreated {
+            let unused = match kind {
+                RouteKind::Publisher => self
+                    .routes_publishers
+                    .get(&ros2_name)
+                    .map_or(false, RoutePublisher::is_unused),
+                _ => self
+                    .routes_subscribers
+                    .get(&ros2_name)
+                    .map_or(false, RouteSubscriber::is_unused),
+            };
+            if unused {
+                self.remove_route(kind, &ros2_name);
+            }
+        }
+    }
+
+    // Create concurrently, up to "max_concurrent_route_creations" at a time, the Publisher and Subscriber routes
+    // required by a batch of discovery events (e.g. at startup), as each creation awaits Zenoh declarations.
+    // Only the creations are concurrent: the routes are then inserted in order, and the events processed as usual,
+    // finding their routes already created. Thus an undiscovery in the batch applies to a fully created route.
+    // Returns the pre-created routes.
+    async fn precreate_routes(
+        &mut self,
+        events: &[ROS2DiscoveryEvent],
+    ) -> Vec<(RouteKind, String)> {
         use ROS2DiscoveryEvent::*;
-        match event {
-            DiscoveredMsgPub(node, iface) => {
-                // Pick 1 discovered Writer amongst the possibly multiple ones listed in MsgPub
+        let max_concurrent = self.context.config.max_concurrent_route_creations;
+        if max_concurrent <= 1
+            || self.startup_pending.is_some()
+            || self.context.config.mode != RoutingMode::LocalRouting
+        {
+            return Vec::new();
+        }
+
+        // the routes to be created, as (ros2_name, ros2_type, keyless, qos), with the same QoS
+        // than if created while processing their 1st discovery event
+        let mut publishers: Vec<(String, String, bool, Qos)> = Vec::new();
+        let mut subscribers: Vec<(String, String, bool, Qos)> = Vec::new();
+        for event in events {
+            match event {
+                DiscoveredMsgPub(_, iface)
+                    if self.is_precreation_needed(
+                        RouteKind::Publisher,
+                        &iface.name,
+                        &iface.typ,
+                        &publishers,
+                        &subscribers,
+                    ) =>
+                {
+                    let entities: Vec<DdsEntity> = {
+                        let entities = zread!(self.context.discovered_entities);
+                        iface
+                            .writers
+                            .iter()
+                            .filter_map(|w| entities.get_writer(w))
+                            .map(Clone::clone)
+                            .collect()
+                    };
+                    let keyless = entities.iter().all(|e| e.keyless);
+                    if let Some(qos) = merge_writers_qos(entities.iter().map(|e| &e.qos)) {
+                        publishers.push((
+                            iface.name.clone(),
+                            iface.typ.clone(),
+                            keyless,
+                            adapt_writer_qos_for_reader(&qos),
+                        ));
+                    }
+                }
+                DiscoveredMsgSub(_, iface)
+                    if self.is_precreation_needed(
+                        RouteKind::Subscriber,
+                        &iface.name,
+                        &iface.typ,
+                        &subscribers,
+                        &publishers,
+                    ) =>
+                {
+                    let entity = {
+                        let entities = zread!(self.context.discovered_entities);
+                        iface
+                            .readers
+                            .iter()
+                            .find_map(|r| entities.get_reader(r))
+                            .map(Clone::clone)
+                    };
+                    if let Some(entity) = entity {
+                        subscribers.push((
+                            iface.name.clone(),
+                            iface.typ.clone(),
+                            entity.keyless,
+                            adapt_reader_qos_for_writer(&entity.qos),
+                        ));
+                    }
+                }
+                _ => (),
+            }
+        }
+        if publishers.len() + subscribers.len() < 2 {
+            // nothing to be gained
+            return Vec::new();
+        }
+        tracing::debug!(
+            "Create {} Publisher and {} Subscriber routes concurrently",
+            publishers.len(),
+            subscribers.len()
+        );
+
+        let mut precreated = Vec::new();
+        let config = self.context.config.clone();
+        let creations = publishers
+            .into_iter()
+            .map(|(ros2_name, ros2_type, keyless, qos)| {
+                let zenoh_key_expr = route_key_expr(&config, RouteKind::Publisher, &ros2_name);
+                RoutePublisher::create(
+                    ros2_name,
+                    ros2_type,
+                    zenoh_key_expr,
+                    &None,
+                    keyless,
+                    qos,
+                    self.context.clone(),
+                )
+            });
+        for result in run_concurrently(creations.collect(), max_concurrent).await {
+            match result {
+                Ok(route) => {
+                    let ros2_name = self
+                        .insert_route_publisher(route, true)
+                        .ros2_name()
+                        .to_string();
+                    precreated.push((RouteKind::Publisher, ros2_name));
+                }
+                // the creation is attempted again when processing the event
+                Err(e) => tracing::debug!("Error creating route: {e}"),
+            }
+        }
+        let creations = subscribers
+            .into_iter()
+            .map(|(ros2_name, ros2_type, keyless, qos)| {
+                let zenoh_key_expr = route_key_expr(&config, RouteKind::Subscriber, &ros2_name);
+                RouteSubscriber::create(
+                    ros2_name,
+                    ros2_type,
+                    zenoh_key_expr,
+                    keyless,
+                    qos,
+                    self.context.clone(),
+                )
+            });
+        for result in run_concurrently(creations.collect(), max_concurrent).await {
+            match result {
+                Ok(route) => {
+                    let ros2_name = self
+                        .insert_route_subscriber(route, true)
+                        .await
+                        .ros2_name()
+                        .to_string();
+                    precreated.push((RouteKind::Subscriber, ros2_name));
+                }
+                Err(e) => tracing::debug!("Error creating route: {e}"),
+            }
+        }
+        precreated
+    }
+
+    // Check if a route is to be pre-created for a discovered interface (see precreate_routes()): not existing nor
+    // planned yet, and passing the checks done on its creation (the errors being reported when processing its event)
+    fn is_precreation_needed(
+        &mut self,
+        kind: RouteKind,
+        ros2_name: &str,
+        ros2_type: &str,
+        planned: &[(String, String, bool, Qos)],
+        planned_other_kind: &[(String, String, bool, Qos)],
+    ) -> bool {
+        let (routed, routed_type): (Vec<&String>, Option<&str>) = match kind {
+            RouteKind::Publisher => (
+                self.routes_publishers.keys().collect(),
+                self.routes_subscribers
+                    .get(ros2_name)
+                    .map(RouteSubscriber::ros2_type),
+            ),
+            _ => (
+                self.routes_subscribers.keys().collect(),
+                self.routes_publishers
+                    .get(ros2_name)
+                    .map(RoutePublisher::ros2_type),
+            ),
+        };
+        if routed.iter().any(|n| n.as_str() == ros2_name)
+            || planned.iter().any(|(n, ..)| n == ros2_name)
+        {
+            return false;
+        }
+        let routed_type = routed_type.or_else(|| {
+            planned_other_kind
+                .iter()
+                .find(|(n, ..)| n == ros2_name)
+                .map(|(_, t, ..)| t.as_str())
+        });
+        let config = &self.context.config;
+        if check_topic_type(
+            ros2_name,
+            ros2_type,
+            config.get_topic_type(ros2_name),
+            routed_type,
+        )
+        .is_err()
+        {
+            return false;
+        }
+        let routed = routed.into_iter().chain(planned.iter().map(|(n, ..)| n));
+        if check_key_expr_collision(config, kind, ros2_name, ros2_type, routed).is_err() {
+            return false;
+        }
+        check_dds_topic_name(config, kind, ros2_name, &mut self.long_names).is_ok()
+    }
+
+    pub async fn on_ros_announcement_event(
+        &mut self,
+        event: ROS2AnnouncementEvent,
+    ) -> Result<(), String> {
+        let (kind, plugin_id, zenoh_key_expr, _) = announcement_event_key(&event);
+        if unscope_key_expr(zenoh_scope(&self.context.config, kind), zenoh_key_expr).is_none() {
+            tracing::debug!(
+                "Announcement from remote bridge {plugin_id} on {zenoh_key_expr} is out of scope - ignored"
+            );
+            return Ok(());
+        }
+        match self.context.config.mode {
+            RoutingMode::LocalRouting if self.route_idle.is_some() => {
+                self.idle_announcement_event(event).await
+            }
+            RoutingMode::LocalRouting => self.route_announcement_event(event).await,
+            RoutingMode::ForwardDiscovery => self.forward_announcement_event(event).await,
+        }
+    }
+
+    // With "route_idle_timeout", the announcements of the remote routes are recorded,
+    // to be replayed if the route serving them is torn down and then re-created
+    async fn idle_announcement_event(
+        &mut self,
+        event: ROS2AnnouncementEvent,
+    ) -> Result<(), String> {
+        let (kind, plugin_id, zenoh_key_expr, announced) = announcement_event_key(&event);
+        let ros2_name = self.remote_ros2_name(kind, zenoh_key_expr);
+        let remote_id = format!("{plugin_id}:{zenoh_key_expr}");
+        if let Some(idle) = &mut self.route_idle {
+            if announced {
+                idle.on_announced(kind, &ros2_name, &remote_id, event.clone());
+            } else {
+                idle.on_retired(kind, &ros2_name, &remote_id);
+            }
+        }
+        self.route_announcement_event(event).await?;
+        if announced {
+            self.replay_torn_down_route(kind, &ros2_name).await?;
+        }
+        Ok(())
+    }
+
+    // If the route was torn down because idle and has just been re-created,
+    // add again the remote routes it was serving
+    async fn replay_torn_down_route(
+        &mut self,
+        kind: RouteKind,
+        ros2_name: &str,
+    ) -> Result<(), String> {
+        let announcements = match &mut self.route_idle {
+            Some(idle) => idle.take_torn_down(kind, ros2_name),
+            None => return Ok(()),
+        };
+        for announcement in announcements {
+            self.route_announcement_event(announcement).await?;
+        }
+        Ok(())
+    }
+
+    // In "forward_discovery" mode, a local interface is always announced, but routed only
+    // if it has a matching counterpart announced by a remote bridge
+    async fn forward_discovery_event(&mut self, event: ROS2DiscoveryEvent) -> Result<(), String> {
+        let (kind, ros2_name, node, discovered) = discovery_event_key(&event);
+        let (ros2_name, node) = (ros2_name.to_string(), node.to_string());
+        if discovered {
+            let was_matched = self.forward_discovery.is_matched(kind, &ros2_name);
+            if self
+                .forward_discovery
+                .add_local(kind, &ros2_name, &node, event.clone())
+            {
+                // 1st local Node for this interface: announce it for the remote bridges to match it
+                if let Some(token) = self.declare_forward_token(kind, &ros2_name, &event).await? {
+                    self.forward_tokens.insert((kind, ros2_name.clone()), token);
+                }
+            }
+            if self.forward_discovery.is_matched(kind, &ros2_name) {
+                self.route_discovery_event(event).await?;
+                if !was_matched {
+                    // the route was just created: add the remote routes it serves
+                    for announcement in self.forward_discovery.remote_events(kind, &ros2_name) {
+                        self.route_announcement_event(announcement).await?;
+                    }
+                }
+            }
+        } else if self.forward_discovery.remove_local(kind, &ros2_name, &node) {
+            // no more local Node for this interface: stop routing and announcing it
+            self.remove_route(kind, &ros2_name);
+            self.forward_tokens.remove(&(kind, ros2_name));
+        } else {
+            self.route_discovery_event(event).await?;
+        }
+        Ok(())
+    }
+
+    // In "forward_discovery" mode, a remote announcement leads to a route only
+    // if it has a matching local interface
+    async fn forward_announcement_event(
+        &mut self,
+        event: ROS2AnnouncementEvent,
+    ) -> Result<(), String> {
+        let (kind, plugin_id, zenoh_key_expr, announced) = announcement_event_key(&event);
+        let ros2_name = self.remote_ros2_name(kind, zenoh_key_expr);
+        let remote_id = format!("{plugin_id}:{zenoh_key_expr}");
+        if announced {
+            let was_matched = self.forward_discovery.is_matched(kind, &ros2_name);
+            self.forward_discovery
+                .add_remote(kind, &ros2_name, &remote_id, event.clone());
+            if self.forward_discovery.is_matched(kind, &ros2_name) {
+                if !was_matched {
+                    // create the route for the local Nodes
+                    for discovery in self.forward_discovery.local_events(kind, &ros2_name) {
+                        self.route_discovery_event(discovery).await?;
+                    }
+                }
+                self.route_announcement_event(event).await?;
+            }
+        } else if self
+            .forward_discovery
+            .remove_remote(kind, &ros2_name, &remote_id)
+        {
+            // no more remote counterpart: stop routing (the local interface remains announced)
+            self.remove_route(kind, &ros2_name);
+        } else {
+            self.route_announcement_event(event).await?;
+        }
+        Ok(())
+    }
+
+    // In "forward_discovery" mode, announce a local interface via a LivelinessToken with the same
+    // key expression than the route that would serve it (Actions messages and services excepted,
+    // as they are announced by the Action itself).
+    // Note: once routed, the route declares the same token; zenoh retires it only when both are undeclared.
+    async fn declare_forward_token(
+        &self,
+        kind: RouteKind,
+        ros2_name: &str,
+        event: &ROS2DiscoveryEvent,
+    ) -> Result<Option<LivelinessToken<'a>>, String> {
+        use ROS2DiscoveryEvent::*;
+        let plugin_id = &self.context.plugin_id;
+        let zenoh_key_expr = route_key_expr(&self.context.config, kind, ros2_name);
+        let liveliness_ke = match event {
+            DiscoveredMsgPub(_, iface) if !is_message_for_action(&iface.name) => {
                 let entity = {
                     let entities = zread!(self.context.discovered_entities);
                     iface
@@ -146,45 +753,478 @@ impl<'a> RoutesMgr<'a> {
                         .iter()
                         .find_map(|w| entities.get_writer(w))
                         .map(Clone::clone)
+                }
+                .ok_or_else(|| format!("Failed to get DDS info for any Writer of {iface}"))?;
+                // same QoS than announced by the route (see RoutePublisher::announce_route())
+                let qos = if !is_transient_local(&entity.qos)
+                    && self.context.config.is_transient_local_forced(ros2_name)
+                {
+                    force_transient_local(&entity.qos)
+                } else {
+                    entity.qos
                 };
-                match entity {
-                    Some(entity) => {
-                        // Get route (create it if not yet exists)
-                        let route = self
-                            .get_or_create_route_publisher(
-                                iface.name,
-                                iface.typ,
-                                entity.keyless,
-                                adapt_writer_qos_for_reader(&entity.qos),
-                                true,
-                            )
-                            .await?;
-                        route.add_local_node(node, &entity.qos).await;
+                let qos = filter_deadline(qos, self.context.config.propagate_deadline);
+                new_ke_liveliness_pub(
+                    plugin_id,
+                    &zenoh_key_expr,
+                    &iface.typ,
+                    entity.keyless,
+                    &qos,
+                    self.context.config.typed_key_exprs,
+                )?
+            }
+            DiscoveredMsgSub(_, iface) if !is_message_for_action(&iface.name) => {
+                let entity = {
+                    let entities = zread!(self.context.discovered_entities);
+                    iface
+                        .readers
+                        .iter()
+                        .find_map(|r| entities.get_reader(r))
+                        .map(Clone::clone)
+                }
+                .ok_or_else(|| format!("Failed to get DDS info for any Reader of {iface}"))?;
+                let qos = filter_deadline(entity.qos, self.context.config.propagate_deadline);
+                new_ke_liveliness_sub(
+                    plugin_id,
+                    &zenoh_key_expr,
+                    &iface.typ,
+                    entity.keyless,
+                    &qos,
+                    self.context.config.typed_key_exprs,
+                )?
+            }
+            DiscoveredServiceSrv(_, iface) if !is_service_for_action(&iface.name) => {
+                new_ke_liveliness_service_srv(plugin_id, &zenoh_key_expr, &iface.typ)?
+            }
+            DiscoveredServiceCli(_, iface) if !is_service_for_action(&iface.name) => {
+                new_ke_liveliness_service_cli(plugin_id, &zenoh_key_expr, &iface.typ)?
+            }
+            DiscoveredActionSrv(_, iface) => {
+                new_ke_liveliness_action_srv(plugin_id, &zenoh_key_expr, &iface.typ)?
+            }
+            DiscoveredActionCli(_, iface) => {
+                new_ke_liveliness_action_cli(plugin_id, &zenoh_key_expr, &iface.typ)?
+            }
+            _ => return Ok(None),
+        };
+        tracing::debug!("Announce {ros2_name} via token {liveliness_ke}");
+        self.context
+            .zsession
+            .liveliness()
+            .declare_token(liveliness_ke)
+            .res_async()
+            .await
+            .map(Some)
+            .map_err(|e| format!("Failed create LivelinessToken announcing {ros2_name}: {e}"))
+    }
+
+    // Remove a route, whatever its local Nodes and remote routes
+    fn remove_route(&mut self, kind: RouteKind, ros2_name: &str) {
+        let zenoh_key_expr = route_key_expr(&self.context.config, kind, ros2_name);
+        let (admin_ke, route) = match kind {
+            RouteKind::Publisher => (
+                *KE_PREFIX_ROUTE_PUBLISHER / &zenoh_key_expr,
+                self.routes_publishers
+                    .remove(ros2_name)
+                    .map(|r| r.to_string()),
+            ),
+            RouteKind::Subscriber => (
+                *KE_PREFIX_ROUTE_SUBSCRIBER / &zenoh_key_expr,
+                self.routes_subscribers
+                    .remove(ros2_name)
+                    .map(|r| r.to_string()),
+            ),
+            RouteKind::ServiceSrv => (
+                *KE_PREFIX_ROUTE_SERVICE_SRV / &zenoh_key_expr,
+                self.routes_service_srv
+                    .remove(ros2_name)
+                    .map(|r| r.to_string()),
+            ),
+            RouteKind::ServiceCli => (
+                *KE_PREFIX_ROUTE_SERVICE_CLI / &zenoh_key_expr,
+                self.routes_service_cli
+                    .remove(ros2_name)
+                    .map(|r| r.to_string()),
+            ),
+            RouteKind::ActionSrv => (
+                *KE_PREFIX_ROUTE_ACTION_SRV / &zenoh_key_expr,
+                self.routes_action_srv
+                    .remove(ros2_name)
+                    .map(|r| r.to_string()),
+            ),
+            RouteKind::ActionCli => (
+                *KE_PREFIX_ROUTE_ACTION_CLI / &zenoh_key_expr,
+                self.routes_action_cli
+                    .remove(ros2_name)
+                    .map(|r| r.to_string()),
+            ),
+        };
+        if let Some(route) = route {
+            self.admin_space.remove(&admin_ke);
+            tracing::info!("{route} removed");
+            match kind {
+                RouteKind::ServiceSrv | RouteKind::ServiceCli => {
+                    self.unroute_service_events(ros2_name)
+                }
+                RouteKind::Publisher => {
+                    if let Some(service) = service_of_event_topic(ros2_name) {
+                        self.update_service_events_admin_space(service);
                     }
-                    None => {
-                        return Err(format!(
-                            "Failed to get DDS info for any Writer of {iface} ({:?})",
-                            iface.writers
-                        ))
+                }
+                _ => (),
+            }
+        }
+    }
+
+    // With "route_linger", check if a re-discovered Publisher or Subscriber was lingering.
+    // If its QoS didn't change, its route is reused and the lingering Node to be replaced is returned.
+    // Otherwise, the lingering Node is removed from the route first (retiring or removing it).
+    fn end_lingering(
+        &mut self,
+        kind: RouteKind,
+        ros2_name: &str,
+        node: &str,
+        qos_ke: OwnedKeyExpr,
+    ) -> Option<String> {
+        match self
+            .route_linger
+            .as_mut()?
+            .on_discovered(kind, ros2_name, node, qos_ke)
+        {
+            Rediscovery::NotLingering => None,
+            Rediscovery::Reuse(lingering_node) => {
+                tracing::info!("Lingering route for {ros2_name} reused by Node {node}");
+                Some(lingering_node)
+            }
+            Rediscovery::QosChanged(lingering_node) => {
+                tracing::info!(
+                    "Lingering route for {ros2_name} re-created for Node {node} declaring other QoS"
+                );
+                self.remove_lingering_node(kind, ros2_name, &lingering_node);
+                None
+            }
+        }
+    }
+
+    // Remove a lingering Node from its Publisher or Subscriber route, removing the route if unused
+    fn remove_lingering_node(&mut self, kind: RouteKind, ros2_name: &str, node: &str) {
+        let unused = match kind {
+            RouteKind::Publisher => self.routes_publishers.get_mut(ros2_name).map(|route| {
+                route.remove_local_node(node);
+                route.is_unused()
+            }),
+            RouteKind::Subscriber => self.routes_subscribers.get_mut(ros2_name).map(|route| {
+                route.remove_local_node(node);
+                route.is_unused()
+            }),
+            _ => None,
+        };
+        if unused == Some(true) {
+            self.remove_route(kind, ros2_name);
+        }
+    }
+
+    /// The time of the next check of the idle routes (if "route_idle_timeout" is configured)
+    pub fn next_idle_check(&self) -> Option<Instant> {
+        self.route_idle.as_ref().map(RouteIdle::next_check)
+    }
+
+    /// Remove the routes serving no local Node and without traffic for their "route_idle_timeout"
+    pub fn expire_idle_routes(&mut self) {
+        if self.route_idle.is_none() {
+            return;
+        }
+        let candidates = self.idle_candidates();
+        let idle_routes = match &mut self.route_idle {
+            Some(idle) => idle.take_idle(candidates, Instant::now()),
+            None => return,
+        };
+        for (kind, ros2_name) in idle_routes {
+            tracing::info!("{kind:?} route for {ros2_name} idle for its route_idle_timeout");
+            self.remove_route(kind, &ros2_name);
+        }
+    }
+
+    // The routes serving no local Node, with their "route_idle_timeout" and their time without traffic.
+    // The routes of the topics in "force_transient_local" are pinned and never torn down.
+    fn idle_candidates(&self) -> Vec<IdleCandidate> {
+        let config = &self.context.config;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let mut candidates = Vec::new();
+        let mut push = |kind: RouteKind, ros2_name: &String, stats: RouteStatsSnapshot| {
+            if let Some(timeout) = config.get_route_idle_timeout(kind) {
+                candidates.push(IdleCandidate {
+                    kind,
+                    ros2_name: ros2_name.clone(),
+                    timeout,
+                    inactivity: stats.last_activity.map_or(Duration::MAX, |t| {
+                        Duration::from_secs_f64((now - t).max(0.0))
+                    }),
+                });
+            }
+        };
+        for (name, route) in &self.routes_publishers {
+            if !route.is_serving_local_node() && !config.is_transient_local_forced(name) {
+                push(RouteKind::Publisher, name, route.stats());
+            }
+        }
+        for (name, route) in &self.routes_subscribers {
+            if !route.is_serving_local_node() && !config.is_transient_local_forced(name) {
+                push(RouteKind::Subscriber, name, route.stats());
+            }
+        }
+        for (name, route) in &self.routes_service_srv {
+            if !route.is_serving_local_node() {
+                push(RouteKind::ServiceSrv, name, route.stats());
+            }
+        }
+        for (name, route) in &self.routes_service_cli {
+            if !route.is_serving_local_node() {
+                push(RouteKind::ServiceCli, name, route.stats());
+            }
+        }
+        for (name, route) in &self.routes_action_srv {
+            if !route.is_serving_local_node() {
+                push(RouteKind::ActionSrv, name, route.stats());
+            }
+        }
+        for (name, route) in &self.routes_action_cli {
+            if !route.is_serving_local_node() {
+                push(RouteKind::ActionCli, name, route.stats());
+            }
+        }
+        candidates
+    }
+
+    /// The time of the next expiry of a lingering route (if any)
+    pub fn next_linger_expiry(&self) -> Option<Instant> {
+        self.route_linger.as_ref()?.next_expiry()
+    }
+
+    /// Remove the lingering Nodes for which the "route_linger" delay expired without re-discovery
+    pub fn expire_lingering_routes(&mut self) {
+        if let Some(linger) = &mut self.route_linger {
+            for (kind, ros2_name, node) in linger.take_expired(Instant::now()) {
+                tracing::debug!("Linger delay expired for Node {node} on {ros2_name}");
+                self.remove_lingering_node(kind, &ros2_name, &node);
+            }
+        }
+    }
+
+    // Route a discovered local Publisher (creating its route if not yet exists)
+    async fn add_msg_pub_node(&mut self, node: String, iface: MsgPub) -> Result<(), String> {
+        if !self.is_topic_type_accepted(&iface.name, &iface.typ, &format!("Node {node}")) {
+            return Ok(());
+        }
+        // Merge the QoS of the possibly multiple discovered Writers listed in MsgPub
+        let entities: Vec<DdsEntity> = {
+            let entities = zread!(self.context.discovered_entities);
+            iface
+                .writers
+                .iter()
+                .filter_map(|w| entities.get_writer(w))
+                .map(Clone::clone)
+                .collect()
+        };
+        let keyless = entities.iter().all(|e| e.keyless);
+        match merge_writers_qos(entities.iter().map(|e| &e.qos)) {
+            Some(qos) => {
+                let lingering_node = self.end_lingering(
+                    RouteKind::Publisher,
+                    &iface.name,
+                    &node,
+                    qos_to_key_expr(keyless, &qos),
+                );
+                let ros2_name = iface.name.clone();
+                let service = service_of_event_topic(&ros2_name);
+                // Get route (create it if not yet exists)
+                let route = self
+                    .get_or_create_route_publisher(
+                        iface.name,
+                        iface.typ,
+                        keyless,
+                        adapt_writer_qos_for_reader(&qos),
+                        service.is_none(),
+                    )
+                    .await?;
+                route.add_local_node(node.clone(), &qos).await;
+                // the lingering Node is replaced by the re-discovered one
+                if let Some(lingering_node) = lingering_node {
+                    if lingering_node != node {
+                        route.remove_local_node(&lingering_node);
+                    }
+                }
+                self.update_route_publisher_qos(&ros2_name).await?;
+                // the route of a Service's introspection events is grouped under the Service in admin space
+                if let Some(service) = service {
+                    self.update_service_events_admin_space(service);
+                }
+                Ok(())
+            }
+            None => Err(format!(
+                "Failed to get DDS info for any Writer of {iface} ({:?})",
+                iface.writers
+            )),
+        }
+    }
+
+    // Route the introspection events of a Service with its route, once it's created
+    async fn route_service_events(&mut self, service: &str) -> Result<(), String> {
+        for (node, iface) in self.service_events.on_service_routed(service) {
+            self.add_msg_pub_node(node, iface).await?;
+        }
+        self.update_service_events_admin_space(service);
+        self.update_parameters_admin_space(service);
+        Ok(())
+    }
+
+    // Tear down the route of the introspection events of a Service with its last route
+    fn unroute_service_events(&mut self, service: &str) {
+        self.update_parameters_admin_space(service);
+        if self.routes_service_srv.contains_key(service)
+            || self.routes_service_cli.contains_key(service)
+        {
+            self.update_service_events_admin_space(service);
+            return;
+        }
+        if self.service_events.on_service_unrouted(service) {
+            if let Some(route) = self.routes_publishers.remove(&service_event_topic(service)) {
+                tracing::info!("{route} removed with its Service");
+            }
+        }
+        self.update_service_events_admin_space(service);
+    }
+
+    // List the route of the introspection events of a Service (if any) under each route of the Service
+    // in admin space (e.g. "route/service/srv/add_two_ints/_service_event")
+    fn update_service_events_admin_space(&mut self, service: &str) {
+        let topic = service_event_topic(service);
+        self.admin_space.retain(
+            |_, route_ref| !matches!(route_ref, RouteRef::Publisher(name) if *name == topic),
+        );
+        if !self.routes_publishers.contains_key(&topic) {
+            return;
+        }
+        for (kind, prefix, routed) in [
+            (
+                RouteKind::ServiceSrv,
+                *KE_PREFIX_ROUTE_SERVICE_SRV,
+                self.routes_service_srv.contains_key(service),
+            ),
+            (
+                RouteKind::ServiceCli,
+                *KE_PREFIX_ROUTE_SERVICE_CLI,
+                self.routes_service_cli.contains_key(service),
+            ),
+        ] {
+            if routed {
+                let zenoh_key_expr = route_key_expr(&self.context.config, kind, service);
+                self.admin_space.insert(
+                    &(prefix / &zenoh_key_expr) / *KE_SUFFIX_SERVICE_EVENT,
+                    RouteRef::Publisher(topic.clone()),
+                );
+            }
+        }
+    }
+
+    // List the routes of a parameter Service of a Node configured in "bridge_parameters" grouped under
+    // the Node in admin space (e.g. "route/parameters/talker/srv/get_parameters")
+    fn update_parameters_admin_space(&mut self, service: &str) {
+        let Some((node, name)) = parameter_service_node(service) else {
+            return;
+        };
+        if !self.context.config.is_node_parameters_bridged(node) {
+            return;
+        }
+        let Ok(node_ke) = keyexpr::new(&node[1..]) else {
+            return;
+        };
+        let Ok(name_ke) = keyexpr::new(name) else {
+            return;
+        };
+        let node_prefix = *KE_PREFIX_ROUTE_PARAMETERS / node_ke;
+        let srv_ke = &(&node_prefix / *KE_SUFFIX_SRV) / name_ke;
+        let cli_ke = &(&node_prefix / *KE_SUFFIX_CLI) / name_ke;
+        if self.routes_service_srv.contains_key(service) {
+            self.admin_space
+                .insert(srv_ke, RouteRef::ServiceSrv(service.to_string()));
+        } else {
+            self.admin_space.remove(&srv_ke);
+        }
+        if self.routes_service_cli.contains_key(service) {
+            self.admin_space
+                .insert(cli_ke, RouteRef::ServiceCli(service.to_string()));
+        } else {
+            self.admin_space.remove(&cli_ke);
+        }
+    }
+
+    async fn route_discovery_event(&mut self, event: ROS2DiscoveryEvent) -> Result<(), String> {
+        use ROS2DiscoveryEvent::*;
+        match event {
+            DiscoveredMsgPub(node, iface) => {
+                // the Publishers of a Service's introspection events are routed with the Service
+                if let Some(service) = service_of_event_topic(&iface.name) {
+                    if !self
+                        .service_events
+                        .on_discovered(service, &node, iface.clone())
+                    {
+                        tracing::debug!(
+                            "Route Publisher {} deferred until a route of its Service",
+                            iface.name
+                        );
+                        return Ok(());
                     }
                 }
+                self.add_msg_pub_node(node, iface).await?;
             }
 
             UndiscoveredMsgPub(node, iface) => {
+                if let Some(service) = service_of_event_topic(&iface.name) {
+                    if !self.service_events.on_undiscovered(service, &node) {
+                        return Ok(());
+                    }
+                }
                 if let Entry::Occupied(mut entry) = self.routes_publishers.entry(iface.name.clone())
                 {
                     let route = entry.get_mut();
+                    // with "route_linger", defer the removal of the last local Node
+                    if let Some(linger) = &mut self.route_linger {
+                        if linger.on_undiscovered(
+                            RouteKind::Publisher,
+                            &iface.name,
+                            &node,
+                            route.is_last_local_node(&node),
+                            Instant::now(),
+                        ) {
+                            tracing::info!(
+                                "{route} lingering after undiscovery of its last local Node {node}"
+                            );
+                            return Ok(());
+                        }
+                    }
                     route.remove_local_node(&node);
                     if route.is_unused() {
                         self.admin_space
                             .remove(&(*KE_PREFIX_ROUTE_PUBLISHER / iface.name_as_keyexpr()));
                         let route = entry.remove();
                         tracing::info!("{route} removed");
+                        if let Some(service) = service_of_event_topic(&iface.name) {
+                            self.update_service_events_admin_space(service);
+                        }
+                    } else {
+                        self.update_route_publisher_qos(&iface.name).await?;
                     }
                 }
             }
 
             DiscoveredMsgSub(node, iface) => {
+                if !self.is_topic_type_accepted(&iface.name, &iface.typ, &format!("Node {node}")) {
+                    return Ok(());
+                }
                 // Pick 1 discovered Reader amongst the possibly multiple ones listed in MsgSub
                 let entity = {
                     let entities = zread!(self.context.discovered_entities);
@@ -196,6 +1236,12 @@ impl<'a> RoutesMgr<'a> {
                 };
                 match entity {
                     Some(entity) => {
+                        let lingering_node = self.end_lingering(
+                            RouteKind::Subscriber,
+                            &iface.name,
+                            &node,
+                            qos_to_key_expr(entity.keyless, &entity.qos),
+                        );
                         // Get route (create it if not yet exists)
                         let route = self
                             .get_or_create_route_subscriber(
@@ -206,7 +1252,13 @@ impl<'a> RoutesMgr<'a> {
                                 true,
                             )
                             .await?;
-                        route.add_local_node(node, &entity.qos).await;
+                        route.add_local_node(node.clone(), &entity.qos).await;
+                        // the lingering Node is replaced by the re-discovered one
+                        if let Some(lingering_node) = lingering_node {
+                            if lingering_node != node {
+                                route.remove_local_node(&lingering_node);
+                            }
+                        }
                     }
                     None => {
                         return Err(format!(
@@ -222,6 +1274,21 @@ impl<'a> RoutesMgr<'a> {
                     self.routes_subscribers.entry(iface.name.clone())
                 {
                     let route = entry.get_mut();
+                    // with "route_linger", defer the removal of the last local Node
+                    if let Some(linger) = &mut self.route_linger {
+                        if linger.on_undiscovered(
+                            RouteKind::Subscriber,
+                            &iface.name,
+                            &node,
+                            route.is_last_local_node(&node),
+                            Instant::now(),
+                        ) {
+                            tracing::info!(
+                                "{route} lingering after undiscovery of its last local Node {node}"
+                            );
+                            return Ok(());
+                        }
+                    }
                     route.remove_local_node(&node);
                     if route.is_unused() {
                         self.admin_space
@@ -232,11 +1299,13 @@ impl<'a> RoutesMgr<'a> {
                 }
             }
             DiscoveredServiceSrv(node, iface) => {
+                let ros2_name = iface.name.clone();
                 // Get route (create it if not yet exists)
                 let route = self
                     .get_or_create_route_service_srv(iface.name, iface.typ, true)
                     .await?;
-                route.add_local_node(node).await;
+                route.add_local_node(node, iface.entities).await;
+                self.route_service_events(&ros2_name).await?;
             }
             UndiscoveredServiceSrv(node, iface) => {
                 if let Entry::Occupied(mut entry) =
@@ -249,27 +1318,31 @@ impl<'a> RoutesMgr<'a> {
                             .remove(&(*KE_PREFIX_ROUTE_SERVICE_SRV / iface.name_as_keyexpr()));
                         let route = entry.remove();
                         tracing::info!("{route} removed");
+                        self.unroute_service_events(&iface.name);
                     }
                 }
             }
             DiscoveredServiceCli(node, iface) => {
+                let ros2_name = iface.name.clone();
                 // Get route (create it if not yet exists)
                 let route = self
                     .get_or_create_route_service_cli(iface.name, iface.typ, true)
                     .await?;
                 route.add_local_node(node).await;
+                self.route_service_events(&ros2_name).await?;
             }
             UndiscoveredServiceCli(node, iface) => {
                 if let Entry::Occupied(mut entry) =
                     self.routes_service_cli.entry(iface.name.clone())
                 {
                     let route = entry.get_mut();
-                    route.remove_local_node(&node);
+                    route.remove_local_node(&node, &iface.entities);
                     if route.is_unused() {
                         self.admin_space
                             .remove(&(*KE_PREFIX_ROUTE_SERVICE_CLI / iface.name_as_keyexpr()));
                         let route = entry.remove();
                         tracing::info!("{route} removed");
+                        self.unroute_service_events(&iface.name);
                     }
                 }
             }
@@ -278,7 +1351,7 @@ impl<'a> RoutesMgr<'a> {
                 let route = self
                     .get_or_create_route_action_srv(iface.name, iface.typ)
                     .await?;
-                route.add_local_node(node).await;
+                route.add_local_node(node, &iface.entities).await;
             }
             UndiscoveredActionSrv(node, iface) => {
                 if let Entry::Occupied(mut entry) = self.routes_action_srv.entry(iface.name.clone())
@@ -304,7 +1377,7 @@ impl<'a> RoutesMgr<'a> {
                 if let Entry::Occupied(mut entry) = self.routes_action_cli.entry(iface.name.clone())
                 {
                     let route = entry.get_mut();
-                    route.remove_local_node(&node);
+                    route.remove_local_node(&node, &iface.entities);
                     if route.is_unused() {
                         self.admin_space
                             .remove(&(*KE_PREFIX_ROUTE_ACTION_CLI / iface.name_as_keyexpr()));
@@ -317,7 +1390,7 @@ impl<'a> RoutesMgr<'a> {
         Ok(())
     }
 
-    pub async fn on_ros_announcement_event(
+    async fn route_announcement_event(
         &mut self,
         event: ROS2AnnouncementEvent,
     ) -> Result<(), String> {
@@ -330,7 +1403,19 @@ impl<'a> RoutesMgr<'a> {
                 keyless,
                 writer_qos,
             } => {
-                let mut qos = writer_qos.clone();
+                let ros2_name = self.remote_ros2_name(RouteKind::Subscriber, &zenoh_key_expr);
+                if !self.is_announcement_accepted(
+                    RouteKind::Subscriber,
+                    &plugin_id,
+                    &ros2_name,
+                    &ros2_type,
+                    &writer_qos,
+                ) {
+                    return Ok(());
+                }
+                self.check_announced_keyless(&plugin_id, &ros2_name, keyless);
+                let ownership_strength = get_exclusive_ownership_strength(&writer_qos);
+                let mut qos = filter_deadline(writer_qos, self.context.config.propagate_deadline);
                 qos.ignore_local = Some(IgnoreLocal {
                     kind: cyclors::qos::IgnoreLocalKind::PARTICIPANT,
                 });
@@ -338,28 +1423,40 @@ impl<'a> RoutesMgr<'a> {
                 // with an associated DDS Writer allowing local ROS2 Nodes to discover it
                 let route = self
                     .get_or_create_route_subscriber(
-                        key_expr_to_ros2_name(&zenoh_key_expr, &self.context.config),
+                        ros2_name,
                         ros2_type,
                         keyless,
-                        qos,
+                        qos.clone(),
                         true,
                     )
                     .await?;
+                // if the route doesn't serve another remote route (e.g. the remote Publisher restarted),
+                // follow the QoS of the remote Writer, re-creating the DDS Writer if it changed
+                if !route.is_serving_remote_route() {
+                    route.update_received_qos(qos).await;
+                }
                 route.add_remote_route(&plugin_id, &zenoh_key_expr);
+                route.set_remote_ownership_strength(
+                    &plugin_id,
+                    &zenoh_key_expr,
+                    ownership_strength,
+                );
             }
 
             RetiredMsgPub {
                 plugin_id,
                 zenoh_key_expr,
             } => {
-                if let Entry::Occupied(mut entry) =
-                    self.routes_subscribers.entry(format!("/{zenoh_key_expr}"))
-                {
+                let ros2_name = self.remote_ros2_name(RouteKind::Subscriber, &zenoh_key_expr);
+                self.remove_conflict(RouteKind::Subscriber, &plugin_id, &ros2_name);
+                let local_key_expr =
+                    route_key_expr(&self.context.config, RouteKind::Subscriber, &ros2_name);
+                if let Entry::Occupied(mut entry) = self.routes_subscribers.entry(ros2_name) {
                     let route = entry.get_mut();
                     route.remove_remote_route(&plugin_id, &zenoh_key_expr);
                     if route.is_unused() {
                         self.admin_space
-                            .remove(&(*KE_PREFIX_ROUTE_SUBSCRIBER / &zenoh_key_expr));
+                            .remove(&(*KE_PREFIX_ROUTE_SUBSCRIBER / &local_key_expr));
                         let route = entry.remove();
                         tracing::info!("{route} removed");
                     }
@@ -373,36 +1470,45 @@ impl<'a> RoutesMgr<'a> {
                 keyless,
                 reader_qos,
             } => {
-                let mut qos = reader_qos.clone();
+                let ros2_name = self.remote_ros2_name(RouteKind::Publisher, &zenoh_key_expr);
+                if !self.is_announcement_accepted(
+                    RouteKind::Publisher,
+                    &plugin_id,
+                    &ros2_name,
+                    &ros2_type,
+                    &reader_qos,
+                ) {
+                    return Ok(());
+                }
+                self.check_announced_keyless(&plugin_id, &ros2_name, keyless);
+                let separation = get_time_based_filter_separation(&reader_qos)
+                    .filter(|_| self.context.config.propagate_time_based_filter);
+                let mut qos = filter_deadline(reader_qos, self.context.config.propagate_deadline);
                 qos.ignore_local = Some(IgnoreLocal {
                     kind: cyclors::qos::IgnoreLocalKind::PARTICIPANT,
                 });
                 // On remote Subscriber route announcement, prepare a Publisher route
                 // with an associated DDS Reader allowing local ROS2 Nodes to discover it
                 let route = self
-                    .get_or_create_route_publisher(
-                        key_expr_to_ros2_name(&zenoh_key_expr, &self.context.config),
-                        ros2_type,
-                        keyless,
-                        qos,
-                        true,
-                    )
+                    .get_or_create_route_publisher(ros2_name, ros2_type, keyless, qos, true)
                     .await?;
-                route.add_remote_route(&plugin_id, &zenoh_key_expr);
+                route.add_remote_route(&plugin_id, &zenoh_key_expr, separation);
             }
 
             RetiredMsgSub {
                 plugin_id,
                 zenoh_key_expr,
             } => {
-                if let Entry::Occupied(mut entry) =
-                    self.routes_publishers.entry(format!("/{zenoh_key_expr}"))
-                {
+                let ros2_name = self.remote_ros2_name(RouteKind::Publisher, &zenoh_key_expr);
+                self.remove_conflict(RouteKind::Publisher, &plugin_id, &ros2_name);
+                let local_key_expr =
+                    route_key_expr(&self.context.config, RouteKind::Publisher, &ros2_name);
+                if let Entry::Occupied(mut entry) = self.routes_publishers.entry(ros2_name) {
                     let route = entry.get_mut();
                     route.remove_remote_route(&plugin_id, &zenoh_key_expr);
                     if route.is_unused() {
                         self.admin_space
-                            .remove(&(*KE_PREFIX_ROUTE_PUBLISHER / &zenoh_key_expr));
+                            .remove(&(*KE_PREFIX_ROUTE_PUBLISHER / &local_key_expr));
                         let route = entry.remove();
                         tracing::info!("{route} removed");
                     }
@@ -416,30 +1522,31 @@ impl<'a> RoutesMgr<'a> {
             } => {
                 // On remote Service Server route announcement, prepare a Service Client route
                 // with a associated DDS Reader/Writer allowing local ROS2 Nodes to discover it
+                let ros2_name = self.remote_ros2_name(RouteKind::ServiceCli, &zenoh_key_expr);
                 let route = self
-                    .get_or_create_route_service_cli(
-                        key_expr_to_ros2_name(&zenoh_key_expr, &self.context.config),
-                        ros2_type,
-                        true,
-                    )
+                    .get_or_create_route_service_cli(ros2_name.clone(), ros2_type, true)
                     .await?;
                 route.add_remote_route(&plugin_id, &zenoh_key_expr);
+                self.update_parameters_admin_space(&ros2_name);
             }
 
             RetiredServiceSrv {
                 plugin_id,
                 zenoh_key_expr,
             } => {
-                if let Entry::Occupied(mut entry) =
-                    self.routes_service_cli.entry(format!("/{zenoh_key_expr}"))
+                let ros2_name = self.remote_ros2_name(RouteKind::ServiceCli, &zenoh_key_expr);
+                let local_key_expr =
+                    route_key_expr(&self.context.config, RouteKind::ServiceCli, &ros2_name);
+                if let Entry::Occupied(mut entry) = self.routes_service_cli.entry(ros2_name.clone())
                 {
                     let route = entry.get_mut();
                     route.remove_remote_route(&plugin_id, &zenoh_key_expr);
                     if route.is_unused() {
                         self.admin_space
-                            .remove(&(*KE_PREFIX_ROUTE_SERVICE_CLI / &zenoh_key_expr));
+                            .remove(&(*KE_PREFIX_ROUTE_SERVICE_CLI / &local_key_expr));
                         let route = entry.remove();
                         tracing::info!("{route} removed");
+                        self.update_parameters_admin_space(&ros2_name);
                     }
                 }
             }
@@ -451,30 +1558,31 @@ impl<'a> RoutesMgr<'a> {
             } => {
                 // On remote Service Client route announcement, prepare a Service Server route
                 // with a associated DDS Reader/Writer allowing local ROS2 Nodes to discover it
+                let ros2_name = self.remote_ros2_name(RouteKind::ServiceSrv, &zenoh_key_expr);
                 let route = self
-                    .get_or_create_route_service_srv(
-                        key_expr_to_ros2_name(&zenoh_key_expr, &self.context.config),
-                        ros2_type,
-                        true,
-                    )
+                    .get_or_create_route_service_srv(ros2_name.clone(), ros2_type, true)
                     .await?;
                 route.add_remote_route(&plugin_id, &zenoh_key_expr);
+                self.update_parameters_admin_space(&ros2_name);
             }
 
             RetiredServiceCli {
                 plugin_id,
                 zenoh_key_expr,
             } => {
-                if let Entry::Occupied(mut entry) =
-                    self.routes_service_srv.entry(format!("/{zenoh_key_expr}"))
+                let ros2_name = self.remote_ros2_name(RouteKind::ServiceSrv, &zenoh_key_expr);
+                let local_key_expr =
+                    route_key_expr(&self.context.config, RouteKind::ServiceSrv, &ros2_name);
+                if let Entry::Occupied(mut entry) = self.routes_service_srv.entry(ros2_name.clone())
                 {
                     let route = entry.get_mut();
                     route.remove_remote_route(&plugin_id, &zenoh_key_expr);
                     if route.is_unused() {
                         self.admin_space
-                            .remove(&(*KE_PREFIX_ROUTE_SERVICE_SRV / &zenoh_key_expr));
+                            .remove(&(*KE_PREFIX_ROUTE_SERVICE_SRV / &local_key_expr));
                         let route = entry.remove();
                         tracing::info!("{route} removed");
+                        self.update_parameters_admin_space(&ros2_name);
                     }
                 }
             }
@@ -486,11 +1594,9 @@ impl<'a> RoutesMgr<'a> {
             } => {
                 // On remote Action Server route announcement, prepare a Action Client route
                 // with a associated DDS Reader/Writer allowing local ROS2 Nodes to discover it
+                let ros2_name = self.remote_ros2_name(RouteKind::ActionCli, &zenoh_key_expr);
                 let route = self
-                    .get_or_create_route_action_cli(
-                        key_expr_to_ros2_name(&zenoh_key_expr, &self.context.config),
-                        ros2_type,
-                    )
+                    .get_or_create_route_action_cli(ros2_name, ros2_type)
                     .await?;
                 route.add_remote_route(&plugin_id, &zenoh_key_expr);
             }
@@ -499,14 +1605,15 @@ impl<'a> RoutesMgr<'a> {
                 plugin_id,
                 zenoh_key_expr,
             } => {
-                if let Entry::Occupied(mut entry) =
-                    self.routes_action_cli.entry(format!("/{zenoh_key_expr}"))
-                {
+                let ros2_name = self.remote_ros2_name(RouteKind::ActionCli, &zenoh_key_expr);
+                let local_key_expr =
+                    route_key_expr(&self.context.config, RouteKind::ActionCli, &ros2_name);
+                if let Entry::Occupied(mut entry) = self.routes_action_cli.entry(ros2_name) {
                     let route = entry.get_mut();
                     route.remove_remote_route(&plugin_id, &zenoh_key_expr);
                     if route.is_unused() {
                         self.admin_space
-                            .remove(&(*KE_PREFIX_ROUTE_SERVICE_CLI / &zenoh_key_expr));
+                            .remove(&(*KE_PREFIX_ROUTE_ACTION_CLI / &local_key_expr));
                         let route = entry.remove();
                         tracing::info!("{route} removed");
                     }
@@ -520,11 +1627,9 @@ impl<'a> RoutesMgr<'a> {
             } => {
                 // On remote Action Client route announcement, prepare a Action Server route
                 // with a associated DDS Reader/Writer allowing local ROS2 Nodes to discover it
+                let ros2_name = self.remote_ros2_name(RouteKind::ActionSrv, &zenoh_key_expr);
                 let route = self
-                    .get_or_create_route_action_srv(
-                        key_expr_to_ros2_name(&zenoh_key_expr, &self.context.config),
-                        ros2_type,
-                    )
+                    .get_or_create_route_action_srv(ros2_name, ros2_type)
                     .await?;
                 route.add_remote_route(&plugin_id, &zenoh_key_expr);
             }
@@ -533,14 +1638,15 @@ impl<'a> RoutesMgr<'a> {
                 plugin_id,
                 zenoh_key_expr,
             } => {
-                if let Entry::Occupied(mut entry) =
-                    self.routes_action_srv.entry(format!("/{zenoh_key_expr}"))
-                {
+                let ros2_name = self.remote_ros2_name(RouteKind::ActionSrv, &zenoh_key_expr);
+                let local_key_expr =
+                    route_key_expr(&self.context.config, RouteKind::ActionSrv, &ros2_name);
+                if let Entry::Occupied(mut entry) = self.routes_action_srv.entry(ros2_name) {
                     let route = entry.get_mut();
                     route.remove_remote_route(&plugin_id, &zenoh_key_expr);
                     if route.is_unused() {
                         self.admin_space
-                            .remove(&(*KE_PREFIX_ROUTE_SERVICE_SRV / &zenoh_key_expr));
+                            .remove(&(*KE_PREFIX_ROUTE_ACTION_SRV / &local_key_expr));
                         let route = entry.remove();
                         tracing::info!("{route} removed");
                     }
@@ -550,12 +1656,306 @@ impl<'a> RoutesMgr<'a> {
         Ok(())
     }
 
+    // Check the type of a discovered or announced topic against the one configured in `topic_types` (if any),
+    // and against the one of an already existing route for this topic.
+    // On mismatch, log an error, count it (visible in admin space) and return false.
+    fn is_topic_type_accepted(&mut self, ros2_name: &str, ros2_type: &str, origin: &str) -> bool {
+        let routed_type = self
+            .routes_publishers
+            .get(ros2_name)
+            .map(RoutePublisher::ros2_type)
+            .or_else(|| {
+                self.routes_subscribers
+                    .get(ros2_name)
+                    .map(RouteSubscriber::ros2_type)
+            });
+        match check_topic_type(
+            ros2_name,
+            ros2_type,
+            self.context.config.get_topic_type(ros2_name),
+            routed_type,
+        ) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::error!("{e} - not routing it for {origin}");
+                *self
+                    .type_mismatches
+                    .entry(ros2_name.to_string())
+                    .or_default() += 1;
+                false
+            }
+        }
+    }
+
+    // Check a topic announced by a remote bridge (a Publisher for a Subscriber route, or a Subscriber for a
+    // Publisher route) against the same topic discovered locally and served by the route, if any.
+    // On conflict (see check_topic_conflict()), log it, record it (visible in admin space) and return false.
+    // Otherwise, check the type as for any topic (see is_topic_type_accepted()).
+    fn is_announcement_accepted(
+        &mut self,
+        kind: RouteKind,
+        plugin_id: &keyexpr,
+        ros2_name: &str,
+        ros2_type: &str,
+        qos: &Qos,
+    ) -> bool {
+        let local = match kind {
+            RouteKind::Subscriber => self
+                .routes_subscribers
+                .get(ros2_name)
+                .filter(|r| r.is_serving_local_node())
+                .and_then(|r| {
+                    Some((
+                        r.ros2_type().to_string(),
+                        r.discovered_reader_qos()?.clone(),
+                    ))
+                }),
+            RouteKind::Publisher => self
+                .routes_publishers
+                .get(ros2_name)
+                .filter(|r| r.is_serving_local_node())
+                .and_then(|r| Some((r.ros2_type().to_string(), r.merged_writers_qos()?))),
+            _ => None,
+        };
+        if let Some((local_type, local_qos)) = local {
+            // the Deadline announced by the remote bridge is ignored if not propagated
+            let remote_qos = filter_deadline(qos.clone(), self.context.config.propagate_deadline);
+            if let Some((conflict_kind, reasons)) = check_topic_conflict(
+                &self.context.config,
+                kind,
+                ros2_name,
+                (&local_type, &local_qos),
+                (ros2_type, &remote_qos),
+            ) {
+                let conflict = TopicConflict {
+                    topic: ros2_name.to_string(),
+                    kind: conflict_kind,
+                    reasons,
+                    remote_interface: remote_interface(kind),
+                    local_bridge: self.context.plugin_id.to_string(),
+                    local_type,
+                    local_qos: qos_to_json(&local_qos).unwrap_or_default(),
+                    remote_bridge: plugin_id.to_string(),
+                    remote_type: ros2_type.to_string(),
+                    remote_qos: qos_to_json(&remote_qos).unwrap_or_default(),
+                };
+                tracing::error!(
+                    "Conflict for topic {ros2_name} between the local {kind:?} route and remote bridge {plugin_id} - not routing it: {}",
+                    serde_json::to_string(&conflict).unwrap_or_default()
+                );
+                if conflict_kind == ConflictKind::Type {
+                    *self
+                        .type_mismatches
+                        .entry(ros2_name.to_string())
+                        .or_default() += 1;
+                }
+                self.remove_conflict(kind, plugin_id, ros2_name);
+                self.conflicts
+                    .entry(ros2_name.to_string())
+                    .or_default()
+                    .push(conflict);
+                return false;
+            }
+        }
+        self.is_topic_type_accepted(ros2_name, ros2_type, &format!("remote bridge {plugin_id}"))
+    }
+
+    // Remove the conflict recorded for a topic announced by a remote bridge (e.g. on its retirement)
+    fn remove_conflict(&mut self, kind: RouteKind, plugin_id: &keyexpr, ros2_name: &str) {
+        if let Entry::Occupied(mut entry) = self.conflicts.entry(ros2_name.to_string()) {
+            entry.get_mut().retain(|c| {
+                c.remote_bridge != plugin_id.as_str()
+                    || c.remote_interface != remote_interface(kind)
+            });
+            if entry.get().is_empty() {
+                entry.remove();
+            }
+        }
+    }
+
+    // The ROS2 interface name of the route of `kind` for a key expression announced by a remote bridge
+    // (see route_ros2_name()), cached as it's computed for each announcement and retirement
+    fn remote_ros2_name(&mut self, kind: RouteKind, key_expr: &keyexpr) -> String {
+        self.ros2_names.get_or_compute(
+            &self.context.config,
+            &(kind, key_expr.to_owned()),
+            |config| route_ros2_name(config, kind, key_expr),
+        )
+    }
+
+    // Check that the key expression of a new route of `kind` for `ros2_name` is not already used by the
+    // route of another interface of the same kind (their traffic would be merged).
+    // On collision, log an error, record it (visible in admin space) and return it.
+    fn check_key_expr_free(
+        &mut self,
+        kind: RouteKind,
+        ros2_name: &str,
+        ros2_type: &str,
+    ) -> Result<(), String> {
+        let routed: Vec<&String> = match kind {
+            RouteKind::Publisher => self.routes_publishers.keys().collect(),
+            RouteKind::Subscriber => self.routes_subscribers.keys().collect(),
+            RouteKind::ServiceSrv => self.routes_service_srv.keys().collect(),
+            RouteKind::ServiceCli => self.routes_service_cli.keys().collect(),
+            RouteKind::ActionSrv => self.routes_action_srv.keys().collect(),
+            RouteKind::ActionCli => self.routes_action_cli.keys().collect(),
+        };
+        if routed.iter().any(|n| n.as_str() == ros2_name) {
+            // the route already exists
+            return Ok(());
+        }
+        check_key_expr_collision(&self.context.config, kind, ros2_name, ros2_type, routed).map_err(
+            |e| {
+                tracing::error!("{e}");
+                self.key_expr_collisions
+                    .insert(ros2_name.to_string(), e.clone());
+                e
+            },
+        )
+    }
+
+    /// Re-declare the Zenoh entities and re-assert the LivelinessTokens of all the routes
+    /// (e.g. after a reconnection to the Zenoh infrastructure). It can be called several times.
+    pub async fn restore_routes(&mut self) -> Result<(), String> {
+        let mut errors = Vec::new();
+        for route in self.routes_publishers.values_mut() {
+            route
+                .restore_announcement()
+                .await
+                .unwrap_or_else(|e| errors.push(e));
+        }
+        for route in self.routes_subscribers.values_mut() {
+            route
+                .restore_announcement()
+                .await
+                .unwrap_or_else(|e| errors.push(e));
+        }
+        for route in self.routes_service_srv.values_mut() {
+            route
+                .restore_announcement()
+                .await
+                .unwrap_or_else(|e| errors.push(e));
+        }
+        for route in self.routes_service_cli.values_mut() {
+            route
+                .restore_announcement()
+                .await
+                .unwrap_or_else(|e| errors.push(e));
+        }
+        for route in self.routes_action_srv.values_mut() {
+            route
+                .restore_announcement()
+                .await
+                .unwrap_or_else(|e| errors.push(e));
+        }
+        for route in self.routes_action_cli.values_mut() {
+            route
+                .restore_announcement()
+                .await
+                .unwrap_or_else(|e| errors.push(e));
+        }
+        // in "forward_discovery" mode, re-declare the tokens announcing the local interfaces
+        let forwarded: Vec<(RouteKind, String)> = self.forward_tokens.keys().cloned().collect();
+        for (kind, ros2_name) in forwarded {
+            if let Some(event) = self
+                .forward_discovery
+                .local_events(kind, &ros2_name)
+                .first()
+            {
+                match self.declare_forward_token(kind, &ros2_name, event).await {
+                    Ok(Some(token)) => {
+                        self.forward_tokens.insert((kind, ros2_name), token);
+                    }
+                    Ok(None) => (),
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Failed to restore {} route(s): {}",
+                errors.len(),
+                errors.join(", ")
+            ))
+        }
+    }
+
     pub async fn query_all_historical_publications(&mut self, plugin_id: &keyexpr) {
         for route in self.routes_subscribers.values_mut() {
             route.query_historical_publications(plugin_id).await;
         }
     }
 
+    // Re-create a Publisher route if the QoS merged from its local Writers changed
+    // (i.e. on discovery or undiscovery of a Writer with other QoS), so its DDS Reader,
+    // its publication cache and its announcement don't depend on the discovery order of the Writers
+    async fn update_route_publisher_qos(&mut self, ros2_name: &str) -> Result<(), String> {
+        let Some(qos) = self
+            .routes_publishers
+            .get(ros2_name)
+            .and_then(RoutePublisher::outdated_writers_qos)
+        else {
+            return Ok(());
+        };
+        let Some(route) = self.routes_publishers.remove(ros2_name) else {
+            return Ok(());
+        };
+        tracing::info!("{route}: re-created with the QoS merged from its local Writers: {qos:?}");
+        let ros2_type = route.ros2_type().to_string();
+        let keyless = route.is_keyless();
+        let local_writers_qos = route.local_writers_qos().clone();
+        let remote_routes: Vec<(String, OwnedKeyExpr, Option<Duration>)> = route
+            .remote_routes()
+            .map(|(plugin_id, ke, separation)| (plugin_id.to_string(), ke.to_owned(), separation))
+            .collect();
+        // drop the old route first, as the new one uses the same key expressions
+        drop(route);
+        let route = self
+            .get_or_create_route_publisher(
+                ros2_name.to_string(),
+                ros2_type,
+                keyless,
+                adapt_writer_qos_for_reader(&qos),
+                true,
+            )
+            .await?;
+        for (plugin_id, ke, separation) in &remote_routes {
+            route.add_remote_route(plugin_id, ke, *separation);
+        }
+        route.preset_local_writers_qos(local_writers_qos.clone());
+        for (node, qos) in &local_writers_qos {
+            route.add_local_node(node.clone(), qos).await;
+        }
+        Ok(())
+    }
+
+    // The keyless flag of a route to be created, unless forced via `keyed` as discovered or announced
+    fn route_keyless(&self, kind: RouteKind, ros2_name: &str, discovered: bool) -> bool {
+        let keyless = self.context.config.is_keyless(ros2_name, discovered);
+        if keyless != discovered {
+            tracing::info!(
+                "{kind:?} route for {ros2_name}: forced as {} via 'keyed' configuration",
+                if keyless { "keyless" } else { "keyed" }
+            );
+        }
+        keyless
+    }
+
+    // Warn if a remote bridge announces a topic differently than forced via `keyed`
+    // (its announcement still being routed as forced)
+    fn check_announced_keyless(&self, plugin_id: &str, ros2_name: &str, announced: bool) {
+        let keyless = self.context.config.is_keyless(ros2_name, announced);
+        if keyless != announced {
+            tracing::warn!(
+                "Topic {ros2_name} announced as {} by bridge {plugin_id}, but forced as {} via 'keyed' configuration",
+                if announced { "keyless" } else { "keyed" },
+                if keyless { "keyless" } else { "keyed" }
+            );
+        }
+    }
+
     async fn get_or_create_route_publisher(
         &mut self,
         ros2_name: String,
@@ -564,34 +1964,57 @@ impl<'a> RoutesMgr<'a> {
         reader_qos: Qos,
         admin_space_ref: bool,
     ) -> Result<&mut RoutePublisher<'a>, String> {
-        match self.routes_publishers.entry(ros2_name.clone()) {
-            Entry::Vacant(entry) => {
-                // ROS2 topic name => Zenoh key expr
-                let zenoh_key_expr = ros2_name_to_key_expr(&ros2_name, &self.context.config);
-                // create route
-                let route = RoutePublisher::create(
-                    ros2_name.clone(),
-                    ros2_type,
-                    zenoh_key_expr.clone(),
-                    &None,
-                    keyless,
-                    reader_qos,
-                    self.context.clone(),
-                )
-                .await?;
-                tracing::info!("{route} created");
+        self.check_key_expr_free(RouteKind::Publisher, &ros2_name, &ros2_type)?;
+        if self.routes_publishers.contains_key(&ros2_name) {
+            return Ok(self.routes_publishers.get_mut(&ros2_name).unwrap());
+        }
+        check_dds_topic_name(
+            &self.context.config,
+            RouteKind::Publisher,
+            &ros2_name,
+            &mut self.long_names,
+        )?;
+        // ROS2 topic name => Zenoh key expr
+        let zenoh_key_expr = route_key_expr(&self.context.config, RouteKind::Publisher, &ros2_name);
+        let keyless = self.route_keyless(RouteKind::Publisher, &ros2_name, keyless);
+        // create route
+        let route = RoutePublisher::create(
+            ros2_name,
+            ros2_type,
+            zenoh_key_expr,
+            &None,
+            keyless,
+            reader_qos,
+            self.context.clone(),
+        )
+        .await?;
+        Ok(self.insert_route_publisher(route, admin_space_ref))
+    }
 
-                if admin_space_ref {
-                    // insert reference in admin_space
-                    let admin_ke = *KE_PREFIX_ROUTE_PUBLISHER / &zenoh_key_expr;
-                    self.admin_space
-                        .insert(admin_ke, RouteRef::Publisher(ros2_name));
-                }
+    // Insert a created Publisher route, paused if it was before being removed
+    fn insert_route_publisher(
+        &mut self,
+        route: RoutePublisher<'a>,
+        admin_space_ref: bool,
+    ) -> &mut RoutePublisher<'a> {
+        tracing::info!("{route} created");
+        let ros2_name = route.ros2_name().to_string();
+        if self
+            .paused
+            .contains(&(RouteKind::Publisher, ros2_name.clone()))
+        {
+            route.set_paused(true);
+            tracing::info!("{route} paused");
+        }
 
-                Ok(entry.insert(route))
-            }
-            Entry::Occupied(entry) => Ok(entry.into_mut()),
+        if admin_space_ref {
+            // insert reference in admin_space
+            let admin_ke = *KE_PREFIX_ROUTE_PUBLISHER / route.zenoh_key_expr();
+            self.admin_space
+                .insert(admin_ke, RouteRef::Publisher(ros2_name.clone()));
         }
+
+        self.routes_publishers.entry(ros2_name).or_insert(route)
     }
 
     async fn get_or_create_route_subscriber(
@@ -602,33 +2025,57 @@ impl<'a> RoutesMgr<'a> {
         writer_qos: Qos,
         admin_space_ref: bool,
     ) -> Result<&mut RouteSubscriber<'a>, String> {
-        match self.routes_subscribers.entry(ros2_name.clone()) {
-            Entry::Vacant(entry) => {
-                // ROS2 topic name => Zenoh key expr
-                let zenoh_key_expr = ros2_name_to_key_expr(&ros2_name, &self.context.config);
-                // create route
-                let route = RouteSubscriber::create(
-                    ros2_name.clone(),
-                    ros2_type,
-                    zenoh_key_expr.clone(),
-                    keyless,
-                    writer_qos,
-                    self.context.clone(),
-                )
-                .await?;
-                tracing::info!("{route} created");
+        self.check_key_expr_free(RouteKind::Subscriber, &ros2_name, &ros2_type)?;
+        if self.routes_subscribers.contains_key(&ros2_name) {
+            return Ok(self.routes_subscribers.get_mut(&ros2_name).unwrap());
+        }
+        check_dds_topic_name(
+            &self.context.config,
+            RouteKind::Subscriber,
+            &ros2_name,
+            &mut self.long_names,
+        )?;
+        // ROS2 topic name => Zenoh key expr
+        let zenoh_key_expr =
+            route_key_expr(&self.context.config, RouteKind::Subscriber, &ros2_name);
+        let keyless = self.route_keyless(RouteKind::Subscriber, &ros2_name, keyless);
+        // create route
+        let route = RouteSubscriber::create(
+            ros2_name,
+            ros2_type,
+            zenoh_key_expr,
+            keyless,
+            writer_qos,
+            self.context.clone(),
+        )
+        .await?;
+        Ok(self.insert_route_subscriber(route, admin_space_ref).await)
+    }
 
-                if admin_space_ref {
-                    // insert reference in admin_space
-                    let admin_ke = *KE_PREFIX_ROUTE_SUBSCRIBER / &zenoh_key_expr;
-                    self.admin_space
-                        .insert(admin_ke, RouteRef::Subscriber(ros2_name));
-                }
+    // Insert a created Subscriber route, paused if it was before being removed
+    async fn insert_route_subscriber(
+        &mut self,
+        mut route: RouteSubscriber<'a>,
+        admin_space_ref: bool,
+    ) -> &mut RouteSubscriber<'a> {
+        tracing::info!("{route} created");
+        let ros2_name = route.ros2_name().to_string();
+        if self
+            .paused
+            .contains(&(RouteKind::Subscriber, ros2_name.clone()))
+        {
+            route.set_paused(true).await;
+            tracing::info!("{route} paused");
+        }
 
-                Ok(entry.insert(route))
-            }
-            Entry::Occupied(entry) => Ok(entry.into_mut()),
+        if admin_space_ref {
+            // insert reference in admin_space
+            let admin_ke = *KE_PREFIX_ROUTE_SUBSCRIBER / route.zenoh_key_expr();
+            self.admin_space
+                .insert(admin_ke, RouteRef::Subscriber(ros2_name.clone()));
         }
+
+        self.routes_subscribers.entry(ros2_name).or_insert(route)
     }
 
     async fn get_or_create_route_service_srv(
@@ -637,16 +2084,34 @@ impl<'a> RoutesMgr<'a> {
         ros2_type: String,
         admin_space_ref: bool,
     ) -> Result<&mut RouteServiceSrv<'a>, String> {
+        self.check_key_expr_free(RouteKind::ServiceSrv, &ros2_name, &ros2_type)?;
         match self.routes_service_srv.entry(ros2_name.clone()) {
             Entry::Vacant(entry) => {
+                check_dds_topic_name(
+                    &self.context.config,
+                    RouteKind::ServiceSrv,
+                    &ros2_name,
+                    &mut self.long_names,
+                )?;
                 // ROS2 topic name => Zenoh key expr
-                let zenoh_key_expr = ros2_name_to_key_expr(&ros2_name, &self.context.config);
+                let zenoh_key_expr =
+                    route_key_expr(&self.context.config, RouteKind::ServiceSrv, &ros2_name);
+                // configured queries timeout for services calls (the queries without reply expire after it)
+                let queries_timeout = self.context.config.get_queries_timeout_service(&ros2_name);
                 // create route
+                // configured priority for the replies to long calls
+                let priority = self
+                    .context
+                    .config
+                    .get_pub_priorities(&ros2_name)
+                    .unwrap_or_default();
                 let route = RouteServiceSrv::create(
                     ros2_name.clone(),
                     ros2_type,
                     zenoh_key_expr.clone(),
                     &None,
+                    queries_timeout,
+                    priority,
                     self.context.clone(),
                 )
                 .await?;
@@ -671,10 +2136,18 @@ impl<'a> RoutesMgr<'a> {
         ros2_type: String,
         admin_space_ref: bool,
     ) -> Result<&mut RouteServiceCli<'a>, String> {
+        self.check_key_expr_free(RouteKind::ServiceCli, &ros2_name, &ros2_type)?;
         match self.routes_service_cli.entry(ros2_name.clone()) {
             Entry::Vacant(entry) => {
+                check_dds_topic_name(
+                    &self.context.config,
+                    RouteKind::ServiceCli,
+                    &ros2_name,
+                    &mut self.long_names,
+                )?;
                 // ROS2 topic name => Zenoh key expr : strip '/' prefix
-                let zenoh_key_expr = ros2_name_to_key_expr(&ros2_name, &self.context.config);
+                let zenoh_key_expr =
+                    route_key_expr(&self.context.config, RouteKind::ServiceCli, &ros2_name);
                 // configured queries timeout for services calls
                 let queries_timeout = self.context.config.get_queries_timeout_service(&ros2_name);
                 // create route
@@ -707,10 +2180,18 @@ impl<'a> RoutesMgr<'a> {
         ros2_name: String,
         ros2_type: String,
     ) -> Result<&mut RouteActionSrv<'a>, String> {
+        self.check_key_expr_free(RouteKind::ActionSrv, &ros2_name, &ros2_type)?;
         match self.routes_action_srv.entry(ros2_name.clone()) {
             Entry::Vacant(entry) => {
+                check_dds_topic_name(
+                    &self.context.config,
+                    RouteKind::ActionSrv,
+                    &ros2_name,
+                    &mut self.long_names,
+                )?;
                 // ROS2 topic name => Zenoh key expr : strip '/' prefix
-                let zenoh_key_expr = ros2_name_to_key_expr(&ros2_name, &self.context.config);
+                let zenoh_key_expr =
+                    route_key_expr(&self.context.config, RouteKind::ActionSrv, &ros2_name);
                 // create route
                 let route = RouteActionSrv::create(
                     ros2_name.clone(),
@@ -737,10 +2218,18 @@ impl<'a> RoutesMgr<'a> {
         ros2_name: String,
         ros2_type: String,
     ) -> Result<&mut RouteActionCli<'a>, String> {
+        self.check_key_expr_free(RouteKind::ActionCli, &ros2_name, &ros2_type)?;
         match self.routes_action_cli.entry(ros2_name.clone()) {
             Entry::Vacant(entry) => {
+                check_dds_topic_name(
+                    &self.context.config,
+                    RouteKind::ActionCli,
+                    &ros2_name,
+                    &mut self.long_names,
+                )?;
                 // ROS2 topic name => Zenoh key expr : strip '/' prefix
-                let zenoh_key_expr = ros2_name_to_key_expr(&ros2_name, &self.context.config);
+                let zenoh_key_expr =
+                    route_key_expr(&self.context.config, RouteKind::ActionCli, &ros2_name);
                 // create route
                 let route = RouteActionCli::create(
                     ros2_name.clone(),
@@ -772,6 +2261,10 @@ impl<'a> RoutesMgr<'a> {
             tracing::error!("Received query for admin space: '{}' - but it's not prefixed by admin_keyexpr_prefix='{}'", selector, &self.admin_prefix);
             return;
         }
+        // with "detail=qos", the QoS of the Publisher and Subscriber routes are included
+        let qos_detail = is_qos_detail_requested(selector.parameters());
+        // with "events", the latest events of the routes are included
+        let events = is_events_requested(selector.parameters());
 
         // For all sub-key expression
         for sub_ke in sub_kes {
@@ -779,20 +2272,129 @@ impl<'a> RoutesMgr<'a> {
                 // iterate over all admin space to find matching keys and reply for each
                 for (ke, route_ref) in self.admin_space.iter() {
                     if sub_ke.intersects(ke) {
-                        self.send_admin_reply(query, ke, route_ref).await;
+                        self.send_admin_reply(query, ke, route_ref, qos_detail, events)
+                            .await;
                     }
                 }
             } else {
                 // sub_ke correspond to 1 key - just get it and reply
                 if let Some(route_ref) = self.admin_space.get(sub_ke) {
-                    self.send_admin_reply(query, sub_ke, route_ref).await;
+                    self.send_admin_reply(query, sub_ke, route_ref, qos_detail, events)
+                        .await;
                 }
             }
         }
     }
 
-    async fn send_admin_reply(&self, query: &Query, key_expr: &keyexpr, route_ref: &RouteRef) {
-        match self.get_entity_json_value(route_ref) {
+    /// Operate the routes via a PUT on "<admin_prefix>/<route>/<operation>", where <route> is the admin key
+    /// of 1 route or a key expression matching several ones (e.g. "route/topic/pub/camera/**"):
+    ///   - "pause" or "resume" the Publisher and Subscriber routes
+    ///   - "reset_stats" or "flush_pending" (the requests waiting for a reply) of the Service routes
+    pub async fn treat_admin_put(&mut self, key_expr: &keyexpr) {
+        let sub_kes = key_expr.strip_prefix(&self.admin_prefix);
+        let Some((routes_ke, operation)) = sub_kes.first().and_then(|ke| parse_admin_operation(ke))
+        else {
+            tracing::warn!("Received unsupported PUT on admin space: '{key_expr}' - expecting '<route>/pause', '<route>/resume', '<route>/reset_stats' or '<route>/flush_pending'");
+            return;
+        };
+        let pause = match operation {
+            AdminOperation::Pause => true,
+            AdminOperation::Resume => false,
+            AdminOperation::ResetStats | AdminOperation::FlushPending => {
+                self.treat_service_operation(&routes_ke, operation);
+                return;
+            }
+        };
+        let routes: Vec<(RouteKind, String)> = self
+            .admin_space
+            .iter()
+            .filter(|(ke, _)| routes_ke.intersects(ke))
+            .filter_map(|(_, route_ref)| match route_ref {
+                RouteRef::Publisher(name) => Some((RouteKind::Publisher, name.clone())),
+                RouteRef::Subscriber(name) => Some((RouteKind::Subscriber, name.clone())),
+                _ => None,
+            })
+            .collect();
+        if routes.is_empty() {
+            tracing::warn!("Received PUT on admin space: '{key_expr}' - but no Publisher or Subscriber route matches '{routes_ke}'");
+        }
+        let operation = if pause { "paused" } else { "resumed" };
+        for (kind, ros2_name) in routes {
+            match kind {
+                RouteKind::Publisher => {
+                    if let Some(route) = self.routes_publishers.get(&ros2_name) {
+                        route.set_paused(pause);
+                        tracing::info!("{route} {operation}");
+                    }
+                }
+                RouteKind::Subscriber => {
+                    if let Some(route) = self.routes_subscribers.get_mut(&ros2_name) {
+                        route.set_paused(pause).await;
+                        tracing::info!("{route} {operation}");
+                    }
+                }
+                _ => (),
+            }
+            if pause {
+                self.paused.insert((kind, ros2_name));
+            } else {
+                self.paused.remove(&(kind, ros2_name));
+            }
+        }
+    }
+
+    // Reset the statistics or flush the pending requests of the Service routes matching `routes_ke`
+    fn treat_service_operation(&self, routes_ke: &keyexpr, operation: AdminOperation) {
+        // a route may have several admin keys (e.g. also under "route/parameters")
+        let routes: HashSet<(RouteKind, &String)> = self
+            .admin_space
+            .iter()
+            .filter(|(ke, _)| routes_ke.intersects(ke))
+            .filter_map(|(_, route_ref)| match route_ref {
+                RouteRef::ServiceSrv(name) => Some((RouteKind::ServiceSrv, name)),
+                RouteRef::ServiceCli(name) => Some((RouteKind::ServiceCli, name)),
+                _ => None,
+            })
+            .collect();
+        if routes.is_empty() {
+            tracing::warn!("Received {operation:?} operation on admin space - but no Service route matches '{routes_ke}'");
+        }
+        for (kind, ros2_name) in routes {
+            match kind {
+                RouteKind::ServiceSrv => {
+                    if let Some(route) = self.routes_service_srv.get(ros2_name) {
+                        if operation == AdminOperation::ResetStats {
+                            route.reset_stats();
+                            tracing::info!("{route} statistics reset");
+                        } else {
+                            route.flush_pending();
+                        }
+                    }
+                }
+                RouteKind::ServiceCli => {
+                    if let Some(route) = self.routes_service_cli.get(ros2_name) {
+                        if operation == AdminOperation::ResetStats {
+                            route.reset_stats();
+                            tracing::info!("{route} statistics reset");
+                        } else {
+                            route.flush_pending();
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    async fn send_admin_reply(
+        &self,
+        query: &Query,
+        key_expr: &keyexpr,
+        route_ref: &RouteRef,
+        qos_detail: bool,
+        events: bool,
+    ) {
+        match self.get_entity_json_value(route_ref, qos_detail, events) {
             Ok(Some(v)) => {
                 let admin_keyexpr = &self.admin_prefix / key_expr;
                 if let Err(e) = query
@@ -815,38 +2417,1520 @@ impl<'a> RoutesMgr<'a> {
     fn get_entity_json_value(
         &self,
         route_ref: &RouteRef,
+        qos_detail: bool,
+        events: bool,
     ) -> Result<Option<serde_json::Value>, serde_json::Error> {
         match route_ref {
             RouteRef::Publisher(ke) => self
                 .routes_publishers
                 .get(ke)
-                .map(serde_json::to_value)
+                .map(|route| {
+                    with_events(
+                        with_qos_detail(
+                            with_stats(serde_json::to_value(route)?, route.stats())?,
+                            qos_detail.then(|| route.qos_details()).transpose()?,
+                        )?,
+                        events.then(|| route.events()),
+                    )
+                })
                 .transpose(),
             RouteRef::Subscriber(ke) => self
                 .routes_subscribers
                 .get(ke)
-                .map(serde_json::to_value)
+                .map(|route| {
+                    with_events(
+                        with_qos_detail(
+                            with_stats(serde_json::to_value(route)?, route.stats())?,
+                            qos_detail.then(|| route.qos_details()).transpose()?,
+                        )?,
+                        events.then(|| route.events()),
+                    )
+                })
                 .transpose(),
             RouteRef::ServiceSrv(ke) => self
                 .routes_service_srv
                 .get(ke)
-                .map(serde_json::to_value)
+                .map(|route| {
+                    with_events(
+                        with_stats(serde_json::to_value(route)?, route.stats())?,
+                        events.then(|| route.events()),
+                    )
+                })
                 .transpose(),
             RouteRef::ServiceCli(ke) => self
                 .routes_service_cli
                 .get(ke)
-                .map(serde_json::to_value)
+                .map(|route| {
+                    with_events(
+                        with_stats(serde_json::to_value(route)?, route.stats())?,
+                        events.then(|| route.events()),
+                    )
+                })
                 .transpose(),
             RouteRef::ActionSrv(ke) => self
                 .routes_action_srv
                 .get(ke)
-                .map(serde_json::to_value)
+                .map(|route| {
+                    with_events(
+                        with_stats(serde_json::to_value(route)?, route.stats())?,
+                        events.then(|| route.events()),
+                    )
+                })
                 .transpose(),
             RouteRef::ActionCli(ke) => self
                 .routes_action_cli
                 .get(ke)
-                .map(serde_json::to_value)
+                .map(|route| {
+                    with_events(
+                        with_stats(serde_json::to_value(route)?, route.stats())?,
+                        events.then(|| route.events()),
+                    )
+                })
                 .transpose(),
+            RouteRef::TypeMismatches => serde_json::to_value(&self.type_mismatches).map(Some),
+            RouteRef::LongNames => serde_json::to_value(&self.long_names).map(Some),
+            RouteRef::KeyExprCollisions => {
+                serde_json::to_value(&self.key_expr_collisions).map(Some)
+            }
+            RouteRef::Conflicts => serde_json::to_value(&self.conflicts).map(Some),
+            RouteRef::Stats => serde_json::to_value(self.aggregated_stats()).map(Some),
+        }
+    }
+
+    // The statistics of all the routes, aggregated per kind and in total
+    fn aggregated_stats(&self) -> AggregatedStats {
+        fn merge(stats: impl Iterator<Item = RouteStatsSnapshot>) -> RouteStatsSnapshot {
+            stats.fold(RouteStatsSnapshot::default(), RouteStatsSnapshot::merge)
+        }
+        let publishers = merge(self.routes_publishers.values().map(RoutePublisher::stats));
+        let subscribers = merge(self.routes_subscribers.values().map(RouteSubscriber::stats));
+        let service_srv = merge(self.routes_service_srv.values().map(RouteServiceSrv::stats));
+        let service_cli = merge(self.routes_service_cli.values().map(RouteServiceCli::stats));
+        let action_srv = merge(self.routes_action_srv.values().map(RouteActionSrv::stats));
+        let action_cli = merge(self.routes_action_cli.values().map(RouteActionCli::stats));
+        AggregatedStats {
+            total: merge(
+                [
+                    publishers,
+                    subscribers,
+                    service_srv,
+                    service_cli,
+                    action_srv,
+                    action_cli,
+                ]
+                .into_iter(),
+            ),
+            publishers,
+            subscribers,
+            service_srv,
+            service_cli,
+            action_srv,
+            action_cli,
+            busiest_routes: busiest_routes(
+                self.admin_space
+                    .iter()
+                    .filter_map(|(ke, route_ref)| Some((ke, self.route_stats(route_ref)?))),
+            ),
+        }
+    }
+
+    // The statistics of a route referenced in admin space (None for the other admin space entries)
+    fn route_stats(&self, route_ref: &RouteRef) -> Option<RouteStatsSnapshot> {
+        match route_ref {
+            RouteRef::Publisher(ke) => self.routes_publishers.get(ke).map(RoutePublisher::stats),
+            RouteRef::Subscriber(ke) => self.routes_subscribers.get(ke).map(RouteSubscriber::stats),
+            RouteRef::ServiceSrv(ke) => self.routes_service_srv.get(ke).map(RouteServiceSrv::stats),
+            RouteRef::ServiceCli(ke) => self.routes_service_cli.get(ke).map(RouteServiceCli::stats),
+            RouteRef::ActionSrv(ke) => self.routes_action_srv.get(ke).map(RouteActionSrv::stats),
+            RouteRef::ActionCli(ke) => self.routes_action_cli.get(ke).map(RouteActionCli::stats),
+            _ => None,
+        }
+    }
+}
+
+// The statistics of all the routes reported in admin space under "stats"
+#[derive(Serialize)]
+struct AggregatedStats {
+    total: RouteStatsSnapshot,
+    publishers: RouteStatsSnapshot,
+    subscribers: RouteStatsSnapshot,
+    service_srv: RouteStatsSnapshot,
+    service_cli: RouteStatsSnapshot,
+    action_srv: RouteStatsSnapshot,
+    action_cli: RouteStatsSnapshot,
+    busiest_routes: Vec<BusiestRoute>,
+}
+
+// A route with the highest rates of routed messages, identified by its admin space key expression
+#[derive(Debug, PartialEq, Serialize)]
+struct BusiestRoute {
+    route: String,
+    msgs_per_sec: f64,
+    kbytes_per_sec: f64,
+}
+
+// The BUSIEST_ROUTES_COUNT routes with the highest rates of messages, the busiest first
+fn busiest_routes<'a>(
+    routes: impl Iterator<Item = (&'a OwnedKeyExpr, RouteStatsSnapshot)>,
+) -> Vec<BusiestRoute> {
+    let mut busiest: Vec<BusiestRoute> = routes
+        .filter(|(_, stats)| stats.msgs_per_sec >= BUSIEST_ROUTES_MIN_RATE)
+        .map(|(ke, stats)| BusiestRoute {
+            route: ke.to_string(),
+            msgs_per_sec: stats.msgs_per_sec,
+            kbytes_per_sec: stats.kbytes_per_sec,
+        })
+        .collect();
+    busiest.sort_by(|a, b| {
+        b.msgs_per_sec
+            .total_cmp(&a.msgs_per_sec)
+            .then_with(|| b.kbytes_per_sec.total_cmp(&a.kbytes_per_sec))
+    });
+    busiest.truncate(BUSIEST_ROUTES_COUNT);
+    busiest
+}
+
+// An operation on routes via a PUT on the admin space
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AdminOperation {
+    Pause,
+    Resume,
+    ResetStats,
+    FlushPending,
+}
+
+// The routes admin key expression and the operation of a PUT on "<route>/<operation>"
+// (relative to the admin prefix)
+fn parse_admin_operation(sub_ke: &keyexpr) -> Option<(OwnedKeyExpr, AdminOperation)> {
+    let (routes, operation) = sub_ke.as_str().rsplit_once('/')?;
+    let operation = match operation {
+        "pause" => AdminOperation::Pause,
+        "resume" => AdminOperation::Resume,
+        "reset_stats" => AdminOperation::ResetStats,
+        "flush_pending" => AdminOperation::FlushPending,
+        _ => return None,
+    };
+    let routes = OwnedKeyExpr::try_from(routes).ok()?;
+    routes.starts_with("route/").then_some((routes, operation))
+}
+
+// Return true if the "detail" parameter of an admin space query's selector includes "qos"
+// (e.g. "detail=qos" or "detail=qos,stats")
+fn is_qos_detail_requested(parameters: &str) -> bool {
+    parameters
+        .split([';', '&'])
+        .filter_map(|p| p.split_once('='))
+        .any(|(k, v)| k == "detail" && v.split(',').any(|d| d == "qos"))
+}
+
+// Add the QoS details of a route (if requested) to its JSON value in admin space
+fn with_qos_detail(
+    mut value: serde_json::Value,
+    qos_detail: Option<serde_json::Value>,
+) -> Result<serde_json::Value, serde_json::Error> {
+    if let (serde_json::Value::Object(obj), Some(qos)) = (&mut value, qos_detail) {
+        obj.insert("qos".into(), qos);
+    }
+    Ok(value)
+}
+
+// Return true if an admin space query's selector has the "events" parameter (e.g. "?events")
+fn is_events_requested(parameters: &str) -> bool {
+    parameters
+        .split([';', '&'])
+        .any(|p| p.split_once('=').map_or(p, |(k, _)| k) == "events")
+}
+
+// Add the latest events of a route (if requested) to its JSON value in admin space
+fn with_events(
+    mut value: serde_json::Value,
+    events: Option<Vec<RouteEvent>>,
+) -> Result<serde_json::Value, serde_json::Error> {
+    if let (serde_json::Value::Object(obj), Some(events)) = (&mut value, events) {
+        obj.insert("events".into(), serde_json::to_value(events)?);
+    }
+    Ok(value)
+}
+
+// Add the statistics of a route to its JSON value in admin space
+fn with_stats(
+    mut value: serde_json::Value,
+    stats: RouteStatsSnapshot,
+) -> Result<serde_json::Value, serde_json::Error> {
+    if let serde_json::Value::Object(obj) = &mut value {
+        obj.insert("stats".into(), serde_json::to_value(stats)?);
+    }
+    Ok(value)
+}
+
+// The route kind, ROS name, Node name of a local discovery event, and if it's a discovery or an undiscovery
+pub(crate) fn discovery_event_key(event: &ROS2DiscoveryEvent) -> (RouteKind, &str, &str, bool) {
+    use ROS2DiscoveryEvent::*;
+    match event {
+        DiscoveredMsgPub(node, iface) => (RouteKind::Publisher, &iface.name, node, true),
+        UndiscoveredMsgPub(node, iface) => (RouteKind::Publisher, &iface.name, node, false),
+        DiscoveredMsgSub(node, iface) => (RouteKind::Subscriber, &iface.name, node, true),
+        UndiscoveredMsgSub(node, iface) => (RouteKind::Subscriber, &iface.name, node, false),
+        DiscoveredServiceSrv(node, iface) => (RouteKind::ServiceSrv, &iface.name, node, true),
+        UndiscoveredServiceSrv(node, iface) => (RouteKind::ServiceSrv, &iface.name, node, false),
+        DiscoveredServiceCli(node, iface) => (RouteKind::ServiceCli, &iface.name, node, true),
+        UndiscoveredServiceCli(node, iface) => (RouteKind::ServiceCli, &iface.name, node, false),
+        DiscoveredActionSrv(node, iface) => (RouteKind::ActionSrv, &iface.name, node, true),
+        UndiscoveredActionSrv(node, iface) => (RouteKind::ActionSrv, &iface.name, node, false),
+        DiscoveredActionCli(node, iface) => (RouteKind::ActionCli, &iface.name, node, true),
+        UndiscoveredActionCli(node, iface) => (RouteKind::ActionCli, &iface.name, node, false),
+    }
+}
+
+// Check if a local discovery event is for a parameter Service of a Node configured in "bridge_parameters":
+// such a route is created at once (i.e. not deferred by "startup_grace_period" nor "discovery_batch_window")
+pub(crate) fn is_parameter_service_event(config: &Config, event: &ROS2DiscoveryEvent) -> bool {
+    let (kind, ros2_name, ..) = discovery_event_key(event);
+    matches!(kind, RouteKind::ServiceSrv | RouteKind::ServiceCli)
+        && config.is_parameters_interface(ros2_name)
+}
+
+// The namespace and the scope of the key expressions of the routes of `kind`, depending on their direction:
+//   - from DDS to Zenoh: `namespace_to_zenoh` (or `namespace`) and `scope_out`
+//   - from Zenoh to DDS: `namespace_from_zenoh` (or `namespace`) and `scope_in`
+fn zenoh_namespace_and_scope(config: &Config, kind: RouteKind) -> (&str, Option<&keyexpr>) {
+    match kind {
+        RouteKind::Publisher | RouteKind::ServiceSrv | RouteKind::ActionSrv => {
+            (config.get_namespace_to_zenoh(), config.scope_out.as_deref())
+        }
+        RouteKind::Subscriber | RouteKind::ServiceCli | RouteKind::ActionCli => (
+            config.get_namespace_from_zenoh(),
+            config.scope_in.as_deref(),
+        ),
+    }
+}
+
+#[inline]
+fn zenoh_scope(config: &Config, kind: RouteKind) -> Option<&keyexpr> {
+    zenoh_namespace_and_scope(config, kind).1
+}
+
+// Check that the longest DDS topic name used by a route of `kind` for `ros2_name` doesn't exceed
+// the DDS maximum length, or is hashed as per "long_name_policy". The overflows are recorded in `long_names`.
+fn check_dds_topic_name(
+    config: &Config,
+    kind: RouteKind,
+    ros2_name: &str,
+    long_names: &mut HashMap<String, String>,
+) -> Result<(), String> {
+    // a raw DDS topic is used verbatim
+    if ros2_name_to_raw_dds_topic(ros2_name).is_some() {
+        return Ok(());
+    }
+    let (prefix, suffix) = match kind {
+        RouteKind::Publisher | RouteKind::Subscriber => ("rt", ""),
+        RouteKind::ServiceSrv | RouteKind::ServiceCli => ("rq", "Request"),
+        RouteKind::ActionSrv | RouteKind::ActionCli => ("rq", "/_action/cancel_goalRequest"),
+    };
+    match dds_topic_name(prefix, ros2_name, suffix, config.long_name_policy) {
+        Ok(dds_topic) if dds_topic.len() < prefix.len() + ros2_name.len() + suffix.len() => {
+            tracing::warn!("DDS topic name for {ros2_name} is too long, hashed as '{dds_topic}'");
+            long_names.insert(ros2_name.into(), dds_topic);
+            Ok(())
+        }
+        Ok(_) => Ok(()),
+        Err(e) => {
+            long_names.insert(ros2_name.into(), e.to_string());
+            Err(e.into())
+        }
+    }
+}
+
+// The key expression of the route of `kind` for a ROS2 interface
+pub(crate) fn route_key_expr(config: &Config, kind: RouteKind, ros2_name: &str) -> OwnedKeyExpr {
+    let (namespace, scope) = zenoh_namespace_and_scope(config, kind);
+    // a raw DDS topic is routed as "raw/<topic>", without namespace nor remapping
+    if let Some(dds_topic) = ros2_name_to_raw_dds_topic(ros2_name) {
+        return scope_key_expr(
+            scope,
+            raw_dds_topic_to_key_expr(dds_topic, &config.segment_separators),
+        );
+    }
+    scope_key_expr(
+        scope,
+        ros2_name_to_key_expr(
+            ros2_name,
+            namespace,
+            &config.namespace_rules,
+            &config.remappings,
+        ),
+    )
+}
+
+// The ROS2 interface name of the route of `kind` for a key expression announced by a remote bridge
+// (assumed to be in the scope of the route's direction)
+pub(crate) fn route_ros2_name(config: &Config, kind: RouteKind, key_expr: &keyexpr) -> String {
+    let (namespace, scope) = zenoh_namespace_and_scope(config, kind);
+    let key_expr = unscope_key_expr(scope, key_expr).unwrap_or(key_expr);
+    // "raw/<topic>" is a raw DDS topic only if configured as such in "raw_dds_topics".
+    // With "segment_separators", the chunks are joined with the 1st separator making a configured topic.
+    if matches!(kind, RouteKind::Publisher | RouteKind::Subscriber) {
+        let separators = &config.segment_separators;
+        let candidates = separators
+            .iter()
+            .copied()
+            .map(Some)
+            .chain(separators.is_empty().then_some(None));
+        for separator in candidates {
+            if let Some(dds_topic) = key_expr_to_raw_dds_topic(key_expr, separator) {
+                if config.is_raw_dds_topic(&dds_topic) {
+                    return raw_dds_topic_to_ros2_name(&dds_topic);
+                }
+            }
+        }
+    }
+    key_expr_to_ros2_name(
+        key_expr,
+        namespace,
+        &config.namespace_rules,
+        &config.remappings,
+    )
+}
+
+// The kind of the local route serving a remote announcement, its plugin_id and key expression,
+// and if it's an announcement or a retirement
+fn announcement_event_key(event: &ROS2AnnouncementEvent) -> (RouteKind, &keyexpr, &keyexpr, bool) {
+    use ROS2AnnouncementEvent::*;
+    match event {
+        AnnouncedMsgPub {
+            plugin_id,
+            zenoh_key_expr,
+            ..
+        } => (RouteKind::Subscriber, plugin_id, zenoh_key_expr, true),
+        RetiredMsgPub {
+            plugin_id,
+            zenoh_key_expr,
+        } => (RouteKind::Subscriber, plugin_id, zenoh_key_expr, false),
+        AnnouncedMsgSub {
+            plugin_id,
+            zenoh_key_expr,
+            ..
+        } => (RouteKind::Publisher, plugin_id, zenoh_key_expr, true),
+        RetiredMsgSub {
+            plugin_id,
+            zenoh_key_expr,
+        } => (RouteKind::Publisher, plugin_id, zenoh_key_expr, false),
+        AnnouncedServiceSrv {
+            plugin_id,
+            zenoh_key_expr,
+            ..
+        } => (RouteKind::ServiceCli, plugin_id, zenoh_key_expr, true),
+        RetiredServiceSrv {
+            plugin_id,
+            zenoh_key_expr,
+        } => (RouteKind::ServiceCli, plugin_id, zenoh_key_expr, false),
+        AnnouncedServiceCli {
+            plugin_id,
+            zenoh_key_expr,
+            ..
+        } => (RouteKind::ServiceSrv, plugin_id, zenoh_key_expr, true),
+        RetiredServiceCli {
+            plugin_id,
+            zenoh_key_expr,
+        } => (RouteKind::ServiceSrv, plugin_id, zenoh_key_expr, false),
+        AnnouncedActionSrv {
+            plugin_id,
+            zenoh_key_expr,
+            ..
+        } => (RouteKind::ActionCli, plugin_id, zenoh_key_expr, true),
+        RetiredActionSrv {
+            plugin_id,
+            zenoh_key_expr,
+        } => (RouteKind::ActionCli, plugin_id, zenoh_key_expr, false),
+        AnnouncedActionCli {
+            plugin_id,
+            zenoh_key_expr,
+            ..
+        } => (RouteKind::ActionSrv, plugin_id, zenoh_key_expr, true),
+        RetiredActionCli {
+            plugin_id,
+            zenoh_key_expr,
+        } => (RouteKind::ActionSrv, plugin_id, zenoh_key_expr, false),
+    }
+}
+
+// Check that the key expression of a route of `kind` for `ros2_name` is not the same than the one of
+// the `routed` interfaces of the same kind (e.g. after a remapping to the name of an existing interface)
+fn check_key_expr_collision<'n>(
+    config: &Config,
+    kind: RouteKind,
+    ros2_name: &str,
+    ros2_type: &str,
+    routed: impl IntoIterator<Item = &'n String>,
+) -> Result<(), String> {
+    let key_expr = route_key_expr(config, kind, ros2_name);
+    match routed
+        .into_iter()
+        .find(|n| n.as_str() != ros2_name && route_key_expr(config, kind, n) == key_expr)
+    {
+        Some(other) => Err(format!(
+            "Key expression collision for {kind:?} {ros2_name} ({ros2_type}): '{key_expr}' is already used by the route for {other} - not routing it (check the 'remappings' and 'namespace_rules' configuration)"
+        )),
+        None => Ok(()),
+    }
+}
+
+// The remote interface announced for a local route of `kind`
+fn remote_interface(kind: RouteKind) -> &'static str {
+    match kind {
+        RouteKind::Subscriber => "Publisher",
+        _ => "Subscriber",
+    }
+}
+
+// Check a topic announced by a remote bridge against the same topic discovered locally, returning the kind of
+// conflict and its reasons, if any:
+//  - a type conflict if their types differ
+//  - a QoS conflict if the Writer's QoS (remote for a Subscriber route, local for a Publisher route) is incompatible
+//    with the Reader's QoS, meaning they wouldn't match if in the same DDS domain. Not if `auto_adapt_qos` is
+//    configured, the bridge adapting the QoS in such case. Note that the TRANSIENT_LOCAL Durability offered
+//    for a topic configured in `force_transient_local` is taken into account.
+fn check_topic_conflict(
+    config: &Config,
+    kind: RouteKind,
+    ros2_name: &str,
+    (local_type, local_qos): (&str, &Qos),
+    (remote_type, remote_qos): (&str, &Qos),
+) -> Option<(ConflictKind, Vec<String>)> {
+    if local_type != remote_type {
+        return Some((
+            ConflictKind::Type,
+            vec![format!(
+                "Type: {local_type} discovered locally, but {remote_type} announced by the remote bridge"
+            )],
+        ));
+    }
+    if config.auto_adapt_qos {
+        return None;
+    }
+    let (offered, requested) = match kind {
+        RouteKind::Subscriber => (remote_qos, local_qos),
+        _ => (local_qos, remote_qos),
+    };
+    let incompatibilities = if config.is_transient_local_forced(ros2_name) {
+        qos_incompatibilities(&force_transient_local(offered), requested)
+    } else {
+        qos_incompatibilities(offered, requested)
+    };
+    if incompatibilities.is_empty() {
+        None
+    } else {
+        Some((ConflictKind::Qos, incompatibilities))
+    }
+}
+
+// Check a topic's type against the expected one (configured in `topic_types`),
+// and against the one of the already existing route
+fn check_topic_type(
+    ros2_name: &str,
+    ros2_type: &str,
+    expected_type: Option<&str>,
+    routed_type: Option<&str>,
+) -> Result<(), String> {
+    if let Some(expected) = expected_type {
+        if ros2_type != expected {
+            return Err(format!(
+                "Type mismatch for topic {ros2_name}: got {ros2_type} while {expected} is expected (as configured in 'topic_types')"
+            ));
+        }
+    }
+    if let Some(routed) = routed_type {
+        if ros2_type != routed {
+            return Err(format!(
+                "Type conflict for topic {ros2_name}: got {ros2_type} while already routed with {routed}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Run futures concurrently, at most `max_concurrent` at a time, returning their outputs in order
+async fn run_concurrently<F: Future>(futures: Vec<F>, max_concurrent: usize) -> Vec<F::Output> {
+    stream::iter(futures)
+        .buffered(max_concurrent.max(1))
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        busiest_routes, check_key_expr_collision, check_topic_conflict, check_topic_type,
+        is_events_requested, is_qos_detail_requested, parse_admin_operation, route_ros2_name,
+        run_concurrently, with_stats, AdminOperation, Config, ConflictKind, Ros2NamesCache,
+        RouteKind,
+    };
+    use super::{DdsEntity, DiscoveredEntities, MsgPub, RosDiscoveryInfoMgr, RoutesMgr};
+    use crate::dds_types::DDSInstanceState;
+    use crate::dds_utils::{dds_write, dds_write_with_instance_state, get_guid};
+    use crate::events::ROS2DiscoveryEvent;
+    use crate::gid::Gid;
+    use crate::liveliness_mgt::{ke_liveliness_all, split_format_version};
+    use crate::node_info::{MsgSub, ServiceCli};
+    use crate::parse_announcement_event;
+    use crate::ros2_utils::{instance_keyhash_from_attachment, instance_state_from_attachment};
+    use crate::route_stats::RouteStats;
+    use crate::test_utils::{
+        cdr_string, connected_sessions, dds_participant, node_dds_reader, node_dds_writer,
+        node_qos, session_pair, take_node_messages,
+    };
+    use flume::Receiver;
+    use std::sync::{Arc, RwLock};
+    use std::time::{Duration, Instant};
+    use zenoh::prelude::r#async::AsyncResolve;
+    use zenoh::prelude::{keyexpr, OwnedKeyExpr, SampleKind};
+    use zenoh::queryable::Query;
+    use zenoh::sample::Sample;
+    use zenoh::Session;
+    use zenoh_core::zwrite;
+
+    #[test]
+    fn test_with_stats() {
+        let stats = RouteStats::default();
+        for _ in 0..5 {
+            stats.on_received(8);
+            stats.on_routed(8);
+        }
+        stats.on_received(8);
+        stats.on_dropped();
+        let value = with_stats(
+            serde_json::json!({"ros2_name": "/chatter"}),
+            stats.snapshot(),
+        )
+        .unwrap();
+        assert_eq!(value["ros2_name"], "/chatter");
+        assert_eq!(value["stats"]["messages_in"], 6);
+        assert_eq!(value["stats"]["bytes_in"], 48);
+        assert_eq!(value["stats"]["messages_out"], 5);
+        assert_eq!(value["stats"]["bytes_out"], 40);
+        assert_eq!(value["stats"]["dropped"], 1);
+        assert_eq!(value["stats"]["errors"], 0);
+    }
+
+    #[test]
+    fn test_busiest_routes() {
+        use super::{RouteStatsSnapshot, BUSIEST_ROUTES_COUNT};
+
+        let routes: Vec<(OwnedKeyExpr, RouteStatsSnapshot)> = (0..15)
+            .map(|i| {
+                (
+                    OwnedKeyExpr::try_from(format!("route/topic/pub/t{i}")).unwrap(),
+                    RouteStatsSnapshot {
+                        // t0 is idle (its rate decayed), t14 is the busiest
+                        msgs_per_sec: if i == 0 { 1e-9 } else { i as f64 },
+                        kbytes_per_sec: i as f64 * 0.5,
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+        let busiest = busiest_routes(routes.iter().map(|(ke, s)| (ke, *s)));
+        assert_eq!(busiest.len(), BUSIEST_ROUTES_COUNT);
+        assert_eq!(busiest[0].route, "route/topic/pub/t14");
+        assert_eq!(busiest[0].kbytes_per_sec, 7.0);
+        assert_eq!(busiest[9].route, "route/topic/pub/t5");
+        assert!(busiest
+            .windows(2)
+            .all(|w| w[0].msgs_per_sec >= w[1].msgs_per_sec));
+
+        // only the routes with traffic are reported
+        let busiest = busiest_routes(routes[..3].iter().map(|(ke, s)| (ke, *s)));
+        let names: Vec<&str> = busiest.iter().map(|r| r.route.as_str()).collect();
+        assert_eq!(names, vec!["route/topic/pub/t2", "route/topic/pub/t1"]);
+        let json = serde_json::to_value(&busiest[0]).unwrap();
+        assert_eq!(json["msgs_per_sec"], 2.0);
+    }
+
+    #[test]
+    fn test_qos_detail_requested() {
+        assert!(!is_qos_detail_requested(""));
+        assert!(!is_qos_detail_requested("_time=[now(-1h)..]"));
+        assert!(!is_qos_detail_requested("detail=stats"));
+        assert!(!is_qos_detail_requested("qos"));
+        assert!(is_qos_detail_requested("detail=qos"));
+        assert!(is_qos_detail_requested("detail=stats,qos"));
+        assert!(is_qos_detail_requested("_time=[now(-1h)..];detail=qos"));
+        assert!(is_qos_detail_requested("a=1&detail=qos"));
+    }
+
+    #[test]
+    fn test_events_requested() {
+        assert!(!is_events_requested(""));
+        assert!(!is_events_requested("detail=events"));
+        assert!(!is_events_requested("eventsx"));
+        assert!(is_events_requested("events"));
+        assert!(is_events_requested("events="));
+        assert!(is_events_requested("detail=qos;events"));
+        assert!(is_events_requested("a=1&events"));
+    }
+
+    #[test]
+    fn test_parse_admin_operation() {
+        let parse = |ke: &str| parse_admin_operation(keyexpr::new(ke).unwrap());
+        assert_eq!(
+            parse("route/topic/pub/camera/**/pause"),
+            Some((
+                OwnedKeyExpr::try_from("route/topic/pub/camera/**").unwrap(),
+                AdminOperation::Pause
+            ))
+        );
+        assert_eq!(
+            parse("route/topic/sub/cmd_vel/resume"),
+            Some((
+                OwnedKeyExpr::try_from("route/topic/sub/cmd_vel").unwrap(),
+                AdminOperation::Resume
+            ))
+        );
+        // a topic named "pause" requires the operation suffix
+        assert_eq!(
+            parse("route/topic/pub/robot/pause/pause"),
+            Some((
+                OwnedKeyExpr::try_from("route/topic/pub/robot/pause").unwrap(),
+                AdminOperation::Pause
+            ))
+        );
+        assert_eq!(
+            parse("route/service/cli/get_state/reset_stats"),
+            Some((
+                OwnedKeyExpr::try_from("route/service/cli/get_state").unwrap(),
+                AdminOperation::ResetStats
+            ))
+        );
+        assert_eq!(
+            parse("route/service/srv/**/flush_pending"),
+            Some((
+                OwnedKeyExpr::try_from("route/service/srv/**").unwrap(),
+                AdminOperation::FlushPending
+            ))
+        );
+        assert_eq!(parse("route/topic/pub/camera/stop"), None);
+        assert_eq!(parse("config/pause"), None);
+        assert_eq!(parse("pause"), None);
+    }
+
+    #[test]
+    fn test_check_topic_type_at_discovery() {
+        const TWIST: &str = "geometry_msgs/msg/Twist";
+        // not configured: any type accepted
+        assert!(check_topic_type("/cmd_vel", TWIST, None, None).is_ok());
+        // configured
+        assert!(check_topic_type("/cmd_vel", TWIST, Some(TWIST), None).is_ok());
+        let err =
+            check_topic_type("/cmd_vel", "std_msgs/msg/String", Some(TWIST), None).unwrap_err();
+        assert!(err.contains("/cmd_vel"));
+        assert!(err.contains("std_msgs/msg/String"));
+        assert!(err.contains(TWIST));
+    }
+
+    #[test]
+    fn test_check_topic_type_conflict_with_route() {
+        const TWIST: &str = "geometry_msgs/msg/Twist";
+        const TWIST_STAMPED: &str = "geometry_msgs/msg/TwistStamped";
+        // a new Writer with the same type than the existing route
+        assert!(check_topic_type("/cmd_vel", TWIST, None, Some(TWIST)).is_ok());
+        // a new Writer with a conflicting type, even if not configured in `topic_types`
+        let err = check_topic_type("/cmd_vel", TWIST_STAMPED, None, Some(TWIST)).unwrap_err();
+        assert!(err.contains("already routed with geometry_msgs/msg/Twist"));
+        // the configured type takes precedence in the report
+        let err =
+            check_topic_type("/cmd_vel", TWIST_STAMPED, Some(TWIST), Some(TWIST)).unwrap_err();
+        assert!(err.contains("topic_types"));
+    }
+
+    #[test]
+    fn test_topic_conflict_type() {
+        use cyclors::qos::Qos;
+        const ODOMETRY: &str = "nav_msgs/msg/Odometry";
+        const POSE: &str = "geometry_msgs/msg/PoseStamped";
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        let qos = Qos::default();
+
+        for kind in [RouteKind::Subscriber, RouteKind::Publisher] {
+            assert_eq!(
+                check_topic_conflict(&config, kind, "/odom", (ODOMETRY, &qos), (ODOMETRY, &qos)),
+                None
+            );
+            let (conflict, reasons) =
+                check_topic_conflict(&config, kind, "/odom", (POSE, &qos), (ODOMETRY, &qos))
+                    .unwrap();
+            assert_eq!(conflict, ConflictKind::Type);
+            assert!(reasons[0].contains(&format!("{POSE} discovered locally")));
+            assert!(reasons[0].contains(&format!("{ODOMETRY} announced")));
+        }
+        // even if the QoS are adapted by the bridge
+        let config = serde_json::from_str::<Config>(r#"{"auto_adapt_qos": true}"#).unwrap();
+        assert!(check_topic_conflict(
+            &config,
+            RouteKind::Subscriber,
+            "/odom",
+            (POSE, &qos),
+            (ODOMETRY, &qos)
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn test_topic_conflict_reliability() {
+        use cyclors::qos::{Qos, Reliability, ReliabilityKind, DDS_100MS_DURATION};
+        const ODOMETRY: &str = "nav_msgs/msg/Odometry";
+        let config = serde_json::from_str::<Config>(r#"{}"#).unwrap();
+        let qos = |kind| Qos {
+            reliability: Some(Reliability {
+                kind,
+                max_blocking_time: DDS_100MS_DURATION,
+            }),
+            ..Default::default()
+        };
+        let (reliable, best_effort) = (
+            qos(ReliabilityKind::RELIABLE),
+            qos(ReliabilityKind::BEST_EFFORT),
+        );
+
+        // a remote BEST_EFFORT Publisher and a local RELIABLE Subscriber
+        let (conflict, reasons) = check_topic_conflict(
+            &config,
+            RouteKind::Subscriber,
+            "/odom",
+            (ODOMETRY, &reliable),
+            (ODOMETRY, &best_effort),
+        )
+        .unwrap();
+        assert_eq!(conflict, ConflictKind::Qos);
+        assert_eq!(reasons.len(), 1);
+        assert!(reasons[0].starts_with("Reliability"));
+        // a local BEST_EFFORT Publisher and a remote RELIABLE Subscriber
+        assert!(check_topic_conflict(
+            &config,
+            RouteKind::Publisher,
+            "/odom",
+            (ODOMETRY, &best_effort),
+            (ODOMETRY, &reliable),
+        )
+        .is_some());
+        // compatible in the other direction
+        assert_eq!(
+            check_topic_conflict(
+                &config,
+                RouteKind::Subscriber,
+                "/odom",
+                (ODOMETRY, &best_effort),
+                (ODOMETRY, &reliable),
+            ),
+            None
+        );
+        assert_eq!(
+            check_topic_conflict(
+                &config,
+                RouteKind::Publisher,
+                "/odom",
+                (ODOMETRY, &reliable),
+                (ODOMETRY, &best_effort),
+            ),
+            None
+        );
+
+        // not a conflict if the bridge adapts the QoS
+        let config = serde_json::from_str::<Config>(r#"{"auto_adapt_qos": true}"#).unwrap();
+        assert_eq!(
+            check_topic_conflict(
+                &config,
+                RouteKind::Subscriber,
+                "/odom",
+                (ODOMETRY, &reliable),
+                (ODOMETRY, &best_effort),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_key_expr_collision() {
+        const TWIST: &str = "geometry_msgs/msg/Twist";
+        let config = serde_json::from_str::<Config>(
+            r#"{"remappings": {"/robot1/cmd_vel": "/cmd_vel"}, "raw_dds_topics": ["lidar"]}"#,
+        )
+        .unwrap();
+        let routed = vec!["/cmd_vel".to_string(), "/raw/lidar".to_string()];
+        // no collision with other names, nor with the existing route for the same name
+        assert!(
+            check_key_expr_collision(&config, RouteKind::Publisher, "/odom", TWIST, &routed)
+                .is_ok()
+        );
+        assert!(check_key_expr_collision(
+            &config,
+            RouteKind::Publisher,
+            "/cmd_vel",
+            TWIST,
+            &routed
+        )
+        .is_ok());
+        // remapped to the name of an existing route
+        let err = check_key_expr_collision(
+            &config,
+            RouteKind::Publisher,
+            "/robot1/cmd_vel",
+            TWIST,
+            &routed,
+        )
+        .unwrap_err();
+        assert!(err.contains("'cmd_vel' is already used by the route for /cmd_vel"));
+        // a raw DDS topic with the same key expression than a ROS topic
+        let err = check_key_expr_collision(
+            &config,
+            RouteKind::Subscriber,
+            "@raw/lidar",
+            "sensor_msgs::msg::dds_::PointCloud2_",
+            &routed,
+        )
+        .unwrap_err();
+        assert!(err.contains("/raw/lidar"));
+    }
+
+    #[test]
+    fn test_ros2_names_cache_config_change() {
+        let mut cache: Ros2NamesCache<(RouteKind, OwnedKeyExpr), Config> = Ros2NamesCache::new(16);
+        let mut name = |config: &Arc<Config>, ke: &str| {
+            let ke = keyexpr::new(ke).unwrap();
+            cache.get_or_compute(config, &(RouteKind::Subscriber, ke.to_owned()), |c| {
+                route_ros2_name(c, RouteKind::Subscriber, ke)
+            })
+        };
+
+        let config =
+            Arc::new(serde_json::from_str::<Config>(r#"{"namespace": "/robot1"}"#).unwrap());
+        assert_eq!(name(&config, "robot1/cmd_vel"), "/cmd_vel");
+        assert_eq!(name(&config, "robot1/cmd_vel"), "/cmd_vel");
+
+        // a new configuration (e.g. reloaded) with another namespace and a remapping
+        let config = Arc::new(
+            serde_json::from_str::<Config>(
+                r#"{"namespace": "/robot2", "remappings": {"/cmd_vel": "/cmd_vel_safe"}}"#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(name(&config, "robot1/cmd_vel"), "/robot1/cmd_vel");
+        assert_eq!(name(&config, "robot2/cmd_vel_safe"), "/cmd_vel");
+    }
+
+    #[test]
+    fn test_ros2_names_cache_hits() {
+        let mut cache = Ros2NamesCache::new(1024);
+        let computed = std::cell::Cell::new(0);
+        let mut name = |config: &Arc<Config>, ke: &OwnedKeyExpr| {
+            cache.get_or_compute(config, &(RouteKind::Subscriber, ke.clone()), |c| {
+                computed.set(computed.get() + 1);
+                route_ros2_name(c, RouteKind::Subscriber, ke)
+            })
+        };
+        let load_config = || {
+            Arc::new(
+                serde_json::from_str::<Config>(
+                    r#"{
+                        "namespace": "/robot1",
+                        "namespace_rules": [{"pattern": "/tf.*", "namespace": "/"}],
+                        "remappings": {"/ns/**": "/other_ns/**", "/old_cmd_vel": "/cmd_vel"}
+                    }"#,
+                )
+                .unwrap(),
+            )
+        };
+        let config = load_config();
+        let kes: Vec<OwnedKeyExpr> = (0..100)
+            .map(|i| OwnedKeyExpr::try_from(format!("robot1/other_ns/topic_{i}%2A")).unwrap())
+            .collect();
+
+        // each name is computed once, the repeated announcements hitting the cache with the same name
+        for _ in 0..3 {
+            for ke in &kes {
+                assert_eq!(
+                    name(&config, ke),
+                    route_ros2_name(&config, RouteKind::Subscriber, ke)
+                );
+            }
+        }
+        assert_eq!(computed.get(), kes.len());
+
+        // a reloaded configuration (even if equal) invalidates the cache: the names are computed again, once
+        let reloaded = load_config();
+        for _ in 0..2 {
+            for ke in &kes {
+                name(&reloaded, ke);
+            }
+        }
+        assert_eq!(computed.get(), 2 * kes.len());
+    }
+
+    #[async_std::test]
+    async fn test_run_concurrently() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        // route creations of various durations: the results are in order, and at most 4 run at a time
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+        let creations = (0..20u64)
+            .map(|i| {
+                let (running, max_running) = (&running, &max_running);
+                async move {
+                    let n = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(n, Ordering::SeqCst);
+                    async_std::task::sleep(Duration::from_millis(20 - i)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    i
+                }
+            })
+            .collect();
+        let results = run_concurrently(creations, 4).await;
+        assert_eq!(results, (0..20).collect::<Vec<_>>());
+        assert_eq!(max_running.load(Ordering::SeqCst), 4);
+
+        // 0 is considered as sequential
+        let results = run_concurrently(vec![async { 1 }, async { 2 }], 0).await;
+        assert_eq!(results, vec![1, 2]);
+    }
+
+    // The DDS topic and type of "/chatter", as published and subscribed by the test Nodes
+    const CHATTER_TOPIC: &str = "rt/chatter";
+    const STRING_TYPE: &str = "std_msgs/msg/String";
+    const STRING_DDS_TYPE: &str = "std_msgs::msg::dds_::String_";
+
+    // A bridge with its own DDS Participant (as the plugin), its RoutesMgr being passed
+    // the discovery events of the test Nodes as they would be by the DiscoveryMgr
+    struct TestBridge {
+        plugin_id: OwnedKeyExpr,
+        routes_mgr: RoutesMgr<'static>,
+        discovered_entities: Arc<RwLock<DiscoveredEntities>>,
+    }
+
+    impl TestBridge {
+        fn new(config: serde_json::Value, zsession: Session) -> TestBridge {
+            let config: Config = serde_json::from_value(config).unwrap();
+            let participant = dds_participant(config.domain);
+            let plugin_id = zsession.zid().into_keyexpr().to_owned();
+            let ros_discovery_mgr = Arc::new(
+                RosDiscoveryInfoMgr::new(participant, &config.namespace, &config.nodename).unwrap(),
+            );
+            let discovered_entities = Arc::new(RwLock::new(DiscoveredEntities::default()));
+            let admin_prefix = OwnedKeyExpr::try_from(format!("@ros2/{plugin_id}")).unwrap();
+            let routes_mgr = RoutesMgr::new(
+                plugin_id.clone(),
+                Arc::new(config),
+                Arc::new(zsession),
+                participant,
+                discovered_entities.clone(),
+                ros_discovery_mgr,
+                admin_prefix,
+            );
+            TestBridge {
+                plugin_id,
+                routes_mgr,
+                discovered_entities,
+            }
+        }
+
+        // The discovery of a Node's Publisher of std_msgs/msg/String, registering its DDS Writer
+        fn node_publisher(
+            &self,
+            node: &str,
+            ros2_name: &str,
+            writer: Gid,
+            keyless: bool,
+        ) -> ROS2DiscoveryEvent {
+            zwrite!(self.discovered_entities).add_writer(node_entity(writer, ros2_name, keyless));
+            ROS2DiscoveryEvent::DiscoveredMsgPub(
+                node.into(),
+                MsgPub::create(ros2_name.into(), STRING_TYPE.into(), writer).unwrap(),
+            )
+        }
+
+        // The discovery of a Node's Subscriber of std_msgs/msg/String, registering its DDS Reader
+        fn node_subscriber(
+            &self,
+            node: &str,
+            ros2_name: &str,
+            reader: Gid,
+            keyless: bool,
+        ) -> ROS2DiscoveryEvent {
+            zwrite!(self.discovered_entities).add_reader(node_entity(reader, ros2_name, keyless));
+            ROS2DiscoveryEvent::DiscoveredMsgSub(
+                node.into(),
+                MsgSub::create(ros2_name.into(), STRING_TYPE.into(), reader).unwrap(),
+            )
+        }
+    }
+
+    // Pass to a bridge the announcements of the remote bridges received so far by a liveliness subscriber
+    // (as the plugin does), after a while for their propagation
+    async fn relay_announcements(bridge: &mut TestBridge, liveliness: &Receiver<Sample>) {
+        async_std::task::sleep(Duration::from_millis(500)).await;
+        while let Ok(sample) = liveliness.try_recv() {
+            let ke = sample.key_expr.as_keyexpr();
+            let parsed = ke_liveliness_all::parse(ke).unwrap();
+            if parsed.plugin_id() == &*bridge.plugin_id {
+                continue;
+            }
+            let (_, remaining) = split_format_version(parsed.remaining().unwrap().as_str());
+            let event = parse_announcement_event(ke, &remaining[..3], sample.kind).unwrap();
+            bridge
+                .routes_mgr
+                .on_ros_announcement_event(event)
+                .await
+                .unwrap();
+        }
+    }
+
+    // A DDS Writer or Reader of a test Node, as discovered by a bridge
+    fn node_entity(key: Gid, ros2_name: &str, keyless: bool) -> DdsEntity {
+        DdsEntity {
+            key,
+            participant_key: Gid::from([0xff; 16]),
+            topic_name: format!("rt{ros2_name}"),
+            type_name: STRING_DDS_TYPE.into(),
+            type_info: None,
+            keyless,
+            qos: node_qos(),
+        }
+    }
+
+    // Two bridges on a same DDS domain, both routing a topic published and subscribed by its Nodes:
+    // each one writes to DDS the message routed by the other, that neither routes back to Zenoh
+    #[async_std::test]
+    async fn test_no_echo_between_bridges() {
+        const DOMAIN: u32 = 211;
+        let (zsession_1, zsession_2) = session_pair().await;
+        let mut bridges = [
+            TestBridge::new(serde_json::json!({ "domain": DOMAIN }), zsession_1),
+            TestBridge::new(serde_json::json!({ "domain": DOMAIN }), zsession_2),
+        ];
+        let nodes = dds_participant(DOMAIN);
+        let writer = node_dds_writer(nodes, CHATTER_TOPIC, STRING_DDS_TYPE, true);
+        let reader = node_dds_reader(nodes, CHATTER_TOPIC, STRING_DDS_TYPE, true);
+        for bridge in &mut bridges {
+            let events = [
+                bridge.node_publisher("/talker", "/chatter", get_guid(&writer).unwrap(), true),
+                bridge.node_subscriber("/listener", "/chatter", get_guid(&reader).unwrap(), true),
+            ];
+            for event in events {
+                bridge
+                    .routes_mgr
+                    .on_ros_discovery_event(event)
+                    .await
+                    .unwrap();
+            }
+        }
+        // let the Publisher routes activate their DDS Reader on the matching of the other bridge's Subscriber
+        async_std::task::sleep(Duration::from_millis(1000)).await;
+
+        dds_write(writer, cdr_string("hello")).unwrap();
+        // the Node's message, and the copies written by each bridge: no more even after a while
+        let received = take_node_messages(reader, 4).await;
+        assert_eq!(received.len(), 3);
+        assert!(received
+            .iter()
+            .all(|(state, msg)| *state == DDSInstanceState::Alive && *msg == cdr_string("hello")));
+        for bridge in &bridges {
+            let route = &bridge.routes_mgr.routes_publishers["/chatter"];
+            assert_eq!(route.stats().messages_out, 1);
+        }
+    }
+
+    // Two bridges in "forward_discovery" mode on distinct DDS domains: a Node's Publisher is routed only
+    // once matched by the Subscriber announced by the other bridge, and no more once this one is retired
+    #[async_std::test]
+    async fn test_forward_discovery_between_bridges() {
+        const DOMAIN_1: u32 = 212;
+        const DOMAIN_2: u32 = 213;
+        let (zsession_1, zsession_2) = session_pair().await;
+        let mut bridge_1 = TestBridge::new(
+            serde_json::json!({ "domain": DOMAIN_1, "mode": "forward_discovery" }),
+            zsession_1,
+        );
+        let mut bridge_2 = TestBridge::new(
+            serde_json::json!({ "domain": DOMAIN_2, "mode": "forward_discovery" }),
+            zsession_2,
+        );
+        let zsession_1 = bridge_1.routes_mgr.context.zsession.clone();
+        let liveliness_1 = zsession_1
+            .liveliness()
+            .declare_subscriber("@ros2_lv/**")
+            .res_async()
+            .await
+            .unwrap();
+        let zsession_2 = bridge_2.routes_mgr.context.zsession.clone();
+        let liveliness_2 = zsession_2
+            .liveliness()
+            .declare_subscriber("@ros2_lv/**")
+            .res_async()
+            .await
+            .unwrap();
+
+        // a Node publishing "/chatter" and "/unmatched" on the 1st domain, and one subscribing "/chatter" on the 2nd
+        let nodes_1 = dds_participant(DOMAIN_1);
+        let writer = node_dds_writer(nodes_1, CHATTER_TOPIC, STRING_DDS_TYPE, true);
+        let events = [
+            bridge_1.node_publisher("/talker", "/chatter", get_guid(&writer).unwrap(), true),
+            bridge_1.node_publisher("/talker", "/unmatched", Gid::from([1; 16]), true),
+        ];
+        for event in events {
+            bridge_1
+                .routes_mgr
+                .on_ros_discovery_event(event)
+                .await
+                .unwrap();
+        }
+        let nodes_2 = dds_participant(DOMAIN_2);
+        let reader = node_dds_reader(nodes_2, CHATTER_TOPIC, STRING_DDS_TYPE, true);
+        let subscriber =
+            bridge_2.node_subscriber("/listener", "/chatter", get_guid(&reader).unwrap(), true);
+        bridge_2
+            .routes_mgr
+            .on_ros_discovery_event(subscriber.clone())
+            .await
+            .unwrap();
+        // the local interfaces are only announced
+        assert!(bridge_1.routes_mgr.routes_publishers.is_empty());
+        assert!(bridge_2.routes_mgr.routes_subscribers.is_empty());
+        assert_eq!(bridge_1.routes_mgr.forward_tokens.len(), 2);
+        assert_eq!(bridge_2.routes_mgr.forward_tokens.len(), 1);
+
+        // the matching interfaces are routed once the announcements received (twice, as the routes announce them too)
+        for _ in 0..2 {
+            relay_announcements(&mut bridge_2, &liveliness_2).await;
+            relay_announcements(&mut bridge_1, &liveliness_1).await;
+        }
+        let routed: Vec<&String> = bridge_1.routes_mgr.routes_publishers.keys().collect();
+        assert_eq!(routed, vec!["/chatter"]);
+        let routed: Vec<&String> = bridge_2.routes_mgr.routes_subscribers.keys().collect();
+        assert_eq!(routed, vec!["/chatter"]);
+        assert!(bridge_2.routes_mgr.routes_publishers.is_empty());
+
+        // let the Publisher route activate its DDS Reader on the matching of the Subscriber route
+        async_std::task::sleep(Duration::from_millis(500)).await;
+        dds_write(writer, cdr_string("hello")).unwrap();
+        let received = take_node_messages(reader, 1).await;
+        assert_eq!(
+            received,
+            vec![(DDSInstanceState::Alive, cdr_string("hello"))]
+        );
+
+        // the undiscovered Subscriber is retired, and the Publisher no more routed (but still announced)
+        let ROS2DiscoveryEvent::DiscoveredMsgSub(node, iface) = subscriber else {
+            unreachable!()
+        };
+        bridge_2
+            .routes_mgr
+            .on_ros_discovery_event(ROS2DiscoveryEvent::UndiscoveredMsgSub(node, iface))
+            .await
+            .unwrap();
+        assert!(bridge_2.routes_mgr.routes_subscribers.is_empty());
+        assert!(bridge_2.routes_mgr.forward_tokens.is_empty());
+        relay_announcements(&mut bridge_1, &liveliness_1).await;
+        assert!(bridge_1.routes_mgr.routes_publishers.is_empty());
+        assert_eq!(bridge_1.routes_mgr.forward_tokens.len(), 2);
+    }
+
+    // The routes of a bridge for the topics of the test Nodes, as listed in its admin space
+    // and announced by its liveliness tokens (queried from another session)
+    async fn announced_routes(
+        bridge: &TestBridge,
+        zsession: &Session,
+    ) -> (Vec<String>, Vec<String>) {
+        let mut admin_routes: Vec<String> = bridge
+            .routes_mgr
+            .admin_space
+            .keys()
+            .filter(|ke| ke.starts_with("route/topic/"))
+            .map(ToString::to_string)
+            .collect();
+        admin_routes.sort();
+        let prefix = format!("@ros2_lv/{}/", bridge.plugin_id);
+        let replies = zsession
+            .liveliness()
+            .get(format!("{prefix}**"))
+            .res_async()
+            .await
+            .unwrap();
+        let mut tokens = Vec::new();
+        while let Ok(reply) = replies.recv_async().await {
+            let ke = reply.sample.unwrap().key_expr;
+            let remaining = ke.as_str().strip_prefix(&prefix).unwrap();
+            // e.g. "MP/t1" for the route of the Publisher of "/t1"
+            tokens.push(
+                remaining
+                    .splitn(3, '/')
+                    .take(2)
+                    .collect::<Vec<_>>()
+                    .join("/"),
+            );
+        }
+        tokens.sort();
+        (admin_routes, tokens)
+    }
+
+    // A batch of discovery events (e.g. at startup) with routes created concurrently, some undiscovered in the same
+    // batch: those are removed once created, without leaking their admin space entry nor their liveliness token
+    #[async_std::test]
+    async fn test_concurrent_route_creations_undiscovered() {
+        let (zsession, observer) = session_pair().await;
+        let mut bridge = TestBridge::new(
+            serde_json::json!({ "domain": 214, "max_concurrent_route_creations": 4 }),
+            zsession,
+        );
+        let mut discoveries = Vec::new();
+        for i in 0..6u8 {
+            let name = format!("/t{i}");
+            discoveries.push(bridge.node_publisher("/talker", &name, Gid::from([i; 16]), true));
+            discoveries.push(bridge.node_subscriber(
+                "/listener",
+                &name,
+                Gid::from([i + 10; 16]),
+                true,
+            ));
+        }
+        let undiscovery = |event: &ROS2DiscoveryEvent| match event.clone() {
+            ROS2DiscoveryEvent::DiscoveredMsgPub(node, iface) => {
+                ROS2DiscoveryEvent::UndiscoveredMsgPub(node, iface)
+            }
+            ROS2DiscoveryEvent::DiscoveredMsgSub(node, iface) => {
+                ROS2DiscoveryEvent::UndiscoveredMsgSub(node, iface)
+            }
+            _ => unreachable!(),
+        };
+        // the Nodes of "/t0", "/t2" and "/t4" leave while their routes are being created
+        let mut batch = discoveries.clone();
+        batch.extend(discoveries.iter().step_by(4).map(undiscovery));
+        batch.extend(discoveries.iter().skip(1).step_by(4).map(undiscovery));
+        bridge.routes_mgr.on_ros_discovery_events(batch).await;
+
+        let odd = ["/t1", "/t3", "/t5"];
+        let mut routed: Vec<&String> = bridge.routes_mgr.routes_publishers.keys().collect();
+        routed.sort();
+        assert_eq!(routed, odd);
+        let mut routed: Vec<&String> = bridge.routes_mgr.routes_subscribers.keys().collect();
+        routed.sort();
+        assert_eq!(routed, odd);
+        async_std::task::sleep(Duration::from_millis(500)).await;
+        let (admin_routes, tokens) = announced_routes(&bridge, &observer).await;
+        assert_eq!(
+            admin_routes,
+            [
+                "route/topic/pub/t1",
+                "route/topic/pub/t3",
+                "route/topic/pub/t5",
+                "route/topic/sub/t1",
+                "route/topic/sub/t3",
+                "route/topic/sub/t5"
+            ]
+        );
+        assert_eq!(
+            tokens,
+            ["MP/t1", "MP/t3", "MP/t5", "MS/t1", "MS/t3", "MS/t5"]
+        );
+
+        // the other Nodes leave: nothing remains
+        let batch = discoveries.iter().skip(2).step_by(4).map(undiscovery);
+        let batch = batch.chain(discoveries.iter().skip(3).step_by(4).map(undiscovery));
+        bridge
+            .routes_mgr
+            .on_ros_discovery_events(batch.collect())
+            .await;
+        assert!(bridge.routes_mgr.routes_publishers.is_empty());
+        assert!(bridge.routes_mgr.routes_subscribers.is_empty());
+        async_std::task::sleep(Duration::from_millis(500)).await;
+        let (admin_routes, tokens) = announced_routes(&bridge, &observer).await;
+        assert!(admin_routes.is_empty());
+        assert!(tokens.is_empty());
+    }
+
+    // A keyed topic routed by two bridges on distinct DDS domains: the dispose and the unregister of an instance
+    // by the publishing Node are routed as Zenoh deletes, and notified to the subscribing Node on the far side
+    #[async_std::test]
+    async fn test_instance_lifecycle_between_bridges() {
+        const DOMAIN_1: u32 = 215;
+        const DOMAIN_2: u32 = 216;
+        const FLEET_TOPIC: &str = "rt/fleet";
+        // the observer is connected to the 1st bridge, which routes the Node's messages to Zenoh
+        let mut zsessions = connected_sessions(3).await;
+        let observer = zsessions.pop().unwrap();
+        let mut bridge_2 = TestBridge::new(
+            serde_json::json!({ "domain": DOMAIN_2 }),
+            zsessions.pop().unwrap(),
+        );
+        let mut bridge_1 = TestBridge::new(
+            serde_json::json!({ "domain": DOMAIN_1 }),
+            zsessions.pop().unwrap(),
+        );
+        let nodes_1 = dds_participant(DOMAIN_1);
+        let writer = node_dds_writer(nodes_1, FLEET_TOPIC, STRING_DDS_TYPE, false);
+        let publisher = bridge_1.node_publisher(
+            "/fleet_manager",
+            "/fleet",
+            get_guid(&writer).unwrap(),
+            false,
+        );
+        bridge_1
+            .routes_mgr
+            .on_ros_discovery_event(publisher)
+            .await
+            .unwrap();
+        let nodes_2 = dds_participant(DOMAIN_2);
+        let reader = node_dds_reader(nodes_2, FLEET_TOPIC, STRING_DDS_TYPE, false);
+        let subscriber = bridge_2.node_subscriber(
+            "/fleet_monitor",
+            "/fleet",
+            get_guid(&reader).unwrap(),
+            false,
+        );
+        bridge_2
+            .routes_mgr
+            .on_ros_discovery_event(subscriber)
+            .await
+            .unwrap();
+        // the messages routed over Zenoh
+        let routed = observer
+            .declare_subscriber("fleet")
+            .res_async()
+            .await
+            .unwrap();
+        let routed = &routed;
+        let next_routed = move || async move {
+            async_std::future::timeout(Duration::from_secs(5), routed.recv_async())
+                .await
+                .unwrap()
+                .unwrap()
+        };
+        // let the Publisher route activate its DDS Reader on the matching of the Subscriber route
+        async_std::task::sleep(Duration::from_millis(1000)).await;
+
+        let robot = cdr_string("robot-07");
+        for state in [DDSInstanceState::Disposed, DDSInstanceState::Unregistered] {
+            // the instance is (re-)created by a message
+            dds_write(writer, robot.clone()).unwrap();
+            let sample = next_routed().await;
+            assert_eq!(sample.kind, SampleKind::Put);
+            assert_eq!(&*sample.value.payload.contiguous(), robot.as_slice());
+            let keyhash = instance_keyhash_from_attachment(sample.attachment().unwrap());
+            assert!(keyhash.is_some());
+            let received = take_node_messages(reader, 1).await;
+            assert_eq!(received, vec![(DDSInstanceState::Alive, robot.clone())]);
+
+            // and then disposed or unregistered
+            dds_write_with_instance_state(writer, robot.clone(), state).unwrap();
+            let sample = next_routed().await;
+            assert_eq!(sample.kind, SampleKind::Delete);
+            let attachment = sample.attachment().unwrap();
+            assert_eq!(instance_state_from_attachment(attachment), Some(state));
+            assert_eq!(instance_keyhash_from_attachment(attachment), keyhash);
+            let received = take_node_messages(reader, 1).await;
+            assert_eq!(received.len(), 1);
+            assert_eq!(received[0].0, state);
+        }
+    }
+
+    // Two Service Client routes with distinct queries timeouts, their remote Server replying after 1 second:
+    // the call of "/get_state" fails after the default timeout with the configured error reply,
+    // while the call of "/load_map" (with a longer timeout) gets the reply of the Server
+    #[async_std::test]
+    async fn test_per_service_queries_timeout() {
+        const DOMAIN: u32 = 217;
+        const TRIGGER_TYPE: &str = "std_srvs/srv/Trigger";
+        // std_srvs/srv/Trigger Responses with success=false or success=true, and an empty message
+        const ERROR_RESPONSE: [u8; 13] = [0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+        const RESPONSE: [u8; 13] = [0, 1, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 0];
+        let (server, zsession) = session_pair().await;
+        let mut bridge = TestBridge::new(
+            serde_json::json!({
+                "domain": DOMAIN,
+                "queries_timeout": { "default": 0.5, "services": { "/load_map": 5.0 } },
+                "service_error_replies": { ".*": "00010000000000000100000000" }
+            }),
+            zsession,
+        );
+        let mut queryables = Vec::new();
+        for name in ["get_state", "load_map"] {
+            let queryable = server
+                .declare_queryable(name)
+                .callback(|query: Query| {
+                    async_std::task::spawn(async move {
+                        async_std::task::sleep(Duration::from_secs(1)).await;
+                        let ke = query.key_expr().clone();
+                        query
+                            .reply(Ok(Sample::new(ke, RESPONSE.to_vec())))
+                            .res_async()
+                            .await
+                            .unwrap();
+                    });
+                })
+                .res_async()
+                .await
+                .unwrap();
+            queryables.push(queryable);
+        }
+
+        // a Node calling both Services, routed as if their remote Server was announced
+        let nodes = dds_participant(DOMAIN);
+        let mut clients = Vec::new();
+        for name in ["get_state", "load_map"] {
+            let ros2_name = format!("/{name}");
+            let client = ROS2DiscoveryEvent::DiscoveredServiceCli(
+                "/client".into(),
+                ServiceCli::create(ros2_name.clone(), TRIGGER_TYPE.into()).unwrap(),
+            );
+            bridge
+                .routes_mgr
+                .on_ros_discovery_event(client)
+                .await
+                .unwrap();
+            bridge
+                .routes_mgr
+                .routes_service_cli
+                .get_mut(&ros2_name)
+                .unwrap()
+                .add_remote_route("server", keyexpr::new(name).unwrap());
+            let writer = node_dds_writer(
+                nodes,
+                &format!("rq/{name}Request"),
+                "std_srvs::srv::dds_::Trigger_Request_",
+                true,
+            );
+            let reader = node_dds_reader(
+                nodes,
+                &format!("rr/{name}Reply"),
+                "std_srvs::srv::dds_::Trigger_Response_",
+                true,
+            );
+            clients.push((writer, reader));
+        }
+        // let the DDS entities of the routes match the ones of the Node
+        async_std::task::sleep(Duration::from_millis(1000)).await;
+
+        // a std_srvs/srv/Trigger Request, with the request id of the client (its guid and a sequence number)
+        let request = [&[0u8, 1, 0, 0][..], &[7; 8], &1u64.to_le_bytes(), &[0]].concat();
+        // the replies carry the request id of the call
+        let reply = |response: &[u8]| [&response[..4], &request[4..20], &response[4..]].concat();
+        let start = Instant::now();
+        for (writer, _) in &clients {
+            dds_write(*writer, request.clone()).unwrap();
+        }
+        let (_, get_state) = clients[0];
+        let received = take_node_messages(get_state, 1).await;
+        assert_eq!(
+            received,
+            vec![(DDSInstanceState::Alive, reply(&ERROR_RESPONSE))]
+        );
+        assert!(start.elapsed() < Duration::from_secs(1));
+        let (_, load_map) = clients[1];
+        let received = take_node_messages(load_map, 1).await;
+        assert_eq!(received, vec![(DDSInstanceState::Alive, reply(&RESPONSE))]);
+        assert!(start.elapsed() >= Duration::from_secs(1));
+        for (ros2_name, errors) in [("/get_state", 1), ("/load_map", 0)] {
+            let route = &bridge.routes_mgr.routes_service_cli[ros2_name];
+            assert_eq!(route.stats().errors, errors);
         }
     }
 }
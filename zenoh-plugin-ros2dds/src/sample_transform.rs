@@ -0,0 +1,429 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The transformation of the payloads routed by the Route Publishers and Route Subscribers
+//! (e.g. to downscale camera images, or to strip fields from large messages).
+//!
+//! A [`SampleTransform`] is registered for a key expression pattern via [`register_sample_transform()`],
+//! before the plugin starts. Each route whose Zenoh key expression is included in the pattern calls it
+//! before forwarding a message (the first registered pattern matching applies).
+//!
+//! The standalone bridge loads the transforms from dynamic libraries configured in `sample_transforms`,
+//! each one exporting the C ABI functions:
+//! ```c
+//! // Returns: 0 to route the payload unchanged, 1 to route the payload written in *out (with length *out_len),
+//! // 2 to drop the message, any other value for an error (the message is dropped).
+//! int32_t zenoh_ros2dds_transform(const char *key_expr, const uint8_t *payload, size_t len,
+//!                                 uint8_t **out, size_t *out_len);
+//! // Releases a payload returned by zenoh_ros2dds_transform()
+//! void zenoh_ros2dds_transform_free(uint8_t *out, size_t out_len);
+//! ```
+use serde::Serializer;
+use std::any::Any;
+use std::ffi::{c_char, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, RwLock};
+use zenoh::buffers::ZBuf;
+use zenoh::prelude::*;
+use zenoh_util::LibLoader;
+
+/// A transformation of the payloads routed for a key expression.
+/// Returning `None` drops the message. A panic is caught, the message being dropped and counted as an error.
+pub trait SampleTransform: Send + Sync {
+    fn transform(&self, key: &keyexpr, payload: ZBuf) -> Option<ZBuf>;
+}
+
+lazy_static::lazy_static!(
+    static ref SAMPLE_TRANSFORMS: RwLock<Vec<(OwnedKeyExpr, Arc<dyn SampleTransform>)>> =
+        RwLock::new(Vec::new());
+);
+
+/// Register a transformation for the routes which Zenoh key expression is included in `pattern`.
+/// It applies to the routes created afterward.
+pub fn register_sample_transform(pattern: OwnedKeyExpr, transform: Arc<dyn SampleTransform>) {
+    tracing::info!("Register a sample transform for {pattern}");
+    SAMPLE_TRANSFORMS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push((pattern, transform));
+}
+
+/// Load a transformation from a dynamic library (see the module documentation for its C ABI)
+/// and register it for `pattern`
+pub fn load_sample_transform(pattern: OwnedKeyExpr, path: &str) -> Result<(), String> {
+    let transform = DynLibTransform::load(path)?;
+    register_sample_transform(pattern, Arc::new(transform));
+    Ok(())
+}
+
+// The transformation applied by a route
+pub struct RouteTransform {
+    key_expr: OwnedKeyExpr,
+    pattern: OwnedKeyExpr,
+    transform: Arc<dyn SampleTransform>,
+}
+
+impl RouteTransform {
+    // The transformation registered for a route's key expression, if any
+    pub fn for_route(key_expr: &keyexpr) -> Option<Arc<RouteTransform>> {
+        SAMPLE_TRANSFORMS
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|(pattern, _)| pattern.includes(key_expr))
+            .map(|(pattern, transform)| {
+                Arc::new(RouteTransform {
+                    key_expr: key_expr.into(),
+                    pattern: pattern.clone(),
+                    transform: transform.clone(),
+                })
+            })
+    }
+
+    // Return the transformed payload, None if the message is dropped, or an error if the transform panicked
+    pub fn apply(&self, payload: ZBuf) -> Result<Option<ZBuf>, String> {
+        catch_unwind(AssertUnwindSafe(|| {
+            self.transform.transform(&self.key_expr, payload)
+        }))
+        .map_err(|e| {
+            let reason = e
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| e.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown reason".into());
+            format!("sample transform for {} panicked: {reason}", self.pattern)
+        })
+    }
+}
+
+pub fn serialize_route_transform<S>(
+    transform: &Option<Arc<RouteTransform>>,
+    s: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match transform {
+        Some(t) => s.serialize_str(&t.pattern),
+        None => s.serialize_none(),
+    }
+}
+
+type TransformFn = unsafe extern "C" fn(
+    key_expr: *const c_char,
+    payload: *const u8,
+    len: usize,
+    out: *mut *mut u8,
+    out_len: *mut usize,
+) -> i32;
+type TransformFreeFn = unsafe extern "C" fn(out: *mut u8, out_len: usize);
+
+const TRANSFORM_SYMBOL: &[u8] = b"zenoh_ros2dds_transform\0";
+const TRANSFORM_FREE_SYMBOL: &[u8] = b"zenoh_ros2dds_transform_free\0";
+
+// A transformation loaded from a dynamic library
+struct DynLibTransform {
+    path: String,
+    transform_fn: TransformFn,
+    free_fn: TransformFreeFn,
+    // the library, that must outlive its functions
+    _lib: Box<dyn Any + Send + Sync>,
+}
+
+impl DynLibTransform {
+    fn load(path: &str) -> Result<DynLibTransform, String> {
+        unsafe {
+            let (lib, _) = LibLoader::load_file(path)
+                .map_err(|e| format!("Failed to load sample transform library {path}: {e}"))?;
+            let transform_fn = *lib.get::<TransformFn>(TRANSFORM_SYMBOL).map_err(|e| {
+                format!(
+                    "Sample transform library {path} doesn't export zenoh_ros2dds_transform: {e}"
+                )
+            })?;
+            let free_fn = *lib.get::<TransformFreeFn>(TRANSFORM_FREE_SYMBOL).map_err(|e| {
+                format!("Sample transform library {path} doesn't export zenoh_ros2dds_transform_free: {e}")
+            })?;
+            Ok(DynLibTransform {
+                path: path.into(),
+                transform_fn,
+                free_fn,
+                _lib: Box::new(lib),
+            })
+        }
+    }
+}
+
+impl SampleTransform for DynLibTransform {
+    fn transform(&self, key: &keyexpr, payload: ZBuf) -> Option<ZBuf> {
+        let key = CString::new(key.as_str()).ok()?;
+        let mut out: *mut u8 = std::ptr::null_mut();
+        let mut out_len: usize = 0;
+        let ret = {
+            let input = payload.contiguous();
+            unsafe {
+                (self.transform_fn)(
+                    key.as_ptr(),
+                    input.as_ptr(),
+                    input.len(),
+                    &mut out,
+                    &mut out_len,
+                )
+            }
+        };
+        match ret {
+            0 => Some(payload),
+            1 if !out.is_null() => {
+                let result = unsafe { std::slice::from_raw_parts(out, out_len) }.to_vec();
+                unsafe { (self.free_fn)(out, out_len) };
+                Some(result.into())
+            }
+            2 => None,
+            _ => {
+                tracing::warn!(
+                    "Sample transform {} failed for {key:?} (returned {ret}) - drop message",
+                    self.path
+                );
+                None
+            }
+        }
+    }
+}
+
+/// An example of [`SampleTransform`]: the decimation of the `sensor_msgs/msg/Image` messages,
+/// keeping 1 pixel out of `factor` in each dimension (e.g. to route camera images over a low bandwidth link).
+/// A payload that isn't a CDR-encoded Image is routed unchanged.
+pub struct ImageDecimation {
+    pub factor: u32,
+}
+
+impl SampleTransform for ImageDecimation {
+    fn transform(&self, _key: &keyexpr, payload: ZBuf) -> Option<ZBuf> {
+        let image = decimate_image(&payload.contiguous(), self.factor);
+        match image {
+            Some(image) => Some(image.into()),
+            None => Some(payload),
+        }
+    }
+}
+
+// A reader of CDR-encoded data (after the 4 bytes of encapsulation header)
+struct CdrReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    little_endian: bool,
+}
+
+impl CdrReader<'_> {
+    fn align(&mut self, n: usize) {
+        // alignment is relative to the end of the encapsulation header
+        self.pos = 4 + (self.pos - 4 + n - 1) / n * n;
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        self.align(4);
+        let bytes: [u8; 4] = self.buf.get(self.pos..self.pos + 4)?.try_into().ok()?;
+        self.pos += 4;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn skip(&mut self, n: usize) -> Option<()> {
+        (self.pos + n <= self.buf.len()).then(|| self.pos += n)
+    }
+
+    fn write_u32(&self, buf: &mut [u8], pos: usize, v: u32) {
+        let bytes = if self.little_endian {
+            v.to_le_bytes()
+        } else {
+            v.to_be_bytes()
+        };
+        buf[pos..pos + 4].copy_from_slice(&bytes);
+    }
+}
+
+// Decimate a CDR-encoded sensor_msgs/msg/Image, returning None if it can't be decoded
+fn decimate_image(cdr: &[u8], factor: u32) -> Option<Vec<u8>> {
+    if factor <= 1 || cdr.len() < 4 {
+        return None;
+    }
+    let mut r = CdrReader {
+        buf: cdr,
+        pos: 4,
+        little_endian: cdr[1] & 1 == 1,
+    };
+    // header: stamp (sec, nanosec) and frame_id
+    r.read_u32()?;
+    r.read_u32()?;
+    let frame_id_len = r.read_u32()? as usize;
+    r.skip(frame_id_len)?;
+    let height_pos = {
+        r.align(4);
+        r.pos
+    };
+    let height = r.read_u32()?;
+    let width = r.read_u32()?;
+    let encoding_len = r.read_u32()? as usize;
+    r.skip(encoding_len)?;
+    // is_bigendian
+    r.skip(1)?;
+    let step_pos = {
+        r.align(4);
+        r.pos
+    };
+    let step = r.read_u32()?;
+    let data_len = r.read_u32()? as usize;
+    let data = cdr.get(r.pos..r.pos + data_len)?;
+    if width == 0 || step % width != 0 || data_len < step as usize * height as usize {
+        return None;
+    }
+    let pixel_size = (step / width) as usize;
+    let (new_height, new_width) = (height / factor, width / factor);
+    let new_step = new_width as usize * pixel_size;
+
+    let mut result = cdr[..step_pos + 4].to_vec();
+    r.write_u32(&mut result, height_pos, new_height);
+    r.write_u32(&mut result, height_pos + 4, new_width);
+    r.write_u32(&mut result, step_pos, new_step as u32);
+    let mut len = [0u8; 4];
+    r.write_u32(&mut len, 0, (new_step * new_height as usize) as u32);
+    result.extend_from_slice(&len);
+    for row in (0..new_height as usize).map(|y| y * factor as usize) {
+        let row = &data[row * step as usize..];
+        for x in (0..new_width as usize).map(|x| x * factor as usize) {
+            result.extend_from_slice(&row[x * pixel_size..(x + 1) * pixel_size]);
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decimate_image, ImageDecimation, RouteTransform, SampleTransform};
+    use crate::ke_for_sure;
+    use std::sync::Arc;
+    use zenoh::buffers::ZBuf;
+    use zenoh::prelude::*;
+
+    struct Uppercase;
+    impl SampleTransform for Uppercase {
+        fn transform(&self, _key: &keyexpr, payload: ZBuf) -> Option<ZBuf> {
+            Some(payload.contiguous().to_ascii_uppercase().into())
+        }
+    }
+
+    struct DropAll;
+    impl SampleTransform for DropAll {
+        fn transform(&self, _key: &keyexpr, _payload: ZBuf) -> Option<ZBuf> {
+            None
+        }
+    }
+
+    struct Panicking;
+    impl SampleTransform for Panicking {
+        fn transform(&self, _key: &keyexpr, _payload: ZBuf) -> Option<ZBuf> {
+            panic!("invalid message")
+        }
+    }
+
+    fn route_transform(transform: Arc<dyn SampleTransform>) -> RouteTransform {
+        RouteTransform {
+            key_expr: ke_for_sure!("camera/image_raw").into(),
+            pattern: ke_for_sure!("**").into(),
+            transform,
+        }
+    }
+
+    fn payload(s: &str) -> ZBuf {
+        s.as_bytes().to_vec().into()
+    }
+
+    #[test]
+    fn test_route_transform() {
+        // pass-through (no transform registered for the key expression)
+        assert!(RouteTransform::for_route(ke_for_sure!("unregistered/topic")).is_none());
+        // mutation
+        let t = route_transform(Arc::new(Uppercase));
+        assert_eq!(
+            t.apply(payload("hello")).unwrap().unwrap().contiguous(),
+            b"HELLO".as_slice()
+        );
+        // drop
+        let t = route_transform(Arc::new(DropAll));
+        assert!(matches!(t.apply(payload("hello")), Ok(None)));
+        // a panic doesn't crash the route
+        let t = route_transform(Arc::new(Panicking));
+        let err = t.apply(payload("hello")).unwrap_err();
+        assert!(err.contains("invalid message"), "{err}");
+    }
+
+    #[test]
+    fn test_register_sample_transform() {
+        super::register_sample_transform(
+            ke_for_sure!("test_register/**/image_raw").into(),
+            Arc::new(DropAll),
+        );
+        super::register_sample_transform(
+            ke_for_sure!("test_register/**").into(),
+            Arc::new(Uppercase),
+        );
+        // the first matching pattern applies
+        let t = RouteTransform::for_route(ke_for_sure!("test_register/camera/image_raw")).unwrap();
+        assert_eq!(t.pattern.as_str(), "test_register/**/image_raw");
+        let t = RouteTransform::for_route(ke_for_sure!("test_register/tf")).unwrap();
+        assert_eq!(t.pattern.as_str(), "test_register/**");
+        assert!(RouteTransform::for_route(ke_for_sure!("other/tf")).is_none());
+    }
+
+    // A CDR little-endian sensor_msgs/msg/Image of 4x4 "mono8" pixels valued 0..16
+    fn image_4x4() -> Vec<u8> {
+        let mut cdr = vec![0x00, 0x01, 0x00, 0x00];
+        cdr.extend_from_slice(&1u32.to_le_bytes()); // stamp.sec
+        cdr.extend_from_slice(&2u32.to_le_bytes()); // stamp.nanosec
+        cdr.extend_from_slice(&4u32.to_le_bytes()); // frame_id
+        cdr.extend_from_slice(b"cam\0");
+        cdr.extend_from_slice(&4u32.to_le_bytes()); // height
+        cdr.extend_from_slice(&4u32.to_le_bytes()); // width
+        cdr.extend_from_slice(&6u32.to_le_bytes()); // encoding
+        cdr.extend_from_slice(b"mono8\0");
+        cdr.push(0); // is_bigendian
+        cdr.push(0); // padding
+        cdr.extend_from_slice(&4u32.to_le_bytes()); // step
+        cdr.extend_from_slice(&16u32.to_le_bytes()); // data
+        cdr.extend(0u8..16);
+        cdr
+    }
+
+    #[test]
+    fn test_image_decimation() {
+        let image = image_4x4();
+        let decimated = decimate_image(&image, 2).unwrap();
+        // same header, encoding and is_bigendian
+        assert_eq!(decimated[..20], image[..20]);
+        assert_eq!(decimated[28..40], image[28..40]);
+        // height, width and step halved, 1 pixel out of 2 in each dimension
+        assert_eq!(decimated[20..28], [2, 0, 0, 0, 2, 0, 0, 0]);
+        assert_eq!(decimated[40..], [2, 0, 0, 0, 4, 0, 0, 0, 0, 2, 8, 10]);
+
+        // not an Image: unchanged
+        let t = ImageDecimation { factor: 2 };
+        let key = ke_for_sure!("camera/image_raw");
+        let p = t.transform(key, b"not an image".to_vec().into()).unwrap();
+        assert_eq!(p.contiguous(), b"not an image".as_slice());
+        let p = t.transform(key, image.clone().into()).unwrap();
+        assert_eq!(p.contiguous(), decimated.as_slice());
+    }
+}
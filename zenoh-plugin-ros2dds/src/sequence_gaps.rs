@@ -0,0 +1,208 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use zenoh_core::zlock;
+
+use crate::gid::Gid;
+
+// The binary layout of a SequenceNumber: [stream id (u64 LE, 8 bytes)][sequence number (u64 LE, 8 bytes)]
+const SEQUENCE_NUMBER_SIZE: usize = 16;
+
+// Beyond this count of tracked streams, the ones idle for STREAM_TIMEOUT are forgotten
+const MAX_STREAMS: usize = 1024;
+const STREAM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The sequence number of a message routed by a Publisher route from a DDS Writer (see `sequence_tracking`),
+/// counting the messages published over Zenoh from this Writer, starting at 1.
+/// The stream id identifies the Publisher route (changing with its re-creation), for the messages
+/// of the same Writer routed by several bridges (or by a re-created route) to be tracked separately.
+/// Cyclone DDS doesn't expose the RTPS sequence numbers, so the losses between the DDS Writer
+/// and the bridge are not detected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SequenceNumber {
+    pub stream_id: u64,
+    pub seq: u64,
+}
+
+impl SequenceNumber {
+    pub fn to_bytes(self) -> [u8; SEQUENCE_NUMBER_SIZE] {
+        let mut bytes = [0u8; SEQUENCE_NUMBER_SIZE];
+        bytes[..8].copy_from_slice(&self.stream_id.to_le_bytes());
+        bytes[8..].copy_from_slice(&self.seq.to_le_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<SequenceNumber> {
+        if bytes.len() != SEQUENCE_NUMBER_SIZE {
+            return None;
+        }
+        Some(SequenceNumber {
+            stream_id: u64::from_le_bytes(bytes[..8].try_into().ok()?),
+            seq: u64::from_le_bytes(bytes[8..].try_into().ok()?),
+        })
+    }
+}
+
+/// The numbering of the messages published by a Publisher route, per DDS Writer
+pub struct Sequencer {
+    stream_id: u64,
+    // the last sequence number per Writer, and the time it was assigned
+    writers: Mutex<HashMap<Gid, (u64, Instant)>>,
+}
+
+impl Sequencer {
+    pub fn new() -> Sequencer {
+        // the route's creation time is unique enough to distinguish the streams of the same Writer
+        let stream_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Sequencer::with_stream_id(stream_id)
+    }
+
+    fn with_stream_id(stream_id: u64) -> Sequencer {
+        Sequencer {
+            stream_id,
+            writers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The sequence number of the next message published from the `gid` Writer
+    pub fn next(&self, gid: Gid, now: Instant) -> SequenceNumber {
+        let mut writers = zlock!(self.writers);
+        if writers.len() >= MAX_STREAMS && !writers.contains_key(&gid) {
+            writers.retain(|_, (_, t)| now.saturating_duration_since(*t) < STREAM_TIMEOUT);
+        }
+        let entry = writers.entry(gid).or_insert((0, now));
+        *entry = (entry.0 + 1, now);
+        SequenceNumber {
+            stream_id: self.stream_id,
+            seq: entry.0,
+        }
+    }
+}
+
+/// The detection of the gaps in the sequence numbers of the messages received by a Subscriber route,
+/// per DDS Writer and per stream (i.e. per remote Publisher route).
+/// The messages received out of order, after a gap was detected, are not discounted from the lost ones.
+#[derive(Default)]
+pub struct GapTracker {
+    // the last sequence number received per Writer and stream, and the time it was received
+    streams: Mutex<HashMap<(Gid, u64), (u64, Instant)>>,
+}
+
+impl GapTracker {
+    /// Return the count of the messages lost since the previous one of the same Writer and stream (0 if none)
+    pub fn on_sequence(&self, gid: Gid, sn: SequenceNumber, now: Instant) -> u64 {
+        let mut streams = zlock!(self.streams);
+        let key = (gid, sn.stream_id);
+        if streams.len() >= MAX_STREAMS && !streams.contains_key(&key) {
+            streams.retain(|_, (_, t)| now.saturating_duration_since(*t) < STREAM_TIMEOUT);
+        }
+        match streams.get_mut(&key) {
+            // a duplicate or a late message
+            Some((last, t)) if sn.seq <= *last && sn.seq != 1 => {
+                *t = now;
+                0
+            }
+            Some((last, t)) => {
+                // a Writer forgotten by the remote Sequencer restarts at 1
+                let lost = sn.seq.saturating_sub(*last + 1);
+                *last = sn.seq;
+                *t = now;
+                lost
+            }
+            // the 1st message of a stream (the route might have been created after the Writer)
+            None => {
+                streams.insert(key, (sn.seq, now));
+                0
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GapTracker, SequenceNumber, Sequencer};
+    use crate::gid::Gid;
+    use std::time::Instant;
+
+    fn gid(writer: u8) -> Gid {
+        [writer; 16].into()
+    }
+
+    #[test]
+    fn test_sequence_number_bytes() {
+        let sn = SequenceNumber {
+            stream_id: 0x0123_4567_89ab_cdef,
+            seq: 42,
+        };
+        assert_eq!(SequenceNumber::from_bytes(&sn.to_bytes()), Some(sn));
+        assert_eq!(SequenceNumber::from_bytes(&sn.to_bytes()[..12]), None);
+    }
+
+    #[test]
+    fn test_sequencer_per_writer() {
+        let now = Instant::now();
+        let sequencer = Sequencer::with_stream_id(7);
+        assert_eq!(sequencer.next(gid(1), now).seq, 1);
+        assert_eq!(sequencer.next(gid(1), now).seq, 2);
+        assert_eq!(sequencer.next(gid(2), now).seq, 1);
+        assert_eq!(sequencer.next(gid(1), now).seq, 3);
+        assert_eq!(sequencer.next(gid(2), now).stream_id, 7);
+    }
+
+    #[test]
+    fn test_gap_tracker() {
+        let now = Instant::now();
+        let tracker = GapTracker::default();
+        let sn = |stream_id, seq| SequenceNumber { stream_id, seq };
+        // the 1st message of a stream joined late is not a gap
+        assert_eq!(tracker.on_sequence(gid(1), sn(1, 10), now), 0);
+        assert_eq!(tracker.on_sequence(gid(1), sn(1, 11), now), 0);
+        // 3 messages lost, then 1
+        assert_eq!(tracker.on_sequence(gid(1), sn(1, 15), now), 3);
+        assert_eq!(tracker.on_sequence(gid(1), sn(1, 17), now), 1);
+        // a late message and a duplicate
+        assert_eq!(tracker.on_sequence(gid(1), sn(1, 16), now), 0);
+        assert_eq!(tracker.on_sequence(gid(1), sn(1, 17), now), 0);
+        assert_eq!(tracker.on_sequence(gid(1), sn(1, 18), now), 0);
+        // another Writer, and the same Writer routed by another bridge, are tracked separately
+        assert_eq!(tracker.on_sequence(gid(2), sn(1, 1), now), 0);
+        assert_eq!(tracker.on_sequence(gid(2), sn(1, 2), now), 0);
+        assert_eq!(tracker.on_sequence(gid(1), sn(2, 5), now), 0);
+        assert_eq!(tracker.on_sequence(gid(1), sn(2, 6), now), 0);
+        assert_eq!(tracker.on_sequence(gid(1), sn(1, 20), now), 1);
+        // a Writer forgotten by the remote route restarts at 1
+        assert_eq!(tracker.on_sequence(gid(2), sn(1, 1), now), 0);
+        assert_eq!(tracker.on_sequence(gid(2), sn(1, 2), now), 0);
+    }
+
+    #[test]
+    fn test_gap_tracker_from_sequencer() {
+        // a Publisher route's messages, some of them lost on the Zenoh leg
+        let now = Instant::now();
+        let sequencer = Sequencer::with_stream_id(1);
+        let tracker = GapTracker::default();
+        let mut lost = 0;
+        for i in 0..100 {
+            let sn = sequencer.next(gid(1), now);
+            if i % 10 != 5 {
+                lost += tracker.on_sequence(gid(1), sn, now);
+            }
+        }
+        assert_eq!(lost, 10);
+    }
+}
@@ -0,0 +1,118 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::{HashMap, HashSet};
+
+/// The bookkeeping of the Services introspection events (the "<service>/_service_event" topics):
+/// their discovered local Publishers (`P`) are routed only while a Service Server or Client route
+/// of their Service exists, and their route is torn down with the last route of the Service.
+pub struct ServiceEvents<P> {
+    // the discovered Publishers of the events, indexed by Service name and Node
+    publishers: HashMap<String, HashMap<String, P>>,
+    // the Services with a route
+    routed: HashSet<String>,
+}
+
+impl<P> Default for ServiceEvents<P> {
+    fn default() -> Self {
+        ServiceEvents {
+            publishers: HashMap::new(),
+            routed: HashSet::new(),
+        }
+    }
+}
+
+impl<P: Clone> ServiceEvents<P> {
+    /// Record a discovered Publisher of a Service's events, and return if it's to be routed now
+    /// (i.e. if its Service is routed)
+    pub fn on_discovered(&mut self, service: &str, node: &str, publisher: P) -> bool {
+        self.publishers
+            .entry(service.to_string())
+            .or_default()
+            .insert(node.to_string(), publisher);
+        self.routed.contains(service)
+    }
+
+    /// Forget an undiscovered Publisher of a Service's events, and return if it was routed
+    pub fn on_undiscovered(&mut self, service: &str, node: &str) -> bool {
+        if let Some(publishers) = self.publishers.get_mut(service) {
+            publishers.remove(node);
+            if publishers.is_empty() {
+                self.publishers.remove(service);
+            }
+        }
+        self.routed.contains(service)
+    }
+
+    /// Record a Service as routed, and return the Publishers of its events to be routed with it
+    /// (none if it was already routed)
+    pub fn on_service_routed(&mut self, service: &str) -> Vec<(String, P)> {
+        if !self.routed.insert(service.to_string()) {
+            return Vec::new();
+        }
+        self.publishers
+            .get(service)
+            .map(|publishers| {
+                publishers
+                    .iter()
+                    .map(|(node, publisher)| (node.clone(), publisher.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Record a Service as no longer routed, and return if the route of its events is to be torn down
+    pub fn on_service_unrouted(&mut self, service: &str) -> bool {
+        self.routed.remove(service) && self.publishers.contains_key(service)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ServiceEvents;
+
+    #[test]
+    fn test_events_routed_with_service() {
+        let mut events = ServiceEvents::default();
+        // the events Publisher discovered before the Service waits for its route
+        assert!(!events.on_discovered("/add_two_ints", "/server", "server_pub"));
+        assert_eq!(
+            events.on_service_routed("/add_two_ints"),
+            vec![("/server".to_string(), "server_pub")]
+        );
+        assert!(events.on_service_routed("/add_two_ints").is_empty());
+        // the ones discovered after are routed at once
+        assert!(events.on_discovered("/add_two_ints", "/client", "client_pub"));
+        assert!(!events.on_discovered("/other", "/client", "client_pub"));
+    }
+
+    #[test]
+    fn test_events_torn_down_with_service() {
+        let mut events = ServiceEvents::default();
+        events.on_discovered("/add_two_ints", "/server", "server_pub");
+        events.on_service_routed("/add_two_ints");
+        assert!(events.on_service_unrouted("/add_two_ints"));
+        assert!(!events.on_service_unrouted("/add_two_ints"));
+        // re-routed with the Service
+        assert_eq!(events.on_service_routed("/add_two_ints").len(), 1);
+
+        // a Service without events Publisher has no events route to tear down
+        events.on_service_routed("/trigger");
+        assert!(!events.on_service_unrouted("/trigger"));
+
+        // the undiscovered Publishers are not re-routed
+        assert!(events.on_undiscovered("/add_two_ints", "/server"));
+        assert!(!events.on_service_unrouted("/add_two_ints"));
+        assert!(events.on_service_routed("/add_two_ints").is_empty());
+    }
+}
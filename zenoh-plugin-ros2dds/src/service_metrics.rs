@@ -0,0 +1,302 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use zenoh_core::zlock;
+
+/// The upper bounds (in milliseconds) of the buckets of the calls latency histogram.
+/// The latencies above the last bound are counted in an extra overflow bucket.
+pub const LATENCY_BUCKETS_MS: [u64; 12] = [1, 2, 5, 10, 20, 50, 100, 200, 500, 1000, 2000, 5000];
+
+// The count of distinct callers of a Service tracked by a route (the requests of the next ones
+// being counted as untracked), and the count of the top ones exposed in admin space
+const MAX_TRACKED_CALLERS: usize = 256;
+const TOP_CALLERS: usize = 10;
+
+/// The metrics of the calls of a Service routed by a Service Server or Client route:
+///   - requests: the requests forwarded (from DDS to Zenoh, or from Zenoh to DDS)
+///   - replies: the replies delivered back (written to DDS, or replied to Zenoh)
+///   - timeouts: the calls that got no reply in time (after their retries, if any)
+///   - latency: the histogram of the bridge-side latency of the replied calls,
+///     from the receipt of the request to the delivery of its reply
+///
+/// Each call costs a clock read and a couple of atomic increments.
+#[derive(Default)]
+pub struct ServiceMetrics {
+    requests: AtomicU64,
+    replies: AtomicU64,
+    timeouts: AtomicU64,
+    // the counts per bucket of LATENCY_BUCKETS_MS, plus the overflow one
+    latency_buckets: [AtomicU64; LATENCY_BUCKETS_MS.len() + 1],
+    // the sum of the latencies, in microseconds
+    latency_sum_us: AtomicU64,
+}
+
+impl ServiceMetrics {
+    #[inline]
+    pub fn on_request(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn on_reply(&self, latency: Duration) {
+        self.replies.fetch_add(1, Ordering::Relaxed);
+        self.latency_buckets[latency_bucket(latency)].fetch_add(1, Ordering::Relaxed);
+        self.latency_sum_us
+            .fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn on_timeouts(&self, count: u64) {
+        self.timeouts.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Reset the counters and the latency histogram (the calls completing meanwhile counted or not)
+    pub fn reset(&self) {
+        self.requests.store(0, Ordering::Relaxed);
+        self.replies.store(0, Ordering::Relaxed);
+        self.timeouts.store(0, Ordering::Relaxed);
+        for bucket in &self.latency_buckets {
+            bucket.store(0, Ordering::Relaxed);
+        }
+        self.latency_sum_us.store(0, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> ServiceMetricsSnapshot {
+        let replies = self.replies.load(Ordering::Relaxed);
+        let latency_sum_us = self.latency_sum_us.load(Ordering::Relaxed);
+        ServiceMetricsSnapshot {
+            requests: self.requests.load(Ordering::Relaxed),
+            replies,
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+            latency: LatencyHistogram {
+                buckets_ms: LATENCY_BUCKETS_MS.to_vec(),
+                counts: self
+                    .latency_buckets
+                    .iter()
+                    .map(|c| c.load(Ordering::Relaxed))
+                    .collect(),
+                mean_ms: (replies > 0).then(|| latency_sum_us as f64 / replies as f64 / 1000.0),
+            },
+        }
+    }
+}
+
+// The index of the bucket of a latency (the overflow bucket being the last one)
+fn latency_bucket(latency: Duration) -> usize {
+    let latency_us = latency.as_micros();
+    LATENCY_BUCKETS_MS
+        .iter()
+        .position(|bound_ms| latency_us <= *bound_ms as u128 * 1000)
+        .unwrap_or(LATENCY_BUCKETS_MS.len())
+}
+
+/// A snapshot of the [`ServiceMetrics`] of a route, as exposed in admin space
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ServiceMetricsSnapshot {
+    pub requests: u64,
+    pub replies: u64,
+    pub timeouts: u64,
+    pub latency: LatencyHistogram,
+}
+
+/// The latency histogram: `counts[i]` is the count of calls replied within `buckets_ms[i]`
+/// (and above the previous bound), the last count being the one of the calls above all bounds
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct LatencyHistogram {
+    pub buckets_ms: Vec<u64>,
+    pub counts: Vec<u64>,
+    pub mean_ms: Option<f64>,
+}
+
+/// The requests per caller of a Service (see `caller_from_attachment()`), for the operator of a
+/// Service Server to tell which remote clients (e.g. robots) issue the requests
+#[derive(Default)]
+pub struct TopCallers {
+    requests: Mutex<HashMap<String, u64>>,
+    untracked: AtomicU64,
+}
+
+impl TopCallers {
+    pub fn on_request(&self, caller: &str) {
+        let mut requests = zlock!(self.requests);
+        if let Some(count) = requests.get_mut(caller) {
+            *count += 1;
+        } else if requests.len() < MAX_TRACKED_CALLERS {
+            requests.insert(caller.to_string(), 1);
+        } else {
+            self.untracked.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn reset(&self) {
+        zlock!(self.requests).clear();
+        self.untracked.store(0, Ordering::Relaxed);
+    }
+
+    /// The `n` callers with the most requests, by decreasing count
+    pub fn top(&self, n: usize) -> Vec<(String, u64)> {
+        let mut top: Vec<(String, u64)> = zlock!(self.requests)
+            .iter()
+            .map(|(caller, count)| (caller.clone(), *count))
+            .collect();
+        top.sort_by(|(c1, n1), (c2, n2)| n2.cmp(n1).then_with(|| c1.cmp(c2)));
+        top.truncate(n);
+        top
+    }
+}
+
+#[derive(Serialize)]
+struct CallerRequests {
+    caller: String,
+    requests: u64,
+}
+
+pub fn serialize_top_callers<S, C>(callers: &C, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    C: std::ops::Deref<Target = TopCallers>,
+{
+    use serde::ser::SerializeStruct;
+    let top: Vec<CallerRequests> = callers
+        .top(TOP_CALLERS)
+        .into_iter()
+        .map(|(caller, requests)| CallerRequests { caller, requests })
+        .collect();
+    let mut s = serializer.serialize_struct("TopCallers", 2)?;
+    s.serialize_field("top", &top)?;
+    s.serialize_field("untracked", &callers.untracked.load(Ordering::Relaxed))?;
+    s.end()
+}
+
+pub fn serialize_service_metrics<S, M>(metrics: &M, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    M: std::ops::Deref<Target = ServiceMetrics>,
+{
+    metrics.snapshot().serialize(serializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        latency_bucket, serialize_top_callers, ServiceMetrics, TopCallers, LATENCY_BUCKETS_MS,
+        MAX_TRACKED_CALLERS,
+    };
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_latency_bucket() {
+        assert_eq!(latency_bucket(Duration::ZERO), 0);
+        assert_eq!(latency_bucket(Duration::from_micros(1000)), 0);
+        assert_eq!(latency_bucket(Duration::from_micros(1001)), 1);
+        assert_eq!(latency_bucket(Duration::from_millis(7)), 3);
+        assert_eq!(latency_bucket(Duration::from_millis(5000)), 11);
+        assert_eq!(
+            latency_bucket(Duration::from_secs(60)),
+            LATENCY_BUCKETS_MS.len()
+        );
+    }
+
+    #[test]
+    fn test_service_metrics() {
+        let metrics = ServiceMetrics::default();
+        let snapshot = metrics.snapshot();
+        assert_eq!((snapshot.requests, snapshot.replies), (0, 0));
+        assert_eq!(snapshot.latency.mean_ms, None);
+
+        // 10 fast calls, 5 slow ones and 2 without reply
+        for _ in 0..10 {
+            metrics.on_request();
+            metrics.on_reply(Duration::from_micros(800));
+        }
+        for _ in 0..5 {
+            metrics.on_request();
+            metrics.on_reply(Duration::from_millis(300));
+        }
+        for _ in 0..2 {
+            metrics.on_request();
+        }
+        metrics.on_timeouts(2);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.requests, 17);
+        assert_eq!(snapshot.replies, 15);
+        assert_eq!(snapshot.timeouts, 2);
+        assert_eq!(
+            snapshot.latency.counts,
+            vec![10, 0, 0, 0, 0, 0, 0, 0, 5, 0, 0, 0, 0]
+        );
+        assert_eq!(snapshot.latency.mean_ms, Some(100.53333333333333));
+
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["requests"], 17);
+        assert_eq!(json["latency"]["buckets_ms"][8], 500);
+        assert_eq!(json["latency"]["counts"][8], 5);
+
+        metrics.reset();
+        let snapshot = metrics.snapshot();
+        assert_eq!(
+            (snapshot.requests, snapshot.replies, snapshot.timeouts),
+            (0, 0, 0)
+        );
+        assert!(snapshot.latency.counts.iter().all(|c| *c == 0));
+        assert_eq!(snapshot.latency.mean_ms, None);
+    }
+
+    #[test]
+    fn test_top_callers() {
+        let callers = TopCallers::default();
+        for (caller, count) in [("robot1:01", 3), ("robot2:01", 7), ("robot2:02", 3)] {
+            for _ in 0..count {
+                callers.on_request(caller);
+            }
+        }
+        assert_eq!(
+            callers.top(2),
+            vec![("robot2:01".to_string(), 7), ("robot1:01".to_string(), 3)]
+        );
+
+        // beyond the tracked callers, the requests are counted as untracked
+        for i in 0..MAX_TRACKED_CALLERS {
+            callers.on_request(&format!("robot{i}:ff"));
+        }
+        callers.on_request("robot2:01");
+        let mut json = serde_json::Serializer::new(Vec::new());
+        serialize_top_callers(&Arc::new(callers), &mut json).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json.into_inner()).unwrap();
+        assert_eq!(json["top"][0]["caller"], "robot2:01");
+        assert_eq!(json["top"][0]["requests"], 8);
+        assert_eq!(json["top"].as_array().unwrap().len(), 10);
+        assert_eq!(json["untracked"], 3);
+    }
+
+    #[test]
+    fn test_top_callers_reset() {
+        let callers = TopCallers::default();
+        for i in 0..=MAX_TRACKED_CALLERS {
+            callers.on_request(&format!("robot{i}:01"));
+        }
+        callers.reset();
+        assert!(callers.top(10).is_empty());
+        assert_eq!(callers.untracked.load(Ordering::Relaxed), 0);
+        callers.on_request("robot1:01");
+        assert_eq!(callers.top(10), vec![("robot1:01".to_string(), 1)]);
+    }
+}
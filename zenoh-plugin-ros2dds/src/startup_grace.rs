@@ -0,0 +1,88 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use crate::forward_discovery::RouteKind;
+
+/// The discovery events received during the `startup_grace_period`, for which the routes creation
+/// is deferred until the end of the period. A discovery replaces the pending one for the same
+/// (route kind, ROS name, Node), and an undiscovery cancels it.
+pub struct PendingDiscoveries<E> {
+    events: Vec<((RouteKind, String, String), E)>,
+}
+
+impl<E> Default for PendingDiscoveries<E> {
+    fn default() -> Self {
+        PendingDiscoveries { events: Vec::new() }
+    }
+}
+
+impl<E> PendingDiscoveries<E> {
+    pub fn push(
+        &mut self,
+        kind: RouteKind,
+        ros2_name: &str,
+        node: &str,
+        discovered: bool,
+        event: E,
+    ) {
+        let key = (kind, ros2_name.to_string(), node.to_string());
+        self.events.retain(|(k, _)| k != &key);
+        if discovered {
+            self.events.push((key, event));
+        }
+    }
+
+    /// The surviving discovery events, in their reception order
+    pub fn into_events(self) -> Vec<E> {
+        self.events.into_iter().map(|(_, e)| e).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PendingDiscoveries;
+    use crate::forward_discovery::RouteKind::*;
+
+    #[test]
+    fn test_burst_of_discoveries() {
+        let mut pending: PendingDiscoveries<&str> = PendingDiscoveries::default();
+
+        // nodes coming up, some restarting or re-declaring their interfaces
+        pending.push(Publisher, "/chatter", "/talker", true, "pub1");
+        pending.push(Subscriber, "/chatter", "/listener", true, "sub1");
+        pending.push(ServiceSrv, "/add", "/server", true, "srv1");
+        pending.push(Publisher, "/chatter", "/talker", false, "unpub1");
+        pending.push(Publisher, "/tmp", "/tmp_node", true, "tmp1");
+        pending.push(Subscriber, "/chatter", "/listener", true, "sub1bis");
+        pending.push(ServiceSrv, "/add", "/server", false, "unsrv1");
+        pending.push(Publisher, "/chatter", "/talker", true, "pub1bis");
+        pending.push(Publisher, "/tmp", "/tmp_node", false, "untmp1");
+        // an undiscovery of a never discovered interface is ignored
+        pending.push(ActionCli, "/fibonacci", "/client", false, "uncli1");
+        // a same interface declared by another Node is distinct
+        pending.push(Subscriber, "/chatter", "/listener2", true, "sub2");
+
+        // only the surviving entities produce routes, with their last discovery event
+        assert_eq!(pending.into_events(), vec!["sub1bis", "pub1bis", "sub2"]);
+    }
+
+    #[test]
+    fn test_all_cancelled() {
+        let mut pending: PendingDiscoveries<&str> = PendingDiscoveries::default();
+        for _ in 0..10 {
+            pending.push(ActionSrv, "/fibonacci", "/server", true, "srv");
+            pending.push(ActionSrv, "/fibonacci", "/server", false, "unsrv");
+        }
+        assert!(pending.into_events().is_empty());
+    }
+}
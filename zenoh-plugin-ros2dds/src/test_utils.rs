@@ -0,0 +1,175 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use cyclors::qos::{History, HistoryKind, Qos, Reliability, ReliabilityKind, DDS_100MS_DURATION};
+use cyclors::{
+    dds_create_participant, dds_create_reader, dds_create_writer, dds_entity_t, dds_sample_info_t,
+    dds_takecdr, ddsi_serdata, ddsi_serdata_unref, DDS_ANY_STATE,
+};
+use std::mem::MaybeUninit;
+use std::time::{Duration, Instant};
+use zenoh::buffers::ZBuf;
+use zenoh::prelude::r#async::*;
+use zenoh::Session;
+
+use crate::dds_types::{DDSInstanceState, DDSRawSample};
+use crate::dds_utils::create_topic;
+
+// A TCP endpoint on a free port of the loopback interface
+fn free_endpoint() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("tcp/127.0.0.1:{}", listener.local_addr().unwrap().port())
+}
+
+/// Open `count` (at least 1) Zenoh peer sessions without scouting: the 1st one listening on a free port,
+/// the others connected to it. Return once the 1st one is connected to all the others.
+pub async fn connected_sessions(count: usize) -> Vec<Session> {
+    let endpoint = free_endpoint();
+    let mut sessions = Vec::with_capacity(count);
+    for i in 0..count {
+        let mut zconfig = zenoh::config::peer();
+        zconfig.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let endpoints = if i == 0 {
+            "listen/endpoints"
+        } else {
+            "connect/endpoints"
+        };
+        zconfig
+            .insert_json5(endpoints, &format!(r#"["{endpoint}"]"#))
+            .unwrap();
+        sessions.push(zenoh::open(zconfig).res_async().await.unwrap());
+    }
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while sessions[0].info().peers_zid().res_async().await.count() < count - 1 {
+        assert!(
+            Instant::now() < deadline,
+            "sessions not connected via {endpoint}"
+        );
+        async_std::task::sleep(Duration::from_millis(10)).await;
+    }
+    sessions
+}
+
+/// A Zenoh peer session listening on a free port, and another one connected to it
+pub async fn session_pair() -> (Session, Session) {
+    let mut sessions = connected_sessions(2).await;
+    let connected = sessions.pop().unwrap();
+    (sessions.pop().unwrap(), connected)
+}
+
+/// A DDS Participant on `domain`, for the DDS entities of a bridge or of some ROS Nodes.
+/// Each test using DDS has its own domain, not to discover the entities of the other tests.
+pub fn dds_participant(domain: u32) -> dds_entity_t {
+    let participant = unsafe { dds_create_participant(domain, std::ptr::null(), std::ptr::null()) };
+    assert!(
+        participant >= 0,
+        "failed to create DDS Participant on domain {domain}"
+    );
+    participant
+}
+
+/// The QoS of the DDS Writers and Readers of the test Nodes: RELIABLE and KEEP_ALL
+pub fn node_qos() -> Qos {
+    Qos {
+        reliability: Some(Reliability {
+            kind: ReliabilityKind::RELIABLE,
+            max_blocking_time: DDS_100MS_DURATION,
+        }),
+        history: Some(History {
+            kind: HistoryKind::KEEP_ALL,
+            depth: 0,
+        }),
+        ..Default::default()
+    }
+}
+
+/// The DDS Writer of a ROS Node, writing serialized messages (e.g. with `dds_write()`).
+/// Unlike the ones of the bridges it's not tagged (see `tag_bridge_writer()`): its messages are routed.
+pub fn node_dds_writer(
+    participant: dds_entity_t,
+    topic_name: &str,
+    type_name: &str,
+    keyless: bool,
+) -> dds_entity_t {
+    unsafe {
+        let topic = create_topic(participant, topic_name, type_name, &None, keyless);
+        let qos_native = node_qos().to_qos_native();
+        let writer = dds_create_writer(participant, topic, qos_native, std::ptr::null());
+        Qos::delete_qos_native(qos_native);
+        assert!(writer >= 0, "failed to create DDS Writer on {topic_name}");
+        writer
+    }
+}
+
+/// The DDS Reader of a ROS Node, receiving serialized messages (see `take_node_messages()`)
+pub fn node_dds_reader(
+    participant: dds_entity_t,
+    topic_name: &str,
+    type_name: &str,
+    keyless: bool,
+) -> dds_entity_t {
+    unsafe {
+        let topic = create_topic(participant, topic_name, type_name, &None, keyless);
+        let qos_native = node_qos().to_qos_native();
+        let reader = dds_create_reader(participant, topic, qos_native, std::ptr::null());
+        Qos::delete_qos_native(qos_native);
+        assert!(reader >= 0, "failed to create DDS Reader on {topic_name}");
+        reader
+    }
+}
+
+/// Take the messages received by the DDS Reader of a ROS Node until `count` ones are taken (or 5 seconds elapsed),
+/// with the state of their instance when taken. A message notifying a dispose or an unregister is only its key.
+pub async fn take_node_messages(
+    reader: dds_entity_t,
+    count: usize,
+) -> Vec<(DDSInstanceState, Vec<u8>)> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut messages = Vec::new();
+    while messages.len() < count && Instant::now() < deadline {
+        unsafe {
+            let mut zp: *mut ddsi_serdata = std::ptr::null_mut();
+            let mut si = MaybeUninit::<[dds_sample_info_t; 1]>::uninit();
+            while dds_takecdr(
+                reader,
+                &mut zp,
+                1,
+                si.as_mut_ptr() as *mut dds_sample_info_t,
+                DDS_ANY_STATE,
+            ) > 0
+            {
+                let si = si.assume_init();
+                let state = DDSInstanceState::from_sample_info(&si[0]);
+                if si[0].valid_data || state.is_some() {
+                    let payload = ZBuf::from(&DDSRawSample::create(zp));
+                    messages.push((
+                        state.unwrap_or(DDSInstanceState::Alive),
+                        payload.contiguous().into_owned(),
+                    ));
+                }
+                ddsi_serdata_unref(zp);
+            }
+        }
+        async_std::task::sleep(Duration::from_millis(10)).await;
+    }
+    messages
+}
+
+/// A serialized std_msgs/msg/String
+pub fn cdr_string(data: &str) -> Vec<u8> {
+    let mut payload = vec![0x00, 0x01, 0x00, 0x00];
+    payload.extend_from_slice(&(data.len() as u32 + 1).to_le_bytes());
+    payload.extend_from_slice(data.as_bytes());
+    payload.push(0);
+    payload
+}
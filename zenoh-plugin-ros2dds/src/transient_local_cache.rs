@@ -0,0 +1,420 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+use zenoh::buffers::ZBuf;
+use zenoh::prelude::r#async::AsyncResolve;
+use zenoh::prelude::*;
+use zenoh::queryable::{Query, Queryable};
+use zenoh::sample::Attachment;
+use zenoh::Session;
+use zenoh_core::{zread, zwrite, SyncResolve};
+
+use crate::dedup::Origin;
+use crate::gid::Gid;
+use crate::ros2_utils::{add_origin_to_attachment, origin_from_attachment};
+use crate::KE_PREFIX_PUB_CACHE;
+
+/// A buffer of the last publications routed for a TRANSIENT_LOCAL topic.
+/// The oldest samples are evicted as soon as either the number of samples exceeds `history`,
+/// either the total size of the payloads exceeds `max_bytes` (if not 0).
+/// In `per_origin` mode (for latched aggregate topics such as /tf_static), a sample replaces
+/// the cached one of the same origin Writer, so the last publication of each Writer is kept.
+pub struct CacheBuffer {
+    history: usize,
+    max_bytes: usize,
+    per_origin: bool,
+    samples: VecDeque<Sample>,
+    bytes: usize,
+}
+
+impl CacheBuffer {
+    pub fn new(history: usize, max_bytes: usize) -> CacheBuffer {
+        CacheBuffer {
+            history,
+            max_bytes,
+            per_origin: false,
+            samples: VecDeque::new(),
+            bytes: 0,
+        }
+    }
+
+    pub fn per_origin(mut self) -> CacheBuffer {
+        self.per_origin = true;
+        self
+    }
+
+    pub fn push(&mut self, sample: Sample) {
+        if self.history == 0 {
+            return;
+        }
+        if self.per_origin {
+            if let Some(gid) = origin_gid(&sample) {
+                let bytes = &mut self.bytes;
+                self.samples.retain(|s| {
+                    let keep = origin_gid(s) != Some(gid);
+                    if !keep {
+                        *bytes -= s.payload.len();
+                    }
+                    keep
+                });
+            }
+        }
+        self.bytes += sample.payload.len();
+        self.samples.push_back(sample);
+        while self.samples.len() > self.history
+            || (self.max_bytes > 0 && self.bytes > self.max_bytes)
+        {
+            match self.samples.pop_front() {
+                Some(s) => self.bytes -= s.payload.len(),
+                None => break,
+            }
+        }
+    }
+
+    /// Remove all the samples published on a key expression (e.g. for a disposed instance)
+    pub fn remove_key_expr(&mut self, key_expr: &keyexpr) {
+        let bytes = &mut self.bytes;
+        self.samples.retain(|s| {
+            let keep = s.key_expr.as_str() != key_expr.as_str();
+            if !keep {
+                *bytes -= s.payload.len();
+            }
+            keep
+        });
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    #[inline]
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Sample> {
+        self.samples.iter()
+    }
+
+    /// The samples matching a query on `key_expr`, i.e. which key expression prefixed with `prefix`
+    /// (as the cache's Queryable) intersects it
+    pub fn matching(&self, prefix: &keyexpr, key_expr: &keyexpr) -> Vec<Sample> {
+        self.iter()
+            .filter(|s| key_expr.intersects(&(prefix / &*s.key_expr)))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Serialize for CacheBuffer {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("CacheBuffer", 5)?;
+        s.serialize_field("history", &self.history)?;
+        s.serialize_field("max_bytes", &self.max_bytes)?;
+        s.serialize_field("per_origin", &self.per_origin)?;
+        s.serialize_field("cached_samples", &self.len())?;
+        s.serialize_field("cached_bytes", &self.bytes())?;
+        s.end()
+    }
+}
+
+fn origin_gid(sample: &Sample) -> Option<Gid> {
+    sample
+        .attachment()
+        .and_then(origin_from_attachment)
+        .map(|o| o.gid)
+}
+
+/// A cache of publications for a TRANSIENT_LOCAL topic routed by a Route Publisher,
+/// with a Queryable on "<KE_PREFIX_PUB_CACHE>/<plugin_id>/<zenoh_key_expr>" replying
+/// the cached publications to the remote bridges querying for historical data.
+pub struct TransientLocalCache {
+    zsession: Arc<Session>,
+    zenoh_key_expr: OwnedKeyExpr,
+    buffer: Arc<RwLock<CacheBuffer>>,
+    _queryable: Queryable<'static, ()>,
+}
+
+impl TransientLocalCache {
+    pub async fn declare(
+        zsession: &Arc<Session>,
+        plugin_id: &keyexpr,
+        zenoh_key_expr: &OwnedKeyExpr,
+        history: usize,
+        max_bytes: usize,
+        per_origin: bool,
+    ) -> Result<TransientLocalCache, String> {
+        let mut buffer = CacheBuffer::new(history, max_bytes);
+        if per_origin {
+            buffer = buffer.per_origin();
+        }
+        let buffer = Arc::new(RwLock::new(buffer));
+        let queryable_ke = *KE_PREFIX_PUB_CACHE / plugin_id / zenoh_key_expr;
+        let queryable = zsession
+            .declare_queryable(&queryable_ke)
+            .allowed_origin(Locality::Remote) // Note: don't reply to queries from local QueryingSubscribers
+            .callback({
+                let buffer = buffer.clone();
+                let prefix = *KE_PREFIX_PUB_CACHE / plugin_id;
+                move |query: Query| {
+                    // the matching samples are copied out of the lock, not to block the routing while replying
+                    let samples = zread!(buffer).matching(&prefix, query.key_expr());
+                    reply_from_cache(query, samples)
+                }
+            })
+            .res_async()
+            .await
+            .map_err(|e| format!("Failed create Queryable for cache on {queryable_ke}: {e}"))?;
+
+        Ok(TransientLocalCache {
+            zsession: zsession.clone(),
+            zenoh_key_expr: zenoh_key_expr.clone(),
+            buffer,
+            _queryable: queryable,
+        })
+    }
+
+    /// Add a routed publication to the cache, possibly evicting the oldest ones.
+    /// Its `origin` (if tagged) is replied with it, for the remote bridges to write it with its source timestamp.
+    pub fn push(&self, payload: ZBuf, origin: Option<Origin>) {
+        let Some(origin) = origin else {
+            return self.push_at(self.zenoh_key_expr.clone(), payload);
+        };
+        let mut attachment = Attachment::new();
+        add_origin_to_attachment(&mut attachment, origin);
+        let mut sample =
+            Sample::new(self.zenoh_key_expr.clone(), payload).with_attachment(attachment);
+        sample.timestamp = self.zsession.hlc().map(|hlc| hlc.new_timestamp());
+        zwrite!(self.buffer).push(sample);
+    }
+
+    /// Add a publication routed on a specific key expression (e.g. an instance's one in "instance_key_exprs" mode)
+    pub fn push_at(&self, key_expr: OwnedKeyExpr, payload: ZBuf) {
+        let mut sample = Sample::new(key_expr, payload);
+        sample.timestamp = self.zsession.hlc().map(|hlc| hlc.new_timestamp());
+        zwrite!(self.buffer).push(sample);
+    }
+
+    /// Remove the cached publications of a disposed or unregistered instance
+    pub fn forget(&self, key_expr: &keyexpr) {
+        zwrite!(self.buffer).remove_key_expr(key_expr);
+    }
+}
+
+impl Serialize for TransientLocalCache {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        zread!(self.buffer).serialize(serializer)
+    }
+}
+
+fn reply_from_cache(query: Query, samples: Vec<Sample>) {
+    tracing::trace!(
+        "Reply to query {} with {} cached publications",
+        query.selector(),
+        samples.len()
+    );
+    for sample in samples {
+        if let Err(e) = query.reply(Ok(sample)).res_sync() {
+            tracing::warn!(
+                "Error replying to query {} from cache: {e}",
+                query.selector()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CacheBuffer, TransientLocalCache};
+    use crate::dedup::Origin;
+    use crate::ros2_utils::{add_origin_to_attachment, origin_from_attachment};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use zenoh::prelude::r#async::*;
+    use zenoh::sample::Attachment;
+
+    fn sample(size: usize) -> Sample {
+        Sample::new(crate::ke_for_sure!("test"), vec![0u8; size])
+    }
+
+    #[test]
+    fn test_cache_eviction_on_history() {
+        let mut buf = CacheBuffer::new(3, 0);
+        for i in 1..=5 {
+            buf.push(sample(i));
+        }
+        assert_eq!(buf.len(), 3);
+        assert_eq!(buf.bytes(), 3 + 4 + 5);
+        // the oldest samples have been evicted
+        let sizes: Vec<usize> = buf.iter().map(|s| s.payload.len()).collect();
+        assert_eq!(sizes, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_cache_eviction_on_max_bytes() {
+        let mut buf = CacheBuffer::new(100, 10);
+        buf.push(sample(4));
+        buf.push(sample(4));
+        assert_eq!(buf.len(), 2);
+        assert_eq!(buf.bytes(), 8);
+        buf.push(sample(4));
+        assert_eq!(buf.len(), 2);
+        assert_eq!(buf.bytes(), 8);
+        buf.push(sample(9));
+        assert_eq!(buf.len(), 1);
+        assert_eq!(buf.bytes(), 9);
+        // a sample bigger than max_bytes can't be cached
+        buf.push(sample(11));
+        assert_eq!(buf.len(), 0);
+        assert_eq!(buf.bytes(), 0);
+    }
+
+    #[test]
+    fn test_cache_remove_instance() {
+        let instance = |ke: &str, size: usize| {
+            Sample::new(crate::ke_for_sure!(ke).to_owned(), vec![0u8; size])
+        };
+        let mut buf = CacheBuffer::new(10, 0);
+        buf.push(instance("fleet_status/aa", 1));
+        buf.push(instance("fleet_status/bb", 2));
+        buf.push(instance("fleet_status/aa", 3));
+        assert_eq!(buf.len(), 3);
+        assert_eq!(buf.bytes(), 6);
+        // the disposed instance is removed, the other one is kept
+        buf.remove_key_expr(crate::ke_for_sure!("fleet_status/aa"));
+        assert_eq!(buf.len(), 1);
+        assert_eq!(buf.bytes(), 2);
+        assert_eq!(
+            buf.iter().next().unwrap().key_expr.as_str(),
+            "fleet_status/bb"
+        );
+    }
+
+    #[test]
+    fn test_cache_matching_query() {
+        let instance = |ke: &str, size: usize| {
+            Sample::new(crate::ke_for_sure!(ke).to_owned(), vec![0u8; size])
+        };
+        let mut buf = CacheBuffer::new(10, 0);
+        buf.push(instance("fleet_status/aa", 1));
+        buf.push(instance("fleet_status/bb", 2));
+        buf.push(instance("fleet_status/aa", 3));
+        let prefix = crate::ke_for_sure!("@ros2_pub_cache/bridge1");
+        let sizes = |query: &str| -> Vec<usize> {
+            buf.matching(prefix, crate::ke_for_sure!(query))
+                .iter()
+                .map(|s| s.payload.len())
+                .collect()
+        };
+        // only the samples which key expression intersects the query's one are replied
+        assert_eq!(sizes("@ros2_pub_cache/*/fleet_status/**"), vec![1, 2, 3]);
+        assert_eq!(sizes("@ros2_pub_cache/*/fleet_status/aa"), vec![1, 3]);
+        assert_eq!(sizes("@ros2_pub_cache/bridge1/fleet_status/bb"), vec![2]);
+        assert!(sizes("@ros2_pub_cache/bridge2/fleet_status/*").is_empty());
+        assert!(sizes("@ros2_pub_cache/*/map").is_empty());
+    }
+
+    #[test]
+    fn test_cache_per_origin() {
+        let published_by = |writer: u8, size: usize| {
+            let mut attachment = Attachment::new();
+            add_origin_to_attachment(
+                &mut attachment,
+                Origin {
+                    gid: [writer; 16].into(),
+                    source_timestamp: size as i64,
+                },
+            );
+            sample(size).with_attachment(attachment)
+        };
+        // e.g. /tf_static with 2 broadcasters: the last transforms of each must be replayed
+        let mut buf = CacheBuffer::new(usize::MAX, 0).per_origin();
+        buf.push(published_by(1, 1));
+        buf.push(published_by(2, 2));
+        assert_eq!(buf.len(), 2);
+        assert_eq!(buf.bytes(), 3);
+        // a re-publication replaces the cached one of the same Writer only
+        buf.push(published_by(1, 4));
+        assert_eq!(buf.len(), 2);
+        assert_eq!(buf.bytes(), 6);
+        let sizes: Vec<usize> = buf.iter().map(|s| s.payload.len()).collect();
+        assert_eq!(sizes, vec![2, 4]);
+        // the origin is kept, for the remote bridges to write with the original source timestamp
+        let origin = origin_from_attachment(buf.iter().last().unwrap().attachment().unwrap());
+        assert_eq!(origin.map(|o| o.source_timestamp), Some(4));
+        // without origin, a sample is just appended
+        buf.push(sample(8));
+        assert_eq!(buf.len(), 3);
+        assert_eq!(buf.bytes(), 14);
+    }
+
+    #[test]
+    fn test_cache_no_history() {
+        let mut buf = CacheBuffer::new(0, 0);
+        buf.push(sample(1));
+        assert_eq!(buf.len(), 0);
+        assert_eq!(buf.bytes(), 0);
+    }
+
+    #[async_std::test]
+    async fn test_late_subscriber_replay() {
+        const DEPTH: usize = 3;
+
+        // 2 sessions, since the cache only replies to remote queries
+        let (bridge_session, remote_session) = crate::test_utils::session_pair().await;
+        let bridge_session = Arc::new(bridge_session);
+        async_std::task::sleep(Duration::from_millis(500)).await;
+
+        // a durable publisher route caching with depth DEPTH
+        let cache = TransientLocalCache::declare(
+            &bridge_session,
+            crate::ke_for_sure!("bridge1"),
+            &crate::ke_for_sure!("map").to_owned(),
+            DEPTH,
+            0,
+            false,
+        )
+        .await
+        .unwrap();
+        for i in 0..10 {
+            cache.push(format!("{i}").into_bytes().into(), None);
+        }
+        async_std::task::sleep(Duration::from_millis(100)).await;
+
+        // a late subscriber querying for historical publications gets exactly DEPTH samples, the last ones
+        let replies = remote_session
+            .get("@ros2_pub_cache/*/map")
+            .consolidation(ConsolidationMode::None)
+            .timeout(Duration::from_secs(2))
+            .res_async()
+            .await
+            .unwrap();
+        let mut payloads = Vec::new();
+        while let Ok(reply) = replies.recv_async().await {
+            let sample = reply.sample.unwrap();
+            assert_eq!(sample.key_expr.as_str(), "map");
+            payloads.push(String::from_utf8(sample.payload.contiguous().into_owned()).unwrap());
+        }
+        assert_eq!(payloads, vec!["7", "8", "9"]);
+    }
+}
@@ -0,0 +1,269 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use flume::{Sender, TrySendError};
+use serde::{ser::SerializeStruct, Serializer};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// A job run by a [`WorkerPool`] (e.g. the routing of a reply to DDS)
+pub type Job = Box<dyn FnOnce() + Send>;
+
+/// A bounded pool of threads shared by the heavy Services (see `heavy_services`), out of the Zenoh
+/// callbacks and of the async runtime: `workers` threads run the jobs in their order of submission,
+/// and at most `queue_size` jobs wait for a thread. Beyond, the submitted jobs are rejected.
+/// The threads end when the pool is dropped.
+pub struct WorkerPool {
+    sender: Sender<Job>,
+    queue_size: usize,
+}
+
+impl WorkerPool {
+    pub fn new(name: &str, workers: usize, queue_size: usize) -> WorkerPool {
+        let (sender, receiver) = flume::bounded::<Job>(queue_size);
+        for i in 0..workers {
+            let receiver = receiver.clone();
+            if let Err(e) = std::thread::Builder::new()
+                .name(format!("{name}-{i}"))
+                .spawn(move || {
+                    while let Ok(job) = receiver.recv() {
+                        job();
+                    }
+                })
+            {
+                tracing::warn!("Failed to start worker thread {name}-{i}: {e}");
+            }
+        }
+        WorkerPool { sender, queue_size }
+    }
+
+    /// Queue a job for the workers, returning it if the queue is full
+    pub fn submit(&self, job: Job) -> Result<(), Job> {
+        self.sender.try_send(job).map_err(|e| match e {
+            TrySendError::Full(job) | TrySendError::Disconnected(job) => job,
+        })
+    }
+
+    /// The count of jobs waiting for a worker
+    pub fn queued(&self) -> usize {
+        self.sender.len()
+    }
+}
+
+/// The dispatch of the replies of a Service Client route to DDS. The replies of a heavy Service (configured
+/// in `heavy_services`, or once it replied with `reply_size` bytes or more) are routed by the shared
+/// [`WorkerPool`], not to delay the replies of the other Services routed in the same Zenoh callbacks.
+/// The replies of the other Services are routed at once, as the ones of a heavy Service with the pool saturated
+/// (the reply being routed anyway, not to fail a call already replied).
+pub struct ReplyDispatch {
+    pool: Arc<WorkerPool>,
+    reply_size: Option<usize>,
+    heavy: AtomicBool,
+    // the replies routed by the pool, and the ones routed at once with the pool saturated
+    dispatched: AtomicU64,
+    overflowed: AtomicU64,
+}
+
+impl ReplyDispatch {
+    pub fn new(pool: Arc<WorkerPool>, heavy: bool, reply_size: Option<usize>) -> ReplyDispatch {
+        ReplyDispatch {
+            pool,
+            reply_size,
+            heavy: AtomicBool::new(heavy),
+            dispatched: AtomicU64::new(0),
+            overflowed: AtomicU64::new(0),
+        }
+    }
+
+    pub fn is_heavy(&self) -> bool {
+        self.heavy.load(Ordering::Relaxed)
+    }
+
+    /// Route a reply of `len` bytes via `job`: on the pool for a heavy Service, or else at once
+    pub fn dispatch(&self, len: usize, job: Job) {
+        if !self.is_heavy() {
+            match self.reply_size {
+                Some(size) if len >= size => {
+                    tracing::debug!(
+                        "Reply of {len} bytes (exceeding {size}) - now routing the replies of this Service on the heavy Services workers"
+                    );
+                    self.heavy.store(true, Ordering::Relaxed);
+                }
+                _ => return job(),
+            }
+        }
+        match self.pool.submit(job) {
+            Ok(()) => {
+                self.dispatched.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(job) => {
+                self.overflowed.fetch_add(1, Ordering::Relaxed);
+                job();
+            }
+        }
+    }
+}
+
+pub fn serialize_reply_dispatch<S>(
+    dispatch: &Option<Arc<ReplyDispatch>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match dispatch {
+        Some(dispatch) => {
+            let mut s = serializer.serialize_struct("ReplyDispatch", 5)?;
+            s.serialize_field("heavy", &dispatch.is_heavy())?;
+            s.serialize_field("dispatched", &dispatch.dispatched.load(Ordering::Relaxed))?;
+            s.serialize_field("overflowed", &dispatch.overflowed.load(Ordering::Relaxed))?;
+            s.serialize_field("queued", &dispatch.pool.queued())?;
+            s.serialize_field("queue_size", &dispatch.pool.queue_size)?;
+            s.end()
+        }
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReplyDispatch, WorkerPool};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_worker_pool_bounded() {
+        let pool = WorkerPool::new("test-bounded", 1, 2);
+        let (release_tx, release_rx) = flume::unbounded::<()>();
+        let done = Arc::new(AtomicUsize::new(0));
+        let job = |done: &Arc<AtomicUsize>| {
+            let (release_rx, done) = (release_rx.clone(), done.clone());
+            Box::new(move || {
+                let _ = release_rx.recv();
+                done.fetch_add(1, Ordering::Relaxed);
+            })
+        };
+        // the single worker is busy with a 1st job, 2 jobs wait, the next one is rejected at once
+        assert!(pool.submit(job(&done)).is_ok());
+        while pool.queued() > 0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert!(pool.submit(job(&done)).is_ok());
+        assert!(pool.submit(job(&done)).is_ok());
+        assert!(pool.submit(job(&done)).is_err());
+        assert_eq!(pool.queued(), 2);
+        for _ in 0..3 {
+            release_tx.send(()).unwrap();
+        }
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while done.load(Ordering::Relaxed) < 3 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(done.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_reply_dispatch_isolation() {
+        let pool = Arc::new(WorkerPool::new("test-isolation", 1, 4));
+        let heavy = ReplyDispatch::new(pool.clone(), true, None);
+        let small = ReplyDispatch::new(pool.clone(), false, Some(1024 * 1024));
+        let caller = std::thread::current().name().unwrap_or("").to_string();
+        let (release_tx, release_rx) = flume::unbounded::<()>();
+        let (done_tx, done_rx) = flume::unbounded::<String>();
+        let blocked_job = || {
+            let (release_rx, done_tx) = (release_rx.clone(), done_tx.clone());
+            Box::new(move || {
+                let _ = release_rx.recv();
+                let _ = done_tx.send(std::thread::current().name().unwrap_or("").to_string());
+            })
+        };
+        let job = || {
+            let done_tx = done_tx.clone();
+            Box::new(move || {
+                let _ = done_tx.send(std::thread::current().name().unwrap_or("").to_string());
+            })
+        };
+        // a heavy Service saturating the pool: its single worker blocked on a reply, 4 replies waiting
+        heavy.dispatch(1024 * 1024, blocked_job());
+        while pool.queued() > 0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        for _ in 0..4 {
+            heavy.dispatch(1024 * 1024, blocked_job());
+        }
+        assert_eq!(pool.queued(), 4);
+
+        // meanwhile, the replies of a small Service are routed at once in the caller's thread
+        for _ in 0..10 {
+            small.dispatch(64, job());
+            assert_eq!(done_rx.try_recv().unwrap(), caller);
+        }
+        assert!(!small.is_heavy());
+        assert_eq!(small.dispatched.load(Ordering::Relaxed), 0);
+
+        // beyond the worker and the queue, a heavy reply is routed at once too, not dropped
+        heavy.dispatch(1024 * 1024, job());
+        assert_eq!(done_rx.try_recv().unwrap(), caller);
+        assert_eq!(heavy.overflowed.load(Ordering::Relaxed), 1);
+        assert_eq!(heavy.dispatched.load(Ordering::Relaxed), 5);
+        assert!(done_rx.is_empty());
+
+        // the blocked replies are routed by the worker once released
+        for _ in 0..5 {
+            release_tx.send(()).unwrap();
+        }
+        for _ in 0..5 {
+            assert_eq!(
+                done_rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+                "test-isolation-0"
+            );
+        }
+    }
+
+    #[test]
+    fn test_reply_dispatch_detection() {
+        let pool = Arc::new(WorkerPool::new("test-detection", 1, 4));
+        let dispatch = ReplyDispatch::new(pool, false, Some(1000));
+        let (tx, rx) = flume::unbounded::<String>();
+        let tx2 = tx.clone();
+        // a small reply is routed in the caller's thread
+        dispatch.dispatch(
+            999,
+            Box::new(move || {
+                let _ = tx2.send(std::thread::current().name().unwrap_or("").to_string());
+            }),
+        );
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            std::thread::current().name().unwrap_or("")
+        );
+        assert!(!dispatch.is_heavy());
+        // from the 1st big reply on, all the replies are routed by the workers
+        for len in [1000, 10] {
+            let tx = tx.clone();
+            dispatch.dispatch(
+                len,
+                Box::new(move || {
+                    let _ = tx.send(std::thread::current().name().unwrap_or("").to_string());
+                }),
+            );
+            assert_eq!(
+                rx.recv_timeout(Duration::from_secs(1)).unwrap(),
+                "test-detection-0"
+            );
+        }
+        assert!(dispatch.is_heavy());
+        assert_eq!(dispatch.dispatched.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,87 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// The suppression of the writes of the messages by a DDS Writer while it has no matched Reader
+/// (with `write_suppression`): such a write would be for nothing. The count of matched Readers is updated
+/// by the Writer's listener. The writes of a TRANSIENT_LOCAL Writer are never suppressed: its history serves
+/// the late-joining Readers (e.g. the historical publications routed before the matching of a Reader is notified).
+#[derive(Default)]
+pub struct WriteSuppression {
+    matched_readers: AtomicU32,
+}
+
+impl WriteSuppression {
+    /// Reset for a new DDS Writer, not matched yet
+    pub fn reset(&self) {
+        self.matched_readers.store(0, Ordering::Relaxed);
+    }
+
+    /// Update the count of matched Readers
+    pub fn on_matched(&self, count: u32) {
+        self.matched_readers.store(count, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn matched_readers(&self) -> u32 {
+        self.matched_readers.load(Ordering::Relaxed)
+    }
+
+    /// Offer a message to be written: returned if some Readers are matched or if the Writer is TRANSIENT_LOCAL.
+    /// Otherwise it's suppressed and None is returned.
+    pub fn offer<T>(&self, message: T, transient_local: bool) -> Option<T> {
+        (transient_local || self.matched_readers() > 0).then_some(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WriteSuppression;
+
+    #[test]
+    fn test_toggle_local_reader() {
+        let suppression = WriteSuppression::default();
+        // no local Reader: the messages are suppressed
+        assert_eq!(suppression.offer(1, false), None);
+        assert_eq!(suppression.offer(2, false), None);
+
+        // a local Reader matches: the writes resume immediately
+        suppression.on_matched(1);
+        assert_eq!(suppression.offer(3, false), Some(3));
+        suppression.on_matched(2);
+        suppression.on_matched(1);
+        assert_eq!(suppression.offer(4, false), Some(4));
+
+        // the Reader leaves
+        suppression.on_matched(0);
+        assert_eq!(suppression.offer(5, false), None);
+
+        // the Writer is re-created: not matched yet
+        suppression.on_matched(1);
+        suppression.reset();
+        assert_eq!(suppression.matched_readers(), 0);
+        assert_eq!(suppression.offer(6, false), None);
+    }
+
+    #[test]
+    fn test_transient_local_not_suppressed() {
+        // the history of a TRANSIENT_LOCAL Writer without Reader serves the late joining ones:
+        // all the messages are written (e.g. the historical publications of a depth > 1)
+        let suppression = WriteSuppression::default();
+        assert_eq!(suppression.offer("map v1", true), Some("map v1"));
+        assert_eq!(suppression.offer("map v2", true), Some("map v2"));
+        assert_eq!(suppression.offer("map v3", true), Some("map v3"));
+        assert_eq!(suppression.matched_readers(), 0);
+    }
+}